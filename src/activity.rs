@@ -0,0 +1,329 @@
+//! Git activity recorded by `harv sync` for `harv autolog`
+//!
+//! With `[auto_log] enabled`, every sync notes the day's commits and the last
+//! change to each repository's index. `harv autolog` splits a day's activity
+//! into coding sessions wherever it went quiet for longer than
+//! `auto_log.gap_minutes`, across all repositories so parallel work isn't
+//! counted twice, and proposes a stopped entry per session. Activity older
+//! than `KEEP_DAYS` is dropped when new activity is recorded.
+
+use crate::error::{HarjiraError, Result};
+use crate::git;
+use crate::models::Commit;
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ACTIVITY_VERSION: u8 = 1;
+
+/// Days of activity kept for `harv autolog --date`
+const KEEP_DAYS: i64 = 14;
+
+/// A moment of work in a repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Activity {
+    pub repository: String,
+    pub at: DateTime<Utc>,
+    /// Subject of a commit, None for a change to the index
+    #[serde(default)]
+    pub commit: Option<String>,
+    /// Branch checked out when the index changed
+    #[serde(default)]
+    pub branch: Option<String>,
+}
+
+/// Recorded activity, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityLog {
+    version: u8,
+    pub activity: Vec<Activity>,
+}
+
+impl Default for ActivityLog {
+    fn default() -> Self {
+        Self {
+            version: ACTIVITY_VERSION,
+            activity: Vec::new(),
+        }
+    }
+}
+
+impl ActivityLog {
+    /// Load the log, empty if missing or unreadable
+    pub fn load() -> Self {
+        match Self::load_internal() {
+            Ok(log) => log,
+            Err(e) => {
+                debug!("No usable activity log: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    fn load_internal() -> Result<Self> {
+        let path = activity_path()?;
+        let contents = fs::read_to_string(&path)?;
+        let log: ActivityLog = serde_json::from_str(&contents)?;
+
+        if log.version > ACTIVITY_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "Activity log version {} is newer than supported version {}",
+                log.version, ACTIVITY_VERSION
+            )));
+        }
+
+        Ok(log)
+    }
+
+    /// Save the log to disk, logs errors but doesn't fail
+    pub fn save(&self) {
+        if let Err(e) = self.save_internal() {
+            warn!("Failed to save activity log: {}", e);
+        }
+    }
+
+    fn save_internal(&self) -> Result<()> {
+        let path = activity_path()?;
+
+        crate::fs::write_private_atomic(&path, serde_json::to_string(self)?)?;
+
+        debug!("Saved activity log to {}", path.display());
+        Ok(())
+    }
+
+    /// Add the activity not seen before and drop what is older than `KEEP_DAYS`
+    ///
+    /// Every sync sees the same commits and often the same index time again,
+    /// activity counts as seen when its repository and time match.
+    pub fn record(&mut self, activity: Vec<Activity>, now: DateTime<Utc>) {
+        for new in activity {
+            let seen = self
+                .activity
+                .iter()
+                .any(|a| a.repository == new.repository && a.at == new.at);
+            if !seen {
+                self.activity.push(new);
+            }
+        }
+        let cutoff = now - Duration::days(KEEP_DAYS);
+        self.activity.retain(|a| a.at >= cutoff);
+        self.activity.sort_by_key(|a| a.at);
+    }
+
+    /// Activity on a local date
+    pub fn on(&self, date: NaiveDate) -> Vec<&Activity> {
+        self.activity
+            .iter()
+            .filter(|a| a.at.with_timezone(&Local).date_naive() == date)
+            .collect()
+    }
+
+    /// Drop the activity on a local date, once its sessions have been logged
+    pub fn forget(&mut self, date: NaiveDate) {
+        self.activity
+            .retain(|a| a.at.with_timezone(&Local).date_naive() != date);
+    }
+}
+
+/// The commits made in `repos` and the last change to each of their indexes
+pub fn observe(repos: &[String], commits: &[Commit]) -> Vec<Activity> {
+    let mut activity: Vec<Activity> = commits
+        .iter()
+        .filter_map(|commit| {
+            Some(Activity {
+                repository: commit.repository.clone(),
+                at: Utc.timestamp_opt(commit.timestamp, 0).single()?,
+                commit: Some(commit.message.lines().next()?.trim().to_string()),
+                branch: None,
+            })
+        })
+        .collect();
+    for repo in repos {
+        if let Some(at) = git::index_changed_at(repo) {
+            activity.push(Activity {
+                repository: repo.clone(),
+                at,
+                commit: None,
+                branch: git::current_branch(repo),
+            });
+        }
+    }
+    activity
+}
+
+/// A stretch of activity without a quiet gap longer than `auto_log.gap_minutes`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodingSession {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// The repository with the most activity in the session
+    pub repository: String,
+    /// Subjects of the session's commits, oldest first
+    pub commits: Vec<String>,
+    /// Branches seen while the index changed, without repeats
+    pub branches: Vec<String>,
+}
+
+impl CodingSession {
+    pub fn hours(&self) -> f64 {
+        (self.end - self.start).num_seconds().max(0) as f64 / 3600.0
+    }
+
+    /// Entry notes: the commit subjects, or "repo: branch" without commits
+    pub fn notes(&self) -> String {
+        if !self.commits.is_empty() {
+            return self.commits.join("; ");
+        }
+        let name = Path::new(self.repository.trim_end_matches('/'))
+            .file_name()
+            .map_or(self.repository.clone(), |name| {
+                name.to_string_lossy().to_string()
+            });
+        if self.branches.is_empty() {
+            name
+        } else {
+            format!("{}: {}", name, self.branches.join(", "))
+        }
+    }
+}
+
+/// Split activity into sessions at gaps longer than `gap`
+///
+/// Each session starts `lead` before its first activity, for the work that
+/// went into it, but never before the previous session ended.
+pub fn sessions(activity: &[&Activity], gap: Duration, lead: Duration) -> Vec<CodingSession> {
+    let mut sorted = activity.to_vec();
+    sorted.sort_by_key(|a| a.at);
+
+    let mut groups: Vec<Vec<&Activity>> = Vec::new();
+    for a in sorted {
+        match groups.last_mut() {
+            Some(group) if group.last().is_some_and(|last| a.at - last.at <= gap) => group.push(a),
+            _ => groups.push(vec![a]),
+        }
+    }
+
+    let mut sessions: Vec<CodingSession> = Vec::new();
+    for group in groups {
+        let first = group[0].at;
+        let earliest = sessions.last().map_or(first - lead, |s| s.end);
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for a in &group {
+            match counts.iter_mut().find(|(repo, _)| *repo == a.repository) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((&a.repository, 1)),
+            }
+        }
+        let repository = counts
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(repo, _)| repo.to_string())
+            .unwrap_or_default();
+        let mut branches: Vec<String> = Vec::new();
+        for branch in group.iter().filter_map(|a| a.branch.clone()) {
+            if !branches.contains(&branch) {
+                branches.push(branch);
+            }
+        }
+        sessions.push(CodingSession {
+            start: (first - lead).max(earliest),
+            end: group[group.len() - 1].at,
+            repository,
+            commits: group.iter().filter_map(|a| a.commit.clone()).collect(),
+            branches,
+        });
+    }
+    sessions
+}
+
+fn activity_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("activity.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 10, hour, minute, 0).unwrap()
+    }
+
+    fn commit(repo: &str, hour: u32, minute: u32, subject: &str) -> Activity {
+        Activity {
+            repository: repo.to_string(),
+            at: at(hour, minute),
+            commit: Some(subject.to_string()),
+            branch: None,
+        }
+    }
+
+    fn staged(repo: &str, hour: u32, minute: u32, branch: &str) -> Activity {
+        Activity {
+            repository: repo.to_string(),
+            at: at(hour, minute),
+            commit: None,
+            branch: Some(branch.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sessions_split_on_gaps() {
+        let activity = [
+            staged("/src/shop", 9, 5, "feature/PROJ-1"),
+            commit("/src/shop", 9, 30, "PROJ-1: Add cart"),
+            commit("/src/api", 9, 50, "PROJ-2: Prices"),
+            commit("/src/shop", 10, 10, "PROJ-1: Fix cart"),
+            // Quiet for over half an hour
+            staged("/src/api", 11, 0, "main"),
+            staged("/src/api", 11, 20, "main"),
+        ];
+        let refs: Vec<&Activity> = activity.iter().rev().collect();
+        let found = sessions(&refs, Duration::minutes(30), Duration::minutes(15));
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].start, at(8, 50));
+        assert_eq!(found[0].end, at(10, 10));
+        assert_eq!(found[0].repository, "/src/shop");
+        assert_eq!(
+            found[0].notes(),
+            "PROJ-1: Add cart; PROJ-2: Prices; PROJ-1: Fix cart"
+        );
+        assert_eq!(found[1].start, at(10, 45));
+        assert_eq!(found[1].notes(), "api: main");
+        assert!((found[1].hours() - 35.0 / 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_session_starts_after_the_previous_one() {
+        let activity = [
+            commit("/src/shop", 9, 0, "One"),
+            commit("/src/shop", 9, 40, "Two"),
+        ];
+        let refs: Vec<&Activity> = activity.iter().collect();
+        let found = sessions(&refs, Duration::minutes(30), Duration::minutes(60));
+        assert_eq!(found[1].start, at(9, 0));
+    }
+
+    #[test]
+    fn test_record_skips_seen_activity() {
+        let mut log = ActivityLog::default();
+        log.record(vec![commit("/src/shop", 9, 0, "One")], at(12, 0));
+        log.record(
+            vec![
+                commit("/src/shop", 9, 0, "One"),
+                staged("/src/shop", 8, 0, "main"),
+            ],
+            at(12, 0),
+        );
+        assert_eq!(log.activity.len(), 2);
+        assert_eq!(log.activity[0].at, at(8, 0));
+
+        log.record(Vec::new(), at(12, 0) + Duration::days(KEEP_DAYS + 1));
+        assert!(log.activity.is_empty());
+    }
+}
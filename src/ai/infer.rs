@@ -0,0 +1,170 @@
+use crate::ai::{parse, tally, AiProvider, ChatMessage, TokenUsage};
+use crate::error::{HarjiraError, Result};
+use crate::models::Ticket;
+use serde::Deserialize;
+
+/// Minimum confidence for picking an inferred ticket without asking
+pub const AUTO_SELECT_CONFIDENCE: f64 = 0.8;
+
+#[derive(Debug, Deserialize)]
+struct InferResponse {
+    #[serde(default)]
+    matches: Vec<InferMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InferMatch {
+    ticket: Option<String>,
+    #[serde(default)]
+    confidence: Option<f64>,
+}
+
+/// Ask the AI which of `issues` today's commits belong to
+///
+/// Matches to keys that aren't in `issues` are dropped. A ticket matched by
+/// several commits keeps its highest confidence. Returns the matched issues
+/// with `confidence` set, most likely first. The tokens the call used are
+/// added to `spent`, also when the reply can't be parsed.
+pub fn infer_tickets(
+    provider: &dyn AiProvider,
+    commit_messages: &[String],
+    issues: &[Ticket],
+    spent: &mut Option<TokenUsage>,
+) -> Result<Vec<Ticket>> {
+    if commit_messages.is_empty() || issues.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let prompt = build_infer_prompt(commit_messages, issues);
+    let completion = provider.complete(&[ChatMessage::user(prompt)], &mut |_, _| {})?;
+    tally(spent, completion.usage);
+
+    parse_infer_response(&completion.text, issues)
+}
+
+fn build_infer_prompt(commit_messages: &[String], issues: &[Ticket]) -> String {
+    let commits: Vec<String> = commit_messages
+        .iter()
+        .enumerate()
+        .map(|(idx, msg)| format!("{}. {}", idx, msg.lines().next().unwrap_or("").trim()))
+        .collect();
+
+    let issues: Vec<String> = issues
+        .iter()
+        .map(|t| match &t.status {
+            Some(status) => format!("- {}: {} [{}]", t.key, t.summary, status),
+            None => format!("- {}: {}", t.key, t.summary),
+        })
+        .collect();
+
+    format!(
+        r#"You match git commits to Jira issues.
+
+COMMITS (no ticket key in the message):
+{commits}
+
+CANDIDATE ISSUES:
+{issues}
+
+For each commit, pick the issue it most likely belongs to, based on the commit
+message and the issue summary. Use null when no issue fits. Only use keys from
+the list above.
+
+OUTPUT FORMAT (JSON):
+{{
+  "matches": [
+    {{"commit": 0, "ticket": "PROJ-123", "confidence": 0.8}},
+    {{"commit": 1, "ticket": null, "confidence": 0.0}}
+  ]
+}}"#,
+        commits = commits.join("\n"),
+        issues = issues.join("\n"),
+    )
+}
+
+fn parse_infer_response(response_text: &str, issues: &[Ticket]) -> Result<Vec<Ticket>> {
+    let response: InferResponse = parse::parse_json(response_text).map_err(|e| {
+        HarjiraError::Ai(format!(
+            "Failed to parse ticket inference response: {}. Raw response: {}",
+            e,
+            response_text.trim()
+        ))
+    })?;
+
+    let mut tickets: Vec<Ticket> = Vec::new();
+    for m in response.matches {
+        let Some(key) = m.ticket else { continue };
+        let Some(issue) = issues
+            .iter()
+            .find(|t| t.key.eq_ignore_ascii_case(key.trim()))
+        else {
+            continue;
+        };
+        let confidence = m.confidence.unwrap_or(0.5).clamp(0.0, 1.0);
+
+        match tickets.iter_mut().find(|t| t.key == issue.key) {
+            Some(existing) => {
+                existing.confidence = Some(existing.confidence.unwrap_or(0.0).max(confidence));
+            }
+            None => tickets.push(Ticket {
+                confidence: Some(confidence),
+                ..issue.clone()
+            }),
+        }
+    }
+
+    tickets.sort_by(|a, b| {
+        b.confidence
+            .unwrap_or(0.0)
+            .total_cmp(&a.confidence.unwrap_or(0.0))
+    });
+    Ok(tickets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(key: &str, summary: &str) -> Ticket {
+        Ticket {
+            key: key.to_string(),
+            summary: summary.to_string(),
+            status: Some("In Progress".to_string()),
+            confidence: None,
+            in_sprint: false,
+            estimate: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_infer_response() {
+        let issues = vec![issue("PROJ-1", "Login page"), issue("PROJ-2", "Billing")];
+        let response = r#"```json
+{"matches": [
+  {"commit": 0, "ticket": "PROJ-1", "confidence": 0.6},
+  {"commit": 1, "ticket": "proj-1", "confidence": 0.9},
+  {"commit": 2, "ticket": "PROJ-2", "confidence": 0.7},
+  {"commit": 3, "ticket": "OTHER-9", "confidence": 1.0},
+  {"commit": 4, "ticket": null}
+]}
+```"#;
+
+        let tickets = parse_infer_response(response, &issues).unwrap();
+
+        assert_eq!(tickets.len(), 2);
+        assert_eq!(tickets[0].key, "PROJ-1");
+        assert_eq!(tickets[0].confidence, Some(0.9));
+        assert_eq!(tickets[1].key, "PROJ-2");
+    }
+
+    #[test]
+    fn test_prompt_lists_commits_and_issues() {
+        let prompt = build_infer_prompt(
+            &["Fix login redirect\n\nLonger body".to_string()],
+            &[issue("PROJ-1", "Login page")],
+        );
+        assert!(prompt.contains("0. Fix login redirect\n"));
+        assert!(!prompt.contains("Longer body"));
+        assert!(prompt.contains("- PROJ-1: Login page [In Progress]"));
+    }
+}
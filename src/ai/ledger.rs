@@ -0,0 +1,227 @@
+use crate::ai::TokenUsage;
+use crate::error::{HarjiraError, Result};
+use chrono::{DateTime, Datelike, Duration, Local, Utc};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const LEDGER_FILE_VERSION: u8 = 1;
+
+/// Records older than this many days are dropped whenever the ledger is saved
+const RETENTION_DAYS: i64 = 400;
+
+/// Estimated USD prices per million (input, output) tokens
+///
+/// Matched by model name prefix, so more specific names come first.
+const MODEL_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("claude-3-5-haiku", 0.80, 4.00),
+    ("claude-3-5-sonnet", 3.00, 15.00),
+    ("claude-3-7-sonnet", 3.00, 15.00),
+    ("claude-sonnet-4", 3.00, 15.00),
+    ("claude-3-opus", 15.00, 75.00),
+    ("claude-opus-4", 15.00, 75.00),
+];
+
+/// One `generate` call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerRecord {
+    pub timestamp: DateTime<Utc>,
+    pub provider: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Estimated cost in USD, None for models without a known price
+    pub cost_usd: Option<f64>,
+}
+
+/// Local record of AI token usage and estimated cost
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiLedger {
+    version: u8,
+    #[serde(default)]
+    records: Vec<LedgerRecord>,
+}
+
+impl Default for AiLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AiLedger {
+    /// Create an empty ledger
+    pub fn new() -> Self {
+        Self {
+            version: LEDGER_FILE_VERSION,
+            records: Vec::new(),
+        }
+    }
+
+    /// Load the ledger from disk, returns an empty ledger if missing or corrupt
+    pub fn load() -> Self {
+        match Self::load_internal() {
+            Ok(ledger) => {
+                debug!("Loaded AI ledger with {} records", ledger.records.len());
+                ledger
+            }
+            Err(e) => {
+                if ledger_file_path().map(|p| p.exists()).unwrap_or(false) {
+                    warn!("Failed to load AI usage ledger: {}. Starting fresh.", e);
+                } else {
+                    debug!("No AI usage ledger found, starting fresh");
+                }
+                Self::new()
+            }
+        }
+    }
+
+    fn load_internal() -> Result<Self> {
+        let path = ledger_file_path()?;
+        let contents = fs::read_to_string(&path)?;
+        let ledger: AiLedger = serde_json::from_str(&contents)?;
+
+        if ledger.version > LEDGER_FILE_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "AI ledger version {} is newer than supported version {}",
+                ledger.version, LEDGER_FILE_VERSION
+            )));
+        }
+
+        Ok(ledger)
+    }
+
+    /// Save the ledger to disk, logs errors but doesn't fail
+    pub fn save(&self) {
+        if let Err(e) = self.save_internal() {
+            warn!("Failed to save AI usage ledger: {}", e);
+        }
+    }
+
+    fn save_internal(&self) -> Result<()> {
+        let path = ledger_file_path()?;
+
+        // Keep the file from growing forever
+        let cutoff = Utc::now() - Duration::days(RETENTION_DAYS);
+        let mut pruned = self.clone();
+        pruned.records.retain(|r| r.timestamp >= cutoff);
+
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(&pruned)?)?;
+
+        debug!("Saved AI usage ledger to {}", path.display());
+        Ok(())
+    }
+
+    /// Record a call and return the stored record
+    pub fn record(
+        &mut self,
+        provider: &str,
+        model: &str,
+        usage: TokenUsage,
+        now: DateTime<Utc>,
+    ) -> &LedgerRecord {
+        self.records.push(LedgerRecord {
+            timestamp: now,
+            provider: provider.to_string(),
+            model: model.to_string(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cost_usd: estimate_cost(model, usage),
+        });
+        self.records.last().unwrap()
+    }
+
+    /// Estimated spend in the local calendar month containing `now`
+    pub fn month_cost(&self, now: DateTime<Utc>) -> f64 {
+        let now = now.with_timezone(&Local);
+        self.records
+            .iter()
+            .filter(|r| {
+                let ts = r.timestamp.with_timezone(&Local);
+                ts.year() == now.year() && ts.month() == now.month()
+            })
+            .filter_map(|r| r.cost_usd)
+            .sum()
+    }
+}
+
+/// Estimated USD cost of a call, None if the model has no known price
+pub fn estimate_cost(model: &str, usage: TokenUsage) -> Option<f64> {
+    let model = model.to_lowercase();
+    MODEL_PRICES
+        .iter()
+        .find(|(prefix, _, _)| model.starts_with(prefix))
+        .map(|(_, input, output)| {
+            (usage.input_tokens as f64 * input + usage.output_tokens as f64 * output) / 1_000_000.0
+        })
+}
+
+/// Refuse to generate when this month's spend has reached `cap`
+pub fn check_monthly_cap(spent: f64, cap: Option<f64>) -> Result<()> {
+    match cap {
+        Some(cap) if spent >= cap => Err(HarjiraError::Ai(format!(
+            "Monthly AI cost cap reached (${:.2} of ${:.2}). Use --force to generate anyway.",
+            spent, cap
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn ledger_file_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("ai_ledger.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input_tokens: u64, output_tokens: u64) -> TokenUsage {
+        TokenUsage {
+            input_tokens,
+            output_tokens,
+        }
+    }
+
+    #[test]
+    fn test_estimate_cost_uses_most_specific_prefix() {
+        let cost = estimate_cost("gpt-4o-mini-2024-07-18", usage(1_000_000, 0)).unwrap();
+        assert!((cost - 0.15).abs() < 1e-9);
+
+        let cost = estimate_cost("gpt-4o", usage(1_000, 1_000)).unwrap();
+        assert!((cost - 0.0125).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_estimate_cost_unknown_model() {
+        assert_eq!(estimate_cost("llama-3", usage(1_000, 1_000)), None);
+    }
+
+    #[test]
+    fn test_month_cost_ignores_other_months() {
+        let now = Utc::now();
+        let mut ledger = AiLedger::new();
+        ledger.record("OpenAI", "gpt-4o", usage(1_000_000, 0), now);
+        ledger.record(
+            "OpenAI",
+            "gpt-4o",
+            usage(1_000_000, 0),
+            now - Duration::days(62),
+        );
+        ledger.record("OpenAI", "local-model", usage(1_000_000, 0), now);
+
+        assert!((ledger.month_cost(now) - 2.50).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_check_monthly_cap() {
+        assert!(check_monthly_cap(4.99, Some(5.0)).is_ok());
+        assert!(check_monthly_cap(5.0, Some(5.0)).is_err());
+        assert!(check_monthly_cap(100.0, None).is_ok());
+    }
+}
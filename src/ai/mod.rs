@@ -1,29 +1,220 @@
+pub mod infer;
+pub mod ledger;
+mod parse;
 pub mod providers;
+pub mod summarize;
 
 use crate::config::AiConfig;
 use crate::error::{HarjiraError, Result};
-use crate::models::{HarvestProject, HarvestTask, ProposedTimeEntry, TimeEntry};
+use crate::models::{
+    HarvestProject, HarvestTask, ProposedTimeEntry, Ticket, TicketDetails, TimeEntry,
+};
+use log::{debug, warn};
 use serde::Deserialize;
+use std::path::PathBuf;
 
 /// Context provided to AI for generating time entries
 #[derive(Debug, Clone)]
 pub struct AiContext {
     pub available_projects: Vec<HarvestProject>,
     pub available_tasks: Vec<HarvestTask>,
+    /// (project_id, task_id) pairs the tasks are assigned to, empty skips the check
+    pub task_assignments: Vec<(u64, u64)>,
     pub existing_entries: Vec<TimeEntry>,
     pub target_hours: f64,
     pub today_total_hours: f64,
+    /// The user's issues in the active sprint, offered as likely work items
+    pub sprint_issues: Vec<Ticket>,
+    /// Descriptions and comments of mentioned issues, with `ai.ticket_details`
+    pub ticket_details: Vec<TicketDetails>,
+    /// Custom prompt template, the built-in one is used when None
+    pub prompt_template: Option<String>,
+}
+
+/// Built-in prompt used when no `prompt.tmpl` exists
+///
+/// Placeholders: `{summary}`, `{target_hours}`, `{logged_hours}`,
+/// `{remaining_hours}`, `{existing_entries}`, `{sprint_issues}`,
+/// `{ticket_details}`, `{projects}` and `{tasks}`.
+pub const DEFAULT_PROMPT_TEMPLATE: &str = include_str!("prompt.tmpl");
+
+/// Tokens consumed by a single provider call
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    fn add(self, other: TokenUsage) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.input_tokens + other.input_tokens,
+            output_tokens: self.output_tokens + other.output_tokens,
+        }
+    }
+}
+
+/// Add a call's usage to `spent`, None stays None until a call reports some
+fn tally(spent: &mut Option<TokenUsage>, usage: Option<TokenUsage>) {
+    if let Some(u) = usage {
+        *spent = Some(spent.map_or(u, |total| total.add(u)));
+    }
+}
+
+/// Author of a message in the conversation with the provider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        }
+    }
+}
+
+/// A single message sent to or received from the provider
+#[derive(Debug, Clone)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: content.into(),
+        }
+    }
+}
+
+/// Raw reply from a provider
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub text: String,
+    pub usage: Option<TokenUsage>,
 }
 
 /// AI provider trait for extensibility
 pub trait AiProvider: Send + Sync {
-    fn generate_time_entries(
+    /// Send the conversation and return the model's reply
+    ///
+    /// Streaming providers call `on_delta` with each piece of text as it
+    /// arrives, along with the text received so far.
+    fn complete(
         &self,
-        summary: &str,
-        context: &AiContext,
-    ) -> Result<Vec<ProposedTimeEntry>>;
+        messages: &[ChatMessage],
+        on_delta: &mut dyn FnMut(&str, &str),
+    ) -> Result<Completion>;
+
+    /// IDs of the models the API key can use, to check the key works
+    fn list_models(&self) -> Result<Vec<String>>;
 
     fn name(&self) -> &str;
+
+    /// Model used for requests, for cost estimates
+    fn model(&self) -> &str;
+}
+
+/// Generate time entries, retrying when the reply is invalid
+///
+/// Unparseable JSON or unknown project/task IDs are sent back to the model
+/// as a follow-up message, up to `max_retries` times. Request failures
+/// (network, auth, API errors) are returned immediately. `on_delta` is
+/// passed to the provider for streamed output. The tokens of every attempt
+/// are added to `spent`, also when the generation fails in the end.
+pub fn generate_time_entries(
+    provider: &dyn AiProvider,
+    summary: &str,
+    context: &AiContext,
+    max_retries: u32,
+    on_delta: &mut dyn FnMut(&str, &str),
+    spent: &mut Option<TokenUsage>,
+) -> Result<Vec<ProposedTimeEntry>> {
+    let mut messages = vec![ChatMessage::user(build_prompt(summary, context))];
+    let mut attempt = 0;
+
+    loop {
+        let completion = provider.complete(&messages, on_delta)?;
+        tally(spent, completion.usage);
+
+        let result =
+            parse_response(&completion.text).and_then(|entries| validate_entries(entries, context));
+        match result {
+            Ok(entries) => return Ok(entries),
+            Err(e) if attempt < max_retries => {
+                attempt += 1;
+                warn!(
+                    "{} returned an invalid response, retrying ({}/{}): {}",
+                    provider.name(),
+                    attempt,
+                    max_retries,
+                    e
+                );
+                messages.push(ChatMessage::assistant(completion.text));
+                messages.push(ChatMessage::user(format!(
+                    "Your previous response was invalid: {}\n\nReply again with only the corrected JSON object, using project_id and task_id values from the lists above.",
+                    e
+                )));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Reject entries that reference projects or tasks that weren't offered,
+/// or a task that isn't assigned to the entry's project
+fn validate_entries(
+    entries: Vec<ProposedTimeEntry>,
+    context: &AiContext,
+) -> Result<Vec<ProposedTimeEntry>> {
+    for entry in &entries {
+        if !context.available_projects.is_empty()
+            && !context
+                .available_projects
+                .iter()
+                .any(|p| p.id == entry.project_id)
+        {
+            return Err(HarjiraError::InvalidEntry(format!(
+                "project_id {} is not in the available projects",
+                entry.project_id
+            )));
+        }
+        if !context.available_tasks.is_empty()
+            && !context
+                .available_tasks
+                .iter()
+                .any(|t| t.id == entry.task_id)
+        {
+            return Err(HarjiraError::InvalidEntry(format!(
+                "task_id {} is not in the available tasks",
+                entry.task_id
+            )));
+        }
+        if !context.task_assignments.is_empty()
+            && !context
+                .task_assignments
+                .contains(&(entry.project_id, entry.task_id))
+        {
+            return Err(HarjiraError::InvalidEntry(format!(
+                "task_id {} is not assigned to project_id {}",
+                entry.task_id, entry.project_id
+            )));
+        }
+    }
+    Ok(entries)
 }
 
 /// Factory function to create the appropriate AI provider
@@ -32,10 +223,12 @@ pub fn create_provider(config: &AiConfig) -> Result<Box<dyn AiProvider>> {
         "openai" => Ok(Box::new(providers::openai::OpenAiProvider::new(
             config.api_key.clone(),
             config.model.clone(),
+            config.stream,
         )?)),
         "anthropic" | "claude" => Ok(Box::new(providers::anthropic::AnthropicProvider::new(
             config.api_key.clone(),
             config.model.clone(),
+            config.stream,
         )?)),
         _ => Err(HarjiraError::Config(format!(
             "Unsupported AI provider: {}. Supported: openai, anthropic",
@@ -75,85 +268,162 @@ pub fn build_prompt(summary: &str, context: &AiContext) -> String {
         )
     };
 
-    format!(
-        r#"You are a time tracking assistant. Your task is to analyze a user's work summary
-and generate time entries for Harvest.
-
-USER'S WORK SUMMARY:
-{summary}
-
-CONTEXT:
-- Target hours for today: {target_hours:.2}
-- Already logged: {logged_hours:.2} hours
-- Remaining to log: {remaining_hours:.2} hours
-
-{existing_entries_summary}
-
-AVAILABLE PROJECTS:
-{projects_json}
-
-AVAILABLE TASKS:
-{tasks_json}
-
-INSTRUCTIONS:
-1. Parse the user's summary and identify distinct work activities
-2. Allocate the remaining {remaining_hours:.2} hours across these activities
-3. For each activity, select the most appropriate project_id and task_id from the lists above
-4. Be reasonable with time allocation - don't create dozens of tiny entries
-5. Aim for 2-5 entries typically, unless the user explicitly mentions more activities
-6. Each entry should have clear, professional notes describing what was done
-7. Hours should be in decimal format (e.g., 1.5 for 1 hour 30 minutes)
-8. The sum of all entry hours should approximately equal {remaining_hours:.2} hours
-
-IMPORTANT MATCHING RULES:
-- Match project names based on keywords in the user's summary
-- If uncertain about project/task, prefer general/administrative tasks
-- If the user mentions specific project names, prioritize those
-- Common task name mappings:
-  * "Development" for coding/programming work
-  * "Meeting" for meetings/calls
-  * "Planning" for planning/design work
-  * "Bug Fix" for debugging/fixing issues
-  * "Code Review" for reviewing PRs
-  * "Documentation" for writing docs
-
-OUTPUT FORMAT (JSON):
-Return a JSON object with a "time_entries" array. Each entry must have:
-- "description": Clear description of the work (string)
-- "project_id": Numeric project ID from the available projects (number)
-- "task_id": Numeric task ID from the available tasks (number)
-- "hours": Time in decimal hours (number)
-- "confidence": Your confidence in this allocation from 0.0 to 1.0 (number, optional)
-
-Example output:
-{{
-  "time_entries": [
-    {{
-      "description": "Implemented user authentication feature",
-      "project_id": 12345,
-      "task_id": 67890,
-      "hours": 3.5,
-      "confidence": 0.9
-    }},
-    {{
-      "description": "Team standup meeting and sprint planning",
-      "project_id": 12345,
-      "task_id": 67891,
-      "hours": 1.0,
-      "confidence": 1.0
-    }}
-  ]
-}}
-
-Now generate the time entries based on the user's summary."#,
-        summary = summary,
-        target_hours = context.target_hours,
-        logged_hours = context.today_total_hours,
-        remaining_hours = remaining_hours,
-        existing_entries_summary = existing_entries_summary,
-        projects_json = projects_json,
-        tasks_json = tasks_json,
-    )
+    let sprint_summary = if context.sprint_issues.is_empty() {
+        "No sprint issues available.".to_string()
+    } else {
+        let issues_list: Vec<String> = context
+            .sprint_issues
+            .iter()
+            .map(|t| match &t.status {
+                Some(status) => format!("- {}: {} [{}]", t.key, t.summary, status),
+                None => format!("- {}: {}", t.key, t.summary),
+            })
+            .collect();
+        format!(
+            "Likely work items (the user's issues in the active sprint):\n{}",
+            issues_list.join("\n")
+        )
+    };
+
+    // Only there when asked for, so the prompt stays as it was otherwise
+    let details_summary = if context.ticket_details.is_empty() {
+        String::new()
+    } else {
+        let details: Vec<String> = context
+            .ticket_details
+            .iter()
+            .map(|details| {
+                let mut lines = vec![format!("- {}: {}", details.key, details.summary)];
+                if let Some(description) = &details.description {
+                    lines.push("  Description:".to_string());
+                    lines.extend(description.lines().map(|line| format!("    {}", line)));
+                }
+                for comment in &details.comments {
+                    lines.push(format!("  Comment by {}:", comment.author));
+                    lines.extend(comment.body.lines().map(|line| format!("    {}", line)));
+                }
+                lines.join("\n")
+            })
+            .collect();
+        format!(
+            "Details of the mentioned issues, to write better descriptions:\n{}",
+            details.join("\n")
+        )
+    };
+
+    let values = [
+        ("summary", summary.to_string()),
+        ("target_hours", format!("{:.2}", context.target_hours)),
+        ("logged_hours", format!("{:.2}", context.today_total_hours)),
+        ("remaining_hours", format!("{:.2}", remaining_hours)),
+        ("existing_entries", existing_entries_summary),
+        ("sprint_issues", sprint_summary),
+        ("ticket_details", details_summary),
+        ("projects", projects_json),
+        ("tasks", tasks_json),
+    ];
+
+    let template = context
+        .prompt_template
+        .as_deref()
+        .unwrap_or(DEFAULT_PROMPT_TEMPLATE);
+
+    render_template(template, &values).trim_end().to_string()
+}
+
+/// Cut a ticket's description and comments down to at most `max_chars` characters
+///
+/// The description comes first, then as many of the newest comments as fit.
+pub fn fit_ticket_details(mut details: TicketDetails, max_chars: usize) -> TicketDetails {
+    let mut budget = max_chars;
+    if let Some(description) = &mut details.description {
+        *description = truncate_chars(description, budget);
+        budget = budget.saturating_sub(description.chars().count());
+    }
+
+    let mut kept = Vec::new();
+    for comment in std::mem::take(&mut details.comments).into_iter().rev() {
+        let length = comment.body.chars().count();
+        if length > budget {
+            break;
+        }
+        budget -= length;
+        kept.push(comment);
+    }
+    kept.reverse();
+    details.comments = kept;
+    details
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// Replace `{name}` placeholders in a single pass
+///
+/// Substituted values are not scanned again, so braces in the work summary
+/// or project names are left alone. Unknown placeholders and other braces
+/// (like the JSON example) are kept as-is.
+pub(crate) fn render_template(template: &str, values: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let value = after.find('}').and_then(|close| {
+            values
+                .iter()
+                .find(|(name, _)| *name == &after[..close])
+                .map(|(_, value)| (value, close))
+        });
+        match value {
+            Some((value, close)) => {
+                out.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Load the user's prompt template, if one exists
+///
+/// Returns None when `~/.config/harv/prompt.tmpl` is missing so the built-in
+/// template is used.
+pub fn load_prompt_template() -> Result<Option<String>> {
+    let path = prompt_template_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let template = std::fs::read_to_string(&path)?;
+    if template.trim().is_empty() {
+        return Err(HarjiraError::Config(format!(
+            "Prompt template {} is empty",
+            path.display()
+        )));
+    }
+
+    debug!("Using prompt template from {}", path.display());
+    Ok(Some(template))
+}
+
+/// Path to the user's prompt template
+pub fn prompt_template_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("prompt.tmpl"))
 }
 
 /// AI response structure
@@ -166,39 +436,32 @@ struct AiResponse {
 #[derive(Debug, Deserialize)]
 struct AiTimeEntry {
     description: String,
+    #[serde(deserialize_with = "parse::lenient_id")]
     project_id: u64,
+    #[serde(deserialize_with = "parse::lenient_id")]
     task_id: u64,
+    #[serde(deserialize_with = "parse::lenient_hours")]
     hours: f64,
+    #[serde(default, deserialize_with = "parse::lenient_score")]
     confidence: Option<f64>,
 }
 
 /// Parse AI response JSON into proposed time entries
+///
+/// Surrounding prose, code fences and common JSON slips are tolerated, see
+/// `parse::parse_json`.
 pub fn parse_response(response_text: &str) -> Result<Vec<ProposedTimeEntry>> {
-    // Handle both raw JSON and JSON inside markdown code blocks
-    let json_text = if response_text.contains("```json") {
-        // Extract from markdown code block
-        let start = response_text.find("```json").unwrap() + 7;
-        let end = response_text[start..].find("```").unwrap() + start;
-        &response_text[start..end]
-    } else if response_text.contains("```") {
-        let start = response_text.find("```").unwrap() + 3;
-        let end = response_text[start..].find("```").unwrap() + start;
-        &response_text[start..end]
-    } else {
-        response_text
-    };
-
-    let ai_response: AiResponse = serde_json::from_str(json_text.trim()).map_err(|e| {
+    let ai_response: AiResponse = parse::parse_json(response_text).map_err(|e| {
         HarjiraError::Ai(format!(
             "Failed to parse AI response: {}. Raw response: {}",
             e,
-            json_text.trim()
+            response_text.trim()
         ))
     })?;
 
     // Validate entries
     for entry in &ai_response.time_entries {
-        if entry.hours <= 0.0 || entry.hours > 24.0 {
+        if !(entry.hours > 0.0 && entry.hours <= 24.0) {
             return Err(HarjiraError::InvalidEntry(format!(
                 "Invalid hours value: {}. Must be between 0 and 24.",
                 entry.hours
@@ -215,7 +478,7 @@ pub fn parse_response(response_text: &str) -> Result<Vec<ProposedTimeEntry>> {
         .time_entries
         .into_iter()
         .map(|e| ProposedTimeEntry {
-            description: e.description,
+            description: e.description.trim().to_string(),
             project_id: e.project_id,
             task_id: e.task_id,
             hours: e.hours,
@@ -223,3 +486,243 @@ pub fn parse_response(response_text: &str) -> Result<Vec<ProposedTimeEntry>> {
         })
         .collect())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TicketComment;
+    use std::sync::Mutex;
+
+    /// Provider that replays canned replies and records what it was sent
+    struct ScriptedProvider {
+        replies: Mutex<Vec<String>>,
+        sent: Mutex<Vec<Vec<ChatMessage>>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(replies: &[&str]) -> Self {
+            Self {
+                replies: Mutex::new(replies.iter().rev().map(|r| r.to_string()).collect()),
+                sent: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl AiProvider for ScriptedProvider {
+        fn complete(
+            &self,
+            messages: &[ChatMessage],
+            _on_delta: &mut dyn FnMut(&str, &str),
+        ) -> Result<Completion> {
+            self.sent.lock().unwrap().push(messages.to_vec());
+            let text = self.replies.lock().unwrap().pop().expect("no reply left");
+            Ok(Completion {
+                text,
+                usage: Some(TokenUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                }),
+            })
+        }
+
+        fn list_models(&self) -> Result<Vec<String>> {
+            Ok(vec!["scripted".to_string()])
+        }
+
+        fn name(&self) -> &str {
+            "Scripted"
+        }
+
+        fn model(&self) -> &str {
+            "scripted"
+        }
+    }
+
+    const VALID_REPLY: &str = r#"{"time_entries": [{"description": "Work", "project_id": 1, "task_id": 2, "hours": 1.0}]}"#;
+    const UNKNOWN_PROJECT_REPLY: &str = r#"{"time_entries": [{"description": "Work", "project_id": 99, "task_id": 2, "hours": 1.0}]}"#;
+
+    fn context_with_ids() -> AiContext {
+        let mut ctx = context(None);
+        ctx.available_projects = vec![HarvestProject {
+            id: 1,
+            name: "Backend".to_string(),
+            code: None,
+            client: None,
+        }];
+        ctx.available_tasks = vec![HarvestTask {
+            id: 2,
+            name: "Development".to_string(),
+            billable: None,
+        }];
+        ctx.task_assignments = vec![(1, 2)];
+        ctx
+    }
+
+    #[test]
+    fn test_retries_with_validation_error() {
+        let provider = ScriptedProvider::new(&["not json", UNKNOWN_PROJECT_REPLY, VALID_REPLY]);
+        let mut spent = None;
+        let entries = generate_time_entries(
+            &provider,
+            "Work",
+            &context_with_ids(),
+            2,
+            &mut |_, _| {},
+            &mut spent,
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            spent,
+            Some(TokenUsage {
+                input_tokens: 30,
+                output_tokens: 15
+            })
+        );
+
+        let sent = provider.sent.lock().unwrap();
+        assert_eq!(sent.len(), 3);
+        let last = &sent[2];
+        assert_eq!(last.len(), 5);
+        assert_eq!(last[3].content, UNKNOWN_PROJECT_REPLY);
+        assert!(last[4].content.contains("project_id 99"));
+    }
+
+    #[test]
+    fn test_gives_up_after_max_retries() {
+        let provider = ScriptedProvider::new(&[UNKNOWN_PROJECT_REPLY, UNKNOWN_PROJECT_REPLY]);
+        let mut spent = None;
+        let err = generate_time_entries(
+            &provider,
+            "Work",
+            &context_with_ids(),
+            1,
+            &mut |_, _| {},
+            &mut spent,
+        )
+        .unwrap_err();
+        assert!(matches!(err, HarjiraError::InvalidEntry(_)));
+        assert_eq!(provider.sent.lock().unwrap().len(), 2);
+        // The failed attempts were paid for all the same
+        assert_eq!(
+            spent,
+            Some(TokenUsage {
+                input_tokens: 20,
+                output_tokens: 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejects_a_task_not_assigned_to_the_project() {
+        let mut context = context_with_ids();
+        context.available_tasks.push(HarvestTask {
+            id: 3,
+            name: "Design".to_string(),
+            billable: None,
+        });
+        let unassigned = r#"{"time_entries": [{"description": "Work", "project_id": 1, "task_id": 3, "hours": 1.0}]}"#;
+        let err = validate_entries(parse_response(unassigned).unwrap(), &context).unwrap_err();
+        assert!(err.to_string().contains("not assigned to project_id 1"));
+        assert!(validate_entries(parse_response(VALID_REPLY).unwrap(), &context).is_ok());
+    }
+
+    fn context(prompt_template: Option<&str>) -> AiContext {
+        AiContext {
+            available_projects: Vec::new(),
+            available_tasks: Vec::new(),
+            task_assignments: Vec::new(),
+            existing_entries: Vec::new(),
+            target_hours: 8.0,
+            today_total_hours: 1.5,
+            sprint_issues: Vec::new(),
+            ticket_details: Vec::new(),
+            prompt_template: prompt_template.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_default_template_fills_every_placeholder() {
+        let prompt = build_prompt("Fixed the {login} bug", &context(None));
+        assert!(prompt.contains("Fixed the {login} bug"));
+        assert!(prompt.contains("Remaining to log: 6.50 hours"));
+        assert!(prompt.contains("No time entries logged yet today."));
+        assert!(prompt.contains("No sprint issues available."));
+        for name in [
+            "{summary}",
+            "{remaining_hours}",
+            "{sprint_issues}",
+            "{ticket_details}",
+            "{projects}",
+            "{tasks}",
+        ] {
+            assert!(!prompt.contains(name), "{} was not replaced", name);
+        }
+        // The JSON example keeps its braces
+        assert!(prompt.contains("\"time_entries\": ["));
+    }
+
+    #[test]
+    fn test_sprint_issues_in_prompt() {
+        let mut ctx = context(None);
+        ctx.sprint_issues = vec![Ticket {
+            key: "ABC-7".to_string(),
+            summary: "Billing export".to_string(),
+            status: Some("In Progress".to_string()),
+            confidence: None,
+            in_sprint: true,
+            estimate: None,
+        }];
+        let prompt = build_prompt("Worked on exports", &ctx);
+        assert!(prompt.contains("active sprint):\n- ABC-7: Billing export [In Progress]"));
+    }
+
+    #[test]
+    fn test_ticket_details_in_prompt() {
+        let comment = |author: &str, body: &str| TicketComment {
+            author: author.to_string(),
+            body: body.to_string(),
+        };
+        let details = TicketDetails {
+            key: "ABC-7".to_string(),
+            summary: "Billing export".to_string(),
+            description: Some("Export invoices as CSV\nfor the finance team".to_string()),
+            comments: vec![
+                comment("Ann", "An old comment that no longer fits"),
+                comment("Bob", "Use semicolons"),
+            ],
+        };
+
+        let fitted = fit_ticket_details(details.clone(), 60);
+        assert_eq!(fitted.description, details.description);
+        assert_eq!(fitted.comments, vec![comment("Bob", "Use semicolons")]);
+        let fitted = fit_ticket_details(details, 10);
+        assert_eq!(fitted.description.as_deref(), Some("Export in…"));
+        assert!(fitted.comments.is_empty());
+
+        let mut ctx = context(None);
+        ctx.ticket_details = vec![fit_ticket_details(
+            TicketDetails {
+                key: "ABC-7".to_string(),
+                summary: "Billing export".to_string(),
+                description: Some("Export invoices as CSV".to_string()),
+                comments: vec![comment("Bob", "Use semicolons")],
+            },
+            100,
+        )];
+        let prompt = build_prompt("Worked on exports", &ctx);
+        assert!(prompt.contains(
+            "- ABC-7: Billing export\n  Description:\n    Export invoices as CSV\n  Comment by Bob:\n    Use semicolons"
+        ));
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let prompt = build_prompt(
+            "Standup",
+            &context(Some("Log {remaining_hours}h for: {summary} {unknown}\n")),
+        );
+        assert_eq!(prompt, "Log 6.50h for: Standup {unknown}");
+    }
+}
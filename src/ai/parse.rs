@@ -0,0 +1,293 @@
+//! Tolerant JSON extraction for provider replies
+//!
+//! Models are asked for a bare JSON object but regularly wrap it in prose or
+//! code fences (sometimes nested), leave trailing commas, put raw newlines in
+//! strings, or quote numbers. Everything here tries the strict reading first
+//! and only then falls back to repairs.
+
+use crate::time_parser;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Deserialize the first JSON object or array in `text` that fits `T`
+///
+/// Candidates are tried in order of appearance, each as-is and then
+/// repaired. Returns the strict parse error of the first candidate when
+/// nothing fits.
+pub(crate) fn parse_json<T: DeserializeOwned>(text: &str) -> std::result::Result<T, String> {
+    let candidates = candidates(text);
+    if candidates.is_empty() {
+        return Err(if text.contains('{') {
+            "the JSON object is incomplete, the reply may have been cut off".to_string()
+        } else {
+            "no JSON object found".to_string()
+        });
+    }
+
+    let mut first_error = None;
+    for candidate in candidates {
+        match serde_json::from_str::<T>(candidate) {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                if let Ok(value) = serde_json::from_str::<T>(&repair(candidate)) {
+                    return Ok(value);
+                }
+                first_error.get_or_insert_with(|| e.to_string());
+            }
+        }
+    }
+    Err(first_error.unwrap_or_default())
+}
+
+/// Balanced `{...}` / `[...]` spans, outermost first
+fn candidates(text: &str) -> Vec<&str> {
+    let mut spans = Vec::new();
+    let mut covered_until = 0;
+    for (start, c) in text.char_indices() {
+        if (c == '{' || c == '[') && start >= covered_until {
+            if let Some(end) = balanced_end(text, start) {
+                spans.push(&text[start..end]);
+                covered_until = end;
+            }
+        }
+    }
+    // Spans nested inside earlier ones come last, for replies whose outer
+    // object is beyond repair
+    for (start, c) in text.char_indices() {
+        if (c == '{' || c == '[') && !spans.iter().any(|s| span_start(text, s) == start) {
+            if let Some(end) = balanced_end(text, start) {
+                spans.push(&text[start..end]);
+            }
+        }
+    }
+    spans
+}
+
+fn span_start(text: &str, span: &str) -> usize {
+    span.as_ptr() as usize - text.as_ptr() as usize
+}
+
+/// Byte offset just past the bracket closing the one at `start`
+///
+/// Brackets inside strings (including backticks and code fences in
+/// descriptions) are ignored.
+fn balanced_end(text: &str, start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, c) in text[start..].char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(start + offset + c.len_utf8());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Fix the syntax slips models make most often
+///
+/// Drops trailing commas and `//` / `/* */` comments, and escapes raw
+/// newlines and tabs inside strings.
+pub(crate) fn repair(json: &str) -> String {
+    drop_trailing_commas(&strip_comments(json))
+}
+
+/// Remove comments and escape control characters inside strings
+fn strip_comments(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            match c {
+                _ if escaped => {
+                    escaped = false;
+                    out.push(c);
+                }
+                '\\' => {
+                    escaped = true;
+                    out.push(c);
+                }
+                '"' => {
+                    in_string = false;
+                    out.push(c);
+                }
+                '\n' => out.push_str("\\n"),
+                '\r' => {}
+                '\t' => out.push_str("\\t"),
+                _ => out.push(c),
+            }
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+fn drop_trailing_commas(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Hours as a number or a string like "1.5", "1:30" or "2h"
+pub(crate) fn lenient_hours<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_f64()
+            .ok_or_else(|| D::Error::custom("hours out of range")),
+        Value::String(s) => {
+            let trimmed = s
+                .trim()
+                .trim_end_matches("hours")
+                .trim_end_matches("hrs")
+                .trim_end_matches('h')
+                .trim();
+            if trimmed.contains(':') {
+                time_parser::parse_hours(trimmed).map_err(D::Error::custom)
+            } else {
+                trimmed
+                    .parse()
+                    .map_err(|_| D::Error::custom(format!("invalid hours: {:?}", s)))
+            }
+        }
+        other => Err(D::Error::custom(format!("invalid hours: {}", other))),
+    }
+}
+
+/// An ID as a number or a numeric string
+pub(crate) fn lenient_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_u64()
+            .ok_or_else(|| D::Error::custom(format!("invalid ID: {}", n))),
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| D::Error::custom(format!("invalid ID: {:?}", s))),
+        other => Err(D::Error::custom(format!("invalid ID: {}", other))),
+    }
+}
+
+/// An optional 0-1 score as a number, a numeric string or a percentage
+pub(crate) fn lenient_score<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => Ok(n.as_f64()),
+        Some(Value::String(s)) => {
+            let trimmed = s.trim();
+            let score = match trimmed.strip_suffix('%') {
+                Some(percent) => percent.trim().parse::<f64>().map(|p| p / 100.0),
+                None => trimmed.parse::<f64>(),
+            };
+            score
+                .map(Some)
+                .map_err(|_| D::Error::custom(format!("invalid confidence: {:?}", s)))
+        }
+        Some(other) => Err(D::Error::custom(format!("invalid confidence: {}", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_skip_prose_and_fences() {
+        let text =
+            "Sure! Here you go:\n```json\n{\"a\": [1, 2]}\n```\nLet me know {if} that helps.";
+        let found = candidates(text);
+        assert_eq!(found[0], "{\"a\": [1, 2]}");
+        assert!(found.contains(&"{if}"));
+    }
+
+    #[test]
+    fn test_balanced_end_ignores_brackets_in_strings() {
+        let text = r#"{"notes": "fix } and ``` fences \" {"} trailing"#;
+        let end = balanced_end(text, 0).unwrap();
+        assert_eq!(&text[..end], r#"{"notes": "fix } and ``` fences \" {"}"#);
+        assert_eq!(balanced_end("{\"open\": [1, 2", 0), None);
+    }
+
+    #[test]
+    fn test_repair() {
+        let json =
+            "{\n  // entries\n  \"a\": [1, 2,],\n  \"b\": \"line one\nline two\", /* done */\n}";
+        let value: Value = serde_json::from_str(&repair(json)).unwrap();
+        assert_eq!(value["a"], serde_json::json!([1, 2]));
+        assert_eq!(value["b"], "line one\nline two");
+    }
+
+    #[test]
+    fn test_repair_leaves_strings_alone() {
+        let json = r#"{"url": "https://example.com/a,]", "n": 1}"#;
+        assert_eq!(repair(json), json);
+    }
+}
@@ -1,31 +1,34 @@
-use crate::ai::{build_prompt, parse_response, AiContext, AiProvider};
+use crate::ai::providers::{read_models, read_sse};
+use crate::ai::{AiProvider, ChatMessage, Completion, TokenUsage};
 use crate::error::{HarjiraError, Result};
-use crate::models::ProposedTimeEntry;
+use crate::http::ApiClient;
 use log::debug;
-use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
 
 pub struct AnthropicProvider {
-    client: Client,
+    client: ApiClient,
     api_key: String,
     model: String,
+    stream: bool,
 }
 
 impl AnthropicProvider {
-    pub fn new(api_key: String, model: Option<String>) -> Result<Self> {
+    pub fn new(api_key: String, model: Option<String>, stream: bool) -> Result<Self> {
         if api_key.is_empty() {
             return Err(HarjiraError::Config(
                 "Anthropic API key is required".to_string(),
             ));
         }
 
-        let client = Client::new();
+        let client = ApiClient::default();
         let model = model.unwrap_or_else(|| "claude-3-5-sonnet-20241022".to_string());
 
         Ok(Self {
             client,
             api_key,
             model,
+            stream,
         })
     }
 }
@@ -35,6 +38,8 @@ struct AnthropicRequest {
     model: String,
     max_tokens: u32,
     messages: Vec<Message>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -46,6 +51,14 @@ struct Message {
 #[derive(Debug, Deserialize)]
 struct AnthropicResponse {
     content: Vec<ContentBlock>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    input_tokens: u64,
+    output_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,21 +66,108 @@ struct ContentBlock {
     text: String,
 }
 
+/// One `data:` payload of a streamed response
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    MessageStart {
+        message: StreamMessage,
+    },
+    ContentBlockDelta {
+        delta: StreamDelta,
+    },
+    MessageDelta {
+        usage: OutputUsage,
+    },
+    MessageStop,
+    Error {
+        error: StreamError,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessage {
+    usage: Usage,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamDelta {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutputUsage {
+    output_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamError {
+    message: String,
+}
+
+/// Collect a streamed response, calling `on_delta` with each piece of text
+/// and the text received so far
+fn read_stream(reader: impl BufRead, on_delta: &mut dyn FnMut(&str, &str)) -> Result<Completion> {
+    let mut text = String::new();
+    let mut usage: Option<TokenUsage> = None;
+
+    read_sse(reader, |data| {
+        let event: StreamEvent = serde_json::from_str(data).map_err(|e| {
+            HarjiraError::Ai(format!("Failed to parse Anthropic stream event: {}", e))
+        })?;
+        match event {
+            StreamEvent::MessageStart { message } => {
+                usage = Some(TokenUsage {
+                    input_tokens: message.usage.input_tokens,
+                    output_tokens: message.usage.output_tokens,
+                });
+            }
+            StreamEvent::ContentBlockDelta { delta } => {
+                if let Some(delta) = delta.text {
+                    text.push_str(&delta);
+                    on_delta(&delta, &text);
+                }
+            }
+            StreamEvent::MessageDelta { usage: delta } => {
+                // Reported output tokens are cumulative
+                let total = usage.get_or_insert_with(TokenUsage::default);
+                total.output_tokens = delta.output_tokens;
+            }
+            StreamEvent::MessageStop => return Ok(false),
+            StreamEvent::Error { error } => {
+                return Err(HarjiraError::Ai(format!(
+                    "Anthropic stream error: {}",
+                    error.message
+                )))
+            }
+            StreamEvent::Other => {}
+        }
+        Ok(true)
+    })?;
+
+    Ok(Completion { text, usage })
+}
+
 impl AiProvider for AnthropicProvider {
-    fn generate_time_entries(
+    fn complete(
         &self,
-        summary: &str,
-        context: &AiContext,
-    ) -> Result<Vec<ProposedTimeEntry>> {
-        let prompt = build_prompt(summary, context);
-
+        messages: &[ChatMessage],
+        on_delta: &mut dyn FnMut(&str, &str),
+    ) -> Result<Completion> {
         let request = AnthropicRequest {
             model: self.model.clone(),
             max_tokens: 4096,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: prompt,
-            }],
+            messages: messages
+                .iter()
+                .map(|m| Message {
+                    role: m.role.as_str().to_string(),
+                    content: m.content.clone(),
+                })
+                .collect(),
+            stream: self.stream,
         };
 
         let url = "https://api.anthropic.com/v1/messages";
@@ -81,7 +181,13 @@ impl AiProvider for AnthropicProvider {
             .header("Content-Type", "application/json")
             .json(&request)
             .send()
-            .map_err(|e| HarjiraError::Ai(format!("Anthropic API request failed: {}", e)))?;
+            .map_err(|e| HarjiraError::Network(format!("Anthropic API request failed: {}", e)))?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(HarjiraError::Auth(
+                "Anthropic rejected the API key. Check ai.api_key in your config.".to_string(),
+            ));
+        }
 
         if !response.status().is_success() {
             let status = response.status();
@@ -94,6 +200,12 @@ impl AiProvider for AnthropicProvider {
             )));
         }
 
+        if self.stream {
+            let completion = read_stream(BufReader::new(response), on_delta)?;
+            debug!("Anthropic response: {}", completion.text);
+            return Ok(completion);
+        }
+
         let anthropic_response: AnthropicResponse = response.json().map_err(|e| {
             HarjiraError::Ai(format!("Failed to parse Anthropic response: {}", e))
         })?;
@@ -107,10 +219,79 @@ impl AiProvider for AnthropicProvider {
         let content = &anthropic_response.content[0].text;
         debug!("Anthropic response: {}", content);
 
-        parse_response(content)
+        Ok(Completion {
+            text: content.clone(),
+            usage: anthropic_response.usage.map(|u| TokenUsage {
+                input_tokens: u.input_tokens,
+                output_tokens: u.output_tokens,
+            }),
+        })
+    }
+
+    fn list_models(&self) -> Result<Vec<String>> {
+        let url = "https://api.anthropic.com/v1/models";
+        debug!("GET {}", url);
+
+        let response = self
+            .client
+            .get(url)
+            .query(&[("limit", "1000")])
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Anthropic API request failed: {}", e)))?;
+        read_models(response, "Anthropic")
     }
 
     fn name(&self) -> &str {
         "Anthropic Claude"
     }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_stream() {
+        let stream = [
+            "event: message_start",
+            r#"data: {"type":"message_start","message":{"usage":{"input_tokens":25,"output_tokens":1}}}"#,
+            "event: content_block_delta",
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"{\"time_"}}"#,
+            r#"data: {"type":"ping"}"#,
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"entries\": []}"}}"#,
+            r#"data: {"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":15}}"#,
+            r#"data: {"type":"message_stop"}"#,
+        ]
+        .join("\n");
+
+        let mut so_far = Vec::new();
+        let completion = read_stream(stream.as_bytes(), &mut |_, text| {
+            so_far.push(text.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(completion.text, "{\"time_entries\": []}");
+        assert_eq!(so_far, vec!["{\"time_", "{\"time_entries\": []}"]);
+        assert_eq!(
+            completion.usage,
+            Some(TokenUsage {
+                input_tokens: 25,
+                output_tokens: 15
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_stream_error_event() {
+        let stream =
+            r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        let err = read_stream(stream.as_bytes(), &mut |_, _| {}).unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+    }
 }
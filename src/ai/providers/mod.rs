@@ -1,2 +1,83 @@
 pub mod anthropic;
 pub mod openai;
+
+use crate::error::{HarjiraError, Result};
+use serde::Deserialize;
+use std::io::BufRead;
+
+/// Response from the `/v1/models` endpoints of both providers
+#[derive(Debug, Deserialize)]
+pub(crate) struct ModelList {
+    data: Vec<ModelInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelInfo {
+    id: String,
+}
+
+/// Parse a model list response, or turn a failed one into an error naming `provider`
+pub(crate) fn read_models(
+    response: reqwest::blocking::Response,
+    provider: &str,
+) -> Result<Vec<String>> {
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(HarjiraError::Auth(format!(
+            "{} rejected the API key. Check ai.api_key in your config.",
+            provider
+        )));
+    }
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(HarjiraError::Ai(format!(
+            "{} API error ({}): {}",
+            provider, status, error_text
+        )));
+    }
+    let list: ModelList = response
+        .json()
+        .map_err(|e| HarjiraError::Ai(format!("Failed to parse {} model list: {}", provider, e)))?;
+    Ok(list.data.into_iter().map(|model| model.id).collect())
+}
+
+/// Read a server-sent event stream, calling `on_data` with each `data:` payload
+///
+/// Stops at the end of the stream or as soon as `on_data` returns false.
+pub(crate) fn read_sse(
+    reader: impl BufRead,
+    mut on_data: impl FnMut(&str) -> Result<bool>,
+) -> Result<()> {
+    for line in reader.lines() {
+        let line = line.map_err(|e| {
+            HarjiraError::Network(format!("Failed to read AI response stream: {}", e))
+        })?;
+        let Some(data) = line.strip_prefix("data:") else {
+            continue;
+        };
+        if !on_data(data.trim())? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_sse_skips_other_fields() {
+        let stream = "event: ping\ndata: one\n\n: comment\ndata:two\n\ndata: [DONE]\ndata: three\n";
+        let mut payloads = Vec::new();
+        read_sse(stream.as_bytes(), |data| {
+            payloads.push(data.to_string());
+            Ok(data != "[DONE]")
+        })
+        .unwrap();
+
+        assert_eq!(payloads, vec!["one", "two", "[DONE]"]);
+    }
+}
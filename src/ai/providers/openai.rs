@@ -1,31 +1,34 @@
-use crate::ai::{build_prompt, parse_response, AiContext, AiProvider};
+use crate::ai::providers::{read_models, read_sse};
+use crate::ai::{AiProvider, ChatMessage, Completion, TokenUsage};
 use crate::error::{HarjiraError, Result};
-use crate::models::ProposedTimeEntry;
+use crate::http::ApiClient;
 use log::debug;
-use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
 
 pub struct OpenAiProvider {
-    client: Client,
+    client: ApiClient,
     api_key: String,
     model: String,
+    stream: bool,
 }
 
 impl OpenAiProvider {
-    pub fn new(api_key: String, model: Option<String>) -> Result<Self> {
+    pub fn new(api_key: String, model: Option<String>, stream: bool) -> Result<Self> {
         if api_key.is_empty() {
             return Err(HarjiraError::Config(
                 "OpenAI API key is required".to_string(),
             ));
         }
 
-        let client = Client::new();
+        let client = ApiClient::default();
         let model = model.unwrap_or_else(|| "gpt-4o".to_string());
 
         Ok(Self {
             client,
             api_key,
             model,
+            stream,
         })
     }
 }
@@ -35,6 +38,15 @@ struct OpenAiRequest {
     model: String,
     messages: Vec<Message>,
     response_format: ResponseFormat,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+}
+
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    include_usage: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -52,6 +64,14 @@ struct ResponseFormat {
 #[derive(Debug, Deserialize)]
 struct OpenAiResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -64,23 +84,80 @@ struct ResponseMessage {
     content: String,
 }
 
+/// One `data:` payload of a streamed response
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    delta: StreamDelta,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Collect a streamed response, calling `on_delta` with each piece of text
+/// and the text received so far
+fn read_stream(reader: impl BufRead, on_delta: &mut dyn FnMut(&str, &str)) -> Result<Completion> {
+    let mut text = String::new();
+    let mut usage = None;
+
+    read_sse(reader, |data| {
+        if data == "[DONE]" {
+            return Ok(false);
+        }
+        let chunk: StreamChunk = serde_json::from_str(data)
+            .map_err(|e| HarjiraError::Ai(format!("Failed to parse OpenAI stream chunk: {}", e)))?;
+        if let Some(delta) = chunk
+            .choices
+            .first()
+            .and_then(|c| c.delta.content.as_deref())
+        {
+            text.push_str(delta);
+            on_delta(delta, &text);
+        }
+        if let Some(u) = chunk.usage {
+            usage = Some(TokenUsage {
+                input_tokens: u.prompt_tokens,
+                output_tokens: u.completion_tokens,
+            });
+        }
+        Ok(true)
+    })?;
+
+    Ok(Completion { text, usage })
+}
+
 impl AiProvider for OpenAiProvider {
-    fn generate_time_entries(
+    fn complete(
         &self,
-        summary: &str,
-        context: &AiContext,
-    ) -> Result<Vec<ProposedTimeEntry>> {
-        let prompt = build_prompt(summary, context);
-
+        messages: &[ChatMessage],
+        on_delta: &mut dyn FnMut(&str, &str),
+    ) -> Result<Completion> {
         let request = OpenAiRequest {
             model: self.model.clone(),
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: prompt,
-            }],
+            messages: messages
+                .iter()
+                .map(|m| Message {
+                    role: m.role.as_str().to_string(),
+                    content: m.content.clone(),
+                })
+                .collect(),
             response_format: ResponseFormat {
                 format_type: "json_object".to_string(),
             },
+            stream: self.stream,
+            stream_options: self.stream.then_some(StreamOptions {
+                include_usage: true,
+            }),
         };
 
         let url = "https://api.openai.com/v1/chat/completions";
@@ -93,7 +170,13 @@ impl AiProvider for OpenAiProvider {
             .header("Content-Type", "application/json")
             .json(&request)
             .send()
-            .map_err(|e| HarjiraError::Ai(format!("OpenAI API request failed: {}", e)))?;
+            .map_err(|e| HarjiraError::Network(format!("OpenAI API request failed: {}", e)))?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(HarjiraError::Auth(
+                "OpenAI rejected the API key. Check ai.api_key in your config.".to_string(),
+            ));
+        }
 
         if !response.status().is_success() {
             let status = response.status();
@@ -106,6 +189,12 @@ impl AiProvider for OpenAiProvider {
             )));
         }
 
+        if self.stream {
+            let completion = read_stream(BufReader::new(response), on_delta)?;
+            debug!("OpenAI response: {}", completion.text);
+            return Ok(completion);
+        }
+
         let openai_response: OpenAiResponse = response.json().map_err(|e| {
             HarjiraError::Ai(format!("Failed to parse OpenAI response: {}", e))
         })?;
@@ -119,10 +208,66 @@ impl AiProvider for OpenAiProvider {
         let content = &openai_response.choices[0].message.content;
         debug!("OpenAI response: {}", content);
 
-        parse_response(content)
+        Ok(Completion {
+            text: content.clone(),
+            usage: openai_response.usage.map(|u| TokenUsage {
+                input_tokens: u.prompt_tokens,
+                output_tokens: u.completion_tokens,
+            }),
+        })
+    }
+
+    fn list_models(&self) -> Result<Vec<String>> {
+        let url = "https://api.openai.com/v1/models";
+        debug!("GET {}", url);
+
+        let response = self
+            .client
+            .get(url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("OpenAI API request failed: {}", e)))?;
+        read_models(response, "OpenAI")
     }
 
     fn name(&self) -> &str {
         "OpenAI"
     }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_stream() {
+        let stream = [
+            r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#,
+            r#"data: {"choices":[{"delta":{"content":"{\"time_"}}]}"#,
+            r#"data: {"choices":[{"delta":{"content":"entries\": []}"}}]}"#,
+            r#"data: {"choices":[],"usage":{"prompt_tokens":12,"completion_tokens":7}}"#,
+            "data: [DONE]",
+        ]
+        .join("\n\n");
+
+        let mut deltas = Vec::new();
+        let completion = read_stream(stream.as_bytes(), &mut |delta, _| {
+            deltas.push(delta.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(completion.text, "{\"time_entries\": []}");
+        assert_eq!(deltas.len(), 2);
+        assert_eq!(
+            completion.usage,
+            Some(TokenUsage {
+                input_tokens: 12,
+                output_tokens: 7
+            })
+        );
+    }
 }
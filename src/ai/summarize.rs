@@ -0,0 +1,192 @@
+use crate::ai::{tally, AiProvider, ChatMessage, TokenUsage};
+use crate::error::{HarjiraError, Result};
+use crate::models::{Commit, TimeEntry};
+use std::str::FromStr;
+
+/// Output style for `harv summarize`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SummaryFormat {
+    Slack,
+    Markdown,
+    Plain,
+}
+
+impl FromStr for SummaryFormat {
+    type Err = HarjiraError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "slack" => Ok(SummaryFormat::Slack),
+            "markdown" | "md" => Ok(SummaryFormat::Markdown),
+            "plain" | "text" => Ok(SummaryFormat::Plain),
+            _ => Err(HarjiraError::Config(format!(
+                "Unknown summary format: {}. Supported: slack, markdown, plain",
+                s
+            ))),
+        }
+    }
+}
+
+impl SummaryFormat {
+    fn instructions(&self) -> &'static str {
+        match self {
+            SummaryFormat::Slack => {
+                "Format for Slack: use *bold* (single asterisks) for the heading and \"•\" bullets. Do not use markdown headings."
+            }
+            SummaryFormat::Markdown => {
+                "Format as Markdown: a \"## \" heading followed by \"- \" bullets."
+            }
+            SummaryFormat::Plain => {
+                "Format as plain text: a short heading line followed by \"- \" bullets, no markup."
+            }
+        }
+    }
+}
+
+/// Ask the AI for a standup summary of a day's entries and commits
+///
+/// The tokens the call used are added to `spent`, also when the reply is
+/// unusable.
+pub fn summarize_day(
+    provider: &dyn AiProvider,
+    date: &str,
+    entries: &[TimeEntry],
+    commits: &[Commit],
+    format: SummaryFormat,
+    spent: &mut Option<TokenUsage>,
+) -> Result<String> {
+    let prompt = build_summary_prompt(date, entries, commits, format);
+    let completion = provider.complete(&[ChatMessage::user(prompt)], &mut |_, _| {})?;
+    tally(spent, completion.usage);
+
+    let text = strip_code_fence(&completion.text);
+    if text.is_empty() {
+        return Err(HarjiraError::Ai("AI returned an empty summary".to_string()));
+    }
+
+    Ok(text)
+}
+
+fn build_summary_prompt(
+    date: &str,
+    entries: &[TimeEntry],
+    commits: &[Commit],
+    format: SummaryFormat,
+) -> String {
+    let entries_list = if entries.is_empty() {
+        "(none)".to_string()
+    } else {
+        entries
+            .iter()
+            .map(|e| {
+                format!(
+                    "- {:.2}h {} / {}: {}",
+                    e.hours.unwrap_or(0.0),
+                    e.project
+                        .as_ref()
+                        .map(|p| p.name.as_str())
+                        .unwrap_or("Unknown Project"),
+                    e.task
+                        .as_ref()
+                        .map(|t| t.name.as_str())
+                        .unwrap_or("Unknown Task"),
+                    e.notes.as_deref().unwrap_or("No description")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let commits_list = if commits.is_empty() {
+        "(none)".to_string()
+    } else {
+        commits
+            .iter()
+            .map(|c| format!("- {}", c.message.lines().next().unwrap_or("").trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    format!(
+        r#"You write concise standup updates for a software developer.
+
+Summarize the work done on {date} based on the logged time and git commits
+below. Group related work, mention Jira keys where they appear, and leave out
+hours and trivial commits (merges, typo fixes). Use at most 6 bullets.
+
+TIME ENTRIES:
+{entries_list}
+
+COMMITS:
+{commits_list}
+
+{format_instructions}
+Reply with only the summary."#,
+        date = date,
+        entries_list = entries_list,
+        commits_list = commits_list,
+        format_instructions = format.instructions(),
+    )
+}
+
+/// Remove a surrounding ``` block some models wrap their reply in
+fn strip_code_fence(text: &str) -> String {
+    let trimmed = text.trim();
+    match trimmed
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+    {
+        // Drop the language tag on the opening fence
+        Some(inner) => inner
+            .split_once('\n')
+            .map(|(_, body)| body)
+            .unwrap_or(inner)
+            .trim()
+            .to_string(),
+        None => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!(
+            "slack".parse::<SummaryFormat>().unwrap(),
+            SummaryFormat::Slack
+        );
+        assert_eq!(
+            "MD".parse::<SummaryFormat>().unwrap(),
+            SummaryFormat::Markdown
+        );
+        assert!("html".parse::<SummaryFormat>().is_err());
+    }
+
+    #[test]
+    fn test_strip_code_fence() {
+        assert_eq!(
+            strip_code_fence("```markdown\n## Standup\n- Work\n```"),
+            "## Standup\n- Work"
+        );
+        assert_eq!(strip_code_fence("  - Work\n"), "- Work");
+    }
+
+    #[test]
+    fn test_prompt_includes_entries_and_commits() {
+        let commits = vec![Commit {
+            message: "PROJ-1 fix login\n\nDetails".to_string(),
+            author: "dev".to_string(),
+            timestamp: 0,
+            repository: String::new(),
+        }];
+        let prompt = build_summary_prompt("2025-01-10", &[], &commits, SummaryFormat::Slack);
+
+        assert!(prompt.contains("on 2025-01-10"));
+        assert!(prompt.contains("TIME ENTRIES:\n(none)"));
+        assert!(prompt.contains("- PROJ-1 fix login\n"));
+        assert!(!prompt.contains("Details"));
+        assert!(prompt.contains("Format for Slack"));
+    }
+}
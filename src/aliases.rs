@@ -0,0 +1,224 @@
+//! Short names for a project and task, from `[aliases]`
+//!
+//! Aliases name the project and task as they appear in Harvest. The IDs they
+//! resolve to are cached, and looked up again when the names in the config
+//! change.
+
+use crate::config::{Config, ProjectAlias, ProjectTask};
+use crate::error::{HarjiraError, Result};
+use crate::harvest::HarvestClient;
+use crate::models::{HarvestProject, HarvestTask};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const ALIAS_CACHE_VERSION: u8 = 1;
+
+/// An alias resolved to IDs, with the account and names it was resolved from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ResolvedAlias {
+    /// Harvest account the IDs belong to
+    #[serde(default)]
+    account_id: String,
+    project: String,
+    task: String,
+    project_id: u64,
+    task_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AliasCache {
+    version: u8,
+    #[serde(default)]
+    aliases: BTreeMap<String, ResolvedAlias>,
+}
+
+impl Default for AliasCache {
+    fn default() -> Self {
+        Self {
+            version: ALIAS_CACHE_VERSION,
+            aliases: BTreeMap::new(),
+        }
+    }
+}
+
+impl ResolvedAlias {
+    /// Whether the IDs still hold for `alias` on the account
+    fn is_for(&self, account_id: &str, alias: &ProjectAlias) -> bool {
+        self.account_id == account_id && self.project == alias.project && self.task == alias.task
+    }
+}
+
+impl AliasCache {
+    fn load() -> Result<Self> {
+        let path = alias_cache_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let cache: AliasCache = serde_json::from_str(&contents).map_err(|e| {
+            HarjiraError::Config(format!(
+                "Failed to parse alias cache {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        if cache.version > ALIAS_CACHE_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "Alias cache version {} is newer than supported version {}",
+                cache.version, ALIAS_CACHE_VERSION
+            )));
+        }
+
+        Ok(cache)
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = alias_cache_path()?;
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(self)?)?;
+
+        debug!("Saved alias cache to {}", path.display());
+        Ok(())
+    }
+}
+
+/// Project and task IDs for the alias `name`
+///
+/// Names are looked up in Harvest the first time, whenever they change in the
+/// config and on another account, otherwise the cached IDs are used.
+pub fn resolve(config: &Config, harvest_client: &HarvestClient, name: &str) -> Result<ProjectTask> {
+    let alias = config.aliases.get(name).ok_or_else(|| {
+        HarjiraError::Config(format!(
+            "No alias named '{}'. Add it under [aliases] in the config",
+            name
+        ))
+    })?;
+
+    let mut cache = AliasCache::load().unwrap_or_else(|e| {
+        debug!("Ignoring alias cache: {}", e);
+        AliasCache::default()
+    });
+    if let Some(cached) = cache.aliases.get(name) {
+        if cached.is_for(harvest_client.account_id(), alias) {
+            debug!("Alias '{}' resolved from cache", name);
+            return Ok(ProjectTask {
+                project_id: cached.project_id,
+                task_id: cached.task_id,
+            });
+        }
+    }
+
+    let projects = harvest_client.get_projects()?;
+    let project = find_project(&projects, &alias.project).ok_or_else(|| {
+        HarjiraError::Config(format!(
+            "Alias '{}': no active Harvest project named '{}'",
+            name, alias.project
+        ))
+    })?;
+    let tasks = harvest_client.get_project_tasks(project.id)?;
+    let task = find_task(&tasks, &alias.task).ok_or_else(|| {
+        HarjiraError::Config(format!(
+            "Alias '{}': project '{}' has no task named '{}'",
+            name, project.name, alias.task
+        ))
+    })?;
+
+    cache.aliases.insert(
+        name.to_string(),
+        ResolvedAlias {
+            account_id: harvest_client.account_id().to_string(),
+            project: alias.project.clone(),
+            task: alias.task.clone(),
+            project_id: project.id,
+            task_id: task.id,
+        },
+    );
+    cache.save()?;
+
+    Ok(ProjectTask {
+        project_id: project.id,
+        task_id: task.id,
+    })
+}
+
+/// The project with this name or code, or "Client ▸ Project" name (case-insensitive)
+fn find_project<'a>(projects: &'a [HarvestProject], name: &str) -> Option<&'a HarvestProject> {
+    let name = name.trim();
+    projects.iter().find(|p| {
+        p.name.eq_ignore_ascii_case(name)
+            || p.code
+                .as_deref()
+                .is_some_and(|c| c.eq_ignore_ascii_case(name))
+            || p.display_name().eq_ignore_ascii_case(name)
+    })
+}
+
+fn find_task<'a>(tasks: &'a [HarvestTask], name: &str) -> Option<&'a HarvestTask> {
+    let name = name.trim();
+    tasks.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+fn alias_cache_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("alias_cache.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: u64, name: &str, code: Option<&str>) -> HarvestProject {
+        HarvestProject {
+            id,
+            name: name.to_string(),
+            code: code.map(str::to_string),
+            client: None,
+        }
+    }
+
+    #[test]
+    fn test_find_project_by_name_or_code() {
+        let projects = [
+            project(1, "Platform Maintenance", Some("PLAT")),
+            project(2, "Website Redesign", None),
+        ];
+        assert_eq!(
+            find_project(&projects, "platform maintenance").map(|p| p.id),
+            Some(1)
+        );
+        assert_eq!(find_project(&projects, "plat").map(|p| p.id), Some(1));
+        assert_eq!(
+            find_project(&projects, " Website Redesign ").map(|p| p.id),
+            Some(2)
+        );
+        assert!(find_project(&projects, "Website").is_none());
+    }
+
+    #[test]
+    fn test_cached_alias_is_only_for_its_account() {
+        let alias = ProjectAlias {
+            project: "Platform Maintenance".to_string(),
+            task: "Development".to_string(),
+        };
+        let cached = ResolvedAlias {
+            account_id: "123".to_string(),
+            project: alias.project.clone(),
+            task: alias.task.clone(),
+            project_id: 1,
+            task_id: 2,
+        };
+        assert!(cached.is_for("123", &alias));
+        assert!(!cached.is_for("456", &alias));
+
+        let renamed = ProjectAlias {
+            task: "Support".to_string(),
+            ..alias
+        };
+        assert!(!cached.is_for("123", &renamed));
+    }
+}
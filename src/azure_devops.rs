@@ -0,0 +1,187 @@
+//! Azure DevOps (Boards) issue tracker
+//!
+//! Work items are numbered per organization, so commits mention them as
+//! `AB#1234` (the form the Azure Boards integrations link) or just `#1234`.
+
+use crate::config::AzureDevOpsConfig;
+use crate::error::{HarjiraError, Result};
+use crate::http::{ApiClient, ApiRequest};
+use crate::models::{AzureWiqlResponse, AzureWorkItem, AzureWorkItemsResponse, Ticket};
+use crate::ticket_parser;
+use crate::tracker::IssueTracker;
+use log::debug;
+use reqwest::blocking::Response;
+use serde_json::json;
+
+const API_VERSION: &str = "7.1";
+
+const FIELDS: &str = "System.Title,System.State";
+
+/// The user's work items that are still open or changed in the last two weeks
+const RECENT_WORK_ITEMS_WIQL: &str = "SELECT [System.Id] FROM WorkItems \
+     WHERE [System.AssignedTo] = @Me \
+     AND ([System.State] NOT IN ('Closed', 'Done', 'Removed') OR [System.ChangedDate] >= @Today - 14) \
+     ORDER BY [System.ChangedDate] DESC";
+
+pub struct AzureDevOpsClient {
+    client: ApiClient,
+    /// Collection root, e.g. https://dev.azure.com/acme
+    base_url: String,
+    config: AzureDevOpsConfig,
+}
+
+impl AzureDevOpsClient {
+    pub fn new(config: AzureDevOpsConfig) -> Result<Self> {
+        if config.access_token.is_empty() {
+            return Err(HarjiraError::Config(
+                "No Azure DevOps access token configured in [azure_devops]".to_string(),
+            ));
+        }
+
+        let base_url = match &config.base_url {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => format!("https://dev.azure.com/{}", config.organization),
+        };
+
+        Ok(Self {
+            client: ApiClient::default(),
+            base_url,
+            config,
+        })
+    }
+
+    /// Send requests to another collection root, e.g. a mock server in tests
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Personal access tokens are sent as the password with an empty user name
+    fn authorized(&self, request: ApiRequest) -> ApiRequest {
+        request.basic_auth("", Some(&self.config.access_token))
+    }
+
+    /// `{collection}/{project}{path}`, with spaces in the project name escaped
+    fn project_url(&self, path: &str) -> String {
+        format!(
+            "{}/{}{}",
+            self.base_url,
+            self.config.project.replace(' ', "%20"),
+            path
+        )
+    }
+
+    fn get_work_items(&self, ids: &[u64]) -> Result<Vec<AzureWorkItem>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = self.project_url("/_apis/wit/workitems");
+        let ids: Vec<String> = ids.iter().map(u64::to_string).collect();
+        debug!("GET {} (ids: {})", url, ids.join(","));
+
+        let response = self
+            .authorized(self.client.get(&url))
+            .query(&[
+                ("ids", ids.join(",")),
+                ("fields", FIELDS.to_string()),
+                ("api-version", API_VERSION.to_string()),
+            ])
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        let items: AzureWorkItemsResponse = check_response(response)?
+            .json()
+            .map_err(|e| HarjiraError::AzureDevOps(format!("Failed to parse work items: {}", e)))?;
+        Ok(items.value)
+    }
+}
+
+impl IssueTracker for AzureDevOpsClient {
+    fn name(&self) -> &str {
+        "Azure DevOps"
+    }
+
+    fn get_issue(&self, ticket_key: &str) -> Result<Ticket> {
+        let id = ticket_parser::work_item_id(ticket_key).ok_or_else(|| {
+            HarjiraError::AzureDevOps(format!("{} is not a work item number", ticket_key))
+        })?;
+        let url = self.project_url(&format!("/_apis/wit/workitems/{}", id));
+        debug!("GET {}", url);
+
+        let response = self
+            .authorized(self.client.get(&url))
+            .query(&[("fields", FIELDS), ("api-version", API_VERSION)])
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        if response.status() == 404 {
+            return Err(HarjiraError::AzureDevOps(format!(
+                "Work item {} not found. Verify the number and azure_devops.project.",
+                ticket_key
+            )));
+        }
+        let item: AzureWorkItem = check_response(response)?
+            .json()
+            .map_err(|e| HarjiraError::AzureDevOps(format!("Failed to parse work item: {}", e)))?;
+
+        debug!("Retrieved work item: {} - {}", item.id, item.fields.title);
+        Ok(item.to_ticket())
+    }
+
+    fn get_my_recent_issues(&self, max_results: u32) -> Result<Vec<Ticket>> {
+        let url = self.project_url("/_apis/wit/wiql");
+        debug!("POST {} (wiql: {})", url, RECENT_WORK_ITEMS_WIQL);
+
+        let response = self
+            .authorized(self.client.post(&url))
+            .query(&[
+                ("$top", max_results.to_string()),
+                ("api-version", API_VERSION.to_string()),
+            ])
+            .json(&json!({ "query": RECENT_WORK_ITEMS_WIQL }))
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        let wiql: AzureWiqlResponse = check_response(response)?.json().map_err(|e| {
+            HarjiraError::AzureDevOps(format!("Failed to parse query result: {}", e))
+        })?;
+        let ids: Vec<u64> = wiql.work_items.iter().map(|item| item.id).collect();
+
+        // The batch endpoint doesn't keep the query's order
+        let mut items = self.get_work_items(&ids)?;
+        items.sort_by_key(|item| ids.iter().position(|id| *id == item.id));
+
+        debug!("Found {} recent work items", items.len());
+        Ok(items.into_iter().map(AzureWorkItem::to_ticket).collect())
+    }
+
+    fn get_ticket_url(&self, ticket_key: &str) -> String {
+        let id = ticket_parser::work_item_id(ticket_key).unwrap_or_default();
+        self.project_url(&format!("/_workitems/edit/{}", id))
+    }
+}
+
+fn check_response(response: Response) -> Result<Response> {
+    let status = response.status();
+    // A token that isn't accepted gets 203 and a sign-in page instead of a 401
+    if status == 401 || status == 203 {
+        return Err(HarjiraError::Auth(
+            "Azure DevOps authentication failed. Check azure_devops.access_token in your config."
+                .to_string(),
+        ));
+    }
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string());
+    Err(HarjiraError::AzureDevOps(format!(
+        "{}: {}",
+        status, message
+    )))
+}
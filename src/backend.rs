@@ -0,0 +1,121 @@
+use crate::error::Result;
+use crate::harvest::HarvestClient;
+use crate::models::{Context, TimeEntry, UpdateTimeEntryRequest};
+
+/// Time-tracking service behind `sync`, `stop` and `status`
+///
+/// Entries are reported as Harvest-style `TimeEntry` values whose notes start
+/// with the ticket key, so the commands work the same whichever service is
+/// configured. Project and task are only filled in by backends that have them.
+pub trait TimeBackend {
+    /// Name shown in messages, e.g. "Harvest"
+    fn name(&self) -> &str;
+
+    /// All entries for today, running ones included
+    fn get_todays_time_entries(&self) -> Result<Vec<TimeEntry>> {
+        self.get_time_entries_for_date(&chrono::Local::now().format("%Y-%m-%d").to_string())
+    }
+
+    /// All entries spent on `date` (YYYY-MM-DD), running ones included
+    fn get_time_entries_for_date(&self, date: &str) -> Result<Vec<TimeEntry>>;
+
+    /// Running timers, newest first, including ones started on earlier days
+    fn get_running_timers(&self) -> Result<Vec<TimeEntry>>;
+
+    /// The most recently started running timer
+    fn get_running_timer(&self) -> Result<Option<TimeEntry>> {
+        Ok(self.get_running_timers()?.into_iter().next())
+    }
+
+    /// Start a timer for a ticket, noted as "KEY - description"
+    fn create_time_entry(
+        &self,
+        ticket_key: &str,
+        description: &str,
+        ticket_url: &str,
+        ctx: &Context,
+    ) -> Result<TimeEntry>;
+
+    /// Log finished work of `hours` on a ticket today, noted like `create_time_entry`
+    fn log_time_entry(
+        &self,
+        ticket_key: &str,
+        description: &str,
+        ticket_url: &str,
+        hours: f64,
+        ctx: &Context,
+    ) -> Result<TimeEntry>;
+
+    /// Stop a running timer and return the entry it produced
+    fn stop_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry>;
+
+    /// Start timing an existing stopped entry again
+    fn restart_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry>;
+
+    /// Change the hours and/or notes of an entry
+    fn update_time_entry(
+        &self,
+        entry_id: u64,
+        update: &UpdateTimeEntryRequest,
+        ctx: &Context,
+    ) -> Result<TimeEntry>;
+
+    /// Remove an entry, running or not
+    fn delete_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<()>;
+}
+
+impl TimeBackend for HarvestClient {
+    fn name(&self) -> &str {
+        "Harvest"
+    }
+
+    fn get_time_entries_for_date(&self, date: &str) -> Result<Vec<TimeEntry>> {
+        HarvestClient::get_time_entries_for_date(self, date)
+    }
+
+    fn get_running_timers(&self) -> Result<Vec<TimeEntry>> {
+        HarvestClient::get_running_timers(self)
+    }
+
+    fn create_time_entry(
+        &self,
+        ticket_key: &str,
+        description: &str,
+        ticket_url: &str,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        HarvestClient::create_time_entry(self, ticket_key, description, ticket_url, ctx)
+    }
+
+    fn log_time_entry(
+        &self,
+        ticket_key: &str,
+        description: &str,
+        ticket_url: &str,
+        hours: f64,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        self.create_stopped_ticket_entry(ticket_key, description, ticket_url, hours, ctx)
+    }
+
+    fn stop_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry> {
+        HarvestClient::stop_time_entry(self, entry_id, ctx)
+    }
+
+    fn restart_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry> {
+        HarvestClient::restart_time_entry(self, entry_id, ctx)
+    }
+
+    fn update_time_entry(
+        &self,
+        entry_id: u64,
+        update: &UpdateTimeEntryRequest,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        HarvestClient::update_time_entry(self, entry_id, update, ctx)
+    }
+
+    fn delete_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<()> {
+        HarvestClient::delete_time_entry(self, entry_id, ctx)
+    }
+}
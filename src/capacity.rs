@@ -0,0 +1,145 @@
+//! Hours to work on a given day
+//!
+//! `ai.target_hours` is the default. `[capacity]` changes it per weekday
+//! (part-time schedules) or per date, and `harv capacity set` per date
+//! without editing the config. The most specific one wins.
+
+use crate::config::Config;
+use crate::error::{HarjiraError, Result};
+use chrono::{Datelike, NaiveDate, Weekday};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const CAPACITY_FILE_VERSION: u8 = 1;
+
+/// Hours per date set with `harv capacity set`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapacityOverrides {
+    version: u8,
+    #[serde(default)]
+    pub days: BTreeMap<String, f64>,
+}
+
+impl Default for CapacityOverrides {
+    fn default() -> Self {
+        Self {
+            version: CAPACITY_FILE_VERSION,
+            days: BTreeMap::new(),
+        }
+    }
+}
+
+impl CapacityOverrides {
+    /// Load the overrides, none if the file doesn't exist
+    pub fn load() -> Result<Self> {
+        let path = capacity_file_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let overrides: CapacityOverrides = serde_json::from_str(&contents).map_err(|e| {
+            HarjiraError::Config(format!(
+                "Failed to parse capacity file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        if overrides.version > CAPACITY_FILE_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "Capacity file version {} is newer than supported version {}",
+                overrides.version, CAPACITY_FILE_VERSION
+            )));
+        }
+
+        Ok(overrides)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = capacity_file_path()?;
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(self)?)?;
+
+        debug!("Saved capacity overrides to {}", path.display());
+        Ok(())
+    }
+}
+
+/// Hours to work on `date`
+///
+/// Overrides that can't be read are left out, the config still applies.
+pub fn target_hours(config: &Config, date: NaiveDate) -> f64 {
+    let overrides = CapacityOverrides::load().unwrap_or_else(|e| {
+        debug!("Ignoring capacity overrides: {}", e);
+        CapacityOverrides::default()
+    });
+    target_hours_with(config, &overrides, date)
+}
+
+fn target_hours_with(config: &Config, overrides: &CapacityOverrides, date: NaiveDate) -> f64 {
+    let key = date.format("%Y-%m-%d").to_string();
+    let capacity = &config.capacity;
+    overrides
+        .days
+        .get(&key)
+        .or_else(|| capacity.dates.get(&key))
+        .or_else(|| {
+            capacity
+                .weekdays
+                .iter()
+                .find(|(name, _)| parse_weekday(name) == Some(date.weekday()))
+                .map(|(_, hours)| hours)
+        })
+        .copied()
+        .unwrap_or(config.ai.target_hours)
+}
+
+/// "friday" or "fri", in any case
+pub fn parse_weekday(name: &str) -> Option<Weekday> {
+    name.trim().parse().ok()
+}
+
+fn capacity_file_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("capacity.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TEMPLATE;
+
+    #[test]
+    fn test_most_specific_capacity_wins() {
+        let config: Config = toml::from_str(&format!(
+            "{}\n[capacity.weekdays]\nFriday = 4.0\n[capacity.dates]\n\"2026-10-16\" = 6.0\n\"2026-10-23\" = 2.0\n",
+            TEMPLATE
+        ))
+        .unwrap();
+        let mut overrides = CapacityOverrides::default();
+        overrides.days.insert("2026-10-23".to_string(), 0.0);
+        let day = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+
+        // Wednesday, Friday, a Friday in [capacity.dates], one set with harv capacity
+        assert_eq!(
+            target_hours_with(&config, &overrides, day("2026-10-14")),
+            8.0
+        );
+        assert_eq!(
+            target_hours_with(&config, &overrides, day("2026-10-09")),
+            4.0
+        );
+        assert_eq!(
+            target_hours_with(&config, &overrides, day("2026-10-16")),
+            6.0
+        );
+        assert_eq!(
+            target_hours_with(&config, &overrides, day("2026-10-23")),
+            0.0
+        );
+    }
+}
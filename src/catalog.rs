@@ -0,0 +1,197 @@
+//! Projects and tasks by name, for `--project` and `--task`
+//!
+//! The account's projects and task assignments are cached for a day, so
+//! looking up a name doesn't fetch them every time. A name that isn't in the
+//! cache refreshes it once, in case the project is new.
+
+use crate::error::{HarjiraError, Result};
+use crate::harvest::HarvestClient;
+use crate::models::{HarvestProject, HarvestTask};
+use crate::search::{self, NameMatch};
+use chrono::{DateTime, Duration, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const CATALOG_CACHE_VERSION: u8 = 1;
+
+/// How long the cached projects and tasks are used before fetching them again
+const CATALOG_TTL_HOURS: i64 = 24;
+
+/// Candidates listed when a name matches more than one project or task
+const MAX_CANDIDATES: usize = 10;
+
+/// Last fetched projects and task assignments of an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CatalogCache {
+    version: u8,
+    fetched_at: DateTime<Utc>,
+    account_id: String,
+    projects: Vec<HarvestProject>,
+    /// (project_id, task) pairs for every task assignment
+    assignments: Vec<(u64, HarvestTask)>,
+}
+
+impl CatalogCache {
+    /// The cached catalog when it's fresh and for this account, and whether it was just fetched
+    fn load_or_fetch(harvest_client: &HarvestClient) -> Result<(Self, bool)> {
+        match Self::load() {
+            Some(cache)
+                if cache.account_id == harvest_client.account_id()
+                    && Utc::now() - cache.fetched_at < Duration::hours(CATALOG_TTL_HOURS) =>
+            {
+                Ok((cache, false))
+            }
+            _ => Ok((Self::fetch(harvest_client)?, true)),
+        }
+    }
+
+    fn fetch(harvest_client: &HarvestClient) -> Result<Self> {
+        let cache = Self {
+            version: CATALOG_CACHE_VERSION,
+            fetched_at: Utc::now(),
+            account_id: harvest_client.account_id().to_string(),
+            projects: harvest_client.get_projects()?,
+            assignments: harvest_client.get_all_available_tasks()?,
+        };
+        if let Err(e) = cache.save() {
+            debug!("Failed to save catalog cache: {}", e);
+        }
+        Ok(cache)
+    }
+
+    fn load() -> Option<Self> {
+        let path = catalog_cache_path().ok()?;
+        let contents = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str::<CatalogCache>(&contents) {
+            Ok(cache) if cache.version <= CATALOG_CACHE_VERSION => Some(cache),
+            Ok(_) => None,
+            Err(e) => {
+                debug!("Ignoring catalog cache {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = catalog_cache_path()?;
+        crate::fs::write_private_atomic(&path, serde_json::to_string(self)?)?;
+
+        debug!("Saved catalog cache to {}", path.display());
+        Ok(())
+    }
+
+    /// Tasks of `project_id`, or of every project without one
+    fn tasks(&self, project_id: Option<u64>) -> Vec<HarvestTask> {
+        let mut tasks: Vec<HarvestTask> = Vec::new();
+        for (pid, task) in &self.assignments {
+            if project_id.is_none_or(|id| id == *pid) && !tasks.iter().any(|t| t.id == task.id) {
+                tasks.push(task.clone());
+            }
+        }
+        tasks
+    }
+}
+
+/// Project ID for a `--project` value, an ID or a (partial) name or code
+pub fn resolve_project(harvest_client: &HarvestClient, query: &str) -> Result<u64> {
+    if let Ok(id) = query.trim().parse() {
+        return Ok(id);
+    }
+    resolve(harvest_client, "project", query, |cache| {
+        pick(
+            search::match_name(&cache.projects, query, |p| {
+                let mut names = vec![p.name.clone(), p.display_name()];
+                names.extend(p.code.clone());
+                names
+            }),
+            "project",
+            query,
+            |p| (p.id, p.display_name()),
+        )
+    })
+}
+
+/// Task ID for a `--task` value, an ID or a (partial) name
+///
+/// Names are looked up in the tasks of `project_id` when given.
+pub fn resolve_task(
+    harvest_client: &HarvestClient,
+    project_id: Option<u64>,
+    query: &str,
+) -> Result<u64> {
+    if let Ok(id) = query.trim().parse() {
+        return Ok(id);
+    }
+    resolve(harvest_client, "task", query, |cache| {
+        let tasks = cache.tasks(project_id);
+        pick(
+            search::match_name(&tasks, query, |t| vec![t.name.clone()]),
+            "task",
+            query,
+            |t| (t.id, t.name.clone()),
+        )
+    })
+}
+
+/// Look a name up in the catalog, fetching it again once when it isn't found
+fn resolve(
+    harvest_client: &HarvestClient,
+    kind: &str,
+    query: &str,
+    find: impl Fn(&CatalogCache) -> Option<Result<u64>>,
+) -> Result<u64> {
+    let (cache, fresh) = CatalogCache::load_or_fetch(harvest_client)?;
+    if let Some(found) = find(&cache) {
+        return found;
+    }
+    if !fresh {
+        debug!("Name not in the cached catalog, fetching it again");
+        if let Some(found) = find(&CatalogCache::fetch(harvest_client)?) {
+            return found;
+        }
+    }
+    Err(HarjiraError::InvalidEntry(format!(
+        "No active Harvest {} matches '{}'",
+        kind, query
+    )))
+}
+
+/// The ID of a match, an error listing the candidates, or None when nothing matched
+fn pick<T>(
+    matched: NameMatch<'_, T>,
+    kind: &str,
+    query: &str,
+    describe: impl Fn(&T) -> (u64, String),
+) -> Option<Result<u64>> {
+    match matched {
+        NameMatch::Found(item) => Some(Ok(describe(item).0)),
+        NameMatch::Ambiguous(items) => {
+            let mut message = format!(
+                "'{}' matches {} {}s, use more of the name or the ID:",
+                query,
+                items.len(),
+                kind
+            );
+            for item in items.iter().take(MAX_CANDIDATES) {
+                let (id, name) = describe(item);
+                message.push_str(&format!("\n  {:>10}  {}", id, name));
+            }
+            if items.len() > MAX_CANDIDATES {
+                message.push_str(&format!(
+                    "\n  ... and {} more",
+                    items.len() - MAX_CANDIDATES
+                ));
+            }
+            Some(Err(HarjiraError::InvalidEntry(message)))
+        }
+        NameMatch::NotFound => None,
+    }
+}
+
+fn catalog_cache_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("catalog_cache.json"))
+}
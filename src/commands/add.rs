@@ -0,0 +1,268 @@
+use super::Session;
+use crate::time_parser::format_hours;
+use crate::*;
+use log::info;
+
+/// Create an entry with interactive prompts
+///
+/// With `user` (an ID or email address) the entry is created for that
+/// teammate, which needs an administrator token. `alias`, `hours` and `notes`
+/// answer the project and task, entry type and hours, and description
+/// prompts. With all three the entry is created for today without asking.
+/// `billable` only offers billable (true) or non-billable (false) tasks, and
+/// with `alias` checks that its task is one.
+#[allow(clippy::too_many_arguments)]
+pub fn run_add(
+    ctx: models::Context,
+    session: &Session,
+    client: Option<String>,
+    user: Option<String>,
+    alias: Option<String>,
+    hours: Option<String>,
+    notes: Option<String>,
+    billable: Option<bool>,
+) -> Result<()> {
+    use crate::models::EntryType;
+
+    info!("Starting manual time entry creation...");
+
+    // Load configuration
+    let harvest_client = session.harvest()?;
+    let user = user
+        .as_deref()
+        .map(|user| harvest_client.find_user(user))
+        .transpose()?;
+    let user_id = user.as_ref().map(|u| u.id);
+    let user_name = user.as_ref().map(|u| u.name());
+    if let Some(name) = &user_name {
+        if !ctx.quiet {
+            prompt::display_info(&format!("Creating an entry for {}", name));
+        }
+    }
+
+    let hours = hours.as_deref().map(time_parser::parse_hours).transpose()?;
+    let description = notes
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+    let scripted = alias.is_some() && hours.is_some() && description.is_some();
+
+    // Load usage cache for sorting
+    let mut usage_cache = usage::UsageCache::load()?;
+
+    // Step 1: Select entry type
+    let entry_type = if hours.is_some() {
+        EntryType::Stopped
+    } else {
+        prompt::prompt_entry_type()?
+    };
+
+    // Step 2: Select date
+    let spent_date = if scripted {
+        chrono::Local::now().format("%Y-%m-%d").to_string()
+    } else {
+        prompt::prompt_date_selection()?
+    };
+
+    // Steps 3 and 4: Project and task, from the alias or picked
+    let (project_id, project_name, task_id, task_name) = match &alias {
+        Some(alias) => {
+            let target = aliases::resolve(&session.config, harvest_client, alias)?;
+            let names = &session.config.aliases[alias];
+            if billable.is_some() {
+                let tasks = harvest_client.get_project_tasks(target.project_id)?;
+                let task = tasks.iter().find(|t| t.id == target.task_id);
+                if task.is_some_and(|t| !t.fits_billable(billable)) {
+                    return Err(HarjiraError::Config(format!(
+                        "Alias '{}' books on {}, which is {}",
+                        alias,
+                        names.task,
+                        if billable == Some(true) {
+                            "not billable"
+                        } else {
+                            "billable"
+                        }
+                    )));
+                }
+            }
+            (
+                target.project_id,
+                names.project.clone(),
+                target.task_id,
+                names.task.clone(),
+            )
+        }
+        None => {
+            if !ctx.quiet {
+                prompt::display_info("Fetching available projects...");
+            }
+            let mut projects = harvest_client.get_projects()?;
+            if let Some(client) = &client {
+                projects = search::filter_projects_by_client(projects, client);
+                if projects.is_empty() {
+                    return Err(HarjiraError::Config(format!(
+                        "No active projects found for client matching '{}'",
+                        client
+                    )));
+                }
+            }
+            projects = usage::sort_by_usage(projects, |p| usage_cache.get_project_score(p.id));
+            let selected_project = prompt::prompt_project_selection(&projects)?;
+
+            if !ctx.quiet {
+                prompt::display_info("Fetching tasks...");
+            }
+            let mut tasks = harvest_client.get_project_tasks(selected_project.id)?;
+            tasks.retain(|t| t.fits_billable(billable));
+            if tasks.is_empty() {
+                return Err(HarjiraError::Config(format!(
+                    "{} has no {} tasks",
+                    selected_project.name,
+                    if billable == Some(false) {
+                        "non-billable"
+                    } else {
+                        "billable"
+                    }
+                )));
+            }
+            tasks = usage::sort_by_usage(tasks, |t| {
+                usage_cache.get_project_task_score(selected_project.id, t.id)
+            });
+            let selected_task = prompt::prompt_task_selection(&tasks)?;
+            (
+                selected_project.id,
+                selected_project.name,
+                selected_task.id,
+                selected_task.name,
+            )
+        }
+    };
+
+    // Step 5: Enter description
+    let description = match description {
+        Some(description) => description,
+        None => prompt::prompt_description()?,
+    };
+
+    // Step 6: Enter hours (only for stopped entries)
+    let hours = match (entry_type.is_running(), hours) {
+        (true, _) => None,
+        (false, Some(hours)) => Some(hours),
+        (false, None) => Some(prompt::prompt_hours()?),
+    };
+
+    // Step 7: Confirm
+    let confirmed = scripted
+        || prompt::confirm_entry_creation(
+            &entry_type,
+            &spent_date,
+            &project_name,
+            &task_name,
+            &description,
+            hours,
+            user_name.as_deref(),
+        )?;
+
+    if !confirmed {
+        if !ctx.quiet {
+            prompt::display_info("Entry creation cancelled");
+        }
+        return Ok(());
+    }
+
+    // Step 8: Check for running timer (if creating running timer). Harvest
+    // stops a teammate's running timer by itself.
+    if entry_type.is_running() && user.is_none() {
+        if let Some(timer) = harvest_client.get_running_timer()? {
+            let should_stop = prompt::confirm_stop_timer_for_new(&timer)?;
+            if !should_stop {
+                if !ctx.quiet {
+                    prompt::display_info("Keeping current timer running");
+                }
+                return Ok(());
+            }
+            harvest_client.stop_time_entry(timer.id, &ctx)?;
+            if !ctx.quiet {
+                prompt::display_success("Stopped previous timer");
+            }
+        }
+    }
+
+    // Step 9: Create entry
+    match entry_type {
+        EntryType::Running => {
+            harvest_client.create_time_entry_for_user(
+                user_id,
+                &description,
+                project_id,
+                task_id,
+                &spent_date,
+                &ctx,
+            )?;
+            if !ctx.quiet {
+                prompt::display_success(&format!(
+                    "Started timer{}: {} - {}",
+                    for_user(&user_name),
+                    project_name,
+                    description
+                ));
+            }
+        }
+        EntryType::Stopped => {
+            let hours_val = hours.unwrap();
+            harvest_client.create_stopped_time_entry_for_user(
+                user_id,
+                &description,
+                project_id,
+                task_id,
+                hours_val,
+                &spent_date,
+                &ctx,
+            )?;
+            if !ctx.quiet {
+                prompt::display_success(&format!(
+                    "Created entry{}: {} ({}) on {}",
+                    for_user(&user_name),
+                    description,
+                    format_hours(hours_val),
+                    spent_date
+                ));
+            }
+        }
+    }
+
+    // Record usage for future sorting (skip in dry-run mode)
+    if !ctx.dry_run {
+        usage_cache.record_entry_usage(project_id, task_id);
+        usage_cache.save()?;
+    }
+
+    // Show total for the date
+    if !ctx.quiet {
+        let total = match user_id {
+            Some(user_id) => harvest_client
+                .get_team_time_entries(&[
+                    ("user_id", user_id.to_string()),
+                    ("from", spent_date.clone()),
+                    ("to", spent_date.clone()),
+                ])?
+                .iter()
+                .filter_map(|e| e.entry.hours)
+                .sum(),
+            None => harvest_client.get_total_hours_for_date(&spent_date)?,
+        };
+        outln!(
+            "\nTotal time on {}{}: {}",
+            spent_date,
+            for_user(&user_name),
+            format_hours(total)
+        );
+    }
+
+    Ok(())
+}
+
+fn for_user(name: &Option<String>) -> String {
+    name.as_ref()
+        .map(|name| format!(" for {}", name))
+        .unwrap_or_default()
+}
@@ -0,0 +1,152 @@
+use super::generate::{fetch_catalog, rebalance_options};
+use super::{record_usage, selected_day, Session};
+use crate::activity::{self, ActivityLog, CodingSession};
+use crate::config::ProjectTask;
+use crate::progress::{count_label, Spinner};
+use crate::time_parser::format_hours;
+use crate::*;
+
+/// Review a day's coding sessions and log them as stopped entries
+pub fn run_autolog(
+    ctx: models::Context,
+    session: &Session,
+    date: Option<String>,
+    yesterday: bool,
+) -> Result<()> {
+    let config = &session.config;
+
+    if !config.auto_log.enabled {
+        return Err(HarjiraError::Config(
+            "Auto log is not enabled. Set 'auto_log.enabled = true' in your config file, sync records git activity from then on."
+                .to_string(),
+        ));
+    }
+
+    let day = selected_day(date.as_deref(), yesterday)?
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    let day_str = day.format("%Y-%m-%d").to_string();
+
+    let mut log = ActivityLog::load();
+    let sessions = activity::sessions(
+        &log.on(day),
+        chrono::Duration::minutes(config.auto_log.gap_minutes.into()),
+        chrono::Duration::minutes(config.auto_log.lead_minutes.into()),
+    );
+    if sessions.is_empty() {
+        if !ctx.quiet {
+            prompt::display_info(&format!("No git activity recorded on {}", day_str));
+        }
+        return Ok(());
+    }
+
+    let harvest_client = session.harvest()?;
+    let spinner = Spinner::start(format!("Fetching entries for {}...", day_str), &ctx);
+    let existing_entries = harvest_client.get_time_entries_range(&day_str, &day_str, &ctx)?;
+    let (projects, all_tasks) = fetch_catalog(&spinner, harvest_client, None)?;
+    spinner.finish();
+
+    let proposed_entries: Vec<models::ProposedTimeEntry> = sessions
+        .iter()
+        .map(|coding| proposal(config, coding))
+        .collect();
+    let overlaps: Vec<Option<&models::TimeEntry>> = proposed_entries
+        .iter()
+        .map(|entry| dedupe::find_overlap(entry, &existing_entries, &config.ticket_filter.denylist))
+        .collect();
+
+    let logged: f64 = existing_entries.iter().filter_map(|e| e.hours).sum();
+    let remaining = (capacity::target_hours(config, day) - logged).max(0.0);
+    let catalog = review::Catalog::new(&projects, &all_tasks);
+    let title = format!(
+        "Coding Sessions on {} ({})",
+        day_str,
+        count_label(sessions.len(), "session", "sessions")
+    );
+    let approved_entries = prompt::review_and_approve_entries(
+        &title,
+        &proposed_entries,
+        &catalog,
+        &overlaps,
+        &rebalance_options(config, remaining),
+        None,
+    )?;
+
+    let mut created_count = 0;
+    let mut used = Vec::new();
+    for entry in &approved_entries {
+        match harvest_client.create_stopped_time_entry_with_date(
+            &entry.description,
+            entry.project_id,
+            entry.task_id,
+            entry.hours,
+            &day_str,
+            &ctx,
+        ) {
+            Ok(_) => {
+                created_count += 1;
+                used.push((entry.project_id, entry.task_id));
+            }
+            Err(e) => prompt::display_warning(&format!(
+                "Failed to create entry '{}': {}",
+                entry.description, e
+            )),
+        }
+    }
+    record_usage(&ctx, &used)?;
+
+    // Reviewed sessions aren't proposed again
+    if !ctx.dry_run {
+        log.forget(day);
+        log.save();
+    }
+
+    if !ctx.quiet {
+        if created_count > 0 {
+            let hours: f64 = approved_entries.iter().map(|e| e.hours).sum();
+            prompt::display_success(&format!(
+                "Logged {} on {} ({})",
+                count_label(created_count, "session", "sessions"),
+                day_str,
+                format_hours(hours)
+            ));
+        } else {
+            prompt::display_info("No entries approved");
+        }
+    }
+
+    Ok(())
+}
+
+/// An entry for a session, on the project its repository or first ticket maps to
+///
+/// Without a mapping it falls back to the `[harvest]` project and task, and
+/// without those the review asks for one.
+fn proposal(config: &Config, coding: &CodingSession) -> models::ProposedTimeEntry {
+    let description = coding.notes();
+    let ticket = ticket_parser::extract_tickets(
+        std::slice::from_ref(&description),
+        &config.ticket_filter.denylist,
+    )
+    .into_iter()
+    .next();
+    let default = match (config.harvest.project_id, config.harvest.task_id) {
+        (Some(project_id), Some(task_id)) => Some(ProjectTask {
+            project_id,
+            task_id,
+        }),
+        _ => None,
+    };
+    let target = config
+        .mapping
+        .for_repository(&coding.repository)
+        .or_else(|| ticket.and_then(|key| config.mapping.for_ticket(&key)))
+        .or(default);
+
+    models::ProposedTimeEntry {
+        description,
+        project_id: target.map_or(0, |t| t.project_id),
+        task_id: target.map_or(0, |t| t.task_id),
+        hours: (coding.hours() * 100.0).round() / 100.0,
+        confidence_score: None,
+    }
+}
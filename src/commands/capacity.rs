@@ -0,0 +1,91 @@
+use crate::capacity::{self, CapacityOverrides};
+use crate::time_parser::format_hours;
+use crate::*;
+use log::info;
+
+/// Work `hours` on `date` instead of what the config says
+pub fn run_capacity_set(ctx: models::Context, date: &str, hours: &str) -> Result<()> {
+    let date = parse_date(date)?;
+    // "4h" reads naturally here, and 0 is a day off
+    let hours = match hours.trim().trim_end_matches(['h', 'H']) {
+        "0" => 0.0,
+        hours => time_parser::parse_hours(hours)?,
+    };
+
+    let mut overrides = CapacityOverrides::load()?;
+    overrides.days.insert(date.clone(), hours);
+    if ctx.dry_run {
+        info!("[DRY RUN] Would set capacity for {} to {}", date, hours);
+        return Ok(());
+    }
+    overrides.save()?;
+
+    if !ctx.quiet {
+        prompt::display_success(&format!(
+            "Capacity for {} set to {}",
+            date,
+            format_hours(hours)
+        ));
+    }
+    Ok(())
+}
+
+/// Go back to the configured hours for `date`
+pub fn run_capacity_clear(ctx: models::Context, date: &str) -> Result<()> {
+    let date = parse_date(date)?;
+    let mut overrides = CapacityOverrides::load()?;
+    if overrides.days.remove(&date).is_none() {
+        if !ctx.quiet {
+            prompt::display_info(&format!("No capacity set for {}", date));
+        }
+        return Ok(());
+    }
+    if ctx.dry_run {
+        info!("[DRY RUN] Would clear capacity for {}", date);
+        return Ok(());
+    }
+    overrides.save()?;
+
+    if !ctx.quiet {
+        prompt::display_success(&format!("Cleared capacity for {}", date));
+    }
+    Ok(())
+}
+
+/// Show this week's hours per day and the dates set with `harv capacity set`
+pub fn run_capacity_list(config: &Config) -> Result<()> {
+    let today = chrono::Local::now().date_naive();
+    let monday = today.week(chrono::Weekday::Mon).first_day();
+
+    outln!("\nThis week:");
+    for day in monday.iter_days().take(7) {
+        let marker = if day == today { "  (today)" } else { "" };
+        outln!(
+            "  {}  {:>6}{}",
+            day.format("%a %Y-%m-%d"),
+            format_hours(capacity::target_hours(config, day)),
+            marker
+        );
+    }
+
+    let overrides = CapacityOverrides::load()?;
+    let today = today.format("%Y-%m-%d").to_string();
+    let upcoming: Vec<_> = overrides
+        .days
+        .iter()
+        .filter(|(date, _)| **date >= today)
+        .collect();
+    if !upcoming.is_empty() {
+        outln!("\nSet with harv capacity set:");
+        for (date, hours) in upcoming {
+            outln!("  {}  {:>6}", date, format_hours(*hours));
+        }
+    }
+    Ok(())
+}
+
+fn parse_date(date: &str) -> Result<String> {
+    chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map_err(|_| HarjiraError::InvalidEntry(format!("Invalid date '{}', use YYYY-MM-DD", date)))
+}
@@ -0,0 +1,268 @@
+use super::Session;
+use crate::*;
+use console::style;
+use std::fs;
+
+pub fn run_login_jira() -> Result<()> {
+    let config = Config::load()?;
+
+    let oauth_config = config.jira.oauth.as_ref().ok_or_else(|| {
+        HarjiraError::Config(
+            "Jira OAuth is not configured. Add a [jira.oauth] section with client_id and client_secret."
+                .to_string(),
+        )
+    })?;
+
+    let token = oauth::login(oauth_config, &config.jira.base_url)?;
+    prompt::display_success(&format!("Logged in to Jira ({})", token.site_url));
+    Ok(())
+}
+
+pub fn run_config_init(interactive: bool) -> Result<()> {
+    if interactive {
+        let config_path = setup::run()?;
+        outln!();
+        prompt::display_success(&format!(
+            "Configuration file created at: {}",
+            config_path.display()
+        ));
+        outln!("\nCheck it with:");
+        outln!("  harv config validate");
+        outln!("\nTip: Enable shell completions with:");
+        outln!("  harv completions install");
+        return Ok(());
+    }
+
+    Config::create_template()?;
+    let config_path = Config::config_path()?;
+    outln!("✓ Configuration file created at: {}", config_path.display());
+    outln!("\nPlease edit the file and add your API credentials:");
+    outln!("  - Harvest access token: https://id.getharvest.com/developers");
+    outln!("  - Jira personal access token: https://id.atlassian.com/manage-profile/security/api-tokens");
+    outln!("\nTip: Enable shell completions with:");
+    outln!("  harv completions install");
+    Ok(())
+}
+
+pub fn run_config_account() -> Result<()> {
+    let config = Config::load_unvalidated()?;
+    if config.harvest.access_token.is_empty()
+        || config.harvest.access_token.contains("your_harvest")
+    {
+        return Err(HarjiraError::Config(
+            "Harvest access token not configured. Please update your config file.".to_string(),
+        ));
+    }
+
+    prompt::display_info("Fetching your Harvest accounts...");
+    let accounts =
+        HarvestClient::list_accounts(&config.harvest.access_token, &config.harvest.user_agent)?;
+    let account = prompt::prompt_account_selection(&accounts)?;
+
+    let config_path = Config::set_account_id(account.id)?;
+    prompt::display_success(&format!(
+        "Using Harvest account {} ({}), saved to {}",
+        account.name,
+        account.id,
+        config_path.display()
+    ));
+    Ok(())
+}
+
+pub fn run_config_show() -> Result<()> {
+    let config = Config::load()?;
+    outln!("\nCurrent Configuration");
+    outln!("====================\n");
+    config.display();
+    Ok(())
+}
+
+pub fn run_config_prompt(default: bool) -> Result<()> {
+    let template = if default {
+        None
+    } else {
+        ai::load_prompt_template()?
+    };
+    print!(
+        "{}",
+        template.as_deref().unwrap_or(ai::DEFAULT_PROMPT_TEMPLATE)
+    );
+    Ok(())
+}
+
+/// Move the tokens in the config file into an encrypted `[secrets]` section
+///
+/// Tokens that are already encrypted are kept, with plain ones taking their
+/// place, so running it again after pasting in a new token updates the blob.
+pub fn run_config_encrypt(
+    tool: Option<String>,
+    recipient: Option<String>,
+    identity: Option<String>,
+) -> Result<()> {
+    let config_path = Config::config_path()?;
+    let content = fs::read_to_string(&config_path)?;
+    let mut table: toml::Table = toml::from_str(&content)?;
+
+    let current = table
+        .get("secrets")
+        .and_then(|v| v.as_table())
+        .map(secrets::Cipher::from_table);
+    secrets::decrypt_into(&mut table)?;
+    let tokens = secrets::collect(&table);
+    if tokens.is_empty() {
+        return Err(HarjiraError::Config(
+            "No tokens in the config file to encrypt".to_string(),
+        ));
+    }
+
+    let cipher = secrets::Cipher {
+        tool: tool
+            .or_else(|| current.as_ref().map(|c| c.tool.clone()))
+            .unwrap_or_else(|| "gpg".to_string()),
+        recipient: recipient.or_else(|| current.as_ref().and_then(|c| c.recipient.clone())),
+        identity: identity.or_else(|| current.as_ref().and_then(|c| c.identity.clone())),
+    };
+    if !secrets::TOOLS.contains(&cipher.tool.as_str()) {
+        return Err(HarjiraError::Config(format!(
+            "Unsupported tool: {}. Supported: {}",
+            cipher.tool,
+            secrets::TOOLS.join(", ")
+        )));
+    }
+
+    let plain = toml::to_string(&tokens)
+        .map_err(|e| HarjiraError::Config(format!("Failed to write the tokens: {}", e)))?;
+    let armored = cipher.encrypt(&plain)?;
+    let updated = format!(
+        "{}\n{}",
+        secrets::strip_tokens(&content),
+        secrets::render_section(&cipher, &armored)
+    );
+    // Never replace a working config with one that doesn't parse
+    toml::from_str::<toml::Table>(&updated)?;
+    Config::rewrite(&updated)?;
+
+    let count: usize = tokens
+        .values()
+        .filter_map(|fields| fields.as_table())
+        .map(|fields| fields.len())
+        .sum();
+    prompt::display_success(&format!(
+        "Encrypted {} with {} in {}",
+        progress::count_label(count, "token", "tokens"),
+        cipher.tool,
+        config_path.display()
+    ));
+    Ok(())
+}
+
+/// Check the config file, and with `remote` that the services accept it
+pub fn run_config_validate(remote: bool) -> Result<()> {
+    let config = Config::load()?;
+    outln!("✓ Configuration is valid");
+    outln!("  Config file: {}", Config::config_path()?.display());
+    if !remote {
+        return Ok(());
+    }
+
+    let session = Session::new(config);
+    let config = &session.config;
+    outln!("\nRemote checks:");
+    let mut failed = 0;
+    let mut report = |name: &str, result: Result<String>| match result {
+        Ok(detail) => outln!("  {} {}: {}", style("✓").green(), name, detail),
+        Err(e) => {
+            failed += 1;
+            outln!("  {} {}: {}", style("✗").red(), name, e);
+        }
+    };
+
+    if config.backend.is_harvest() {
+        report("Harvest account", check_harvest_user(&session));
+        match (config.harvest.project_id, config.harvest.task_id) {
+            (Some(project_id), Some(task_id)) => report(
+                "Harvest project and task",
+                check_harvest_task(&session, project_id, task_id),
+            ),
+            _ => outln!("  - Harvest project and task: skipped, none configured"),
+        }
+    }
+    let trackers = config.tracker.providers();
+    if trackers.iter().any(|p| p == "jira") || config.backend.provider.eq_ignore_ascii_case("tempo")
+    {
+        report("Jira", check_jira(&session));
+    }
+    if config.ai.enabled {
+        report("AI", check_ai(&session));
+    }
+
+    if failed > 0 {
+        return Err(HarjiraError::Config(format!(
+            "{} failed",
+            progress::count_label(failed, "remote check", "remote checks")
+        )));
+    }
+    Ok(())
+}
+
+fn check_harvest_user(session: &Session) -> Result<String> {
+    let user = session.harvest()?.get_current_user()?;
+    Ok(format!(
+        "signed in as {} {} (account {})",
+        user.first_name, user.last_name, session.config.harvest.account_id
+    ))
+}
+
+/// The configured task must be active on a project the user can log time on
+fn check_harvest_task(session: &Session, project_id: u64, task_id: u64) -> Result<String> {
+    let harvest_client = session.harvest()?;
+    let project = harvest_client
+        .get_projects()?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| {
+            HarjiraError::Config(format!(
+                "project {} is not among your active projects",
+                project_id
+            ))
+        })?;
+    let task = harvest_client
+        .get_project_tasks(project_id)?
+        .into_iter()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| {
+            HarjiraError::Config(format!(
+                "task {} is not assigned to {} ({})",
+                task_id, project.name, project_id
+            ))
+        })?;
+    Ok(format!("{} ▸ {}", project.name, task.name))
+}
+
+fn check_jira(session: &Session) -> Result<String> {
+    let info = session.jira()?.get_server_info()?;
+    Ok(format!(
+        "{} answers (version {})",
+        session.config.jira.base_url,
+        if info.version.is_empty() {
+            "unknown"
+        } else {
+            info.version.as_str()
+        }
+    ))
+}
+
+fn check_ai(session: &Session) -> Result<String> {
+    let provider = session.ai()?;
+    let models = provider.list_models()?;
+    let mut detail = format!(
+        "{} key lists {}",
+        provider.name(),
+        progress::count_label(models.len(), "model", "models")
+    );
+    // Aliases such as "-latest" aren't listed, so a missing model is only noted
+    if !models.iter().any(|m| m == provider.model()) {
+        detail.push_str(&format!(", {} is not among them", provider.model()));
+    }
+    Ok(detail)
+}
@@ -0,0 +1,227 @@
+use super::record_usage;
+use super::Session;
+use crate::progress::Spinner;
+use crate::*;
+use log::info;
+
+pub fn run_continue(
+    ctx: models::Context,
+    session: &Session,
+    query: Option<String>,
+    days: Option<u8>,
+    new_entry: bool,
+    restart: bool,
+) -> Result<()> {
+    info!("Starting continue operation...");
+
+    // Load configuration
+    let config = &session.config;
+    let harvest_client = session.harvest()?;
+
+    // Determine lookback period (default: 1 day = today only)
+    let lookback_days = days.unwrap_or(config.settings.continue_days.unwrap_or(1));
+
+    // Calculate date range
+    let today = chrono::Local::now();
+    let from_date = if lookback_days == 1 {
+        // Today only
+        today.format("%Y-%m-%d").to_string()
+    } else {
+        // N days back
+        let from = today - chrono::Duration::days((lookback_days - 1) as i64);
+        from.format("%Y-%m-%d").to_string()
+    };
+    let to_date = today.format("%Y-%m-%d").to_string();
+
+    // Fetch time entries for date range
+    if !ctx.quiet {
+        if lookback_days == 1 {
+            prompt::display_info("Fetching today's time entries...");
+        } else {
+            prompt::display_info(&format!(
+                "Fetching entries from last {} days...",
+                lookback_days
+            ));
+        }
+    }
+
+    let spinner = Spinner::start("Loading time entries...", &ctx);
+    let all_entries = harvest_client.get_time_entries_range(&from_date, &to_date, &ctx)?;
+    spinner.finish();
+
+    // Filter to stopped entries only (can't continue a running timer)
+    let stopped_entries: Vec<_> = all_entries.into_iter().filter(|e| !e.is_running).collect();
+
+    // Filter out entries without project/task (can't restart them)
+    let valid_entries: Vec<_> = stopped_entries
+        .into_iter()
+        .filter(|e| e.project.is_some() && e.task.is_some())
+        .collect();
+
+    // Favorites are offered at the top of the picker unless searching
+    let favorites = if query.is_none() {
+        favorites::Favorites::load()?
+    } else {
+        favorites::Favorites::new()
+    };
+
+    // Check if we have any entries to continue
+    if valid_entries.is_empty() && favorites.all().is_empty() {
+        let msg = if lookback_days == 1 {
+            "No stopped time entries found today"
+        } else {
+            &format!(
+                "No stopped time entries found in last {} days",
+                lookback_days
+            )
+        };
+        if !ctx.quiet {
+            prompt::display_info(msg);
+        }
+        return Ok(());
+    }
+
+    info!("Found {} valid entries to continue", valid_entries.len());
+
+    // Narrow down by search query if one was given
+    let valid_entries = match query.as_deref() {
+        Some(query) => {
+            let matches = search::filter_entries(valid_entries, query);
+            if matches.is_empty() {
+                if !ctx.quiet {
+                    prompt::display_info(&format!("No entries matching '{}'", query));
+                }
+                return Ok(());
+            }
+            info!("{} entries match '{}'", matches.len(), query);
+            matches
+        }
+        None => valid_entries,
+    };
+
+    // Collapse the same work repeated across days into one candidate
+    let mut candidates: Vec<_> = favorites.all().iter().map(|f| f.to_candidate()).collect();
+    candidates.extend(search::collapse_duplicates(valid_entries));
+
+    // Prompt user to select entry, unless the query left a single match
+    let selected = if query.is_some() && candidates.len() == 1 {
+        &candidates[0]
+    } else {
+        prompt::prompt_entry_selection(&candidates)?
+    };
+    let selected_entry = &selected.entry;
+
+    let notes = selected_entry
+        .notes
+        .as_deref()
+        .unwrap_or("(no description)");
+    let project_name = selected_entry
+        .project
+        .as_ref()
+        .map(|p| p.name.as_str())
+        .unwrap_or("Unknown");
+    let task_name = selected_entry
+        .task
+        .as_ref()
+        .map(|t| t.name.as_str())
+        .unwrap_or("Unknown");
+
+    info!("Selected entry: {} - {}", project_name, notes);
+
+    // Determine how to continue (restart vs new timer)
+    let continue_mode = if selected.favorite.is_some() {
+        // Favorites have no existing entry to restart
+        models::ContinueMode::NewEntry
+    } else if restart {
+        models::ContinueMode::Restart
+    } else if new_entry {
+        models::ContinueMode::NewEntry
+    } else if let Some(ref mode_str) = config.settings.continue_mode {
+        // Use config setting
+        match mode_str.as_str() {
+            "restart" => models::ContinueMode::Restart,
+            "new" => models::ContinueMode::NewEntry,
+            _ => {
+                // "ask" or invalid value -> prompt user
+                prompt::prompt_continue_mode(selected_entry)?
+            }
+        }
+    } else {
+        // No config, no flags -> prompt user
+        prompt::prompt_continue_mode(selected_entry)?
+    };
+
+    info!("Continue mode: {:?}", continue_mode);
+
+    // Check for running timer conflicts
+    let running_timer = harvest_client.get_running_timer()?;
+
+    if let Some(timer) = running_timer {
+        // Check if timer is already for this task (same notes)
+        if let Some(timer_notes) = &timer.notes {
+            if timer_notes == notes && timer.is_today() {
+                if !ctx.quiet {
+                    prompt::display_info(&format!(
+                        "Timer already running for this task: {}",
+                        notes
+                    ));
+                }
+                return Ok(());
+            }
+        }
+
+        // Timer is for a different task
+        let should_stop = if ctx.auto_start {
+            // auto_start implies auto_stop for continue command
+            true
+        } else {
+            prompt::confirm_stop_timer(&timer, notes)?
+        };
+
+        if !should_stop {
+            if !ctx.quiet {
+                prompt::display_info("Keeping current timer running");
+            }
+            return Ok(());
+        }
+
+        // Stop current timer
+        harvest_client.stop_time_entry(timer.id, &ctx)?;
+        if !ctx.quiet {
+            prompt::display_success("Stopped previous timer");
+        }
+    }
+
+    // Execute based on continue mode
+    match continue_mode {
+        models::ContinueMode::Restart => {
+            // Restart existing entry
+            let restarted = harvest_client.restart_time_entry(selected_entry.id, &ctx)?;
+
+            if !ctx.quiet {
+                prompt::display_success(&format!(
+                    "Restarted timer on {}: {} > {} - {}",
+                    restarted.spent_date, project_name, task_name, notes
+                ));
+            }
+        }
+        models::ContinueMode::NewEntry => {
+            // Create new timer (existing behavior)
+            harvest_client.start_timer_from_entry(selected_entry, &ctx)?;
+
+            if !ctx.quiet {
+                prompt::display_success(&format!(
+                    "Started new timer: {} > {} - {}",
+                    project_name, task_name, notes
+                ));
+            }
+        }
+    }
+
+    // Record usage for future sorting
+    if let (Some(project), Some(task)) = (&selected_entry.project, &selected_entry.task) {
+        record_usage(&ctx, &[(project.id, task.id)])?;
+    }
+
+    Ok(())
+}
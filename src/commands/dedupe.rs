@@ -0,0 +1,78 @@
+use super::Session;
+use crate::progress::{count_label, Spinner};
+use crate::*;
+
+pub fn run_dedupe(ctx: models::Context, session: &Session, days: u8, yes: bool) -> Result<()> {
+    let harvest_client = session.harvest()?;
+
+    let today = chrono::Local::now();
+    let from_date = (today - chrono::Duration::days(days.max(1) as i64 - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+    let to_date = today.format("%Y-%m-%d").to_string();
+
+    let spinner = Spinner::start("Scanning time entries...", &ctx);
+    let entries = harvest_client.get_time_entries_range(&from_date, &to_date, &ctx)?;
+    spinner.finish();
+
+    let groups = dedupe::find_duplicates(&entries);
+    if groups.is_empty() {
+        if !ctx.quiet {
+            prompt::display_info(&format!(
+                "No duplicate entries found from {} to {}",
+                from_date, to_date
+            ));
+        }
+        return Ok(());
+    }
+
+    if !ctx.quiet {
+        prompt::display_info(&format!(
+            "Found {}",
+            count_label(groups.len(), "group of duplicates", "groups of duplicates")
+        ));
+    }
+
+    let mut merged = 0;
+    let mut deleted = 0;
+
+    for group in &groups {
+        let confirmed = yes || prompt::confirm_merge_duplicates(group)?;
+        if !confirmed {
+            continue;
+        }
+
+        // Update the kept entry first so a failure never loses hours
+        if let Err(e) =
+            harvest_client.update_time_entry_hours(group.keep.id, group.total_hours(), &ctx)
+        {
+            prompt::display_warning(&format!(
+                "Failed to update entry {}: {}. Skipping this group.",
+                group.keep.id, e
+            ));
+            continue;
+        }
+
+        for extra in &group.extras {
+            match harvest_client.delete_time_entry(extra.id, &ctx) {
+                Ok(()) => deleted += 1,
+                Err(e) => prompt::display_warning(&format!(
+                    "Failed to delete duplicate entry {}: {}",
+                    extra.id, e
+                )),
+            }
+        }
+        merged += 1;
+    }
+
+    if !ctx.quiet {
+        outln!();
+        prompt::display_success(&format!(
+            "Merged {} and deleted {}",
+            count_label(merged, "group", "groups"),
+            count_label(deleted, "entry", "entries")
+        ));
+    }
+
+    Ok(())
+}
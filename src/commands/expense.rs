@@ -0,0 +1,90 @@
+use super::Session;
+use crate::progress::Spinner;
+use crate::*;
+
+pub fn run_expense_add(
+    ctx: models::Context,
+    session: &Session,
+    amount: Option<String>,
+    notes: Option<String>,
+) -> Result<()> {
+    // Validate a passed amount before prompting for anything else
+    let amount = amount.as_deref().map(expenses::parse_amount).transpose()?;
+
+    let harvest_client = session.harvest()?;
+    let usage_cache = usage::UsageCache::load()?;
+
+    // Step 1: Select date
+    let spent_date = prompt::prompt_date_selection()?;
+
+    // Step 2: Fetch and select project
+    if !ctx.quiet {
+        prompt::display_info("Fetching available projects...");
+    }
+    let mut projects = harvest_client.get_projects()?;
+    projects = usage::sort_by_usage(projects, |p| usage_cache.get_project_score(p.id));
+    let selected_project = prompt::prompt_project_selection(&projects)?;
+
+    // Step 3: Fetch and select category
+    if !ctx.quiet {
+        prompt::display_info("Fetching expense categories...");
+    }
+    let categories = harvest_client.get_expense_categories()?;
+    let selected_category = prompt::prompt_expense_category_selection(&categories)?;
+
+    // Step 4: Amount and notes
+    let total_cost = match amount {
+        Some(amount) => amount,
+        None => prompt::prompt_amount()?,
+    };
+    let notes = match notes {
+        Some(notes) => Some(notes.trim().to_string()).filter(|n| !n.is_empty()),
+        None => prompt::prompt_expense_notes()?,
+    };
+
+    let request = expenses::CreateExpenseRequest {
+        project_id: selected_project.id,
+        expense_category_id: selected_category.id,
+        spent_date,
+        total_cost,
+        notes,
+    };
+    harvest_client.create_expense(&request, &ctx)?;
+
+    if !ctx.quiet {
+        prompt::display_success(&format!(
+            "Added expense: {:.2} for {} > {} on {}",
+            request.total_cost,
+            selected_project.display_name(),
+            selected_category.name,
+            request.spent_date
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn run_expense_categories(ctx: models::Context, session: &Session) -> Result<()> {
+    let harvest_client = session.harvest()?;
+
+    let spinner = Spinner::start("Fetching expense categories...", &ctx);
+    let categories = harvest_client.get_expense_categories()?;
+    spinner.finish();
+
+    if categories.is_empty() {
+        prompt::display_info("No active expense categories");
+        return Ok(());
+    }
+
+    outln!("\nExpense Categories:");
+    for category in &categories {
+        match (&category.unit_name, category.unit_price) {
+            (Some(unit), Some(price)) => {
+                outln!("  • {} ({:.2} per {})", category.name, price, unit)
+            }
+            _ => outln!("  • {}", category.name),
+        }
+    }
+
+    Ok(())
+}
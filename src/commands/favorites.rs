@@ -0,0 +1,215 @@
+use super::{or_queue, queue_offline, record_usage, Session};
+use crate::*;
+use log::info;
+
+pub fn run_start(ctx: models::Context, session: &Session, name: &str) -> Result<()> {
+    let favorites = favorites::Favorites::load()?;
+    let favorite = favorites.get(name).ok_or_else(|| {
+        HarjiraError::Config(format!(
+            "No favorite named '@{}'. Add one with 'harv fav add'",
+            favorites::normalize_name(name)
+        ))
+    })?;
+
+    let notes = favorite.render_notes();
+    if !start_timer(&ctx, session, &notes, favorite.project_id, favorite.task_id)? {
+        return Ok(());
+    }
+
+    if !ctx.quiet {
+        prompt::display_success(&format!(
+            "Started timer: {} > {} - {}",
+            favorite.project_name, favorite.task_name, notes
+        ));
+    }
+
+    // Record usage for future sorting
+    record_usage(&ctx, &[(favorite.project_id, favorite.task_id)])?;
+
+    Ok(())
+}
+
+/// Start a timer for today, stopping the running one after asking
+///
+/// Returns false when nothing was started: the same timer is already
+/// running, or the user wants to keep the current one.
+pub(super) fn start_timer(
+    ctx: &models::Context,
+    session: &Session,
+    notes: &str,
+    project_id: u64,
+    task_id: u64,
+) -> Result<bool> {
+    let harvest_client = session.harvest()?;
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    let running = match harvest_client.get_running_timer() {
+        // Whatever is running offline must stop when the new timer starts
+        Err(e) if offline::is_unreachable(&e) => {
+            queue_offline(
+                harvest_client,
+                offline::Operation::Stop {
+                    entry_id: None,
+                    hours: None,
+                },
+            )?;
+            None
+        }
+        other => other?,
+    };
+
+    // Check for running timer conflicts
+    if let Some(timer) = running {
+        if timer.notes.as_deref() == Some(notes) && timer.is_today() {
+            if !ctx.quiet {
+                prompt::display_info(&format!("Timer already running for this task: {}", notes));
+            }
+            return Ok(false);
+        }
+
+        let should_stop = if ctx.auto_stop {
+            true
+        } else {
+            prompt::confirm_stop_timer_for_new(&timer)?
+        };
+        if !should_stop {
+            if !ctx.quiet {
+                prompt::display_info("Keeping current timer running");
+            }
+            return Ok(false);
+        }
+        let stopped = harvest_client.stop_time_entry(timer.id, ctx);
+        or_queue(
+            harvest_client,
+            stopped,
+            offline::Operation::Stop {
+                entry_id: Some(timer.id),
+                hours: None,
+            },
+        )?;
+        if !ctx.quiet {
+            prompt::display_success("Stopped previous timer");
+        }
+    }
+
+    let started =
+        harvest_client.create_time_entry_with_date(notes, project_id, task_id, &today, ctx);
+    or_queue(
+        harvest_client,
+        started,
+        offline::Operation::Create {
+            spent_date: today,
+            project_id,
+            task_id,
+            notes: notes.to_string(),
+            hours: None,
+            entry_id: None,
+        },
+    )?;
+    Ok(true)
+}
+
+pub fn run_fav_add(ctx: models::Context, session: &Session, name: Option<String>) -> Result<()> {
+    let harvest_client = session.harvest()?;
+    let usage_cache = usage::UsageCache::load()?;
+    let mut favorites = favorites::Favorites::load()?;
+
+    // Step 1: Name the favorite
+    let name = match name {
+        Some(name) => {
+            favorites::validate_name(&name).map_err(HarjiraError::Config)?;
+            favorites::normalize_name(&name)
+        }
+        None => prompt::prompt_favorite_name()?,
+    };
+
+    // Step 2: Select project
+    if !ctx.quiet {
+        prompt::display_info("Fetching available projects...");
+    }
+    let mut projects = harvest_client.get_projects()?;
+    projects = usage::sort_by_usage(projects, |p| usage_cache.get_project_score(p.id));
+    let selected_project = prompt::prompt_project_selection(&projects)?;
+
+    // Step 3: Select task
+    if !ctx.quiet {
+        prompt::display_info("Fetching tasks...");
+    }
+    let mut tasks = harvest_client.get_project_tasks(selected_project.id)?;
+    tasks = usage::sort_by_usage(tasks, |t| {
+        usage_cache.get_project_task_score(selected_project.id, t.id)
+    });
+    let selected_task = prompt::prompt_task_selection(&tasks)?;
+
+    // Step 4: Notes template
+    let notes = prompt::prompt_description()?;
+
+    let replaced = favorites.add(favorites::Favorite {
+        name: name.clone(),
+        project_id: selected_project.id,
+        project_name: selected_project.name.clone(),
+        task_id: selected_task.id,
+        task_name: selected_task.name.clone(),
+        notes,
+    });
+
+    if ctx.dry_run {
+        info!("[DRY RUN] Would save favorite @{}", name);
+        return Ok(());
+    }
+    favorites.save()?;
+
+    if !ctx.quiet {
+        let verb = if replaced { "Updated" } else { "Saved" };
+        prompt::display_success(&format!(
+            "{} favorite @{}: {} > {}",
+            verb, name, selected_project.name, selected_task.name
+        ));
+        prompt::display_info(&format!("Start it with: harv start @{}", name));
+    }
+
+    Ok(())
+}
+
+pub fn run_fav_list() -> Result<()> {
+    let favorites = favorites::Favorites::load()?;
+
+    if favorites.all().is_empty() {
+        prompt::display_info("No favorites yet. Add one with 'harv fav add'");
+        return Ok(());
+    }
+
+    outln!("\nFavorites:");
+    for favorite in favorites.all() {
+        outln!(
+            "  @{} - {} > {} - {}",
+            favorite.name, favorite.project_name, favorite.task_name, favorite.notes
+        );
+    }
+
+    Ok(())
+}
+
+pub fn run_fav_remove(ctx: models::Context, name: &str) -> Result<()> {
+    let mut favorites = favorites::Favorites::load()?;
+    let name = favorites::normalize_name(name);
+
+    if !favorites.remove(&name) {
+        return Err(HarjiraError::Config(format!(
+            "No favorite named '@{}'",
+            name
+        )));
+    }
+
+    if ctx.dry_run {
+        info!("[DRY RUN] Would remove favorite @{}", name);
+        return Ok(());
+    }
+    favorites.save()?;
+
+    if !ctx.quiet {
+        prompt::display_success(&format!("Removed favorite @{}", name));
+    }
+
+    Ok(())
+}
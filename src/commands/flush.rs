@@ -0,0 +1,64 @@
+use super::Session;
+use crate::offline::{self, FlushReport, OfflineQueue};
+use crate::progress::count_label;
+use crate::*;
+use log::debug;
+
+pub fn run_flush(ctx: models::Context, session: &Session) -> Result<()> {
+    if OfflineQueue::load()?.is_empty() {
+        if !ctx.quiet {
+            prompt::display_info("Nothing queued");
+        }
+        return Ok(());
+    }
+
+    let report = offline::flush(session.harvest()?, &ctx)?;
+    show_report(&report, &ctx);
+    if report.remaining > 0 {
+        return Err(HarjiraError::Network(format!(
+            "Harvest still can't be reached, {} left in the queue",
+            count_label(report.remaining, "change", "changes")
+        )));
+    }
+    Ok(())
+}
+
+/// Send queued changes before another command runs
+///
+/// Quiet while Harvest is still unreachable, the command will say so itself.
+pub fn flush_queued(ctx: &models::Context) {
+    if !OfflineQueue::load().is_ok_and(|queue| !queue.is_empty()) {
+        return;
+    }
+
+    let result = Session::load().and_then(|session| {
+        if !session.config.backend.is_harvest() {
+            return Ok(FlushReport::default());
+        }
+        offline::flush(session.harvest()?, ctx)
+    });
+    match result {
+        Ok(report) => show_report(&report, ctx),
+        Err(e) => debug!("Couldn't send the offline queue: {}", e),
+    }
+}
+
+fn show_report(report: &FlushReport, ctx: &models::Context) {
+    for (queued, reason) in &report.rejected {
+        prompt::display_warning(&format!(
+            "Harvest rejected queued change ({}, queued {}): {}",
+            queued.operation.label(),
+            queued
+                .queued_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M"),
+            reason
+        ));
+    }
+    if report.sent > 0 && !ctx.quiet {
+        prompt::display_success(&format!(
+            "Sent {} queued while offline",
+            count_label(report.sent, "change", "changes")
+        ));
+    }
+}
@@ -0,0 +1,841 @@
+use super::Session;
+use super::{active_sprint_issues, record_usage, ticket_details};
+use crate::progress::{count_label, Spinner};
+use crate::time_parser::format_hours;
+use crate::*;
+use log::{debug, info};
+
+/// Heading of the review list
+const AI_REVIEW_TITLE: &str = "AI Generated Time Entries";
+
+#[allow(clippy::too_many_arguments)]
+pub fn run_generate(
+    ctx: models::Context,
+    mut session: Session,
+    summary: Option<String>,
+    provider_override: Option<String>,
+    auto_approve: bool,
+    target_hours_override: Option<String>,
+    force: bool,
+    week: bool,
+    plan_output: bool,
+    from_file: Option<String>,
+    billable: Option<bool>,
+) -> Result<()> {
+    if plan_output && !ctx.dry_run {
+        return Err(HarjiraError::Config(
+            "--output json only writes a plan, use it with --dry-run".to_string(),
+        ));
+    }
+    if let Some(path) = from_file {
+        return run_generate_from_file(ctx, &session, &path, auto_approve);
+    }
+
+    info!("Starting AI-powered time entry generation...");
+
+    // Check if AI is enabled
+    if !session.config.ai.enabled {
+        return Err(HarjiraError::Config(
+            "AI generation is not enabled. Set 'ai.enabled = true' in your config file."
+                .to_string(),
+        ));
+    }
+
+    // Apply overrides
+    if let Some(provider) = provider_override {
+        session.config.ai.provider = provider;
+    }
+    // Replaces the capacity of every day, not just the default
+    let target_override = target_hours_override
+        .map(|target| time_parser::parse_hours(&target))
+        .transpose()?;
+
+    if week {
+        return run_generate_week(
+            ctx,
+            &session,
+            summary,
+            auto_approve,
+            force,
+            plan_output,
+            target_override,
+            billable,
+        );
+    }
+    let config = &session.config;
+    let target_hours = target_override
+        .unwrap_or_else(|| capacity::target_hours(config, chrono::Local::now().date_naive()));
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    if let Some(day) = leave::on(&today) {
+        if !ctx.quiet {
+            prompt::display_info(&format!(
+                "Today is marked as {} leave, nothing to generate",
+                day.kind
+            ));
+        }
+        return Ok(());
+    }
+
+    // Get summary from user if not provided
+    let work_summary = if let Some(s) = summary {
+        s
+    } else {
+        prompt::prompt_work_summary()?
+    };
+
+    if work_summary.trim().is_empty() {
+        return Err(HarjiraError::Config(
+            "Work summary cannot be empty".to_string(),
+        ));
+    }
+
+    // Check the monthly spend before doing any work
+    let mut ledger = ai::ledger::AiLedger::load();
+    let month_cost = ledger.month_cost(chrono::Utc::now());
+    if force {
+        if config
+            .ai
+            .monthly_cost_cap
+            .is_some_and(|cap| month_cost >= cap)
+            && !ctx.quiet
+        {
+            prompt::display_warning("Monthly AI cost cap reached, generating anyway (--force)");
+        }
+    } else {
+        ai::ledger::check_monthly_cap(month_cost, config.ai.monthly_cost_cap)?;
+    }
+
+    // Initialize clients
+    let harvest_client = session.harvest()?;
+    let ai_provider = session.ai()?;
+
+    // Gather context for AI
+    let spinner = Spinner::start("Fetching today's time entries...", &ctx);
+    let existing_entries = harvest_client.get_todays_time_entries()?;
+    let today_total = harvest_client.get_total_hours_today()?;
+    let (projects, all_tasks) = fetch_catalog(&spinner, harvest_client, billable)?;
+    spinner.finish();
+    let tasks: Vec<models::HarvestTask> = all_tasks.iter().map(|(_, task)| task.clone()).collect();
+    let task_assignments: Vec<(u64, u64)> = all_tasks
+        .iter()
+        .map(|(project_id, task)| (*project_id, task.id))
+        .collect();
+
+    let sprint_issues = active_sprint_issues(&ctx, &session);
+    let ai_context = ai::AiContext {
+        available_projects: projects.clone(),
+        available_tasks: tasks,
+        task_assignments,
+        existing_entries: existing_entries.clone(),
+        target_hours,
+        today_total_hours: today_total,
+        ticket_details: ticket_details(&ctx, &session, &work_summary, &sprint_issues),
+        sprint_issues,
+        prompt_template: ai::load_prompt_template()?,
+    };
+
+    // Generate entries using AI
+    let label = format!("Generating time entries using {}...", ai_provider.name());
+    let proposed_entries = generate_entries(
+        &ctx,
+        config,
+        ai_provider,
+        &work_summary,
+        &ai_context,
+        &mut ledger,
+        label,
+    )?;
+
+    if proposed_entries.is_empty() {
+        if !ctx.quiet {
+            prompt::display_warning("AI did not generate any time entries");
+        }
+        return Ok(());
+    }
+
+    let remaining = (target_hours - today_total).max(0.0);
+    if plan_output {
+        let today = chrono::Local::now().date_naive();
+        return print_plan(&[(today, remaining, existing_entries, proposed_entries)]);
+    }
+
+    // Flag proposals that look like work already logged today
+    let overlaps: Vec<Option<&models::TimeEntry>> = proposed_entries
+        .iter()
+        .map(|entry| dedupe::find_overlap(entry, &existing_entries, &config.ticket_filter.denylist))
+        .collect();
+
+    // Show proposed entries and get approval
+    let catalog = review::Catalog::new(&projects, &all_tasks);
+    let approved_entries = if auto_approve || ctx.auto_start {
+        auto_approve_entries(
+            &ctx,
+            config,
+            &proposed_entries,
+            &overlaps,
+            &catalog,
+            remaining,
+        )?
+    } else {
+        let rebalance = rebalance_options(config, remaining);
+        prompt::review_and_approve_entries(
+            AI_REVIEW_TITLE,
+            &proposed_entries,
+            &catalog,
+            &overlaps,
+            &rebalance,
+            config.ai.auto_approve_confidence,
+        )?
+    };
+
+    if approved_entries.is_empty() {
+        if !ctx.quiet {
+            prompt::display_info("No entries approved");
+        }
+        return Ok(());
+    }
+
+    // Get fallback project/task from most recent entry
+    let fallback = existing_entries.first().and_then(|entry| {
+        if let (Some(project), Some(task)) = (&entry.project, &entry.task) {
+            Some((project.id, task.id))
+        } else {
+            None
+        }
+    });
+
+    // Create time entries in Harvest
+    let mut created_count = 0;
+    let mut failed_count = 0;
+    let mut used = Vec::new();
+
+    for entry in approved_entries {
+        match harvest_client.create_stopped_time_entry(
+            &entry.description,
+            entry.project_id,
+            entry.task_id,
+            entry.hours,
+            &ctx,
+        ) {
+            Ok(_) => {
+                created_count += 1;
+                used.push((entry.project_id, entry.task_id));
+                if ctx.verbose() {
+                    prompt::display_success(&format!(
+                        "Created: {} ({})",
+                        entry.description,
+                        format_hours(entry.hours)
+                    ));
+                }
+            }
+            Err(e) => {
+                // Check if this is a 422 error (invalid project/task) and we have a fallback
+                let is_422_error =
+                    matches!(&e, HarjiraError::HarvestApi(api) if api.is_unprocessable());
+
+                if let (true, Some((fallback_project_id, fallback_task_id))) =
+                    (is_422_error, fallback)
+                {
+                    if !ctx.quiet {
+                        prompt::display_warning(&format!(
+                            "Invalid project/task for '{}'. Retrying with most recent project/task...",
+                            entry.description
+                        ));
+                    }
+
+                    // Retry with fallback project/task
+                    match harvest_client.create_stopped_time_entry(
+                        &entry.description,
+                        fallback_project_id,
+                        fallback_task_id,
+                        entry.hours,
+                        &ctx,
+                    ) {
+                        Ok(_) => {
+                            created_count += 1;
+                            used.push((fallback_project_id, fallback_task_id));
+                            if ctx.verbose() {
+                                prompt::display_success(&format!(
+                                    "Created with fallback: {} ({})",
+                                    entry.description,
+                                    format_hours(entry.hours)
+                                ));
+                            }
+                        }
+                        Err(retry_error) => {
+                            failed_count += 1;
+                            prompt::display_warning(&format!(
+                                "Failed to create entry '{}' even with fallback: {}",
+                                entry.description, retry_error
+                            ));
+                        }
+                    }
+                } else {
+                    failed_count += 1;
+                    prompt::display_warning(&format!(
+                        "Failed to create entry '{}': {}",
+                        entry.description, e
+                    ));
+                }
+            }
+        }
+    }
+
+    // Record usage for future sorting
+    record_usage(&ctx, &used)?;
+
+    // Summary
+    if !ctx.quiet {
+        outln!();
+        if created_count > 0 {
+            prompt::display_success(&format!(
+                "Successfully created {} time entries",
+                created_count
+            ));
+        }
+        if failed_count > 0 {
+            prompt::display_warning(&format!("{} entries failed", failed_count));
+        }
+
+        // Show new total
+        let new_total = harvest_client.get_total_hours_today()?;
+        outln!("\nTotal time today: {}", format_hours(new_total));
+    }
+
+    Ok(())
+}
+
+/// Ask the AI for entries, recording usage in the ledger and dropping duplicates
+#[allow(clippy::too_many_arguments)]
+fn generate_entries(
+    ctx: &models::Context,
+    config: &Config,
+    ai_provider: &dyn ai::AiProvider,
+    work_summary: &str,
+    ai_context: &ai::AiContext,
+    ledger: &mut ai::ledger::AiLedger,
+    label: String,
+) -> Result<Vec<models::ProposedTimeEntry>> {
+    // In verbose mode the raw stream is echoed instead of drawing a spinner over it
+    let echo_stream = config.ai.stream && ctx.verbose() && !ctx.quiet;
+    let spinner = (!echo_stream).then(|| Spinner::start(label.clone(), ctx));
+    let mut spent = None;
+    let generation = ai::generate_time_entries(
+        ai_provider,
+        work_summary,
+        ai_context,
+        config.ai.max_retries,
+        &mut |delta, so_far| {
+            if echo_stream {
+                eprint!("{}", delta);
+            } else if let Some(spinner) = &spinner {
+                let entries = so_far.matches("\"description\"").count();
+                spinner.set_message(format!(
+                    "{} ({} so far)",
+                    label,
+                    count_label(entries, "entry", "entries")
+                ));
+            }
+        },
+        &mut spent,
+    );
+    if let Some(spinner) = spinner {
+        spinner.finish();
+    }
+    if echo_stream {
+        eprintln!();
+    }
+
+    // Record token usage and estimated cost, failed attempts were paid for too
+    match spent {
+        Some(usage) => {
+            let now = chrono::Utc::now();
+            let record = ledger
+                .record(ai_provider.name(), ai_provider.model(), usage, now)
+                .clone();
+            ledger.save();
+            if ctx.verbose() && !ctx.quiet {
+                let cost = record
+                    .cost_usd
+                    .map(|c| format!("~${:.4}", c))
+                    .unwrap_or_else(|| "unknown cost".to_string());
+                let spent = ledger.month_cost(now);
+                let month = match config.ai.monthly_cost_cap {
+                    Some(cap) => format!("${:.2} of ${:.2} this month", spent, cap),
+                    None => format!("${:.2} this month", spent),
+                };
+                prompt::display_info(&format!(
+                    "{}: {} input + {} output tokens, {} ({})",
+                    record.model, record.input_tokens, record.output_tokens, cost, month
+                ));
+            }
+        }
+        None => debug!("{} response had no token usage", ai_provider.name()),
+    }
+    let mut proposed_entries = generation?;
+
+    // Deduplicate entries based on description, project_id, task_id, and hours
+    let mut seen = std::collections::HashSet::new();
+    proposed_entries.retain(|entry| {
+        let key = (
+            entry.description.clone(),
+            entry.project_id,
+            entry.task_id,
+            (entry.hours * 100.0) as i64, // Convert to cents to handle f64 comparison
+        );
+        seen.insert(key)
+    });
+
+    Ok(proposed_entries)
+}
+
+/// Projects and every (project_id, task) assignment
+pub(crate) type ProjectsAndTasks = (Vec<models::HarvestProject>, Vec<(u64, models::HarvestTask)>);
+
+/// Projects and their task assignments, for the AI and the review
+///
+/// `billable` leaves out the tasks that don't fit `--billable` or `--non-billable`.
+pub(crate) fn fetch_catalog(
+    spinner: &Spinner,
+    harvest_client: &harvest::HarvestClient,
+    billable: Option<bool>,
+) -> Result<ProjectsAndTasks> {
+    spinner.set_message("Fetching Harvest projects...");
+    let projects = harvest_client.get_projects()?;
+    let label = format!(
+        "Fetching tasks for {}",
+        count_label(projects.len(), "project", "projects")
+    );
+    spinner.set_message(label.clone());
+    let mut all_tasks = harvest_client.get_all_available_tasks_with_progress(|done, total| {
+        spinner.set_progress(&label, done, total)
+    })?;
+    all_tasks.retain(|(_, task)| task.fits_billable(billable));
+    Ok((projects, all_tasks))
+}
+
+/// Entries to create with `--auto-approve`, reviewing only the unsure ones
+///
+/// Likely duplicates are never created without a review. Entries below
+/// `ai.auto_approve_confidence` are reviewed on their own, or skipped when
+/// nobody is there to review them (`--auto-start`).
+fn auto_approve_entries(
+    ctx: &models::Context,
+    config: &Config,
+    entries: &[models::ProposedTimeEntry],
+    overlaps: &[Option<&models::TimeEntry>],
+    catalog: &review::Catalog,
+    remaining_hours: f64,
+) -> Result<Vec<models::ProposedTimeEntry>> {
+    let threshold = config.ai.auto_approve_confidence;
+    let mut approved = Vec::new();
+    let mut unsure = Vec::new();
+    let mut unsure_overlaps = Vec::new();
+
+    for (entry, overlap) in entries.iter().zip(overlaps) {
+        if let Some(problem) = catalog.check(entry) {
+            // Only a review can pick another project or task
+            prompt::display_warning(&format!("Skipping '{}': {}", entry.description, problem));
+        } else if review::below_confidence(entry, threshold) {
+            unsure.push(entry.clone());
+            unsure_overlaps.push(*overlap);
+        } else if let Some(existing) = overlap {
+            if !ctx.quiet {
+                prompt::display_warning(&format!(
+                    "Skipping '{}': it looks already logged as '{}'",
+                    entry.description,
+                    existing.notes.as_deref().unwrap_or("(no description)")
+                ));
+            }
+        } else {
+            approved.push(entry.clone());
+        }
+    }
+
+    if let (Some(threshold), false) = (threshold, unsure.is_empty()) {
+        let what = format!(
+            "{} below {:.0}% confidence",
+            count_label(unsure.len(), "entry", "entries"),
+            threshold * 100.0
+        );
+        if ctx.auto_start {
+            if !ctx.quiet {
+                prompt::display_warning(&format!("Skipping {}", what));
+            }
+        } else {
+            if !ctx.quiet {
+                prompt::display_info(&format!(
+                    "Approved {} automatically, {} need review",
+                    count_label(approved.len(), "entry", "entries"),
+                    what
+                ));
+            }
+            let approved_hours: f64 = approved.iter().map(|e| e.hours).sum();
+            let rebalance = rebalance_options(config, (remaining_hours - approved_hours).max(0.0));
+            approved.extend(prompt::review_and_approve_entries(
+                AI_REVIEW_TITLE,
+                &unsure,
+                catalog,
+                &unsure_overlaps,
+                &rebalance,
+                Some(threshold),
+            )?);
+        }
+    }
+
+    Ok(approved)
+}
+
+pub(crate) fn rebalance_options(config: &Config, target_hours: f64) -> review::RebalanceOptions {
+    review::RebalanceOptions {
+        target_hours,
+        rounding_minutes: config.ai.rounding_minutes,
+        filler: match (config.ai.filler_project_id, config.ai.filler_task_id) {
+            (Some(project_id), Some(task_id)) => Some(review::Filler {
+                project_id,
+                task_id,
+                notes: config
+                    .ai
+                    .filler_notes
+                    .clone()
+                    .unwrap_or_else(|| "General".to_string()),
+            }),
+            _ => None,
+        },
+    }
+}
+
+/// Fill in every working day this week that is short of its capacity
+#[allow(clippy::too_many_arguments)]
+fn run_generate_week(
+    ctx: models::Context,
+    session: &Session,
+    summary: Option<String>,
+    auto_approve: bool,
+    force: bool,
+    plan_output: bool,
+    target_override: Option<f64>,
+    billable: Option<bool>,
+) -> Result<()> {
+    let config = &session.config;
+    let mut ledger = ai::ledger::AiLedger::load();
+    let check_cap = |ledger: &ai::ledger::AiLedger| {
+        ai::ledger::check_monthly_cap(
+            ledger.month_cost(chrono::Utc::now()),
+            config.ai.monthly_cost_cap,
+        )
+    };
+    if !force {
+        check_cap(&ledger)?;
+    }
+
+    let harvest_client = session.harvest()?;
+    let ai_provider = session.ai()?;
+
+    // Step 1: Find the days that still need hours
+    let days = review::week_to_date(chrono::Local::now().date_naive());
+    let (Some(first), Some(last)) = (days.first(), days.last()) else {
+        return Ok(());
+    };
+    let from = first.format("%Y-%m-%d").to_string();
+    let to = last.format("%Y-%m-%d").to_string();
+
+    let spinner = Spinner::start(format!("Fetching entries from {} to {}...", from, to), &ctx);
+    let week_entries = harvest_client.get_time_entries_range(&from, &to, &ctx)?;
+    let (projects, all_tasks) = fetch_catalog(&spinner, harvest_client, billable)?;
+    spinner.finish();
+    let tasks: Vec<models::HarvestTask> = all_tasks.iter().map(|(_, task)| task.clone()).collect();
+    let task_assignments: Vec<(u64, u64)> = all_tasks
+        .iter()
+        .map(|(project_id, task)| (*project_id, task.id))
+        .collect();
+
+    let repos = match git::discover_repositories(&config.git.repositories) {
+        Ok(repos) => repos,
+        Err(e) => {
+            debug!("Not reading commits: {}", e);
+            Vec::new()
+        }
+    };
+    let commit_filter = config.git.commit_filter()?;
+    let prompt_template = ai::load_prompt_template()?;
+    let sprint_issues = active_sprint_issues(&ctx, session);
+
+    // Step 2: Generate entries for each short day
+    let mut proposals = Vec::new();
+    for day in days {
+        let date = day.format("%Y-%m-%d").to_string();
+        let existing: Vec<models::TimeEntry> = week_entries
+            .iter()
+            .filter(|e| e.spent_date == date)
+            .cloned()
+            .collect();
+        let logged: f64 = existing.iter().filter_map(|e| e.hours).sum();
+        let target_hours = target_override.unwrap_or_else(|| capacity::target_hours(config, day));
+        if logged >= target_hours - 0.01 {
+            debug!("{} already has {:.2}h, skipping", date, logged);
+            continue;
+        }
+        if let Some(day) = leave::on(&date) {
+            debug!("{} is {} leave, skipping", date, day.kind);
+            continue;
+        }
+
+        let commits = git::get_commits_from_repositories_for_date(&repos, day, &commit_filter)?;
+        let Some(work_summary) = review::day_summary(&commits, summary.as_deref()) else {
+            if !ctx.quiet {
+                prompt::display_warning(&format!(
+                    "Skipping {}: no commits and no summary to go on",
+                    date
+                ));
+            }
+            continue;
+        };
+
+        if !force {
+            if let Err(e) = check_cap(&ledger) {
+                prompt::display_warning(&format!("Stopped generating: {}", e));
+                break;
+            }
+        }
+
+        let ai_context = ai::AiContext {
+            available_projects: projects.clone(),
+            available_tasks: tasks.clone(),
+            task_assignments: task_assignments.clone(),
+            existing_entries: existing.clone(),
+            target_hours,
+            today_total_hours: logged,
+            sprint_issues: sprint_issues.clone(),
+            ticket_details: ticket_details(&ctx, session, &work_summary, &sprint_issues),
+            prompt_template: prompt_template.clone(),
+        };
+        let label = format!("Generating entries for {}...", day.format("%A %Y-%m-%d"));
+        let entries = generate_entries(
+            &ctx,
+            config,
+            ai_provider,
+            &work_summary,
+            &ai_context,
+            &mut ledger,
+            label,
+        )?;
+        if entries.is_empty() {
+            if !ctx.quiet {
+                prompt::display_warning(&format!("AI did not generate entries for {}", date));
+            }
+            continue;
+        }
+        proposals.push((day, (target_hours - logged).max(0.0), existing, entries));
+    }
+
+    if proposals.is_empty() {
+        if !ctx.quiet {
+            prompt::display_info("Nothing to backfill this week");
+        }
+        return Ok(());
+    }
+
+    if plan_output {
+        return print_plan(&proposals);
+    }
+
+    let catalog = review::Catalog::new(&projects, &all_tasks);
+    review_and_create_by_day(
+        &ctx,
+        config,
+        harvest_client,
+        &catalog,
+        &proposals,
+        auto_approve,
+    )
+}
+
+/// Apply a plan written by `--dry-run --output json`, without asking the AI again
+fn run_generate_from_file(
+    ctx: models::Context,
+    session: &Session,
+    path: &str,
+    auto_approve: bool,
+) -> Result<()> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| HarjiraError::Config(format!("Could not read plan file {}: {}", path, e)))?;
+    let plan = review::parse_plan(&json)?;
+
+    let mut dates: Vec<&str> = plan
+        .days
+        .iter()
+        .filter(|day| !day.entries.is_empty())
+        .map(|day| day.date.as_str())
+        .collect();
+    dates.sort_unstable();
+    let (Some(from), Some(to)) = (dates.first(), dates.last()) else {
+        if !ctx.quiet {
+            prompt::display_info("The plan has no entries");
+        }
+        return Ok(());
+    };
+
+    let harvest_client = session.harvest()?;
+    let spinner = Spinner::start(format!("Fetching entries from {} to {}...", from, to), &ctx);
+    let logged_entries = harvest_client.get_time_entries_range(from, to, &ctx)?;
+    let (projects, all_tasks) = fetch_catalog(&spinner, harvest_client, None)?;
+    spinner.finish();
+
+    let mut proposals: Vec<DayProposal> = Vec::new();
+    for day in plan.days.into_iter().filter(|day| !day.entries.is_empty()) {
+        let date = chrono::NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
+            .map_err(|_| HarjiraError::Config(format!("Invalid date in plan: {}", day.date)))?;
+        let existing: Vec<models::TimeEntry> = logged_entries
+            .iter()
+            .filter(|e| e.spent_date == day.date)
+            .cloned()
+            .collect();
+        let logged: f64 = existing.iter().filter_map(|e| e.hours).sum();
+        let remaining = (capacity::target_hours(&session.config, date) - logged).max(0.0);
+        proposals.push((date, remaining, existing, day.entries));
+    }
+
+    let catalog = review::Catalog::new(&projects, &all_tasks);
+    review_and_create_by_day(
+        &ctx,
+        &session.config,
+        harvest_client,
+        &catalog,
+        &proposals,
+        auto_approve,
+    )
+}
+
+/// Proposed entries for one day: the day, hours left to log, its entries, the proposals
+type DayProposal = (
+    chrono::NaiveDate,
+    f64,
+    Vec<models::TimeEntry>,
+    Vec<models::ProposedTimeEntry>,
+);
+
+/// Write proposals as a JSON plan on stdout, for `--from-file` to apply later
+fn print_plan(proposals: &[DayProposal]) -> Result<()> {
+    let plan = models::EntryPlan {
+        days: proposals
+            .iter()
+            .map(|(day, _, _, entries)| models::PlannedDay {
+                date: day.format("%Y-%m-%d").to_string(),
+                entries: entries.clone(),
+            })
+            .collect(),
+    };
+    // Other output goes to the log in plan mode, so the plan can be redirected
+    output::data(&serde_json::to_string_pretty(&plan)?);
+    Ok(())
+}
+
+/// Review each day's proposals, confirm the whole batch, then create them on their dates
+fn review_and_create_by_day(
+    ctx: &models::Context,
+    config: &Config,
+    harvest_client: &harvest::HarvestClient,
+    catalog: &review::Catalog,
+    proposals: &[DayProposal],
+    auto_approve: bool,
+) -> Result<()> {
+    let mut approved: Vec<(String, Vec<models::ProposedTimeEntry>)> = Vec::new();
+    for (day, remaining, existing, entries) in proposals {
+        let overlaps: Vec<Option<&models::TimeEntry>> = entries
+            .iter()
+            .map(|entry| dedupe::find_overlap(entry, existing, &config.ticket_filter.denylist))
+            .collect();
+        let date = day.format("%Y-%m-%d").to_string();
+
+        let remaining = *remaining;
+        let day_entries = if auto_approve || ctx.auto_start {
+            auto_approve_entries(ctx, config, entries, &overlaps, catalog, remaining)?
+        } else {
+            outln!(
+                "\n{}",
+                console::style(day.format("%A %Y-%m-%d").to_string()).bold()
+            );
+            let rebalance = rebalance_options(config, remaining);
+            prompt::review_and_approve_entries(
+                AI_REVIEW_TITLE,
+                entries,
+                catalog,
+                &overlaps,
+                &rebalance,
+                config.ai.auto_approve_confidence,
+            )?
+        };
+        if !day_entries.is_empty() {
+            approved.push((date, day_entries));
+        }
+    }
+
+    if approved.is_empty() {
+        if !ctx.quiet {
+            prompt::display_info("No entries approved");
+        }
+        return Ok(());
+    }
+
+    let reviewed = !(auto_approve || ctx.auto_start);
+    if reviewed && !prompt::confirm_week_entries(&approved, catalog)? {
+        return Err(HarjiraError::UserCancelled);
+    }
+
+    // Create everything
+    let mut created_count = 0;
+    let mut failed_count = 0;
+    let mut used = Vec::new();
+    for (date, entries) in &approved {
+        for entry in entries {
+            match harvest_client.create_stopped_time_entry_with_date(
+                &entry.description,
+                entry.project_id,
+                entry.task_id,
+                entry.hours,
+                date,
+                ctx,
+            ) {
+                Ok(_) => {
+                    created_count += 1;
+                    used.push((entry.project_id, entry.task_id));
+                    if ctx.verbose() {
+                        prompt::display_success(&format!(
+                            "Created on {}: {} ({})",
+                            date,
+                            entry.description,
+                            format_hours(entry.hours)
+                        ));
+                    }
+                }
+                Err(e) => {
+                    failed_count += 1;
+                    prompt::display_warning(&format!(
+                        "Failed to create entry '{}' on {}: {}",
+                        entry.description, date, e
+                    ));
+                }
+            }
+        }
+    }
+
+    record_usage(ctx, &used)?;
+
+    if !ctx.quiet {
+        outln!();
+        if created_count > 0 {
+            prompt::display_success(&format!(
+                "Successfully created {} across {}",
+                count_label(created_count, "time entry", "time entries"),
+                count_label(approved.len(), "day", "days")
+            ));
+        }
+        if failed_count > 0 {
+            prompt::display_warning(&format!("{} entries failed", failed_count));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::journal::{self, HistoryRecord};
+use crate::*;
+use chrono::Local;
+
+pub fn run_history(limit: usize, entry_id: Option<u64>) -> Result<()> {
+    let records = journal::recent_history(journal::history()?, entry_id, limit);
+
+    if records.is_empty() {
+        match entry_id {
+            Some(id) => prompt::display_info(&format!("No changes to entry {} recorded", id)),
+            None => prompt::display_info("No changes recorded yet"),
+        }
+        return Ok(());
+    }
+
+    // Changes made by one command share its timestamp, so they're listed together
+    let mut previous: Option<&HistoryRecord> = None;
+    for record in &records {
+        let same_command =
+            previous.is_some_and(|p| p.at == record.at && p.command == record.command);
+        if !same_command {
+            outln!(
+                "\n{}  harv {}",
+                record.at.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                record.command
+            );
+        }
+        outln!(
+            "  {:<9} {:>10}  {}",
+            record.change.action(),
+            record.change.entry_id(),
+            record.summary
+        );
+        previous = Some(record);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use super::Session;
+use crate::invoices::{self, CreateInvoiceRequest};
+use crate::models::ClientInfo;
+use crate::progress::Spinner;
+use crate::search::{self, NameMatch};
+use crate::time_parser::format_hours;
+use crate::*;
+
+/// Add up a client's unbilled hours, and with `create` turn them into a draft invoice
+pub fn run_invoice_draft(
+    ctx: models::Context,
+    session: &Session,
+    client: &str,
+    from: &str,
+    to: &str,
+    create: bool,
+) -> Result<()> {
+    let from = parse_date(from)?;
+    let to = parse_date(to)?;
+    if from > to {
+        return Err(HarjiraError::InvalidEntry(format!(
+            "--from {} is after --to {}",
+            from, to
+        )));
+    }
+
+    let harvest_client = session.harvest()?;
+    let spinner = Spinner::start("Fetching unbilled hours...", &ctx);
+    let found = find_client(harvest_client, client).and_then(|client| {
+        let entries = harvest_client.get_unbilled_entries(client.id, &from, &to)?;
+        Ok((client, entries))
+    });
+    spinner.finish();
+    let (client, entries) = found?;
+
+    let lines = invoices::invoice_lines(&entries, &session.config.invoice)?;
+    outln!("\nUnbilled hours for {} ({} - {}):", client.name, from, to);
+    if lines.is_empty() {
+        outln!("  No billable hours to invoice");
+        return Ok(());
+    }
+
+    outln!();
+    for line in &lines {
+        outln!(
+            "  {:<40} {:>7} x {:>8.2} {:>10.2}",
+            format!("{} - {}", line.project, line.task),
+            format_hours(line.hours),
+            line.rate,
+            line.amount()
+        );
+    }
+    let hours: f64 = lines.iter().map(|l| l.hours).sum();
+    let amount: f64 = lines.iter().map(|l| l.amount()).sum();
+    outln!(
+        "  {:<40} {:>7}   {:>8} {:>10.2}",
+        "Total",
+        format_hours(hours),
+        "",
+        amount
+    );
+
+    if !create {
+        outln!("\nRun again with --create to make this a draft invoice in Harvest.");
+        return Ok(());
+    }
+
+    let request = CreateInvoiceRequest::import(
+        client.id,
+        format!("Services {} - {}", from, to),
+        &lines,
+        &from,
+        &to,
+    );
+    let mut invoice = harvest_client.create_invoice(&request, &ctx)?;
+
+    // Harvest prices imported hours itself, the configured rates go on afterwards
+    let rates = invoices::configured_rates(&entries, &session.config.invoice);
+    let prices = invoices::repriced_lines(&invoice, &rates);
+    if !prices.is_empty() {
+        invoice = harvest_client.update_invoice_prices(invoice.id, &prices, &ctx)?;
+    }
+    if !ctx.quiet && !ctx.dry_run {
+        prompt::display_success(&format!(
+            "Created draft invoice {} for {} ({:.2})",
+            invoice.number.as_deref().unwrap_or("(no number)"),
+            client.name,
+            invoice.amount.unwrap_or(amount)
+        ));
+    }
+    Ok(())
+}
+
+/// The client with this ID, or whose name matches `query`
+fn find_client(harvest_client: &HarvestClient, query: &str) -> Result<ClientInfo> {
+    let clients = harvest_client.get_clients()?;
+    let id: Option<u64> = query.trim().parse().ok();
+    if let Some(client) = clients.iter().find(|c| Some(c.id) == id) {
+        return Ok(client.clone());
+    }
+
+    match search::match_name(&clients, query, |c| vec![c.name.clone()]) {
+        NameMatch::Found(client) => Ok(client.clone()),
+        NameMatch::Ambiguous(matches) => Err(HarjiraError::InvalidEntry(format!(
+            "'{}' matches several clients: {}",
+            query,
+            matches
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+        NameMatch::NotFound => Err(HarjiraError::InvalidEntry(format!(
+            "No active Harvest client matches '{}'",
+            query
+        ))),
+    }
+}
+
+fn parse_date(date: &str) -> Result<String> {
+    chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map_err(|_| HarjiraError::InvalidEntry(format!("Invalid date '{}', use YYYY-MM-DD", date)))
+}
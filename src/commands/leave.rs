@@ -0,0 +1,101 @@
+use super::{or_queue, record_usage, Session};
+use crate::leave::{LeaveDay, LeaveDays};
+use crate::time_parser::format_hours;
+use crate::*;
+use log::info;
+
+/// Log a day off on the `[leave]` project and task and mark the day as done
+///
+/// The entry gets the day's capacity unless `hours` is given. Its notes are
+/// the leave type, capitalized ("Vacation").
+pub fn run_leave(
+    ctx: models::Context,
+    session: &Session,
+    date: Option<String>,
+    kind: &str,
+    hours: Option<String>,
+) -> Result<()> {
+    let config = &session.config;
+    let day = match date {
+        Some(date) => chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|_| {
+            HarjiraError::InvalidEntry(format!("Invalid date '{}', use YYYY-MM-DD", date))
+        })?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let date = day.format("%Y-%m-%d").to_string();
+    let kind = kind.trim().to_lowercase();
+
+    let mut leave = LeaveDays::load()?;
+    if let Some(day) = leave.get(&date) {
+        if !ctx.quiet {
+            prompt::display_info(&format!("{} is already marked as {} leave", date, day.kind));
+        }
+        return Ok(());
+    }
+
+    let target = config.leave.for_type(&kind).ok_or_else(|| {
+        HarjiraError::Config(format!(
+            "No project and task for {} leave. Set project_id and task_id in [leave], or add it under [leave.types].",
+            kind
+        ))
+    })?;
+    let hours = match hours {
+        Some(hours) => time_parser::parse_hours(&hours)?,
+        None => capacity::target_hours(config, day),
+    };
+    let notes = capitalize(&kind);
+
+    let harvest_client = session.harvest()?;
+    let created = harvest_client.create_stopped_time_entry_with_date(
+        &notes,
+        target.project_id,
+        target.task_id,
+        hours,
+        &date,
+        &ctx,
+    );
+    or_queue(
+        harvest_client,
+        created,
+        offline::Operation::Create {
+            spent_date: date.clone(),
+            project_id: target.project_id,
+            task_id: target.task_id,
+            notes,
+            hours: Some(hours),
+            entry_id: None,
+        },
+    )?;
+    record_usage(&ctx, &[(target.project_id, target.task_id)])?;
+
+    if ctx.dry_run {
+        info!("[DRY RUN] Would mark {} as {} leave", date, kind);
+    } else {
+        leave.insert(
+            &date,
+            LeaveDay {
+                kind: kind.clone(),
+                hours,
+            },
+        );
+        leave.save()?;
+    }
+
+    if !ctx.quiet {
+        prompt::display_success(&format!(
+            "Logged {} of {} leave on {}",
+            format_hours(hours),
+            kind,
+            date
+        ));
+    }
+    Ok(())
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
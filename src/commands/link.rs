@@ -0,0 +1,99 @@
+use super::Session;
+use crate::progress::Spinner;
+use crate::time_parser::format_hours;
+use crate::*;
+use log::debug;
+use std::collections::BTreeMap;
+
+/// List the entries booked on `ticket` over the last `days` days, with totals
+pub fn run_link(ctx: models::Context, session: &Session, ticket: &str, days: u32) -> Result<()> {
+    if days == 0 {
+        return Err(HarjiraError::InvalidEntry(
+            "--days must be at least 1".to_string(),
+        ));
+    }
+    let key = ticket.trim();
+    let today = chrono::Local::now().date_naive();
+    let from = (today - chrono::Duration::days(i64::from(days) - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+    let to = today.format("%Y-%m-%d").to_string();
+
+    let harvest_client = session.harvest()?;
+    let spinner = Spinner::start(format!("Fetching entries since {}...", from), &ctx);
+    let entries = harvest_client.get_time_entries_range(&from, &to, &ctx);
+    spinner.finish();
+    let entries = entries?;
+
+    let found =
+        ticket_time::entries_for_ticket(&entries, key, &session.config.ticket_filter.denylist);
+    outln!("\nTime on {} since {}:", key, from);
+    if found.is_empty() {
+        outln!("  Nothing logged");
+        return Ok(());
+    }
+
+    outln!();
+    let mut per_project: BTreeMap<&str, f64> = BTreeMap::new();
+    for entry in &found {
+        let hours = entry.hours.unwrap_or(0.0);
+        let project = entry
+            .project
+            .as_ref()
+            .map(|p| p.name.as_str())
+            .unwrap_or("No project");
+        *per_project.entry(project).or_default() += hours;
+        outln!(
+            "  {}  {:>6}  {} - {}{}",
+            entry.spent_date,
+            format_hours(hours),
+            project,
+            entry.notes.as_deref().unwrap_or("(no notes)"),
+            if entry.is_running { " (running)" } else { "" }
+        );
+    }
+
+    let total: f64 = found.iter().filter_map(|e| e.hours).sum();
+    outln!(
+        "\nTotal: {} in {}",
+        format_hours(total),
+        progress::count_label(found.len(), "entry", "entries")
+    );
+    if per_project.len() > 1 {
+        for (project, hours) in &per_project {
+            outln!("  {:<30} {:>6}", project, format_hours(*hours));
+        }
+    }
+    show_estimate(session, key, total);
+    Ok(())
+}
+
+/// Compare `total` with the ticket's estimate, when the tracker has one
+fn show_estimate(session: &Session, key: &str, total: f64) {
+    let Ok(tracker) = session.tracker() else {
+        return;
+    };
+    let ticket = match tracker.get_issue(key) {
+        Ok(ticket) => ticket,
+        Err(e) => {
+            debug!("No estimate for {}: {}", key, e);
+            return;
+        }
+    };
+    let Some(estimate) = ticket.estimate else {
+        return;
+    };
+    let logged = total.max(estimate.spent_hours);
+    if let Some(label) = ticket_time::estimate_label(&estimate, logged) {
+        outln!("\n{}: {}", tracker.name(), label);
+    }
+    match ticket_time::remaining_estimate(&estimate, logged) {
+        Some(left) if left < 0.0 => prompt::display_warning(&format!(
+            "{} over its estimate by {}",
+            key,
+            format_hours(-left)
+        )),
+        Some(left) => outln!("{} left", format_hours(left)),
+        None => {}
+    }
+}
@@ -0,0 +1,292 @@
+use super::{or_queue, record_usage, Session};
+use crate::config::ProjectTask;
+use crate::models::{TimeEntry, UpdateTimeEntryRequest};
+use crate::time_parser::format_hours;
+use crate::*;
+use log::{info, warn};
+
+/// Add time to today's entry for a ticket, creating the entry if there is none
+///
+/// New entries go to the `[aliases]` entry `alias` or `project`/`task` (IDs
+/// or names) when given, otherwise to the ticket's `[mapping.tickets]` entry,
+/// otherwise to the `[harvest]` defaults.
+pub fn run_log(
+    ctx: models::Context,
+    session: &Session,
+    ticket: &str,
+    hours: &str,
+    message: Option<String>,
+    target: (Option<String>, Option<String>),
+    alias: Option<String>,
+) -> Result<()> {
+    let config = &session.config;
+    let ticket_key = ticket_key(ticket)?;
+    let hours = time_parser::parse_hours(hours)?;
+    let message = message
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty());
+
+    let harvest_client = session.harvest()?;
+    let entries = match harvest_client.get_todays_time_entries() {
+        // Offline there's no entry to add to, so a new one is queued
+        Err(e) if offline::is_unreachable(&e) => Vec::new(),
+        other => other?,
+    };
+
+    let denylist = &session.config.ticket_filter.denylist;
+    if let Some(entry) = entry_for_ticket(&entries, &ticket_key, denylist) {
+        let total = entry.hours.unwrap_or(0.0) + hours;
+        let update = UpdateTimeEntryRequest {
+            hours: Some(total),
+            notes: message
+                .as_deref()
+                .and_then(|m| appended_notes(entry.notes.as_deref(), m)),
+        };
+        harvest_client.update_time_entry(entry.id, &update, &ctx)?;
+        if let (Some(project), Some(task)) = (&entry.project, &entry.task) {
+            record_usage(&ctx, &[(project.id, task.id)])?;
+        }
+        if !ctx.quiet {
+            prompt::display_success(&format!(
+                "Added {} to {} ({} today)",
+                format_hours(hours),
+                ticket_key,
+                format_hours(total)
+            ));
+        }
+        return Ok(());
+    }
+
+    let target = match alias {
+        Some(alias) => aliases::resolve(config, harvest_client, &alias)?,
+        None => {
+            let project_id = target
+                .0
+                .as_deref()
+                .map(|p| catalog::resolve_project(harvest_client, p))
+                .transpose()?;
+            let task_id = target
+                .1
+                .as_deref()
+                .map(|t| catalog::resolve_task(harvest_client, project_id, t))
+                .transpose()?;
+            resolve_target(config, &ticket_key, (project_id, task_id))?
+        }
+    };
+    let notes = new_entry_notes(session, &ticket_key, message);
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let created = harvest_client.create_stopped_time_entry_with_date(
+        &notes,
+        target.project_id,
+        target.task_id,
+        hours,
+        &today,
+        &ctx,
+    );
+    or_queue(
+        harvest_client,
+        created,
+        offline::Operation::Create {
+            spent_date: today,
+            project_id: target.project_id,
+            task_id: target.task_id,
+            notes: notes.clone(),
+            hours: Some(hours),
+            entry_id: None,
+        },
+    )?;
+    record_usage(&ctx, &[(target.project_id, target.task_id)])?;
+    info!(
+        "Created entry for {} on project {}",
+        ticket_key, target.project_id
+    );
+
+    if !ctx.quiet {
+        prompt::display_success(&format!("Logged {} on {}", format_hours(hours), notes));
+    }
+    Ok(())
+}
+
+/// `ticket` in uppercase, or an error if it isn't a ticket key
+pub(super) fn ticket_key(ticket: &str) -> Result<String> {
+    let ticket_key = ticket.trim().to_uppercase();
+    if ticket_parser::extract_tickets(std::slice::from_ref(&ticket_key), &[])
+        != [ticket_key.clone()]
+    {
+        return Err(HarjiraError::InvalidEntry(format!(
+            "'{}' is not a ticket key like PROJ-123",
+            ticket
+        )));
+    }
+    Ok(ticket_key)
+}
+
+/// Notes for a new entry: the key with `message`, or with the ticket's summary
+pub(super) fn new_entry_notes(
+    session: &Session,
+    ticket_key: &str,
+    message: Option<String>,
+) -> String {
+    let description = match message {
+        Some(message) => message,
+        None => match session
+            .tracker()
+            .and_then(|tracker| tracker.get_issue(ticket_key))
+        {
+            Ok(issue) => issue.summary,
+            Err(e) => {
+                warn!(
+                    "Couldn't fetch {} from {}: {}",
+                    ticket_key,
+                    session.config.tracker.name(),
+                    e
+                );
+                String::new()
+            }
+        },
+    };
+    if description.is_empty() {
+        ticket_key.to_string()
+    } else {
+        format!("{} - {}", ticket_key, description)
+    }
+}
+
+/// Today's entry for the ticket, preferring a stopped one over a running timer
+///
+/// Matches the key exactly, so PROJ-1 doesn't pick up the entry for PROJ-12.
+pub(super) fn entry_for_ticket<'a>(
+    entries: &'a [TimeEntry],
+    ticket_key: &str,
+    denylist: &[String],
+) -> Option<&'a TimeEntry> {
+    let mut matching = entries
+        .iter()
+        .filter(|e| ticket_time::is_for_ticket(e, ticket_key, denylist));
+    let first = matching.clone().next();
+    matching.find(|e| !e.is_running).or(first)
+}
+
+/// Notes with `message` added, or None when they already mention it
+fn appended_notes(notes: Option<&str>, message: &str) -> Option<String> {
+    match notes {
+        Some(notes) if notes.contains(message) => None,
+        Some(notes) if !notes.trim().is_empty() => Some(format!("{}; {}", notes.trim(), message)),
+        _ => Some(message.to_string()),
+    }
+}
+
+/// Project and task for a new entry on `ticket_key`
+pub(super) fn resolve_target(
+    config: &Config,
+    ticket_key: &str,
+    (project_id, task_id): (Option<u64>, Option<u64>),
+) -> Result<ProjectTask> {
+    let mapped = config.mapping.for_ticket(ticket_key);
+    let project_id = project_id
+        .or(mapped.map(|m| m.project_id))
+        .or(config.harvest.project_id);
+    let task_id = task_id
+        .or(mapped.map(|m| m.task_id))
+        .or(config.harvest.task_id);
+
+    match (project_id, task_id) {
+        (Some(project_id), Some(task_id)) => Ok(ProjectTask {
+            project_id,
+            task_id,
+        }),
+        _ => Err(HarjiraError::Config(format!(
+            "No Harvest project and task for {}. Pass --project and --task, add it under [mapping.tickets], or set project_id and task_id in [harvest].",
+            ticket_key
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, notes: &str, is_running: bool) -> TimeEntry {
+        TimeEntry {
+            id,
+            spent_date: "2026-10-14".to_string(),
+            hours: Some(1.0),
+            notes: Some(notes.to_string()),
+            is_running,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_entry_for_ticket_prefers_stopped() {
+        let entries = vec![
+            entry(1, "PROJ-1 - Running", true),
+            entry(2, "PROJ-1 - Earlier", false),
+            entry(3, "PROJ-2 - Other", true),
+        ];
+        assert_eq!(
+            entry_for_ticket(&entries, "PROJ-1", &[]).map(|e| e.id),
+            Some(2)
+        );
+        assert_eq!(
+            entry_for_ticket(&entries, "PROJ-2", &[]).map(|e| e.id),
+            Some(3)
+        );
+        assert!(entry_for_ticket(&entries, "PROJ-3", &[]).is_none());
+    }
+
+    #[test]
+    fn test_entry_for_ticket_matches_the_whole_key() {
+        let entries = vec![entry(1, "PROJ-12 - Search", false)];
+        assert!(entry_for_ticket(&entries, "PROJ-1", &[]).is_none());
+        assert_eq!(
+            entry_for_ticket(&entries, "PROJ-12", &[]).map(|e| e.id),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_appended_notes() {
+        assert_eq!(
+            appended_notes(Some("PROJ-1 - Login"), "code review").as_deref(),
+            Some("PROJ-1 - Login; code review")
+        );
+        assert_eq!(
+            appended_notes(Some("PROJ-1 - code review"), "code review"),
+            None
+        );
+        assert_eq!(
+            appended_notes(None, "code review").as_deref(),
+            Some("code review")
+        );
+    }
+
+    #[test]
+    fn test_resolve_target_order() {
+        let mut config: Config = toml::from_str(config::TEMPLATE).unwrap();
+        config.harvest.project_id = Some(1);
+        config.harvest.task_id = Some(2);
+        config.mapping.tickets.insert(
+            "PROJ".to_string(),
+            ProjectTask {
+                project_id: 10,
+                task_id: 20,
+            },
+        );
+
+        let target = |key, flags| resolve_target(&config, key, flags).unwrap();
+        assert_eq!(target("OTHER-1", (None, None)).project_id, 1);
+        assert_eq!(target("PROJ-1", (None, None)).task_id, 20);
+        assert_eq!(
+            target("PROJ-1", (Some(5), None)),
+            ProjectTask {
+                project_id: 5,
+                task_id: 20
+            }
+        );
+
+        config.harvest.task_id = None;
+        assert!(resolve_target(&config, "OTHER-1", (None, None)).is_err());
+    }
+}
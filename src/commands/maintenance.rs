@@ -0,0 +1,93 @@
+use crate::*;
+use log::info;
+
+pub fn run_cache_prune(ctx: models::Context, months: u32) -> Result<()> {
+    let mut usage_cache = usage::UsageCache::load()?;
+    let removed = usage_cache.prune(months, chrono::Utc::now());
+
+    if ctx.dry_run {
+        info!(
+            "[DRY RUN] Would remove {} usage records unused for {} months",
+            removed, months
+        );
+        return Ok(());
+    }
+
+    if removed > 0 {
+        usage_cache.save()?;
+    }
+
+    if !ctx.quiet {
+        prompt::display_success(&format!(
+            "Removed {} usage records unused for {} months ({} remaining)",
+            removed,
+            months,
+            usage_cache.len()
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn run_install_service(interval: &str) -> Result<()> {
+    let interval_secs = service::parse_interval(interval)?;
+    let written = service::install(interval_secs)?;
+
+    for path in &written {
+        outln!("✓ Wrote {}", path.display());
+    }
+    outln!(
+        "✓ harv sync will run every {} minutes",
+        interval_secs as f64 / 60.0
+    );
+    if cfg!(target_os = "macos") {
+        outln!("\n→ Logs: ~/Library/Logs/harv.log");
+    } else {
+        outln!("\n→ Logs: journalctl --user -u harv.service -f");
+    }
+
+    Ok(())
+}
+
+pub fn run_uninstall_service() -> Result<()> {
+    let removed = service::uninstall()?;
+
+    if removed.is_empty() {
+        outln!("No harv service installed");
+    }
+    for path in &removed {
+        outln!("✓ Removed {}", path.display());
+    }
+
+    Ok(())
+}
+
+pub fn run_hook_install(repo: Option<String>) -> Result<()> {
+    let repo = repo.unwrap_or_else(|| ".".to_string());
+    let (path, outcome) = hook::install(&repo)?;
+
+    match outcome {
+        hook::HookInstall::Created => {
+            outln!("✓ Installed post-commit hook at {}", path.display())
+        }
+        hook::HookInstall::Chained => outln!(
+            "✓ Added harv to the existing post-commit hook at {}",
+            path.display()
+        ),
+        hook::HookInstall::Updated => outln!("✓ Updated post-commit hook at {}", path.display()),
+    }
+    outln!("→ Each commit now runs: harv sync --auto-start --quiet");
+
+    Ok(())
+}
+
+pub fn run_hook_uninstall(repo: Option<String>) -> Result<()> {
+    let repo = repo.unwrap_or_else(|| ".".to_string());
+
+    match hook::uninstall(&repo)? {
+        Some(path) => outln!("✓ Removed harv from post-commit hook at {}", path.display()),
+        None => outln!("No harv post-commit hook installed"),
+    }
+
+    Ok(())
+}
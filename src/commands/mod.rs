@@ -0,0 +1,600 @@
+pub mod add;
+pub mod autolog;
+pub mod capacity;
+pub mod config;
+pub mod continue_work;
+pub mod dedupe;
+pub mod expense;
+pub mod favorites;
+pub mod flush;
+pub mod generate;
+pub mod history;
+pub mod invoice;
+pub mod leave;
+pub mod link;
+pub mod log_time;
+pub mod maintenance;
+pub mod pomodoro;
+pub mod stats;
+pub mod status;
+pub mod summarize;
+pub mod sync;
+pub mod team;
+pub mod templates;
+pub mod undo;
+
+pub use add::run_add;
+pub use autolog::run_autolog;
+pub use capacity::{run_capacity_clear, run_capacity_list, run_capacity_set};
+pub use config::{
+    run_config_account, run_config_encrypt, run_config_init, run_config_prompt, run_config_show,
+    run_config_validate, run_login_jira,
+};
+pub use continue_work::run_continue;
+pub use dedupe::run_dedupe;
+pub use expense::{run_expense_add, run_expense_categories};
+pub use favorites::{run_fav_add, run_fav_list, run_fav_remove, run_start};
+pub use flush::{flush_queued, run_flush};
+pub use generate::run_generate;
+pub use history::run_history;
+pub use invoice::run_invoice_draft;
+pub use leave::run_leave;
+pub use link::run_link;
+pub use log_time::run_log;
+pub use maintenance::{
+    run_cache_prune, run_hook_install, run_hook_uninstall, run_install_service,
+    run_uninstall_service,
+};
+pub use pomodoro::run_pomodoro;
+pub use stats::run_stats;
+pub use status::{
+    run_status, run_status_all_accounts, run_status_day, run_status_short, run_status_watch,
+    run_stop, run_today,
+};
+pub use summarize::run_summarize;
+pub use sync::run_sync;
+pub use team::{run_team_report, run_team_status};
+pub use templates::run_new;
+pub use undo::run_undo;
+
+use crate::*;
+use log::{debug, warn};
+use std::cell::OnceCell;
+
+/// The configuration and API clients a command works with
+///
+/// Clients are built from the configuration the first time a command needs
+/// them. Other frontends and tests can inject their own with the `with_*`
+/// methods, e.g. a `HarvestClient` pointed at a mock server.
+pub struct Session {
+    pub config: Config,
+    harvest: OnceCell<HarvestClient>,
+    jira: OnceCell<JiraClient>,
+    linear: OnceCell<LinearClient>,
+    azure_devops: OnceCell<AzureDevOpsClient>,
+    github: OnceCell<GitHubClient>,
+    tempo: OnceCell<TempoClient>,
+    toggl: OnceCell<TogglClient>,
+    ai: OnceCell<Box<dyn ai::AiProvider>>,
+}
+
+impl Session {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            harvest: OnceCell::new(),
+            jira: OnceCell::new(),
+            linear: OnceCell::new(),
+            azure_devops: OnceCell::new(),
+            github: OnceCell::new(),
+            tempo: OnceCell::new(),
+            toggl: OnceCell::new(),
+            ai: OnceCell::new(),
+        }
+    }
+
+    /// Session for the configuration file
+    pub fn load() -> Result<Self> {
+        Ok(Self::new(Config::load()?))
+    }
+
+    pub fn with_harvest(self, client: HarvestClient) -> Self {
+        let _ = self.harvest.set(client);
+        self
+    }
+
+    pub fn with_jira(self, client: JiraClient) -> Self {
+        let _ = self.jira.set(client);
+        self
+    }
+
+    pub fn with_linear(self, client: LinearClient) -> Self {
+        let _ = self.linear.set(client);
+        self
+    }
+
+    pub fn with_azure_devops(self, client: AzureDevOpsClient) -> Self {
+        let _ = self.azure_devops.set(client);
+        self
+    }
+
+    pub fn with_github(self, client: GitHubClient) -> Self {
+        let _ = self.github.set(client);
+        self
+    }
+
+    pub fn with_tempo(self, client: TempoClient) -> Self {
+        let _ = self.tempo.set(client);
+        self
+    }
+
+    pub fn with_toggl(self, client: TogglClient) -> Self {
+        let _ = self.toggl.set(client);
+        self
+    }
+
+    pub fn with_ai(self, provider: Box<dyn ai::AiProvider>) -> Self {
+        let _ = self.ai.set(provider);
+        self
+    }
+
+    /// The Harvest client, for commands that only work with Harvest
+    pub fn harvest(&self) -> Result<&HarvestClient> {
+        if let Some(client) = self.harvest.get() {
+            return Ok(client);
+        }
+        if !self.config.backend.is_harvest() {
+            return Err(HarjiraError::Config(format!(
+                "This command needs Harvest, but the {} backend is configured",
+                self.config.backend.provider
+            )));
+        }
+        let client = HarvestClient::new(self.config.harvest.clone())?;
+        Ok(self.harvest.get_or_init(|| client))
+    }
+
+    /// The time-tracking backend from `[backend]`, used by sync, stop and status
+    pub fn backend(&self) -> Result<&dyn TimeBackend> {
+        match self.config.backend.provider.to_lowercase().as_str() {
+            "tempo" => self.tempo().map(|c| c as &dyn TimeBackend),
+            "toggl" => self.toggl().map(|c| c as &dyn TimeBackend),
+            _ => self.harvest().map(|c| c as &dyn TimeBackend),
+        }
+    }
+
+    fn tempo(&self) -> Result<&TempoClient> {
+        if let Some(client) = self.tempo.get() {
+            return Ok(client);
+        }
+        let client = TempoClient::new(self.config.tempo.clone(), self.config.jira.clone())?;
+        Ok(self.tempo.get_or_init(|| client))
+    }
+
+    fn toggl(&self) -> Result<&TogglClient> {
+        if let Some(client) = self.toggl.get() {
+            return Ok(client);
+        }
+        let client = TogglClient::new(self.config.toggl.clone())?;
+        Ok(self.toggl.get_or_init(|| client))
+    }
+
+    pub fn jira(&self) -> Result<&JiraClient> {
+        if let Some(client) = self.jira.get() {
+            return Ok(client);
+        }
+        let client = JiraClient::new(self.config.jira.clone())?;
+        Ok(self.jira.get_or_init(|| client))
+    }
+
+    /// The default issue tracker from `[tracker]`
+    pub fn tracker(&self) -> Result<&dyn IssueTracker> {
+        self.tracker_for(&self.config.tracker.provider)
+    }
+
+    /// The issue tracker for a provider name, e.g. one from `[tracker.repositories]`
+    pub fn tracker_for(&self, provider: &str) -> Result<&dyn IssueTracker> {
+        match provider.to_lowercase().as_str() {
+            "linear" => self.linear().map(|c| c as &dyn IssueTracker),
+            "azure_devops" => self.azure_devops().map(|c| c as &dyn IssueTracker),
+            "github" => self.github().map(|c| c as &dyn IssueTracker),
+            _ => self.jira().map(|c| c as &dyn IssueTracker),
+        }
+    }
+
+    fn linear(&self) -> Result<&LinearClient> {
+        if let Some(client) = self.linear.get() {
+            return Ok(client);
+        }
+        let client = LinearClient::new(self.config.linear.clone())?;
+        Ok(self.linear.get_or_init(|| client))
+    }
+
+    fn azure_devops(&self) -> Result<&AzureDevOpsClient> {
+        if let Some(client) = self.azure_devops.get() {
+            return Ok(client);
+        }
+        let client = AzureDevOpsClient::new(self.config.azure_devops.clone())?;
+        Ok(self.azure_devops.get_or_init(|| client))
+    }
+
+    fn github(&self) -> Result<&GitHubClient> {
+        if let Some(client) = self.github.get() {
+            return Ok(client);
+        }
+        let client = GitHubClient::new(self.config.github.clone())?;
+        Ok(self.github.get_or_init(|| client))
+    }
+
+    /// The AI provider from `[ai]`, as it is when first asked for
+    pub fn ai(&self) -> Result<&dyn ai::AiProvider> {
+        if let Some(provider) = self.ai.get() {
+            return Ok(provider.as_ref());
+        }
+        let provider = ai::create_provider(&self.config.ai)?;
+        Ok(self.ai.get_or_init(|| provider).as_ref())
+    }
+}
+
+/// Use the configured or Harvest profile timezone as the local timezone
+pub fn init_timezone() {
+    // Commands report config problems themselves
+    let Ok(config) = Config::load() else {
+        return;
+    };
+
+    let name = match config.settings.timezone.as_deref() {
+        Some("local") => None,
+        Some(tz) => Some(tz.to_string()),
+        None if config.backend.is_harvest() => harvest_timezone(&config),
+        None => None,
+    };
+
+    if let Some(name) = name {
+        if let Err(e) = timezone::apply(&name) {
+            warn!("{}, using the system timezone", e);
+        }
+    }
+}
+
+/// Timezone from the Harvest profile, cached for a day
+fn harvest_timezone(config: &Config) -> Option<String> {
+    if let Some(cache) = timezone::TimezoneCache::load_fresh() {
+        return cache.timezone;
+    }
+
+    let user =
+        HarvestClient::new(config.harvest.clone()).and_then(|client| client.get_current_user());
+    match user {
+        Ok(user) => {
+            timezone::TimezoneCache::new(user.timezone.clone()).save();
+            user.timezone
+        }
+        Err(e) => {
+            debug!("Could not fetch Harvest timezone: {}", e);
+            None
+        }
+    }
+}
+
+/// The day picked with `--date` or `--yesterday`, None for today
+pub(crate) fn selected_day(
+    date: Option<&str>,
+    yesterday: bool,
+) -> Result<Option<chrono::NaiveDate>> {
+    match date {
+        Some(date) => chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| {
+                HarjiraError::InvalidEntry(format!("Invalid date '{}', use YYYY-MM-DD", date))
+            }),
+        None if yesterday => Ok(Some(
+            chrono::Local::now().date_naive() - chrono::Duration::days(1),
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Queue a Harvest change for `harv flush`, returning a placeholder entry
+pub(crate) fn queue_offline(
+    harvest_client: &HarvestClient,
+    operation: offline::Operation,
+) -> Result<models::TimeEntry> {
+    offline::queue(harvest_client.account_id(), operation.clone())?;
+    prompt::display_warning(&format!(
+        "Harvest can't be reached, queued: {}. It's sent with the next command, or run 'harv flush'.",
+        operation.label()
+    ));
+    Ok(offline::placeholder(&operation))
+}
+
+/// The entry a Harvest change produced, or a placeholder once it's queued
+/// because Harvest can't be reached
+pub(crate) fn or_queue(
+    harvest_client: &HarvestClient,
+    result: Result<models::TimeEntry>,
+    operation: offline::Operation,
+) -> Result<models::TimeEntry> {
+    match result {
+        Err(e) if offline::is_unreachable(&e) => {
+            debug!("Queueing {} after: {}", operation.label(), e);
+            queue_offline(harvest_client, operation)
+        }
+        other => other,
+    }
+}
+
+/// Why Harvest rejected the `[harvest]` project and task, when they are archived
+///
+/// A 422 doesn't say, so the configured ids are looked up among the active
+/// projects and the project's active tasks. None for any other error.
+pub(crate) fn archived_target(session: &Session, error: &HarjiraError) -> Result<Option<String>> {
+    let unprocessable = matches!(error, HarjiraError::HarvestApi(api) if api.is_unprocessable());
+    let config = &session.config.harvest;
+    let (true, Some(project_id), Some(task_id)) =
+        (unprocessable, config.project_id, config.task_id)
+    else {
+        return Ok(None);
+    };
+
+    let harvest_client = session.harvest()?;
+    let projects = harvest_client.get_projects()?;
+    let Some(project) = projects.iter().find(|p| p.id == project_id) else {
+        return Ok(Some(format!(
+            "Project {} in your config is archived or no longer assigned to you",
+            project_id
+        )));
+    };
+    let tasks = harvest_client.get_project_tasks(project_id)?;
+    if tasks.iter().any(|t| t.id == task_id) {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "Task {} in your config is archived or no longer part of {}",
+        task_id, project.name
+    )))
+}
+
+/// Offer a new default project and task when `error` came from archived ones
+///
+/// The pick is saved to the config, see `Config::set_harvest_target`, and a
+/// client using it is returned to retry with. Without a terminal to ask on
+/// the error explains what to change instead. None when `error` isn't about
+/// an archived project or task.
+pub(crate) fn repick_archived_target(
+    ctx: &models::Context,
+    session: &Session,
+    error: &HarjiraError,
+) -> Result<Option<HarvestClient>> {
+    let Some(reason) = archived_target(session, error)? else {
+        return Ok(None);
+    };
+    if ctx.quiet || !console::user_attended() || !prompt::confirm_repick_target(&reason)? {
+        return Err(HarjiraError::Config(format!(
+            "{}. Set another project_id and task_id in [harvest].",
+            reason
+        )));
+    }
+
+    let harvest_client = session.harvest()?;
+    let projects = harvest_client.get_projects()?;
+    let project = prompt::prompt_project_selection(&projects)?;
+    let tasks = harvest_client.get_project_tasks(project.id)?;
+    if tasks.is_empty() {
+        return Err(HarjiraError::Config(format!(
+            "{} has no active tasks",
+            project.name
+        )));
+    }
+    let task = prompt::prompt_task_selection(&tasks)?;
+
+    let path = session.config.set_harvest_target(project.id, task.id)?;
+    prompt::display_success(&format!(
+        "Saved {} / {} to {}",
+        project.name,
+        task.name,
+        path.display()
+    ));
+    Ok(Some(HarvestClient::new(crate::config::HarvestConfig {
+        project_id: Some(project.id),
+        task_id: Some(task.id),
+        ..session.config.harvest.clone()
+    })?))
+}
+
+/// Days of commits searched for work before a restart
+const RESTART_LOOKBACK_DAYS: i64 = 7;
+
+/// Offer to trim a timer that kept running while the machine was off
+///
+/// Runs once per boot, and only when a timer started before it. The entry is
+/// trimmed to the last commit or harv change before the restart, so a
+/// weekend with the laptop shut doesn't end up on the timesheet. Never fails
+/// the command it runs for.
+pub(crate) fn check_timer_after_restart(ctx: &models::Context, session: &Session) {
+    if ctx.quiet || !console::user_attended() {
+        return;
+    }
+    if let Err(e) = trim_timer_after_restart(ctx, session) {
+        warn!("Could not check the timer after a restart: {}", e);
+    }
+}
+
+fn trim_timer_after_restart(ctx: &models::Context, session: &Session) -> Result<()> {
+    let Some(boot) = resume::boot_time() else {
+        return Ok(());
+    };
+    let mut state = resume::ResumeState::load()?;
+    if state.checked_boot == Some(boot) {
+        return Ok(());
+    }
+    state.checked_boot = Some(boot);
+    state.save()?;
+
+    let backend = session.backend()?;
+    let Some(timer) = backend.get_running_timer()? else {
+        return Ok(());
+    };
+    let hours = timer.hours.unwrap_or(0.0);
+    let now = chrono::Local::now();
+    let started = now - chrono::Duration::seconds((hours * 3600.0) as i64);
+    if started >= boot {
+        return Ok(());
+    }
+    debug!(
+        "Timer {} started {} before the boot at {}",
+        timer.id, started, boot
+    );
+
+    let mut times: Vec<chrono::DateTime<chrono::Local>> = journal::history()?
+        .into_iter()
+        .map(|record| record.at.with_timezone(&chrono::Local))
+        .collect();
+    let repositories = git::discover_repositories(&session.config.git.repositories)?;
+    let first_day = started
+        .date_naive()
+        .max(boot.date_naive() - chrono::Duration::days(RESTART_LOOKBACK_DAYS));
+    for day in first_day
+        .iter_days()
+        .take_while(|day| *day <= boot.date_naive())
+    {
+        let commits = git::get_commits_from_repositories_for_date(
+            &repositories,
+            day,
+            &git::CommitFilter::default(),
+        )?;
+        times.extend(commits.iter().filter_map(|commit| {
+            chrono::TimeZone::timestamp_opt(&chrono::Local, commit.timestamp, 0).single()
+        }));
+    }
+
+    let Some(last) = resume::last_activity(times, started, boot) else {
+        prompt::display_warning(&format!(
+            "The running timer started before this machine restarted at {}, check whether {} is right",
+            boot.format("%Y-%m-%d %H:%M"),
+            time_parser::format_hours(hours)
+        ));
+        return Ok(());
+    };
+
+    let trimmed = (last - started).num_seconds() as f64 / 3600.0;
+    if !prompt::confirm_trim_orphaned_timer(&timer, boot, last, trimmed)? {
+        return Ok(());
+    }
+    let stopped = backend.stop_time_entry(timer.id, ctx)?;
+    let update = models::UpdateTimeEntryRequest {
+        hours: Some(trimmed),
+        ..Default::default()
+    };
+    backend.update_time_entry(stopped.id, &update, ctx)?;
+    prompt::display_success(&format!(
+        "Stopped the timer at {} ({}, trimmed from {})",
+        last.format("%H:%M"),
+        time_parser::format_hours(trimmed),
+        time_parser::format_hours(hours)
+    ));
+    Ok(())
+}
+
+/// Record project/task pairs in the usage cache (skipped in dry-run mode)
+pub(crate) fn record_usage(ctx: &models::Context, used: &[(u64, u64)]) -> Result<()> {
+    if ctx.dry_run || used.is_empty() {
+        return Ok(());
+    }
+
+    let mut usage_cache = usage::UsageCache::load()?;
+    for &(project_id, task_id) in used {
+        usage_cache.record_entry_usage(project_id, task_id);
+    }
+    usage_cache.save()
+}
+
+/// The user's issues in the active sprint of `jira.board_id`
+///
+/// Empty without a board, when Linear is the tracker or when Jira can't be
+/// reached, the sprint only ranks tickets and hints the AI so it never fails
+/// a command.
+pub(crate) fn active_sprint_issues(
+    ctx: &models::Context,
+    session: &Session,
+) -> Vec<models::Ticket> {
+    let Some(board_id) = session.config.jira.board_id else {
+        return Vec::new();
+    };
+    if !session.config.tracker.is_jira() {
+        return Vec::new();
+    }
+
+    let spinner = progress::Spinner::start("Fetching active sprint...", ctx);
+    let result = session
+        .jira()
+        .and_then(|jira| jira.get_my_sprint_issues(board_id));
+    spinner.finish();
+    result.unwrap_or_else(|e| {
+        warn!(
+            "Could not fetch the active sprint of board {}: {}",
+            board_id, e
+        );
+        Vec::new()
+    })
+}
+
+/// Most issues whose details are fetched for one generation
+const TICKET_DETAILS_LIMIT: usize = 5;
+
+/// Comments kept per issue, the newest ones
+const TICKET_DETAILS_COMMENTS: usize = 3;
+
+/// Descriptions and latest comments for the AI, with `ai.ticket_details` on
+///
+/// Covers the Jira issues the work summary mentions, then the sprint issues,
+/// each cut down to `ai.ticket_details_max_chars`. Issues that can't be
+/// fetched are left out.
+pub(crate) fn ticket_details(
+    ctx: &models::Context,
+    session: &Session,
+    summary: &str,
+    sprint_issues: &[models::Ticket],
+) -> Vec<models::TicketDetails> {
+    let config = &session.config;
+    if !config.ai.ticket_details || !config.tracker.is_jira() {
+        return Vec::new();
+    }
+
+    let mut keys =
+        ticket_parser::extract_tickets(&[summary.to_string()], &config.ticket_filter.denylist);
+    for issue in sprint_issues {
+        if !keys.contains(&issue.key) {
+            keys.push(issue.key.clone());
+        }
+    }
+    keys.truncate(TICKET_DETAILS_LIMIT);
+    if keys.is_empty() {
+        return Vec::new();
+    }
+
+    let jira = match session.jira() {
+        Ok(jira) => jira,
+        Err(e) => {
+            warn!("Not fetching ticket details: {}", e);
+            return Vec::new();
+        }
+    };
+    let label = format!(
+        "Fetching details for {}",
+        progress::count_label(keys.len(), "ticket", "tickets")
+    );
+    let spinner = progress::Spinner::start(label.clone(), ctx);
+    let mut details = Vec::new();
+    for (done, key) in keys.iter().enumerate() {
+        spinner.set_progress(&label, done, keys.len());
+        match jira.get_issue_details(key, TICKET_DETAILS_COMMENTS) {
+            Ok(found) => details.push(ai::fit_ticket_details(
+                found,
+                config.ai.ticket_details_max_chars,
+            )),
+            Err(e) => warn!("Could not fetch details of {}: {}", key, e),
+        }
+    }
+    spinner.finish();
+    details
+}
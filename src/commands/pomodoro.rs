@@ -0,0 +1,223 @@
+use super::log_time::{entry_for_ticket, new_entry_notes, resolve_target, ticket_key};
+use super::Session;
+use crate::models::{TimeEntry, UpdateTimeEntryRequest};
+use crate::progress::count_label;
+use crate::*;
+use console::Term;
+use lazy_static::lazy_static;
+use log::info;
+use regex::Regex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    /// The count harv adds to the end of the notes, e.g. " (3 pomodoros)"
+    static ref COUNT_RE: Regex = Regex::new(r"\s*\((\d+) pomodoros?\)$").unwrap();
+}
+
+/// Work in pomodoros on a ticket's timer, or on the running one
+///
+/// The timer runs during work blocks and is stopped for breaks, then
+/// restarted, so breaks are left out of the entry. After each block the
+/// count of finished pomodoros in the notes goes up. Runs until `cycles`
+/// pomodoros are done, or until interrupted.
+pub fn run_pomodoro(
+    ctx: models::Context,
+    session: &Session,
+    ticket: Option<String>,
+    work_minutes: Option<u32>,
+    break_minutes: Option<u32>,
+    cycles: Option<u32>,
+) -> Result<()> {
+    let config = &session.config.pomodoro;
+    let work = minutes(work_minutes.unwrap_or(config.work_minutes));
+    let harvest_client = session.harvest()?;
+
+    let mut timer = start_timer(&ctx, session, ticket.as_deref())?;
+    let label = timer
+        .notes
+        .clone()
+        .unwrap_or_else(|| "No notes".to_string());
+    let mut count = pomodoro_count(timer.notes.as_deref());
+    let mut done = 0;
+
+    loop {
+        countdown(&ctx, &format!("Pomodoro {}", count + 1), work);
+        count += 1;
+        done += 1;
+
+        let notes = with_pomodoro_count(timer.notes.as_deref().unwrap_or(""), count);
+        let update = UpdateTimeEntryRequest {
+            notes: Some(notes.clone()),
+            ..UpdateTimeEntryRequest::default()
+        };
+        harvest_client.update_time_entry(timer.id, &update, &ctx)?;
+        harvest_client.stop_time_entry(timer.id, &ctx)?;
+        timer.notes = Some(notes);
+        info!("Finished pomodoro {} on entry {}", count, timer.id);
+
+        if cycles.is_some_and(|cycles| done >= cycles) {
+            notify(
+                &ctx,
+                &format!(
+                    "Done: {} on {}",
+                    count_label(done as usize, "pomodoro", "pomodoros"),
+                    label
+                ),
+            );
+            return Ok(());
+        }
+
+        let long = config.long_break_every > 0 && done % config.long_break_every == 0;
+        let pause = match (long, break_minutes) {
+            (true, _) => config.long_break_minutes,
+            (false, Some(minutes)) => minutes,
+            (false, None) => config.break_minutes,
+        };
+        notify(
+            &ctx,
+            &format!(
+                "Pomodoro {} done, take a {} minute break. The timer is stopped until then.",
+                count, pause
+            ),
+        );
+        countdown(&ctx, "Break", minutes(pause));
+
+        timer = TimeEntry {
+            notes: timer.notes.clone(),
+            ..harvest_client.restart_time_entry(timer.id, &ctx)?
+        };
+        notify(&ctx, &format!("Break over, back to {}", label));
+    }
+}
+
+/// The timer to work on: today's entry for `ticket`, a new one, or the running timer
+fn start_timer(
+    ctx: &models::Context,
+    session: &Session,
+    ticket: Option<&str>,
+) -> Result<TimeEntry> {
+    let harvest_client = session.harvest()?;
+    let Some(ticket) = ticket else {
+        return harvest_client.get_running_timer()?.ok_or_else(|| {
+            HarjiraError::InvalidEntry(
+                "No timer is running. Pass a ticket to start one, e.g. harv pomodoro PROJ-123"
+                    .to_string(),
+            )
+        });
+    };
+
+    let ticket_key = ticket_key(ticket)?;
+    let entries = harvest_client.get_todays_time_entries()?;
+    if let Some(entry) = entry_for_ticket(
+        &entries,
+        &ticket_key,
+        &session.config.ticket_filter.denylist,
+    ) {
+        if entry.is_running {
+            return Ok(entry.clone());
+        }
+        let restarted = harvest_client.restart_time_entry(entry.id, ctx)?;
+        return Ok(TimeEntry {
+            notes: entry.notes.clone(),
+            ..restarted
+        });
+    }
+
+    let target = resolve_target(&session.config, &ticket_key, (None, None))?;
+    let notes = new_entry_notes(session, &ticket_key, None);
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let timer = harvest_client.create_time_entry_with_date(
+        &notes,
+        target.project_id,
+        target.task_id,
+        &today,
+        ctx,
+    )?;
+    Ok(TimeEntry {
+        notes: Some(notes),
+        ..timer
+    })
+}
+
+fn minutes(minutes: u32) -> Duration {
+    Duration::from_secs(u64::from(minutes) * 60)
+}
+
+/// Wait for `length`, counting down on the terminal
+fn countdown(ctx: &models::Context, label: &str, length: Duration) {
+    let term = Term::stdout();
+    if ctx.quiet || !term.is_term() {
+        thread::sleep(length);
+        return;
+    }
+
+    let start = Instant::now();
+    while let Some(left) = length.checked_sub(start.elapsed()) {
+        let secs = left.as_secs();
+        let _ = term.clear_line();
+        let _ = term.write_str(&format!(
+            "⏱ {} - {:02}:{:02} left",
+            label,
+            secs / 60,
+            secs % 60
+        ));
+        thread::sleep(
+            left.min(Duration::from_secs(1))
+                .max(Duration::from_millis(10)),
+        );
+    }
+    let _ = term.clear_line();
+}
+
+/// Tell the user a block is over, with the terminal bell to get their attention
+fn notify(ctx: &models::Context, message: &str) {
+    let term = Term::stdout();
+    if term.is_term() {
+        let _ = term.write_str("\x07");
+    }
+    if !ctx.quiet {
+        prompt::display_info(message);
+    }
+}
+
+/// Pomodoros already counted in an entry's notes
+fn pomodoro_count(notes: Option<&str>) -> u32 {
+    notes
+        .and_then(|notes| COUNT_RE.captures(notes))
+        .and_then(|cap| cap[1].parse().ok())
+        .unwrap_or(0)
+}
+
+/// `notes` ending in the pomodoro count, replacing an earlier count
+fn with_pomodoro_count(notes: &str, count: u32) -> String {
+    let notes = COUNT_RE.replace(notes.trim_end(), "");
+    let count = count_label(count as usize, "pomodoro", "pomodoros");
+    if notes.is_empty() {
+        format!("({})", count)
+    } else {
+        format!("{} ({})", notes, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pomodoro_count_in_notes() {
+        assert_eq!(pomodoro_count(Some("PROJ-1 - Login")), 0);
+        assert_eq!(pomodoro_count(Some("PROJ-1 - Login (3 pomodoros)")), 3);
+        assert_eq!(pomodoro_count(None), 0);
+
+        assert_eq!(
+            with_pomodoro_count("PROJ-1 - Login", 1),
+            "PROJ-1 - Login (1 pomodoro)"
+        );
+        assert_eq!(
+            with_pomodoro_count("PROJ-1 - Login (1 pomodoro)", 2),
+            "PROJ-1 - Login (2 pomodoros)"
+        );
+        assert_eq!(with_pomodoro_count("", 4), "(4 pomodoros)");
+    }
+}
@@ -0,0 +1,79 @@
+use super::Session;
+use crate::progress::Spinner;
+use crate::time_parser::format_hours;
+use crate::*;
+
+/// Projects listed under "Top projects"
+const TOP_PROJECTS: usize = 5;
+
+const BAR_WIDTH: usize = 20;
+
+/// Show when and on what the last `weeks` weeks were spent
+pub fn run_stats(ctx: models::Context, session: &Session, weeks: u32) -> Result<()> {
+    if weeks == 0 {
+        return Err(HarjiraError::InvalidEntry(
+            "--weeks must be at least 1".to_string(),
+        ));
+    }
+    let today = chrono::Local::now().date_naive();
+    let from = today - chrono::Duration::days(i64::from(weeks) * 7 - 1);
+    let from_str = from.format("%Y-%m-%d").to_string();
+    let to_str = today.format("%Y-%m-%d").to_string();
+
+    let harvest_client = session.harvest()?;
+    let spinner = Spinner::start("Fetching time entries...", &ctx);
+    let entries = harvest_client.get_time_entries_range(&from_str, &to_str, &ctx);
+    spinner.finish();
+    let entries = entries?;
+
+    outln!(
+        "\nStats for the last {} ({} - {}):",
+        progress::count_label(weeks as usize, "week", "weeks"),
+        from_str,
+        to_str
+    );
+    if entries.is_empty() {
+        outln!("  No time entries logged");
+        return Ok(());
+    }
+
+    let stats = stats::compute(&entries, &session.config.ticket_filter.denylist);
+    outln!(
+        "\n  Logged:          {} over {} ({} per day)",
+        format_hours(stats.total_hours),
+        progress::count_label(stats.days_worked, "day", "days"),
+        format_hours(stats.total_hours / stats.days_worked as f64)
+    );
+    if let (Some(start), Some(stop)) = (stats.average_start, stats.average_stop) {
+        outln!("  Average start:   {}", start.format("%H:%M"));
+        outln!("  Average stop:    {}", stop.format("%H:%M"));
+    }
+    outln!("  Ticket switches: {:.1} per day", stats.switches_per_day);
+
+    outln!("\nHours per weekday (average):");
+    let averages = stats
+        .hours_per_weekday
+        .map(|hours| hours / f64::from(weeks));
+    let max = averages.iter().copied().fold(0.0, f64::max);
+    let monday = today.week(chrono::Weekday::Mon).first_day();
+    for (day, hours) in monday.iter_days().zip(averages) {
+        outln!(
+            "  {}  {:>6}  {}",
+            day.format("%a"),
+            format_hours(hours),
+            report::render_bar(hours, max, BAR_WIDTH)
+        );
+    }
+
+    outln!("\nTop projects:");
+    let max = stats.projects.first().map_or(0.0, |(_, hours)| *hours);
+    for (project, hours) in stats.projects.iter().take(TOP_PROJECTS) {
+        outln!(
+            "  {:<30} {:>6}  {}",
+            project,
+            format_hours(*hours),
+            report::render_bar(*hours, max, BAR_WIDTH)
+        );
+    }
+    Ok(())
+}
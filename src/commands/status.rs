@@ -0,0 +1,534 @@
+use super::{check_timer_after_restart, or_queue, queue_offline, selected_day, Session};
+use crate::progress::Spinner;
+use crate::time_parser::format_hours;
+use crate::*;
+use chrono::{DateTime, Utc};
+use console::Term;
+use log::info;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub fn run_status_all_accounts(session: &Session) -> Result<()> {
+    let config = &session.config;
+
+    outln!("\nHarvest Timer Status (all accounts)");
+    outln!("===================================\n");
+
+    let mut total = 0.0;
+    for (name, harvest) in config.harvest.all_accounts()? {
+        outln!("{} (account {})", name, harvest.account_id);
+
+        let entries = match HarvestClient::new(harvest).and_then(|c| c.get_todays_time_entries()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                prompt::display_warning(&format!("Could not fetch {} entries: {}", name, e));
+                outln!();
+                continue;
+            }
+        };
+
+        match entries.iter().find(|e| e.is_running) {
+            Some(timer) => outln!(
+                "  ✓ Running: {} ({})",
+                timer.notes.as_deref().unwrap_or("No notes"),
+                format_hours(timer.hours.unwrap_or(0.0))
+            ),
+            None => outln!("  ⊗ No timer running"),
+        }
+
+        let hours: f64 = entries.iter().filter_map(|e| e.hours).sum();
+        outln!("  Today: {}\n", format_hours(hours));
+        total += hours;
+    }
+
+    outln!("Total Time Today: {}", format_hours(total));
+    Ok(())
+}
+
+pub fn run_status(ctx: models::Context, session: &Session, no_cache: bool) -> Result<()> {
+    check_timer_after_restart(&ctx, session);
+
+    let now = chrono::Utc::now();
+    let snapshot = load_status_snapshot(session, no_cache, false)?;
+    print_status(session, &snapshot, now, None);
+
+    // Fresh fetches are stamped after `now`, so this only fires for cached data
+    if snapshot.fetched_at < now {
+        outln!(
+            "(cached {}s ago, use --no-cache to refresh)",
+            (now - snapshot.fetched_at).num_seconds()
+        );
+    }
+
+    Ok(())
+}
+
+/// Entries and totals for another day than today
+///
+/// Not cached, the status cache only ever holds today. A timer still running
+/// on that day, such as one left on past midnight, is shown as running.
+pub fn run_status_day(
+    ctx: models::Context,
+    session: &Session,
+    date: Option<String>,
+    yesterday: bool,
+) -> Result<()> {
+    let Some(day) = selected_day(date.as_deref(), yesterday)? else {
+        return run_status(ctx, session, false);
+    };
+    let date = day.format("%Y-%m-%d").to_string();
+    let spinner = Spinner::start(format!("Fetching entries for {}...", date), &ctx);
+    let entries = session.backend()?.get_time_entries_for_date(&date);
+    spinner.finish();
+
+    let snapshot =
+        status_cache::StatusSnapshot::new(&session.config.backend_account_id(), entries?);
+    print_status(session, &snapshot, chrono::Utc::now(), Some(&date));
+    Ok(())
+}
+
+/// Keep the status on screen, fetching it again every `interval` seconds
+///
+/// In a terminal the screen is redrawn every second so a running timer ticks
+/// between fetches. A failed fetch keeps showing the last status with the
+/// error below it. Runs until interrupted.
+pub fn run_status_watch(session: &Session, interval: u64) -> Result<()> {
+    let term = Term::stdout();
+    let redraw = term.is_term();
+    let interval = Duration::from_secs(interval.max(1));
+    let tick = if redraw {
+        Duration::from_secs(1)
+    } else {
+        interval
+    };
+
+    let mut snapshot = load_status_snapshot(session, true, false)?;
+    let mut last_fetch = Instant::now();
+    let mut error = None;
+
+    loop {
+        if last_fetch.elapsed() >= interval {
+            last_fetch = Instant::now();
+            match load_status_snapshot(session, true, false) {
+                Ok(fresh) => {
+                    snapshot = fresh;
+                    error = None;
+                }
+                Err(e) => error = Some(e),
+            }
+        }
+
+        if redraw {
+            let _ = term.clear_screen();
+        }
+        print_status(session, &snapshot, chrono::Utc::now(), None);
+        outln!(
+            "\nUpdated {}, every {}s. Press Ctrl-C to stop.",
+            snapshot
+                .fetched_at
+                .with_timezone(&chrono::Local)
+                .format("%H:%M:%S"),
+            interval.as_secs()
+        );
+        if let Some(e) = &error {
+            prompt::display_warning(&format!("Could not refresh: {}", e));
+        }
+
+        thread::sleep(tick);
+    }
+}
+
+/// Print the timer and entries in `snapshot`, which are for `date` or today
+fn print_status(
+    session: &Session,
+    snapshot: &status_cache::StatusSnapshot,
+    now: DateTime<Utc>,
+    date: Option<&str>,
+) {
+    let title = format!("{} Timer Status", session.config.backend.name());
+    outln!("\n{}", title);
+    outln!("{}\n", "=".repeat(title.chars().count()));
+
+    if let Some(timer) = snapshot.running_entry() {
+        outln!("✓ Timer Running");
+        if let Some(notes) = &timer.notes {
+            outln!("  Notes: {}", notes);
+        }
+        if let Some(project) = &timer.project {
+            outln!("  Project: {}", project.name);
+        }
+        if let Some(task) = &timer.task {
+            outln!("  Task: {}", task.name);
+        }
+        if let Some(started) = &timer.started_time {
+            outln!("  Started: {}", started);
+        }
+        if let Some(hours) = snapshot.running_hours(now) {
+            outln!("  Duration: {}", format_hours(hours));
+        }
+    } else {
+        outln!("⊗ No timer running");
+    }
+
+    outln!();
+
+    let (entries_title, total_title) = match date {
+        Some(date) => (
+            format!("Time Entries on {}:", date),
+            format!("Total Time on {}", date),
+        ),
+        None => (
+            "Today's Time Entries:".to_string(),
+            "Total Time Today".to_string(),
+        ),
+    };
+
+    if !snapshot.entries.is_empty() {
+        outln!("{}", entries_title);
+        for entry in &snapshot.entries {
+            let running_marker = if entry.is_running { " (running)" } else { "" };
+            let hours = entry.hours.unwrap_or(0.0);
+            let notes = entry.notes.as_deref().unwrap_or("No notes");
+            outln!("  • {} - {}{}", format_hours(hours), notes, running_marker);
+        }
+    }
+
+    // Calculate total
+    let total = snapshot.total_hours(now);
+    outln!("\n{}: {}", total_title, format_hours(total));
+    if total > 0.0 {
+        let billable = snapshot.billable_hours(now);
+        outln!(
+            "  Billable: {}, non-billable: {}",
+            format_hours(billable),
+            format_hours(total - billable)
+        );
+    }
+}
+
+pub fn run_status_short(session: &Session, no_cache: bool) -> Result<()> {
+    use status_cache::{DEFAULT_IDLE_FORMAT, DEFAULT_STATUS_FORMAT};
+
+    let config = &session.config;
+    let now = chrono::Utc::now();
+    let snapshot = load_status_snapshot(session, no_cache, true)?;
+
+    let line = status_cache::render_short(
+        &snapshot,
+        now,
+        config
+            .settings
+            .status_format
+            .as_deref()
+            .unwrap_or(DEFAULT_STATUS_FORMAT),
+        config
+            .settings
+            .status_idle_format
+            .as_deref()
+            .unwrap_or(DEFAULT_IDLE_FORMAT),
+        capacity::target_hours(config, chrono::Local::now().date_naive()),
+        &config.ticket_filter.denylist,
+    );
+    outln!("{}", line);
+
+    Ok(())
+}
+
+/// Load today's entries from the status cache, fetching from the backend when it is stale
+///
+/// With `allow_stale`, an expired snapshot is used if the API can't be reached.
+fn load_status_snapshot(
+    session: &Session,
+    no_cache: bool,
+    allow_stale: bool,
+) -> Result<status_cache::StatusSnapshot> {
+    use status_cache::StatusSnapshot;
+    let config = &session.config;
+
+    // A snapshot for another account (`--account`) is no use
+    let cached = if no_cache {
+        None
+    } else {
+        StatusSnapshot::load().filter(|s| s.account_id == config.backend_account_id())
+    };
+
+    match cached {
+        Some(snapshot) if snapshot.is_fresh(chrono::Utc::now()) => {
+            info!("Using cached status from {}", snapshot.fetched_at);
+            Ok(snapshot)
+        }
+        cached => {
+            match session.backend()?.get_todays_time_entries() {
+                Ok(entries) => {
+                    let snapshot = StatusSnapshot::new(&config.backend_account_id(), entries);
+                    snapshot.save();
+                    Ok(snapshot)
+                }
+                // A stale line is more useful in a prompt than an error
+                Err(e) => match cached {
+                    Some(snapshot) if allow_stale => {
+                        info!("Using stale status cache: {}", e);
+                        Ok(snapshot)
+                    }
+                    _ => Err(e),
+                },
+            }
+        }
+    }
+}
+
+/// Stop the running timers, offering to delete ones shorter than `min_entry_minutes`
+///
+/// `keep` stops them all. Without a terminal to ask on they are kept too.
+/// With `date` or `yesterday` only the timers spent on that day are stopped.
+pub fn run_stop(
+    ctx: models::Context,
+    session: &Session,
+    keep: bool,
+    date: Option<String>,
+    yesterday: bool,
+) -> Result<()> {
+    let day = selected_day(date.as_deref(), yesterday)?;
+    let backend = session.backend()?;
+
+    // Includes timers left running since an earlier day
+    let running_timers = match backend.get_running_timers() {
+        Err(e)
+            if offline::is_unreachable(&e)
+                && session.config.backend.is_harvest()
+                && day.is_none() =>
+        {
+            queue_offline(
+                session.harvest()?,
+                offline::Operation::Stop {
+                    entry_id: None,
+                    hours: None,
+                },
+            )?;
+            return Ok(());
+        }
+        other => other?,
+    };
+    let date = day.map(|d| d.format("%Y-%m-%d").to_string());
+    let running_timers: Vec<_> = running_timers
+        .into_iter()
+        .filter(|timer| date.as_ref().is_none_or(|date| &timer.spent_date == date))
+        .collect();
+
+    if running_timers.is_empty() {
+        if !ctx.quiet {
+            match &date {
+                Some(date) => prompt::display_info(&format!("No timer running on {}", date)),
+                None => prompt::display_info("No timer currently running"),
+            }
+        }
+        return Ok(());
+    }
+
+    let min_minutes = session.config.settings.min_entry_minutes;
+    for timer in running_timers {
+        let too_short = min_minutes
+            .is_some_and(|minutes| timer.hours.unwrap_or(0.0) * 60.0 < f64::from(minutes));
+        if too_short
+            && !keep
+            && !ctx.quiet
+            && console::user_attended()
+            && prompt::confirm_discard_short_entry(&timer)?
+        {
+            backend.delete_time_entry(timer.id, &ctx)?;
+            prompt::display_success("Timer discarded");
+            continue;
+        }
+
+        let stopped = backend.stop_time_entry(timer.id, &ctx);
+        if session.config.backend.is_harvest() {
+            or_queue(
+                session.harvest()?,
+                stopped,
+                offline::Operation::Stop {
+                    entry_id: Some(timer.id),
+                    hours: None,
+                },
+            )?;
+        } else {
+            stopped?;
+        }
+        if ctx.quiet {
+            continue;
+        }
+        if timer.is_today() {
+            prompt::display_success("Timer stopped");
+        } else {
+            prompt::display_success(&format!(
+                "Stopped timer '{}' running since {}",
+                timer.notes.as_deref().unwrap_or("(no description)"),
+                timer.spent_date
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Today's entries per project and ticket, only the billable ones with `billable_only`
+pub fn run_today(ctx: models::Context, session: &Session, billable_only: bool) -> Result<()> {
+    use console::style;
+
+    const BAR_WIDTH: usize = 20;
+
+    check_timer_after_restart(&ctx, session);
+
+    let config = &session.config;
+    let harvest_client = session.harvest()?;
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    let spinner = Spinner::start("Fetching today's time entries...", &ctx);
+    let mut entries = harvest_client.get_todays_time_entries()?;
+    if billable_only {
+        entries.retain(|e| e.billable);
+    }
+    // Not every account can see expenses, so don't fail the summary over them
+    let expenses = harvest_client
+        .get_expenses_range(&today, &today)
+        .unwrap_or_else(|e| {
+            info!("Skipping expenses: {}", e);
+            Vec::new()
+        });
+    spinner.finish();
+
+    outln!();
+    outln!(
+        "{}",
+        style(format!(
+            "Today's Summary ({}){}",
+            today,
+            if billable_only { ", billable only" } else { "" }
+        ))
+        .cyan()
+        .bold()
+    );
+    outln!("{}", style("=".repeat(60)).cyan().bold());
+
+    if entries.is_empty() {
+        outln!("\nNo time entries logged today.");
+        print_expenses(&expenses);
+        return Ok(());
+    }
+
+    let groups = report::group_by_project_and_ticket(&entries, &config.ticket_filter.denylist);
+    let total: f64 = groups.iter().map(|g| g.hours).sum();
+    let largest = groups
+        .iter()
+        .flat_map(|g| g.tickets.iter().map(|t| t.hours))
+        .fold(0.0, f64::max);
+
+    // Only with a token that can see rates
+    let money = |entries: Vec<&models::TimeEntry>| {
+        report::billable_amount(entries)
+            .filter(|_| !config.settings.hide_money)
+            .map(|amount| format!("  {:.2}", amount))
+            .unwrap_or_default()
+    };
+
+    for group in &groups {
+        let project_entries = entries
+            .iter()
+            .filter(|e| e.project.as_ref().map(|p| p.name.as_str()) == Some(group.project.as_str()))
+            .collect();
+        outln!();
+        outln!(
+            "{} {}{}",
+            style(&group.project).white().bold(),
+            style(format_hours(group.hours)).green().bold(),
+            money(project_entries)
+        );
+        for ticket in &group.tickets {
+            let running_marker = if ticket.is_running { " (running)" } else { "" };
+            outln!(
+                "  {:<14} {} {:>6}{}",
+                ticket.ticket,
+                style(report::render_bar(ticket.hours, largest, BAR_WIDTH)).cyan(),
+                format_hours(ticket.hours),
+                running_marker
+            );
+        }
+    }
+
+    // A day off is done, whatever its hours
+    let leave = leave::on(&today);
+    let target = match leave {
+        Some(_) => total,
+        None => capacity::target_hours(config, chrono::Local::now().date_naive()),
+    };
+    let percent = report::percent_of_target(total, target);
+    outln!();
+    outln!("{}", style("=".repeat(60)).cyan().bold());
+    outln!(
+        "Total: {} of {} target ({:.0}%){}",
+        style(format_hours(total)).green().bold(),
+        format_hours(target),
+        percent,
+        money(entries.iter().collect())
+    );
+    outln!(
+        "[{}]",
+        style(report::render_bar(total, target, BAR_WIDTH * 2)).green()
+    );
+    if !billable_only {
+        let (billable, non_billable) = report::billable_split(&entries);
+        outln!(
+            "Billable: {}, non-billable: {}",
+            format_hours(billable),
+            format_hours(non_billable)
+        );
+    }
+    if let Some(day) = leave {
+        outln!("On {} leave today", day.kind);
+    }
+
+    print_expenses(&expenses);
+
+    Ok(())
+}
+
+fn print_expenses(expenses: &[expenses::Expense]) {
+    use console::style;
+
+    if expenses.is_empty() {
+        return;
+    }
+
+    outln!();
+    outln!(
+        "{} {}",
+        style("Expenses").white().bold(),
+        style(format!("{:.2}", expenses::total_cost(expenses)))
+            .green()
+            .bold()
+    );
+    for expense in expenses {
+        let category = expense
+            .expense_category
+            .as_ref()
+            .map(|c| c.name.as_str())
+            .unwrap_or("Uncategorized");
+        let project = expense
+            .project
+            .as_ref()
+            .map(|p| p.name.as_str())
+            .unwrap_or("Unknown Project");
+        let notes = expense
+            .notes
+            .as_deref()
+            .map(|n| format!(" - {}", n))
+            .unwrap_or_default();
+        outln!(
+            "  {:>8.2}  {} > {}{}",
+            expense.total_cost.unwrap_or(0.0),
+            project,
+            category,
+            notes
+        );
+    }
+}
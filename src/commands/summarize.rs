@@ -0,0 +1,94 @@
+use super::Session;
+use crate::progress::Spinner;
+use crate::*;
+use log::debug;
+
+pub fn run_summarize(
+    ctx: models::Context,
+    session: &Session,
+    yesterday: bool,
+    date: Option<String>,
+    format: String,
+    force: bool,
+) -> Result<()> {
+    let config = &session.config;
+
+    if !config.ai.enabled {
+        return Err(HarjiraError::Config(
+            "AI generation is not enabled. Set 'ai.enabled = true' in your config file."
+                .to_string(),
+        ));
+    }
+
+    let format: ai::summarize::SummaryFormat = format.parse()?;
+
+    // Step 1: Work out which day to summarize
+    let today = chrono::Local::now().date_naive();
+    let day = match date {
+        Some(d) => chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| {
+            HarjiraError::Config(format!("Invalid date '{}', expected YYYY-MM-DD", d))
+        })?,
+        None if yesterday => previous_working_day(today),
+        None => today,
+    };
+    let day_str = day.format("%Y-%m-%d").to_string();
+
+    let mut ledger = ai::ledger::AiLedger::load();
+    if !force {
+        ai::ledger::check_monthly_cap(
+            ledger.month_cost(chrono::Utc::now()),
+            config.ai.monthly_cost_cap,
+        )?;
+    }
+
+    // Step 2: Gather entries and commits
+    let harvest_client = session.harvest()?;
+    let spinner = Spinner::start(format!("Fetching entries for {}...", day_str), &ctx);
+    let entries = harvest_client.get_time_entries_range(&day_str, &day_str, &ctx)?;
+    spinner.set_message("Reading commits...");
+    let commits = match git::discover_repositories(&config.git.repositories) {
+        Ok(repos) => {
+            git::get_commits_from_repositories_for_date(&repos, day, &config.git.commit_filter()?)?
+        }
+        Err(e) => {
+            debug!("Not reading commits: {}", e);
+            Vec::new()
+        }
+    };
+    spinner.finish();
+
+    if entries.is_empty() && commits.is_empty() {
+        if !ctx.quiet {
+            prompt::display_info(&format!("Nothing logged or committed on {}", day_str));
+        }
+        return Ok(());
+    }
+
+    // Step 3: Ask the AI for the summary
+    let provider = session.ai()?;
+    let spinner = Spinner::start(format!("Summarizing using {}...", provider.name()), &ctx);
+    let mut spent = None;
+    let summary =
+        ai::summarize::summarize_day(provider, &day_str, &entries, &commits, format, &mut spent);
+    spinner.finish();
+
+    if let Some(usage) = spent {
+        ledger.record(provider.name(), provider.model(), usage, chrono::Utc::now());
+        ledger.save();
+    }
+
+    // Printed even with --quiet so it can be piped
+    outln!("{}", summary?);
+    Ok(())
+}
+
+/// The working day before `date`, skipping weekends
+fn previous_working_day(date: chrono::NaiveDate) -> chrono::NaiveDate {
+    use chrono::Datelike;
+
+    let mut day = date.pred_opt().unwrap_or(date);
+    while matches!(day.weekday(), chrono::Weekday::Sat | chrono::Weekday::Sun) {
+        day = day.pred_opt().unwrap_or(day);
+    }
+    day
+}
@@ -0,0 +1,1126 @@
+use super::Session;
+use super::{
+    active_sprint_issues, check_timer_after_restart, record_usage, repick_archived_target,
+};
+use crate::config::ProjectTask;
+use crate::progress::{count_label, Spinner};
+use crate::time_parser::format_hours;
+use crate::*;
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
+
+/// Ask the AI which of the user's Jira issues today's commits belong to
+///
+/// Failures are logged and treated as "no tickets" so sync still reports
+/// normally.
+fn infer_tickets_from_commits(
+    ctx: &models::Context,
+    session: &Session,
+    messages: &[String],
+) -> Result<Vec<models::Ticket>> {
+    let config = &session.config;
+    let mut ledger = ai::ledger::AiLedger::load();
+    if ai::ledger::check_monthly_cap(
+        ledger.month_cost(chrono::Utc::now()),
+        config.ai.monthly_cost_cap,
+    )
+    .is_err()
+    {
+        if !ctx.quiet {
+            prompt::display_warning("Monthly AI cost cap reached, skipping ticket inference");
+        }
+        return Ok(Vec::new());
+    }
+
+    let label = format!(
+        "Matching commits to {} issues...",
+        session.config.tracker.name()
+    );
+    let spinner = Spinner::start(label, ctx);
+    let result = session
+        .tracker()
+        .and_then(|tracker| tracker.get_my_recent_issues(50))
+        .and_then(|issues| {
+            let provider = session.ai()?;
+            let mut spent = None;
+            let tickets = ai::infer::infer_tickets(provider, messages, &issues, &mut spent);
+            if let Some(usage) = spent {
+                ledger.record(provider.name(), provider.model(), usage, chrono::Utc::now());
+                ledger.save();
+            }
+            tickets
+        });
+    spinner.finish();
+
+    match result {
+        Ok(tickets) => {
+            info!("AI matched commits to {} ticket(s)", tickets.len());
+            Ok(tickets)
+        }
+        Err(e) => {
+            if !ctx.quiet {
+                prompt::display_warning(&format!("Could not infer tickets: {}", e));
+            }
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// A stand-in commit for uncommitted work on a ticket's branch, when there are no commits
+///
+/// The branch name takes the place of the commit message, so the ticket in
+/// it goes through the usual lookup. Needs `settings.wip_min_lines` changed
+/// lines, and is only offered when there is someone to ask.
+fn work_in_progress_commit(
+    ctx: &models::Context,
+    config: &Config,
+    repos: &[String],
+) -> Result<Option<models::Commit>> {
+    let min_lines = config.settings.wip_min_lines;
+    if min_lines == 0 {
+        return Ok(None);
+    }
+
+    for repo in repos {
+        let wip = match git::work_in_progress(repo) {
+            Ok(Some(wip)) if wip.lines >= min_lines => wip,
+            Ok(_) => continue,
+            Err(e) => {
+                warn!("Could not read the working tree of {}: {}", repo, e);
+                continue;
+            }
+        };
+        let commit = models::Commit {
+            message: wip.branch.clone(),
+            author: String::new(),
+            timestamp: chrono::Utc::now().timestamp(),
+            repository: wip.repository.clone(),
+        };
+        let Some(ticket) = ticket_keys_per_commit(config, std::slice::from_ref(&commit))
+            .into_iter()
+            .find_map(|(_, keys)| keys.into_iter().next())
+        else {
+            info!("No ticket in branch {} of {}", wip.branch, repo);
+            continue;
+        };
+
+        info!(
+            "{} lines changed but not committed on {} in {}",
+            wip.lines, wip.branch, repo
+        );
+        // Background syncs don't start timers for work nobody committed to yet
+        if ctx.auto_start || ctx.quiet || !console::user_attended() {
+            continue;
+        }
+        if prompt::confirm_start_from_wip(&wip, &ticket)? {
+            return Ok(Some(commit));
+        }
+    }
+    Ok(None)
+}
+
+/// The tracker provider of each commit's repository and the ticket keys it mentions
+fn ticket_keys_per_commit(
+    config: &Config,
+    commits: &[models::Commit],
+) -> Vec<(String, Vec<String>)> {
+    let mut github_repos: HashMap<&str, Option<String>> = HashMap::new();
+
+    commits
+        .iter()
+        .map(|commit| {
+            let provider = config
+                .tracker
+                .provider_for(&commit.repository)
+                .to_lowercase();
+            let text = ticket_parser::searched_text(
+                &commit.message,
+                &config.ticket_filter.trailer_keys,
+                config.ticket_filter.searches_body(),
+            );
+            let message = std::slice::from_ref(&text);
+            let keys = if provider == "github" {
+                // Bare #123 means an issue in the repository the commit was made in
+                let repository = github_repos
+                    .entry(commit.repository.as_str())
+                    .or_insert_with(|| {
+                        git::github_repository(&commit.repository)
+                            .or_else(|| config.github.repository.clone())
+                    });
+                ticket_parser::extract_github_issues(message, repository.as_deref())
+            } else {
+                ticket_parser::extract_for_tracker(
+                    &provider,
+                    message,
+                    &config.ticket_filter.denylist,
+                )
+            };
+            (provider, keys)
+        })
+        .collect()
+}
+
+/// Ticket keys grouped by tracker provider, from `ticket_keys_per_commit`
+fn ticket_keys_by_tracker(per_commit: &[(String, Vec<String>)]) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+
+    for (provider, keys) in per_commit {
+        if keys.is_empty() {
+            continue;
+        }
+
+        let index = match groups.iter().position(|(p, _)| p == provider) {
+            Some(index) => index,
+            None => {
+                groups.push((provider.clone(), Vec::new()));
+                groups.len() - 1
+            }
+        };
+        groups[index].1.extend(keys.iter().cloned());
+    }
+
+    for (_, keys) in &mut groups {
+        keys.sort();
+        keys.dedup();
+    }
+    groups
+}
+
+/// Days of history searched for time already logged on the picker's tickets
+const LOGGED_LOOKBACK_DAYS: i64 = 365;
+
+/// Hours already logged on each ticket, shown in the ticket picker
+///
+/// Only Harvest can be searched by date range; for other backends, or when
+/// the lookup fails, the picker just shows no hours.
+fn logged_on_tickets(
+    ctx: &models::Context,
+    session: &Session,
+    tickets: &[models::Ticket],
+) -> HashMap<String, ticket_time::LoggedTime> {
+    if !session.config.backend.is_harvest() {
+        return HashMap::new();
+    }
+    let today = chrono::Local::now().date_naive();
+    let from = (today - chrono::Duration::days(LOGGED_LOOKBACK_DAYS - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+    let today = today.format("%Y-%m-%d").to_string();
+
+    let spinner = Spinner::start("Looking up time already logged...", ctx);
+    let entries = session
+        .harvest()
+        .and_then(|client| client.get_time_entries_range(&from, &today, ctx));
+    spinner.finish();
+    match entries {
+        Ok(entries) => {
+            let keys: Vec<&str> = tickets.iter().map(|t| t.key.as_str()).collect();
+            ticket_time::logged_per_ticket(
+                &entries,
+                &keys,
+                &session.config.ticket_filter.denylist,
+                &today,
+            )
+        }
+        Err(e) => {
+            warn!("Could not look up time logged on tickets: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Which commits mention which ticket, for the drill-down in the ticket picker
+///
+/// None unless it helps choosing: a commit mentions several tickets, or the
+/// tickets were worked on in more than one repository.
+fn commit_view(
+    tickets: &[models::Ticket],
+    commits: &[models::Commit],
+    per_commit: &[(String, Vec<String>)],
+) -> Option<String> {
+    let mentioning: Vec<(&models::Commit, &[String])> = commits
+        .iter()
+        .zip(per_commit)
+        .filter(|(_, (_, keys))| !keys.is_empty())
+        .map(|(commit, (_, keys))| (commit, keys.as_slice()))
+        .collect();
+    let shared = mentioning.iter().any(|(_, keys)| keys.len() > 1);
+    let repositories: HashSet<&str> = mentioning
+        .iter()
+        .map(|(commit, _)| commit.repository.as_str())
+        .collect();
+    if !shared && repositories.len() < 2 {
+        return None;
+    }
+
+    let mut lines = Vec::new();
+    for ticket in tickets {
+        lines.push(format!("{} - {}", ticket.key, ticket.summary));
+        for (commit, keys) in mentioning
+            .iter()
+            .filter(|(_, keys)| keys.contains(&ticket.key))
+        {
+            let others: Vec<&str> = keys
+                .iter()
+                .filter(|key| **key != ticket.key)
+                .map(String::as_str)
+                .collect();
+            let also = if others.is_empty() {
+                String::new()
+            } else {
+                format!(" (also {})", others.join(", "))
+            };
+            let time = chrono::DateTime::from_timestamp(commit.timestamp, 0)
+                .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+                .unwrap_or_default();
+            let repository = std::path::Path::new(&commit.repository)
+                .file_name()
+                .map_or(commit.repository.clone(), |name| {
+                    name.to_string_lossy().to_string()
+                });
+            lines.push(format!(
+                "  {:<14} {}  {}{}",
+                repository,
+                time,
+                commit.message.lines().next().unwrap_or_default(),
+                also
+            ));
+        }
+    }
+    Some(lines.join("\n"))
+}
+
+/// The latest conventional commit whose scope is in `[scope_mapping]`, and its target
+///
+/// Only used with the Harvest backend, as the targets are Harvest projects.
+fn scope_work(
+    config: &Config,
+    commits: &[models::Commit],
+) -> Option<(conventional::ConventionalCommit, ProjectTask)> {
+    if config.scope_mapping.is_empty() || !config.backend.is_harvest() {
+        return None;
+    }
+
+    // Commits are sorted newest first
+    commits.iter().find_map(|commit| {
+        let parsed = conventional::parse(&commit.message)?;
+        let scope = parsed.scope.as_deref()?;
+        let target = config
+            .scope_mapping
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(scope))
+            .map(|(_, target)| *target)?;
+        Some((parsed, target))
+    })
+}
+
+/// The mapped project and task of a repository without a tracker
+///
+/// Only with the Harvest backend, which the mapped ids belong to.
+fn repository_target(config: &Config, repo_path: &str) -> Option<ProjectTask> {
+    if !config.backend.is_harvest() {
+        return None;
+    }
+    config.mapping.for_repository(repo_path)
+}
+
+/// The `[harvest]` project and task for `settings.fallback_to_branch`, when it's on
+fn branch_fallback_target(config: &Config) -> Option<ProjectTask> {
+    if !config.settings.fallback_to_branch || !config.backend.is_harvest() {
+        return None;
+    }
+    Some(ProjectTask {
+        project_id: config.harvest.project_id?,
+        task_id: config.harvest.task_id?,
+    })
+}
+
+/// Timer notes for work in a mapped repository, e.g. "intranet: feature/login"
+fn repository_notes(repo_path: &str, branch: Option<&str>) -> String {
+    let name = std::path::Path::new(repo_path.trim_end_matches('/'))
+        .file_name()
+        .map_or(repo_path.to_string(), |name| {
+            name.to_string_lossy().to_string()
+        });
+    match branch {
+        Some(branch) => format!("{}: {}", name, branch),
+        None => name,
+    }
+}
+
+/// Start a Harvest timer for work without a ticket, on the project and task it maps to
+///
+/// A timer with the same notes keeps running, and today's stopped entry with
+/// them is continued rather than adding another row.
+fn start_mapped_timer(
+    ctx: &models::Context,
+    session: &Session,
+    notes: &str,
+    target: ProjectTask,
+    source: &str,
+) -> Result<()> {
+    let harvest_client = session.harvest()?;
+    info!("Using {} for: {}", source, notes);
+
+    if let Some(timer) = harvest_client.get_running_timer()? {
+        if timer.notes.as_deref() == Some(notes) && timer.is_today() {
+            if !ctx.quiet {
+                prompt::display_info(&format!("Timer already running: {}", notes));
+            }
+            return Ok(());
+        }
+
+        let should_stop = ctx.auto_stop || prompt::confirm_stop_timer_for_new(&timer)?;
+        if !should_stop {
+            if !ctx.quiet {
+                prompt::display_info("Keeping current timer running");
+            }
+            return Ok(());
+        }
+        harvest_client.stop_time_entry(timer.id, ctx)?;
+        if !ctx.quiet {
+            prompt::display_success("Stopped previous timer");
+        }
+    }
+
+    let entries = harvest_client.get_todays_time_entries()?;
+    let earlier = entries.iter().find(|e| {
+        !e.is_running
+            && e.notes.as_deref() == Some(notes)
+            && e.project.as_ref().map(|p| p.id) == Some(target.project_id)
+    });
+    if let Some(entry) = earlier {
+        let restarted = harvest_client.restart_time_entry(entry.id, ctx)?;
+        record_usage(ctx, &[(target.project_id, target.task_id)])?;
+        if !ctx.quiet {
+            prompt::display_success(&format!(
+                "Restarted today's timer for {}: {} ({} so far)",
+                source,
+                notes,
+                format_hours(restarted.hours.or(entry.hours).unwrap_or(0.0))
+            ));
+        }
+        return Ok(());
+    }
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    harvest_client.create_time_entry_with_date(
+        notes,
+        target.project_id,
+        target.task_id,
+        &today,
+        ctx,
+    )?;
+    record_usage(ctx, &[(target.project_id, target.task_id)])?;
+
+    if !ctx.quiet {
+        prompt::display_success(&format!("Started timer for {}: {}", source, notes));
+    }
+    Ok(())
+}
+
+pub fn run_sync(
+    ctx: models::Context,
+    session: &Session,
+    repo_override: Option<String>,
+) -> Result<()> {
+    info!("Starting sync operation...");
+
+    // Load configuration
+    let config = &session.config;
+
+    check_timer_after_restart(&ctx, session);
+
+    // Stop a timer left running past the end of the day
+    if let Some(stop_at) = &config.settings.auto_stop_at {
+        let past_end_of_day = auto_stop_timer(&ctx, session, stop_at)?;
+        if past_end_of_day && ctx.auto_start {
+            info!("Past auto_stop_at ({}), not starting new timers", stop_at);
+            return Ok(());
+        }
+    }
+
+    // Determine repositories to check
+    let repos = if let Some(repo) = repo_override {
+        vec![repo]
+    } else {
+        git::discover_repositories(&config.git.repositories)?
+    };
+
+    info!("Checking {} repository(ies)", repos.len());
+
+    // Get commits from all repositories
+    let mut commits = git::get_commits_from_repositories(&repos, &config.git.commit_filter()?)?;
+
+    // Remember when there was activity, for `harv autolog`
+    if config.auto_log.enabled {
+        let mut log = activity::ActivityLog::load();
+        log.record(activity::observe(&repos, &commits), chrono::Utc::now());
+        log.save();
+    }
+
+    if commits.is_empty() {
+        if let Some(branch_commit) = work_in_progress_commit(&ctx, config, &repos)? {
+            commits.push(branch_commit);
+        }
+    }
+
+    if commits.is_empty() {
+        if ctx.strict {
+            return Err(HarjiraError::NoCommitsFound);
+        }
+        if !ctx.quiet {
+            prompt::display_info("No commits found from today");
+        }
+        return Ok(());
+    }
+
+    info!("Found {} commits from today", commits.len());
+
+    // Repositories without a tracker book straight onto their project
+    let latest = &commits[0].repository;
+    if let Some(target) = repository_target(config, latest) {
+        let notes = repository_notes(latest, git::current_branch(latest).as_deref());
+        let source = format!("repository {}", latest);
+        return start_mapped_timer(&ctx, session, &notes, target, &source);
+    }
+    let commits: Vec<models::Commit> = commits
+        .into_iter()
+        .filter(|commit| repository_target(config, &commit.repository).is_none())
+        .collect();
+
+    // Extract commit messages
+    let messages: Vec<String> = commits.iter().map(|c| c.message.clone()).collect();
+
+    // Parse ticket keys in the style of each repository's tracker (with denylist filter)
+    let keys_per_commit = ticket_keys_per_commit(config, &commits);
+    let keys_by_tracker = ticket_keys_by_tracker(&keys_per_commit);
+    let ticket_keys: Vec<String> = keys_by_tracker
+        .iter()
+        .flat_map(|(_, keys)| keys.iter().cloned())
+        .collect();
+
+    let inferred = if ticket_keys.is_empty() && config.ai.enabled && config.ai.infer_tickets {
+        infer_tickets_from_commits(&ctx, session, &messages)?
+    } else {
+        Vec::new()
+    };
+
+    if ticket_keys.is_empty() && inferred.is_empty() {
+        if let Some((commit, target)) = scope_work(config, &commits) {
+            let source = format!("scope '{}'", commit.scope.as_deref().unwrap_or_default());
+            return start_mapped_timer(&ctx, session, &commit.header(), target, &source);
+        }
+        if let Some(target) = branch_fallback_target(config) {
+            let latest = &commits[0].repository;
+            let notes = repository_notes(latest, git::current_branch(latest).as_deref());
+            return start_mapped_timer(&ctx, session, &notes, target, "the current branch");
+        }
+        if ctx.strict {
+            return Err(HarjiraError::NoTicketsFound);
+        }
+        if !ctx.quiet {
+            prompt::display_info("No tickets found in today's commits");
+        }
+        return Ok(());
+    }
+
+    info!("Found {} ticket(s): {:?}", ticket_keys.len(), ticket_keys);
+
+    // Initialize API clients
+    let backend = session.backend()?;
+
+    let tickets = if inferred.is_empty() {
+        // Fetch details for all tickets from the tracker they belong to
+        let source = match keys_by_tracker.as_slice() {
+            [(provider, _)] => config::tracker_name(provider),
+            _ => "ticket",
+        };
+        let label = format!(
+            "Fetching {} details for {}",
+            source,
+            count_label(ticket_keys.len(), "ticket", "tickets")
+        );
+        let spinner = Spinner::start(label.clone(), &ctx);
+        let mut tickets = Vec::new();
+        for (provider, keys) in &keys_by_tracker {
+            let fetched = tickets.len();
+            let tracker = session.tracker_for(provider)?;
+            tickets.extend(tracker.get_issues_with_progress(keys, &mut |done, _| {
+                spinner.set_progress(&label, fetched + done, ticket_keys.len())
+            }));
+        }
+        spinner.finish();
+        tickets
+    } else {
+        inferred
+    };
+    let tickets = sprint_first(tickets, &active_sprint_issues(&ctx, session));
+
+    // Guesses are only picked without asking when the AI is confident
+    if ctx.auto_start
+        && tickets[0]
+            .confidence
+            .is_some_and(|c| c < ai::infer::AUTO_SELECT_CONFIDENCE)
+    {
+        if !ctx.quiet {
+            prompt::display_info(&format!(
+                "Best AI guess {} is below {:.0}% confidence, not starting a timer",
+                tickets[0].key,
+                ai::infer::AUTO_SELECT_CONFIDENCE * 100.0
+            ));
+        }
+        return Ok(());
+    }
+
+    // Select ticket (prompt if multiple, or to confirm an AI guess)
+    let single_known = tickets.len() == 1 && tickets[0].confidence.is_none();
+    let pick = !(single_known || ctx.auto_start);
+    let logged = if pick {
+        logged_on_tickets(&ctx, session, &tickets)
+    } else {
+        HashMap::new()
+    };
+    let mut selected = if pick {
+        let view = commit_view(&tickets, &commits, &keys_per_commit);
+        prompt::prompt_ticket_selection(&tickets, view.as_deref(), &logged)?
+    } else {
+        vec![tickets[0].clone()]
+    };
+    let selected_ticket = if selected.len() == 1 {
+        selected.remove(0)
+    } else {
+        // Several picked: log the finished ones, then time the active one
+        let active = prompt::prompt_active_ticket(&selected)?.map(|i| selected.remove(i));
+        log_finished_tickets(&ctx, session, &keys_by_tracker, &selected, &logged)?;
+        match active {
+            Some(ticket) => ticket,
+            None => return Ok(()),
+        }
+    };
+
+    info!(
+        "Selected ticket: {} - {}",
+        selected_ticket.key, selected_ticket.summary
+    );
+    warn_over_estimate(&ctx, &selected_ticket, &logged, 0.0);
+
+    // Check the current timer
+    let running_timer = backend.get_running_timer()?;
+
+    // Handle existing timer
+    if let Some(timer) = running_timer {
+        // Check if timer is already for this ticket
+        let same_ticket = timer
+            .notes
+            .as_deref()
+            .is_some_and(|notes| notes.contains(&selected_ticket.key));
+        if same_ticket && timer.is_today() {
+            if !ctx.quiet {
+                prompt::display_info(&format!(
+                    "Timer already running for {}",
+                    selected_ticket.key
+                ));
+            }
+            return Ok(());
+        }
+
+        // A timer left running since an earlier day is closed so today gets its own entry
+        let should_stop = if ctx.auto_stop || !timer.is_today() {
+            if !timer.is_today() && !ctx.quiet {
+                prompt::display_warning(&format!(
+                    "Timer '{}' has been running since {}",
+                    timer.notes.as_deref().unwrap_or("(no description)"),
+                    timer.spent_date
+                ));
+            }
+            true
+        } else {
+            prompt::confirm_stop_timer(&timer, &selected_ticket.key)?
+        };
+
+        if !should_stop {
+            if !ctx.quiet {
+                prompt::display_info("Keeping current timer running");
+            }
+            return Ok(());
+        }
+
+        // Stop current timer
+        backend.stop_time_entry(timer.id, &ctx)?;
+        if !ctx.quiet {
+            prompt::display_success("Stopped previous timer");
+        }
+    }
+
+    // Pick today's entry for this ticket up again instead of adding another row
+    if config.settings.reuse_entry_same_day {
+        let entries = backend.get_todays_time_entries()?;
+        let denylist = &config.ticket_filter.denylist;
+        if let Some(entry) = reusable_entry(&entries, &selected_ticket.key, denylist) {
+            let restarted = backend.restart_time_entry(entry.id, &ctx)?;
+            if let (Some(project), Some(task)) = (&entry.project, &entry.task) {
+                record_usage(&ctx, &[(project.id, task.id)])?;
+            }
+            if !ctx.quiet {
+                prompt::display_success(&format!(
+                    "Restarted today's timer for {} ({} so far)",
+                    selected_ticket.key,
+                    format_hours(restarted.hours.or(entry.hours).unwrap_or(0.0))
+                ));
+            }
+            return Ok(());
+        }
+    }
+
+    // Create new timer
+    let ticket_url = ticket_url(session, &keys_by_tracker, &selected_ticket.key)?;
+    let created = match backend.create_time_entry(
+        &selected_ticket.key,
+        &selected_ticket.summary,
+        &ticket_url,
+        &ctx,
+    ) {
+        Err(e) if config.backend.is_harvest() => match repick_archived_target(&ctx, session, &e)? {
+            Some(harvest_client) => harvest_client.create_time_entry(
+                &selected_ticket.key,
+                &selected_ticket.summary,
+                &ticket_url,
+                &ctx,
+            )?,
+            None => return Err(e),
+        },
+        other => other?,
+    };
+    if let (Some(project), Some(task)) = (&created.project, &created.task) {
+        record_usage(&ctx, &[(project.id, task.id)])?;
+    }
+
+    if !ctx.quiet {
+        prompt::display_success(&format!(
+            "Started timer for {} - {}",
+            selected_ticket.key, selected_ticket.summary
+        ));
+    }
+
+    Ok(())
+}
+
+/// Link to a ticket in the tracker its key was found for
+///
+/// Inferred tickets come from the default tracker.
+fn ticket_url(
+    session: &Session,
+    keys_by_tracker: &[(String, Vec<String>)],
+    key: &str,
+) -> Result<String> {
+    let provider = keys_by_tracker
+        .iter()
+        .find(|(_, keys)| keys.iter().any(|k| k == key))
+        .map_or(session.config.tracker.provider.as_str(), |(provider, _)| {
+            provider
+        });
+    Ok(session.tracker_for(provider)?.get_ticket_url(key))
+}
+
+/// Log the tickets picked as done, asking one by one how long each took
+///
+/// A done ticket whose timer is still running is stopped instead, the timer
+/// already has its time.
+fn log_finished_tickets(
+    ctx: &models::Context,
+    session: &Session,
+    keys_by_tracker: &[(String, Vec<String>)],
+    tickets: &[models::Ticket],
+    logged: &HashMap<String, ticket_time::LoggedTime>,
+) -> Result<()> {
+    let backend = session.backend()?;
+    let running = backend.get_running_timer()?;
+    let denylist = &session.config.ticket_filter.denylist;
+
+    for ticket in tickets {
+        if let Some(timer) = running_timer_for(running.as_ref(), &ticket.key, denylist) {
+            let stopped = backend.stop_time_entry(timer.id, ctx)?;
+            if !ctx.quiet {
+                prompt::display_success(&format!(
+                    "Stopped the timer for {} ({})",
+                    ticket.key,
+                    format_hours(stopped.hours.unwrap_or(0.0))
+                ));
+            }
+            continue;
+        }
+
+        let Some(hours) = prompt::prompt_finished_ticket_hours(ticket)? else {
+            continue;
+        };
+        warn_over_estimate(ctx, ticket, logged, hours);
+        let url = ticket_url(session, keys_by_tracker, &ticket.key)?;
+        let logged = backend.log_time_entry(&ticket.key, &ticket.summary, &url, hours, ctx)?;
+        if let (Some(project), Some(task)) = (&logged.project, &logged.task) {
+            record_usage(ctx, &[(project.id, task.id)])?;
+        }
+        if !ctx.quiet {
+            prompt::display_success(&format!(
+                "Logged {} on {} - {}",
+                format_hours(hours),
+                ticket.key,
+                ticket.summary
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Warn when logging `hours` on `ticket` goes over what is left of its estimate
+///
+/// With no hours, as when starting a timer, only warns once the estimate is used up.
+fn warn_over_estimate(
+    ctx: &models::Context,
+    ticket: &models::Ticket,
+    logged: &HashMap<String, ticket_time::LoggedTime>,
+    hours: f64,
+) {
+    let Some(estimate) = ticket.estimate else {
+        return;
+    };
+    let total = ticket_time::logged_hours(ticket, logged);
+    let Some(left) = ticket_time::remaining_estimate(&estimate, total) else {
+        return;
+    };
+    if ctx.quiet || (left > 0.0 && hours <= left) {
+        return;
+    }
+    let message = if left <= 0.0 {
+        let label = ticket_time::estimate_label(&estimate, total)
+            .unwrap_or_else(|| "no estimate left".to_string());
+        format!("{} has used up its estimate ({})", ticket.key, label)
+    } else {
+        format!(
+            "Logging {} on {} goes over its remaining estimate of {}",
+            format_hours(hours),
+            ticket.key,
+            format_hours(left)
+        )
+    };
+    prompt::display_warning(&message);
+}
+
+/// Stop the running timer if it is past `stop_at`, trimming it back if configured
+///
+/// Returns whether the current time is past `stop_at` today.
+fn auto_stop_timer(ctx: &models::Context, session: &Session, stop_at: &str) -> Result<bool> {
+    let config = &session.config;
+    let stop_time = time_parser::parse_clock_time(stop_at)?;
+    let now = chrono::Local::now().naive_local();
+    let today = now.date().format("%Y-%m-%d").to_string();
+    let past_end_of_day = time_parser::hours_past(&today, stop_time, now).is_some();
+
+    let backend = session.backend()?;
+    let Some(timer) = backend.get_running_timer()? else {
+        return Ok(past_end_of_day);
+    };
+
+    // Measured from the entry's own date, so timers left running overnight count too
+    let Some(overrun) = time_parser::hours_past(&timer.spent_date, stop_time, now) else {
+        return Ok(past_end_of_day);
+    };
+
+    let notes = timer.notes.as_deref().unwrap_or("(no description)");
+    let stopped = backend.stop_time_entry(timer.id, ctx)?;
+
+    let hours = stopped.hours.or(timer.hours).unwrap_or(0.0);
+    let trimmed = (hours - overrun).max(0.0);
+    if config.settings.auto_stop_trim && trimmed < hours {
+        let update = models::UpdateTimeEntryRequest {
+            hours: Some(trimmed),
+            ..Default::default()
+        };
+        backend.update_time_entry(stopped.id, &update, ctx)?;
+        if !ctx.quiet {
+            prompt::display_success(&format!(
+                "Stopped timer '{}' left running after {} ({}, trimmed from {})",
+                notes,
+                stop_at,
+                format_hours(trimmed),
+                format_hours(hours)
+            ));
+        }
+    } else if !ctx.quiet {
+        prompt::display_success(&format!(
+            "Stopped timer '{}' left running after {} ({})",
+            notes,
+            stop_at,
+            format_hours(hours)
+        ));
+    }
+    info!("Auto-stopped timer {} at end of day", timer.id);
+
+    Ok(past_end_of_day)
+}
+
+/// Mark tickets in the active sprint and move them to the front, keeping order otherwise
+fn sprint_first(
+    mut tickets: Vec<models::Ticket>,
+    sprint: &[models::Ticket],
+) -> Vec<models::Ticket> {
+    for ticket in &mut tickets {
+        ticket.in_sprint = sprint.iter().any(|s| s.key == ticket.key);
+    }
+    tickets.sort_by_key(|t| !t.in_sprint);
+    tickets
+}
+
+/// Today's stopped entry for `ticket_key`, if there is one to restart
+///
+/// The key has to match exactly, PROJ-12's entry is never reused for PROJ-1.
+fn reusable_entry<'a>(
+    entries: &'a [models::TimeEntry],
+    ticket_key: &str,
+    denylist: &[String],
+) -> Option<&'a models::TimeEntry> {
+    entries
+        .iter()
+        .find(|e| !e.is_running && ticket_time::is_for_ticket(e, ticket_key, denylist))
+}
+
+/// The running timer if it is for `ticket_key`, matching the whole key
+fn running_timer_for<'a>(
+    running: Option<&'a models::TimeEntry>,
+    ticket_key: &str,
+    denylist: &[String],
+) -> Option<&'a models::TimeEntry> {
+    running.filter(|timer| ticket_time::is_for_ticket(timer, ticket_key, denylist))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::TimeEntry;
+
+    fn entry(id: u64, notes: &str, is_running: bool) -> TimeEntry {
+        TimeEntry {
+            id,
+            spent_date: "2026-10-14".to_string(),
+            hours: Some(1.0),
+            notes: Some(notes.to_string()),
+            is_running,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ticket_keys_by_tracker() {
+        let mut config: Config = toml::from_str(config::TEMPLATE).unwrap();
+        config
+            .tracker
+            .repositories
+            .insert("/src/legacy/".to_string(), "azure_devops".to_string());
+        let commit = |repository: &str, message: &str| models::Commit {
+            message: message.to_string(),
+            author: "dev".to_string(),
+            timestamp: 0,
+            repository: repository.to_string(),
+        };
+        config
+            .tracker
+            .repositories
+            .insert("/src/oss".to_string(), "github".to_string());
+        config.github.repository = Some("acme/oss".to_string());
+        let commits = vec![
+            commit("/src/web", "ABC-1 fix login, see #4"),
+            commit("/src/legacy", "AB#12 port ABC-1"),
+            commit("/src/docs", "Typo"),
+            commit("/src/oss", "Fix build (#3), needed for acme/api#9"),
+            commit("/src/web", "ABC-1 tests"),
+        ];
+
+        assert_eq!(
+            ticket_keys_by_tracker(&ticket_keys_per_commit(&config, &commits)),
+            vec![
+                ("jira".to_string(), vec!["ABC-1".to_string()]),
+                ("azure_devops".to_string(), vec!["AB#12".to_string()]),
+                (
+                    "github".to_string(),
+                    vec!["acme/api#9".to_string(), "acme/oss#3".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commit_view_only_for_shared_commits_or_repositories() {
+        let commit = |repository: &str, message: &str| models::Commit {
+            message: message.to_string(),
+            author: "dev".to_string(),
+            timestamp: 0,
+            repository: repository.to_string(),
+        };
+        let keys = |keys: &[&str]| {
+            (
+                "jira".to_string(),
+                keys.iter().map(|k| k.to_string()).collect::<Vec<_>>(),
+            )
+        };
+        let tickets = vec![ticket("ABC-1"), ticket("ABC-2")];
+
+        let commits = vec![
+            commit("/src/web", "ABC-1 fix login"),
+            commit("/src/web", "ABC-2 add logout"),
+        ];
+        let per_commit = vec![keys(&["ABC-1"]), keys(&["ABC-2"])];
+        assert_eq!(commit_view(&tickets, &commits, &per_commit), None);
+
+        let commits = vec![
+            commit("/src/web", "ABC-1 ABC-2 share the session code\n\nDetails"),
+            commit("/src/web", "Typo"),
+        ];
+        let per_commit = vec![keys(&["ABC-1", "ABC-2"]), keys(&[])];
+        let view = commit_view(&tickets, &commits, &per_commit).unwrap();
+        assert!(view.starts_with("ABC-1 - "));
+        assert!(view.contains("ABC-1 ABC-2 share the session code (also ABC-2)"));
+        assert!(view.contains("ABC-1 ABC-2 share the session code (also ABC-1)"));
+        assert!(!view.contains("Typo"));
+        assert!(!view.contains("Details"));
+        assert!(view.contains("  web "));
+    }
+
+    #[test]
+    fn test_branch_fallback_target() {
+        let mut config: Config = toml::from_str(config::TEMPLATE).unwrap();
+        config.harvest.project_id = Some(1);
+        config.harvest.task_id = Some(2);
+        assert_eq!(branch_fallback_target(&config), None);
+
+        config.settings.fallback_to_branch = true;
+        assert_eq!(
+            branch_fallback_target(&config),
+            Some(ProjectTask {
+                project_id: 1,
+                task_id: 2
+            })
+        );
+
+        config.backend.provider = "tempo".to_string();
+        assert_eq!(branch_fallback_target(&config), None);
+    }
+
+    #[test]
+    fn test_repository_target_and_notes() {
+        let mut config: Config = toml::from_str(config::TEMPLATE).unwrap();
+        let target = ProjectTask {
+            project_id: 1,
+            task_id: 2,
+        };
+        config
+            .mapping
+            .repositories
+            .insert("/src/intranet".to_string(), target);
+
+        assert_eq!(repository_target(&config, "/src/intranet/"), Some(target));
+        assert_eq!(repository_target(&config, "/src/web"), None);
+        assert_eq!(
+            repository_notes("/src/intranet/", Some("feature/login")),
+            "intranet: feature/login"
+        );
+        assert_eq!(repository_notes("/src/intranet", None), "intranet");
+
+        config.backend.provider = "toggl".to_string();
+        assert_eq!(repository_target(&config, "/src/intranet"), None);
+    }
+
+    #[test]
+    fn test_scope_work_uses_latest_mapped_scope() {
+        let mut config: Config = toml::from_str(config::TEMPLATE).unwrap();
+        let target = ProjectTask {
+            project_id: 1,
+            task_id: 2,
+        };
+        config.scope_mapping.insert("Auth".to_string(), target);
+        let commit = |message: &str| models::Commit {
+            message: message.to_string(),
+            author: "dev".to_string(),
+            timestamp: 0,
+            repository: String::new(),
+        };
+        let commits = vec![
+            commit("docs(readme): typo"),
+            commit("fix(auth): refresh expired tokens"),
+            commit("feat(auth): add token refresh"),
+        ];
+
+        let (work, found) = scope_work(&config, &commits).unwrap();
+        assert_eq!(work.header(), "fix(auth): refresh expired tokens");
+        assert_eq!(found, target);
+
+        assert!(scope_work(&config, &commits[..1]).is_none());
+        config.backend.provider = "toggl".to_string();
+        assert!(scope_work(&config, &commits).is_none());
+    }
+
+    #[test]
+    fn test_reusable_entry() {
+        let entries = vec![
+            entry(1, "ABC-2 - Review", false),
+            entry(2, "ABC-1 - Fix login", true),
+            entry(3, "ABC-1 - Fix login", false),
+        ];
+        assert_eq!(
+            reusable_entry(&entries, "ABC-1", &[]).map(|e| e.id),
+            Some(3)
+        );
+        assert_eq!(
+            reusable_entry(&entries, "ABC-2", &[]).map(|e| e.id),
+            Some(1)
+        );
+        assert!(reusable_entry(&entries, "ABC-3", &[]).is_none());
+    }
+
+    #[test]
+    fn test_reusable_entry_matches_the_whole_key() {
+        let entries = vec![entry(1, "PROJ-12 - Search", false)];
+        assert!(reusable_entry(&entries, "PROJ-1", &[]).is_none());
+        assert_eq!(
+            reusable_entry(&entries, "PROJ-12", &[]).map(|e| e.id),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_running_timer_for_matches_the_whole_key() {
+        let timer = entry(1, "PROJ-12 - Search", true);
+        assert!(running_timer_for(Some(&timer), "PROJ-1", &[]).is_none());
+        assert_eq!(
+            running_timer_for(Some(&timer), "PROJ-12", &[]).map(|e| e.id),
+            Some(1)
+        );
+    }
+
+    fn ticket(key: &str) -> models::Ticket {
+        models::Ticket {
+            key: key.to_string(),
+            summary: String::new(),
+            status: None,
+            confidence: None,
+            in_sprint: false,
+            estimate: None,
+        }
+    }
+
+    #[test]
+    fn test_sprint_first() {
+        let tickets = vec![
+            ticket("ABC-1"),
+            ticket("ABC-2"),
+            ticket("ABC-3"),
+            ticket("ABC-4"),
+        ];
+        let sprint = vec![ticket("ABC-4"), ticket("ABC-2"), ticket("ABC-9")];
+
+        let sorted = sprint_first(tickets, &sprint);
+        let keys: Vec<&str> = sorted.iter().map(|t| t.key.as_str()).collect();
+        assert_eq!(keys, vec!["ABC-2", "ABC-4", "ABC-1", "ABC-3"]);
+        assert!(sorted[0].in_sprint && sorted[1].in_sprint);
+        assert!(!sorted[2].in_sprint);
+    }
+}
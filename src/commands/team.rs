@@ -0,0 +1,169 @@
+use super::Session;
+use crate::models::{HarvestUser, UserTimeEntry};
+use crate::time_parser::format_hours;
+use crate::*;
+use std::collections::HashMap;
+
+/// Show which teammates have a timer running
+///
+/// Needs an administrator token, or a manager's for the people they manage.
+pub fn run_team_status(session: &Session) -> Result<()> {
+    let harvest_client = session.harvest()?;
+    let users = harvest_client.get_users()?;
+    let mut running =
+        harvest_client.get_team_time_entries(&[("is_running", "true".to_string())])?;
+    running.sort_by(|a, b| a.user.name.cmp(&b.user.name));
+
+    if running.is_empty() {
+        outln!("\nNo timers running ({} teammates)", users.len());
+        return Ok(());
+    }
+
+    outln!("\nRunning timers:");
+    for UserTimeEntry { user, entry } in &running {
+        let project = entry
+            .project
+            .as_ref()
+            .map(|p| p.name.as_str())
+            .unwrap_or("No project");
+        outln!(
+            "  {:<24} {:>6}  {} - {}",
+            user.name,
+            format_hours(entry.hours.unwrap_or(0.0)),
+            project,
+            entry.notes.as_deref().unwrap_or("(no notes)")
+        );
+    }
+
+    let idle = users
+        .iter()
+        .filter(|u| !running.iter().any(|e| e.user.id == u.id))
+        .count();
+    if idle > 0 {
+        outln!("\n{} teammates without a timer", idle);
+    }
+    Ok(())
+}
+
+/// Hours per teammate for today, or this week with `week`
+pub fn run_team_report(session: &Session, week: bool) -> Result<()> {
+    let today = chrono::Local::now().date_naive();
+    let from = if week {
+        today.week(chrono::Weekday::Mon).first_day()
+    } else {
+        today
+    };
+
+    let harvest_client = session.harvest()?;
+    let users = harvest_client.get_users()?;
+    let entries = harvest_client.get_team_time_entries(&[
+        ("from", from.format("%Y-%m-%d").to_string()),
+        ("to", today.format("%Y-%m-%d").to_string()),
+    ])?;
+    let totals = hours_per_user(&users, &entries);
+
+    if week {
+        outln!(
+            "\nTeam hours for {} - {}:",
+            from.format("%a %Y-%m-%d"),
+            today.format("%a %Y-%m-%d")
+        );
+    } else {
+        outln!("\nTeam hours for today ({}):", today.format("%Y-%m-%d"));
+    }
+    if totals.is_empty() {
+        outln!("  No teammates found");
+        return Ok(());
+    }
+
+    let max = totals.iter().map(|(_, hours)| *hours).fold(0.0, f64::max);
+    for (name, hours) in &totals {
+        outln!(
+            "  {:<24} {:>6}  {}",
+            name,
+            format_hours(*hours),
+            report::render_bar(*hours, max, 20)
+        );
+    }
+    let amount = report::billable_amount(entries.iter().map(|e| &e.entry))
+        .filter(|_| !session.config.settings.hide_money)
+        .map(|amount| format!(", {:.2} billable", amount))
+        .unwrap_or_default();
+    outln!(
+        "\nTotal: {}{}",
+        format_hours(totals.iter().map(|(_, hours)| hours).sum()),
+        amount
+    );
+    Ok(())
+}
+
+/// Hours per user, most first, including active users who logged nothing
+///
+/// Entries of users that are no longer active still count, under their name
+/// on the entry.
+fn hours_per_user(users: &[HarvestUser], entries: &[UserTimeEntry]) -> Vec<(String, f64)> {
+    let mut totals: HashMap<u64, (String, f64)> =
+        users.iter().map(|u| (u.id, (u.name(), 0.0))).collect();
+    for UserTimeEntry { user, entry } in entries {
+        totals
+            .entry(user.id)
+            .or_insert_with(|| (user.name.clone(), 0.0))
+            .1 += entry.hours.unwrap_or(0.0);
+    }
+
+    let mut totals: Vec<_> = totals.into_values().collect();
+    totals.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TimeEntry, UserInfo};
+
+    fn user(id: u64, first_name: &str) -> HarvestUser {
+        HarvestUser {
+            id,
+            timezone: None,
+            email: None,
+            first_name: first_name.to_string(),
+            last_name: "Jansen".to_string(),
+        }
+    }
+
+    fn entry(user_id: u64, name: &str, hours: f64) -> UserTimeEntry {
+        UserTimeEntry {
+            user: UserInfo {
+                id: user_id,
+                name: name.to_string(),
+            },
+            entry: TimeEntry {
+                id: 1,
+                spent_date: "2026-10-14".to_string(),
+                hours: Some(hours),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_hours_per_user() {
+        let users = [user(1, "Anna"), user(2, "Bram"), user(3, "Cas")];
+        let entries = [
+            entry(1, "Anna Jansen", 2.0),
+            entry(2, "Bram Jansen", 3.5),
+            entry(1, "Anna Jansen", 2.5),
+            entry(9, "Former Colleague", 1.0),
+        ];
+
+        assert_eq!(
+            hours_per_user(&users, &entries),
+            vec![
+                ("Anna Jansen".to_string(), 4.5),
+                ("Bram Jansen".to_string(), 3.5),
+                ("Former Colleague".to_string(), 1.0),
+                ("Cas Jansen".to_string(), 0.0),
+            ]
+        );
+    }
+}
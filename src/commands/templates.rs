@@ -0,0 +1,95 @@
+use super::favorites::start_timer;
+use super::log_time::ticket_key;
+use super::{or_queue, record_usage, Session};
+use crate::time_parser::format_hours;
+use crate::*;
+
+/// Create an entry from a `[templates]` entry, or list them without a name
+///
+/// Templates with hours (or `hours` given) log a stopped entry for today,
+/// the others start a timer.
+pub fn run_new(
+    ctx: models::Context,
+    session: &Session,
+    template: Option<String>,
+    ticket: Option<String>,
+    hours: Option<String>,
+) -> Result<()> {
+    let config = &session.config;
+    let Some(name) = template else {
+        list_templates(config);
+        return Ok(());
+    };
+    let template = config.templates.get(&name).ok_or_else(|| {
+        HarjiraError::Config(format!(
+            "No template named '{}'. Add one under [templates.{}] in the config",
+            name, name
+        ))
+    })?;
+
+    let ticket = ticket.as_deref().map(ticket_key).transpose()?;
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let notes = template
+        .render_notes(&today, ticket.as_deref())
+        .map_err(HarjiraError::InvalidEntry)?;
+    let (Some(project_id), Some(task_id)) = (
+        template.project_id.or(config.harvest.project_id),
+        template.task_id.or(config.harvest.task_id),
+    ) else {
+        return Err(HarjiraError::Config(format!(
+            "Template '{}' has no project and task. Set project_id and task_id in [templates.{}] or [harvest].",
+            name, name
+        )));
+    };
+    let hours = match hours {
+        Some(hours) => Some(time_parser::parse_hours(&hours)?),
+        None => template.hours,
+    };
+
+    let Some(hours) = hours else {
+        if start_timer(&ctx, session, &notes, project_id, task_id)? {
+            record_usage(&ctx, &[(project_id, task_id)])?;
+            if !ctx.quiet {
+                prompt::display_success(&format!("Started timer: {}", notes));
+            }
+        }
+        return Ok(());
+    };
+
+    let harvest_client = session.harvest()?;
+    let created = harvest_client
+        .create_stopped_time_entry_with_date(&notes, project_id, task_id, hours, &today, &ctx);
+    or_queue(
+        harvest_client,
+        created,
+        offline::Operation::Create {
+            spent_date: today,
+            project_id,
+            task_id,
+            notes: notes.clone(),
+            hours: Some(hours),
+            entry_id: None,
+        },
+    )?;
+    record_usage(&ctx, &[(project_id, task_id)])?;
+
+    if !ctx.quiet {
+        prompt::display_success(&format!("Logged {} on {}", format_hours(hours), notes));
+    }
+    Ok(())
+}
+
+fn list_templates(config: &Config) {
+    if config.templates.is_empty() {
+        prompt::display_info("No templates yet. Add one under [templates.NAME] in the config");
+        return;
+    }
+
+    outln!("\nTemplates:");
+    for (name, template) in &config.templates {
+        match template.hours {
+            Some(hours) => outln!("  {} - {} ({})", name, template.notes, format_hours(hours)),
+            None => outln!("  {} - {} (timer)", name, template.notes),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use super::Session;
+use crate::journal::{Change, Journal};
+use crate::models::UpdateTimeEntryRequest;
+use crate::progress::count_label;
+use crate::*;
+
+pub fn run_undo(ctx: models::Context, session: &Session, yes: bool) -> Result<()> {
+    let mut journal = Journal::load()?;
+    let Some(batch) = journal.batches.last().cloned() else {
+        prompt::display_info("Nothing to undo");
+        return Ok(());
+    };
+
+    let account_id = &session.config.harvest.account_id;
+    if batch.account_id != *account_id {
+        return Err(HarjiraError::Config(format!(
+            "The last change was made in Harvest account {}, run undo with --account for it",
+            batch.account_id
+        )));
+    }
+
+    let confirmed = yes || prompt::confirm_undo(&batch)?;
+    if !confirmed {
+        return Err(HarjiraError::UserCancelled);
+    }
+
+    let harvest_client = session.harvest()?;
+    let mut failed = Vec::new();
+
+    // Newest change first, so e.g. a timer started after stopping another is gone
+    // before the old one runs again
+    for change in batch.changes.iter().rev() {
+        if let Err(e) = reverse(harvest_client, change, &ctx) {
+            prompt::display_warning(&format!("{} failed: {}", change.undo_label(), e));
+            failed.insert(0, change.clone());
+        }
+    }
+
+    // What undo itself changed isn't something to undo later
+    journal::skip_undo();
+    if ctx.dry_run {
+        return Ok(());
+    }
+
+    let undone = batch.changes.len() - failed.len();
+    journal.batches.pop();
+    if !failed.is_empty() {
+        // Keep what couldn't be undone so it can be retried
+        journal.batches.push(journal::Batch {
+            changes: failed,
+            ..batch.clone()
+        });
+    }
+    journal.save()?;
+
+    if !ctx.quiet {
+        prompt::display_success(&format!(
+            "Undid {} from harv {}",
+            count_label(undone, "change", "changes"),
+            batch.command
+        ));
+    }
+    Ok(())
+}
+
+fn reverse(harvest_client: &HarvestClient, change: &Change, ctx: &models::Context) -> Result<()> {
+    match change {
+        Change::Created { entry_id } => harvest_client.delete_time_entry(*entry_id, ctx),
+        Change::Stopped { entry_id } => harvest_client
+            .restart_time_entry(*entry_id, ctx)
+            .map(|_| ()),
+        Change::Restarted { entry_id } => {
+            harvest_client.stop_time_entry(*entry_id, ctx).map(|_| ())
+        }
+        Change::Updated {
+            entry_id,
+            previous: Some(previous),
+        } => {
+            let update = UpdateTimeEntryRequest {
+                hours: previous.hours,
+                notes: previous.notes.clone(),
+            };
+            harvest_client
+                .update_time_entry(*entry_id, &update, ctx)
+                .map(|_| ())
+        }
+        Change::Deleted {
+            previous: Some(previous),
+            ..
+        } => {
+            let (Some(project), Some(task)) = (&previous.project, &previous.task) else {
+                return Err(HarjiraError::Harvest(
+                    "The deleted entry had no project or task".to_string(),
+                ));
+            };
+            harvest_client
+                .create_stopped_time_entry_with_date(
+                    previous.notes.as_deref().unwrap_or(""),
+                    project.id,
+                    task.id,
+                    previous.hours.unwrap_or(0.0),
+                    &previous.spent_date,
+                    ctx,
+                )
+                .map(|_| ())
+        }
+        Change::Updated { .. } | Change::Deleted { .. } => Err(HarjiraError::Harvest(format!(
+            "The journal has no copy of entry {} from before the change",
+            change.entry_id()
+        ))),
+    }
+}
@@ -1,49 +1,810 @@
+use crate::config_schema;
 use crate::error::{HarjiraError, Result};
+use crate::outln;
+use crate::time_parser::TimeDisplay;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Name of the top-level `[harvest]` account when listing all accounts
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// Per-repository overrides, looked up in the root of the current repository
+pub const LOCAL_CONFIG_FILE: &str = ".harv.toml";
+
+/// What a `.harv.toml` may set, as a section and optionally one key in it
+const LOCAL_CONFIG_KEYS: &[(&str, Option<&str>)] = &[
+    ("harvest", Some("project_id")),
+    ("harvest", Some("task_id")),
+    ("mapping", None),
+    ("scope_mapping", None),
+    ("ticket_filter", None),
+    ("templates", None),
+];
+
+/// Account picked with `--account`, applied whenever the config is loaded
+static SELECTED_ACCOUNT: OnceLock<String> = OnceLock::new();
+
+/// Use the `[harvest.accounts.<name>]` account for this run
+pub fn select_account(name: &str) {
+    let _ = SELECTED_ACCOUNT.set(name.to_string());
+}
+
+/// Config file given with `--config`, taking precedence over `HARV_CONFIG`
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Environment variable naming the config file to use
+pub const CONFIG_ENV: &str = "HARV_CONFIG";
+
+/// Use `path` as the config file for this run
+pub fn use_config_file(path: &str) {
+    let _ = CONFIG_FILE.set(absolute(path));
+}
+
+/// `path` with `~` expanded, relative to the current directory
+fn absolute(path: &str) -> PathBuf {
+    let path = crate::logging::expand_home(path);
+    match env::current_dir() {
+        Ok(dir) if path.is_relative() => dir.join(path),
+        _ => path,
+    }
+}
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
+    /// Format of the file, see `config_schema`
+    #[serde(default = "default_config_version")]
+    pub version: u32,
+    /// Required unless another backend is selected
+    #[serde(default)]
     pub harvest: HarvestConfig,
+    /// Required unless another tracker is selected
+    #[serde(default)]
     pub jira: JiraConfig,
     #[serde(default)]
+    pub tracker: TrackerConfig,
+    #[serde(default)]
+    pub linear: LinearConfig,
+    #[serde(default)]
+    pub azure_devops: AzureDevOpsConfig,
+    #[serde(default)]
+    pub github: GitHubConfig,
+    #[serde(default)]
+    pub backend: BackendConfig,
+    #[serde(default)]
+    pub tempo: TempoConfig,
+    #[serde(default)]
+    pub toggl: TogglConfig,
+    #[serde(default)]
     pub git: GitConfig,
     #[serde(default)]
     pub settings: Settings,
     #[serde(default)]
     pub ticket_filter: TicketFilterConfig,
     #[serde(default)]
+    pub mapping: MappingConfig,
+    /// Project and task per conventional-commit scope, for commits without a ticket
+    #[serde(default)]
+    pub scope_mapping: BTreeMap<String, ProjectTask>,
+    /// Reusable entries for `harv new --template NAME`
+    #[serde(default)]
+    pub templates: BTreeMap<String, EntryTemplate>,
+    /// Project and task by a short name, for `--alias`
+    #[serde(default)]
+    pub aliases: BTreeMap<String, ProjectAlias>,
+    #[serde(default)]
+    pub leave: LeaveConfig,
+    #[serde(default)]
+    pub capacity: CapacityConfig,
+    #[serde(default)]
+    pub invoice: InvoiceConfig,
+    #[serde(default)]
     pub ai: AiConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub pomodoro: PomodoroConfig,
+    #[serde(default)]
+    pub auto_log: AutoLogConfig,
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    /// The repository's `.harv.toml` applied over this config, if any
+    #[serde(skip)]
+    pub local_config: Option<PathBuf>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct HarvestConfig {
     pub access_token: String,
     pub account_id: String,
     pub user_agent: String,
     pub project_id: Option<u64>,
     pub task_id: Option<u64>,
+    /// Further accounts, picked with `--account <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub accounts: BTreeMap<String, HarvestAccountConfig>,
 }
 
+/// An extra Harvest account; unset fields fall back to the `[harvest]` values
 #[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct HarvestAccountConfig {
+    pub account_id: String,
+    #[serde(default)]
+    pub access_token: Option<String>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<u64>,
+    #[serde(default)]
+    pub task_id: Option<u64>,
+}
+
+impl HarvestConfig {
+    /// Settings for one account, "default" being the top-level `[harvest]` one
+    pub fn for_account(&self, name: &str) -> Result<HarvestConfig> {
+        if name == DEFAULT_ACCOUNT {
+            return Ok(HarvestConfig {
+                accounts: BTreeMap::new(),
+                ..self.clone()
+            });
+        }
+
+        let account = self.accounts.get(name).ok_or_else(|| {
+            let known: Vec<&str> = std::iter::once(DEFAULT_ACCOUNT)
+                .chain(self.accounts.keys().map(String::as_str))
+                .collect();
+            HarjiraError::Config(format!(
+                "Unknown Harvest account '{}'. Configured: {}",
+                name,
+                known.join(", ")
+            ))
+        })?;
+
+        Ok(HarvestConfig {
+            access_token: account
+                .access_token
+                .clone()
+                .unwrap_or_else(|| self.access_token.clone()),
+            account_id: account.account_id.clone(),
+            user_agent: account
+                .user_agent
+                .clone()
+                .unwrap_or_else(|| self.user_agent.clone()),
+            project_id: account.project_id,
+            task_id: account.task_id,
+            accounts: BTreeMap::new(),
+        })
+    }
+
+    /// Every configured account by name, the top-level one first
+    pub fn all_accounts(&self) -> Result<Vec<(String, HarvestConfig)>> {
+        std::iter::once(DEFAULT_ACCOUNT)
+            .chain(self.accounts.keys().map(String::as_str))
+            .map(|name| Ok((name.to_string(), self.for_account(name)?)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct JiraConfig {
+    #[serde(default)]
     pub access_token: String,
+    #[serde(default)]
     pub base_url: String,
+    /// OAuth (3LO) app credentials used by `harv login jira`
+    #[serde(default)]
+    pub oauth: Option<JiraOAuthConfig>,
+    /// Scrum board whose active sprint is shown first in sync and given to the AI
+    #[serde(default)]
+    pub board_id: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JiraOAuthConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    /// Local port for the OAuth redirect (must match the app's callback URL)
+    #[serde(default = "default_redirect_port")]
+    pub redirect_port: u16,
+}
+
+fn default_redirect_port() -> u16 {
+    8739
+}
+
+/// Time-tracking service that timers and entries go to
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BackendConfig {
+    /// "harvest", "tempo" or "toggl"
+    #[serde(default = "default_backend_provider")]
+    pub provider: String,
+}
+
+fn default_backend_provider() -> String {
+    "harvest".to_string()
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        Self {
+            provider: default_backend_provider(),
+        }
+    }
+}
+
+impl BackendConfig {
+    pub fn is_harvest(&self) -> bool {
+        self.provider.eq_ignore_ascii_case("harvest")
+    }
+
+    /// Service name for messages, e.g. "Harvest"
+    pub fn name(&self) -> &'static str {
+        match self.provider.to_lowercase().as_str() {
+            "tempo" => "Tempo",
+            "toggl" => "Toggl Track",
+            _ => "Harvest",
+        }
+    }
+}
+
+/// Tempo Timesheets credentials, used with `[backend] provider = "tempo"`
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TempoConfig {
+    #[serde(default)]
+    pub access_token: String,
+    /// Atlassian account ID the worklogs are logged for
+    #[serde(default)]
+    pub account_id: String,
+    /// API root, defaults to https://api.tempo.io/4
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// Toggl Track credentials, used with `[backend] provider = "toggl"`
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TogglConfig {
+    #[serde(default)]
+    pub api_token: String,
+    /// Workspace timers are created in
+    #[serde(default)]
+    pub workspace_id: u64,
+    /// Optional project for new timers
+    #[serde(default)]
+    pub project_id: Option<u64>,
+    /// API root, defaults to https://api.track.toggl.com/api/v9
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// Issue tracker that ticket keys are looked up in
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TrackerConfig {
+    /// "jira", "linear", "azure_devops" or "github"
+    #[serde(default = "default_tracker_provider")]
+    pub provider: String,
+    /// Provider per repository path, for repositories using another tracker
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub repositories: BTreeMap<String, String>,
+}
+
+/// Issue tracker providers harv can look tickets up in
+pub const TRACKER_PROVIDERS: &[&str] = &["jira", "linear", "azure_devops", "github"];
+
+fn default_tracker_provider() -> String {
+    "jira".to_string()
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        Self {
+            provider: default_tracker_provider(),
+            repositories: BTreeMap::new(),
+        }
+    }
+}
+
+impl TrackerConfig {
+    pub fn is_jira(&self) -> bool {
+        self.provider.eq_ignore_ascii_case("jira")
+    }
+
+    /// Tracker name for messages, e.g. "Jira"
+    pub fn name(&self) -> &'static str {
+        tracker_name(&self.provider)
+    }
+
+    /// Provider for the repository at `repo_path`, the default one unless listed
+    pub fn provider_for(&self, repo_path: &str) -> &str {
+        let wanted = repo_path.trim_end_matches('/');
+        self.repositories
+            .iter()
+            .find(|(path, _)| path.trim_end_matches('/') == wanted)
+            .map_or(self.provider.as_str(), |(_, provider)| provider.as_str())
+    }
+
+    /// Every provider in use, the default one first
+    pub fn providers(&self) -> Vec<String> {
+        let mut providers = vec![self.provider.to_lowercase()];
+        for provider in self.repositories.values() {
+            let provider = provider.to_lowercase();
+            if !providers.contains(&provider) {
+                providers.push(provider);
+            }
+        }
+        providers
+    }
+}
+
+/// Name of a tracker provider for messages, e.g. "Jira" for "jira"
+pub fn tracker_name(provider: &str) -> &'static str {
+    match provider.to_lowercase().as_str() {
+        "linear" => "Linear",
+        "azure_devops" => "Azure DevOps",
+        "github" => "GitHub",
+        _ => "Jira",
+    }
+}
+
+/// Linear credentials, used with `[tracker] provider = "linear"`
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LinearConfig {
+    /// Personal API key from Settings > Security & access
+    #[serde(default)]
+    pub api_key: String,
+    /// Workspace URL key, the "acme" in linear.app/acme, for issue links
+    #[serde(default)]
+    pub workspace: String,
+    /// GraphQL endpoint, defaults to https://api.linear.app/graphql
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// Azure DevOps (Boards) credentials, used with `provider = "azure_devops"`
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct AzureDevOpsConfig {
+    /// Organization, the "acme" in dev.azure.com/acme
+    #[serde(default)]
+    pub organization: String,
+    /// Project the work items belong to
+    #[serde(default)]
+    pub project: String,
+    /// Personal access token with the Work Items (Read) scope
+    #[serde(default)]
+    pub access_token: String,
+    /// Collection URL for Azure DevOps Server, defaults to https://dev.azure.com/{organization}
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// GitHub Issues access, used with `provider = "github"`
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct GitHubConfig {
+    /// Token with read access to issues, optional for public repositories
+    #[serde(default)]
+    pub access_token: String,
+    /// "owner/repo" that bare #123 mentions refer to when the origin remote isn't on GitHub
+    #[serde(default)]
+    pub repository: Option<String>,
+    /// API root for GitHub Enterprise Server, defaults to https://api.github.com
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// Settings for the HTTP client shared by Harvest, Jira and the AI providers
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct HttpConfig {
+    /// Seconds a whole request may take, 0 to wait forever
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Seconds to wait for a connection to be established, 0 to wait forever
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// Proxy URL for all requests, e.g. "http://proxy.example.com:8080"
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Extra PEM root certificate to trust, e.g. for a TLS-inspecting proxy
+    #[serde(default)]
+    pub ca_certificate: Option<String>,
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_timeout_secs(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            proxy: None,
+            ca_certificate: None,
+        }
+    }
+}
+
+/// Block lengths for `harv pomodoro`, in minutes
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PomodoroConfig {
+    #[serde(default = "default_work_minutes")]
+    pub work_minutes: u32,
+    #[serde(default = "default_break_minutes")]
+    pub break_minutes: u32,
+    #[serde(default = "default_long_break_minutes")]
+    pub long_break_minutes: u32,
+    /// Take the long break after this many pomodoros, 0 never does
+    #[serde(default = "default_long_break_every")]
+    pub long_break_every: u32,
+}
+
+fn default_work_minutes() -> u32 {
+    25
+}
+
+fn default_break_minutes() -> u32 {
+    5
+}
+
+fn default_long_break_minutes() -> u32 {
+    15
+}
+
+fn default_long_break_every() -> u32 {
+    4
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        Self {
+            work_minutes: default_work_minutes(),
+            break_minutes: default_break_minutes(),
+            long_break_minutes: default_long_break_minutes(),
+            long_break_every: default_long_break_every(),
+        }
+    }
+}
+
+/// Coding sessions recorded by `harv sync` for `harv autolog`
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AutoLogConfig {
+    /// Record git activity on every sync
+    #[serde(default)]
+    pub enabled: bool,
+    /// A quiet stretch longer than this ends a session
+    #[serde(default = "default_gap_minutes")]
+    pub gap_minutes: u32,
+    /// Time before a session's first activity counted as part of it
+    #[serde(default = "default_lead_minutes")]
+    pub lead_minutes: u32,
+}
+
+fn default_gap_minutes() -> u32 {
+    30
+}
+
+fn default_lead_minutes() -> u32 {
+    15
+}
+
+impl Default for AutoLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            gap_minutes: default_gap_minutes(),
+            lead_minutes: default_lead_minutes(),
+        }
+    }
+}
+
+fn default_config_version() -> u32 {
+    1
+}
+
+/// Tokens encrypted by `harv config encrypt`
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SecretsConfig {
+    /// "gpg" or "age"
+    #[serde(default = "default_secrets_tool")]
+    pub tool: String,
+    /// Key to encrypt to, a passphrase is asked for without one
+    #[serde(default)]
+    pub recipient: Option<String>,
+    /// age identity file to decrypt with
+    #[serde(default)]
+    pub identity: Option<String>,
+    /// ASCII-armored tokens, as a TOML table per section
+    #[serde(default)]
+    pub encrypted: Option<String>,
+}
+
+fn default_secrets_tool() -> String {
+    "gpg".to_string()
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            tool: default_secrets_tool(),
+            recipient: None,
+            identity: None,
+            encrypted: None,
+        }
+    }
+}
+
+/// Log file written next to the console output, whatever its verbosity
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LoggingConfig {
+    /// Path of the log file, "~/" is the home directory. Unset writes no file.
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Level for harv's own messages: error, warn, info, debug or trace
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// The file is moved aside once it grows past this many megabytes
+    #[serde(default = "default_log_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Moved-aside files older than this many days are deleted, 0 keeps them
+    #[serde(default = "default_log_keep_days")]
+    pub keep_days: u32,
+}
+
+fn default_log_level() -> String {
+    "debug".to_string()
+}
+
+fn default_log_max_size_mb() -> u64 {
+    10
+}
+
+fn default_log_keep_days() -> u32 {
+    14
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            level: default_log_level(),
+            max_size_mb: default_log_max_size_mb(),
+            keep_days: default_log_keep_days(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct GitConfig {
     #[serde(default)]
     pub repositories: Vec<String>,
+    /// Leave out merge commits
+    #[serde(default)]
+    pub skip_merges: bool,
+    /// Leave out `git revert` and conventional `revert:` commits
+    #[serde(default)]
+    pub skip_reverts: bool,
+    /// Leave out commits whose subject matches one of these regexes, ignoring case
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+impl GitConfig {
+    /// The commits to leave out of scans
+    pub fn commit_filter(&self) -> Result<crate::git::CommitFilter> {
+        let ignore = self
+            .ignore_patterns
+            .iter()
+            .map(|pattern| {
+                regex::RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| {
+                        HarjiraError::Config(format!(
+                            "Invalid git.ignore_patterns entry '{}': {}",
+                            pattern, e
+                        ))
+                    })
+            })
+            .collect::<Result<_>>()?;
+        Ok(crate::git::CommitFilter {
+            skip_merges: self.skip_merges,
+            skip_reverts: self.skip_reverts,
+            ignore,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct TicketFilterConfig {
     /// List of ticket prefixes to ignore (e.g., ["CWE", "CVE"])
     #[serde(default)]
     pub denylist: Vec<String>,
+    /// Trailer keys and body keywords that name tickets, e.g. "Refs" for `Refs: PROJ-123`
+    #[serde(default = "default_trailer_keys")]
+    pub trailer_keys: Vec<String>,
+    /// Where tickets are looked for: "message" or "subject_and_trailers"
+    #[serde(default = "default_ticket_search")]
+    pub search: String,
+    /// Regex for ticket keys the built-in `PROJ-123` one misses, e.g. `ABC_123`
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Only use `pattern`, leaving out `PROJ-123` keys
+    #[serde(default)]
+    pub replace_default: bool,
+}
+
+impl Default for TicketFilterConfig {
+    fn default() -> Self {
+        Self {
+            denylist: Vec::new(),
+            trailer_keys: default_trailer_keys(),
+            search: default_ticket_search(),
+            pattern: None,
+            replace_default: false,
+        }
+    }
+}
+
+impl TicketFilterConfig {
+    /// Whether the whole commit message is searched, not just the subject and trailers
+    pub fn searches_body(&self) -> bool {
+        !self.search.eq_ignore_ascii_case("subject_and_trailers")
+    }
+}
+
+/// Harvest project and task to book work on
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct ProjectTask {
+    pub project_id: u64,
+    pub task_id: u64,
+}
+
+/// Harvest project and task by name, e.g. `plat = { project = "Platform", task = "Development" }`
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ProjectAlias {
+    /// Project name or code
+    pub project: String,
+    pub task: String,
+}
+
+/// An entry to create again and again, e.g. the daily standup
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct EntryTemplate {
+    /// Defaults to [harvest] project_id and task_id
+    #[serde(default)]
+    pub project_id: Option<u64>,
+    #[serde(default)]
+    pub task_id: Option<u64>,
+    /// `{date}` is replaced with the entry's date, `{ticket}` with the ticket key
+    pub notes: String,
+    /// Log a stopped entry of this length; without it a timer is started
+    #[serde(default)]
+    pub hours: Option<f64>,
+}
+
+impl EntryTemplate {
+    /// Notes with the placeholders filled in, an error if a ticket is needed but missing
+    pub fn render_notes(
+        &self,
+        date: &str,
+        ticket: Option<&str>,
+    ) -> std::result::Result<String, String> {
+        let notes = self.notes.replace("{date}", date);
+        if !notes.contains("{ticket}") {
+            return Ok(notes);
+        }
+        match ticket {
+            Some(ticket) => Ok(notes.replace("{ticket}", ticket)),
+            None => Err("The notes have a {ticket} placeholder, pass --ticket".to_string()),
+        }
+    }
+}
+
+/// Hours per day where they differ from `ai.target_hours`
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CapacityConfig {
+    /// Hours per weekday ("friday" = 4.0), for part-time schedules
+    #[serde(default)]
+    pub weekdays: BTreeMap<String, f64>,
+    /// Hours on a date ("2026-07-03" = 4.0), taking precedence over the weekday
+    #[serde(default)]
+    pub dates: BTreeMap<String, f64>,
+}
+
+/// Hourly rates for `harv invoice draft`
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct InvoiceConfig {
+    /// Rate for projects without one of their own, before Harvest's billable rate
+    #[serde(default)]
+    pub hourly_rate: Option<f64>,
+    /// Rate per project name or code ("Website Redesign" = 95.0)
+    #[serde(default)]
+    pub rates: BTreeMap<String, f64>,
+}
+
+impl InvoiceConfig {
+    /// The configured rate for a project, by name or code (case-insensitive)
+    pub fn rate_for(&self, project: &str, code: Option<&str>) -> Option<f64> {
+        self.rates
+            .iter()
+            .find(|(name, _)| {
+                name.eq_ignore_ascii_case(project)
+                    || code.is_some_and(|code| name.eq_ignore_ascii_case(code))
+            })
+            .map(|(_, rate)| *rate)
+            .or(self.hourly_rate)
+    }
+}
+
+/// Where `harv leave` logs days off
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LeaveConfig {
+    #[serde(default)]
+    pub project_id: Option<u64>,
+    #[serde(default)]
+    pub task_id: Option<u64>,
+    /// Project and task per leave type ("vacation", "sick") instead of the above
+    #[serde(default)]
+    pub types: BTreeMap<String, ProjectTask>,
+}
+
+impl LeaveConfig {
+    /// Project and task for a leave type, its own mapping taking precedence
+    pub fn for_type(&self, kind: &str) -> Option<ProjectTask> {
+        let own = self
+            .types
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(kind))
+            .map(|(_, target)| *target);
+        own.or(match (self.project_id, self.task_id) {
+            (Some(project_id), Some(task_id)) => Some(ProjectTask {
+                project_id,
+                task_id,
+            }),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct MappingConfig {
+    /// Project and task per ticket prefix ("PROJ") or full ticket key ("PROJ-123")
+    #[serde(default)]
+    pub tickets: BTreeMap<String, ProjectTask>,
+    /// Project and task per repository path, for repositories without a tracker
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub repositories: BTreeMap<String, ProjectTask>,
+}
+
+impl MappingConfig {
+    /// Mapping for a ticket, an exact key taking precedence over its prefix
+    pub fn for_ticket(&self, ticket_key: &str) -> Option<ProjectTask> {
+        let prefix = ticket_key.split('-').next().unwrap_or(ticket_key);
+        let find = |wanted: &str| {
+            self.tickets
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(wanted))
+                .map(|(_, target)| *target)
+        };
+        find(ticket_key).or_else(|| find(prefix))
+    }
+
+    /// Mapping for the repository at `repo_path`, ignoring a trailing slash
+    pub fn for_repository(&self, repo_path: &str) -> Option<ProjectTask> {
+        let wanted = repo_path.trim_end_matches('/');
+        self.repositories
+            .iter()
+            .find(|(path, _)| path.trim_end_matches('/') == wanted)
+            .map(|(_, target)| *target)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -66,6 +827,50 @@ pub struct AiConfig {
     /// Target hours per day for time entry generation
     #[serde(default = "default_target_hours")]
     pub target_hours: f64,
+
+    /// Stream responses to show progress while generating
+    #[serde(default)]
+    pub stream: bool,
+
+    /// When commits have no ticket key, ask the AI to match them to your Jira issues
+    #[serde(default)]
+    pub infer_tickets: bool,
+
+    /// Send the description and latest comments of mentioned Jira issues to the AI
+    #[serde(default)]
+    pub ticket_details: bool,
+
+    /// Characters of description and comments sent per issue
+    #[serde(default = "default_ticket_details_max_chars")]
+    pub ticket_details_max_chars: usize,
+
+    /// How often to re-ask the model when its reply is invalid
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// Entries at or above this confidence are pre-approved; below it they need review
+    #[serde(default)]
+    pub auto_approve_confidence: Option<f64>,
+
+    /// Estimated monthly spend (USD) after which `generate` needs `--force`
+    #[serde(default)]
+    pub monthly_cost_cap: Option<f64>,
+
+    /// Round rebalanced hours to this many minutes (e.g. 15)
+    #[serde(default)]
+    pub rounding_minutes: Option<u32>,
+
+    /// Project for filler entries that top up the day to the target
+    #[serde(default)]
+    pub filler_project_id: Option<u64>,
+
+    /// Task for filler entries
+    #[serde(default)]
+    pub filler_task_id: Option<u64>,
+
+    /// Notes for filler entries (default: "General")
+    #[serde(default)]
+    pub filler_notes: Option<String>,
 }
 
 fn default_provider() -> String {
@@ -76,6 +881,14 @@ fn default_target_hours() -> f64 {
     8.0
 }
 
+fn default_max_retries() -> u32 {
+    2
+}
+
+fn default_ticket_details_max_chars() -> usize {
+    1500
+}
+
 impl Default for AiConfig {
     fn default() -> Self {
         Self {
@@ -84,6 +897,17 @@ impl Default for AiConfig {
             api_key: String::new(),
             model: None,
             target_hours: default_target_hours(),
+            stream: false,
+            infer_tickets: false,
+            ticket_details: false,
+            ticket_details_max_chars: default_ticket_details_max_chars(),
+            max_retries: default_max_retries(),
+            auto_approve_confidence: None,
+            monthly_cost_cap: None,
+            rounding_minutes: None,
+            filler_project_id: None,
+            filler_task_id: None,
+            filler_notes: None,
         }
     }
 }
@@ -100,128 +924,170 @@ pub struct Settings {
     pub continue_days: Option<u8>,
     #[serde(default)]
     pub continue_mode: Option<String>,
+    #[serde(default)]
+    pub status_format: Option<String>,
+    #[serde(default)]
+    pub status_idle_format: Option<String>,
+    /// End of the working day ("HH:MM"), timers still running after it are stopped
+    #[serde(default)]
+    pub auto_stop_at: Option<String>,
+    /// Take the time after `auto_stop_at` off the stopped timer
+    #[serde(default = "default_true")]
+    pub auto_stop_trim: bool,
+    /// Show hours as decimals (1.75h) or clock time (1:45)
+    #[serde(default)]
+    pub time_display: TimeDisplay,
+    /// Timezone that decides what "today" is: an IANA or Harvest name, or
+    /// "local" for the machine's timezone. Defaults to the Harvest profile.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Restart today's entry for a ticket on repeated syncs instead of adding a new one
+    #[serde(default)]
+    pub reuse_entry_same_day: bool,
+    /// `harv stop` offers to delete timers that ran for less than this many minutes
+    #[serde(default)]
+    pub min_entry_minutes: Option<u32>,
+    /// Without tickets in today's commits, `harv sync` starts a timer named
+    /// after the repository and branch on the `[harvest]` project and task
+    #[serde(default)]
+    pub fallback_to_branch: bool,
+    /// Without commits today, `harv sync` offers a timer for the ticket in the
+    /// branch name once this many lines are changed but not committed, 0 turns it off
+    #[serde(default = "default_wip_min_lines")]
+    pub wip_min_lines: usize,
+    /// Leave out the money next to hours, shown when the token can see rates
+    #[serde(default)]
+    pub hide_money: bool,
 }
 
-fn default_true() -> bool {
-    true
-}
-
-impl Default for Settings {
-    fn default() -> Self {
-        Self {
-            auto_start: false,
-            auto_stop: false,
-            auto_select_single: true,
-            continue_days: None,
-            continue_mode: None,
-        }
-    }
-}
-
-impl Config {
-    /// Load configuration from file or create template
-    pub fn load() -> Result<Self> {
-        // Attempt to migrate from old harjira config if needed
-        Self::migrate_from_harjira()?;
-
-        let config_path = Self::config_path()?;
-
-        if !config_path.exists() {
-            return Err(HarjiraError::Config(format!(
-                "Configuration file not found at {}. Run 'harv config init' to create one.",
-                config_path.display()
-            )));
-        }
-
-        let content = fs::read_to_string(&config_path)?;
-        let mut config: Config = toml::from_str(&content)?;
-
-        // Override with environment variables if present
-        config.apply_env_overrides();
-
-        // Validate configuration
-        config.validate()?;
-
-        Ok(config)
-    }
-
-    /// Get the default configuration file path
-    pub fn config_path() -> Result<PathBuf> {
-        let home = env::var("HOME").map_err(|_| {
-            HarjiraError::Config("HOME environment variable not set".to_string())
-        })?;
-
-        let config_dir = PathBuf::from(home).join(".config").join("harv");
-        Ok(config_dir.join("config.toml"))
-    }
-
-    /// Migrate from old harjira config directory to new harv directory
-    fn migrate_from_harjira() -> Result<()> {
-        let home = env::var("HOME").map_err(|_| {
-            HarjiraError::Config("HOME environment variable not set".to_string())
-        })?;
-
-        let old_config_dir = PathBuf::from(&home).join(".config").join("harjira");
-        let new_config_dir = PathBuf::from(&home).join(".config").join("harv");
-
-        // Only migrate if old directory exists and new one doesn't
-        if old_config_dir.exists() && !new_config_dir.exists() {
-            // Copy entire directory
-            if let Err(e) = copy_dir_all(&old_config_dir, &new_config_dir) {
-                // Non-fatal: warn and continue
-                eprintln!(
-                    "Warning: Failed to migrate config from {} to {}: {}",
-                    old_config_dir.display(),
-                    new_config_dir.display(),
-                    e
-                );
-            } else {
-                println!(
-                    "Migrated config from {} to {}",
-                    old_config_dir.display(),
-                    new_config_dir.display()
-                );
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Create a template configuration file
-    pub fn create_template() -> Result<()> {
-        let config_path = Self::config_path()?;
+fn default_true() -> bool {
+    true
+}
 
-        if config_path.exists() {
-            return Err(HarjiraError::Config(format!(
-                "Configuration file already exists at {}",
-                config_path.display()
-            )));
-        }
+fn default_wip_min_lines() -> usize {
+    20
+}
 
-        // Create directory if it doesn't exist
-        if let Some(parent) = config_path.parent() {
-            fs::create_dir_all(parent)?;
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            auto_start: false,
+            auto_stop: false,
+            auto_select_single: true,
+            continue_days: None,
+            continue_mode: None,
+            status_format: None,
+            status_idle_format: None,
+            auto_stop_at: None,
+            auto_stop_trim: true,
+            time_display: TimeDisplay::Decimal,
+            timezone: None,
+            reuse_entry_same_day: false,
+            min_entry_minutes: None,
+            fallback_to_branch: false,
+            wip_min_lines: default_wip_min_lines(),
+            hide_money: false,
         }
+    }
+}
 
-        let template = r#"# Harv Configuration File
+/// Commented configuration written by `harv config init`
+pub const TEMPLATE: &str = r#"# Harv Configuration File
 # See: https://help.getharvest.com/api-v2/ for Harvest API docs
 # See: https://developer.atlassian.com/cloud/jira/platform/rest/v3/ for Jira API docs
 
+# Format of this file, upgraded by harv when it changes
+version = 2
+
 [harvest]
 # Get your access token from: https://id.getharvest.com/developers
 access_token = "your_harvest_access_token_here"
 account_id = "your_account_id_here"
 user_agent = "harv (your.email@example.com)"
+# Or read the token from a password manager, first line of the output
+# access_token_cmd = "pass show work/harvest"
 
 # Optional: Default project and task IDs for time entries
 # Get these from: https://api.harvestapp.com/v2/projects
 # project_id = 12345678
 # task_id = 87654321
 
+# Optional: More Harvest accounts, used with `harv --account side ...`
+# The access token and user agent default to the ones above
+# [harvest.accounts.side]
+# account_id = "7654321"
+# access_token = "another_token"
+# project_id = 23456789
+# task_id = 98765432
+
+# Optional: Track time in Tempo Timesheets instead of Harvest. harv keeps the
+# running timer locally and logs a worklog on the ticket when it stops.
+# The [harvest] section can then be left out.
+# [backend]
+# provider = "tempo"
+#
+# [tempo]
+# Create a token under Tempo > Settings > API integration
+# access_token = "your_tempo_api_token"
+# Your Atlassian account ID, the last part of your Jira profile URL
+# account_id = "5b10ac8d82e05b22cc7d4ef5"
+
+# Or Toggl Track, with provider = "toggl"
+# [toggl]
+# Find your API token at https://track.toggl.com/profile
+# api_token = "your_toggl_api_token"
+# workspace_id = 1234567
+# project_id = 7654321
+
 [jira]
 # Create a Personal Access Token: https://id.atlassian.com/manage-profile/security/api-tokens
 access_token = "your_jira_personal_access_token_here"
 base_url = "https://your-company.atlassian.net"
+# Or: access_token_cmd = "op read op://Work/Jira/token"
+
+# Optional: Use OAuth instead of a personal access token (Jira Cloud only)
+# Create an OAuth 2.0 (3LO) app at https://developer.atlassian.com/console/myapps/
+# with callback URL http://localhost:8739/callback, then run 'harv login jira'
+# [jira.oauth]
+# client_id = "your_client_id"
+# client_secret = "your_client_secret"
+# redirect_port = 8739
+
+# Optional: Scrum board (the number in .../boards/42 URLs). Your issues in its
+# active sprint are listed first when picking a ticket during sync, and given
+# to the AI as likely work items by 'harv generate'
+# board_id = 42
+
+# Optional: Look tickets up in Linear instead of Jira. Keys like ENG-123 are
+# resolved through the Linear API, and the [jira] section can then be left out
+# (unless Tempo is the backend, which logs worklogs on Jira issues).
+# [tracker]
+# provider = "linear"
+#
+# [linear]
+# Create a personal API key under Settings > Security & access
+# api_key = "lin_api_your_key_here"
+# The workspace part of your Linear URLs, e.g. "acme" for linear.app/acme
+# workspace = "acme"
+
+# Or Azure DevOps work items, with provider = "azure_devops". Commits mention
+# them as AB#1234 or #1234.
+# [azure_devops]
+# organization = "acme"
+# project = "Web Shop"
+# Create a PAT with the Work Items (Read) scope under User settings > Personal access tokens
+# access_token = "your_azure_devops_pat"
+
+# Or GitHub Issues, with provider = "github". Commits mention them as #123,
+# meaning an issue in the repository's GitHub origin, or as org/repo#123.
+# [github]
+# A fine-grained token with Issues (Read); optional for public repositories
+# access_token = "github_pat_your_token"
+# repository = "acme/web"  # for #123 when the origin remote isn't on GitHub
+
+# Optional: Use another tracker for some repositories, by repository path
+# [tracker.repositories]
+# "/home/user/projects/legacy" = "azure_devops"
 
 [git]
 # Leave empty to use current working directory
@@ -232,6 +1098,11 @@ repositories = []
 #     "/home/user/projects/backend",
 #     "/home/user/projects/frontend"
 # ]
+# Leave noise out of the ticket picker and the AI: merge commits, reverts,
+# and commits whose subject matches a regex (case-insensitive)
+# skip_merges = false
+# skip_reverts = false
+# ignore_patterns = ["^chore:", "\\bwip\\b"]
 
 [settings]
 # Skip prompts and automatically start timers (useful for systemd timer)
@@ -252,10 +1123,123 @@ auto_select_single = true
 # - "ask": Prompt user each time (default)
 # continue_mode = "ask"
 
+# One-line format for `harv status --short` (for tmux/starship prompts)
+# Placeholders: {ticket}, {notes}, {project}, {task}, {hours}, {today}, {target}
+# status_format = "⏱ {ticket} {hours}h ({today}/{target}h)"
+# status_idle_format = "⊗ {today}/{target}h"
+
+# Stop any timer still running after this time when `harv sync` runs
+# (the systemd timer runs it hourly), and trim it back to this time
+# auto_stop_at = "18:30"
+# auto_stop_trim = true
+
+# Show hours as "decimal" (1.75h) or "clock" (1:45)
+# time_display = "decimal"
+
+# Timezone that decides what "today" is, for git commits and Harvest entries.
+# Defaults to the timezone in your Harvest profile. Use "local" for this
+# machine's timezone, or a name like "Europe/Amsterdam".
+# timezone = "Europe/Amsterdam"
+
+# When `harv sync` starts a ticket that already has a stopped entry today,
+# restart that entry (adding to its time) instead of creating a new one
+# reuse_entry_same_day = false
+
+# Optional: When `harv stop` stops a timer that ran for less than this many
+# minutes, offer to delete the entry instead (`harv stop --keep` skips this)
+# min_entry_minutes = 2
+
+# When today's commits have no ticket (and no mapped scope), start a timer
+# anyway with the repository and branch as notes, e.g. "shop: feature/cart",
+# on the project_id and task_id from [harvest]
+# fallback_to_branch = false
+
+# Without commits today, offer a timer for the ticket in the branch name
+# (e.g. feature/PROJ-123-login) once this many lines are changed but not
+# committed yet. 0 turns it off
+# wip_min_lines = 20
+
+# `today` and `team report` show what the hours are worth when the token can
+# see billable rates (administrators and managers). Hide it to see only hours
+# hide_money = false
+
 [ticket_filter]
 # Ignore specific ticket prefixes that match the pattern but aren't Jira tickets
 # Common examples: CWE (Common Weakness Enumeration), CVE (Common Vulnerabilities)
 denylist = ["CWE", "CVE"]
+# Where tickets are looked for in commit messages: "message" (all of it) or
+# "subject_and_trailers", the subject plus body lines starting with one of the
+# trailer_keys, such as `Refs: PROJ-123` or `Fixes PROJ-7`
+# search = "message"
+# trailer_keys = ["Refs", "Ticket", "Issue", "Fixes", "Closes", "Resolves"]
+# Extra regex for ticket keys that don't look like PROJ-123. The first capture
+# group that matched is the key, e.g. ABC_123 or PROJ/123 from #PROJ/123
+# pattern = '\b([A-Z]+_\d+)\b|#([A-Z]+/\d+)'
+# Only use the pattern above, not PROJ-123
+# replace_default = false
+
+# Optional: Harvest project and task per Jira project, used by `harv log`.
+# Keys are ticket prefixes or full ticket keys; the [harvest] defaults
+# apply to tickets without a mapping.
+# [mapping.tickets]
+# PROJ = { project_id = 12345678, task_id = 87654321 }
+# "PROJ-42" = { project_id = 12345678, task_id = 11223344 }
+
+# Optional: Repositories without a ticket tracker. When the latest commit
+# today is in one of these, `harv sync` skips ticket lookup and starts (or
+# continues) a Harvest timer on its project and task, noting the branch.
+# [mapping.repositories]
+# "/home/user/projects/intranet" = { project_id = 12345678, task_id = 87654321 }
+
+# Optional: When today's commits have no ticket key, `harv sync` reads
+# conventional-commit scopes (feat(auth): ...) and starts a Harvest timer on
+# the project and task mapped to the scope of the latest such commit
+# [scope_mapping]
+# auth = { project_id = 12345678, task_id = 87654321 }
+# ci = { project_id = 12345678, task_id = 11223344 }
+
+# Optional: Entries to create with `harv new --template NAME`. Notes can use
+# {date} and {ticket}. With hours a stopped entry is logged, without them a
+# timer is started. Project and task default to the [harvest] ones.
+# [templates.standup]
+# project_id = 12345678
+# task_id = 87654321
+# notes = "Standup {date}"
+# hours = 0.25
+#
+# [templates.review]
+# notes = "{ticket} - Code review"
+
+# Optional: Short names for a project and task, for `harv add --alias` and
+# `harv log --alias`. Projects match on name or code, tasks on name.
+# [aliases]
+# plat = { project = "Platform Maintenance", task = "Development" }
+
+# Optional: Hours per day where they differ from ai.target_hours, used by
+# `generate`, `today`, `status --short` and `leave`. `harv capacity set`
+# overrides single dates without editing this file.
+# [capacity.weekdays]
+# friday = 4.0
+#
+# [capacity.dates]
+# "2026-07-03" = 4.0
+
+# Optional: Hourly rates for `harv invoice draft`. Projects without a rate
+# here use hourly_rate, then the billable rate set in Harvest.
+# [invoice]
+# hourly_rate = 85.0
+#
+# [invoice.rates]
+# "Website Redesign" = 95.0
+
+# Optional: Project and task for `harv leave`, which logs a full day off
+# [leave]
+# project_id = 12345678
+# task_id = 99887766
+#
+# Other leave types can go to their own task
+# [leave.types]
+# sick = { project_id = 12345678, task_id = 99887700 }
 
 [ai]
 # Enable AI-powered time entry generation
@@ -268,15 +1252,348 @@ provider = "openai"
 # OpenAI: Get from https://platform.openai.com/api-keys
 # Anthropic: Get from https://console.anthropic.com/settings/keys
 api_key = ""
+# Or: api_key_cmd = "bw get password openai"
 
 # Optional: Specify model (defaults to provider's best model)
 # model = "gpt-4o"  # or "claude-3-5-sonnet-20241022"
 
 # Target hours per day (default: 8.0)
 target_hours = 8.0
+
+# Stream responses to show entries as they are generated
+# (use --verbose to see the raw output)
+stream = false
+
+# When today's commits have no Jira key, let the AI match them to your
+# assigned/recent Jira issues during sync (sends commit messages to the provider)
+infer_tickets = false
+
+# Give the AI the description and last few comments of the Jira issues a
+# work summary mentions (and of sprint issues) when generating entries.
+# Costs more tokens; each issue is cut off after ticket_details_max_chars.
+ticket_details = false
+ticket_details_max_chars = 1500
+
+# Re-ask the model this many times when it returns invalid JSON or unknown
+# project/task IDs (default: 2)
+max_retries = 2
+
+# Optional: Pre-select entries the AI is at least this confident about (0.0-1.0).
+# Less confident entries are listed first and always need review, and with
+# --auto-approve only the confident ones are created without asking
+# auto_approve_confidence = 0.9
+
+# Optional: Refuse to generate once this month's estimated spend (USD) reaches
+# this cap; pass --force to generate anyway
+# monthly_cost_cap = 5.0
+
+# Optional: Round hours to this many minutes when rebalancing reviewed entries
+# rounding_minutes = 15
+
+# Optional: Project and task for a filler entry that tops up the day to the
+# target during review
+# filler_project_id = 12345678
+# filler_task_id = 87654321
+# filler_notes = "General"
+
+[http]
+# Give up on a request after this many seconds (0 waits forever)
+timeout_secs = 60
+# Give up connecting after this many seconds (0 waits forever)
+connect_timeout_secs = 10
+
+# Optional: Send all requests through a proxy
+# (HTTP_PROXY/HTTPS_PROXY are used when this is not set)
+# proxy = "http://proxy.example.com:8080"
+
+# Optional: Extra root certificate (PEM) to trust for all API requests,
+# e.g. when a corporate proxy re-signs TLS traffic
+# ca_certificate = "/etc/ssl/certs/company-ca.pem"
+
+[logging]
+# Optional: Also write logs to this file as JSON lines, whatever -v says,
+# e.g. to see what the systemd timer did
+# file = "~/.local/state/harv/harv.log"
+# Level for the file: error, warn, info, debug or trace
+# level = "debug"
+# Start a new file past this size; older files are kept for keep_days (0 = forever)
+# max_size_mb = 10
+# keep_days = 14
+
+[pomodoro]
+# Block lengths for `harv pomodoro`, in minutes
+# work_minutes = 25
+# break_minutes = 5
+# long_break_minutes = 15
+# Take the long break after every this many pomodoros (0 = never)
+# long_break_every = 4
+
+[auto_log]
+# Record commits and staging in the repositories above on every sync, so
+# `harv autolog` can turn the day's coding sessions into entries to review
+# enabled = false
+# A quiet stretch longer than this ends a session, in minutes
+# gap_minutes = 30
+# Minutes before a session's first activity that count as part of it
+# lead_minutes = 15
+
+# [secrets]
+# Written by `harv config encrypt`, which moves the tokens above into an
+# encrypted blob here; harv decrypts it whenever it loads the config.
+# Encrypt with gpg (default) or age
+# tool = "gpg"
+# Key to encrypt to; without one a passphrase is asked for
+# recipient = "you@example.com"
+# age only: identity file to decrypt with
+# identity = "~/.config/age/key.txt"
 "#;
 
-        fs::write(&config_path, template)?;
+impl Config {
+    /// Load configuration from file or create template
+    pub fn load() -> Result<Self> {
+        let config = Self::load_unvalidated()?;
+
+        // Validate configuration
+        config.validate()?;
+
+        crate::time_parser::set_time_display(config.settings.time_display);
+        if let Some(pattern) = &config.ticket_filter.pattern {
+            crate::ticket_parser::set_pattern(pattern, config.ticket_filter.replace_default)?;
+        }
+        crate::http::configure(&config.http)?;
+        crate::logging::configure(&config.logging);
+
+        Ok(config)
+    }
+
+    /// Load configuration without validating it, for commands that fill in missing values
+    pub fn load_unvalidated() -> Result<Self> {
+        // Attempt to migrate from old harjira config if needed
+        if Self::explicit_path().is_none() {
+            Self::migrate_from_harjira()?;
+        }
+
+        let config_path = Self::config_path()?;
+
+        if !config_path.exists() {
+            return Err(HarjiraError::Config(format!(
+                "Configuration file not found at {}. Run 'harv config init' to create one.",
+                config_path.display()
+            )));
+        }
+
+        let mut content = fs::read_to_string(&config_path)?;
+        if let Some(upgrade) = config_schema::upgrade(&content)? {
+            content = Self::apply_upgrade(&config_path, upgrade)?;
+        }
+        let mut table: toml::Table = toml::from_str(&content)?;
+        crate::secrets::resolve_commands(&mut table)?;
+        crate::secrets::decrypt_into(&mut table)?;
+        let local_config = Self::apply_local_config(&mut table)?;
+        let mut config: Config = table.clone().try_into()?;
+        config.local_config = local_config;
+        config.warn_unknown_keys(&table);
+
+        // Override with environment variables if present
+        config.apply_env_overrides();
+
+        if let Some(name) = SELECTED_ACCOUNT.get() {
+            config.harvest = config.harvest.for_account(name)?;
+        }
+
+        Ok(config)
+    }
+
+    /// Write an upgraded config file, keeping the old one next to it
+    fn apply_upgrade(config_path: &Path, upgrade: config_schema::Upgrade) -> Result<String> {
+        let backup = config_path.with_extension(format!("toml.v{}.bak", upgrade.from));
+        fs::copy(config_path, &backup)?;
+        Self::rewrite(&upgrade.content)?;
+
+        log::info!(
+            "Upgraded {} from version {} to {}, the old file is kept as {}",
+            config_path.display(),
+            upgrade.from,
+            config_schema::CONFIG_VERSION,
+            backup.display()
+        );
+        for change in &upgrade.changes {
+            log::info!("  {}", change);
+        }
+        Ok(upgrade.content)
+    }
+
+    /// Warn once per run about keys in `table` that this config didn't read
+    fn warn_unknown_keys(&self, table: &toml::Table) {
+        static WARNED: OnceLock<()> = OnceLock::new();
+        if WARNED.set(()).is_err() {
+            return;
+        }
+        let Ok(toml::Value::Table(known)) = toml::Value::try_from(self) else {
+            return;
+        };
+        for key in config_schema::unknown_keys(table, &known) {
+            log::warn!("Unknown config key `{}` is ignored", key);
+        }
+    }
+
+    /// Merge the current repository's `.harv.toml` over `table`, returning its path
+    fn apply_local_config(table: &mut toml::Table) -> Result<Option<PathBuf>> {
+        let Some(root) = env::current_dir()
+            .ok()
+            .and_then(|dir| git2::Repository::discover(dir).ok())
+            .and_then(|repo| repo.workdir().map(|dir| dir.to_path_buf()))
+        else {
+            return Ok(None);
+        };
+        let path = root.join(LOCAL_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let local: toml::Table = toml::from_str(&content)
+            .map_err(|e| HarjiraError::Config(format!("Invalid {}: {}", path.display(), e)))?;
+        for ignored in overlay_local(table, local) {
+            log::warn!(
+                "Ignoring {} in {}, it can only be set in the global config",
+                ignored,
+                path.display()
+            );
+        }
+        log::debug!("Applied {}", path.display());
+        Ok(Some(path))
+    }
+
+    /// Store a Harvest account ID in the config file, keeping its comments
+    pub fn set_account_id(account_id: u64) -> Result<PathBuf> {
+        let config_path = Self::config_path()?;
+        let content = fs::read_to_string(&config_path)?;
+        Self::rewrite(&replace_account_id(&content, account_id))
+    }
+
+    /// Save a new default project and task for the Harvest account in use
+    ///
+    /// They go where the current ones came from: the `[harvest.accounts.<name>]`
+    /// section for `--account`, the repository's `.harv.toml` when it sets
+    /// them, and `[harvest]` otherwise. Comments in the file are kept.
+    pub fn set_harvest_target(&self, project_id: u64, task_id: u64) -> Result<PathBuf> {
+        let values = [
+            ("project_id", project_id.to_string()),
+            ("task_id", task_id.to_string()),
+        ];
+
+        if let Some(name) = SELECTED_ACCOUNT.get().filter(|n| *n != DEFAULT_ACCOUNT) {
+            let content = fs::read_to_string(Self::config_path()?)?;
+            let section = format!("harvest.accounts.{}", name);
+            return Self::rewrite(&set_keys(&content, &section, &values));
+        }
+
+        if let Some(local) = &self.local_config {
+            let content = fs::read_to_string(local)?;
+            let table: toml::Table = toml::from_str(&content)?;
+            let sets_target = table
+                .get("harvest")
+                .and_then(|h| h.as_table())
+                .is_some_and(|h| h.contains_key("project_id") || h.contains_key("task_id"));
+            if sets_target {
+                crate::fs::write_private_atomic(local, set_keys(&content, "harvest", &values))?;
+                return Ok(local.clone());
+            }
+        }
+
+        let content = fs::read_to_string(Self::config_path()?)?;
+        Self::rewrite(&set_keys(&content, "harvest", &values))
+    }
+
+    /// Replace the config file's contents
+    pub fn rewrite(contents: &str) -> Result<PathBuf> {
+        let config_path = Self::config_path()?;
+        crate::fs::write_private_atomic(&config_path, contents)?;
+        Ok(config_path)
+    }
+
+    /// The configuration file: `--config`, `HARV_CONFIG` or the default path
+    pub fn config_path() -> Result<PathBuf> {
+        if let Some(path) = Self::explicit_path() {
+            return Ok(path);
+        }
+        Self::default_path()
+    }
+
+    /// Config file picked with `--config` or `HARV_CONFIG`
+    fn explicit_path() -> Option<PathBuf> {
+        CONFIG_FILE.get().cloned().or_else(|| {
+            env::var(CONFIG_ENV)
+                .ok()
+                .filter(|path| !path.is_empty())
+                .map(|path| absolute(&path))
+        })
+    }
+
+    /// `~/.config/harv/config.toml`
+    fn default_path() -> Result<PathBuf> {
+        let home = env::var("HOME")
+            .map_err(|_| HarjiraError::Config("HOME environment variable not set".to_string()))?;
+
+        let config_dir = PathBuf::from(home).join(".config").join("harv");
+        Ok(config_dir.join("config.toml"))
+    }
+
+    /// Migrate from old harjira config directory to new harv directory
+    fn migrate_from_harjira() -> Result<()> {
+        let home = env::var("HOME")
+            .map_err(|_| HarjiraError::Config("HOME environment variable not set".to_string()))?;
+
+        let old_config_dir = PathBuf::from(&home).join(".config").join("harjira");
+        let new_config_dir = PathBuf::from(&home).join(".config").join("harv");
+
+        // Only migrate if old directory exists and new one doesn't
+        if old_config_dir.exists() && !new_config_dir.exists() {
+            // Copy entire directory
+            if let Err(e) = copy_dir_all(&old_config_dir, &new_config_dir) {
+                // Non-fatal: warn and continue
+                eprintln!(
+                    "Warning: Failed to migrate config from {} to {}: {}",
+                    old_config_dir.display(),
+                    new_config_dir.display(),
+                    e
+                );
+            } else {
+                outln!(
+                    "Migrated config from {} to {}",
+                    old_config_dir.display(),
+                    new_config_dir.display()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create a template configuration file
+    pub fn create_template() -> Result<()> {
+        Self::write_new(TEMPLATE)?;
+        Ok(())
+    }
+
+    /// Write a new configuration file, refusing to overwrite an existing one
+    pub fn write_new(contents: &str) -> Result<PathBuf> {
+        let config_path = Self::config_path()?;
+
+        if config_path.exists() {
+            return Err(HarjiraError::Config(format!(
+                "Configuration file already exists at {}",
+                config_path.display()
+            )));
+        }
+
+        // Create directory if it doesn't exist
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&config_path, contents)?;
 
         // Set file permissions to 600 (owner read/write only)
         #[cfg(unix)]
@@ -287,7 +1604,7 @@ target_hours = 8.0
             fs::set_permissions(&config_path, perms)?;
         }
 
-        Ok(())
+        Ok(config_path)
     }
 
     /// Apply environment variable overrides
@@ -326,8 +1643,7 @@ target_hours = 8.0
         }
     }
 
-    /// Validate configuration
-    fn validate(&self) -> Result<()> {
+    fn validate_harvest(&self) -> Result<()> {
         if self.harvest.access_token.is_empty()
             || self.harvest.access_token.contains("your_harvest")
         {
@@ -340,13 +1656,70 @@ target_hours = 8.0
         if self.harvest.account_id.is_empty() || self.harvest.account_id.contains("your_account")
         {
             return Err(HarjiraError::Config(
-                "Harvest account ID not configured. Please update your config file.".to_string(),
+                "Harvest account ID not configured. Run 'harv config account' to pick one."
+                    .to_string(),
+            ));
+        }
+
+        if self.settings.fallback_to_branch
+            && (self.harvest.project_id.is_none() || self.harvest.task_id.is_none())
+        {
+            return Err(HarjiraError::Config(
+                "settings.fallback_to_branch needs project_id and task_id in [harvest]".to_string(),
+            ));
+        }
+
+        for (name, account) in &self.harvest.accounts {
+            if account.account_id.trim().is_empty() {
+                return Err(HarjiraError::Config(format!(
+                    "Harvest account '{}' has no account_id",
+                    name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_tempo(&self) -> Result<()> {
+        if self.tempo.access_token.is_empty() || self.tempo.access_token.contains("your_tempo") {
+            return Err(HarjiraError::Config(
+                "Tempo access token not configured. Please update your config file.".to_string(),
+            ));
+        }
+
+        if self.tempo.account_id.trim().is_empty() {
+            return Err(HarjiraError::Config(
+                "Tempo account_id not configured. Set it to your Atlassian account ID.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_toggl(&self) -> Result<()> {
+        if self.toggl.api_token.is_empty() || self.toggl.api_token.contains("your_toggl") {
+            return Err(HarjiraError::Config(
+                "Toggl API token not configured. Please update your config file.".to_string(),
             ));
         }
 
-        if self.jira.access_token.is_empty() || self.jira.access_token.contains("your_jira") {
+        if self.toggl.workspace_id == 0 {
             return Err(HarjiraError::Config(
-                "Jira access token not configured. Please update your config file.".to_string(),
+                "Toggl workspace_id not configured.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_jira(&self) -> Result<()> {
+        let has_jira_token =
+            !self.jira.access_token.is_empty() && !self.jira.access_token.contains("your_jira");
+        if !has_jira_token && self.jira.oauth.is_none() {
+            return Err(HarjiraError::Config(
+                "Jira access token not configured. Please update your config file or configure [jira.oauth] and run 'harv login jira'."
+                    .to_string(),
             ));
         }
 
@@ -362,6 +1735,110 @@ target_hours = 8.0
             ));
         }
 
+        Ok(())
+    }
+
+    fn validate_linear(&self) -> Result<()> {
+        if self.linear.api_key.is_empty() || self.linear.api_key.contains("your_key") {
+            return Err(HarjiraError::Config(
+                "Linear API key not configured. Please update your config file.".to_string(),
+            ));
+        }
+
+        if self.linear.workspace.trim().is_empty() {
+            return Err(HarjiraError::Config(
+                "Linear workspace not configured. Set it to the workspace part of your Linear URLs."
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_azure_devops(&self) -> Result<()> {
+        let azure = &self.azure_devops;
+        if azure.access_token.is_empty() || azure.access_token.contains("your_azure") {
+            return Err(HarjiraError::Config(
+                "Azure DevOps access token not configured. Please update your config file."
+                    .to_string(),
+            ));
+        }
+
+        if azure.base_url.is_none() && azure.organization.trim().is_empty() {
+            return Err(HarjiraError::Config(
+                "Azure DevOps organization not configured.".to_string(),
+            ));
+        }
+
+        if azure.project.trim().is_empty() {
+            return Err(HarjiraError::Config(
+                "Azure DevOps project not configured.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_github(&self) -> Result<()> {
+        if let Some(ref repository) = self.github.repository {
+            let parts: Vec<&str> = repository.split('/').collect();
+            if parts.len() != 2 || parts.iter().any(|part| part.trim().is_empty()) {
+                return Err(HarjiraError::Config(format!(
+                    "Invalid github.repository: '{}'. Must be owner/repo",
+                    repository
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Account the backend's entries belong to, e.g. to key cached entries
+    pub fn backend_account_id(&self) -> String {
+        match self.backend.provider.to_lowercase().as_str() {
+            "tempo" => self.tempo.account_id.clone(),
+            "toggl" => self.toggl.workspace_id.to_string(),
+            _ => self.harvest.account_id.clone(),
+        }
+    }
+
+    /// Validate configuration
+    fn validate(&self) -> Result<()> {
+        match self.backend.provider.to_lowercase().as_str() {
+            "harvest" => self.validate_harvest()?,
+            "tempo" => self.validate_tempo()?,
+            "toggl" => self.validate_toggl()?,
+            other => {
+                return Err(HarjiraError::Config(format!(
+                    "Unsupported backend provider: {}. Supported: harvest, tempo, toggl",
+                    other
+                )))
+            }
+        }
+
+        let trackers = self.tracker.providers();
+        for provider in &trackers {
+            match provider.as_str() {
+                "jira" => self.validate_jira()?,
+                "linear" => self.validate_linear()?,
+                "azure_devops" => self.validate_azure_devops()?,
+                "github" => self.validate_github()?,
+                other => {
+                    return Err(HarjiraError::Config(format!(
+                        "Unsupported tracker provider: {}. Supported: {}",
+                        other,
+                        TRACKER_PROVIDERS.join(", ")
+                    )))
+                }
+            }
+        }
+        // Tempo logs worklogs on Jira issues whichever tracker names them
+        if !trackers.iter().any(|p| p == "jira")
+            && self.backend.provider.eq_ignore_ascii_case("tempo")
+        {
+            self.validate_jira()?;
+        }
+
         // AI validation (only if enabled)
         if self.ai.enabled {
             if self.ai.api_key.is_empty() || self.ai.api_key.contains("your_") {
@@ -385,6 +1862,43 @@ target_hours = 8.0
                     "AI target_hours must be between 0 and 24".to_string(),
                 ));
             }
+
+            if let Some(threshold) = self.ai.auto_approve_confidence {
+                if !(0.0..=1.0).contains(&threshold) {
+                    return Err(HarjiraError::Config(
+                        "AI auto_approve_confidence must be between 0.0 and 1.0".to_string(),
+                    ));
+                }
+            }
+
+            if self.ai.ticket_details && self.ai.ticket_details_max_chars == 0 {
+                return Err(HarjiraError::Config(
+                    "AI ticket_details_max_chars must be greater than 0".to_string(),
+                ));
+            }
+        }
+
+        if !["message", "subject_and_trailers"]
+            .contains(&self.ticket_filter.search.to_lowercase().as_str())
+        {
+            return Err(HarjiraError::Config(format!(
+                "Invalid ticket_filter.search: '{}'. Must be 'message' or 'subject_and_trailers'",
+                self.ticket_filter.search
+            )));
+        }
+
+        self.git.commit_filter()?;
+
+        match &self.ticket_filter.pattern {
+            Some(pattern) => {
+                crate::ticket_parser::compile_pattern(pattern)?;
+            }
+            None if self.ticket_filter.replace_default => {
+                return Err(HarjiraError::Config(
+                    "ticket_filter.replace_default needs a ticket_filter.pattern".to_string(),
+                ));
+            }
+            None => {}
         }
 
         // Validate continue_mode if present
@@ -400,71 +1914,672 @@ target_hours = 8.0
             }
         }
 
+        if let Some(ref tz) = self.settings.timezone {
+            if tz != "local" && crate::timezone::to_iana(tz).is_none() {
+                return Err(HarjiraError::Config(format!(
+                    "Invalid timezone: '{}'. Use an IANA name like 'Europe/Amsterdam', or 'local'",
+                    tz
+                )));
+            }
+        }
+
+        if let Some(ref proxy) = self.http.proxy {
+            reqwest::Url::parse(proxy).map_err(|_| {
+                HarjiraError::Config(format!(
+                    "Invalid http.proxy: '{}'. Must be a URL (e.g., http://proxy.example.com:8080)",
+                    proxy
+                ))
+            })?;
+        }
+
+        if log::LevelFilter::from_str(&self.logging.level).is_err() {
+            return Err(HarjiraError::Config(format!(
+                "Invalid logging.level: '{}'. Must be error, warn, info, debug or trace",
+                self.logging.level
+            )));
+        }
+        if self.logging.max_size_mb == 0 {
+            return Err(HarjiraError::Config(
+                "logging.max_size_mb must be greater than 0".to_string(),
+            ));
+        }
+
+        let valid_hours = |hours: f64| (0.0..=24.0).contains(&hours);
+        for (day, hours) in &self.capacity.weekdays {
+            if crate::capacity::parse_weekday(day).is_none() {
+                return Err(HarjiraError::Config(format!(
+                    "Invalid weekday in [capacity.weekdays]: '{}'. Use e.g. friday",
+                    day
+                )));
+            }
+            if !valid_hours(*hours) {
+                return Err(HarjiraError::Config(format!(
+                    "capacity.weekdays.{} must be between 0 and 24",
+                    day
+                )));
+            }
+        }
+        for (date, hours) in &self.capacity.dates {
+            if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                return Err(HarjiraError::Config(format!(
+                    "Invalid date in [capacity.dates]: '{}'. Use YYYY-MM-DD",
+                    date
+                )));
+            }
+            if !valid_hours(*hours) {
+                return Err(HarjiraError::Config(format!(
+                    "capacity.dates.\"{}\" must be between 0 and 24",
+                    date
+                )));
+            }
+        }
+
+        let valid_rate = |rate: f64| rate.is_finite() && rate >= 0.0;
+        if self
+            .invoice
+            .hourly_rate
+            .is_some_and(|rate| !valid_rate(rate))
+        {
+            return Err(HarjiraError::Config(
+                "invoice.hourly_rate must be 0 or more".to_string(),
+            ));
+        }
+        for (project, rate) in &self.invoice.rates {
+            if !valid_rate(*rate) {
+                return Err(HarjiraError::Config(format!(
+                    "invoice.rates.\"{}\" must be 0 or more",
+                    project
+                )));
+            }
+        }
+
+        for (name, alias) in &self.aliases {
+            if alias.project.trim().is_empty() || alias.task.trim().is_empty() {
+                return Err(HarjiraError::Config(format!(
+                    "aliases.{} needs both a project and a task",
+                    name
+                )));
+            }
+        }
+
+        for (name, template) in &self.templates {
+            if template.hours.is_some_and(|hours| hours <= 0.0) {
+                return Err(HarjiraError::Config(format!(
+                    "templates.{}.hours must be greater than 0",
+                    name
+                )));
+            }
+        }
+
+        if !crate::secrets::TOOLS.contains(&self.secrets.tool.as_str()) {
+            return Err(HarjiraError::Config(format!(
+                "Unsupported secrets.tool: {}. Supported: {}",
+                self.secrets.tool,
+                crate::secrets::TOOLS.join(", ")
+            )));
+        }
+
+        if self.pomodoro.work_minutes == 0 {
+            return Err(HarjiraError::Config(
+                "pomodoro.work_minutes must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.auto_log.gap_minutes == 0 {
+            return Err(HarjiraError::Config(
+                "auto_log.gap_minutes must be greater than 0".to_string(),
+            ));
+        }
+
+        if let Some(ref stop_at) = self.settings.auto_stop_at {
+            crate::time_parser::parse_clock_time(stop_at).map_err(|_| {
+                HarjiraError::Config(format!(
+                    "Invalid auto_stop_at: '{}'. Must be HH:MM (e.g., 18:30)",
+                    stop_at
+                ))
+            })?;
+        }
+
         Ok(())
     }
 
     /// Display current configuration (masking sensitive data)
     pub fn display(&self) {
-        println!("Harvest Configuration:");
-        println!("  Account ID: {}", self.harvest.account_id);
-        println!(
-            "  Access Token: {}***",
-            &self.harvest.access_token.chars().take(8).collect::<String>()
-        );
-        println!("  User Agent: {}", self.harvest.user_agent);
-        if let Some(project_id) = self.harvest.project_id {
-            println!("  Default Project ID: {}", project_id);
+        if let Some(path) = &self.local_config {
+            outln!("Repository overrides: {}\n", path.display());
         }
-        if let Some(task_id) = self.harvest.task_id {
-            println!("  Default Task ID: {}", task_id);
+        match self.backend.provider.to_lowercase().as_str() {
+            "tempo" => {
+                outln!("Tempo Configuration:");
+                outln!("  Account ID: {}", self.tempo.account_id);
+                outln!(
+                    "  Access Token: {}***",
+                    &self.tempo.access_token.chars().take(8).collect::<String>()
+                );
+            }
+            "toggl" => {
+                outln!("Toggl Track Configuration:");
+                outln!("  Workspace ID: {}", self.toggl.workspace_id);
+                outln!(
+                    "  API Token: {}***",
+                    &self.toggl.api_token.chars().take(8).collect::<String>()
+                );
+                if let Some(project_id) = self.toggl.project_id {
+                    outln!("  Default Project ID: {}", project_id);
+                }
+            }
+            _ => {
+                outln!("Harvest Configuration:");
+                outln!("  Account ID: {}", self.harvest.account_id);
+                outln!(
+                    "  Access Token: {}***",
+                    &self
+                        .harvest
+                        .access_token
+                        .chars()
+                        .take(8)
+                        .collect::<String>()
+                );
+                outln!("  User Agent: {}", self.harvest.user_agent);
+                if let Some(project_id) = self.harvest.project_id {
+                    outln!("  Default Project ID: {}", project_id);
+                }
+                if let Some(task_id) = self.harvest.task_id {
+                    outln!("  Default Task ID: {}", task_id);
+                }
+                for (name, account) in &self.harvest.accounts {
+                    outln!("  Account '{}': {}", name, account.account_id);
+                }
+            }
         }
 
-        println!("\nJira Configuration:");
-        println!("  Base URL: {}", self.jira.base_url);
-        println!(
-            "  Access Token: {}***",
-            &self.jira.access_token.chars().take(8).collect::<String>()
-        );
+        let trackers = self.tracker.providers();
+        if trackers.iter().any(|p| p == "jira") {
+            outln!("\nJira Configuration:");
+            outln!("  Base URL: {}", self.jira.base_url);
+            if let Some(oauth) = &self.jira.oauth {
+                outln!(
+                    "  OAuth Client ID: {}***",
+                    &oauth.client_id.chars().take(8).collect::<String>()
+                );
+            } else {
+                outln!(
+                    "  Access Token: {}***",
+                    &self.jira.access_token.chars().take(8).collect::<String>()
+                );
+            }
+            if let Some(board_id) = self.jira.board_id {
+                outln!("  Board ID: {}", board_id);
+            }
+        }
+        if trackers.iter().any(|p| p == "linear") {
+            outln!("\nLinear Configuration:");
+            outln!("  Workspace: {}", self.linear.workspace);
+            outln!(
+                "  API Key: {}***",
+                &self.linear.api_key.chars().take(8).collect::<String>()
+            );
+        }
+        if trackers.iter().any(|p| p == "azure_devops") {
+            outln!("\nAzure DevOps Configuration:");
+            if let Some(base_url) = &self.azure_devops.base_url {
+                outln!("  Base URL: {}", base_url);
+            } else {
+                outln!("  Organization: {}", self.azure_devops.organization);
+            }
+            outln!("  Project: {}", self.azure_devops.project);
+            outln!(
+                "  Access Token: {}***",
+                &self
+                    .azure_devops
+                    .access_token
+                    .chars()
+                    .take(8)
+                    .collect::<String>()
+            );
+        }
+        if trackers.iter().any(|p| p == "github") {
+            outln!("\nGitHub Configuration:");
+            if let Some(base_url) = &self.github.base_url {
+                outln!("  Base URL: {}", base_url);
+            }
+            if let Some(repository) = &self.github.repository {
+                outln!("  Repository: {}", repository);
+            }
+            if self.github.access_token.is_empty() {
+                outln!("  Access Token: (not set)");
+            } else {
+                outln!(
+                    "  Access Token: {}***",
+                    &self.github.access_token.chars().take(8).collect::<String>()
+                );
+            }
+        }
+        if !self.tracker.repositories.is_empty() {
+            outln!("\nRepository Trackers:");
+            for (path, provider) in &self.tracker.repositories {
+                outln!("  {}: {}", path, tracker_name(provider));
+            }
+        }
 
-        println!("\nGit Configuration:");
+        outln!("\nGit Configuration:");
         if self.git.repositories.is_empty() {
-            println!("  Repositories: Using current working directory");
+            outln!("  Repositories: Using current working directory");
         } else {
-            println!("  Repositories:");
+            outln!("  Repositories:");
             for repo in &self.git.repositories {
-                println!("    - {}", repo);
+                outln!("    - {}", repo);
             }
         }
+        if self.git.skip_merges {
+            outln!("  Skip merge commits: true");
+        }
+        if self.git.skip_reverts {
+            outln!("  Skip revert commits: true");
+        }
+        if !self.git.ignore_patterns.is_empty() {
+            outln!("  Ignored commits: {}", self.git.ignore_patterns.join(", "));
+        }
 
-        println!("\nSettings:");
-        println!("  Auto-start timers: {}", self.settings.auto_start);
-        println!("  Auto-stop timers: {}", self.settings.auto_stop);
-        println!(
+        outln!("\nSettings:");
+        outln!("  Auto-start timers: {}", self.settings.auto_start);
+        outln!("  Auto-stop timers: {}", self.settings.auto_stop);
+        outln!(
             "  Auto-select single ticket: {}",
             self.settings.auto_select_single
         );
         if let Some(ref mode) = self.settings.continue_mode {
-            println!("  Continue mode: {}", mode);
+            outln!("  Continue mode: {}", mode);
+        }
+        if let Some(ref format) = self.settings.status_format {
+            outln!("  Status format: {}", format);
+        }
+        if let Some(ref format) = self.settings.status_idle_format {
+            outln!("  Status idle format: {}", format);
+        }
+        outln!(
+            "  Time display: {}",
+            match self.settings.time_display {
+                TimeDisplay::Decimal => "decimal",
+                TimeDisplay::Clock => "clock",
+            }
+        );
+        outln!(
+            "  Timezone: {}",
+            self.settings
+                .timezone
+                .as_deref()
+                .unwrap_or("from Harvest profile")
+        );
+        outln!(
+            "  Reuse same-day entries: {}",
+            self.settings.reuse_entry_same_day
+        );
+        if let Some(minutes) = self.settings.min_entry_minutes {
+            outln!("  Minimum entry length: {} min", minutes);
+        }
+        if self.settings.fallback_to_branch {
+            outln!("  Timer from branch without tickets: true");
+        }
+        if self.settings.wip_min_lines == 0 {
+            outln!("  Timer from uncommitted work: off");
+        } else {
+            outln!(
+                "  Timer from uncommitted work: {} lines or more",
+                self.settings.wip_min_lines
+            );
+        }
+        if self.settings.hide_money {
+            outln!("  Hide money: true");
+        }
+        if let Some(ref stop_at) = self.settings.auto_stop_at {
+            outln!(
+                "  Auto-stop at: {}{}",
+                stop_at,
+                if self.settings.auto_stop_trim {
+                    " (trimmed)"
+                } else {
+                    ""
+                }
+            );
+        }
+
+        outln!("\nTicket Detection:");
+        if self.ticket_filter.searches_body() {
+            outln!("  Searched: whole commit message");
+        } else {
+            outln!(
+                "  Searched: subject and {} lines",
+                self.ticket_filter.trailer_keys.join("/")
+            );
+        }
+        if let Some(pattern) = &self.ticket_filter.pattern {
+            outln!(
+                "  Pattern: {}{}",
+                pattern,
+                if self.ticket_filter.replace_default {
+                    ""
+                } else {
+                    " (and PROJ-123)"
+                }
+            );
+        }
+        if !self.ticket_filter.denylist.is_empty() {
+            outln!(
+                "  Ignored prefixes: {}",
+                self.ticket_filter.denylist.join(", ")
+            );
+        }
+
+        if !self.mapping.tickets.is_empty() {
+            outln!("\nTicket Mapping:");
+            for (key, target) in &self.mapping.tickets {
+                outln!(
+                    "  {}: project {}, task {}",
+                    key,
+                    target.project_id,
+                    target.task_id
+                );
+            }
+        }
+
+        if !self.mapping.repositories.is_empty() {
+            outln!("\nRepository Mapping:");
+            for (path, target) in &self.mapping.repositories {
+                outln!(
+                    "  {}: project {}, task {}",
+                    path,
+                    target.project_id,
+                    target.task_id
+                );
+            }
+        }
+
+        if !self.scope_mapping.is_empty() {
+            outln!("\nScope Mapping:");
+            for (scope, target) in &self.scope_mapping {
+                outln!(
+                    "  {}: project {}, task {}",
+                    scope,
+                    target.project_id,
+                    target.task_id
+                );
+            }
+        }
+
+        if !self.capacity.weekdays.is_empty() || !self.capacity.dates.is_empty() {
+            outln!("\nCapacity:");
+            for (day, hours) in self.capacity.weekdays.iter().chain(&self.capacity.dates) {
+                outln!("  {}: {}", day, crate::time_parser::format_hours(*hours));
+            }
+        }
+
+        if self.invoice.hourly_rate.is_some() || !self.invoice.rates.is_empty() {
+            outln!("\nInvoice Rates:");
+            if let Some(rate) = self.invoice.hourly_rate {
+                outln!("  Default: {:.2}", rate);
+            }
+            for (project, rate) in &self.invoice.rates {
+                outln!("  {}: {:.2}", project, rate);
+            }
+        }
+
+        if self.leave.project_id.is_some() || !self.leave.types.is_empty() {
+            outln!("\nLeave:");
+            if let (Some(project_id), Some(task_id)) = (self.leave.project_id, self.leave.task_id) {
+                outln!("  Default: project {}, task {}", project_id, task_id);
+            }
+            for (kind, target) in &self.leave.types {
+                outln!(
+                    "  {}: project {}, task {}",
+                    kind,
+                    target.project_id,
+                    target.task_id
+                );
+            }
+        }
+
+        if !self.templates.is_empty() {
+            outln!("\nTemplates:");
+            for (name, template) in &self.templates {
+                outln!("  {}: {}", name, template.notes);
+            }
         }
 
-        println!("\nAI Configuration:");
-        println!("  Enabled: {}", self.ai.enabled);
+        if !self.aliases.is_empty() {
+            outln!("\nAliases:");
+            for (name, alias) in &self.aliases {
+                outln!("  {}: {} / {}", name, alias.project, alias.task);
+            }
+        }
+
+        outln!("\nAI Configuration:");
+        outln!("  Enabled: {}", self.ai.enabled);
         if self.ai.enabled {
-            println!("  Provider: {}", self.ai.provider);
+            outln!("  Provider: {}", self.ai.provider);
             if !self.ai.api_key.is_empty() {
-                println!(
+                outln!(
                     "  API Key: {}***",
                     &self.ai.api_key.chars().take(8).collect::<String>()
                 );
             } else {
-                println!("  API Key: (not set)");
+                outln!("  API Key: (not set)");
             }
             if let Some(model) = &self.ai.model {
-                println!("  Model: {}", model);
+                outln!("  Model: {}", model);
+            }
+            outln!("  Target hours: {}", self.ai.target_hours);
+            outln!("  Stream: {}", self.ai.stream);
+            outln!("  Infer tickets: {}", self.ai.infer_tickets);
+            if self.ai.ticket_details {
+                outln!(
+                    "  Ticket details: up to {} characters per issue",
+                    self.ai.ticket_details_max_chars
+                );
+            }
+            outln!("  Max retries: {}", self.ai.max_retries);
+            if let Some(threshold) = self.ai.auto_approve_confidence {
+                outln!("  Auto-approve confidence: {:.0}%", threshold * 100.0);
+            }
+            if let Some(cap) = self.ai.monthly_cost_cap {
+                outln!("  Monthly cost cap: ${:.2}", cap);
+            }
+            if let Some(minutes) = self.ai.rounding_minutes {
+                outln!("  Rounding: {} minutes", minutes);
+            }
+            if let (Some(project_id), Some(task_id)) =
+                (self.ai.filler_project_id, self.ai.filler_task_id)
+            {
+                outln!("  Filler: project {} / task {}", project_id, task_id);
+            }
+        }
+
+        outln!("\nHTTP Configuration:");
+        outln!("  Timeout: {}", display_timeout(self.http.timeout_secs));
+        outln!(
+            "  Connect timeout: {}",
+            display_timeout(self.http.connect_timeout_secs)
+        );
+        if let Some(ref proxy) = self.http.proxy {
+            outln!("  Proxy: {}", proxy);
+        }
+        if let Some(ref path) = self.http.ca_certificate {
+            outln!("  CA certificate: {}", path);
+        }
+
+        if let Some(ref file) = self.logging.file {
+            outln!("\nLog File:");
+            outln!("  Path: {}", file);
+            outln!("  Level: {}", self.logging.level);
+            outln!(
+                "  Rotation: {} MB, kept {}",
+                self.logging.max_size_mb,
+                match self.logging.keep_days {
+                    0 => "forever".to_string(),
+                    days => format!("{} days", days),
+                }
+            );
+        }
+
+        if self.secrets.encrypted.is_some() {
+            outln!("\nSecrets:");
+            outln!("  Tokens encrypted with {}", self.secrets.tool);
+        }
+
+        outln!("\nPomodoro:");
+        outln!(
+            "  Work: {} min, break: {} min",
+            self.pomodoro.work_minutes,
+            self.pomodoro.break_minutes
+        );
+        if self.pomodoro.long_break_every > 0 {
+            outln!(
+                "  Long break: {} min after every {}",
+                self.pomodoro.long_break_minutes,
+                self.pomodoro.long_break_every
+            );
+        }
+
+        if self.auto_log.enabled {
+            outln!("\nAuto Log:");
+            outln!(
+                "  Sessions end after {} min without git activity, counted from {} min before",
+                self.auto_log.gap_minutes,
+                self.auto_log.lead_minutes
+            );
+        }
+    }
+}
+
+/// Merge the allowed parts of a `.harv.toml` over the global config
+///
+/// Tables merge key by key and other values replace the global ones, so a
+/// repository's `denylist` replaces the global list. Returns the keys that
+/// aren't allowed in a repository and were left out.
+fn overlay_local(config: &mut toml::Table, local: toml::Table) -> Vec<String> {
+    let mut ignored = Vec::new();
+    for (section, value) in local {
+        let whole = LOCAL_CONFIG_KEYS.contains(&(section.as_str(), None));
+        let keys: Vec<&str> = LOCAL_CONFIG_KEYS
+            .iter()
+            .filter(|(s, _)| *s == section)
+            .filter_map(|(_, key)| *key)
+            .collect();
+
+        match value {
+            toml::Value::Table(fields) if !whole && !keys.is_empty() => {
+                for (key, value) in fields {
+                    if keys.contains(&key.as_str()) {
+                        merge_value(config, &section, toml::Table::from_iter([(key, value)]));
+                    } else {
+                        ignored.push(format!("{}.{}", section, key));
+                    }
+                }
+            }
+            toml::Value::Table(fields) if whole => merge_value(config, &section, fields),
+            _ => ignored.push(section),
+        }
+    }
+    ignored
+}
+
+/// Merge `fields` into `config[section]`, recursing into nested tables
+fn merge_value(config: &mut toml::Table, section: &str, fields: toml::Table) {
+    let target = config
+        .entry(section)
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    let toml::Value::Table(target) = target else {
+        *target = toml::Value::Table(fields);
+        return;
+    };
+    for (key, value) in fields {
+        match value {
+            toml::Value::Table(nested) => merge_value(target, &key, nested),
+            value => {
+                target.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Config file contents with `account_id` in the [harvest] section set to `account_id`
+fn replace_account_id(content: &str, account_id: u64) -> String {
+    set_keys(
+        content,
+        "harvest",
+        &[("account_id", format!("\"{}\"", account_id))],
+    )
+}
+
+/// `content` with each `key = value` set in `[section]`, keeping comments
+///
+/// A key already in the section is replaced where it is, a missing one goes
+/// right below the section header, and a missing section is added at the top.
+fn set_keys(content: &str, section: &str, values: &[(&str, String)]) -> String {
+    let header = format!("[{}]", section);
+    let mut lines: Vec<String> = Vec::new();
+    let mut current_section = String::new();
+    let mut missing: Vec<(&str, &String)> = values.iter().map(|(k, v)| (*k, v)).collect();
+
+    for current in content.lines() {
+        let trimmed = current.trim();
+        if trimmed.starts_with('[') {
+            current_section = trimmed.to_string();
+        }
+        let key = trimmed.split_once('=').map(|(key, _)| key.trim());
+        let found = missing
+            .iter()
+            .position(|(wanted, _)| current_section == header && key == Some(*wanted));
+        match found {
+            Some(idx) => {
+                let (key, value) = missing.remove(idx);
+                lines.push(format!("{} = {}", key, value));
+            }
+            None => lines.push(current.to_string()),
+        }
+    }
+
+    if !missing.is_empty() {
+        let added = missing
+            .iter()
+            .map(|(key, value)| format!("{} = {}", key, value));
+        match lines.iter().position(|l| l.trim() == header) {
+            Some(idx) => {
+                lines.splice(idx + 1..idx + 1, added);
+            }
+            None => {
+                let block: Vec<String> = std::iter::once(header.clone())
+                    .chain(added)
+                    .chain(std::iter::once(String::new()))
+                    .collect();
+                lines.splice(0..0, block);
             }
-            println!("  Target hours: {}", self.ai.target_hours);
         }
     }
+
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated
+}
+
+fn default_trailer_keys() -> Vec<String> {
+    ["Refs", "Ticket", "Issue", "Fixes", "Closes", "Resolves"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_ticket_search() -> String {
+    "message".to_string()
+}
+
+fn display_timeout(secs: u64) -> String {
+    if secs == 0 {
+        "none".to_string()
+    } else {
+        format!("{}s", secs)
+    }
 }
 
 /// Recursively copy a directory and its contents
@@ -484,3 +2599,264 @@ fn copy_dir_all(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapping_for_ticket() {
+        let config: Config = toml::from_str(&format!(
+            "{}\n[mapping.tickets]\nproj = {{ project_id = 1, task_id = 2 }}\n\"PROJ-42\" = {{ project_id = 1, task_id = 3 }}\n[mapping.repositories]\n\"/src/intranet/\" = {{ project_id = 4, task_id = 5 }}\n",
+            TEMPLATE
+        ))
+        .unwrap();
+
+        let mapping = &config.mapping;
+        assert_eq!(mapping.for_ticket("PROJ-7").map(|t| t.task_id), Some(2));
+        assert_eq!(mapping.for_ticket("PROJ-42").map(|t| t.task_id), Some(3));
+        assert_eq!(mapping.for_ticket("OTHER-1"), None);
+        assert_eq!(
+            mapping.for_repository("/src/intranet").map(|t| t.task_id),
+            Some(5)
+        );
+        assert_eq!(mapping.for_repository("/src/web"), None);
+    }
+
+    #[test]
+    fn test_leave_for_type() {
+        let leave = LeaveConfig {
+            project_id: Some(1),
+            task_id: Some(2),
+            types: BTreeMap::from([(
+                "sick".to_string(),
+                ProjectTask {
+                    project_id: 1,
+                    task_id: 3,
+                },
+            )]),
+        };
+        assert_eq!(leave.for_type("vacation").map(|t| t.task_id), Some(2));
+        assert_eq!(leave.for_type("Sick").map(|t| t.task_id), Some(3));
+        assert_eq!(LeaveConfig::default().for_type("vacation"), None);
+    }
+
+    #[test]
+    fn test_template_notes() {
+        let config: Config = toml::from_str(&format!(
+            "{}\n[templates.standup]\nnotes = \"Standup {{date}}\"\nhours = 0.25\n[templates.review]\nnotes = \"{{ticket}} - Review\"\n",
+            TEMPLATE
+        ))
+        .unwrap();
+
+        let standup = &config.templates["standup"];
+        assert_eq!(standup.hours, Some(0.25));
+        assert_eq!(
+            standup.render_notes("2026-10-14", None).unwrap(),
+            "Standup 2026-10-14"
+        );
+
+        let review = &config.templates["review"];
+        assert_eq!(
+            review.render_notes("2026-10-14", Some("PROJ-1")).unwrap(),
+            "PROJ-1 - Review"
+        );
+        assert!(review.render_notes("2026-10-14", None).is_err());
+    }
+
+    #[test]
+    fn test_replace_account_id_keeps_comments() {
+        let updated = replace_account_id(TEMPLATE, 1234567);
+        assert!(updated.contains("account_id = \"1234567\"\nuser_agent"));
+        assert!(updated.contains("# Get your access token from"));
+        assert_eq!(updated.lines().count(), TEMPLATE.lines().count());
+
+        let config: Config = toml::from_str(&updated).unwrap();
+        assert_eq!(config.harvest.account_id, "1234567");
+    }
+
+    #[test]
+    fn test_replace_account_id_adds_missing_line() {
+        let content = "[jira]\naccount_id = \"x\"\n\n[harvest]\naccess_token = \"t\"\n";
+        let updated = replace_account_id(content, 42);
+        assert_eq!(
+            updated,
+            "[jira]\naccount_id = \"x\"\n\n[harvest]\naccount_id = \"42\"\naccess_token = \"t\"\n"
+        );
+    }
+
+    #[test]
+    fn test_set_keys_replaces_and_adds() {
+        let content = "[harvest]\n# Default project\nproject_id = 1\n\n[harvest.accounts.side]\naccount_id = \"2\"\n";
+        let values = [
+            ("project_id", "10".to_string()),
+            ("task_id", "20".to_string()),
+        ];
+        assert_eq!(
+            set_keys(content, "harvest", &values),
+            "[harvest]\ntask_id = 20\n# Default project\nproject_id = 10\n\n[harvest.accounts.side]\naccount_id = \"2\"\n"
+        );
+        assert_eq!(
+            set_keys(content, "harvest.accounts.side", &values),
+            "[harvest]\n# Default project\nproject_id = 1\n\n[harvest.accounts.side]\nproject_id = 10\ntask_id = 20\naccount_id = \"2\"\n"
+        );
+    }
+
+    #[test]
+    fn test_absolute_config_path() {
+        let dir = env::current_dir().unwrap();
+        assert_eq!(absolute("work/harv.toml"), dir.join("work/harv.toml"));
+        assert_eq!(absolute("/etc/harv.toml"), PathBuf::from("/etc/harv.toml"));
+    }
+
+    #[test]
+    fn test_overlay_local_config() {
+        let mut table: toml::Table = toml::from_str(TEMPLATE).unwrap();
+        let local: toml::Table = toml::from_str(
+            r#"
+            [harvest]
+            project_id = 111
+            access_token = "committed-by-mistake"
+
+            [mapping.tickets]
+            API = { project_id = 111, task_id = 222 }
+
+            [ticket_filter]
+            denylist = ["RFC"]
+
+            [ai]
+            enabled = true
+            "#,
+        )
+        .unwrap();
+
+        let ignored = overlay_local(&mut table, local);
+        assert_eq!(ignored, ["ai", "harvest.access_token"]);
+
+        let config: Config = table.try_into().unwrap();
+        assert_eq!(config.harvest.project_id, Some(111));
+        assert_eq!(
+            config.harvest.access_token,
+            "your_harvest_access_token_here"
+        );
+        assert_eq!(
+            config.mapping.for_ticket("API-7"),
+            Some(ProjectTask {
+                project_id: 111,
+                task_id: 222
+            })
+        );
+        assert_eq!(config.ticket_filter.denylist, ["RFC"]);
+        assert!(!config.ai.enabled);
+    }
+
+    #[test]
+    fn test_for_account_falls_back_to_default_credentials() {
+        let content = format!(
+            "{}\n[harvest.accounts.side]\naccount_id = \"999\"\ntask_id = 5\n",
+            replace_account_id(TEMPLATE, 111)
+        );
+        let config: Config = toml::from_str(&content).unwrap();
+
+        let side = config.harvest.for_account("side").unwrap();
+        assert_eq!(side.account_id, "999");
+        assert_eq!(side.access_token, config.harvest.access_token);
+        assert_eq!(side.task_id, Some(5));
+        assert!(side.accounts.is_empty());
+
+        let names: Vec<String> = config
+            .harvest
+            .all_accounts()
+            .unwrap()
+            .into_iter()
+            .map(|(name, harvest)| format!("{}={}", name, harvest.account_id))
+            .collect();
+        assert_eq!(names, vec!["default=111", "side=999"]);
+
+        assert!(matches!(
+            config.harvest.for_account("other"),
+            Err(HarjiraError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_tempo_backend_needs_no_harvest_section() {
+        let content = "[backend]\nprovider = \"tempo\"\n\n[tempo]\naccess_token = \"t\"\naccount_id = \"acc-1\"\n\n[jira]\naccess_token = \"j\"\nbase_url = \"https://acme.atlassian.net\"\n";
+        let config: Config = toml::from_str(content).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.backend.name(), "Tempo");
+        assert_eq!(config.backend_account_id(), "acc-1");
+
+        let toggl = Config {
+            backend: BackendConfig {
+                provider: "Toggl".to_string(),
+            },
+            ..config.clone()
+        };
+        assert!(
+            matches!(toggl.validate(), Err(HarjiraError::Config(msg)) if msg.contains("Toggl"))
+        );
+
+        let harvest = Config {
+            backend: BackendConfig::default(),
+            ..config.clone()
+        };
+        assert!(matches!(harvest.validate(), Err(HarjiraError::Config(_))));
+
+        let unknown = Config {
+            backend: BackendConfig {
+                provider: "clockify".to_string(),
+            },
+            ..config
+        };
+        assert!(
+            matches!(unknown.validate(), Err(HarjiraError::Config(msg)) if msg.contains("clockify"))
+        );
+    }
+
+    #[test]
+    fn test_tracker_per_repository() {
+        let content = "[harvest]\naccess_token = \"t\"\naccount_id = \"1\"\nuser_agent = \"harv\"\n\n[jira]\naccess_token = \"j\"\nbase_url = \"https://acme.atlassian.net\"\n\n[tracker.repositories]\n\"/src/legacy/\" = \"Azure_DevOps\"\n";
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.tracker.provider_for("/src/legacy"), "Azure_DevOps");
+        assert_eq!(config.tracker.provider_for("/src/web"), "jira");
+        assert_eq!(config.tracker.providers(), vec!["jira", "azure_devops"]);
+        assert!(matches!(
+            config.validate(),
+            Err(HarjiraError::Config(msg)) if msg.contains("Azure DevOps")
+        ));
+    }
+
+    #[test]
+    fn test_linear_tracker_needs_no_jira_section() {
+        let content = "[harvest]\naccess_token = \"t\"\naccount_id = \"1\"\nuser_agent = \"harv\"\n\n[tracker]\nprovider = \"linear\"\n\n[linear]\napi_key = \"lin_api_x\"\nworkspace = \"acme\"\n";
+        let config: Config = toml::from_str(content).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.tracker.name(), "Linear");
+
+        let tempo = Config {
+            backend: BackendConfig {
+                provider: "tempo".to_string(),
+            },
+            tempo: TempoConfig {
+                access_token: "t".to_string(),
+                account_id: "acc-1".to_string(),
+                base_url: None,
+            },
+            ..config.clone()
+        };
+        assert!(matches!(tempo.validate(), Err(HarjiraError::Config(msg)) if msg.contains("Jira")));
+
+        let no_workspace = Config {
+            linear: LinearConfig {
+                workspace: String::new(),
+                ..config.linear.clone()
+            },
+            ..config
+        };
+        assert!(matches!(
+            no_workspace.validate(),
+            Err(HarjiraError::Config(msg)) if msg.contains("workspace")
+        ));
+    }
+}
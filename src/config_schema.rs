@@ -0,0 +1,238 @@
+//! Versions of the config file format and the migrations between them
+//!
+//! The file's top-level `version` says which format it is in, files without
+//! one are version 1. Loading an older file runs the migrations after its
+//! version in order and rewrites it, keeping a copy of the old file. The
+//! migrations edit the text line by line so comments survive.
+//!
+//! Keys harv doesn't know are reported instead of silently ignored, which
+//! catches typos and keys that were renamed.
+
+use crate::error::{HarjiraError, Result};
+
+/// Format written by this version of harv
+pub const CONFIG_VERSION: u32 = 2;
+
+/// A step from `from` to `from + 1`
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: fn(&str) -> String,
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    description: "ai.provider \"claude\" is now \"anthropic\"",
+    apply: claude_is_anthropic,
+}];
+
+/// Version of a parsed config file
+pub fn version_of(table: &toml::Table) -> u32 {
+    table
+        .get("version")
+        .and_then(|v| v.as_integer())
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(1)
+}
+
+/// An upgraded config file
+pub struct Upgrade {
+    pub from: u32,
+    pub content: String,
+    /// One line per migration that changed something
+    pub changes: Vec<&'static str>,
+}
+
+/// Upgrade `content` to `CONFIG_VERSION`, None when it is current
+pub fn upgrade(content: &str) -> Result<Option<Upgrade>> {
+    let table: toml::Table = toml::from_str(content)?;
+    let from = version_of(&table);
+    if from > CONFIG_VERSION {
+        return Err(HarjiraError::Config(format!(
+            "Config version {} is newer than supported version {}. Update harv to use this config.",
+            from, CONFIG_VERSION
+        )));
+    }
+    if from == CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    let mut upgraded = content.to_string();
+    let mut changes = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from) {
+        let migrated = (migration.apply)(&upgraded);
+        if migrated != upgraded {
+            changes.push(migration.description);
+        }
+        upgraded = migrated;
+    }
+    Ok(Some(Upgrade {
+        from,
+        content: set_version(&upgraded, CONFIG_VERSION),
+        changes,
+    }))
+}
+
+/// Content with the top-level `version` set, added above the first section if missing
+fn set_version(content: &str, version: u32) -> String {
+    let line = format!("version = {}", version);
+    let mut lines: Vec<String> = Vec::new();
+    let mut replaced = false;
+    let mut in_section = false;
+
+    for current in content.lines() {
+        let trimmed = current.trim();
+        if trimmed.starts_with('[') {
+            if !replaced {
+                lines.push(line.clone());
+                lines.push(String::new());
+                replaced = true;
+            }
+            in_section = true;
+        }
+        let is_version = trimmed
+            .split_once('=')
+            .is_some_and(|(key, _)| key.trim() == "version");
+        if !in_section && is_version {
+            if !replaced {
+                // Keep a trailing comment
+                match current.find('#') {
+                    Some(at) => lines.push(format!("{} {}", line, &current[at..])),
+                    None => lines.push(line.clone()),
+                }
+                replaced = true;
+            }
+        } else {
+            lines.push(current.to_string());
+        }
+    }
+    if !replaced {
+        lines.push(line);
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
+/// Version 1 to 2: "claude" was accepted as another name for the Anthropic provider
+fn claude_is_anthropic(content: &str) -> String {
+    let mut section = String::new();
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                section = trimmed.to_string();
+            }
+            let is_claude = trimmed.split_once('=').is_some_and(|(key, value)| {
+                key.trim() == "provider"
+                    && value
+                        .split('#')
+                        .next()
+                        .is_some_and(|v| v.trim().trim_matches('"').eq_ignore_ascii_case("claude"))
+            });
+            if section == "[ai]" && is_claude {
+                // Swap only the value, indentation and a trailing comment stay
+                let (key, rest) = line.split_once('=').unwrap_or((line, ""));
+                let value = rest.split('#').next().unwrap_or("").trim();
+                format!("{}={}", key, rest.replacen(value, "\"anthropic\"", 1))
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
+/// Dotted paths of keys in `file` that aren't in `known`, the same config
+/// serialized back
+///
+/// Whatever harv read ends up in `known`, so what is left over was ignored.
+pub fn unknown_keys(file: &toml::Table, known: &toml::Table) -> Vec<String> {
+    let mut unknown = Vec::new();
+    collect_unknown(file, known, "", &mut unknown);
+    unknown
+}
+
+fn collect_unknown(file: &toml::Table, known: &toml::Table, prefix: &str, out: &mut Vec<String>) {
+    for (key, value) in file {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match (value, known.get(key)) {
+            (toml::Value::Table(fields), Some(toml::Value::Table(known_fields))) => {
+                collect_unknown(fields, known_fields, &path, out)
+            }
+            (_, Some(_)) => {}
+            // Read by the loader itself, see `secrets::resolve_commands`
+            _ if key.ends_with("_cmd") => {}
+            // Empty tables and lists aren't written back
+            (toml::Value::Table(fields), None) if fields.is_empty() => {}
+            (toml::Value::Array(items), None) if items.is_empty() => {}
+            _ => out.push(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_from_version_1() {
+        let content = "# My config\n\n[harvest]\naccount_id = \"1\"\n\n[ai]\n# Which one\nprovider = \"Claude\"  # or openai\n";
+        let upgrade = upgrade(content).unwrap().unwrap();
+        assert_eq!(upgrade.from, 1);
+        assert_eq!(upgrade.changes.len(), 1);
+        assert_eq!(
+            upgrade.content,
+            "# My config\n\nversion = 2\n\n[harvest]\naccount_id = \"1\"\n\n[ai]\n# Which one\nprovider = \"anthropic\"  # or openai\n"
+        );
+        assert!(super::upgrade(&upgrade.content).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_upgrade_only_lists_changes() {
+        let upgrade = upgrade("[ai]\nprovider = \"openai\"\n").unwrap().unwrap();
+        assert!(upgrade.changes.is_empty());
+        assert_eq!(
+            upgrade.content,
+            "version = 2\n\n[ai]\nprovider = \"openai\"\n"
+        );
+    }
+
+    #[test]
+    fn test_upgrade_keeps_the_version_comment() {
+        let upgrade = upgrade("version = 1 # set by setup\n\n[ai]\nprovider = \"openai\"\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            upgrade.content,
+            "version = 2 # set by setup\n\n[ai]\nprovider = \"openai\"\n"
+        );
+    }
+
+    #[test]
+    fn test_newer_version_is_an_error() {
+        assert!(upgrade("version = 99\n").is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let file: toml::Table = toml::from_str(
+            "[harvest]\naccess_token_cmd = \"pass\"\naccount_id = \"1\"\n[setings]\nauto_start = true\n[ai]\nenabled = true\nmodle = \"x\"\n",
+        )
+        .unwrap();
+        let known: toml::Table = toml::from_str(
+            "[harvest]\naccess_token = \"t\"\naccount_id = \"1\"\n[ai]\nenabled = true\n",
+        )
+        .unwrap();
+        assert_eq!(unknown_keys(&file, &known), ["ai.modle", "setings"]);
+    }
+}
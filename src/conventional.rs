@@ -0,0 +1,73 @@
+//! Conventional Commits headers (`feat(auth): add token refresh`)
+//!
+//! Used to classify work that has no ticket key: the scope names the area of
+//! the codebase, which `[scope_mapping]` maps to a Harvest project and task.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// type(scope)!: description, with the scope and "!" optional
+    static ref HEADER_RE: Regex =
+        Regex::new(r"^([A-Za-z]+)(?:\(([^()]+)\))?(!)?: +(\S.*)$").unwrap();
+}
+
+/// The parsed header line of a conventional commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConventionalCommit {
+    /// "feat", "fix", "chore", ... in lowercase
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+impl ConventionalCommit {
+    /// The header as it would be written, e.g. "feat(auth): add token refresh"
+    pub fn header(&self) -> String {
+        match &self.scope {
+            Some(scope) => format!("{}({}): {}", self.kind, scope, self.description),
+            None => format!("{}: {}", self.kind, self.description),
+        }
+    }
+}
+
+/// Parse the first line of a commit message, None if it isn't conventional
+pub fn parse(message: &str) -> Option<ConventionalCommit> {
+    let header = message.lines().next()?.trim();
+    let cap = HEADER_RE.captures(header)?;
+
+    Some(ConventionalCommit {
+        kind: cap[1].to_lowercase(),
+        scope: cap.get(2).map(|m| m.as_str().trim().to_string()),
+        breaking: cap.get(3).is_some(),
+        description: cap[4].trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        let commit = parse("feat(auth)!: drop legacy tokens\n\nBREAKING CHANGE: ...").unwrap();
+        assert_eq!(commit.kind, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("auth"));
+        assert!(commit.breaking);
+        assert_eq!(commit.description, "drop legacy tokens");
+        assert_eq!(commit.header(), "feat(auth): drop legacy tokens");
+
+        let commit = parse("Fix: typo").unwrap();
+        assert_eq!(commit.kind, "fix");
+        assert_eq!(commit.scope, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_other_messages() {
+        assert_eq!(parse("Merge branch 'main'"), None);
+        assert_eq!(parse("PROJ-1: fix login"), None);
+        assert_eq!(parse("feat(auth):no space"), None);
+        assert_eq!(parse(""), None);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::models::{ProposedTimeEntry, TimeEntry};
+use crate::ticket_parser;
+use std::collections::HashSet;
+
+/// Share of common words above which two descriptions count as the same work
+const SIMILARITY_THRESHOLD: f64 = 0.6;
+
+/// Entries logged more than once for the same work on the same day
+#[derive(Debug, Clone)]
+pub struct DuplicateGroup {
+    /// Entry that is kept and receives the combined hours
+    pub keep: TimeEntry,
+    /// Entries that are deleted after merging
+    pub extras: Vec<TimeEntry>,
+}
+
+impl DuplicateGroup {
+    /// Combined hours of all entries in the group
+    pub fn total_hours(&self) -> f64 {
+        self.keep.hours.unwrap_or(0.0) + self.extras.iter().filter_map(|e| e.hours).sum::<f64>()
+    }
+
+    /// All entries in the group, kept entry first
+    pub fn entries(&self) -> impl Iterator<Item = &TimeEntry> {
+        std::iter::once(&self.keep).chain(self.extras.iter())
+    }
+}
+
+/// Find entries with identical project, task and notes on the same day
+///
+/// Running timers are never merged. The oldest entry (lowest id) of each
+/// group is kept. Groups are ordered by date, then by first appearance.
+pub fn find_duplicates(entries: &[TimeEntry]) -> Vec<DuplicateGroup> {
+    let mut groups: Vec<Vec<&TimeEntry>> = Vec::new();
+
+    for entry in entries.iter().filter(|e| !e.is_running) {
+        match groups.iter_mut().find(|g| is_duplicate(g[0], entry)) {
+            Some(group) => group.push(entry),
+            None => groups.push(vec![entry]),
+        }
+    }
+
+    let mut duplicates: Vec<DuplicateGroup> = groups
+        .into_iter()
+        .filter(|g| g.len() > 1)
+        .map(|mut g| {
+            g.sort_by_key(|e| e.id);
+            DuplicateGroup {
+                keep: g[0].clone(),
+                extras: g[1..].iter().map(|e| (*e).clone()).collect(),
+            }
+        })
+        .collect();
+
+    duplicates.sort_by(|a, b| a.keep.spent_date.cmp(&b.keep.spent_date));
+    duplicates
+}
+
+/// Find an existing entry that a proposed AI entry likely duplicates
+///
+/// An entry overlaps when it mentions the same ticket key or its notes share
+/// most of their words with the proposed description.
+pub fn find_overlap<'a>(
+    proposed: &ProposedTimeEntry,
+    existing: &'a [TimeEntry],
+    denylist: &[String],
+) -> Option<&'a TimeEntry> {
+    let proposed_tickets =
+        ticket_parser::extract_tickets(std::slice::from_ref(&proposed.description), denylist);
+    let proposed_words = words(&proposed.description);
+
+    existing.iter().find(|entry| {
+        let Some(notes) = entry.notes.as_deref() else {
+            return false;
+        };
+
+        let existing_tickets = ticket_parser::extract_tickets(&[notes.to_string()], denylist);
+        if proposed_tickets
+            .iter()
+            .any(|t| existing_tickets.contains(t))
+        {
+            return true;
+        }
+
+        similarity(&proposed_words, &words(notes)) >= SIMILARITY_THRESHOLD
+    })
+}
+
+/// Lowercased words of three or more characters
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Jaccard similarity of two word sets
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let common = a.intersection(b).count() as f64;
+    let all = a.union(b).count() as f64;
+    common / all
+}
+
+fn is_duplicate(a: &TimeEntry, b: &TimeEntry) -> bool {
+    a.spent_date == b.spent_date
+        && a.project.as_ref().map(|p| p.id) == b.project.as_ref().map(|p| p.id)
+        && a.task.as_ref().map(|t| t.id) == b.task.as_ref().map(|t| t.id)
+        && normalize_notes(a) == normalize_notes(b)
+}
+
+fn normalize_notes(entry: &TimeEntry) -> String {
+    entry
+        .notes
+        .as_deref()
+        .unwrap_or("")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ProjectInfo, TaskInfo};
+
+    fn entry(id: u64, date: &str, notes: &str, hours: f64) -> TimeEntry {
+        TimeEntry {
+            id,
+            spent_date: date.to_string(),
+            hours: Some(hours),
+            notes: Some(notes.to_string()),
+            project: Some(ProjectInfo {
+                id: 1,
+                name: "Backend".to_string(),
+            }),
+            task: Some(TaskInfo {
+                id: 2,
+                name: "Programming".to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_duplicates_same_day() {
+        let entries = vec![
+            entry(3, "2025-01-10", "PROJ-1 - Login", 1.0),
+            entry(1, "2025-01-10", "PROJ-1 - Login", 2.0),
+            entry(2, "2025-01-10", "proj-1 -  login", 0.5),
+            entry(4, "2025-01-10", "PROJ-2 - Review", 1.0),
+        ];
+
+        let groups = find_duplicates(&entries);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].keep.id, 1);
+        assert_eq!(
+            groups[0].extras.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(groups[0].total_hours(), 3.5);
+        assert_eq!(groups[0].entries().count(), 3);
+    }
+
+    #[test]
+    fn test_different_days_are_not_duplicates() {
+        let entries = vec![
+            entry(1, "2025-01-10", "Daily standup", 0.25),
+            entry(2, "2025-01-11", "Daily standup", 0.25),
+        ];
+        assert!(find_duplicates(&entries).is_empty());
+    }
+
+    #[test]
+    fn test_different_tasks_are_not_duplicates() {
+        let mut review = entry(2, "2025-01-10", "PROJ-1 - Login", 1.0);
+        review.task.as_mut().unwrap().id = 3;
+        let entries = vec![entry(1, "2025-01-10", "PROJ-1 - Login", 1.0), review];
+        assert!(find_duplicates(&entries).is_empty());
+    }
+
+    fn proposed(description: &str) -> ProposedTimeEntry {
+        ProposedTimeEntry {
+            description: description.to_string(),
+            project_id: 1,
+            task_id: 2,
+            hours: 1.0,
+            confidence_score: None,
+        }
+    }
+
+    #[test]
+    fn test_overlap_by_ticket() {
+        let existing = vec![entry(1, "2025-01-10", "PROJ-123 - Login page", 2.0)];
+        let overlap = find_overlap(&proposed("Fixed proj-123 redirect bug"), &existing, &[]);
+        assert_eq!(overlap.map(|e| e.id), Some(1));
+    }
+
+    #[test]
+    fn test_overlap_by_similar_description() {
+        let existing = vec![entry(
+            1,
+            "2025-01-10",
+            "Code review for payment service",
+            1.0,
+        )];
+        let overlap = find_overlap(&proposed("Payment service code review"), &existing, &[]);
+        assert!(overlap.is_some());
+    }
+
+    #[test]
+    fn test_no_overlap_for_unrelated_work() {
+        let existing = vec![
+            entry(1, "2025-01-10", "PROJ-1 - Login page", 1.0),
+            entry(2, "2025-01-10", "Daily standup", 0.25),
+        ];
+        assert!(find_overlap(&proposed("PROJ-2 - Write API docs"), &existing, &[]).is_none());
+    }
+
+    #[test]
+    fn test_running_entries_are_skipped() {
+        let mut running = entry(2, "2025-01-10", "PROJ-1 - Login", 1.0);
+        running.is_running = true;
+        let entries = vec![entry(1, "2025-01-10", "PROJ-1 - Login", 1.0), running];
+        assert!(find_duplicates(&entries).is_empty());
+    }
+}
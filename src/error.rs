@@ -1,3 +1,4 @@
+use reqwest::StatusCode;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,12 +12,39 @@ pub enum HarjiraError {
     #[error("Harvest API error: {0}")]
     Harvest(String),
 
+    #[error("Harvest API error: {0}")]
+    HarvestApi(#[from] HarvestApiError),
+
     #[error("Jira API error: {0}")]
     Jira(String),
 
+    #[error("Azure DevOps API error: {0}")]
+    AzureDevOps(String),
+
+    #[error("GitHub API error: {0}")]
+    GitHub(String),
+
+    #[error("Linear API error: {0}")]
+    Linear(String),
+
+    #[error("Tempo API error: {0}")]
+    Tempo(String),
+
+    #[error("Toggl API error: {0}")]
+    Toggl(String),
+
     #[error("HTTP request failed: {0}")]
     Http(#[from] reqwest::Error),
 
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("Authentication failed: {0}")]
+    Auth(String),
+
+    #[error("No commits found from today")]
+    NoCommitsFound,
+
     #[error("No Jira tickets found in commits")]
     NoTicketsFound,
 
@@ -38,8 +66,161 @@ pub enum HarjiraError {
     #[error("Invalid time entry: {0}")]
     InvalidEntry(String),
 
+    #[error("{0}")]
+    Locked(String),
+
     #[error("Show help")]
     ShowHelp,
 }
 
+/// Process exit codes, one per error category
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+    pub const GENERAL: i32 = 1;
+    pub const CONFIG: i32 = 3;
+    pub const AUTH: i32 = 4;
+    pub const NETWORK: i32 = 5;
+    pub const NOTHING_TO_DO: i32 = 6;
+    pub const LOCKED: i32 = 7;
+    pub const CANCELLED: i32 = 130;
+}
+
+impl HarjiraError {
+    /// Exit code for this error's category
+    ///
+    /// 1 general, 3 configuration, 4 authentication, 5 network,
+    /// 6 nothing to do (only with `--strict`), 7 another harv is running,
+    /// 130 cancelled by user.
+    /// Exit code 2 is left to clap for usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            HarjiraError::Config(_) | HarjiraError::TomlParse(_) => exit_code::CONFIG,
+            HarjiraError::Auth(_) => exit_code::AUTH,
+            HarjiraError::HarvestApi(api) if api.is_auth_error() => exit_code::AUTH,
+            HarjiraError::Network(_) => exit_code::NETWORK,
+            HarjiraError::Http(e) if e.is_connect() || e.is_timeout() => exit_code::NETWORK,
+            HarjiraError::NoCommitsFound | HarjiraError::NoTicketsFound => exit_code::NOTHING_TO_DO,
+            HarjiraError::Locked(_) => exit_code::LOCKED,
+            HarjiraError::UserCancelled => exit_code::CANCELLED,
+            HarjiraError::ShowHelp => exit_code::SUCCESS,
+            _ => exit_code::GENERAL,
+        }
+    }
+}
+
+/// Error response returned by the Harvest API
+#[derive(Error, Debug, Clone)]
+#[error("{status}: {message}")]
+pub struct HarvestApiError {
+    pub status: StatusCode,
+    /// Machine-readable error code (e.g. "invalid_token"), when provided
+    pub code: Option<String>,
+    pub message: String,
+    /// Seconds to wait before retrying, from the Retry-After header on 429s
+    pub retry_after: Option<u64>,
+}
+
+impl HarvestApiError {
+    /// Build from the parts of a failed response
+    ///
+    /// Harvest returns either `{"error": ..., "error_description": ...}`
+    /// (auth failures) or `{"message": ...}` (validation failures).
+    pub fn from_parts(status: StatusCode, retry_after: Option<&str>, body: &str) -> Self {
+        #[derive(serde::Deserialize)]
+        struct ErrorBody {
+            error: Option<String>,
+            error_description: Option<String>,
+            message: Option<String>,
+        }
+
+        let parsed: Option<ErrorBody> = serde_json::from_str(body).ok();
+        let (code, message) = match parsed {
+            Some(b) => {
+                let message = b
+                    .message
+                    .or(b.error_description)
+                    .or_else(|| b.error.clone());
+                (b.error, message)
+            }
+            None => (None, None),
+        };
+
+        let message = message.unwrap_or_else(|| {
+            let trimmed = body.trim();
+            if trimmed.is_empty() {
+                "Unknown error".to_string()
+            } else {
+                trimmed.to_string()
+            }
+        });
+
+        Self {
+            status,
+            code,
+            message,
+            retry_after: retry_after.and_then(|v| v.trim().parse().ok()),
+        }
+    }
+
+    /// Request was rejected by validation (invalid or archived project/task, etc.)
+    pub fn is_unprocessable(&self) -> bool {
+        self.status == StatusCode::UNPROCESSABLE_ENTITY
+    }
+
+    /// Token is invalid or lacks permissions
+    pub fn is_auth_error(&self) -> bool {
+        self.status == StatusCode::UNAUTHORIZED || self.status == StatusCode::FORBIDDEN
+    }
+
+    /// Rate limit exceeded
+    pub fn is_rate_limited(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS
+    }
+}
+
 pub type Result<T> = std::result::Result<T, HarjiraError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_by_category() {
+        assert_eq!(
+            HarjiraError::Config("x".into()).exit_code(),
+            exit_code::CONFIG
+        );
+        assert_eq!(HarjiraError::Auth("x".into()).exit_code(), exit_code::AUTH);
+        assert_eq!(
+            HarjiraError::Network("x".into()).exit_code(),
+            exit_code::NETWORK
+        );
+        assert_eq!(
+            HarjiraError::NoTicketsFound.exit_code(),
+            exit_code::NOTHING_TO_DO
+        );
+        assert_eq!(
+            HarjiraError::Locked("x".into()).exit_code(),
+            exit_code::LOCKED
+        );
+        assert_eq!(
+            HarjiraError::UserCancelled.exit_code(),
+            exit_code::CANCELLED
+        );
+        assert_eq!(
+            HarjiraError::Harvest("x".into()).exit_code(),
+            exit_code::GENERAL
+        );
+    }
+
+    #[test]
+    fn test_harvest_auth_errors_map_to_auth_code() {
+        let unauthorized = HarvestApiError::from_parts(StatusCode::UNAUTHORIZED, None, "");
+        let invalid = HarvestApiError::from_parts(StatusCode::UNPROCESSABLE_ENTITY, None, "");
+        assert_eq!(
+            HarjiraError::from(unauthorized).exit_code(),
+            exit_code::AUTH
+        );
+        assert_eq!(HarjiraError::from(invalid).exit_code(), exit_code::GENERAL);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::error::{HarjiraError, Result};
+use crate::harvest::{check_response, HarvestClient};
+use crate::models::{Context, ProjectInfo};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+
+/// Harvest expense category
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpenseCategory {
+    pub id: u64,
+    pub name: String,
+    pub unit_name: Option<String>,
+    pub unit_price: Option<f64>,
+    #[serde(default)]
+    pub is_active: bool,
+}
+
+/// Response from Harvest API for expense categories
+#[derive(Debug, Deserialize)]
+pub struct ExpenseCategoriesResponse {
+    pub expense_categories: Vec<ExpenseCategory>,
+}
+
+/// Category reference embedded in an expense
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpenseCategoryInfo {
+    pub id: u64,
+    pub name: String,
+}
+
+/// Harvest expense response
+#[derive(Debug, Clone, Deserialize)]
+pub struct Expense {
+    pub id: u64,
+    pub spent_date: String,
+    pub total_cost: Option<f64>,
+    pub notes: Option<String>,
+    pub project: Option<ProjectInfo>,
+    pub expense_category: Option<ExpenseCategoryInfo>,
+}
+
+/// Response from Harvest API for expenses list
+#[derive(Debug, Deserialize)]
+pub struct ExpensesResponse {
+    pub expenses: Vec<Expense>,
+}
+
+/// Harvest expense request
+#[derive(Debug, Serialize)]
+pub struct CreateExpenseRequest {
+    pub project_id: u64,
+    pub expense_category_id: u64,
+    pub spent_date: String,
+    pub total_cost: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl HarvestClient {
+    /// Get active expense categories
+    pub fn get_expense_categories(&self) -> Result<Vec<ExpenseCategory>> {
+        let url = format!("{}/expense_categories?is_active=true", self.base_url);
+
+        debug!("GET {}", url);
+
+        let response = self.client.get(&url).send().map_err(|e| {
+            HarjiraError::Network(format!("Failed to fetch expense categories: {}", e))
+        })?;
+
+        let response = check_response(response)?;
+
+        let categories_response: ExpenseCategoriesResponse = response.json().map_err(|e| {
+            HarjiraError::Harvest(format!(
+                "Failed to parse expense categories response: {}",
+                e
+            ))
+        })?;
+
+        debug!(
+            "Retrieved {} expense categories",
+            categories_response.expense_categories.len()
+        );
+
+        Ok(categories_response.expense_categories)
+    }
+
+    /// Get expenses for a date range (inclusive, YYYY-MM-DD)
+    pub fn get_expenses_range(&self, from_date: &str, to_date: &str) -> Result<Vec<Expense>> {
+        let url = format!(
+            "{}/expenses?from={}&to={}",
+            self.base_url, from_date, to_date
+        );
+
+        debug!("GET {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to fetch expenses: {}", e)))?;
+
+        let response = check_response(response)?;
+
+        let expenses_response: ExpensesResponse = response.json().map_err(|e| {
+            HarjiraError::Harvest(format!("Failed to parse expenses response: {}", e))
+        })?;
+
+        debug!(
+            "Retrieved {} expenses from {} to {}",
+            expenses_response.expenses.len(),
+            from_date,
+            to_date
+        );
+
+        Ok(expenses_response.expenses)
+    }
+
+    /// Create an expense against a project
+    pub fn create_expense(&self, request: &CreateExpenseRequest, ctx: &Context) -> Result<Expense> {
+        if ctx.dry_run {
+            crate::plan::record(
+                "POST",
+                &format!("{}/expenses", self.base_url),
+                Some(request),
+            );
+            info!("[DRY RUN] Would create expense:");
+            info!("  Project ID: {}", request.project_id);
+            info!("  Category ID: {}", request.expense_category_id);
+            info!("  Date: {}", request.spent_date);
+            info!("  Amount: {:.2}", request.total_cost);
+            if let Some(notes) = &request.notes {
+                info!("  Notes: {}", notes);
+            }
+            return Ok(Expense {
+                id: 0,
+                spent_date: request.spent_date.clone(),
+                total_cost: Some(request.total_cost),
+                notes: request.notes.clone(),
+                project: None,
+                expense_category: None,
+            });
+        }
+
+        let url = format!("{}/expenses", self.base_url);
+        debug!("POST {}", url);
+        debug!("Request body: {:?}", request);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(request)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to create expense: {}", e)))?;
+
+        let response = check_response(response)?;
+
+        let expense: Expense = response.json().map_err(|e| {
+            HarjiraError::Harvest(format!("Failed to parse created expense: {}", e))
+        })?;
+
+        info!(
+            "Created expense: {:.2} on {}",
+            request.total_cost, request.spent_date
+        );
+        Ok(expense)
+    }
+}
+
+/// Parse an expense amount like "12.50" or "12,50"
+pub fn parse_amount(input: &str) -> Result<f64> {
+    let normalized = input
+        .trim()
+        .trim_start_matches(['€', '$', '£'])
+        .replace(',', ".");
+    let amount: f64 = normalized
+        .trim()
+        .parse()
+        .map_err(|_| HarjiraError::Config(format!("Invalid amount: '{}'", input.trim())))?;
+
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(HarjiraError::Config(
+            "Amount must be greater than zero".to_string(),
+        ));
+    }
+
+    // Harvest stores costs with two decimals
+    Ok((amount * 100.0).round() / 100.0)
+}
+
+/// Total cost of a list of expenses
+pub fn total_cost(expenses: &[Expense]) -> f64 {
+    expenses.iter().filter_map(|e| e.total_cost).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("12.50").unwrap(), 12.5);
+        assert_eq!(parse_amount("12,50").unwrap(), 12.5);
+        assert_eq!(parse_amount(" €7 ").unwrap(), 7.0);
+        assert_eq!(parse_amount("3.456").unwrap(), 3.46);
+    }
+
+    #[test]
+    fn test_parse_amount_rejects_invalid() {
+        assert!(parse_amount("").is_err());
+        assert!(parse_amount("abc").is_err());
+        assert!(parse_amount("0").is_err());
+        assert!(parse_amount("-5").is_err());
+    }
+
+    #[test]
+    fn test_parse_expenses_response() {
+        let json = r#"{
+            "expenses": [
+                {
+                    "id": 15296442,
+                    "spent_date": "2025-01-10",
+                    "total_cost": 33.35,
+                    "notes": "Train to client",
+                    "billable": true,
+                    "project": {"id": 14307913, "name": "Website Redesign", "code": "WR"},
+                    "expense_category": {"id": 4195926, "name": "Transportation"}
+                },
+                {
+                    "id": 15296443,
+                    "spent_date": "2025-01-10",
+                    "total_cost": null,
+                    "notes": null,
+                    "project": null,
+                    "expense_category": null
+                }
+            ]
+        }"#;
+
+        let response: ExpensesResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.expenses.len(), 2);
+        assert_eq!(
+            response.expenses[0].expense_category.as_ref().unwrap().name,
+            "Transportation"
+        );
+        assert_eq!(total_cost(&response.expenses), 33.35);
+    }
+}
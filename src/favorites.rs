@@ -0,0 +1,276 @@
+use crate::error::{HarjiraError, Result};
+use crate::models::{ContinueCandidate, ProjectInfo, TaskInfo, TimeEntry};
+use chrono::Local;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const FAVORITES_FILE_VERSION: u8 = 1;
+
+/// A named shortcut for starting a timer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Favorite {
+    pub name: String,
+    pub project_id: u64,
+    pub project_name: String,
+    pub task_id: u64,
+    pub task_name: String,
+    /// Notes for the entry, `{date}` is replaced with today's date
+    pub notes: String,
+}
+
+impl Favorite {
+    /// Notes with template placeholders filled in for today
+    pub fn render_notes(&self) -> String {
+        render_notes(&self.notes, &Local::now().format("%Y-%m-%d").to_string())
+    }
+
+    /// Present this favorite as a `continue` picker candidate for today
+    pub fn to_candidate(&self) -> ContinueCandidate {
+        ContinueCandidate {
+            entry: TimeEntry {
+                id: 0,
+                spent_date: Local::now().format("%Y-%m-%d").to_string(),
+                hours: None,
+                notes: Some(self.render_notes()),
+                is_running: false,
+                project: Some(ProjectInfo {
+                    id: self.project_id,
+                    name: self.project_name.clone(),
+                }),
+                task: Some(TaskInfo {
+                    id: self.task_id,
+                    name: self.task_name.clone(),
+                }),
+                started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
+            },
+            days: 1,
+            favorite: Some(self.name.clone()),
+        }
+    }
+}
+
+/// Saved favorites, stored next to the usage cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Favorites {
+    version: u8,
+    #[serde(default)]
+    favorites: Vec<Favorite>,
+}
+
+impl Default for Favorites {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Favorites {
+    /// Create an empty favorites list
+    pub fn new() -> Self {
+        Self {
+            version: FAVORITES_FILE_VERSION,
+            favorites: Vec::new(),
+        }
+    }
+
+    /// Load favorites from disk, returns an empty list if the file doesn't exist
+    ///
+    /// Unlike the usage cache, a corrupt file is an error so it never gets
+    /// overwritten with an empty list.
+    pub fn load() -> Result<Self> {
+        let path = favorites_file_path()?;
+        if !path.exists() {
+            debug!("No favorites file found");
+            return Ok(Self::new());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let favorites: Favorites = serde_json::from_str(&contents).map_err(|e| {
+            HarjiraError::Config(format!(
+                "Failed to parse favorites file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        if favorites.version > FAVORITES_FILE_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "Favorites file version {} is newer than supported version {}",
+                favorites.version, FAVORITES_FILE_VERSION
+            )));
+        }
+
+        debug!("Loaded {} favorites", favorites.favorites.len());
+        Ok(favorites)
+    }
+
+    /// Save favorites to disk
+    pub fn save(&self) -> Result<()> {
+        let path = favorites_file_path()?;
+
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(self)?)?;
+
+        debug!("Saved favorites to {}", path.display());
+        Ok(())
+    }
+
+    /// All favorites, in the order they were added
+    pub fn all(&self) -> &[Favorite] {
+        &self.favorites
+    }
+
+    /// Look up a favorite by name (a leading `@` is ignored)
+    pub fn get(&self, name: &str) -> Option<&Favorite> {
+        let name = normalize_name(name);
+        self.favorites
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(&name))
+    }
+
+    /// Add a favorite, replacing any existing one with the same name
+    ///
+    /// Returns true if an existing favorite was replaced.
+    pub fn add(&mut self, mut favorite: Favorite) -> bool {
+        favorite.name = normalize_name(&favorite.name);
+        match self
+            .favorites
+            .iter_mut()
+            .find(|f| f.name.eq_ignore_ascii_case(&favorite.name))
+        {
+            Some(existing) => {
+                *existing = favorite;
+                true
+            }
+            None => {
+                self.favorites.push(favorite);
+                false
+            }
+        }
+    }
+
+    /// Remove a favorite by name, returns false if it didn't exist
+    pub fn remove(&mut self, name: &str) -> bool {
+        let name = normalize_name(name);
+        let before = self.favorites.len();
+        self.favorites
+            .retain(|f| !f.name.eq_ignore_ascii_case(&name));
+        self.favorites.len() != before
+    }
+}
+
+/// Strip the `@` shorthand and surrounding whitespace from a favorite name
+pub fn normalize_name(name: &str) -> String {
+    name.trim().trim_start_matches('@').trim().to_string()
+}
+
+/// Validate a favorite name (letters, digits, `-` and `_`)
+pub fn validate_name(name: &str) -> std::result::Result<(), String> {
+    let name = normalize_name(name);
+    if name.is_empty() {
+        Err("Name cannot be empty".to_string())
+    } else if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        Err("Name may only contain letters, digits, '-' and '_'".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Fill in template placeholders in favorite notes
+pub fn render_notes(template: &str, date: &str) -> String {
+    template.replace("{date}", date)
+}
+
+/// Get the path to the favorites file
+fn favorites_file_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("favorites.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn favorite(name: &str) -> Favorite {
+        Favorite {
+            name: name.to_string(),
+            project_id: 1,
+            project_name: "Internal".to_string(),
+            task_id: 2,
+            task_name: "Meetings".to_string(),
+            notes: "Daily standup".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_add_and_get() {
+        let mut favorites = Favorites::new();
+        assert!(!favorites.add(favorite("@standup")));
+
+        assert_eq!(favorites.all().len(), 1);
+        assert_eq!(favorites.all()[0].name, "standup");
+        assert!(favorites.get("@standup").is_some());
+        assert!(favorites.get("Standup").is_some());
+        assert!(favorites.get("review").is_none());
+    }
+
+    #[test]
+    fn test_add_replaces_existing() {
+        let mut favorites = Favorites::new();
+        favorites.add(favorite("standup"));
+
+        let mut updated = favorite("standup");
+        updated.notes = "Standup + planning".to_string();
+        assert!(favorites.add(updated));
+
+        assert_eq!(favorites.all().len(), 1);
+        assert_eq!(
+            favorites.get("standup").unwrap().notes,
+            "Standup + planning"
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut favorites = Favorites::new();
+        favorites.add(favorite("standup"));
+        assert!(favorites.remove("@standup"));
+        assert!(!favorites.remove("standup"));
+        assert!(favorites.all().is_empty());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("standup").is_ok());
+        assert!(validate_name("@code-review_2").is_ok());
+        assert!(validate_name("@").is_err());
+        assert!(validate_name("two words").is_err());
+    }
+
+    #[test]
+    fn test_render_notes() {
+        assert_eq!(
+            render_notes("Standup {date}", "2025-01-10"),
+            "Standup 2025-01-10"
+        );
+        assert_eq!(render_notes("Standup", "2025-01-10"), "Standup");
+    }
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let mut favorites = Favorites::new();
+        favorites.add(favorite("standup"));
+
+        let json = serde_json::to_string(&favorites).unwrap();
+        let loaded: Favorites = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.all(), favorites.all());
+    }
+}
@@ -0,0 +1,50 @@
+//! File helpers shared by the caches and state files
+
+use crate::error::Result;
+use std::path::Path;
+
+/// Write `contents` to `path` readable by the user only
+///
+/// Creates the parent directory if needed. The contents go to a temporary
+/// file first and are renamed into place, so a crash never leaves a half
+/// written file behind.
+pub fn write_private_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, contents)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&temp_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_private_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("harv").join("state.json");
+
+        write_private_atomic(&path, "{}").unwrap();
+        write_private_atomic(&path, "[]").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[]");
+        assert!(!path.with_extension("tmp").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use crate::error::{HarjiraError, Result};
 use crate::models::Commit;
-use chrono::{Local, TimeZone};
-use git2::{BranchType, Repository};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use git2::{BranchType, DiffOptions, Repository};
 use log::{debug, info, warn};
+use regex::Regex;
 use std::collections::HashSet;
 use std::env;
 
@@ -46,18 +47,51 @@ pub fn discover_repositories(configured_repos: &[String]) -> Result<Vec<String>>
     }
 }
 
+/// Commits left out when scanning, see `[git]` in the config
+#[derive(Debug, Default)]
+pub struct CommitFilter {
+    pub skip_merges: bool,
+    pub skip_reverts: bool,
+    /// Matched against the subject line
+    pub ignore: Vec<Regex>,
+}
+
+impl CommitFilter {
+    /// Whether a commit with `message` and `parents` parent commits is left out
+    pub fn skips(&self, message: &str, parents: usize) -> bool {
+        let subject = message.lines().next().unwrap_or_default().trim();
+        (self.skip_merges && parents > 1)
+            || (self.skip_reverts && is_revert(subject))
+            || self.ignore.iter().any(|pattern| pattern.is_match(subject))
+    }
+}
+
+/// `Revert "..."` as written by `git revert`, or a conventional `revert:` commit
+fn is_revert(subject: &str) -> bool {
+    let lower = subject.to_lowercase();
+    lower.starts_with("revert \"") || lower.starts_with("revert:") || lower.starts_with("revert(")
+}
+
 /// Get all commits from today across all branches in a repository
-pub fn get_todays_commits(repo_path: &str) -> Result<Vec<Commit>> {
+pub fn get_todays_commits(repo_path: &str, filter: &CommitFilter) -> Result<Vec<Commit>> {
+    get_commits_for_date(repo_path, Local::now().date_naive(), filter)
+}
+
+/// Get all commits made on a local date across all branches in a repository
+pub fn get_commits_for_date(
+    repo_path: &str,
+    date: NaiveDate,
+    filter: &CommitFilter,
+) -> Result<Vec<Commit>> {
     let repo = Repository::open(repo_path)?;
 
-    // Calculate today's date range (00:00:00 to now)
-    let today = Local::now().date_naive();
-    let start_of_day = Local
-        .from_local_datetime(&today.and_hms_opt(0, 0, 0).unwrap())
-        .single()
-        .ok_or_else(|| HarjiraError::Git(git2::Error::from_str("Invalid datetime")))?
-        .timestamp();
-    let now = Local::now().timestamp();
+    // Calculate the date range (00:00:00 to the end of the day, or now for today)
+    let start_of_day = local_midnight(date)?;
+    let end_of_day = match date.succ_opt() {
+        Some(next) => local_midnight(next)? - 1,
+        None => i64::MAX,
+    };
+    let now = end_of_day.min(Local::now().timestamp());
 
     debug!(
         "Searching for commits between {} and {} in {}",
@@ -96,11 +130,19 @@ pub fn get_todays_commits(repo_path: &str) -> Result<Vec<Commit>> {
                 let commit = repo.find_commit(oid)?;
                 let timestamp = commit.time().seconds();
 
-                // Only include commits from today
+                // Only include commits from that day
                 if timestamp >= start_of_day && timestamp <= now {
                     seen_oids.insert(oid);
 
                     let message = commit.message().unwrap_or("").to_string();
+                    if filter.skips(&message, commit.parent_count()) {
+                        debug!(
+                            "Skipping commit {}: {}",
+                            oid,
+                            message.lines().next().unwrap_or("")
+                        );
+                        continue;
+                    }
                     let author = commit
                         .author()
                         .name()
@@ -108,7 +150,7 @@ pub fn get_todays_commits(repo_path: &str) -> Result<Vec<Commit>> {
                         .to_string();
 
                     debug!(
-                        "Found commit from today: {} by {}",
+                        "Found commit: {} by {}",
                         &commit.message().unwrap_or("")[..50.min(commit.message().unwrap_or("").len())],
                         &author
                     );
@@ -117,10 +159,11 @@ pub fn get_todays_commits(repo_path: &str) -> Result<Vec<Commit>> {
                         message,
                         author,
                         timestamp,
+                        repository: repo_path.to_string(),
                     });
                 }
 
-                // Stop walking if we've gone past today
+                // Stop walking if we've gone past the start of the day
                 if timestamp < start_of_day {
                     break;
                 }
@@ -129,23 +172,45 @@ pub fn get_todays_commits(repo_path: &str) -> Result<Vec<Commit>> {
     }
 
     // Sort by timestamp (most recent first)
-    all_commits.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    all_commits.sort_by_key(|c| std::cmp::Reverse(c.timestamp));
 
     info!(
-        "Found {} commits from today in {}",
+        "Found {} commits from {} in {}",
         all_commits.len(),
+        date,
         repo_path
     );
 
     Ok(all_commits)
 }
 
+/// Unix timestamp of local midnight at the start of `date`
+fn local_midnight(date: NaiveDate) -> Result<i64> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .ok_or_else(|| HarjiraError::Git(git2::Error::from_str("Invalid datetime")))
+}
+
 /// Get commits from today across multiple repositories
-pub fn get_commits_from_repositories(repo_paths: &[String]) -> Result<Vec<Commit>> {
+pub fn get_commits_from_repositories(
+    repo_paths: &[String],
+    filter: &CommitFilter,
+) -> Result<Vec<Commit>> {
+    get_commits_from_repositories_for_date(repo_paths, Local::now().date_naive(), filter)
+}
+
+/// Get commits made on a local date across multiple repositories
+pub fn get_commits_from_repositories_for_date(
+    repo_paths: &[String],
+    date: NaiveDate,
+    filter: &CommitFilter,
+) -> Result<Vec<Commit>> {
     let mut all_commits = Vec::new();
 
     for repo_path in repo_paths {
-        match get_todays_commits(repo_path) {
+        match get_commits_for_date(repo_path, date, filter) {
             Ok(mut commits) => {
                 all_commits.append(&mut commits);
             }
@@ -157,11 +222,105 @@ pub fn get_commits_from_repositories(repo_paths: &[String]) -> Result<Vec<Commit
     }
 
     // Sort all commits by timestamp (most recent first)
-    all_commits.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    all_commits.sort_by_key(|c| std::cmp::Reverse(c.timestamp));
 
     Ok(all_commits)
 }
 
+/// Uncommitted changes in a repository's working tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkInProgress {
+    pub repository: String,
+    pub branch: String,
+    pub files: usize,
+    /// Lines added and removed, the lines of new files included
+    pub lines: usize,
+}
+
+/// Staged, unstaged and untracked changes on the checked out branch
+///
+/// None when HEAD is detached or the working tree is clean. Ignored files
+/// don't count.
+pub fn work_in_progress(repo_path: &str) -> Result<Option<WorkInProgress>> {
+    let repo = Repository::open(repo_path)?;
+    let Ok(head) = repo.head() else {
+        return Ok(None);
+    };
+    if !head.is_branch() {
+        return Ok(None);
+    }
+    let branch = head.shorthand().unwrap_or_default().to_string();
+    let tree = head.peel_to_tree().ok();
+
+    let mut options = DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    let stats = repo
+        .diff_tree_to_workdir_with_index(tree.as_ref(), Some(&mut options))?
+        .stats()?;
+    if stats.files_changed() == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(WorkInProgress {
+        repository: repo_path.to_string(),
+        branch,
+        files: stats.files_changed(),
+        lines: stats.insertions() + stats.deletions(),
+    }))
+}
+
+/// Name of the branch checked out in the repository, None when HEAD is detached
+pub fn current_branch(repo_path: &str) -> Option<String> {
+    let repo = Repository::open(repo_path).ok()?;
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    head.shorthand().map(str::to_string)
+}
+
+/// When the repository's index was last written, e.g. by `git add` or a checkout
+pub fn index_changed_at(repo_path: &str) -> Option<DateTime<Utc>> {
+    let repo = Repository::open(repo_path).ok()?;
+    let modified = std::fs::metadata(repo.path().join("index"))
+        .ok()?
+        .modified()
+        .ok()?;
+    Some(modified.into())
+}
+
+/// GitHub "owner/repo" of the repository's origin remote, if it is on GitHub
+pub fn github_repository(repo_path: &str) -> Option<String> {
+    let repo = Repository::open(repo_path).ok()?;
+    let remote = repo.find_remote("origin").ok()?;
+    parse_github_remote(remote.url()?)
+}
+
+/// "owner/repo" from an SSH or HTTPS remote URL on a GitHub host
+fn parse_github_remote(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?,
+        // scp-like SSH syntax: git@github.com:owner/repo.git
+        None => url.split_once(':')?,
+    };
+    let host = host.rsplit('@').next()?;
+    if !host.contains("github") {
+        return None;
+    }
+
+    let mut parts = path.trim_end_matches(".git").split('/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(owner), Some(name), None) if !owner.is_empty() && !name.is_empty() => {
+            Some(format!("{}/{}", owner, name))
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,4 +350,80 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_commit_filter() {
+        let filter = CommitFilter {
+            skip_merges: true,
+            skip_reverts: true,
+            ignore: vec![regex::RegexBuilder::new(r"^chore:|\bwip\b")
+                .case_insensitive(true)
+                .build()
+                .unwrap()],
+        };
+
+        assert!(filter.skips("Merge branch 'PROJ-1'", 2));
+        assert!(filter.skips(
+            "Revert \"PROJ-2: Add cache\"\n\nThis reverts commit abc.",
+            1
+        ));
+        assert!(filter.skips("revert(api): PROJ-3", 1));
+        assert!(filter.skips("Chore: bump deps", 1));
+        assert!(filter.skips("PROJ-4 WIP", 1));
+        assert!(!filter.skips("PROJ-5: Fix login\n\nwip in the body", 1));
+        assert!(!filter.skips("Reverted cache expiry for PROJ-6", 1));
+        assert!(!CommitFilter::default().skips("Merge branch 'PROJ-1'", 2));
+    }
+
+    #[test]
+    fn test_work_in_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let repo_path = dir.path().to_str().unwrap();
+
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Dev", "dev@example.com").unwrap();
+        let commit = repo
+            .commit(None, &signature, &signature, "Initial", &tree, &[])
+            .unwrap();
+        repo.branch(
+            "feature/PROJ-12-login",
+            &repo.find_commit(commit).unwrap(),
+            false,
+        )
+        .unwrap();
+        repo.set_head("refs/heads/feature/PROJ-12-login").unwrap();
+        assert_eq!(work_in_progress(repo_path).unwrap(), None);
+        assert!(index_changed_at(repo_path).is_some());
+
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "new\nfile\n").unwrap();
+        let wip = work_in_progress(repo_path).unwrap().unwrap();
+        assert_eq!(wip.branch, "feature/PROJ-12-login");
+        assert_eq!(wip.files, 2);
+        assert_eq!(wip.lines, 3);
+    }
+
+    #[test]
+    fn test_parse_github_remote() {
+        for url in [
+            "git@github.com:acme/web.git",
+            "https://github.com/acme/web",
+            "https://token@github.com/acme/web.git",
+            "ssh://git@github.example.com/acme/web.git",
+        ] {
+            assert_eq!(
+                parse_github_remote(url).as_deref(),
+                Some("acme/web"),
+                "{}",
+                url
+            );
+        }
+        assert_eq!(parse_github_remote("git@gitlab.com:acme/web.git"), None);
+        assert_eq!(parse_github_remote("https://github.com/acme"), None);
+    }
 }
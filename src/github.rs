@@ -0,0 +1,179 @@
+//! GitHub Issues tracker
+//!
+//! Issue numbers are only unique per repository, so tickets are keyed
+//! `owner/repo#123`. Commits that just say `#123` are qualified with the
+//! repository they were made in before they get here.
+
+use crate::config::GitHubConfig;
+use crate::error::{HarjiraError, Result};
+use crate::http::ApiClient;
+use crate::models::{GitHubIssue, Ticket};
+use crate::ticket_parser;
+use crate::tracker::IssueTracker;
+use chrono::{DateTime, Duration, Utc};
+use log::debug;
+use reqwest::blocking::Response;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
+
+const DEFAULT_BASE_URL: &str = "https://api.github.com";
+
+/// Closed issues still count as recent work for this long
+const RECENT_DAYS: i64 = 14;
+
+pub struct GitHubClient {
+    client: ApiClient,
+    base_url: String,
+    /// Root of issue links, github.com or the Enterprise Server host
+    web_url: String,
+}
+
+impl GitHubClient {
+    pub fn new(config: GitHubConfig) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        );
+        // GitHub rejects requests without a user agent
+        headers.insert(USER_AGENT, HeaderValue::from_static("harv"));
+        if !config.access_token.is_empty() {
+            let auth_value = format!("Bearer {}", config.access_token);
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&auth_value).map_err(|e| {
+                    HarjiraError::Config(format!("Invalid GitHub access token: {}", e))
+                })?,
+            );
+        }
+
+        let base_url = config
+            .base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_BASE_URL)
+            .trim_end_matches('/')
+            .to_string();
+        // Enterprise Server serves the API under /api/v3 on the web host
+        let web_url = match &config.base_url {
+            Some(_) => base_url.trim_end_matches("/api/v3").to_string(),
+            None => "https://github.com".to_string(),
+        };
+
+        Ok(Self {
+            client: ApiClient::new(headers),
+            base_url,
+            web_url,
+        })
+    }
+
+    /// Send requests to another API root, e.g. a mock server in tests
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+}
+
+impl IssueTracker for GitHubClient {
+    fn name(&self) -> &str {
+        "GitHub"
+    }
+
+    fn get_issue(&self, ticket_key: &str) -> Result<Ticket> {
+        let (repository, number) = ticket_parser::github_issue(ticket_key).ok_or_else(|| {
+            HarjiraError::GitHub(format!("{} is not an owner/repo#123 issue", ticket_key))
+        })?;
+        let url = format!("{}/repos/{}/issues/{}", self.base_url, repository, number);
+        debug!("GET {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        // Private repositories the token can't see also answer 404
+        if response.status() == 404 {
+            return Err(HarjiraError::GitHub(format!(
+                "Issue {} not found. Verify the number, and that github.access_token can read {}.",
+                ticket_key, repository
+            )));
+        }
+        let issue: GitHubIssue = check_response(response)?
+            .json()
+            .map_err(|e| HarjiraError::GitHub(format!("Failed to parse issue: {}", e)))?;
+
+        debug!("Retrieved GitHub issue: {} - {}", ticket_key, issue.title);
+        Ok(issue.to_ticket(repository))
+    }
+
+    fn get_my_recent_issues(&self, max_results: u32) -> Result<Vec<Ticket>> {
+        let url = format!("{}/issues", self.base_url);
+        debug!("GET {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("filter", "assigned".to_string()),
+                ("state", "all".to_string()),
+                ("sort", "updated".to_string()),
+                ("per_page", max_results.min(100).to_string()),
+            ])
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        let issues: Vec<GitHubIssue> = check_response(response)?
+            .json()
+            .map_err(|e| HarjiraError::GitHub(format!("Failed to parse issues: {}", e)))?;
+
+        let cutoff = Utc::now() - Duration::days(RECENT_DAYS);
+        let tickets: Vec<Ticket> = issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .filter(|issue| issue.state == "open" || updated_since(issue, cutoff))
+            .filter_map(|issue| {
+                let repository = issue.repository.as_ref()?.full_name.clone();
+                Some(issue.to_ticket(&repository))
+            })
+            .collect();
+
+        debug!("Found {} recent GitHub issues", tickets.len());
+        Ok(tickets)
+    }
+
+    fn get_ticket_url(&self, ticket_key: &str) -> String {
+        match ticket_parser::github_issue(ticket_key) {
+            Some((repository, number)) => {
+                format!("{}/{}/issues/{}", self.web_url, repository, number)
+            }
+            None => self.web_url.clone(),
+        }
+    }
+}
+
+fn updated_since(issue: &GitHubIssue, cutoff: DateTime<Utc>) -> bool {
+    issue
+        .updated_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| t >= cutoff)
+}
+
+fn check_response(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string());
+
+    if status == 401 {
+        return Err(HarjiraError::Auth(
+            "GitHub authentication failed. Check github.access_token in your config.".to_string(),
+        ));
+    }
+    Err(HarjiraError::GitHub(format!("{}: {}", status, message)))
+}
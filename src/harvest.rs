@@ -1,20 +1,40 @@
 use crate::config::HarvestConfig;
-use crate::error::{HarjiraError, Result};
+use crate::error::{HarjiraError, HarvestApiError, Result};
+use crate::http::ApiClient;
+use crate::journal::{self, Change};
 use crate::models::{
-    Context, CreateStoppedTimeEntryRequest, CreateTimeEntryRequest, ExternalReference,
-    HarvestProject, HarvestTask, ProjectsResponse, TaskAssignmentsResponse, TimeEntriesResponse,
-    TimeEntry, UserProjectAssignmentsResponse,
+    AccountTaskAssignmentsResponse, Context, CreateStoppedTimeEntryRequest, CreateTimeEntryRequest,
+    ExternalReference, HarvestAccount, HarvestAccountsResponse, HarvestProject, HarvestTask,
+    HarvestUser, ProjectsResponse, TaskAssignmentsResponse, TimeEntriesResponse, TimeEntry,
+    UpdateTimeEntryRequest, UserProjectAssignmentsResponse, UserTimeEntriesResponse, UserTimeEntry,
+    UsersResponse,
 };
+use crate::status_cache;
 use chrono::Local;
 use log::{debug, info, warn};
-use reqwest::blocking::Client;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT};
+use reqwest::blocking::Response;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, RETRY_AFTER, USER_AGENT};
 use reqwest::StatusCode;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
+
+/// Task assignment requests in flight at once when fetching every project
+const TASK_FETCH_CONCURRENCY: usize = 8;
+
+/// Harvest ID endpoint listing the accounts a token has access to
+const ACCOUNTS_URL: &str = "https://id.getharvest.com/api/v2/accounts";
+
+/// User ID per access token, from the first `/users/me` of the process
+static CURRENT_USER_IDS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
 
 pub struct HarvestClient {
-    client: Client,
-    base_url: String,
+    pub(crate) client: ApiClient,
+    pub(crate) base_url: String,
     config: HarvestConfig,
+    /// The token's user, which entry queries are limited to
+    user_id: OnceLock<u64>,
 }
 
 impl HarvestClient {
@@ -46,106 +66,331 @@ impl HarvestClient {
             })?,
         );
 
-        let client = Client::builder()
-            .default_headers(headers)
-            .build()
-            .map_err(|e| HarjiraError::Harvest(format!("Failed to create HTTP client: {}", e)))?;
-
         Ok(Self {
-            client,
+            client: ApiClient::new(headers),
             base_url: "https://api.harvestapp.com/v2".to_string(),
             config,
+            user_id: OnceLock::new(),
         })
     }
 
+    /// Use this as the token's user instead of asking `/users/me`
+    pub fn with_user_id(self, user_id: u64) -> Self {
+        let _ = self.user_id.set(user_id);
+        self
+    }
+
+    /// Send requests to another API root, e.g. a mock server in tests
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// The Harvest account requests are made for
+    pub fn account_id(&self) -> &str {
+        &self.config.account_id
+    }
+
+    /// List the Harvest accounts an access token can use
+    ///
+    /// Needs no account ID, so it can fill one in. Forecast accounts are left out.
+    pub fn list_accounts(access_token: &str, user_agent: &str) -> Result<Vec<HarvestAccount>> {
+        fetch_accounts(ACCOUNTS_URL, access_token, user_agent)
+    }
+
+    /// Get the user the access token belongs to
+    pub fn get_current_user(&self) -> Result<HarvestUser> {
+        let url = format!("{}/users/me", self.base_url);
+        debug!("GET {}", url);
+
+        let response =
+            self.client.get(&url).send().map_err(|e| {
+                HarjiraError::Network(format!("Failed to fetch current user: {}", e))
+            })?;
+
+        let response = check_response(response)?;
+
+        let user: HarvestUser = response
+            .json()
+            .map_err(|e| HarjiraError::Harvest(format!("Failed to parse user response: {}", e)))?;
+        if let Ok(mut ids) = CURRENT_USER_IDS.lock() {
+            ids.insert(self.config.access_token.clone(), user.id);
+        }
+        Ok(user)
+    }
+
+    /// ID of the user the access token belongs to
+    ///
+    /// Entry queries ask for this user only, an administrator or manager token
+    /// would get the whole team's entries otherwise. Looked up once per
+    /// process, reusing the `/users/me` fetched for the timezone if there was one.
+    pub fn current_user_id(&self) -> Result<u64> {
+        if let Some(&id) = self.user_id.get() {
+            return Ok(id);
+        }
+        let known = CURRENT_USER_IDS
+            .lock()
+            .ok()
+            .and_then(|ids| ids.get(&self.config.access_token).copied());
+        let id = match known {
+            Some(id) => id,
+            None => self.get_current_user()?.id,
+        };
+        Ok(*self.user_id.get_or_init(|| id))
+    }
+
+    /// `entries` without those of other users, should Harvest send any
+    fn own_entries(&self, entries: Vec<TimeEntry>, user_id: u64) -> Vec<TimeEntry> {
+        entries
+            .into_iter()
+            .filter(|e| e.user.as_ref().is_none_or(|user| user.id == user_id))
+            .collect()
+    }
+
     /// Get all time entries for today
     pub fn get_todays_time_entries(&self) -> Result<Vec<TimeEntry>> {
         let today = Local::now().format("%Y-%m-%d").to_string();
-        let url = format!(
-            "{}/time_entries?from={}&to={}",
-            self.base_url, today, today
-        );
+        self.get_time_entries_for_date(&today)
+    }
+
+    /// Get all time entries spent on `date` (YYYY-MM-DD)
+    pub fn get_time_entries_for_date(&self, date: &str) -> Result<Vec<TimeEntry>> {
+        let user_id = self.current_user_id()?;
+        let url = format!("{}/time_entries?from={}&to={}", self.base_url, date, date);
 
         debug!("GET {}", url);
 
         let response = self
             .client
             .get(&url)
+            .query(&[("user_id", user_id)])
             .send()
-            .map_err(|e| HarjiraError::Harvest(format!("Request failed: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "API request failed with status {}: {}",
-                status, error_text
-            )));
-        }
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        let response = check_response(response)?;
 
         let entries_response: TimeEntriesResponse = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse time entries response: {}", e))
         })?;
 
-        debug!(
-            "Retrieved {} time entries for today",
-            entries_response.time_entries.len()
-        );
+        let entries = self.own_entries(entries_response.time_entries, user_id);
+        debug!("Retrieved {} time entries for {}", entries.len(), date);
 
-        Ok(entries_response.time_entries)
+        Ok(entries)
     }
 
-    /// Get the currently running time entry, if any
+    /// Get one time entry by ID
+    pub fn get_time_entry(&self, entry_id: u64) -> Result<TimeEntry> {
+        let url = format!("{}/time_entries/{}", self.base_url, entry_id);
+        debug!("GET {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        let response = check_response(response)?;
+
+        response
+            .json()
+            .map_err(|e| HarjiraError::Harvest(format!("Failed to parse time entry: {}", e)))
+    }
+
+    /// The entry before a change, or None if it can't be fetched
+    fn previous_entry(&self, entry_id: u64) -> Option<TimeEntry> {
+        match self.get_time_entry(entry_id) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                debug!("Couldn't fetch entry {} for the journal: {}", entry_id, e);
+                None
+            }
+        }
+    }
+
+    /// Note a change in the journal, `entry` is what it left behind
+    fn record(&self, change: Change, entry: Option<&TimeEntry>) {
+        let summary = entry.map(journal::summarize).unwrap_or_default();
+        journal::record(self.account_id(), change, summary);
+    }
+
+    /// Active users in the account, which needs an administrator or manager token
+    pub fn get_users(&self) -> Result<Vec<HarvestUser>> {
+        let mut users = Vec::new();
+        let mut page = 1;
+        loop {
+            let url = format!("{}/users", self.base_url);
+            debug!("GET {} (page {})", url, page);
+
+            let response = self
+                .client
+                .get(&url)
+                .query(&[
+                    ("is_active", "true".to_string()),
+                    ("page", page.to_string()),
+                ])
+                .send()
+                .map_err(|e| HarjiraError::Network(format!("Failed to fetch users: {}", e)))?;
+            let response: UsersResponse = check_team_access(response)?
+                .json()
+                .map_err(|e| HarjiraError::Harvest(format!("Failed to parse users: {}", e)))?;
+
+            users.extend(response.users);
+            match response.next_page {
+                Some(next) if next > page => page = next,
+                _ => break,
+            }
+        }
+
+        debug!("Retrieved {} users", users.len());
+        Ok(users)
+    }
+
+    /// The active user with this ID or email address (case-insensitive)
+    pub fn find_user(&self, id_or_email: &str) -> Result<HarvestUser> {
+        let query = id_or_email.trim();
+        let id: Option<u64> = query.parse().ok();
+        self.get_users()?
+            .into_iter()
+            .find(|user| {
+                Some(user.id) == id
+                    || user
+                        .email
+                        .as_deref()
+                        .is_some_and(|email| email.eq_ignore_ascii_case(query))
+            })
+            .ok_or_else(|| {
+                HarjiraError::InvalidEntry(format!(
+                    "No active Harvest user with ID or email '{}'",
+                    query
+                ))
+            })
+    }
+
+    /// Time entries of everyone in the account, filtered by `query`
+    ///
+    /// E.g. `is_running=true` or `from`/`to`, plus `user_id` for one user. Like
+    /// `get_users` this needs an administrator or manager token.
+    pub fn get_team_time_entries(&self, query: &[(&str, String)]) -> Result<Vec<UserTimeEntry>> {
+        let mut entries = Vec::new();
+        let mut page = 1;
+        loop {
+            let url = format!("{}/time_entries", self.base_url);
+            debug!("GET {} {:?} (page {})", url, query, page);
+
+            let response = self
+                .client
+                .get(&url)
+                .query(query)
+                .query(&[("page", page.to_string())])
+                .send()
+                .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+            let response: UserTimeEntriesResponse =
+                check_team_access(response)?.json().map_err(|e| {
+                    HarjiraError::Harvest(format!("Failed to parse time entries response: {}", e))
+                })?;
+
+            entries.extend(response.time_entries);
+            match response.next_page {
+                Some(next) if next > page => page = next,
+                _ => break,
+            }
+        }
+
+        debug!("Retrieved {} team time entries", entries.len());
+        Ok(entries)
+    }
+
+    /// Get the currently running time entry, if any, whatever day it started
     pub fn get_running_timer(&self) -> Result<Option<TimeEntry>> {
-        let entries = self.get_todays_time_entries()?;
-        Ok(entries.into_iter().find(|e| e.is_running))
+        Ok(self.get_running_timers()?.into_iter().next())
     }
 
-    /// Get time entries for a specific date range
-    pub fn get_time_entries_range(
-        &self,
-        from_date: &str,
-        to_date: &str,
-        _ctx: &Context,
-    ) -> Result<Vec<TimeEntry>> {
-        let url = format!(
-            "{}/time_entries?from={}&to={}",
-            self.base_url, from_date, to_date
-        );
+    /// Get all running time entries, newest first
+    ///
+    /// Asks Harvest for `is_running=true` instead of filtering today's
+    /// entries, so a timer started before midnight is found too.
+    pub fn get_running_timers(&self) -> Result<Vec<TimeEntry>> {
+        let user_id = self.current_user_id()?;
+        let url = format!("{}/time_entries?is_running=true", self.base_url);
 
         debug!("GET {}", url);
 
         let response = self
             .client
             .get(&url)
+            .query(&[("user_id", user_id)])
             .send()
-            .map_err(|e| HarjiraError::Harvest(format!("Request failed: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "API request failed with status {}: {}",
-                status, error_text
-            )));
-        }
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        let response = check_response(response)?;
 
         let entries_response: TimeEntriesResponse = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse time entries response: {}", e))
         })?;
 
+        // The filter is trusted, but a stopped entry must never be reported as running
+        let mut running: Vec<TimeEntry> = self
+            .own_entries(entries_response.time_entries, user_id)
+            .into_iter()
+            .filter(|e| e.is_running)
+            .collect();
+        running.sort_by(|a, b| b.spent_date.cmp(&a.spent_date));
+
+        debug!("Found {} running time entries", running.len());
+        Ok(running)
+    }
+
+    /// Get time entries for a specific date range, following every page
+    pub fn get_time_entries_range(
+        &self,
+        from_date: &str,
+        to_date: &str,
+        _ctx: &Context,
+    ) -> Result<Vec<TimeEntry>> {
+        let user_id = self.current_user_id()?;
+        let mut entries = Vec::new();
+        let mut page = 1;
+        loop {
+            let url = format!("{}/time_entries", self.base_url);
+            debug!(
+                "GET {} from {} to {} (page {})",
+                url, from_date, to_date, page
+            );
+
+            let response = self
+                .client
+                .get(&url)
+                .query(&[
+                    ("user_id", user_id.to_string()),
+                    ("from", from_date.to_string()),
+                    ("to", to_date.to_string()),
+                    ("page", page.to_string()),
+                ])
+                .send()
+                .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+            let response = check_response(response)?;
+
+            let entries_response: TimeEntriesResponse = response.json().map_err(|e| {
+                HarjiraError::Harvest(format!("Failed to parse time entries response: {}", e))
+            })?;
+
+            entries.extend(self.own_entries(entries_response.time_entries, user_id));
+            match entries_response.next_page {
+                Some(next) if next > page => page = next,
+                _ => break,
+            }
+        }
+
         debug!(
             "Retrieved {} time entries from {} to {}",
-            entries_response.time_entries.len(),
+            entries.len(),
             from_date,
             to_date
         );
 
-        Ok(entries_response.time_entries)
+        Ok(entries)
     }
 
     /// Create a new time entry (start a timer)
@@ -169,9 +414,15 @@ impl HarvestClient {
                 group_id: "jira".to_string(),
                 permalink: jira_url.to_string(),
             }),
+            user_id: None,
         };
 
         if ctx.dry_run {
+            crate::plan::record(
+                "POST",
+                &format!("{}/time_entries", self.base_url),
+                Some(&request),
+            );
             info!("[DRY RUN] Would create time entry:");
             info!("  Project ID: {:?}", request.project_id);
             info!("  Task ID: {:?}", request.task_id);
@@ -186,6 +437,10 @@ impl HarvestClient {
                 project: None,
                 task: None,
                 started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
             });
         }
 
@@ -198,23 +453,16 @@ impl HarvestClient {
             .post(&url)
             .json(&request)
             .send()
-            .map_err(|e| HarjiraError::Harvest(format!("Failed to create time entry: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to create time entry ({}): {}",
-                status, error_text
-            )));
-        }
+            .map_err(|e| HarjiraError::Network(format!("Failed to create time entry: {}", e)))?;
+
+        let response = check_response(response)?;
 
         let entry: TimeEntry = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse created time entry: {}", e))
         })?;
 
+        status_cache::invalidate();
+        self.record(Change::Created { entry_id: entry.id }, Some(&entry));
         info!("Created time entry: {}", notes);
         Ok(entry)
     }
@@ -222,6 +470,11 @@ impl HarvestClient {
     /// Stop a running timer
     pub fn stop_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry> {
         if ctx.dry_run {
+            crate::plan::record::<()>(
+                "PATCH",
+                &format!("{}/time_entries/{}/stop", self.base_url, entry_id),
+                None,
+            );
             info!("[DRY RUN] Would stop time entry {}", entry_id);
             return Ok(TimeEntry {
                 id: entry_id,
@@ -232,6 +485,10 @@ impl HarvestClient {
                 project: None,
                 task: None,
                 started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
             });
         }
 
@@ -239,24 +496,17 @@ impl HarvestClient {
         debug!("PATCH {}", url);
 
         let response = self.client.patch(&url).send().map_err(|e| {
-            HarjiraError::Harvest(format!("Failed to stop time entry: {}", e))
+            HarjiraError::Network(format!("Failed to stop time entry: {}", e))
         })?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to stop time entry ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let entry: TimeEntry = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse stopped time entry: {}", e))
         })?;
 
+        status_cache::invalidate();
+        self.record(Change::Stopped { entry_id }, Some(&entry));
         info!("Stopped time entry {}", entry_id);
         Ok(entry)
     }
@@ -266,6 +516,11 @@ impl HarvestClient {
     /// and sets the timer to running.
     pub fn restart_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry> {
         if ctx.dry_run {
+            crate::plan::record::<()>(
+                "PATCH",
+                &format!("{}/time_entries/{}/restart", self.base_url, entry_id),
+                None,
+            );
             info!("[DRY RUN] Would restart time entry {}", entry_id);
             return Ok(TimeEntry {
                 id: entry_id,
@@ -276,6 +531,10 @@ impl HarvestClient {
                 project: None,
                 task: None,
                 started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
             });
         }
 
@@ -283,24 +542,17 @@ impl HarvestClient {
         debug!("PATCH {}", url);
 
         let response = self.client.patch(&url).send().map_err(|e| {
-            HarjiraError::Harvest(format!("Failed to restart time entry: {}", e))
+            HarjiraError::Network(format!("Failed to restart time entry: {}", e))
         })?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to restart time entry ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let entry: TimeEntry = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse restarted time entry: {}", e))
         })?;
 
+        status_cache::invalidate();
+        self.record(Change::Restarted { entry_id }, Some(&entry));
         info!(
             "Restarted time entry {} on date {}",
             entry_id, entry.spent_date
@@ -336,9 +588,15 @@ impl HarvestClient {
             spent_date: today,
             notes: notes.clone(),
             external_reference: None,
+            user_id: None,
         };
 
         if ctx.dry_run {
+            crate::plan::record(
+                "POST",
+                &format!("{}/time_entries", self.base_url),
+                Some(&request),
+            );
             info!("[DRY RUN] Would create time entry:");
             info!("  Project ID: {}", project_id);
             info!("  Task ID: {}", task_id);
@@ -352,6 +610,10 @@ impl HarvestClient {
                 project: entry.project.clone(),
                 task: entry.task.clone(),
                 started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
             });
         }
 
@@ -364,27 +626,127 @@ impl HarvestClient {
             .post(&url)
             .json(&request)
             .send()
-            .map_err(|e| HarjiraError::Harvest(format!("Failed to create time entry: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to create time entry ({}): {}",
-                status, error_text
-            )));
-        }
+            .map_err(|e| HarjiraError::Network(format!("Failed to create time entry: {}", e)))?;
+
+        let response = check_response(response)?;
 
         let new_entry: TimeEntry = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse created time entry: {}", e))
         })?;
 
+        status_cache::invalidate();
+        self.record(
+            Change::Created {
+                entry_id: new_entry.id,
+            },
+            Some(&new_entry),
+        );
         info!("Started timer: {}", notes);
         Ok(new_entry)
     }
 
+    /// Set the hours on an existing time entry
+    pub fn update_time_entry_hours(
+        &self,
+        entry_id: u64,
+        hours: f64,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let update = UpdateTimeEntryRequest {
+            hours: Some(hours),
+            ..UpdateTimeEntryRequest::default()
+        };
+        self.update_time_entry(entry_id, &update, ctx)
+    }
+
+    /// Change the hours and/or notes of an existing time entry
+    pub fn update_time_entry(
+        &self,
+        entry_id: u64,
+        update: &UpdateTimeEntryRequest,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        if ctx.dry_run {
+            crate::plan::record(
+                "PATCH",
+                &format!("{}/time_entries/{}", self.base_url, entry_id),
+                Some(update),
+            );
+            info!(
+                "[DRY RUN] Would update time entry {}: {:?}",
+                entry_id, update
+            );
+            return Ok(TimeEntry {
+                id: entry_id,
+                spent_date: Local::now().format("%Y-%m-%d").to_string(),
+                hours: update.hours,
+                notes: update.notes.clone(),
+                is_running: false,
+                project: None,
+                task: None,
+                started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
+            });
+        }
+
+        // Kept in the journal so the change can be undone
+        let previous = self.previous_entry(entry_id);
+
+        let url = format!("{}/time_entries/{}", self.base_url, entry_id);
+        debug!("PATCH {}", url);
+
+        let response =
+            self.client.patch(&url).json(update).send().map_err(|e| {
+                HarjiraError::Network(format!("Failed to update time entry: {}", e))
+            })?;
+
+        let response = check_response(response)?;
+
+        let entry: TimeEntry = response.json().map_err(|e| {
+            HarjiraError::Harvest(format!("Failed to parse updated time entry: {}", e))
+        })?;
+
+        status_cache::invalidate();
+        self.record(Change::Updated { entry_id, previous }, Some(&entry));
+        info!("Updated time entry {}: {:?}", entry_id, update);
+        Ok(entry)
+    }
+
+    /// Delete a time entry
+    pub fn delete_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<()> {
+        if ctx.dry_run {
+            crate::plan::record::<()>(
+                "DELETE",
+                &format!("{}/time_entries/{}", self.base_url, entry_id),
+                None,
+            );
+            info!("[DRY RUN] Would delete time entry {}", entry_id);
+            return Ok(());
+        }
+
+        // Kept in the journal so the change can be undone
+        let previous = self.previous_entry(entry_id);
+
+        let url = format!("{}/time_entries/{}", self.base_url, entry_id);
+        debug!("DELETE {}", url);
+
+        let response =
+            self.client.delete(&url).send().map_err(|e| {
+                HarjiraError::Network(format!("Failed to delete time entry: {}", e))
+            })?;
+
+        check_response(response)?;
+
+        status_cache::invalidate();
+        let deleted = previous.clone();
+        self.record(Change::Deleted { entry_id, previous }, deleted.as_ref());
+        info!("Deleted time entry {}", entry_id);
+        Ok(())
+    }
+
     /// Calculate total hours logged today
     pub fn get_total_hours_today(&self) -> Result<f64> {
         let entries = self.get_todays_time_entries()?;
@@ -399,7 +761,7 @@ impl HarvestClient {
         debug!("GET {}", url);
 
         let response = self.client.get(&url).send().map_err(|e| {
-            HarjiraError::Harvest(format!("Failed to fetch projects: {}", e))
+            HarjiraError::Network(format!("Failed to fetch projects: {}", e))
         })?;
 
         // If 403 Forbidden, fall back to user project assignments
@@ -409,16 +771,7 @@ impl HarvestClient {
             return self.get_user_project_assignments();
         }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to fetch projects ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let projects_response: ProjectsResponse = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse projects response: {}", e))
@@ -438,19 +791,10 @@ impl HarvestClient {
         debug!("GET {} (fallback method)", url);
 
         let response = self.client.get(&url).send().map_err(|e| {
-            HarjiraError::Harvest(format!("Failed to fetch user project assignments: {}", e))
+            HarjiraError::Network(format!("Failed to fetch user project assignments: {}", e))
         })?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to fetch user project assignments ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let assignments_response: UserProjectAssignmentsResponse = response.json().map_err(|e| {
             HarjiraError::Harvest(format!(
@@ -463,7 +807,14 @@ impl HarvestClient {
             .project_assignments
             .into_iter()
             .filter(|pa| pa.is_active)
-            .map(|pa| pa.project)
+            .map(|pa| {
+                // Assignments carry the client next to the project rather than inside it
+                let mut project = pa.project;
+                if project.client.is_none() {
+                    project.client = pa.client;
+                }
+                project
+            })
             .collect();
 
         debug!("Retrieved {} projects via user assignments", projects.len());
@@ -480,7 +831,7 @@ impl HarvestClient {
         debug!("GET {}", url);
 
         let response = self.client.get(&url).send().map_err(|e| {
-            HarjiraError::Harvest(format!("Failed to fetch tasks: {}", e))
+            HarjiraError::Network(format!("Failed to fetch tasks: {}", e))
         })?;
 
         // If 403 Forbidden, try to get tasks from user assignments
@@ -492,16 +843,7 @@ impl HarvestClient {
             return self.get_user_project_tasks(project_id);
         }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to fetch tasks ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let tasks_response: TaskAssignmentsResponse = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse tasks response: {}", e))
@@ -514,6 +856,7 @@ impl HarvestClient {
             .map(|ta| HarvestTask {
                 id: ta.task.id,
                 name: ta.task.name,
+                billable: ta.billable,
             })
             .collect();
 
@@ -528,19 +871,10 @@ impl HarvestClient {
         debug!("GET {} (to fetch tasks for project {})", url, project_id);
 
         let response = self.client.get(&url).send().map_err(|e| {
-            HarjiraError::Harvest(format!("Failed to fetch user project assignments: {}", e))
+            HarjiraError::Network(format!("Failed to fetch user project assignments: {}", e))
         })?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to fetch user project assignments ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let assignments_response: UserProjectAssignmentsResponse = response.json().map_err(|e| {
             HarjiraError::Harvest(format!(
@@ -569,6 +903,7 @@ impl HarvestClient {
             .map(|ta| HarvestTask {
                 id: ta.task.id,
                 name: ta.task.name,
+                billable: ta.billable,
             })
             .collect();
 
@@ -583,12 +918,26 @@ impl HarvestClient {
     /// Get all available tasks across all projects
     /// Optimized to use a single API call when using limited permissions
     pub fn get_all_available_tasks(&self) -> Result<Vec<(u64, HarvestTask)>> {
+        self.get_all_available_tasks_with_progress(|_, _| {})
+    }
+
+    /// Same as `get_all_available_tasks`, reporting `(done, total)` progress
+    /// while fetching pages or projects
+    pub fn get_all_available_tasks_with_progress(
+        &self,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<(u64, HarvestTask)>> {
+        // One paginated request for the whole account when the token allows it
+        if let Some(tasks) = self.get_account_task_assignments(&mut on_progress)? {
+            return Ok(tasks);
+        }
+
         // Try direct projects endpoint first
         let url = format!("{}/projects?is_active=true", self.base_url);
         debug!("GET {}", url);
 
         let response = self.client.get(&url).send().map_err(|e| {
-            HarjiraError::Harvest(format!("Failed to fetch projects: {}", e))
+            HarjiraError::Network(format!("Failed to fetch projects: {}", e))
         })?;
 
         // If 403 Forbidden, use optimized user assignments path
@@ -598,33 +947,56 @@ impl HarvestClient {
         }
 
         // If we have full access, fetch projects then tasks individually
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to fetch projects ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let projects_response: ProjectsResponse = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse projects response: {}", e))
         })?;
 
+        // Fetch several projects at a time, results are kept in project order
+        let projects = projects_response.projects;
+        let total = projects.len();
+        let mut results: Vec<Option<Result<Vec<HarvestTask>>>> = (0..total).map(|_| None).collect();
+        on_progress(0, total);
+
+        let next = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::channel();
+        thread::scope(|scope| {
+            for _ in 0..TASK_FETCH_CONCURRENCY.min(total) {
+                let tx = tx.clone();
+                let (next, projects) = (&next, &projects);
+                scope.spawn(move || loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(project) = projects.get(idx) else {
+                        break;
+                    };
+                    if tx.send((idx, self.get_project_tasks(project.id))).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(tx);
+
+            // Progress is reported from this thread, so the callback needn't be Send
+            for (done, (idx, result)) in rx.iter().enumerate() {
+                results[idx] = Some(result);
+                on_progress(done + 1, total);
+            }
+        });
+
         let mut all_tasks = Vec::new();
-        for project in projects_response.projects {
-            match self.get_project_tasks(project.id) {
-                Ok(tasks) => {
+        for (project, result) in projects.iter().zip(results) {
+            match result {
+                Some(Ok(tasks)) => {
                     for task in tasks {
                         all_tasks.push((project.id, task));
                     }
                 }
-                Err(e) => {
+                Some(Err(e)) => {
                     warn!("Failed to fetch tasks for project {}: {}", project.id, e);
                     // Continue with other projects (non-fatal)
                 }
+                None => {}
             }
         }
 
@@ -632,6 +1004,64 @@ impl HarvestClient {
         Ok(all_tasks)
     }
 
+    /// Every active task assignment in the account, None if the token may not list them
+    fn get_account_task_assignments(
+        &self,
+        on_progress: &mut impl FnMut(usize, usize),
+    ) -> Result<Option<Vec<(u64, HarvestTask)>>> {
+        let mut all_tasks = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let url = format!(
+                "{}/task_assignments?is_active=true&page={}",
+                self.base_url, page
+            );
+            debug!("GET {}", url);
+
+            let response = self.client.get(&url).send().map_err(|e| {
+                HarjiraError::Network(format!("Failed to fetch task assignments: {}", e))
+            })?;
+
+            if response.status() == StatusCode::FORBIDDEN {
+                debug!("Access denied to /v2/task_assignments. Fetching per project.");
+                return Ok(None);
+            }
+
+            let response = check_response(response)?;
+            let assignments: AccountTaskAssignmentsResponse = response.json().map_err(|e| {
+                HarjiraError::Harvest(format!("Failed to parse task assignments response: {}", e))
+            })?;
+
+            for assignment in assignments.task_assignments {
+                if assignment.is_active {
+                    all_tasks.push((
+                        assignment.project.id,
+                        HarvestTask {
+                            id: assignment.task.id,
+                            name: assignment.task.name,
+                            billable: assignment.billable,
+                        },
+                    ));
+                }
+            }
+
+            let total_pages = assignments.total_pages.unwrap_or(page) as usize;
+            on_progress(page as usize, total_pages.max(page as usize));
+
+            match assignments.next_page {
+                Some(next) if next > page => page = next,
+                _ => break,
+            }
+        }
+
+        debug!(
+            "Retrieved {} total task assignments via account-wide endpoint",
+            all_tasks.len()
+        );
+        Ok(Some(all_tasks))
+    }
+
     /// Optimized method to get all projects and tasks in a single API call
     /// Used when PAT has limited permissions
     fn get_all_tasks_from_user_assignments(&self) -> Result<Vec<(u64, HarvestTask)>> {
@@ -639,19 +1069,10 @@ impl HarvestClient {
         debug!("GET {} (optimized - fetching all projects and tasks)", url);
 
         let response = self.client.get(&url).send().map_err(|e| {
-            HarjiraError::Harvest(format!("Failed to fetch user project assignments: {}", e))
+            HarjiraError::Network(format!("Failed to fetch user project assignments: {}", e))
         })?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to fetch user project assignments ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let assignments_response: UserProjectAssignmentsResponse = response.json().map_err(|e| {
             HarjiraError::Harvest(format!(
@@ -674,6 +1095,7 @@ impl HarvestClient {
                         HarvestTask {
                             id: task_assignment.task.id,
                             name: task_assignment.task.name,
+                            billable: task_assignment.billable,
                         },
                     ));
                 }
@@ -708,12 +1130,59 @@ impl HarvestClient {
         let request = CreateStoppedTimeEntryRequest {
             project_id,
             task_id,
-            spent_date: today.clone(),
+            spent_date: today,
             notes: description.to_string(),
             hours,
+            external_reference: None,
+            user_id: None,
         };
+        self.create_stopped(request, ctx)
+    }
 
+    /// Log finished work on a ticket today, on the default project and task
+    ///
+    /// Noted and linked like the timers `create_time_entry` starts.
+    pub fn create_stopped_ticket_entry(
+        &self,
+        jira_ticket: &str,
+        description: &str,
+        jira_url: &str,
+        hours: f64,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let (Some(project_id), Some(task_id)) = (self.config.project_id, self.config.task_id)
+        else {
+            return Err(HarjiraError::Config(
+                "Logging finished work needs project_id and task_id in [harvest]".to_string(),
+            ));
+        };
+        let request = CreateStoppedTimeEntryRequest {
+            project_id,
+            task_id,
+            spent_date: Local::now().format("%Y-%m-%d").to_string(),
+            notes: format!("{} - {}", jira_ticket, description),
+            hours,
+            external_reference: Some(ExternalReference {
+                id: jira_ticket.to_string(),
+                group_id: "jira".to_string(),
+                permalink: jira_url.to_string(),
+            }),
+            user_id: None,
+        };
+        self.create_stopped(request, ctx)
+    }
+
+    fn create_stopped(
+        &self,
+        request: CreateStoppedTimeEntryRequest,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
         if ctx.dry_run {
+            crate::plan::record(
+                "POST",
+                &format!("{}/time_entries", self.base_url),
+                Some(&request),
+            );
             info!("[DRY RUN] Would create stopped time entry:");
             info!("  Project ID: {}", request.project_id);
             info!("  Task ID: {}", request.task_id);
@@ -728,6 +1197,10 @@ impl HarvestClient {
                 project: None,
                 task: None,
                 started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
             });
         }
 
@@ -741,25 +1214,21 @@ impl HarvestClient {
             .json(&request)
             .send()
             .map_err(|e| {
-                HarjiraError::Harvest(format!("Failed to create time entry: {}", e))
+                HarjiraError::Network(format!("Failed to create time entry: {}", e))
             })?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to create time entry ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let entry: TimeEntry = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse created time entry: {}", e))
         })?;
 
-        info!("Created time entry: {} ({:.2}h)", description, hours);
+        status_cache::invalidate();
+        self.record(Change::Created { entry_id: entry.id }, Some(&entry));
+        info!(
+            "Created time entry: {} ({:.2}h)",
+            request.notes, request.hours
+        );
         Ok(entry)
     }
 
@@ -771,6 +1240,19 @@ impl HarvestClient {
         task_id: u64,
         spent_date: &str,
         ctx: &Context,
+    ) -> Result<TimeEntry> {
+        self.create_time_entry_for_user(None, description, project_id, task_id, spent_date, ctx)
+    }
+
+    /// Like `create_time_entry_with_date`, for `user_id` instead of the token's user
+    pub fn create_time_entry_for_user(
+        &self,
+        user_id: Option<u64>,
+        description: &str,
+        project_id: u64,
+        task_id: u64,
+        spent_date: &str,
+        ctx: &Context,
     ) -> Result<TimeEntry> {
         let request = CreateTimeEntryRequest {
             project_id: Some(project_id),
@@ -778,10 +1260,19 @@ impl HarvestClient {
             spent_date: spent_date.to_string(),
             notes: description.to_string(),
             external_reference: None,
+            user_id,
         };
 
         if ctx.dry_run {
+            crate::plan::record(
+                "POST",
+                &format!("{}/time_entries", self.base_url),
+                Some(&request),
+            );
             info!("[DRY RUN] Would create time entry:");
+            if let Some(user_id) = user_id {
+                info!("  User ID: {}", user_id);
+            }
             info!("  Project ID: {}", project_id);
             info!("  Task ID: {}", task_id);
             info!("  Date: {}", spent_date);
@@ -795,6 +1286,10 @@ impl HarvestClient {
                 project: None,
                 task: None,
                 started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
             });
         }
 
@@ -807,23 +1302,16 @@ impl HarvestClient {
             .post(&url)
             .json(&request)
             .send()
-            .map_err(|e| HarjiraError::Harvest(format!("Failed to create time entry: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to create time entry ({}): {}",
-                status, error_text
-            )));
-        }
+            .map_err(|e| HarjiraError::Network(format!("Failed to create time entry: {}", e)))?;
+
+        let response = check_response(response)?;
 
         let entry: TimeEntry = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse created time entry: {}", e))
         })?;
 
+        status_cache::invalidate();
+        self.record(Change::Created { entry_id: entry.id }, Some(&entry));
         info!("Created time entry: {} on {}", description, spent_date);
         Ok(entry)
     }
@@ -837,6 +1325,29 @@ impl HarvestClient {
         hours: f64,
         spent_date: &str,
         ctx: &Context,
+    ) -> Result<TimeEntry> {
+        self.create_stopped_time_entry_for_user(
+            None,
+            description,
+            project_id,
+            task_id,
+            hours,
+            spent_date,
+            ctx,
+        )
+    }
+
+    /// Like `create_stopped_time_entry_with_date`, for `user_id` instead of the token's user
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stopped_time_entry_for_user(
+        &self,
+        user_id: Option<u64>,
+        description: &str,
+        project_id: u64,
+        task_id: u64,
+        hours: f64,
+        spent_date: &str,
+        ctx: &Context,
     ) -> Result<TimeEntry> {
         let request = CreateStoppedTimeEntryRequest {
             project_id,
@@ -844,10 +1355,20 @@ impl HarvestClient {
             spent_date: spent_date.to_string(),
             notes: description.to_string(),
             hours,
+            external_reference: None,
+            user_id,
         };
 
         if ctx.dry_run {
+            crate::plan::record(
+                "POST",
+                &format!("{}/time_entries", self.base_url),
+                Some(&request),
+            );
             info!("[DRY RUN] Would create stopped time entry:");
+            if let Some(user_id) = user_id {
+                info!("  User ID: {}", user_id);
+            }
             info!("  Project ID: {}", project_id);
             info!("  Task ID: {}", task_id);
             info!("  Date: {}", spent_date);
@@ -862,6 +1383,10 @@ impl HarvestClient {
                 project: None,
                 task: None,
                 started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
             });
         }
 
@@ -875,24 +1400,17 @@ impl HarvestClient {
             .json(&request)
             .send()
             .map_err(|e| {
-                HarjiraError::Harvest(format!("Failed to create time entry: {}", e))
+                HarjiraError::Network(format!("Failed to create time entry: {}", e))
             })?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "Failed to create time entry ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = check_response(response)?;
 
         let entry: TimeEntry = response.json().map_err(|e| {
             HarjiraError::Harvest(format!("Failed to parse created time entry: {}", e))
         })?;
 
+        status_cache::invalidate();
+        self.record(Change::Created { entry_id: entry.id }, Some(&entry));
         info!(
             "Created time entry: {} ({:.2}h) on {}",
             description, hours, spent_date
@@ -902,37 +1420,235 @@ impl HarvestClient {
 
     /// Get total hours logged for a specific date
     pub fn get_total_hours_for_date(&self, date: &str) -> Result<f64> {
-        let url = format!("{}/time_entries?from={}&to={}", self.base_url, date, date);
+        let total = self
+            .get_time_entries_for_date(date)?
+            .iter()
+            .filter_map(|e| e.hours)
+            .sum();
 
-        debug!("GET {}", url);
+        Ok(total)
+    }
+}
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .map_err(|e| HarjiraError::Harvest(format!("Request failed: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(HarjiraError::Harvest(format!(
-                "API request failed with status {}: {}",
-                status, error_text
-            )));
-        }
+/// Turn a non-success response into a typed `HarvestApiError`
+pub(crate) fn check_response(response: Response) -> Result<Response> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
 
-        let entries_response: TimeEntriesResponse = response.json().map_err(|e| {
-            HarjiraError::Harvest(format!("Failed to parse time entries response: {}", e))
-        })?;
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+    let body = response.text().unwrap_or_default();
+
+    let error = HarvestApiError::from_parts(status, retry_after.as_deref(), &body);
+    debug!("Harvest API error: {:?}", error);
+    Err(error.into())
+}
+
+/// Like `check_response`, explaining that a 403 means the token isn't an admin's
+fn check_team_access(response: Response) -> Result<Response> {
+    if response.status() == StatusCode::FORBIDDEN {
+        return Err(HarjiraError::Auth(
+            "Harvest denied access to other users' data. This needs an access token of an administrator, or of a manager for the people they manage.".to_string(),
+        ));
+    }
+    check_response(response)
+}
+
+fn fetch_accounts(url: &str, access_token: &str, user_agent: &str) -> Result<Vec<HarvestAccount>> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {}", access_token))
+            .map_err(|e| HarjiraError::Config(format!("Invalid Harvest access token: {}", e)))?,
+    );
+    headers.insert(
+        USER_AGENT,
+        HeaderValue::from_str(user_agent)
+            .map_err(|e| HarjiraError::Config(format!("Invalid user agent: {}", e)))?,
+    );
+
+    debug!("GET {}", url);
+    let response = ApiClient::new(headers)
+        .get(url)
+        .send()
+        .map_err(|e| HarjiraError::Network(format!("Failed to list Harvest accounts: {}", e)))?;
+
+    let response = check_response(response)?;
+
+    let accounts: HarvestAccountsResponse = response
+        .json()
+        .map_err(|e| HarjiraError::Harvest(format!("Failed to parse accounts response: {}", e)))?;
+
+    Ok(accounts
+        .accounts
+        .into_iter()
+        .filter(|a| a.product == "harvest")
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_client(base_url: &str) -> HarvestClient {
+        HarvestClient::new(HarvestConfig {
+            access_token: "token".to_string(),
+            account_id: "123".to_string(),
+            user_agent: "harv tests".to_string(),
+            project_id: None,
+            task_id: None,
+            accounts: Default::default(),
+        })
+        .unwrap()
+        .with_base_url(base_url)
+    }
 
-        let total = entries_response
-            .time_entries
+    #[test]
+    fn test_all_available_tasks_keeps_project_order() {
+        let mut server = mockito::Server::new();
+        let _account = server
+            .mock("GET", "/task_assignments")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .create();
+        let project_ids: Vec<u64> = (1..=12).collect();
+        let projects: Vec<String> = project_ids
             .iter()
-            .filter_map(|e| e.hours)
-            .sum();
+            .map(|id| format!(r#"{{"id":{},"name":"Project {}"}}"#, id, id))
+            .collect();
+        let _projects = server
+            .mock("GET", "/projects")
+            .match_query(mockito::Matcher::Any)
+            .with_body(format!(r#"{{"projects":[{}]}}"#, projects.join(",")))
+            .create();
+        let _tasks: Vec<_> = project_ids
+            .iter()
+            .map(|id| {
+                server
+                    .mock("GET", format!("/projects/{}/task_assignments", id).as_str())
+                    .with_body(format!(
+                        r#"{{"task_assignments":[{{"is_active":true,"task":{{"id":{},"name":"Task {}"}}}}]}}"#,
+                        id * 10,
+                        id
+                    ))
+                    .create()
+            })
+            .collect();
 
-        Ok(total)
+        let mut reported = Vec::new();
+        let tasks = test_client(&server.url())
+            .get_all_available_tasks_with_progress(|done, total| reported.push((done, total)))
+            .unwrap();
+
+        let pairs: Vec<(u64, u64)> = tasks.iter().map(|(pid, t)| (*pid, t.id)).collect();
+        let expected: Vec<(u64, u64)> = project_ids.iter().map(|id| (*id, id * 10)).collect();
+        assert_eq!(pairs, expected);
+        assert_eq!(reported.last(), Some(&(12, 12)));
+    }
+
+    #[test]
+    fn test_all_available_tasks_from_account_endpoint() {
+        let mut server = mockito::Server::new();
+        let assignment = |project: u64, task: u64, active: bool| {
+            format!(
+                r#"{{"is_active":{},"project":{{"id":{},"name":"P"}},"task":{{"id":{},"name":"T"}}}}"#,
+                active, project, task
+            )
+        };
+        let _page1 = server
+            .mock("GET", "/task_assignments")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+            .with_body(format!(
+                r#"{{"task_assignments":[{},{}],"total_pages":2,"next_page":2}}"#,
+                assignment(1, 10, true),
+                assignment(1, 11, false)
+            ))
+            .create();
+        let _page2 = server
+            .mock("GET", "/task_assignments")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_body(format!(
+                r#"{{"task_assignments":[{}],"total_pages":2,"next_page":null}}"#,
+                assignment(2, 20, true)
+            ))
+            .create();
+        let projects = server.mock("GET", "/projects").expect(0).create();
+
+        let tasks = test_client(&server.url())
+            .get_all_available_tasks()
+            .unwrap();
+
+        let pairs: Vec<(u64, u64)> = tasks.iter().map(|(pid, t)| (*pid, t.id)).collect();
+        assert_eq!(pairs, vec![(1, 10), (2, 20)]);
+        projects.assert();
+    }
+
+    #[test]
+    fn test_api_error_from_validation_body() {
+        let error = HarvestApiError::from_parts(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            None,
+            r#"{"message":"Project is archived"}"#,
+        );
+        assert!(error.is_unprocessable());
+        assert_eq!(error.message, "Project is archived");
+        assert!(error.code.is_none());
+    }
+
+    #[test]
+    fn test_api_error_from_auth_body() {
+        let error = HarvestApiError::from_parts(
+            StatusCode::UNAUTHORIZED,
+            None,
+            r#"{"error":"invalid_token","error_description":"The access token is invalid"}"#,
+        );
+        assert!(error.is_auth_error());
+        assert_eq!(error.code.as_deref(), Some("invalid_token"));
+        assert_eq!(error.message, "The access token is invalid");
+    }
+
+    #[test]
+    fn test_api_error_rate_limit_retry_after() {
+        let error = HarvestApiError::from_parts(StatusCode::TOO_MANY_REQUESTS, Some("15"), "");
+        assert!(error.is_rate_limited());
+        assert_eq!(error.retry_after, Some(15));
+        assert_eq!(error.message, "Unknown error");
+    }
+
+    #[test]
+    fn test_api_error_non_json_body() {
+        let error =
+            HarvestApiError::from_parts(StatusCode::BAD_GATEWAY, None, "<html>Bad gateway</html>");
+        assert_eq!(error.message, "<html>Bad gateway</html>");
+        assert_eq!(
+            error.to_string(),
+            "502 Bad Gateway: <html>Bad gateway</html>"
+        );
+    }
+
+    #[test]
+    fn test_fetch_accounts_skips_forecast() {
+        let mut server = mockito::Server::new();
+        let _mock = server
+            .mock("GET", "/accounts")
+            .match_header("authorization", "Bearer token")
+            .with_status(200)
+            .with_body(
+                r#"{"user":{"id":1},"accounts":[
+                    {"id":111,"name":"Acme","product":"harvest"},
+                    {"id":222,"name":"Acme","product":"forecast"},
+                    {"id":333,"name":"Side Gig","product":"harvest"}]}"#,
+            )
+            .create();
+
+        let accounts =
+            fetch_accounts(&format!("{}/accounts", server.url()), "token", "harv tests").unwrap();
+        let ids: Vec<u64> = accounts.iter().map(|a| a.id).collect();
+        assert_eq!(ids, vec![111, 333]);
     }
 }
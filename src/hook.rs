@@ -0,0 +1,208 @@
+use crate::error::{HarjiraError, Result};
+use git2::Repository;
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BLOCK_START: &str = "# >>> harv >>>";
+const BLOCK_END: &str = "# <<< harv <<<";
+
+/// What `install` did to the post-commit hook
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookInstall {
+    /// No hook existed, a new one was written
+    Created,
+    /// harv was added after the commands of an existing hook
+    Chained,
+    /// The hook already runs harv, it was refreshed
+    Updated,
+}
+
+/// Add harv's post-commit hook to the repository containing `repo_path`
+///
+/// An existing hook is kept: harv's block is appended to it, or replaced if
+/// it is already there. Returns the hook path.
+pub fn install(repo_path: &str) -> Result<(PathBuf, HookInstall)> {
+    let path = hook_path(repo_path)?;
+    let exe = std::env::current_exe()?;
+    let existing = read_hook(&path)?;
+
+    let outcome = match &existing {
+        None => HookInstall::Created,
+        Some(contents) if contents.contains(BLOCK_START) => HookInstall::Updated,
+        Some(_) => HookInstall::Chained,
+    };
+    let contents = add_block(existing.as_deref(), &exe);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, contents).map_err(|e| {
+        HarjiraError::Config(format!("Failed to write hook {}: {}", path.display(), e))
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(&path)?.permissions();
+        perms.set_mode(perms.mode() | 0o755);
+        fs::set_permissions(&path, perms)?;
+    }
+
+    debug!("Installed post-commit hook at {}", path.display());
+    Ok((path, outcome))
+}
+
+/// Remove harv's block from the post-commit hook
+///
+/// The hook file is deleted when nothing else is left in it. Returns the
+/// hook path, or None if harv wasn't installed.
+pub fn uninstall(repo_path: &str) -> Result<Option<PathBuf>> {
+    let path = hook_path(repo_path)?;
+    let Some(existing) = read_hook(&path)? else {
+        return Ok(None);
+    };
+    if !existing.contains(BLOCK_START) {
+        return Ok(None);
+    }
+
+    match remove_block(&existing) {
+        Some(rest) => fs::write(&path, rest)?,
+        None => fs::remove_file(&path)?,
+    }
+
+    debug!("Removed post-commit hook from {}", path.display());
+    Ok(Some(path))
+}
+
+/// Location of the post-commit hook, honouring `core.hooksPath`
+fn hook_path(repo_path: &str) -> Result<PathBuf> {
+    let repo = Repository::discover(repo_path)?;
+
+    let hooks_dir = match repo.config()?.get_path("core.hooksPath") {
+        Ok(dir) if dir.is_absolute() => dir,
+        // Relative hook paths are relative to the working tree
+        Ok(dir) => repo.workdir().unwrap_or_else(|| repo.path()).join(dir),
+        Err(_) => repo.path().join("hooks"),
+    };
+
+    Ok(hooks_dir.join("post-commit"))
+}
+
+fn read_hook(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(fs::read_to_string(path)?))
+}
+
+fn harv_block(exe: &Path) -> String {
+    format!(
+        "{start}\n\
+         # Start a Harvest timer for the ticket just committed (runs in the background)\n\
+         \"{exe}\" sync --auto-start --quiet </dev/null >/dev/null 2>&1 &\n\
+         {end}\n",
+        start = BLOCK_START,
+        exe = exe.display(),
+        end = BLOCK_END
+    )
+}
+
+/// Hook contents with harv's block added to (or refreshed in) `existing`
+fn add_block(existing: Option<&str>, exe: &Path) -> String {
+    let block = harv_block(exe);
+    match existing {
+        None => format!("#!/bin/sh\n{}", block),
+        Some(contents) => {
+            let base = remove_block(contents).unwrap_or_else(|| {
+                let shebang = contents
+                    .lines()
+                    .next()
+                    .filter(|line| line.starts_with("#!"))
+                    .unwrap_or("#!/bin/sh");
+                format!("{}\n", shebang)
+            });
+            let separator = if base.ends_with('\n') { "" } else { "\n" };
+            format!("{}{}{}", base, separator, block)
+        }
+    }
+}
+
+/// Hook contents without harv's block, None if only a shebang would remain
+fn remove_block(contents: &str) -> Option<String> {
+    let mut kept = Vec::new();
+    let mut in_block = false;
+    for line in contents.lines() {
+        match line.trim() {
+            BLOCK_START => in_block = true,
+            BLOCK_END => in_block = false,
+            _ if !in_block => kept.push(line),
+            _ => {}
+        }
+    }
+
+    let has_commands = kept
+        .iter()
+        .any(|line| !line.trim().is_empty() && !line.starts_with("#!"));
+    if !has_commands {
+        return None;
+    }
+
+    let mut rest = kept.join("\n");
+    rest.push('\n');
+    Some(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const EXE: &str = "/usr/local/bin/harv";
+
+    #[test]
+    fn test_add_block_to_new_hook() {
+        let hook = add_block(None, Path::new(EXE));
+        assert!(hook.starts_with("#!/bin/sh\n# >>> harv >>>\n"));
+        assert!(hook.contains("\"/usr/local/bin/harv\" sync --auto-start --quiet"));
+    }
+
+    #[test]
+    fn test_add_block_chains_existing_hook() {
+        let existing = "#!/bin/sh\nnpx lint-staged";
+        let hook = add_block(Some(existing), Path::new(EXE));
+        assert!(hook.starts_with("#!/bin/sh\nnpx lint-staged\n# >>> harv >>>\n"));
+
+        // Installing twice keeps a single block
+        let again = add_block(Some(&hook), Path::new(EXE));
+        assert_eq!(again, hook);
+    }
+
+    #[test]
+    fn test_remove_block() {
+        let chained = add_block(Some("#!/bin/sh\nnpx lint-staged\n"), Path::new(EXE));
+        assert_eq!(
+            remove_block(&chained).as_deref(),
+            Some("#!/bin/sh\nnpx lint-staged\n")
+        );
+
+        let only_harv = add_block(None, Path::new(EXE));
+        assert_eq!(remove_block(&only_harv), None);
+    }
+
+    #[test]
+    fn test_install_and_uninstall() {
+        let dir = TempDir::new().unwrap();
+        Repository::init(dir.path()).unwrap();
+        let repo_path = dir.path().to_str().unwrap();
+
+        let (path, outcome) = install(repo_path).unwrap();
+        assert_eq!(outcome, HookInstall::Created);
+        assert!(path.ends_with(".git/hooks/post-commit"));
+        assert_eq!(install(repo_path).unwrap().1, HookInstall::Updated);
+
+        assert_eq!(uninstall(repo_path).unwrap(), Some(path.clone()));
+        assert!(!path.exists());
+        assert_eq!(uninstall(repo_path).unwrap(), None);
+    }
+}
@@ -0,0 +1,396 @@
+use crate::config::HttpConfig;
+use crate::error::{HarjiraError, Result};
+use log::{debug, log_enabled, trace, Level};
+use reqwest::blocking::{Client, Request, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::{Certificate, IntoUrl, Method, Proxy};
+use serde::Serialize;
+use std::fmt::Display;
+use std::fs;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Headers whose values are never logged
+const SECRET_HEADERS: &[&str] = &["authorization", "x-api-key", "cookie", "set-cookie"];
+
+/// Logged bodies are cut off after this many characters
+const MAX_LOGGED_BODY: usize = 4000;
+
+/// Process-wide client, so every API shares one connection pool
+static SHARED: OnceLock<Client> = OnceLock::new();
+
+/// Build the shared client from the `[http]` config section
+///
+/// Only the first call has an effect; later calls keep the existing pool.
+pub fn configure(config: &HttpConfig) -> Result<()> {
+    if SHARED.get().is_none() {
+        let _ = SHARED.set(build(config)?);
+    }
+    Ok(())
+}
+
+/// The shared client, built with default settings if `configure` wasn't called
+///
+/// Cloning a `Client` is cheap: clones use the same connection pool.
+pub fn client() -> Client {
+    SHARED
+        .get_or_init(|| build(&HttpConfig::default()).unwrap_or_else(|_| Client::new()))
+        .clone()
+}
+
+fn build(config: &HttpConfig) -> Result<Client> {
+    // Blocking clients default to a 30s timeout, so always set it explicitly
+    let timeout = (config.timeout_secs > 0).then(|| Duration::from_secs(config.timeout_secs));
+    let mut builder = Client::builder().timeout(timeout);
+    if config.connect_timeout_secs > 0 {
+        builder = builder.connect_timeout(Duration::from_secs(config.connect_timeout_secs));
+    }
+
+    if let Some(ref proxy) = config.proxy {
+        let proxy = Proxy::all(proxy)
+            .map_err(|e| HarjiraError::Config(format!("Invalid proxy {}: {}", proxy, e)))?;
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(ref path) = config.ca_certificate {
+        let pem = fs::read(path).map_err(|e| {
+            HarjiraError::Config(format!("Failed to read CA certificate {}: {}", path, e))
+        })?;
+        let certificate = Certificate::from_pem(&pem)
+            .map_err(|e| HarjiraError::Config(format!("Invalid CA certificate {}: {}", path, e)))?;
+        debug!("Trusting extra CA certificate from {}", path);
+        builder = builder.add_root_certificate(certificate);
+    }
+
+    builder
+        .build()
+        .map_err(|e| HarjiraError::Config(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// The shared client plus headers sent with every request to one API
+///
+/// Requests made through it are logged, with secrets redacted, at trace
+/// level (`-vv`).
+#[derive(Clone)]
+pub struct ApiClient {
+    client: Client,
+    headers: HeaderMap,
+}
+
+impl Default for ApiClient {
+    fn default() -> Self {
+        Self::new(HeaderMap::new())
+    }
+}
+
+impl ApiClient {
+    pub fn new(headers: HeaderMap) -> Self {
+        Self {
+            client: client(),
+            headers,
+        }
+    }
+
+    pub fn get<U: IntoUrl>(&self, url: U) -> ApiRequest {
+        self.request(Method::GET, url)
+    }
+
+    pub fn post<U: IntoUrl>(&self, url: U) -> ApiRequest {
+        self.request(Method::POST, url)
+    }
+
+    pub fn patch<U: IntoUrl>(&self, url: U) -> ApiRequest {
+        self.request(Method::PATCH, url)
+    }
+
+    pub fn put<U: IntoUrl>(&self, url: U) -> ApiRequest {
+        self.request(Method::PUT, url)
+    }
+
+    pub fn delete<U: IntoUrl>(&self, url: U) -> ApiRequest {
+        self.request(Method::DELETE, url)
+    }
+
+    fn request<U: IntoUrl>(&self, method: Method, url: U) -> ApiRequest {
+        ApiRequest(
+            self.client
+                .request(method, url)
+                .headers(self.headers.clone()),
+        )
+    }
+}
+
+/// A request being built, see `reqwest::blocking::RequestBuilder`
+pub struct ApiRequest(RequestBuilder);
+
+impl ApiRequest {
+    pub fn header(self, name: &'static str, value: impl AsRef<str>) -> Self {
+        Self(self.0.header(name, value.as_ref()))
+    }
+
+    pub fn bearer_auth(self, token: impl Display) -> Self {
+        Self(self.0.bearer_auth(token))
+    }
+
+    pub fn basic_auth(self, username: impl Display, password: Option<impl Display>) -> Self {
+        Self(self.0.basic_auth(username, password))
+    }
+
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> Self {
+        Self(self.0.json(json))
+    }
+
+    pub fn query<T: Serialize + ?Sized>(self, query: &T) -> Self {
+        Self(self.0.query(query))
+    }
+
+    pub fn send(self) -> reqwest::Result<Response> {
+        if !log_enabled!(Level::Debug) {
+            return self.0.send();
+        }
+        // -vvv: headers and bodies as well as the request line
+        let detailed = log_enabled!(Level::Trace);
+
+        let (client, request) = self.0.build_split();
+        let request = request?;
+        let method = request.method().clone();
+        let url = request.url().clone();
+        if detailed {
+            log_request(&request);
+        }
+
+        let started = Instant::now();
+        let response = client.execute(request).inspect_err(|e| {
+            debug!(
+                "{} {} failed after {}ms: {}",
+                method,
+                url,
+                started.elapsed().as_millis(),
+                e
+            )
+        })?;
+        debug!(
+            "{} {} -> {} in {}ms",
+            method,
+            url,
+            response.status(),
+            started.elapsed().as_millis()
+        );
+        if !detailed {
+            return Ok(response);
+        }
+        log_headers(response.headers());
+        log_response_body(response)
+    }
+}
+
+fn log_request(request: &Request) {
+    trace!("{} {}", request.method(), request.url());
+    log_headers(request.headers());
+    if let Some(body) = request.body().and_then(|b| b.as_bytes()) {
+        trace!("  body: {}", redact_body(body));
+    }
+}
+
+fn log_headers(headers: &HeaderMap) {
+    for (name, value) in headers {
+        trace!("  {}: {}", name, redact_header(name.as_str(), value));
+    }
+}
+
+/// Log the response body and hand back an equivalent response
+///
+/// Streamed (server-sent event) bodies are left alone so they can still be
+/// read incrementally.
+fn log_response_body(response: Response) -> reqwest::Result<Response> {
+    let is_stream = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+    if is_stream {
+        trace!("  body: (streamed)");
+        return Ok(response);
+    }
+
+    let mut builder = ::http::Response::builder()
+        .status(response.status())
+        .version(response.version());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+
+    let body = response.bytes()?.to_vec();
+    if !body.is_empty() {
+        trace!("  body: {}", redact_body(&body));
+    }
+
+    let rebuilt = builder
+        .body(body)
+        .expect("status and headers come from a valid response");
+    Ok(rebuilt.into())
+}
+
+fn redact_header(name: &str, value: &HeaderValue) -> String {
+    if SECRET_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+        // Keep the scheme so "Bearer" vs "Basic" is still visible
+        return match value.to_str().ok().and_then(|v| v.split_once(' ')) {
+            Some((scheme, _)) => format!("{} ***", scheme),
+            None => "***".to_string(),
+        };
+    }
+    value.to_str().unwrap_or("(binary)").to_string()
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    [
+        "token",
+        "secret",
+        "password",
+        "api_key",
+        "apikey",
+        "authorization",
+    ]
+    .iter()
+    .any(|secret| key.contains(secret))
+}
+
+fn redact_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            // Only strings: usage counts like "input_tokens" stay readable
+            for (key, value) in map {
+                if is_secret_key(key) && value.is_string() {
+                    *value = serde_json::Value::String("***".to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// A body as loggable text: JSON with secret fields masked, anything else as-is
+fn redact_body(body: &[u8]) -> String {
+    let text = match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(mut json) => {
+            redact_json(&mut json);
+            json.to_string()
+        }
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    };
+
+    if text.chars().count() > MAX_LOGGED_BODY {
+        let cut: String = text.chars().take(MAX_LOGGED_BODY).collect();
+        format!("{}... ({} bytes)", cut, body.len())
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, AUTHORIZATION};
+
+    #[test]
+    fn test_api_client_sends_headers() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/ping")
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .create();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        let api = ApiClient::new(headers);
+
+        let response = api.get(format!("{}/ping", server.url())).send().unwrap();
+        assert!(response.status().is_success());
+        mock.assert();
+    }
+
+    #[test]
+    fn test_redact_header() {
+        let bearer = HeaderValue::from_static("Bearer abc123");
+        assert_eq!(redact_header("Authorization", &bearer), "Bearer ***");
+
+        let key = HeaderValue::from_static("sk-ant-123");
+        assert_eq!(redact_header("x-api-key", &key), "***");
+
+        let agent = HeaderValue::from_static("harv (me@example.com)");
+        assert_eq!(redact_header("user-agent", &agent), "harv (me@example.com)");
+    }
+
+    #[test]
+    fn test_redact_body() {
+        let body = br#"{"grant_type":"refresh_token","refresh_token":"r-123","client_secret":"s","entries":[{"access_token":"a","notes":"ABC-1"}]}"#;
+        let redacted = redact_body(body);
+        assert!(!redacted.contains("r-123"));
+        assert!(redacted.contains(r#""grant_type":"refresh_token""#));
+        assert!(redacted.contains(r#""client_secret":"***""#));
+        assert!(redacted.contains(r#""access_token":"***""#));
+        assert!(redacted.contains(r#""notes":"ABC-1""#));
+
+        assert_eq!(redact_body(b"plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_log_response_body_keeps_response() {
+        let mut server = mockito::Server::new();
+        let _mock = server
+            .mock("GET", "/entries")
+            .with_status(422)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"message":"Project is archived"}"#)
+            .create();
+
+        let response = Client::new()
+            .get(format!("{}/entries", server.url()))
+            .send()
+            .unwrap();
+        let response = log_response_body(response).unwrap();
+        assert_eq!(response.status().as_u16(), 422);
+        assert_eq!(
+            response.text().unwrap(),
+            r#"{"message":"Project is archived"}"#
+        );
+    }
+
+    #[test]
+    fn test_missing_ca_certificate() {
+        let config = HttpConfig {
+            ca_certificate: Some("/nonexistent/ca.pem".to_string()),
+            ..HttpConfig::default()
+        };
+        assert!(matches!(build(&config), Err(HarjiraError::Config(_))));
+    }
+
+    #[test]
+    fn test_timeout_aborts_slow_server() {
+        // Accept the connection but never answer
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let config = HttpConfig {
+            timeout_secs: 1,
+            ..HttpConfig::default()
+        };
+        let error = build(&config).unwrap().get(&url).send().unwrap_err();
+        assert!(error.is_timeout());
+        drop(listener);
+    }
+
+    #[test]
+    fn test_invalid_proxy() {
+        let config = HttpConfig {
+            proxy: Some("not a url".to_string()),
+            ..HttpConfig::default()
+        };
+        assert!(matches!(build(&config), Err(HarjiraError::Config(_))));
+    }
+}
@@ -0,0 +1,461 @@
+//! Draft invoices from unbilled hours, for `harv invoice draft`
+//!
+//! Billable entries are added up per project and task and priced with the
+//! rates in `[invoice]`, falling back to the billable rate set in Harvest.
+//! The draft imports the hours from Harvest, which attaches the entries to
+//! the invoice so they count as billed and stay out of the next draft.
+
+use crate::config::InvoiceConfig;
+use crate::error::{HarjiraError, Result};
+use crate::harvest::{check_response, HarvestClient};
+use crate::models::{ClientInfo, Context, HarvestProject, ProjectInfo, TaskInfo};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A time entry that hasn't been invoiced yet
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnbilledEntry {
+    pub id: u64,
+    pub hours: Option<f64>,
+    #[serde(default)]
+    pub billable: bool,
+    /// Rate Harvest bills the entry at, if the project has one
+    pub billable_rate: Option<f64>,
+    pub project: Option<HarvestProject>,
+    pub task: Option<TaskInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnbilledEntriesResponse {
+    time_entries: Vec<UnbilledEntry>,
+    #[serde(default)]
+    next_page: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientsResponse {
+    clients: Vec<ClientInfo>,
+    #[serde(default)]
+    next_page: Option<u32>,
+}
+
+/// Hours on one project and task at one rate
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvoiceLine {
+    pub project_id: u64,
+    pub project: String,
+    pub task: String,
+    pub hours: f64,
+    pub rate: f64,
+}
+
+impl InvoiceLine {
+    /// Hours times rate, rounded to cents like Harvest does
+    pub fn amount(&self) -> f64 {
+        (self.hours * self.rate * 100.0).round() / 100.0
+    }
+}
+
+/// Harvest invoice request, the invoice starts out as a draft
+#[derive(Debug, Serialize)]
+pub struct CreateInvoiceRequest {
+    pub client_id: u64,
+    pub subject: String,
+    pub line_items_import: LineItemsImport,
+}
+
+impl CreateInvoiceRequest {
+    /// Import the unbilled hours of `lines`' projects between two dates, a line per task
+    pub fn import(
+        client_id: u64,
+        subject: String,
+        lines: &[InvoiceLine],
+        from: &str,
+        to: &str,
+    ) -> Self {
+        let mut project_ids: Vec<u64> = lines.iter().map(|l| l.project_id).collect();
+        project_ids.sort_unstable();
+        project_ids.dedup();
+        Self {
+            client_id,
+            subject,
+            line_items_import: LineItemsImport {
+                project_ids,
+                time: TimeImport {
+                    summary_type: "task".to_string(),
+                    from: from.to_string(),
+                    to: to.to_string(),
+                },
+            },
+        }
+    }
+}
+
+/// What Harvest turns into line items, linking the imported entries to the invoice
+#[derive(Debug, Serialize)]
+pub struct LineItemsImport {
+    pub project_ids: Vec<u64>,
+    pub time: TimeImport,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeImport {
+    /// "project", "task", "people" or "detailed"
+    pub summary_type: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Harvest invoice response
+#[derive(Debug, Clone, Deserialize)]
+pub struct Invoice {
+    pub id: u64,
+    pub number: Option<String>,
+    pub amount: Option<f64>,
+    pub state: Option<String>,
+    #[serde(default)]
+    pub line_items: Vec<InvoiceLineItem>,
+}
+
+/// A line of a created invoice
+#[derive(Debug, Clone, Deserialize)]
+pub struct InvoiceLineItem {
+    pub id: u64,
+    pub project: Option<ProjectInfo>,
+    #[serde(default)]
+    pub unit_price: f64,
+}
+
+/// New prices for lines of an existing invoice
+#[derive(Debug, Serialize)]
+struct UpdateInvoiceRequest {
+    line_items: Vec<LinePrice>,
+}
+
+#[derive(Debug, Serialize)]
+struct LinePrice {
+    id: u64,
+    unit_price: f64,
+}
+
+impl HarvestClient {
+    /// Active clients in the account
+    pub fn get_clients(&self) -> Result<Vec<ClientInfo>> {
+        let mut clients = Vec::new();
+        let mut page = 1;
+        loop {
+            let url = format!("{}/clients", self.base_url);
+            debug!("GET {} (page {})", url, page);
+
+            let response = self
+                .client
+                .get(&url)
+                .query(&[
+                    ("is_active", "true".to_string()),
+                    ("page", page.to_string()),
+                ])
+                .send()
+                .map_err(|e| HarjiraError::Network(format!("Failed to fetch clients: {}", e)))?;
+            let response: ClientsResponse = check_response(response)?
+                .json()
+                .map_err(|e| HarjiraError::Harvest(format!("Failed to parse clients: {}", e)))?;
+
+            clients.extend(response.clients);
+            match response.next_page {
+                Some(next) if next > page => page = next,
+                _ => break,
+            }
+        }
+
+        debug!("Retrieved {} clients", clients.len());
+        Ok(clients)
+    }
+
+    /// Entries of a client between two dates (inclusive) that aren't invoiced yet
+    pub fn get_unbilled_entries(
+        &self,
+        client_id: u64,
+        from_date: &str,
+        to_date: &str,
+    ) -> Result<Vec<UnbilledEntry>> {
+        let mut entries = Vec::new();
+        let mut page = 1;
+        loop {
+            let url = format!("{}/time_entries", self.base_url);
+            debug!(
+                "GET {} for client {} from {} to {} (page {})",
+                url, client_id, from_date, to_date, page
+            );
+
+            let response = self
+                .client
+                .get(&url)
+                .query(&[
+                    ("client_id", client_id.to_string()),
+                    ("is_billed", "false".to_string()),
+                    ("from", from_date.to_string()),
+                    ("to", to_date.to_string()),
+                    ("page", page.to_string()),
+                ])
+                .send()
+                .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+            let response: UnbilledEntriesResponse =
+                check_response(response)?.json().map_err(|e| {
+                    HarjiraError::Harvest(format!("Failed to parse time entries response: {}", e))
+                })?;
+
+            entries.extend(response.time_entries);
+            match response.next_page {
+                Some(next) if next > page => page = next,
+                _ => break,
+            }
+        }
+
+        debug!("Retrieved {} unbilled entries", entries.len());
+        Ok(entries)
+    }
+
+    /// Create a draft invoice
+    pub fn create_invoice(&self, request: &CreateInvoiceRequest, ctx: &Context) -> Result<Invoice> {
+        let url = format!("{}/invoices", self.base_url);
+        if ctx.dry_run {
+            crate::plan::record("POST", &url, Some(request));
+            info!("[DRY RUN] Would create draft invoice:");
+            info!("  Client ID: {}", request.client_id);
+            info!("  Subject: {}", request.subject);
+            let import = &request.line_items_import;
+            info!(
+                "  Hours of projects {:?} from {} to {}",
+                import.project_ids, import.time.from, import.time.to
+            );
+            return Ok(Invoice {
+                id: 0,
+                number: None,
+                amount: None,
+                state: Some("draft".to_string()),
+                line_items: Vec::new(),
+            });
+        }
+
+        debug!("POST {}", url);
+        debug!("Request body: {:?}", request);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(request)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to create invoice: {}", e)))?;
+
+        let response = check_response(response)?;
+
+        let invoice: Invoice = response.json().map_err(|e| {
+            HarjiraError::Harvest(format!("Failed to parse created invoice: {}", e))
+        })?;
+
+        info!("Created draft invoice {}", invoice.id);
+        Ok(invoice)
+    }
+
+    /// Set the unit price of invoice lines, by line item ID
+    pub fn update_invoice_prices(
+        &self,
+        invoice_id: u64,
+        prices: &[(u64, f64)],
+        ctx: &Context,
+    ) -> Result<Invoice> {
+        let url = format!("{}/invoices/{}", self.base_url, invoice_id);
+        let request = UpdateInvoiceRequest {
+            line_items: prices
+                .iter()
+                .map(|&(id, unit_price)| LinePrice { id, unit_price })
+                .collect(),
+        };
+        if ctx.dry_run {
+            crate::plan::record("PATCH", &url, Some(&request));
+            info!("[DRY RUN] Would reprice {} invoice lines", prices.len());
+            return Ok(Invoice {
+                id: invoice_id,
+                number: None,
+                amount: None,
+                state: Some("draft".to_string()),
+                line_items: Vec::new(),
+            });
+        }
+
+        debug!("PATCH {}", url);
+        let response = self
+            .client
+            .patch(&url)
+            .json(&request)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to update invoice: {}", e)))?;
+
+        check_response(response)?
+            .json()
+            .map_err(|e| HarjiraError::Harvest(format!("Failed to parse updated invoice: {}", e)))
+    }
+}
+
+/// Rates from `[invoice]` per project ID, for the projects that have one
+///
+/// Harvest prices imported hours at its own billable rates, these replace them.
+pub fn configured_rates(entries: &[UnbilledEntry], config: &InvoiceConfig) -> BTreeMap<u64, f64> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let project = entry.project.as_ref()?;
+            let rate = config.rate_for(&project.name, project.code.as_deref())?;
+            Some((project.id, rate))
+        })
+        .collect()
+}
+
+/// New prices for the lines of `invoice` whose project has a configured rate
+pub fn repriced_lines(invoice: &Invoice, rates: &BTreeMap<u64, f64>) -> Vec<(u64, f64)> {
+    invoice
+        .line_items
+        .iter()
+        .filter_map(|item| {
+            let rate = *rates.get(&item.project.as_ref()?.id)?;
+            (rate != item.unit_price).then_some((item.id, rate))
+        })
+        .collect()
+}
+
+/// Billable hours per project, task and rate, ordered by project and task
+///
+/// Fails naming the project when no rate can be found for it.
+pub fn invoice_lines(
+    entries: &[UnbilledEntry],
+    config: &InvoiceConfig,
+) -> Result<Vec<InvoiceLine>> {
+    let mut lines: Vec<InvoiceLine> = Vec::new();
+    for entry in entries.iter().filter(|e| e.billable) {
+        let Some(project) = &entry.project else {
+            continue;
+        };
+        let task = entry
+            .task
+            .as_ref()
+            .map(|t| t.name.clone())
+            .unwrap_or_default();
+        let rate = config
+            .rate_for(&project.name, project.code.as_deref())
+            .or(entry.billable_rate)
+            .ok_or_else(|| {
+                HarjiraError::InvalidEntry(format!(
+                    "No hourly rate for project '{}'. Set invoice.hourly_rate or add it to [invoice.rates]",
+                    project.name
+                ))
+            })?;
+
+        let hours = entry.hours.unwrap_or(0.0);
+        match lines
+            .iter_mut()
+            .find(|l| l.project_id == project.id && l.task == task && l.rate == rate)
+        {
+            Some(line) => line.hours += hours,
+            None => lines.push(InvoiceLine {
+                project_id: project.id,
+                project: project.name.clone(),
+                task,
+                hours,
+                rate,
+            }),
+        }
+    }
+
+    lines.sort_by(|a, b| {
+        a.project
+            .cmp(&b.project)
+            .then_with(|| a.task.cmp(&b.task))
+            .then_with(|| a.rate.total_cmp(&b.rate))
+    });
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(project: &str, task: &str, hours: f64, billable_rate: Option<f64>) -> UnbilledEntry {
+        UnbilledEntry {
+            id: 1,
+            hours: Some(hours),
+            billable: true,
+            billable_rate,
+            project: Some(HarvestProject {
+                id: project.len() as u64,
+                name: project.to_string(),
+                code: None,
+                client: None,
+            }),
+            task: Some(TaskInfo {
+                id: 1,
+                name: task.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_invoice_lines_use_configured_rates() {
+        let config = InvoiceConfig {
+            hourly_rate: None,
+            rates: BTreeMap::from([("website".to_string(), 95.0)]),
+        };
+        let mut unbillable = entry("Website", "Development", 3.0, None);
+        unbillable.billable = false;
+        let entries = [
+            entry("Website", "Development", 2.0, Some(80.0)),
+            entry("Support", "Helpdesk", 1.0, Some(60.0)),
+            entry("Website", "Development", 1.5, None),
+            unbillable,
+        ];
+
+        let lines = invoice_lines(&entries, &config).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            (lines[0].project.as_str(), lines[0].rate),
+            ("Support", 60.0)
+        );
+        assert_eq!((lines[1].hours, lines[1].rate), (3.5, 95.0));
+        assert_eq!(lines[1].amount(), 332.5);
+
+        let no_rate = [entry("Support", "Helpdesk", 1.0, None)];
+        assert!(invoice_lines(&no_rate, &config).is_err());
+    }
+
+    #[test]
+    fn test_only_configured_projects_are_repriced() {
+        let config = InvoiceConfig {
+            hourly_rate: None,
+            rates: BTreeMap::from([("website".to_string(), 95.0)]),
+        };
+        // Project IDs are the name lengths
+        let entries = [
+            entry("Website", "Development", 2.0, Some(80.0)),
+            entry("Support", "Helpdesk", 1.0, Some(60.0)),
+        ];
+        let rates = configured_rates(&entries, &config);
+        assert_eq!(rates, BTreeMap::from([(7, 95.0)]));
+
+        let line = |id: u64, project_id: u64, unit_price: f64| InvoiceLineItem {
+            id,
+            project: Some(ProjectInfo {
+                id: project_id,
+                name: String::new(),
+            }),
+            unit_price,
+        };
+        let invoice = Invoice {
+            id: 1,
+            number: None,
+            amount: None,
+            state: None,
+            line_items: vec![line(10, 7, 80.0), line(11, 7, 95.0), line(12, 8, 60.0)],
+        };
+        assert_eq!(repriced_lines(&invoice, &rates), [(10, 95.0)]);
+    }
+}
@@ -1,21 +1,50 @@
 use crate::config::JiraConfig;
 use crate::error::{HarjiraError, Result};
-use crate::models::{JiraIssue, Ticket};
-use log::{debug, warn};
-use reqwest::blocking::Client;
+use crate::http::ApiClient;
+use crate::models::{
+    JiraIssue, JiraSearchResponse, JiraServerInfo, JiraSprint, JiraSprintsResponse, Ticket,
+    TicketComment, TicketDetails,
+};
+use crate::oauth;
+use log::debug;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 
+/// Fields requested when searching issues
+const ISSUE_FIELDS: &str = "summary,status,timeoriginalestimate,timeestimate,timespent";
+
 pub struct JiraClient {
-    client: Client,
+    client: ApiClient,
     config: JiraConfig,
+    /// Root for REST calls; differs from `base_url` when using OAuth
+    api_base_url: String,
 }
 
 impl JiraClient {
     pub fn new(config: JiraConfig) -> Result<Self> {
         let mut headers = HeaderMap::new();
 
+        // Prefer OAuth credentials from `harv login jira`, refreshing them if expired
+        let oauth_token = match &config.oauth {
+            Some(oauth_config) => oauth::valid_token(oauth_config)?,
+            None => None,
+        };
+
+        if oauth_token.is_none() && config.access_token.is_empty() {
+            return Err(HarjiraError::Config(
+                "No Jira credentials found. Run 'harv login jira' first.".to_string(),
+            ));
+        }
+
+        let (access_token, api_base_url) = match &oauth_token {
+            Some(token) => (token.access_token.clone(), token.api_base_url()),
+            None => (
+                config.access_token.clone(),
+                config.base_url.trim_end_matches('/').to_string(),
+            ),
+        };
+
         // Authorization: Bearer {token}
-        let auth_value = format!("Bearer {}", config.access_token);
+        let auth_value = format!("Bearer {}", access_token);
         headers.insert(
             AUTHORIZATION,
             HeaderValue::from_str(&auth_value).map_err(|e| {
@@ -26,20 +55,82 @@ impl JiraClient {
         // Content-Type: application/json
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
-        let client = Client::builder()
-            .default_headers(headers)
-            .build()
-            .map_err(|e| HarjiraError::Jira(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self {
+            client: ApiClient::new(headers),
+            config,
+            api_base_url,
+        })
+    }
 
-        Ok(Self { client, config })
+    /// Send REST calls to another API root, e.g. a mock server in tests
+    pub fn with_api_base_url(mut self, api_base_url: &str) -> Self {
+        self.api_base_url = api_base_url.trim_end_matches('/').to_string();
+        self
     }
 
     /// Get issue details from Jira
     pub fn get_issue(&self, ticket_key: &str) -> Result<Ticket> {
+        let issue = self.fetch_issue(ticket_key)?;
+        Ok(Ticket {
+            estimate: issue.fields.estimate(),
+            key: issue.key,
+            summary: issue.fields.summary,
+            status: Some(issue.fields.status.name),
+            confidence: None,
+            in_sprint: false,
+        })
+    }
+
+    /// Description and the last `max_comments` comments of an issue, as plain text
+    pub fn get_issue_details(
+        &self,
+        ticket_key: &str,
+        max_comments: usize,
+    ) -> Result<TicketDetails> {
+        let issue = self.fetch_issue(ticket_key)?;
+        let description = issue
+            .fields
+            .description
+            .as_ref()
+            .map(adf_to_text)
+            .filter(|text| !text.is_empty());
+
+        let mut comments: Vec<TicketComment> = issue
+            .fields
+            .comment
+            .map(|page| page.comments)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|comment| TicketComment {
+                author: comment
+                    .author
+                    .map_or_else(|| "Unknown".to_string(), |a| a.display_name),
+                body: adf_to_text(&comment.body),
+            })
+            .filter(|comment| !comment.body.is_empty())
+            .collect();
+        comments.drain(..comments.len().saturating_sub(max_comments));
+
+        Ok(TicketDetails {
+            key: issue.key,
+            summary: issue.fields.summary,
+            description,
+            comments,
+        })
+    }
+
+    /// Numeric ID of an issue, which Tempo uses instead of the key
+    pub fn get_issue_id(&self, ticket_key: &str) -> Result<u64> {
+        let issue = self.fetch_issue(ticket_key)?;
+        issue.id.parse().map_err(|_| {
+            HarjiraError::Jira(format!("Jira returned no numeric ID for {}", ticket_key))
+        })
+    }
+
+    fn fetch_issue(&self, ticket_key: &str) -> Result<JiraIssue> {
         let url = format!(
             "{}/rest/api/3/issue/{}",
-            self.config.base_url.trim_end_matches('/'),
-            ticket_key
+            self.api_base_url, ticket_key
         );
 
         debug!("GET {}", url);
@@ -48,7 +139,7 @@ impl JiraClient {
             .client
             .get(&url)
             .send()
-            .map_err(|e| HarjiraError::Jira(format!("Request failed: {}", e)))?;
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -63,8 +154,9 @@ impl JiraClient {
                     ticket_key
                 )));
             } else if status == 401 {
-                return Err(HarjiraError::Jira(
-                    "Authentication failed. Check your Jira access token.".to_string(),
+                return Err(HarjiraError::Auth(
+                    "Jira authentication failed. Check your Jira access token or run 'harv login jira'."
+                        .to_string(),
                 ));
             } else if status == 403 {
                 return Err(HarjiraError::Jira(format!(
@@ -88,33 +180,188 @@ impl JiraClient {
             issue.key, issue.fields.summary
         );
 
-        Ok(Ticket {
-            key: issue.key,
-            summary: issue.fields.summary,
-            status: Some(issue.fields.status.name),
-        })
+        Ok(issue)
     }
 
-    /// Get multiple issues at once
-    pub fn get_issues(&self, ticket_keys: &[String]) -> Vec<Ticket> {
-        let mut tickets = Vec::new();
-
-        for key in ticket_keys {
-            match self.get_issue(key) {
-                Ok(ticket) => tickets.push(ticket),
-                Err(e) => {
-                    warn!("Failed to fetch Jira ticket {}: {}", key, e);
-                    // Create a ticket with just the key for failed fetches
-                    tickets.push(Ticket {
-                        key: key.clone(),
-                        summary: format!("(Failed to fetch: {})", e),
-                        status: None,
-                    });
-                }
+    /// Issues assigned to the current user that are open or recently updated
+    ///
+    /// Used as candidates when inferring tickets for commits without a key.
+    pub fn get_my_recent_issues(&self, max_results: u32) -> Result<Vec<Ticket>> {
+        let url = format!("{}/rest/api/3/search/jql", self.api_base_url);
+        let jql = "assignee = currentUser() AND (statusCategory != Done OR updated >= -14d) \
+                   ORDER BY updated DESC";
+
+        debug!("GET {} (jql: {})", url, jql);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("jql", jql),
+                ("fields", ISSUE_FIELDS),
+                ("maxResults", &max_results.to_string()),
+            ])
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        if response.status() == 401 {
+            return Err(HarjiraError::Auth(
+                "Jira authentication failed. Check your Jira access token or run 'harv login jira'."
+                    .to_string(),
+            ));
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(HarjiraError::Jira(format!(
+                "Issue search failed with status {}: {}",
+                status, error_text
+            )));
+        }
+
+        let search: JiraSearchResponse = response
+            .json()
+            .map_err(|e| HarjiraError::Jira(format!("Failed to parse search response: {}", e)))?;
+
+        debug!("Found {} recent Jira issues", search.issues.len());
+
+        Ok(search
+            .issues
+            .into_iter()
+            .map(|issue| Ticket {
+                estimate: issue.fields.estimate(),
+                key: issue.key,
+                summary: issue.fields.summary,
+                status: Some(issue.fields.status.name),
+                confidence: None,
+                in_sprint: false,
+            })
+            .collect())
+    }
+
+    /// The board's active sprint, None when no sprint is running
+    pub fn get_active_sprint(&self, board_id: u64) -> Result<Option<JiraSprint>> {
+        let url = format!(
+            "{}/rest/agile/1.0/board/{}/sprint",
+            self.api_base_url, board_id
+        );
+
+        debug!("GET {} (state: active)", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("state", "active")])
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(agile_error(response, &format!("board {}", board_id)));
+        }
+
+        let sprints: JiraSprintsResponse = response
+            .json()
+            .map_err(|e| HarjiraError::Jira(format!("Failed to parse sprint response: {}", e)))?;
+
+        Ok(sprints.values.into_iter().find(|s| s.state == "active"))
+    }
+
+    /// Issues in a sprint assigned to the current user
+    pub fn get_sprint_issues(&self, board_id: u64, sprint_id: u64) -> Result<Vec<Ticket>> {
+        let url = format!(
+            "{}/rest/agile/1.0/board/{}/sprint/{}/issue",
+            self.api_base_url, board_id, sprint_id
+        );
+        let jql = "assignee = currentUser() ORDER BY rank";
+
+        debug!("GET {} (jql: {})", url, jql);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("jql", jql),
+                ("fields", ISSUE_FIELDS),
+                ("maxResults", "100"),
+            ])
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(agile_error(response, &format!("sprint {}", sprint_id)));
+        }
+
+        let search: JiraSearchResponse = response
+            .json()
+            .map_err(|e| HarjiraError::Jira(format!("Failed to parse sprint issues: {}", e)))?;
+
+        debug!(
+            "Found {} issues in sprint {}",
+            search.issues.len(),
+            sprint_id
+        );
+
+        Ok(search
+            .issues
+            .into_iter()
+            .map(|issue| Ticket {
+                estimate: issue.fields.estimate(),
+                key: issue.key,
+                summary: issue.fields.summary,
+                status: Some(issue.fields.status.name),
+                confidence: None,
+                in_sprint: true,
+            })
+            .collect())
+    }
+
+    /// The current user's issues in the board's active sprint
+    ///
+    /// Empty when the board has no active sprint.
+    pub fn get_my_sprint_issues(&self, board_id: u64) -> Result<Vec<Ticket>> {
+        match self.get_active_sprint(board_id)? {
+            Some(sprint) => {
+                debug!("Active sprint: {} ({})", sprint.name, sprint.id);
+                self.get_sprint_issues(board_id, sprint.id)
             }
+            None => Ok(Vec::new()),
         }
+    }
+
+    /// Title and version of the Jira site, to check that it answers
+    pub fn get_server_info(&self) -> Result<JiraServerInfo> {
+        let url = format!("{}/rest/api/3/serverInfo", self.api_base_url);
+        debug!("GET {}", url);
 
-        tickets
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        if response.status() == 401 {
+            return Err(HarjiraError::Auth(
+                "Jira authentication failed. Check your Jira access token or run 'harv login jira'."
+                    .to_string(),
+            ));
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(HarjiraError::Jira(format!(
+                "Server info request failed with status {}: {}",
+                status, error_text
+            )));
+        }
+
+        response
+            .json()
+            .map_err(|e| HarjiraError::Jira(format!("Failed to parse server info: {}", e)))
     }
 
     /// Build the Jira ticket URL
@@ -126,3 +373,69 @@ impl JiraClient {
         )
     }
 }
+
+/// Plain text of an Atlassian Document Format node, one line per block
+///
+/// Older servers send descriptions as plain strings, which are kept as-is.
+fn adf_to_text(node: &serde_json::Value) -> String {
+    fn walk(node: &serde_json::Value, out: &mut String) {
+        if let Some(text) = node.as_str() {
+            out.push_str(text);
+            return;
+        }
+        match node["type"].as_str() {
+            Some("text") => out.push_str(node["text"].as_str().unwrap_or_default()),
+            Some("hardBreak") => out.push('\n'),
+            Some("mention") | Some("emoji") => {
+                let attrs = &node["attrs"];
+                out.push_str(
+                    attrs["text"]
+                        .as_str()
+                        .or(attrs["shortName"].as_str())
+                        .unwrap_or_default(),
+                );
+            }
+            _ => {}
+        }
+        for child in node["content"].as_array().into_iter().flatten() {
+            walk(child, out);
+        }
+        if matches!(
+            node["type"].as_str(),
+            Some("paragraph" | "heading" | "codeBlock" | "listItem" | "blockquote")
+        ) {
+            out.push('\n');
+        }
+    }
+
+    let mut out = String::new();
+    walk(node, &mut out);
+    out.lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Error for a failed Agile API call about `what` (a board or sprint)
+fn agile_error(response: reqwest::blocking::Response, what: &str) -> HarjiraError {
+    let status = response.status();
+    let error_text = response
+        .text()
+        .unwrap_or_else(|_| "Unknown error".to_string());
+
+    match status.as_u16() {
+        401 => HarjiraError::Auth(
+            "Jira authentication failed. Check your Jira access token or run 'harv login jira'."
+                .to_string(),
+        ),
+        404 => HarjiraError::Jira(format!(
+            "Jira {} not found. Check jira.board_id in your config.",
+            what
+        )),
+        _ => HarjiraError::Jira(format!(
+            "Agile API request for {} failed with status {}: {}",
+            what, status, error_text
+        )),
+    }
+}
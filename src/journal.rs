@@ -0,0 +1,459 @@
+//! Journal of the changes harv makes in Harvest
+//!
+//! The Harvest client notes every entry it creates, stops, restarts, updates
+//! or deletes. When a command finishes those notes are saved as one batch,
+//! which `harv undo` reverses, and appended to the history that
+//! `harv history` shows. The history is never trimmed or rewritten.
+
+use crate::error::{HarjiraError, Result};
+use crate::models::TimeEntry;
+use chrono::{DateTime, Utc};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const JOURNAL_VERSION: u8 = 1;
+
+/// Older batches are dropped when the journal is saved
+const MAX_BATCHES: usize = 20;
+
+/// Changes made by the running command, not yet saved as a batch
+static PENDING: Mutex<Pending> = Mutex::new(Pending {
+    account_id: String::new(),
+    changes: Vec::new(),
+    summaries: Vec::new(),
+    undoable: true,
+});
+
+struct Pending {
+    account_id: String,
+    changes: Vec<Change>,
+    /// What each change left the entry as, for the history
+    summaries: Vec<String>,
+    undoable: bool,
+}
+
+/// One change to a time entry, with what's needed to reverse it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Change {
+    /// A new entry or running timer
+    Created {
+        entry_id: u64,
+    },
+    Stopped {
+        entry_id: u64,
+    },
+    Restarted {
+        entry_id: u64,
+    },
+    /// The entry as it was before, when it could be fetched
+    Updated {
+        entry_id: u64,
+        previous: Option<TimeEntry>,
+    },
+    Deleted {
+        entry_id: u64,
+        previous: Option<TimeEntry>,
+    },
+}
+
+impl Change {
+    pub fn entry_id(&self) -> u64 {
+        match self {
+            Change::Created { entry_id }
+            | Change::Stopped { entry_id }
+            | Change::Restarted { entry_id }
+            | Change::Updated { entry_id, .. }
+            | Change::Deleted { entry_id, .. } => *entry_id,
+        }
+    }
+
+    /// "created", "stopped", ... as shown in the history
+    pub fn action(&self) -> &'static str {
+        match self {
+            Change::Created { .. } => "created",
+            Change::Stopped { .. } => "stopped",
+            Change::Restarted { .. } => "restarted",
+            Change::Updated { .. } => "updated",
+            Change::Deleted { .. } => "deleted",
+        }
+    }
+
+    /// What undoing this change does, e.g. "Delete entry 42"
+    pub fn undo_label(&self) -> String {
+        match self {
+            Change::Created { entry_id } => format!("Delete entry {}", entry_id),
+            Change::Stopped { entry_id } => format!("Restart timer {}", entry_id),
+            Change::Restarted { entry_id } => format!("Stop timer {}", entry_id),
+            Change::Updated {
+                entry_id,
+                previous: Some(previous),
+            } => format!(
+                "Set entry {} back to {:.2}h \"{}\"",
+                entry_id,
+                previous.hours.unwrap_or(0.0),
+                previous.notes.as_deref().unwrap_or("")
+            ),
+            Change::Deleted {
+                entry_id,
+                previous: Some(previous),
+            } => format!(
+                "Recreate entry {} ({:.2}h on {})",
+                entry_id,
+                previous.hours.unwrap_or(0.0),
+                previous.spent_date
+            ),
+            Change::Updated { entry_id, .. } | Change::Deleted { entry_id, .. } => {
+                format!("Can't undo the change to entry {}", entry_id)
+            }
+        }
+    }
+}
+
+/// The changes made by one command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Batch {
+    pub at: DateTime<Utc>,
+    /// Arguments the command was run with, e.g. "log PROJ-1 1h"
+    pub command: String,
+    /// Harvest account the entries belong to
+    pub account_id: String,
+    pub changes: Vec<Change>,
+}
+
+/// Saved batches, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journal {
+    version: u8,
+    #[serde(default)]
+    pub batches: Vec<Batch>,
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self {
+            version: JOURNAL_VERSION,
+            batches: Vec::new(),
+        }
+    }
+}
+
+impl Journal {
+    /// Load the journal, empty if there is none yet
+    pub fn load() -> Result<Self> {
+        let path = journal_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let journal: Journal = serde_json::from_str(&contents)?;
+
+        if journal.version > JOURNAL_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "Journal version {} is newer than supported version {}",
+                journal.version, JOURNAL_VERSION
+            )));
+        }
+
+        Ok(journal)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = journal_path()?;
+
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(self)?)?;
+
+        debug!("Saved journal to {}", path.display());
+        Ok(())
+    }
+
+    /// Add a batch, dropping the oldest ones past `MAX_BATCHES`
+    pub fn push(&mut self, batch: Batch) {
+        self.batches.push(batch);
+        let excess = self.batches.len().saturating_sub(MAX_BATCHES);
+        self.batches.drain(..excess);
+    }
+}
+
+/// One change in the history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub at: DateTime<Utc>,
+    /// Arguments the command was run with
+    pub command: String,
+    pub account_id: String,
+    pub change: Change,
+    /// The entry after the change, e.g. "PROJ-1 - Login (1.50h on 2026-10-14)"
+    pub summary: String,
+}
+
+/// All changes in the history, oldest first
+///
+/// Lines that can't be read are skipped, so one bad write doesn't hide the rest.
+pub fn history() -> Result<Vec<HistoryRecord>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                debug!("Skipping unreadable history line: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// The last `limit` records, optionally only those about one entry
+pub fn recent_history(
+    records: Vec<HistoryRecord>,
+    entry_id: Option<u64>,
+    limit: usize,
+) -> Vec<HistoryRecord> {
+    let mut records: Vec<HistoryRecord> = records
+        .into_iter()
+        .filter(|record| entry_id.is_none_or(|id| record.change.entry_id() == id))
+        .collect();
+    let excess = records.len().saturating_sub(limit);
+    records.drain(..excess);
+    records
+}
+
+/// Short description of an entry for the history
+pub fn summarize(entry: &TimeEntry) -> String {
+    format!(
+        "{} ({:.2}h on {})",
+        entry.notes.as_deref().unwrap_or("(no description)"),
+        entry.hours.unwrap_or(0.0),
+        entry.spent_date
+    )
+}
+
+/// Note a change the Harvest client just made
+pub fn record(account_id: &str, change: Change, summary: String) {
+    let mut pending = PENDING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    pending.account_id = account_id.to_string();
+    pending.changes.push(change);
+    pending.summaries.push(summary);
+}
+
+/// Keep the changes recorded so far out of the undo journal, e.g. the ones
+/// `harv undo` made. They still go into the history.
+pub fn skip_undo() {
+    PENDING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .undoable = false;
+}
+
+/// Save the recorded changes as one batch, logs errors but doesn't fail
+pub fn commit(command: &str) {
+    let pending = {
+        let mut pending = PENDING
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let undoable = std::mem::replace(&mut pending.undoable, true);
+        if pending.changes.is_empty() {
+            return;
+        }
+        Pending {
+            account_id: std::mem::take(&mut pending.account_id),
+            changes: std::mem::take(&mut pending.changes),
+            summaries: std::mem::take(&mut pending.summaries),
+            undoable,
+        }
+    };
+    let at = Utc::now();
+
+    let records: Vec<HistoryRecord> = pending
+        .changes
+        .iter()
+        .zip(pending.summaries)
+        .map(|(change, summary)| HistoryRecord {
+            at,
+            command: command.to_string(),
+            account_id: pending.account_id.clone(),
+            change: change.clone(),
+            summary,
+        })
+        .collect();
+    if let Err(e) = append_history(&records) {
+        warn!("Failed to write the change history: {}", e);
+    }
+
+    if !pending.undoable {
+        return;
+    }
+    let batch = Batch {
+        at,
+        command: command.to_string(),
+        account_id: pending.account_id,
+        changes: pending.changes,
+    };
+    let result = Journal::load().and_then(|mut journal| {
+        journal.push(batch);
+        journal.save()
+    });
+    if let Err(e) = result {
+        warn!("Failed to save the undo journal: {}", e);
+    }
+}
+
+fn append_history(records: &[HistoryRecord]) -> Result<()> {
+    let path = history_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut lines = String::new();
+    for record in records {
+        lines.push_str(&serde_json::to_string(record)?);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = file.metadata()?.permissions();
+        perms.set_mode(0o600);
+        fs::set_permissions(&path, perms)?;
+    }
+    // One write per command, so concurrent runs don't interleave their lines
+    file.write_all(lines.as_bytes())?;
+
+    debug!("Appended {} changes to {}", records.len(), path.display());
+    Ok(())
+}
+
+fn journal_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("journal.json"))
+}
+
+fn history_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("history.jsonl"))
+}
+
+fn config_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch(command: &str) -> Batch {
+        Batch {
+            at: Utc::now(),
+            command: command.to_string(),
+            account_id: "123".to_string(),
+            changes: vec![Change::Created { entry_id: 1 }],
+        }
+    }
+
+    #[test]
+    fn test_push_keeps_the_newest_batches() {
+        let mut journal = Journal::default();
+        for i in 0..MAX_BATCHES + 3 {
+            journal.push(batch(&format!("log PROJ-{} 1h", i)));
+        }
+        assert_eq!(journal.batches.len(), MAX_BATCHES);
+        assert_eq!(journal.batches[0].command, "log PROJ-3 1h");
+    }
+
+    #[test]
+    fn test_undo_labels() {
+        let previous = TimeEntry {
+            id: 7,
+            spent_date: "2026-10-13".to_string(),
+            hours: Some(1.5),
+            notes: Some("PROJ-1 - Login".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Change::Created { entry_id: 1 }.undo_label(),
+            "Delete entry 1"
+        );
+        assert_eq!(
+            Change::Stopped { entry_id: 2 }.undo_label(),
+            "Restart timer 2"
+        );
+        assert_eq!(
+            Change::Updated {
+                entry_id: 7,
+                previous: Some(previous.clone()),
+            }
+            .undo_label(),
+            "Set entry 7 back to 1.50h \"PROJ-1 - Login\""
+        );
+        assert_eq!(
+            Change::Deleted {
+                entry_id: 7,
+                previous: Some(previous),
+            }
+            .undo_label(),
+            "Recreate entry 7 (1.50h on 2026-10-13)"
+        );
+        assert_eq!(
+            Change::Deleted {
+                entry_id: 8,
+                previous: None,
+            }
+            .undo_label(),
+            "Can't undo the change to entry 8"
+        );
+    }
+
+    #[test]
+    fn test_recent_history() {
+        let records: Vec<HistoryRecord> = [1, 2, 1, 3, 1]
+            .iter()
+            .enumerate()
+            .map(|(i, id)| HistoryRecord {
+                at: Utc::now(),
+                command: format!("command {}", i),
+                account_id: "123".to_string(),
+                change: Change::Stopped { entry_id: *id },
+                summary: String::new(),
+            })
+            .collect();
+
+        let commands = |records: Vec<HistoryRecord>| -> Vec<String> {
+            records.into_iter().map(|r| r.command).collect()
+        };
+        assert_eq!(
+            commands(recent_history(records.clone(), None, 2)),
+            vec!["command 3", "command 4"]
+        );
+        assert_eq!(
+            commands(recent_history(records, Some(1), 10)),
+            vec!["command 0", "command 2", "command 4"]
+        );
+    }
+
+    #[test]
+    fn test_change_is_tagged_json() {
+        let json = serde_json::to_value(Change::Stopped { entry_id: 5 }).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "action": "stopped", "entry_id": 5 })
+        );
+    }
+}
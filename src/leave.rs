@@ -0,0 +1,103 @@
+//! Days marked as leave by `harv leave`
+//!
+//! The leave itself is a Harvest entry. The mark is kept here so `generate`
+//! and `today` know the day is done, even when its hours fall short of the
+//! target or commits were made on it anyway.
+
+use crate::error::{HarjiraError, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const LEAVE_FILE_VERSION: u8 = 1;
+
+/// A day off, e.g. vacation or sick leave
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaveDay {
+    /// The `--type` it was logged with, e.g. "vacation"
+    pub kind: String,
+    pub hours: f64,
+}
+
+/// Leave days by date ("YYYY-MM-DD")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaveDays {
+    version: u8,
+    #[serde(default)]
+    days: BTreeMap<String, LeaveDay>,
+}
+
+impl Default for LeaveDays {
+    fn default() -> Self {
+        Self {
+            version: LEAVE_FILE_VERSION,
+            days: BTreeMap::new(),
+        }
+    }
+}
+
+impl LeaveDays {
+    /// Load the leave days, none if the file doesn't exist
+    pub fn load() -> Result<Self> {
+        let path = leave_file_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let leave: LeaveDays = serde_json::from_str(&contents).map_err(|e| {
+            HarjiraError::Config(format!(
+                "Failed to parse leave file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        if leave.version > LEAVE_FILE_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "Leave file version {} is newer than supported version {}",
+                leave.version, LEAVE_FILE_VERSION
+            )));
+        }
+
+        Ok(leave)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = leave_file_path()?;
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(self)?)?;
+
+        debug!("Saved leave days to {}", path.display());
+        Ok(())
+    }
+
+    /// The leave on `date`, if the day is marked
+    pub fn get(&self, date: &str) -> Option<&LeaveDay> {
+        self.days.get(date)
+    }
+
+    pub fn insert(&mut self, date: &str, day: LeaveDay) {
+        self.days.insert(date.to_string(), day);
+    }
+}
+
+/// Leave on `date`, None when the day isn't marked or the file can't be read
+///
+/// For reports that are still useful without the marks.
+pub fn on(date: &str) -> Option<LeaveDay> {
+    match LeaveDays::load() {
+        Ok(leave) => leave.get(date).cloned(),
+        Err(e) => {
+            debug!("Ignoring leave days: {}", e);
+            None
+        }
+    }
+}
+
+fn leave_file_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("leave.json"))
+}
@@ -1,19 +1,67 @@
+pub mod activity;
 pub mod ai;
+pub mod aliases;
+pub mod azure_devops;
+pub mod backend;
+pub mod capacity;
+pub mod catalog;
+pub mod commands;
 pub mod config;
+pub mod config_schema;
+pub mod conventional;
+pub mod dedupe;
 pub mod error;
+pub mod expenses;
+pub mod favorites;
+pub mod fs;
 pub mod git;
+pub mod github;
 pub mod harvest;
+pub mod hook;
+pub mod http;
+pub mod invoices;
 pub mod jira;
+pub mod journal;
+pub mod leave;
+pub mod linear;
+pub mod lock;
+pub mod logging;
 pub mod models;
+pub mod oauth;
+pub mod offline;
+pub mod output;
+pub mod plan;
+pub mod progress;
 pub mod prompt;
+pub mod report;
+pub mod resume;
+pub mod review;
+pub mod search;
+pub mod secrets;
+pub mod service;
+pub mod setup;
+pub mod stats;
+pub mod status_cache;
+pub mod tempo;
 pub mod ticket_parser;
+pub mod ticket_time;
 pub mod time_parser;
+pub mod timezone;
+pub mod toggl;
+pub mod tracker;
 pub mod usage;
 
 // Re-export commonly used types
+pub use azure_devops::AzureDevOpsClient;
+pub use backend::TimeBackend;
 pub use config::Config;
-pub use error::{HarjiraError, Result};
+pub use error::{HarjiraError, HarvestApiError, Result};
+pub use github::GitHubClient;
 pub use harvest::HarvestClient;
 pub use jira::JiraClient;
+pub use linear::LinearClient;
 pub use models::{Context, Ticket};
+pub use tempo::TempoClient;
 pub use time_parser::parse_hours;
+pub use toggl::TogglClient;
+pub use tracker::IssueTracker;
@@ -0,0 +1,153 @@
+//! Linear issue tracker
+//!
+//! Linear only has a GraphQL API. Issues are looked up by their identifier
+//! ("ENG-123"), which the `issue` query accepts in place of the UUID.
+
+use crate::config::LinearConfig;
+use crate::error::{HarjiraError, Result};
+use crate::http::ApiClient;
+use crate::models::{LinearIssueData, LinearResponse, LinearViewerData, Ticket};
+use crate::tracker::IssueTracker;
+use log::debug;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+const DEFAULT_BASE_URL: &str = "https://api.linear.app/graphql";
+
+const ISSUE_QUERY: &str = "query Issue($id: String!) { \
+     issue(id: $id) { identifier title state { name } } }";
+
+/// Assigned issues that are still open or were updated in the last two weeks
+const RECENT_ISSUES_QUERY: &str = "query RecentIssues($first: Int!) { \
+     viewer { assignedIssues(first: $first, orderBy: updatedAt, filter: { or: [ \
+     { state: { type: { nin: [\"completed\", \"canceled\"] } } }, \
+     { updatedAt: { gt: \"-P2W\" } } ] }) { \
+     nodes { identifier title state { name } } } } }";
+
+pub struct LinearClient {
+    client: ApiClient,
+    base_url: String,
+    config: LinearConfig,
+}
+
+impl LinearClient {
+    pub fn new(config: LinearConfig) -> Result<Self> {
+        if config.api_key.is_empty() {
+            return Err(HarjiraError::Config(
+                "No Linear API key configured in [linear]".to_string(),
+            ));
+        }
+
+        // Personal API keys go in the header as they are, without "Bearer"
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&config.api_key)
+                .map_err(|e| HarjiraError::Config(format!("Invalid Linear API key: {}", e)))?,
+        );
+
+        let base_url = config
+            .base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_BASE_URL)
+            .to_string();
+
+        Ok(Self {
+            client: ApiClient::new(headers),
+            base_url,
+            config,
+        })
+    }
+
+    /// Send queries to another endpoint, e.g. a mock server in tests
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    fn query<T: DeserializeOwned>(&self, query: &str, variables: serde_json::Value) -> Result<T> {
+        debug!("POST {} ({})", self.base_url, query);
+
+        let response = self
+            .client
+            .post(&self.base_url)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        let status = response.status();
+        if status == 401 {
+            return Err(HarjiraError::Auth(
+                "Linear authentication failed. Check linear.api_key in your config.".to_string(),
+            ));
+        }
+        // Query errors come back as 400 with the same body as a 200 with errors
+        let body = response.text().unwrap_or_default();
+        let reply: LinearResponse<T> = serde_json::from_str(&body).map_err(|e| {
+            if status.is_success() {
+                HarjiraError::Linear(format!("Failed to parse response: {}", e))
+            } else {
+                HarjiraError::Linear(format!("{}: {}", status, body.trim()))
+            }
+        })?;
+
+        if !reply.errors.is_empty() {
+            let messages: Vec<&str> = reply.errors.iter().map(|e| e.message.as_str()).collect();
+            return Err(HarjiraError::Linear(messages.join("; ")));
+        }
+        reply
+            .data
+            .ok_or_else(|| HarjiraError::Linear("Response had no data".to_string()))
+    }
+}
+
+impl IssueTracker for LinearClient {
+    fn name(&self) -> &str {
+        "Linear"
+    }
+
+    fn get_issue(&self, ticket_key: &str) -> Result<Ticket> {
+        let data: LinearIssueData = self
+            .query(ISSUE_QUERY, json!({ "id": ticket_key }))
+            .map_err(|e| match e {
+                // Unknown identifiers are reported as an error rather than a null issue
+                HarjiraError::Linear(msg) if msg.to_lowercase().contains("not found") => {
+                    not_found(ticket_key)
+                }
+                other => other,
+            })?;
+        let issue = data.issue.ok_or_else(|| not_found(ticket_key))?;
+
+        debug!(
+            "Retrieved Linear issue: {} - {}",
+            issue.identifier, issue.title
+        );
+        Ok(issue.to_ticket())
+    }
+
+    fn get_my_recent_issues(&self, max_results: u32) -> Result<Vec<Ticket>> {
+        let data: LinearViewerData =
+            self.query(RECENT_ISSUES_QUERY, json!({ "first": max_results }))?;
+        let issues = data.viewer.assigned_issues.nodes;
+
+        debug!("Found {} recent Linear issues", issues.len());
+        Ok(issues.into_iter().map(|issue| issue.to_ticket()).collect())
+    }
+
+    /// Built from the workspace, so no request is needed
+    fn get_ticket_url(&self, ticket_key: &str) -> String {
+        format!(
+            "https://linear.app/{}/issue/{}",
+            self.config.workspace.trim_matches('/'),
+            ticket_key
+        )
+    }
+}
+
+fn not_found(ticket_key: &str) -> HarjiraError {
+    HarjiraError::Linear(format!(
+        "Ticket {} not found. Verify the ticket key is correct.",
+        ticket_key
+    ))
+}
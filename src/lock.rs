@@ -0,0 +1,89 @@
+//! Lock that keeps two harv processes from changing Harvest at once
+//!
+//! A sync started by the systemd timer and a manual `harv stop` could
+//! otherwise both stop and start timers. The lock is an advisory file lock,
+//! so it goes away with the process even if harv crashes.
+
+use crate::error::{HarjiraError, Result};
+use log::debug;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+/// Held while a command runs, released when dropped
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Take the lock, or fail if another harv holds it
+    pub fn acquire() -> Result<Self> {
+        Self::acquire_at(&lock_path()?)
+    }
+
+    fn acquire_at(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut holder = String::new();
+                file.read_to_string(&mut holder).ok();
+                let holder = match holder.trim() {
+                    "" => String::new(),
+                    pid => format!(" (pid {})", pid),
+                };
+                return Err(HarjiraError::Locked(format!(
+                    "Another harv instance{} is running. Try again when it's done, or pass --no-lock.",
+                    holder
+                )));
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        // Note who holds the lock for the message above
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "{}", std::process::id())?;
+
+        debug!("Acquired {}", path.display());
+        Ok(Self { _file: file })
+    }
+}
+
+fn lock_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("harv.lock"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_lock_fails_until_first_is_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("harv.lock");
+
+        let first = InstanceLock::acquire_at(&path).unwrap();
+        let err = InstanceLock::acquire_at(&path).unwrap_err();
+        assert!(matches!(err, HarjiraError::Locked(_)));
+        assert!(err
+            .to_string()
+            .contains(&format!("(pid {})", std::process::id())));
+
+        drop(first);
+        assert!(InstanceLock::acquire_at(&path).is_ok());
+    }
+}
@@ -0,0 +1,291 @@
+//! Console log filtering for `-v`, `-vv` and `-vvv`, and the `[logging]` file
+//!
+//! Each level adds detail: harv's own debug messages, then one line per HTTP
+//! request, then the headers and bodies of those requests. Other crates stay
+//! at info until the last level, their internals are rarely what's wrong.
+//!
+//! The log file gets JSON lines at its own level whatever the console shows,
+//! so runs from the systemd timer leave something to look at afterwards.
+
+use crate::config::LoggingConfig;
+use chrono::{DateTime, Local, SecondsFormat};
+use log::{warn, Level, LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+/// The log file, once a config with `[logging] file` has been loaded
+static FILE: OnceLock<Mutex<FileSink>> = OnceLock::new();
+
+/// `env_logger` filter for a verbosity count, used unless RUST_LOG is set
+pub fn filter(verbosity: u8, quiet: bool) -> &'static str {
+    match verbosity {
+        0 if quiet => "error",
+        0 => "info",
+        // Request lines are debug messages of harv::http, kept for -vv
+        1 => "info,harv=debug,harv::http=info",
+        2 => "info,harv=debug",
+        _ => "debug,harv=trace",
+    }
+}
+
+/// Install the logger: `console` as configured, plus the log file once known
+pub fn init(console: env_logger::Logger) {
+    let max_level = console.filter();
+    if log::set_boxed_logger(Box::new(TeeLogger { console })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Start writing the log file from `[logging]`
+///
+/// Only the first call has an effect. A file that can't be opened is
+/// reported and skipped, it's no reason to stop the command.
+pub fn configure(config: &LoggingConfig) {
+    let Some(ref file) = config.file else {
+        return;
+    };
+    if FILE.get().is_some() {
+        return;
+    }
+
+    let level = LevelFilter::from_str(&config.level).unwrap_or(LevelFilter::Debug);
+    let sink = FileSink::open(
+        expand_home(file),
+        level,
+        config.max_size_mb.saturating_mul(1024 * 1024),
+        config.keep_days,
+    );
+    match sink {
+        Ok(sink) => {
+            if FILE.set(Mutex::new(sink)).is_ok() && level > log::max_level() {
+                log::set_max_level(level);
+            }
+        }
+        Err(e) => warn!("Not writing the log file {}: {}", file, e),
+    }
+}
+
+/// `~/...` relative to the home directory, anything else as it is
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+struct TeeLogger {
+    console: env_logger::Logger,
+}
+
+impl Log for TeeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.console.enabled(metadata)
+            || FILE.get().is_some_and(|file| {
+                file.lock()
+                    .map(|file| file.enabled(metadata))
+                    .unwrap_or(false)
+            })
+    }
+
+    fn log(&self, record: &Record) {
+        if self.console.matches(record) {
+            self.console.log(record);
+        }
+        if let Some(file) = FILE.get() {
+            let mut file = file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if file.enabled(record.metadata()) {
+                file.write(record);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+    }
+}
+
+struct FileSink {
+    path: PathBuf,
+    file: File,
+    /// Level for harv's own messages, other crates only log warnings
+    level: LevelFilter,
+    size: u64,
+    max_bytes: u64,
+}
+
+impl FileSink {
+    fn open(
+        path: PathBuf,
+        level: LevelFilter,
+        max_bytes: u64,
+        keep_days: u32,
+    ) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if size >= max_bytes {
+            rotate(&path, Local::now())?;
+        }
+        if keep_days > 0 {
+            prune(
+                &path,
+                Duration::from_secs(u64::from(keep_days) * 24 * 60 * 60),
+            );
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            level,
+            size,
+            max_bytes,
+        })
+    }
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        if metadata.target().starts_with("harv") {
+            metadata.level() <= self.level
+        } else {
+            metadata.level() <= Level::Warn
+        }
+    }
+
+    fn write(&mut self, record: &Record) {
+        let line = format_line(record, Local::now());
+        // Keep going after a failed rotation, a big log beats a lost one
+        if self.size + line.len() as u64 > self.max_bytes && self.size > 0 {
+            let reopened = rotate(&self.path, Local::now()).and_then(|_| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+            });
+            if let Ok(file) = reopened {
+                self.file = file;
+                self.size = 0;
+            }
+        }
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+}
+
+/// One JSON line per message: time, level, module, process and message
+fn format_line(record: &Record, now: DateTime<Local>) -> String {
+    let line = serde_json::json!({
+        "time": now.to_rfc3339_opts(SecondsFormat::Millis, false),
+        "level": record.level().as_str(),
+        "target": record.target(),
+        "pid": std::process::id(),
+        "message": record.args().to_string(),
+    });
+    format!("{}\n", line)
+}
+
+/// Move the log aside as `harv.log.20261014-103215`
+fn rotate(path: &Path, now: DateTime<Local>) -> std::io::Result<()> {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(now.format(".%Y%m%d-%H%M%S").to_string());
+    fs::rename(path, rotated)
+}
+
+/// Delete rotated logs last written more than `keep` ago
+fn prune(path: &Path, keep: Duration) {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let cutoff = SystemTime::now().checked_sub(keep);
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with(&prefix) {
+            continue;
+        }
+        let modified = entry.metadata().and_then(|m| m.modified()).ok();
+        if modified
+            .zip(cutoff)
+            .is_some_and(|(modified, cutoff)| modified < cutoff)
+        {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_by_verbosity() {
+        assert_eq!(filter(0, false), "info");
+        assert_eq!(filter(0, true), "error");
+        // Asking for detail beats --quiet
+        assert_eq!(filter(1, true), "info,harv=debug,harv::http=info");
+        assert_eq!(filter(2, false), "info,harv=debug");
+        assert_eq!(filter(3, false), "debug,harv=trace");
+        assert_eq!(filter(5, false), "debug,harv=trace");
+    }
+
+    #[test]
+    fn test_format_line() {
+        let now = DateTime::parse_from_rfc3339("2026-10-14T10:32:15.123+02:00")
+            .unwrap()
+            .with_timezone(&Local);
+        let line = format_line(
+            &Record::builder()
+                .level(Level::Info)
+                .target("harv::commands::sync")
+                .args(format_args!("Started timer for {}", "PROJ-1"))
+                .build(),
+            now,
+        );
+
+        assert!(line.ends_with('\n'));
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["level"], "INFO");
+        assert_eq!(json["target"], "harv::commands::sync");
+        assert_eq!(json["message"], "Started timer for PROJ-1");
+        assert_eq!(
+            DateTime::parse_from_rfc3339(json["time"].as_str().unwrap()).unwrap(),
+            now
+        );
+    }
+
+    #[test]
+    fn test_full_log_is_rotated_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("harv.log");
+        fs::write(&path, "x".repeat(100)).unwrap();
+
+        let sink = FileSink::open(path.clone(), LevelFilter::Debug, 50, 14).unwrap();
+
+        assert_eq!(sink.size, 0);
+        let rotated: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with("harv.log."))
+            .collect();
+        assert_eq!(rotated.len(), 1);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_home("~/logs/harv.log"), home.join("logs/harv.log"));
+        assert_eq!(
+            expand_home("/var/log/harv.log"),
+            PathBuf::from("/var/log/harv.log")
+        );
+    }
+}
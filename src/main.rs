@@ -1,6 +1,6 @@
 use clap::{CommandFactory, Parser, Subcommand};
 use harv::*;
-use log::{error, info};
+use log::error;
 use std::process;
 
 #[derive(Parser)]
@@ -11,9 +11,9 @@ struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
 
-    /// Enable verbose logging
-    #[arg(short, long, global = true)]
-    verbose: bool,
+    /// More detail: -v debug messages, -vv HTTP requests, -vvv HTTP headers and bodies
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
 
     /// Suppress non-essential output
     #[arg(short, long, global = true)]
@@ -22,6 +22,34 @@ struct Cli {
     /// Show what would happen without making changes
     #[arg(short = 'n', long, global = true)]
     dry_run: bool,
+
+    /// Dry run, then print every API change it would make (--explain=json for JSON)
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text",
+        value_parser = ["text", "json"]
+    )]
+    explain: Option<String>,
+
+    /// Exit with a failure code when there is nothing to do (no commits or tickets found)
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// Config file to use instead of ~/.config/harv/config.toml (or set HARV_CONFIG)
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<String>,
+
+    /// Use the Harvest account configured under [harvest.accounts.<NAME>]
+    #[arg(long, global = true, value_name = "NAME")]
+    account: Option<String>,
+
+    /// Run even if another harv instance is changing Harvest
+    #[arg(long, global = true)]
+    no_lock: bool,
 }
 
 #[derive(Subcommand)]
@@ -42,16 +70,219 @@ enum Commands {
     },
 
     /// Show current Harvest timer status
-    Status,
+    Status {
+        /// Print a single line for shell prompts and status bars
+        #[arg(long)]
+        short: bool,
+
+        /// Always fetch from Harvest instead of using the cached status
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Show timers and totals for every configured Harvest account
+        #[arg(long, conflicts_with = "short")]
+        all_accounts: bool,
+
+        /// Keep the status on screen, fetching it again every SECS seconds
+        #[arg(
+            long,
+            value_name = "SECS",
+            num_args = 0..=1,
+            default_missing_value = "30",
+            conflicts_with_all = ["short", "all_accounts"]
+        )]
+        watch: Option<u64>,
+
+        /// Show the entries and totals of another day (YYYY-MM-DD)
+        #[arg(long, conflicts_with_all = ["short", "all_accounts", "watch"])]
+        date: Option<String>,
+
+        /// Show yesterday's entries and totals
+        #[arg(long, conflicts_with_all = ["date", "short", "all_accounts", "watch"])]
+        yesterday: bool,
+    },
 
     /// Stop the currently running Harvest timer
-    Stop,
+    Stop {
+        /// Keep a timer shorter than settings.min_entry_minutes instead of offering to delete it
+        #[arg(long)]
+        keep: bool,
+
+        /// Only stop a timer spent on this day (YYYY-MM-DD)
+        #[arg(long)]
+        date: Option<String>,
+
+        /// Only stop a timer spent yesterday, e.g. one still running after midnight
+        #[arg(long, conflicts_with = "date")]
+        yesterday: bool,
+    },
+
+    /// Summarize today's entries grouped by project and Jira ticket
+    Today {
+        /// Leave out non-billable entries
+        #[arg(long)]
+        billable_only: bool,
+    },
 
     /// Manually add a time entry with interactive prompts
-    Add,
+    Add {
+        /// Only offer projects whose client name contains this text
+        #[arg(long)]
+        client: Option<String>,
+
+        /// Create the entry for this teammate, by user ID or email (needs an admin token)
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Project and task from [aliases] instead of picking them
+        #[arg(long, conflicts_with = "client")]
+        alias: Option<String>,
+
+        /// Hours for a stopped entry, decimal (1.5) or colon format (1:30)
+        #[arg(long)]
+        hours: Option<String>,
+
+        /// Notes instead of prompting for a description
+        #[arg(long)]
+        notes: Option<String>,
+
+        /// Only offer billable tasks, or check that the alias's task is billable
+        #[arg(long)]
+        billable: bool,
+
+        /// Only offer non-billable tasks, or check that the alias's task isn't billable
+        #[arg(long, conflicts_with = "billable")]
+        non_billable: bool,
+    },
+
+    /// Add time to today's entry for a ticket, e.g. `harv log PROJ-123 1:30 "code review"`
+    Log {
+        /// Jira ticket key
+        ticket: String,
+
+        /// Hours to add, decimal (1.5) or colon format (1:30)
+        hours: String,
+
+        /// Added to the entry's notes
+        message: Option<String>,
+
+        /// Project ID or name for a new entry (default: [mapping.tickets], then [harvest])
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Task ID or name for a new entry (default: [mapping.tickets], then [harvest])
+        #[arg(long)]
+        task: Option<String>,
+
+        /// Project and task for a new entry from [aliases]
+        #[arg(long, conflicts_with_all = ["project", "task"])]
+        alias: Option<String>,
+    },
+
+    /// Start a timer from a saved favorite (e.g. `harv start @standup`)
+    Start {
+        /// Favorite name, with or without the leading @
+        favorite: String,
+
+        /// Automatically stop existing timer without prompting
+        #[arg(long)]
+        auto_stop: bool,
+    },
+
+    /// Work in pomodoros, stopping the timer during breaks
+    Pomodoro {
+        /// Ticket to work on (default: the running timer)
+        ticket: Option<String>,
+
+        /// Minutes per pomodoro (default: [pomodoro] work_minutes)
+        #[arg(long, value_name = "MIN")]
+        work: Option<u32>,
+
+        /// Minutes per short break (default: [pomodoro] break_minutes)
+        #[arg(long = "break", value_name = "MIN")]
+        break_minutes: Option<u32>,
+
+        /// Stop after this many pomodoros instead of running until interrupted
+        #[arg(long, short = 'n')]
+        cycles: Option<u32>,
+    },
+
+    /// Log a day off on the [leave] project and task, e.g. `harv leave --date 2026-12-24`
+    Leave {
+        /// Day of the leave, YYYY-MM-DD (default: today)
+        #[arg(long)]
+        date: Option<String>,
+
+        /// Kind of leave, picks a task from [leave.types] and becomes the notes
+        #[arg(long = "type", default_value = "vacation")]
+        kind: String,
+
+        /// Hours to log (default: the day's capacity)
+        #[arg(long)]
+        hours: Option<String>,
+    },
+
+    /// Set the hours to work on a date, e.g. `harv capacity set 2026-07-03 4h`
+    Capacity {
+        #[command(subcommand)]
+        action: CapacityAction,
+    },
+
+    /// List the entries booked on a ticket, with totals
+    Link {
+        /// Ticket key, e.g. PROJ-42
+        ticket: String,
+
+        /// Number of days to look back
+        #[arg(long, default_value_t = 365)]
+        days: u32,
+    },
+
+    /// Invoice a client's unbilled hours
+    Invoice {
+        #[command(subcommand)]
+        action: InvoiceAction,
+    },
+
+    /// Show start and stop times, hours per weekday and top projects
+    Stats {
+        /// Number of weeks to look back
+        #[arg(long, default_value_t = 4)]
+        weeks: u32,
+    },
+
+    /// See what teammates are working on (needs an admin or manager token)
+    Team {
+        #[command(subcommand)]
+        action: TeamAction,
+    },
+
+    /// Create an entry from a [templates] entry in the config, or list them
+    New {
+        /// Template name
+        #[arg(long, short = 't')]
+        template: Option<String>,
+
+        /// Ticket key for a {ticket} placeholder in the notes
+        #[arg(long)]
+        ticket: Option<String>,
+
+        /// Hours to log instead of the template's, decimal (1.5) or colon format (1:30)
+        #[arg(long)]
+        hours: Option<String>,
+    },
+
+    /// Manage favorite timer shortcuts
+    Fav {
+        #[command(subcommand)]
+        action: FavAction,
+    },
 
     /// Continue work on an existing time entry by starting a new timer
     Continue {
+        /// Fuzzy search entries by notes, project, or task (e.g. "standup")
+        query: Option<String>,
+
         /// Number of days to look back for entries (default: 1)
         #[arg(long, short = 'd')]
         days: Option<u8>,
@@ -87,6 +318,109 @@ enum Commands {
         /// Supports decimal (e.g., 1.5) or colon format (e.g., 1:30)
         #[arg(long)]
         target_hours: Option<String>,
+
+        /// Generate even when the monthly AI cost cap has been reached
+        #[arg(long)]
+        force: bool,
+
+        /// Backfill every working day this week that is short of the target
+        /// Commits are read per day; the summary, if given, applies to all days
+        #[arg(long)]
+        week: bool,
+
+        /// With --dry-run, "json" prints the proposed entries as a plan instead of reviewing them
+        #[arg(long, value_name = "FORMAT", default_value = "text", value_parser = ["text", "json"])]
+        output: String,
+
+        /// Review and create the entries of a plan written by --dry-run --output json
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["summary", "provider", "target_hours", "force", "week", "billable", "non_billable"]
+        )]
+        from_file: Option<String>,
+
+        /// Only book on billable tasks
+        #[arg(long)]
+        billable: bool,
+
+        /// Only book on non-billable tasks
+        #[arg(long, conflicts_with = "billable")]
+        non_billable: bool,
+    },
+
+    /// Write a standup summary of a day's entries and commits using AI
+    Summarize {
+        /// Summarize the previous working day (Friday on Mondays)
+        #[arg(long)]
+        yesterday: bool,
+
+        /// Summarize a specific date (YYYY-MM-DD)
+        #[arg(long, conflicts_with = "yesterday")]
+        date: Option<String>,
+
+        /// Output format
+        #[arg(long, default_value = "plain", value_parser = ["slack", "markdown", "plain"])]
+        format: String,
+
+        /// Summarize even when the monthly AI cost cap has been reached
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Review the coding sessions recorded by sync and log them (needs [auto_log])
+    Autolog {
+        /// Log yesterday's sessions
+        #[arg(long)]
+        yesterday: bool,
+
+        /// Log the sessions of a specific date (YYYY-MM-DD)
+        #[arg(long, conflicts_with = "yesterday")]
+        date: Option<String>,
+    },
+
+    /// Find and merge duplicate entries (same project, task and notes on one day)
+    Dedupe {
+        /// Number of days to scan, including today (default: 7)
+        #[arg(long, short = 'd', default_value_t = 7)]
+        days: u8,
+
+        /// Merge all duplicates without reviewing each group
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Reverse the changes the last command made in Harvest
+    Undo {
+        /// Undo without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// List the changes harv has made in Harvest, newest last
+    History {
+        /// Number of changes to show
+        #[arg(long, short = 'l', default_value_t = 20)]
+        limit: usize,
+
+        /// Only show changes to this time entry
+        #[arg(long, value_name = "ID")]
+        entry: Option<u64>,
+    },
+
+    /// Send entries and stops queued while Harvest couldn't be reached
+    Flush,
+
+    /// Log and inspect Harvest expenses
+    Expense {
+        #[command(subcommand)]
+        action: ExpenseAction,
+    },
+
+    /// Authenticate with an external service
+    Login {
+        #[command(subcommand)]
+        service: LoginService,
     },
 
     /// Configuration management
@@ -95,6 +429,28 @@ enum Commands {
         action: ConfigAction,
     },
 
+    /// Maintain local caches
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Run `harv sync` periodically in the background (systemd or launchd)
+    InstallService {
+        /// How often to sync, e.g. 15m or 1h
+        #[arg(long, default_value = "1h")]
+        interval: String,
+    },
+
+    /// Remove the background sync service
+    UninstallService,
+
+    /// Manage the git post-commit hook that runs `harv sync`
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
+    },
+
     /// Generate shell completions
     Completions {
         #[command(subcommand)]
@@ -105,13 +461,168 @@ enum Commands {
 #[derive(Subcommand)]
 enum ConfigAction {
     /// Create a template configuration file
-    Init,
+    Init {
+        /// Walk through the settings and check the credentials instead
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// Look up the Harvest accounts for your access token and store one
+    Account,
 
     /// Display current configuration
     Show,
 
     /// Validate configuration file
-    Validate,
+    Validate {
+        /// Also check the credentials, project and task, Jira site and AI key against the APIs
+        #[arg(long)]
+        remote: bool,
+    },
+
+    /// Move the tokens into an encrypted [secrets] section, decrypted on load
+    Encrypt {
+        /// Encryption tool: gpg or age (default: gpg, or the one used before)
+        #[arg(long)]
+        tool: Option<String>,
+
+        /// Key to encrypt to (GPG key ID or age public key); asks for a passphrase without one
+        #[arg(long)]
+        recipient: Option<String>,
+
+        /// age identity file to decrypt with
+        #[arg(long)]
+        identity: Option<String>,
+    },
+
+    /// Print the AI prompt template (redirect to ~/.config/harv/prompt.tmpl to customize)
+    Prompt {
+        /// Print the built-in template even if a custom one exists
+        #[arg(long)]
+        default: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum FavAction {
+    /// Save a project, task and notes as a named shortcut
+    Add {
+        /// Favorite name (prompted if omitted)
+        name: Option<String>,
+    },
+
+    /// List saved favorites
+    List,
+
+    /// Remove a favorite
+    Remove {
+        /// Favorite name, with or without the leading @
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TeamAction {
+    /// List teammates with a timer running
+    Status,
+
+    /// Hours per teammate for today
+    Report {
+        /// This week so far instead of today
+        #[arg(long)]
+        week: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CapacityAction {
+    /// Work this many hours on a date instead of the configured ones
+    Set {
+        /// Date, YYYY-MM-DD
+        date: String,
+
+        /// Hours, decimal (4), with a unit (4h) or colon format (4:30)
+        hours: String,
+    },
+
+    /// Go back to the configured hours for a date
+    Clear {
+        /// Date, YYYY-MM-DD
+        date: String,
+    },
+
+    /// Show this week's hours per day
+    List,
+}
+
+#[derive(Subcommand)]
+enum ExpenseAction {
+    /// Add an expense to a project with interactive prompts
+    Add {
+        /// Amount (e.g. 12.50), prompted if omitted
+        #[arg(long)]
+        amount: Option<String>,
+
+        /// Receipt notes, prompted if omitted
+        #[arg(long)]
+        notes: Option<String>,
+    },
+
+    /// List active expense categories
+    Categories,
+}
+
+#[derive(Subcommand)]
+enum InvoiceAction {
+    /// Add up billable hours per project and task at the [invoice] rates
+    Draft {
+        /// Client name or ID
+        #[arg(long)]
+        client: String,
+
+        /// First day to invoice (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+
+        /// Last day to invoice (YYYY-MM-DD)
+        #[arg(long)]
+        to: String,
+
+        /// Create the draft invoice in Harvest instead of only printing it
+        #[arg(long)]
+        create: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Remove project/task usage records that haven't been used recently
+    Prune {
+        /// Drop records unused for this many months
+        #[arg(long, default_value_t = usage::DEFAULT_PRUNE_MONTHS)]
+        months: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum LoginService {
+    /// Log in to Jira Cloud via OAuth (requires [jira.oauth] in config)
+    Jira,
+}
+
+#[derive(Subcommand)]
+enum HookAction {
+    /// Start a timer on every commit (chains an existing post-commit hook)
+    Install {
+        /// Repository to install into (default: current directory)
+        repo: Option<String>,
+    },
+
+    /// Remove the harv post-commit hook
+    Uninstall {
+        /// Repository to remove it from (default: current directory)
+        repo: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -131,25 +642,77 @@ fn main() {
     let cli = Cli::parse();
 
     // Setup logging
-    let log_level = if cli.verbose {
-        "debug"
-    } else if cli.quiet {
-        "error"
-    } else {
-        "info"
-    };
+    let log_level = logging::filter(cli.verbose, cli.quiet);
+    logging::init(
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(log_level))
+            .build(),
+    );
+
+    if let Some(ref path) = cli.config {
+        config::use_config_file(path);
+    }
+    if let Some(ref account) = cli.account {
+        config::select_account(account);
+    }
 
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(log_level)).init();
+    // Settle the timezone before anything asks for today's date
+    if !matches!(
+        cli.command,
+        Some(
+            Commands::Config { .. }
+                | Commands::Completions { .. }
+                | Commands::InstallService { .. }
+                | Commands::UninstallService
+                | Commands::Hook { .. }
+        )
+    ) {
+        commands::init_timezone();
+    }
+
+    let explain: Option<plan::ExplainFormat> = cli.explain.as_deref().and_then(|f| f.parse().ok());
 
     // Build context
     let ctx = models::Context {
-        dry_run: cli.dry_run,
+        dry_run: cli.dry_run || explain.is_some(),
         auto_start: false,
         auto_stop: false,
         quiet: cli.quiet,
-        verbose: cli.verbose,
+        verbosity: cli.verbose,
+        strict: cli.strict,
     };
 
+    // One harv at a time changes Harvest, so a timer-triggered sync can't race a manual command
+    let instance_lock = if cli.no_lock || ctx.dry_run {
+        None
+    } else {
+        Some(lock::InstanceLock::acquire())
+    };
+    if let Some(Err(e)) = &instance_lock {
+        if changes_harvest(&cli.command) {
+            error!("{}", e);
+            process::exit(e.exit_code());
+        }
+    }
+    let may_change = cli.no_lock || matches!(instance_lock, Some(Ok(_)));
+
+    // Catch up on changes queued offline before making new ones
+    if !ctx.dry_run
+        && may_change
+        && !matches!(
+            cli.command,
+            Some(
+                Commands::Flush
+                    | Commands::Config { .. }
+                    | Commands::Completions { .. }
+                    | Commands::InstallService { .. }
+                    | Commands::UninstallService
+                    | Commands::Hook { .. }
+            )
+        )
+    {
+        commands::flush_queued(&ctx);
+    }
+
     // Run command
     let result = match cli.command {
         Some(Commands::Sync {
@@ -160,12 +723,136 @@ fn main() {
             let mut sync_ctx = ctx.clone();
             sync_ctx.auto_start = auto_start;
             sync_ctx.auto_stop = auto_stop;
-            run_sync(sync_ctx, repo)
+            with_session(|session| commands::run_sync(sync_ctx, session, repo))
+        }
+        Some(Commands::Status {
+            short,
+            no_cache,
+            all_accounts,
+            watch,
+            date,
+            yesterday,
+        }) => {
+            if date.is_some() || yesterday {
+                with_session(|session| commands::run_status_day(ctx, session, date, yesterday))
+            } else if let Some(interval) = watch {
+                with_session(|session| commands::run_status_watch(session, interval))
+            } else if all_accounts {
+                with_session(commands::run_status_all_accounts)
+            } else if short {
+                with_session(|session| commands::run_status_short(session, no_cache))
+            } else {
+                with_session(|session| commands::run_status(ctx, session, no_cache))
+            }
+        }
+        Some(Commands::Stop {
+            keep,
+            date,
+            yesterday,
+        }) => with_session(|session| commands::run_stop(ctx, session, keep, date, yesterday)),
+        Some(Commands::Today { billable_only }) => {
+            with_session(|session| commands::run_today(ctx, session, billable_only))
+        }
+        Some(Commands::Add {
+            client,
+            user,
+            alias,
+            hours,
+            notes,
+            billable,
+            non_billable,
+        }) => with_session(|session| {
+            commands::run_add(
+                ctx,
+                session,
+                client,
+                user,
+                alias,
+                hours,
+                notes,
+                billable_choice(billable, non_billable),
+            )
+        }),
+        Some(Commands::Log {
+            ticket,
+            hours,
+            message,
+            project,
+            task,
+            alias,
+        }) => with_session(|session| {
+            commands::run_log(
+                ctx,
+                session,
+                &ticket,
+                &hours,
+                message,
+                (project, task),
+                alias,
+            )
+        }),
+        Some(Commands::Start {
+            favorite,
+            auto_stop,
+        }) => {
+            let mut start_ctx = ctx.clone();
+            start_ctx.auto_stop = auto_stop;
+            with_session(|session| commands::run_start(start_ctx, session, &favorite))
+        }
+        Some(Commands::Pomodoro {
+            ticket,
+            work,
+            break_minutes,
+            cycles,
+        }) => with_session(|session| {
+            commands::run_pomodoro(ctx, session, ticket, work, break_minutes, cycles)
+        }),
+        Some(Commands::Leave { date, kind, hours }) => {
+            with_session(|session| commands::run_leave(ctx, session, date, &kind, hours))
+        }
+        Some(Commands::Capacity { action }) => match action {
+            CapacityAction::Set { date, hours } => commands::run_capacity_set(ctx, &date, &hours),
+            CapacityAction::Clear { date } => commands::run_capacity_clear(ctx, &date),
+            CapacityAction::List => {
+                with_session(|session| commands::run_capacity_list(&session.config))
+            }
+        },
+        Some(Commands::Link { ticket, days }) => {
+            with_session(|session| commands::run_link(ctx, session, &ticket, days))
+        }
+        Some(Commands::Invoice { action }) => match action {
+            InvoiceAction::Draft {
+                client,
+                from,
+                to,
+                create,
+            } => with_session(|session| {
+                commands::run_invoice_draft(ctx, session, &client, &from, &to, create)
+            }),
+        },
+        Some(Commands::Stats { weeks }) => {
+            with_session(|session| commands::run_stats(ctx, session, weeks))
         }
-        Some(Commands::Status) => run_status(ctx),
-        Some(Commands::Stop) => run_stop(ctx),
-        Some(Commands::Add) => run_add(ctx),
+        Some(Commands::Team { action }) => match action {
+            TeamAction::Status => with_session(commands::run_team_status),
+            TeamAction::Report { week } => {
+                with_session(|session| commands::run_team_report(session, week))
+            }
+        },
+        Some(Commands::New {
+            template,
+            ticket,
+            hours,
+        }) => with_session(|session| commands::run_new(ctx, session, template, ticket, hours)),
+        Some(Commands::Fav { action }) => match action {
+            FavAction::Add { name } => {
+                with_session(|session| commands::run_fav_add(ctx, session, name))
+            }
+            FavAction::List => commands::run_fav_list(),
+            FavAction::Remove { name } => commands::run_fav_remove(ctx, &name),
+        },
         Some(Commands::Continue {
+            query,
             days,
             auto_start,
             new_entry,
@@ -173,18 +860,93 @@ fn main() {
         }) => {
             let mut continue_ctx = ctx.clone();
             continue_ctx.auto_start = auto_start;
-            run_continue(continue_ctx, days, new_entry, restart)
+            with_session(|session| {
+                commands::run_continue(continue_ctx, session, query, days, new_entry, restart)
+            })
         }
         Some(Commands::Generate {
             summary,
             provider,
             auto_approve,
             target_hours,
-        }) => run_generate(ctx, summary, provider, auto_approve, target_hours),
+            force,
+            week,
+            output: output_format,
+            from_file,
+            billable,
+            non_billable,
+        }) => {
+            let plan_output = output_format == "json";
+            if plan_output {
+                // Keep stdout for the plan
+                output::set_reporter(output::LogReporter);
+            }
+            commands::Session::load().and_then(|session| {
+                commands::run_generate(
+                    ctx,
+                    session,
+                    summary,
+                    provider,
+                    auto_approve,
+                    target_hours,
+                    force,
+                    week,
+                    plan_output,
+                    from_file,
+                    billable_choice(billable, non_billable),
+                )
+            })
+        }
+        Some(Commands::Summarize {
+            yesterday,
+            date,
+            format,
+            force,
+        }) => with_session(|session| {
+            commands::run_summarize(ctx, session, yesterday, date, format, force)
+        }),
+        Some(Commands::Autolog { yesterday, date }) => {
+            with_session(|session| commands::run_autolog(ctx, session, date, yesterday))
+        }
+        Some(Commands::Dedupe { days, yes }) => {
+            with_session(|session| commands::run_dedupe(ctx, session, days, yes))
+        }
+        Some(Commands::Undo { yes }) => {
+            with_session(|session| commands::run_undo(ctx, session, yes))
+        }
+        Some(Commands::History { limit, entry }) => commands::run_history(limit, entry),
+        Some(Commands::Flush) => with_session(|session| commands::run_flush(ctx, session)),
+        Some(Commands::Expense { action }) => match action {
+            ExpenseAction::Add { amount, notes } => {
+                with_session(|session| commands::run_expense_add(ctx, session, amount, notes))
+            }
+            ExpenseAction::Categories => {
+                with_session(|session| commands::run_expense_categories(ctx, session))
+            }
+        },
+        Some(Commands::Login { service }) => match service {
+            LoginService::Jira => commands::run_login_jira(),
+        },
         Some(Commands::Config { action }) => match action {
-            ConfigAction::Init => run_config_init(),
-            ConfigAction::Show => run_config_show(),
-            ConfigAction::Validate => run_config_validate(),
+            ConfigAction::Init { interactive } => commands::run_config_init(interactive),
+            ConfigAction::Show => commands::run_config_show(),
+            ConfigAction::Validate { remote } => commands::run_config_validate(remote),
+            ConfigAction::Prompt { default } => commands::run_config_prompt(default),
+            ConfigAction::Account => commands::run_config_account(),
+            ConfigAction::Encrypt {
+                tool,
+                recipient,
+                identity,
+            } => commands::run_config_encrypt(tool, recipient, identity),
+        },
+        Some(Commands::Cache { action }) => match action {
+            CacheAction::Prune { months } => commands::run_cache_prune(ctx, months),
+        },
+        Some(Commands::InstallService { interval }) => commands::run_install_service(&interval),
+        Some(Commands::UninstallService) => commands::run_uninstall_service(),
+        Some(Commands::Hook { action }) => match action {
+            HookAction::Install { repo } => commands::run_hook_install(repo),
+            HookAction::Uninstall { repo } => commands::run_hook_uninstall(repo),
         },
         Some(Commands::Completions { action }) => match action {
             CompletionsAction::Install => run_completions_install(),
@@ -192,10 +954,19 @@ fn main() {
         },
         None => {
             // Default to sync command
-            run_sync(ctx, None)
+            with_session(|session| commands::run_sync(ctx, session, None))
         }
     };
 
+    // Saved even if the command failed part-way, so what it did can be undone
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    journal::commit(&args.join(" "));
+
+    // Print the plan even if the command failed part-way, it shows how far it got
+    if let Some(format) = explain {
+        output::data(&plan::render(&plan::take(), format));
+    }
+
     if let Err(e) = result {
         match e {
             HarjiraError::ShowHelp => {
@@ -205,742 +976,53 @@ fn main() {
             }
             _ => {
                 error!("{}", e);
-                process::exit(1);
-            }
-        }
-    }
-}
-
-fn run_sync(ctx: models::Context, repo_override: Option<String>) -> Result<()> {
-    info!("Starting sync operation...");
-
-    // Load configuration
-    let config = Config::load()?;
-
-    // Determine repositories to check
-    let repos = if let Some(repo) = repo_override {
-        vec![repo]
-    } else {
-        git::discover_repositories(&config.git.repositories)?
-    };
-
-    info!("Checking {} repository(ies)", repos.len());
-
-    // Get commits from all repositories
-    let commits = git::get_commits_from_repositories(&repos)?;
-
-    if commits.is_empty() {
-        if !ctx.quiet {
-            prompt::display_info("No commits found from today");
-        }
-        return Ok(());
-    }
-
-    info!("Found {} commits from today", commits.len());
-
-    // Extract commit messages
-    let messages: Vec<String> = commits.iter().map(|c| c.message.clone()).collect();
-
-    // Parse Jira tickets (with denylist filter)
-    let ticket_keys = ticket_parser::extract_tickets(&messages, &config.ticket_filter.denylist);
-
-    if ticket_keys.is_empty() {
-        if !ctx.quiet {
-            prompt::display_info("No Jira tickets found in today's commits");
-        }
-        return Ok(());
-    }
-
-    info!("Found {} Jira ticket(s): {:?}", ticket_keys.len(), ticket_keys);
-
-    // Initialize API clients
-    let jira_client = JiraClient::new(config.jira.clone())?;
-    let harvest_client = HarvestClient::new(config.harvest.clone())?;
-
-    // Fetch Jira details for all tickets
-    let tickets = jira_client.get_issues(&ticket_keys);
-
-    // Select ticket (prompt if multiple)
-    let selected_ticket = if tickets.len() == 1 && config.settings.auto_select_single {
-        tickets[0].clone()
-    } else if tickets.len() == 1 || ctx.auto_start {
-        tickets[0].clone()
-    } else {
-        prompt::prompt_ticket_selection(&tickets)?
-    };
-
-    info!("Selected ticket: {} - {}", selected_ticket.key, selected_ticket.summary);
-
-    // Check current Harvest status
-    let running_timer = harvest_client.get_running_timer()?;
-
-    // Handle existing timer
-    if let Some(timer) = running_timer {
-        // Check if timer is already for this ticket
-        if let Some(notes) = &timer.notes {
-            if notes.contains(&selected_ticket.key) {
-                if !ctx.quiet {
-                    prompt::display_info(&format!(
-                        "Timer already running for {}",
-                        selected_ticket.key
-                    ));
-                }
-                return Ok(());
-            }
-        }
-
-        // Timer is for a different ticket
-        let should_stop = if ctx.auto_stop {
-            true
-        } else {
-            prompt::confirm_stop_timer(&timer, &selected_ticket.key)?
-        };
-
-        if !should_stop {
-            if !ctx.quiet {
-                prompt::display_info("Keeping current timer running");
+                process::exit(e.exit_code());
             }
-            return Ok(());
-        }
-
-        // Stop current timer
-        harvest_client.stop_time_entry(timer.id, &ctx)?;
-        if !ctx.quiet {
-            prompt::display_success("Stopped previous timer");
-        }
-    }
-
-    // Create new timer
-    let jira_url = jira_client.get_ticket_url(&selected_ticket.key);
-    harvest_client.create_time_entry(
-        &selected_ticket.key,
-        &selected_ticket.summary,
-        &jira_url,
-        &ctx,
-    )?;
-
-    if !ctx.quiet {
-        prompt::display_success(&format!(
-            "Started timer for {} - {}",
-            selected_ticket.key, selected_ticket.summary
-        ));
-    }
-
-    Ok(())
-}
-
-fn run_status(_ctx: models::Context) -> Result<()> {
-    let config = Config::load()?;
-    let harvest_client = HarvestClient::new(config.harvest)?;
-
-    println!("\nHarvest Timer Status");
-    println!("====================\n");
-
-    let running_timer = harvest_client.get_running_timer()?;
-
-    if let Some(timer) = running_timer {
-        println!("✓ Timer Running");
-        if let Some(notes) = &timer.notes {
-            println!("  Notes: {}", notes);
-        }
-        if let Some(project) = &timer.project {
-            println!("  Project: {}", project.name);
-        }
-        if let Some(task) = &timer.task {
-            println!("  Task: {}", task.name);
-        }
-        if let Some(started) = &timer.started_time {
-            println!("  Started: {}", started);
-        }
-        if let Some(hours) = timer.hours {
-            println!("  Duration: {:.2} hours", hours);
-        }
-    } else {
-        println!("⊗ No timer running");
-    }
-
-    println!();
-
-    // Show today's entries
-    let entries = harvest_client.get_todays_time_entries()?;
-    if !entries.is_empty() {
-        println!("Today's Time Entries:");
-        for entry in &entries {
-            let running_marker = if entry.is_running { " (running)" } else { "" };
-            let hours = entry.hours.unwrap_or(0.0);
-            let notes = entry.notes.as_deref().unwrap_or("No notes");
-            println!("  • {:.2}h - {}{}", hours, notes, running_marker);
-        }
-    }
-
-    // Calculate total
-    let total_hours = harvest_client.get_total_hours_today()?;
-    println!("\nTotal Time Today: {:.2} hours", total_hours);
-
-    Ok(())
-}
-
-fn run_stop(ctx: models::Context) -> Result<()> {
-    let config = Config::load()?;
-    let harvest_client = HarvestClient::new(config.harvest)?;
-
-    let running_timer = harvest_client.get_running_timer()?;
-
-    if let Some(timer) = running_timer {
-        harvest_client.stop_time_entry(timer.id, &ctx)?;
-        if !ctx.quiet {
-            prompt::display_success("Timer stopped");
-        }
-    } else {
-        if !ctx.quiet {
-            prompt::display_info("No timer currently running");
         }
     }
-
-    Ok(())
 }
 
-fn run_config_init() -> Result<()> {
-    Config::create_template()?;
-    let config_path = Config::config_path()?;
-    println!("✓ Configuration file created at: {}", config_path.display());
-    println!("\nPlease edit the file and add your API credentials:");
-    println!("  - Harvest access token: https://id.getharvest.com/developers");
-    println!("  - Jira personal access token: https://id.atlassian.com/manage-profile/security/api-tokens");
-    println!("\nTip: Enable shell completions with:");
-    println!("  harv completions install");
-    Ok(())
-}
-
-fn run_config_show() -> Result<()> {
-    let config = Config::load()?;
-    println!("\nCurrent Configuration");
-    println!("====================\n");
-    config.display();
-    Ok(())
-}
-
-fn run_config_validate() -> Result<()> {
-    let _config = Config::load()?;
-    println!("✓ Configuration is valid");
-    println!("  Config file: {}", Config::config_path()?.display());
-    Ok(())
-}
-
-fn run_generate(
-    ctx: models::Context,
-    summary: Option<String>,
-    provider_override: Option<String>,
-    auto_approve: bool,
-    target_hours_override: Option<String>,
-) -> Result<()> {
-    info!("Starting AI-powered time entry generation...");
-
-    // Load configuration
-    let mut config = Config::load()?;
-
-    // Check if AI is enabled
-    if !config.ai.enabled {
-        return Err(HarjiraError::Config(
-            "AI generation is not enabled. Set 'ai.enabled = true' in your config file."
-                .to_string(),
-        ));
-    }
-
-    // Apply overrides
-    if let Some(provider) = provider_override {
-        config.ai.provider = provider;
-    }
-    if let Some(target_str) = target_hours_override {
-        let parsed = time_parser::parse_hours(&target_str)?;
-        config.ai.target_hours = parsed;
-    }
-
-    // Get summary from user if not provided
-    let work_summary = if let Some(s) = summary {
-        s
-    } else {
-        prompt::prompt_work_summary()?
-    };
-
-    if work_summary.trim().is_empty() {
-        return Err(HarjiraError::Config(
-            "Work summary cannot be empty".to_string(),
-        ));
-    }
-
-    // Initialize clients
-    let harvest_client = HarvestClient::new(config.harvest.clone())?;
-    let ai_provider = ai::create_provider(&config.ai)?;
-
-    // Gather context for AI
-    if !ctx.quiet {
-        prompt::display_info("Fetching Harvest data...");
-    }
-
-    let projects = harvest_client.get_projects()?;
-    let existing_entries = harvest_client.get_todays_time_entries()?;
-    let today_total = harvest_client.get_total_hours_today()?;
-
-    // Get all available tasks
-    let all_tasks = harvest_client.get_all_available_tasks()?;
-    let tasks: Vec<models::HarvestTask> = all_tasks.into_iter().map(|(_, task)| task).collect();
-
-    let ai_context = ai::AiContext {
-        available_projects: projects.clone(),
-        available_tasks: tasks,
-        existing_entries: existing_entries.clone(),
-        target_hours: config.ai.target_hours,
-        today_total_hours: today_total,
-    };
-
-    // Generate entries using AI
-    if !ctx.quiet {
-        prompt::display_info(&format!(
-            "Generating time entries using {}...",
-            ai_provider.name()
-        ));
-    }
-
-    let mut proposed_entries = ai_provider.generate_time_entries(&work_summary, &ai_context)?;
-
-    // Deduplicate entries based on description, project_id, task_id, and hours
-    let mut seen = std::collections::HashSet::new();
-    proposed_entries.retain(|entry| {
-        let key = (
-            entry.description.clone(),
-            entry.project_id,
-            entry.task_id,
-            (entry.hours * 100.0) as i64, // Convert to cents to handle f64 comparison
-        );
-        seen.insert(key)
-    });
-
-    if proposed_entries.is_empty() {
-        if !ctx.quiet {
-            prompt::display_warning("AI did not generate any time entries");
-        }
-        return Ok(());
-    }
-
-    // Show proposed entries and get approval
-    let approved_entries = if auto_approve || ctx.auto_start {
-        proposed_entries
-    } else {
-        prompt::review_and_approve_entries(&proposed_entries, &projects)?
-    };
-
-    if approved_entries.is_empty() {
-        if !ctx.quiet {
-            prompt::display_info("No entries approved");
-        }
-        return Ok(());
-    }
-
-    // Get fallback project/task from most recent entry
-    let fallback = existing_entries.first().and_then(|entry| {
-        if let (Some(project), Some(task)) = (&entry.project, &entry.task) {
-            Some((project.id, task.id))
-        } else {
-            None
-        }
-    });
-
-    // Create time entries in Harvest
-    let mut created_count = 0;
-    let mut failed_count = 0;
-
-    for entry in approved_entries {
-        match harvest_client.create_stopped_time_entry(
-            &entry.description,
-            entry.project_id,
-            entry.task_id,
-            entry.hours,
-            &ctx,
-        ) {
-            Ok(_) => {
-                created_count += 1;
-                if ctx.verbose {
-                    prompt::display_success(&format!(
-                        "Created: {} ({:.2}h)",
-                        entry.description, entry.hours
-                    ));
+/// Whether a command creates, stops or changes entries, and so needs the lock
+fn changes_harvest(command: &Option<Commands>) -> bool {
+    matches!(
+        command,
+        None | Some(
+            Commands::Sync { .. }
+                | Commands::Stop { .. }
+                | Commands::Add { .. }
+                | Commands::Log { .. }
+                | Commands::Start { .. }
+                | Commands::Pomodoro { .. }
+                | Commands::New { .. }
+                | Commands::Leave { .. }
+                | Commands::Continue { .. }
+                | Commands::Generate { .. }
+                | Commands::Autolog { .. }
+                | Commands::Dedupe { .. }
+                | Commands::Expense {
+                    action: ExpenseAction::Add { .. }
                 }
-            }
-            Err(e) => {
-                // Check if this is a 422 error (invalid project/task) and we have a fallback
-                let is_422_error = e.to_string().contains("422 Unprocessable Entity");
-
-                if is_422_error && fallback.is_some() {
-                    let (fallback_project_id, fallback_task_id) = fallback.unwrap();
-
-                    if !ctx.quiet {
-                        prompt::display_warning(&format!(
-                            "Invalid project/task for '{}'. Retrying with most recent project/task...",
-                            entry.description
-                        ));
-                    }
-
-                    // Retry with fallback project/task
-                    match harvest_client.create_stopped_time_entry(
-                        &entry.description,
-                        fallback_project_id,
-                        fallback_task_id,
-                        entry.hours,
-                        &ctx,
-                    ) {
-                        Ok(_) => {
-                            created_count += 1;
-                            if ctx.verbose {
-                                prompt::display_success(&format!(
-                                    "Created with fallback: {} ({:.2}h)",
-                                    entry.description, entry.hours
-                                ));
-                            }
-                        }
-                        Err(retry_error) => {
-                            failed_count += 1;
-                            prompt::display_warning(&format!(
-                                "Failed to create entry '{}' even with fallback: {}",
-                                entry.description, retry_error
-                            ));
-                        }
-                    }
-                } else {
-                    failed_count += 1;
-                    prompt::display_warning(&format!(
-                        "Failed to create entry '{}': {}",
-                        entry.description, e
-                    ));
+                | Commands::Invoice {
+                    action: InvoiceAction::Draft { create: true, .. }
                 }
-            }
-        }
-    }
-
-    // Summary
-    if !ctx.quiet {
-        println!();
-        if created_count > 0 {
-            prompt::display_success(&format!(
-                "Successfully created {} time entries",
-                created_count
-            ));
-        }
-        if failed_count > 0 {
-            prompt::display_warning(&format!("{} entries failed", failed_count));
-        }
-
-        // Show new total
-        let new_total = harvest_client.get_total_hours_today()?;
-        println!("\nTotal time today: {:.2} hours", new_total);
-    }
-
-    Ok(())
+                | Commands::Undo { .. }
+                | Commands::Flush
+        )
+    )
 }
 
-fn run_add(ctx: models::Context) -> Result<()> {
-    use crate::models::EntryType;
-
-    info!("Starting manual time entry creation...");
-
-    // Load configuration
-    let config = Config::load()?;
-    let harvest_client = HarvestClient::new(config.harvest.clone())?;
-
-    // Load usage cache for sorting
-    let mut usage_cache = usage::UsageCache::load()?;
-
-    // Step 1: Select entry type
-    let entry_type = prompt::prompt_entry_type()?;
-
-    // Step 2: Select date
-    let spent_date = prompt::prompt_date_selection()?;
-
-    // Step 3: Fetch and select project
-    if !ctx.quiet {
-        prompt::display_info("Fetching available projects...");
-    }
-    let mut projects = harvest_client.get_projects()?;
-    projects = usage::sort_by_usage(projects, |p| usage_cache.get_project_score(p.id));
-    let selected_project = prompt::prompt_project_selection(&projects)?;
-
-    // Step 4: Fetch and select task
-    if !ctx.quiet {
-        prompt::display_info("Fetching tasks...");
-    }
-    let mut tasks = harvest_client.get_project_tasks(selected_project.id)?;
-    tasks = usage::sort_by_usage(tasks, |t| usage_cache.get_task_score(t.id));
-    let selected_task = prompt::prompt_task_selection(&tasks)?;
-
-    // Step 5: Enter description
-    let description = prompt::prompt_description()?;
-
-    // Step 6: Enter hours (only for stopped entries)
-    let hours = if entry_type.is_running() {
-        None
-    } else {
-        Some(prompt::prompt_hours()?)
-    };
-
-    // Step 7: Confirm
-    let confirmed = prompt::confirm_entry_creation(
-        &entry_type,
-        &spent_date,
-        &selected_project.name,
-        &selected_task.name,
-        &description,
-        hours,
-    )?;
-
-    if !confirmed {
-        if !ctx.quiet {
-            prompt::display_info("Entry creation cancelled");
-        }
-        return Ok(());
-    }
-
-    // Step 8: Check for running timer (if creating running timer)
-    if entry_type.is_running() {
-        if let Some(timer) = harvest_client.get_running_timer()? {
-            let should_stop = prompt::confirm_stop_timer_for_new(&timer)?;
-            if !should_stop {
-                if !ctx.quiet {
-                    prompt::display_info("Keeping current timer running");
-                }
-                return Ok(());
-            }
-            harvest_client.stop_time_entry(timer.id, &ctx)?;
-            if !ctx.quiet {
-                prompt::display_success("Stopped previous timer");
-            }
-        }
-    }
-
-    // Step 9: Create entry
-    match entry_type {
-        EntryType::Running => {
-            harvest_client.create_time_entry_with_date(
-                &description,
-                selected_project.id,
-                selected_task.id,
-                &spent_date,
-                &ctx,
-            )?;
-            if !ctx.quiet {
-                prompt::display_success(&format!(
-                    "Started timer: {} - {}",
-                    selected_project.name, description
-                ));
-            }
-        }
-        EntryType::Stopped => {
-            let hours_val = hours.unwrap();
-            harvest_client.create_stopped_time_entry_with_date(
-                &description,
-                selected_project.id,
-                selected_task.id,
-                hours_val,
-                &spent_date,
-                &ctx,
-            )?;
-            if !ctx.quiet {
-                prompt::display_success(&format!(
-                    "Created entry: {} ({:.2}h) on {}",
-                    description, hours_val, spent_date
-                ));
-            }
-        }
-    }
-
-    // Record usage for future sorting (skip in dry-run mode)
-    if !ctx.dry_run {
-        usage_cache.record_project_usage(selected_project.id);
-        usage_cache.record_task_usage(selected_task.id);
-        usage_cache.save()?;
-    }
-
-    // Show total for the date
-    if !ctx.quiet {
-        let total = harvest_client.get_total_hours_for_date(&spent_date)?;
-        println!("\nTotal time on {}: {:.2} hours", spent_date, total);
+/// `--billable` as Some(true), `--non-billable` as Some(false)
+fn billable_choice(billable: bool, non_billable: bool) -> Option<bool> {
+    match (billable, non_billable) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
     }
-
-    Ok(())
 }
 
-fn run_continue(
-    ctx: models::Context,
-    days: Option<u8>,
-    new_entry: bool,
-    restart: bool,
-) -> Result<()> {
-    info!("Starting continue operation...");
-
-    // Load configuration
-    let config = Config::load()?;
-    let harvest_client = HarvestClient::new(config.harvest.clone())?;
-
-    // Determine lookback period (default: 1 day = today only)
-    let lookback_days = days.unwrap_or(config.settings.continue_days.unwrap_or(1));
-
-    // Calculate date range
-    let today = chrono::Local::now();
-    let from_date = if lookback_days == 1 {
-        // Today only
-        today.format("%Y-%m-%d").to_string()
-    } else {
-        // N days back
-        let from = today - chrono::Duration::days((lookback_days - 1) as i64);
-        from.format("%Y-%m-%d").to_string()
-    };
-    let to_date = today.format("%Y-%m-%d").to_string();
-
-    // Fetch time entries for date range
-    if !ctx.quiet {
-        if lookback_days == 1 {
-            prompt::display_info("Fetching today's time entries...");
-        } else {
-            prompt::display_info(&format!("Fetching entries from last {} days...", lookback_days));
-        }
-    }
-
-    let all_entries = harvest_client.get_time_entries_range(&from_date, &to_date, &ctx)?;
-
-    // Filter to stopped entries only (can't continue a running timer)
-    let stopped_entries: Vec<_> = all_entries
-        .into_iter()
-        .filter(|e| !e.is_running)
-        .collect();
-
-    // Filter out entries without project/task (can't restart them)
-    let valid_entries: Vec<_> = stopped_entries
-        .into_iter()
-        .filter(|e| e.project.is_some() && e.task.is_some())
-        .collect();
-
-    // Check if we have any entries to continue
-    if valid_entries.is_empty() {
-        let msg = if lookback_days == 1 {
-            "No stopped time entries found today"
-        } else {
-            &format!("No stopped time entries found in last {} days", lookback_days)
-        };
-        if !ctx.quiet {
-            prompt::display_info(msg);
-        }
-        return Ok(());
-    }
-
-    info!("Found {} valid entries to continue", valid_entries.len());
-
-    // Prompt user to select entry
-    let selected_entry = prompt::prompt_entry_selection(&valid_entries)?;
-
-    let notes = selected_entry
-        .notes
-        .as_deref()
-        .unwrap_or("(no description)");
-    let project_name = selected_entry
-        .project
-        .as_ref()
-        .map(|p| p.name.as_str())
-        .unwrap_or("Unknown");
-    let task_name = selected_entry
-        .task
-        .as_ref()
-        .map(|t| t.name.as_str())
-        .unwrap_or("Unknown");
-
-    info!("Selected entry: {} - {}", project_name, notes);
-
-    // Determine how to continue (restart vs new timer)
-    let continue_mode = if restart {
-        models::ContinueMode::Restart
-    } else if new_entry {
-        models::ContinueMode::NewEntry
-    } else if let Some(ref mode_str) = config.settings.continue_mode {
-        // Use config setting
-        match mode_str.as_str() {
-            "restart" => models::ContinueMode::Restart,
-            "new" => models::ContinueMode::NewEntry,
-            _ => {
-                // "ask" or invalid value -> prompt user
-                prompt::prompt_continue_mode(selected_entry)?
-            }
-        }
-    } else {
-        // No config, no flags -> prompt user
-        prompt::prompt_continue_mode(selected_entry)?
-    };
-
-    info!("Continue mode: {:?}", continue_mode);
-
-    // Check for running timer conflicts
-    let running_timer = harvest_client.get_running_timer()?;
-
-    if let Some(timer) = running_timer {
-        // Check if timer is already for this task (same notes)
-        if let Some(timer_notes) = &timer.notes {
-            if timer_notes == notes {
-                if !ctx.quiet {
-                    prompt::display_info(&format!(
-                        "Timer already running for this task: {}",
-                        notes
-                    ));
-                }
-                return Ok(());
-            }
-        }
-
-        // Timer is for a different task
-        let should_stop = if ctx.auto_start {
-            // auto_start implies auto_stop for continue command
-            true
-        } else {
-            prompt::confirm_stop_timer(&timer, notes)?
-        };
-
-        if !should_stop {
-            if !ctx.quiet {
-                prompt::display_info("Keeping current timer running");
-            }
-            return Ok(());
-        }
-
-        // Stop current timer
-        harvest_client.stop_time_entry(timer.id, &ctx)?;
-        if !ctx.quiet {
-            prompt::display_success("Stopped previous timer");
-        }
-    }
-
-    // Execute based on continue mode
-    match continue_mode {
-        models::ContinueMode::Restart => {
-            // Restart existing entry
-            let restarted = harvest_client.restart_time_entry(selected_entry.id, &ctx)?;
-
-            if !ctx.quiet {
-                prompt::display_success(&format!(
-                    "Restarted timer on {}: {} > {} - {}",
-                    restarted.spent_date, project_name, task_name, notes
-                ));
-            }
-        }
-        models::ContinueMode::NewEntry => {
-            // Create new timer (existing behavior)
-            harvest_client.start_timer_from_entry(selected_entry, &ctx)?;
-
-            if !ctx.quiet {
-                prompt::display_success(&format!(
-                    "Started new timer: {} > {} - {}",
-                    project_name, task_name, notes
-                ));
-            }
-        }
-    }
-
-    Ok(())
+/// Run a command that needs the configuration and API clients
+fn with_session(command: impl FnOnce(&commands::Session) -> Result<()>) -> Result<()> {
+    command(&commands::Session::load()?)
 }
 
 fn run_completions_generate(shell: clap_complete::Shell) -> Result<()> {
@@ -6,6 +6,8 @@ pub struct Commit {
     pub message: String,
     pub author: String,
     pub timestamp: i64,
+    /// Path of the repository the commit was found in
+    pub repository: String,
 }
 
 /// Represents a Jira ticket
@@ -14,6 +16,39 @@ pub struct Ticket {
     pub key: String,
     pub summary: String,
     pub status: Option<String>,
+    /// How sure the AI is that today's commits belong to this ticket,
+    /// None when the key was found in a commit message
+    pub confidence: Option<f64>,
+    /// Assigned to the user in the board's active sprint
+    pub in_sprint: bool,
+    /// Time tracking from the tracker, None when the ticket has no estimate
+    pub estimate: Option<TicketEstimate>,
+}
+
+/// Estimate and logged work of a ticket, as the tracker reports them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TicketEstimate {
+    pub original_hours: Option<f64>,
+    pub remaining_hours: Option<f64>,
+    /// Work logged in the tracker itself, e.g. through Tempo
+    pub spent_hours: f64,
+}
+
+/// Description and latest comments of a ticket, given to the AI as context
+#[derive(Debug, Clone, PartialEq)]
+pub struct TicketDetails {
+    pub key: String,
+    pub summary: String,
+    /// Plain text, None when the ticket has no description
+    pub description: Option<String>,
+    /// Oldest first
+    pub comments: Vec<TicketComment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TicketComment {
+    pub author: String,
+    pub body: String,
 }
 
 /// Harvest time entry request for creating a timer
@@ -25,18 +60,23 @@ pub struct CreateTimeEntryRequest {
     pub notes: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub external_reference: Option<ExternalReference>,
+    /// Someone else to create the entry for, needs an administrator token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<u64>,
 }
 
 /// External reference to link Harvest entry to Jira
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ExternalReference {
     pub id: String,
+    #[serde(default)]
     pub group_id: String,
+    #[serde(default)]
     pub permalink: String,
 }
 
 /// Harvest time entry response
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct TimeEntry {
     pub id: u64,
     pub spent_date: String,
@@ -46,15 +86,34 @@ pub struct TimeEntry {
     pub project: Option<ProjectInfo>,
     pub task: Option<TaskInfo>,
     pub started_time: Option<String>,
+    /// Whether the time is billed to the client, from the project's task assignment
+    #[serde(default)]
+    pub billable: bool,
+    /// Rate the hours are billed at, only sent to administrators and managers
+    #[serde(default)]
+    pub billable_rate: Option<f64>,
+    /// The ticket the entry was created for, e.g. by `harv sync`
+    #[serde(default)]
+    pub external_reference: Option<ExternalReference>,
+    /// Who logged the entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<UserInfo>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl TimeEntry {
+    /// Whether the entry is for today, false for timers left running since an earlier day
+    pub fn is_today(&self) -> bool {
+        self.spent_date == chrono::Local::now().format("%Y-%m-%d").to_string()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProjectInfo {
     pub id: u64,
     pub name: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TaskInfo {
     pub id: u64,
     pub name: String,
@@ -64,19 +123,79 @@ pub struct TaskInfo {
 #[derive(Debug, Deserialize)]
 pub struct TimeEntriesResponse {
     pub time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    pub next_page: Option<u32>,
 }
 
 /// Jira issue response
 #[derive(Debug, Deserialize)]
 pub struct JiraIssue {
+    /// Numeric issue ID as a string, needed by Tempo
+    #[serde(default)]
+    pub id: String,
     pub key: String,
     pub fields: JiraFields,
 }
 
+/// Response from /rest/api/3/search/jql and the Agile sprint issue list
+#[derive(Debug, Deserialize)]
+pub struct JiraSearchResponse {
+    #[serde(default)]
+    pub issues: Vec<JiraIssue>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct JiraFields {
     pub summary: String,
     pub status: JiraStatus,
+    /// Time tracking, all in seconds
+    #[serde(default)]
+    pub timeoriginalestimate: Option<u64>,
+    #[serde(default)]
+    pub timeestimate: Option<u64>,
+    #[serde(default)]
+    pub timespent: Option<u64>,
+    /// Atlassian Document Format, only present when the issue is fetched with all fields
+    #[serde(default)]
+    pub description: Option<serde_json::Value>,
+    #[serde(default)]
+    pub comment: Option<JiraCommentPage>,
+}
+
+impl JiraFields {
+    /// The issue's estimate, None when it has neither an original nor a remaining estimate
+    pub fn estimate(&self) -> Option<TicketEstimate> {
+        let hours = |seconds: u64| seconds as f64 / 3600.0;
+        if self.timeoriginalestimate.is_none() && self.timeestimate.is_none() {
+            return None;
+        }
+        Some(TicketEstimate {
+            original_hours: self.timeoriginalestimate.map(hours),
+            remaining_hours: self.timeestimate.map(hours),
+            spent_hours: self.timespent.map(hours).unwrap_or(0.0),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraCommentPage {
+    #[serde(default)]
+    pub comments: Vec<JiraComment>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraComment {
+    #[serde(default)]
+    pub author: Option<JiraUser>,
+    /// Atlassian Document Format
+    #[serde(default)]
+    pub body: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraUser {
+    pub display_name: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -84,38 +203,370 @@ pub struct JiraStatus {
     pub name: String,
 }
 
+/// Response from /rest/api/3/serverInfo
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraServerInfo {
+    #[serde(default)]
+    pub server_title: String,
+    #[serde(default)]
+    pub version: String,
+}
+
+/// Sprint from /rest/agile/1.0/board/{id}/sprint
+#[derive(Debug, Deserialize, Clone)]
+pub struct JiraSprint {
+    pub id: u64,
+    pub name: String,
+    pub state: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraSprintsResponse {
+    #[serde(default)]
+    pub values: Vec<JiraSprint>,
+}
+
+/// Reply to a Linear GraphQL query, errors come with status 200
+#[derive(Debug, Deserialize)]
+pub struct LinearResponse<T> {
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<LinearError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinearError {
+    pub message: String,
+}
+
+/// Issue from the Linear API
+#[derive(Debug, Deserialize)]
+pub struct LinearIssue {
+    /// Team key and number, e.g. "ENG-123"
+    pub identifier: String,
+    pub title: String,
+    pub state: LinearState,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinearState {
+    pub name: String,
+}
+
+impl LinearIssue {
+    pub fn to_ticket(self) -> Ticket {
+        Ticket {
+            key: self.identifier,
+            summary: self.title,
+            status: Some(self.state.name),
+            confidence: None,
+            in_sprint: false,
+            estimate: None,
+        }
+    }
+}
+
+/// `data` of the `issue(id:)` query, None when there is no such issue
+#[derive(Debug, Deserialize)]
+pub struct LinearIssueData {
+    pub issue: Option<LinearIssue>,
+}
+
+/// `data` of the `viewer { assignedIssues }` query
+#[derive(Debug, Deserialize)]
+pub struct LinearViewerData {
+    pub viewer: LinearViewer,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearViewer {
+    pub assigned_issues: LinearIssueConnection,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinearIssueConnection {
+    #[serde(default)]
+    pub nodes: Vec<LinearIssue>,
+}
+
+/// Work item from the Azure DevOps REST API
+#[derive(Debug, Deserialize)]
+pub struct AzureWorkItem {
+    pub id: u64,
+    pub fields: AzureWorkItemFields,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AzureWorkItemFields {
+    #[serde(rename = "System.Title")]
+    pub title: String,
+    #[serde(rename = "System.State", default)]
+    pub state: Option<String>,
+}
+
+impl AzureWorkItem {
+    /// As a ticket keyed `AB#1234`, the form commits mention it in
+    pub fn to_ticket(self) -> Ticket {
+        Ticket {
+            key: format!("AB#{}", self.id),
+            summary: self.fields.title,
+            status: self.fields.state,
+            confidence: None,
+            in_sprint: false,
+            estimate: None,
+        }
+    }
+}
+
+/// Response from the batch work items endpoint
+#[derive(Debug, Deserialize)]
+pub struct AzureWorkItemsResponse {
+    #[serde(default)]
+    pub value: Vec<AzureWorkItem>,
+}
+
+/// Response from a WIQL query, only the IDs of the matching work items
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureWiqlResponse {
+    #[serde(default)]
+    pub work_items: Vec<AzureWorkItemRef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AzureWorkItemRef {
+    pub id: u64,
+}
+
+/// Issue from the GitHub REST API
+#[derive(Debug, Deserialize)]
+pub struct GitHubIssue {
+    pub number: u64,
+    pub title: String,
+    /// "open" or "closed"
+    pub state: String,
+    /// RFC 3339 time of the last change
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Only set in the cross-repository /issues list
+    #[serde(default)]
+    pub repository: Option<GitHubRepository>,
+    /// Set when the "issue" is a pull request
+    #[serde(default)]
+    pub pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitHubRepository {
+    pub full_name: String,
+}
+
+impl GitHubIssue {
+    /// As a ticket keyed `owner/repo#123`
+    pub fn to_ticket(self, repository: &str) -> Ticket {
+        Ticket {
+            key: format!("{}#{}", repository, self.number),
+            summary: self.title,
+            status: Some(self.state),
+            confidence: None,
+            in_sprint: false,
+            estimate: None,
+        }
+    }
+}
+
+/// Worklog from the Tempo API
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TempoWorklog {
+    pub tempo_worklog_id: u64,
+    pub issue: TempoIssue,
+    pub time_spent_seconds: u64,
+    #[serde(default)]
+    pub billable_seconds: u64,
+    pub start_date: String,
+    #[serde(default)]
+    pub start_time: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TempoIssue {
+    pub id: u64,
+}
+
+impl TempoWorklog {
+    pub fn to_time_entry(&self) -> TimeEntry {
+        TimeEntry {
+            id: self.tempo_worklog_id,
+            spent_date: self.start_date.clone(),
+            hours: Some(self.time_spent_seconds as f64 / 3600.0),
+            notes: self.description.clone(),
+            is_running: false,
+            project: None,
+            task: None,
+            started_time: self.start_time.clone(),
+            billable: self.billable_seconds > 0,
+            billable_rate: None,
+            external_reference: None,
+            user: None,
+        }
+    }
+}
+
+/// Response from /4/worklogs/user/{accountId}
+#[derive(Debug, Deserialize)]
+pub struct TempoWorklogsResponse {
+    #[serde(default)]
+    pub results: Vec<TempoWorklog>,
+    #[serde(default)]
+    pub metadata: TempoMetadata,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct TempoMetadata {
+    /// URL of the next page, None on the last one
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// Body for creating or replacing a Tempo worklog
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempoWorklogRequest {
+    pub author_account_id: String,
+    pub issue_id: u64,
+    pub start_date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+    pub time_spent_seconds: u64,
+    pub description: String,
+}
+
+/// Time entry from the Toggl Track API
+#[derive(Debug, Deserialize, Clone)]
+pub struct TogglTimeEntry {
+    pub id: u64,
+    #[serde(default)]
+    pub project_id: Option<u64>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// RFC 3339 start time
+    pub start: String,
+    /// Seconds, negative while the timer is running
+    pub duration: i64,
+    #[serde(default)]
+    pub billable: bool,
+}
+
+impl TogglTimeEntry {
+    pub fn is_running(&self) -> bool {
+        self.duration < 0
+    }
+
+    /// As a harv entry, with running timers measured up to `now`
+    pub fn to_time_entry(&self, now: chrono::DateTime<chrono::Utc>) -> TimeEntry {
+        let start = chrono::DateTime::parse_from_rfc3339(&self.start).ok();
+        let seconds = match start {
+            Some(start) if self.is_running() => (now - start.with_timezone(&chrono::Utc))
+                .num_seconds()
+                .max(0),
+            _ => self.duration.max(0),
+        };
+        let local_start = start.map(|s| s.with_timezone(&chrono::Local));
+
+        TimeEntry {
+            id: self.id,
+            spent_date: local_start
+                .map(|s| s.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            hours: Some(seconds as f64 / 3600.0),
+            notes: self.description.clone(),
+            is_running: self.is_running(),
+            project: None,
+            task: None,
+            started_time: local_start.map(|s| s.format("%H:%M").to_string()),
+            billable: self.billable,
+            billable_rate: None,
+            external_reference: None,
+            user: None,
+        }
+    }
+}
+
+/// Project from /workspaces/{id}/projects
+#[derive(Debug, Deserialize, Clone)]
+pub struct TogglProject {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub active: bool,
+}
+
+/// Body for creating or replacing a Toggl time entry
+#[derive(Debug, Serialize)]
+pub struct TogglTimeEntryRequest {
+    pub created_with: String,
+    pub workspace_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<u64>,
+    pub description: String,
+    pub start: String,
+    /// -1 starts a running timer
+    pub duration: i64,
+}
+
 /// Application context for passing configuration and flags
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Context {
     pub dry_run: bool,
     pub auto_start: bool,
     pub auto_stop: bool,
     pub quiet: bool,
-    pub verbose: bool,
+    /// How many times -v was given: 1 debug, 2 HTTP requests, 3 HTTP bodies
+    pub verbosity: u8,
+    /// Treat "nothing to do" outcomes (no commits/tickets) as failures
+    pub strict: bool,
 }
 
-impl Default for Context {
-    fn default() -> Self {
-        Self {
-            dry_run: false,
-            auto_start: false,
-            auto_stop: false,
-            quiet: false,
-            verbose: false,
-        }
+impl Context {
+    /// Whether any -v was given
+    pub fn verbose(&self) -> bool {
+        self.verbosity > 0
     }
 }
 
 /// Proposed time entry from AI provider
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProposedTimeEntry {
     pub description: String,
     pub project_id: u64,
     pub task_id: u64,
     pub hours: f64,
+    #[serde(
+        rename = "confidence",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub confidence_score: Option<f64>,
 }
 
+/// Proposed entries written by `generate --dry-run --output json`, read by `--from-file`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryPlan {
+    pub days: Vec<PlannedDay>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedDay {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub entries: Vec<ProposedTimeEntry>,
+}
+
 /// Request for creating a stopped time entry (not a running timer)
 #[derive(Debug, Serialize)]
 pub struct CreateStoppedTimeEntryRequest {
@@ -124,6 +575,20 @@ pub struct CreateStoppedTimeEntryRequest {
     pub spent_date: String,
     pub notes: String,
     pub hours: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_reference: Option<ExternalReference>,
+    /// Someone else to create the entry for, needs an administrator token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<u64>,
+}
+
+/// Changes to an existing time entry, unset fields are left as they are
+#[derive(Debug, Default, Serialize)]
+pub struct UpdateTimeEntryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hours: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
 }
 
 /// Response from Harvest API for projects list
@@ -138,6 +603,25 @@ pub struct HarvestProject {
     pub id: u64,
     pub name: String,
     pub code: Option<String>,
+    #[serde(default)]
+    pub client: Option<ClientInfo>,
+}
+
+impl HarvestProject {
+    /// Project name prefixed with its client, e.g. "ACME ▸ Website Redesign"
+    pub fn display_name(&self) -> String {
+        match &self.client {
+            Some(client) => format!("{} ▸ {}", client.name, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Harvest client a project belongs to
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct ClientInfo {
+    pub id: u64,
+    pub name: String,
 }
 
 /// Response from Harvest API for task assignments
@@ -146,10 +630,32 @@ pub struct TaskAssignmentsResponse {
     pub task_assignments: Vec<TaskAssignment>,
 }
 
+/// Response from the account-wide /v2/task_assignments endpoint
+#[derive(Debug, Deserialize)]
+pub struct AccountTaskAssignmentsResponse {
+    pub task_assignments: Vec<AccountTaskAssignment>,
+    #[serde(default)]
+    pub total_pages: Option<u32>,
+    #[serde(default)]
+    pub next_page: Option<u32>,
+}
+
+/// Task assignment including the project it belongs to
+#[derive(Debug, Deserialize)]
+pub struct AccountTaskAssignment {
+    pub is_active: bool,
+    #[serde(default)]
+    pub billable: Option<bool>,
+    pub project: ProjectInfo,
+    pub task: TaskDetail,
+}
+
 /// Task assignment with activation status
 #[derive(Debug, Deserialize)]
 pub struct TaskAssignment {
     pub is_active: bool,
+    #[serde(default)]
+    pub billable: Option<bool>,
     pub task: TaskDetail,
 }
 
@@ -161,10 +667,13 @@ pub struct TaskDetail {
 }
 
 /// Simplified task information
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HarvestTask {
     pub id: u64,
     pub name: String,
+    /// Whether time on the task is billable in this project, None when unknown
+    #[serde(default)]
+    pub billable: Option<bool>,
 }
 
 /// Response from /v2/users/me/project_assignments
@@ -173,12 +682,86 @@ pub struct UserProjectAssignmentsResponse {
     pub project_assignments: Vec<UserProjectAssignment>,
 }
 
+/// A Harvest user, the authenticated one (`/v2/users/me`) or from `/v2/users`
+#[derive(Debug, Clone, Deserialize)]
+pub struct HarvestUser {
+    pub id: u64,
+    /// Harvest timezone name, e.g. "Amsterdam" or "Eastern Time (US & Canada)"
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub first_name: String,
+    #[serde(default)]
+    pub last_name: String,
+}
+
+impl HarvestUser {
+    /// "First Last", or the email for users without a name
+    pub fn name(&self) -> String {
+        let name = format!("{} {}", self.first_name, self.last_name);
+        match name.trim() {
+            "" => self.email.clone().unwrap_or_else(|| self.id.to_string()),
+            name => name.to_string(),
+        }
+    }
+}
+
+/// Response from /v2/users
+#[derive(Debug, Deserialize)]
+pub struct UsersResponse {
+    pub users: Vec<HarvestUser>,
+    #[serde(default)]
+    pub next_page: Option<u32>,
+}
+
+/// The user a time entry belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserInfo {
+    pub id: u64,
+    pub name: String,
+}
+
+/// A time entry of any user in the account, for `harv team`
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserTimeEntry {
+    pub user: UserInfo,
+    #[serde(flatten)]
+    pub entry: TimeEntry,
+}
+
+/// Response from /v2/time_entries, read with the user of each entry
+#[derive(Debug, Deserialize)]
+pub struct UserTimeEntriesResponse {
+    pub time_entries: Vec<UserTimeEntry>,
+    #[serde(default)]
+    pub next_page: Option<u32>,
+}
+
+/// An account the access token can use, from Harvest ID
+#[derive(Debug, Clone, Deserialize)]
+pub struct HarvestAccount {
+    pub id: u64,
+    pub name: String,
+    /// "harvest" or "forecast"
+    pub product: String,
+}
+
+/// Response from Harvest ID for the accounts list
+#[derive(Debug, Deserialize)]
+pub struct HarvestAccountsResponse {
+    pub accounts: Vec<HarvestAccount>,
+}
+
 /// Project assignment for a user
 #[derive(Debug, Deserialize)]
 pub struct UserProjectAssignment {
     pub id: u64,
     pub is_active: bool,
     pub project: HarvestProject,
+    #[serde(default)]
+    pub client: Option<ClientInfo>,
     pub task_assignments: Vec<TaskAssignment>,
 }
 
@@ -204,6 +787,17 @@ pub enum ContinueMode {
     NewEntry,
 }
 
+/// An entry offered by `continue`, standing in for identical entries across days
+#[derive(Debug, Clone)]
+pub struct ContinueCandidate {
+    /// Most recent of the collapsed entries
+    pub entry: TimeEntry,
+    /// Number of distinct days the entry appeared on
+    pub days: usize,
+    /// Name of the favorite this candidate was built from, if any
+    pub favorite: Option<String>,
+}
+
 // Implement HasName trait for usage tracking
 impl crate::usage::HasName for HarvestProject {
     fn name(&self) -> &str {
@@ -211,6 +805,18 @@ impl crate::usage::HasName for HarvestProject {
     }
 }
 
+impl HarvestTask {
+    /// Whether the task fits `--billable` (Some(true)) or `--non-billable` (Some(false))
+    ///
+    /// Tasks whose billability isn't known always fit.
+    pub fn fits_billable(&self, billable: Option<bool>) -> bool {
+        match (billable, self.billable) {
+            (Some(wanted), Some(actual)) => wanted == actual,
+            _ => true,
+        }
+    }
+}
+
 impl crate::usage::HasName for HarvestTask {
     fn name(&self) -> &str {
         &self.name
@@ -0,0 +1,442 @@
+use crate::config::JiraOAuthConfig;
+use crate::error::{HarjiraError, Result};
+use crate::http::ApiClient;
+use crate::outln;
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, info, warn};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+
+const AUTHORIZE_URL: &str = "https://auth.atlassian.com/authorize";
+const TOKEN_URL: &str = "https://auth.atlassian.com/oauth/token";
+const RESOURCES_URL: &str = "https://api.atlassian.com/oauth/token/accessible-resources";
+const SCOPES: &str = "read:jira-work read:jira-user offline_access";
+
+/// Refresh tokens this long before they actually expire
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// Stored Atlassian OAuth (3LO) credentials
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JiraOAuthToken {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: DateTime<Utc>,
+    /// Atlassian cloud ID of the Jira site the token was granted for
+    pub cloud_id: String,
+    /// Site URL, e.g. https://your-company.atlassian.net
+    pub site_url: String,
+}
+
+impl JiraOAuthToken {
+    /// Whether the access token has expired (or is about to)
+    pub fn is_expired(&self) -> bool {
+        Utc::now() + Duration::seconds(EXPIRY_MARGIN_SECS) >= self.expires_at
+    }
+
+    /// Base URL for REST calls made with this token
+    pub fn api_base_url(&self) -> String {
+        format!("https://api.atlassian.com/ex/jira/{}", self.cloud_id)
+    }
+
+    /// Load stored token, returns None if no login has been performed
+    pub fn load() -> Result<Option<Self>> {
+        let path = token_file_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let token: JiraOAuthToken = serde_json::from_str(&contents)?;
+        Ok(Some(token))
+    }
+
+    /// Persist token to disk with 600 permissions
+    pub fn save(&self) -> Result<()> {
+        let path = token_file_path()?;
+
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(self)?)?;
+        debug!("Saved Jira OAuth token to {}", path.display());
+        Ok(())
+    }
+}
+
+/// Token endpoint response
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: i64,
+}
+
+/// Site accessible with the granted token
+#[derive(Debug, Deserialize)]
+struct AccessibleResource {
+    id: String,
+    url: String,
+    name: String,
+}
+
+/// Return a valid token, refreshing it first if it has expired
+pub fn valid_token(oauth: &JiraOAuthConfig) -> Result<Option<JiraOAuthToken>> {
+    let token = match JiraOAuthToken::load()? {
+        Some(token) => token,
+        None => return Ok(None),
+    };
+
+    if !token.is_expired() {
+        return Ok(Some(token));
+    }
+
+    info!("Jira access token expired, refreshing...");
+    let refreshed = refresh_token(oauth, &token)?;
+    refreshed.save()?;
+    Ok(Some(refreshed))
+}
+
+/// Exchange a refresh token for a new access token
+fn refresh_token(oauth: &JiraOAuthConfig, token: &JiraOAuthToken) -> Result<JiraOAuthToken> {
+    let body = serde_json::json!({
+        "grant_type": "refresh_token",
+        "client_id": oauth.client_id,
+        "client_secret": oauth.client_secret,
+        "refresh_token": token.refresh_token,
+    });
+
+    let response = request_token(&body).map_err(|e| {
+        HarjiraError::Jira(format!(
+            "Failed to refresh Jira access token: {}. Run 'harv login jira' again.",
+            e
+        ))
+    })?;
+
+    Ok(JiraOAuthToken {
+        access_token: response.access_token,
+        // Atlassian rotates refresh tokens; keep the old one if none was returned
+        refresh_token: response
+            .refresh_token
+            .unwrap_or_else(|| token.refresh_token.clone()),
+        expires_at: Utc::now() + Duration::seconds(response.expires_in),
+        cloud_id: token.cloud_id.clone(),
+        site_url: token.site_url.clone(),
+    })
+}
+
+/// Run the interactive 3LO authorization code flow
+///
+/// Opens the Atlassian consent page, waits for the redirect on localhost,
+/// exchanges the code for tokens and stores them for `JiraClient`.
+pub fn login(oauth: &JiraOAuthConfig, base_url: &str) -> Result<JiraOAuthToken> {
+    if oauth.client_id.is_empty() || oauth.client_secret.is_empty() {
+        return Err(HarjiraError::Config(
+            "Jira OAuth client_id and client_secret must be set in [jira.oauth]".to_string(),
+        ));
+    }
+
+    let redirect_uri = format!("http://localhost:{}/callback", oauth.redirect_port);
+    let state = random_state();
+
+    let authorize_url = Url::parse_with_params(
+        AUTHORIZE_URL,
+        &[
+            ("audience", "api.atlassian.com"),
+            ("client_id", oauth.client_id.as_str()),
+            ("scope", SCOPES),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("state", state.as_str()),
+            ("response_type", "code"),
+            ("prompt", "consent"),
+        ],
+    )
+    .map_err(|e| HarjiraError::Config(format!("Invalid authorize URL: {}", e)))?;
+
+    let listener = TcpListener::bind(("127.0.0.1", oauth.redirect_port)).map_err(|e| {
+        HarjiraError::Config(format!(
+            "Could not listen on port {} for the OAuth callback: {}",
+            oauth.redirect_port, e
+        ))
+    })?;
+
+    outln!("\nOpen this URL in your browser to authorize harv:\n");
+    outln!("  {}\n", authorize_url);
+    open_browser(authorize_url.as_str());
+    outln!("Waiting for authorization...");
+
+    let code = wait_for_callback(&listener, &state)?;
+
+    debug!("Received authorization code, exchanging for tokens");
+    let body = serde_json::json!({
+        "grant_type": "authorization_code",
+        "client_id": oauth.client_id,
+        "client_secret": oauth.client_secret,
+        "code": code,
+        "redirect_uri": redirect_uri,
+    });
+    let response = request_token(&body)?;
+
+    let refresh_token = response.refresh_token.ok_or_else(|| {
+        HarjiraError::Jira(
+            "Atlassian did not return a refresh token. Ensure the offline_access scope is enabled."
+                .to_string(),
+        )
+    })?;
+
+    let resource = select_resource(&response.access_token, base_url)?;
+
+    let token = JiraOAuthToken {
+        access_token: response.access_token,
+        refresh_token,
+        expires_at: Utc::now() + Duration::seconds(response.expires_in),
+        cloud_id: resource.id,
+        site_url: resource.url,
+    };
+    token.save()?;
+
+    info!(
+        "Logged in to Jira site {} ({})",
+        resource.name, token.site_url
+    );
+    Ok(token)
+}
+
+/// POST to the Atlassian token endpoint
+fn request_token(body: &serde_json::Value) -> Result<TokenResponse> {
+    let response = ApiClient::default()
+        .post(TOKEN_URL)
+        .json(body)
+        .send()
+        .map_err(|e| HarjiraError::Network(format!("Token request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(HarjiraError::Jira(format!(
+            "Token request failed ({}): {}",
+            status, error_text
+        )));
+    }
+
+    response
+        .json()
+        .map_err(|e| HarjiraError::Jira(format!("Failed to parse token response: {}", e)))
+}
+
+/// Pick the Jira site matching the configured base URL
+fn select_resource(access_token: &str, base_url: &str) -> Result<AccessibleResource> {
+    let response = ApiClient::default()
+        .get(RESOURCES_URL)
+        .bearer_auth(access_token)
+        .send()
+        .map_err(|e| HarjiraError::Network(format!("Failed to list Jira sites: {}", e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(HarjiraError::Jira(format!(
+            "Failed to list Jira sites ({}): {}",
+            status, error_text
+        )));
+    }
+
+    let mut resources: Vec<AccessibleResource> = response
+        .json()
+        .map_err(|e| HarjiraError::Jira(format!("Failed to parse Jira sites: {}", e)))?;
+
+    let wanted = base_url.trim_end_matches('/');
+    if let Some(idx) = resources
+        .iter()
+        .position(|r| r.url.trim_end_matches('/').eq_ignore_ascii_case(wanted))
+    {
+        return Ok(resources.swap_remove(idx));
+    }
+
+    match resources.len() {
+        0 => Err(HarjiraError::Jira(
+            "The authorized account has no accessible Jira sites".to_string(),
+        )),
+        1 => {
+            let resource = resources.remove(0);
+            warn!(
+                "Configured base URL {} does not match authorized site {}; using it anyway",
+                base_url, resource.url
+            );
+            Ok(resource)
+        }
+        _ => {
+            let items: Vec<String> = resources
+                .iter()
+                .map(|r| format!("{} ({})", r.name, r.url))
+                .collect();
+            let selection = dialoguer::Select::new()
+                .with_prompt("Select the Jira site to use")
+                .items(&items)
+                .default(0)
+                .interact()
+                .map_err(|_| HarjiraError::UserCancelled)?;
+            Ok(resources.swap_remove(selection))
+        }
+    }
+}
+
+/// Accept redirects on the local listener until the authorization code arrives
+fn wait_for_callback(listener: &TcpListener, expected_state: &str) -> Result<String> {
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+
+        let mut request_line = String::new();
+        BufReader::new(stream.try_clone()?).read_line(&mut request_line)?;
+
+        // GET /callback?code=...&state=... HTTP/1.1
+        let path = request_line.split_whitespace().nth(1).unwrap_or("");
+        if !path.starts_with("/callback") {
+            respond(&mut stream, "404 Not Found", "Not found")?;
+            continue;
+        }
+
+        let url = Url::parse(&format!("http://localhost{}", path))
+            .map_err(|e| HarjiraError::Jira(format!("Invalid OAuth callback: {}", e)))?;
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.into_owned())
+        };
+
+        if let Some(error) = param("error") {
+            respond(
+                &mut stream,
+                "400 Bad Request",
+                "Authorization failed. You can close this tab.",
+            )?;
+            return Err(HarjiraError::Jira(format!(
+                "Authorization denied: {}",
+                error
+            )));
+        }
+
+        if param("state").as_deref() != Some(expected_state) {
+            respond(
+                &mut stream,
+                "400 Bad Request",
+                "State mismatch. Please retry the login.",
+            )?;
+            return Err(HarjiraError::Jira(
+                "OAuth state mismatch; aborting login".to_string(),
+            ));
+        }
+
+        if let Some(code) = param("code") {
+            respond(
+                &mut stream,
+                "200 OK",
+                "harv is now authorized. You can close this tab.",
+            )?;
+            return Ok(code);
+        }
+
+        respond(&mut stream, "400 Bad Request", "Missing authorization code")?;
+    }
+
+    Err(HarjiraError::Jira(
+        "OAuth callback listener closed unexpectedly".to_string(),
+    ))
+}
+
+/// Write a minimal HTML response to the browser
+fn respond(stream: &mut impl Write, status: &str, message: &str) -> Result<()> {
+    let body = format!("<html><body><p>{}</p></body></html>", message);
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Best-effort attempt to open the URL in the user's browser
+fn open_browser(url: &str) {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+
+    if let Err(e) = std::process::Command::new(opener)
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+    {
+        debug!("Could not launch browser with {}: {}", opener, e);
+    }
+}
+
+/// Generate an unguessable state value for CSRF protection
+fn random_state() -> String {
+    let mut bytes = [0u8; 16];
+    let from_urandom = fs::File::open("/dev/urandom").and_then(|mut f| f.read_exact(&mut bytes));
+
+    if from_urandom.is_err() {
+        // Fall back to clock + pid entropy on platforms without /dev/urandom
+        let seed = Utc::now().timestamp_nanos_opt().unwrap_or_default() as u128
+            ^ ((std::process::id() as u128) << 64);
+        bytes = seed.to_le_bytes();
+    }
+
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Get the path to the stored OAuth token
+fn token_file_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("jira_oauth.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_expiring_in(secs: i64) -> JiraOAuthToken {
+        JiraOAuthToken {
+            access_token: "access".to_string(),
+            refresh_token: "refresh".to_string(),
+            expires_at: Utc::now() + Duration::seconds(secs),
+            cloud_id: "abc-123".to_string(),
+            site_url: "https://example.atlassian.net".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_token_expiry_uses_margin() {
+        assert!(!token_expiring_in(3600).is_expired());
+        assert!(token_expiring_in(30).is_expired());
+        assert!(token_expiring_in(-10).is_expired());
+    }
+
+    #[test]
+    fn test_api_base_url_uses_cloud_id() {
+        let token = token_expiring_in(3600);
+        assert_eq!(
+            token.api_base_url(),
+            "https://api.atlassian.com/ex/jira/abc-123"
+        );
+    }
+
+    #[test]
+    fn test_random_state_is_hex() {
+        let state = random_state();
+        assert_eq!(state.len(), 32);
+        assert!(state.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(state, random_state());
+    }
+}
@@ -0,0 +1,423 @@
+//! Entries and stops queued while Harvest can't be reached
+//!
+//! `log`, `start` and `stop` queue their changes here instead of failing when
+//! there is no connection. `harv flush` sends them later, keeping the time
+//! they were queued at: a timer started and stopped offline becomes an entry
+//! for the time in between.
+
+use crate::error::{HarjiraError, Result};
+use crate::harvest::HarvestClient;
+use crate::models::{Context, TimeEntry, UpdateTimeEntryRequest};
+use chrono::{DateTime, Local, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const QUEUE_VERSION: u8 = 1;
+
+/// A change to make once Harvest is reachable again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Operation {
+    /// A stopped entry, or a running timer when `hours` is None
+    Create {
+        spent_date: String,
+        project_id: u64,
+        task_id: u64,
+        notes: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hours: Option<f64>,
+        /// Timer an earlier flush started but couldn't set the hours of
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        entry_id: Option<u64>,
+    },
+    /// Stop a timer, whichever one is running when `entry_id` is None
+    Stop {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        entry_id: Option<u64>,
+        /// Hours to correct the timer to, set once an earlier flush stopped it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hours: Option<f64>,
+    },
+}
+
+impl Operation {
+    /// Short description for messages, e.g. "start timer 'PROJ-1 - Login'"
+    pub fn label(&self) -> String {
+        match self {
+            Operation::Create {
+                notes,
+                hours: Some(hours),
+                spent_date,
+                ..
+            } => format!("log {:.2}h '{}' on {}", hours, notes, spent_date),
+            Operation::Create { notes, .. } => format!("start timer '{}'", notes),
+            Operation::Stop {
+                entry_id: Some(id), ..
+            } => format!("stop timer {}", id),
+            Operation::Stop { entry_id: None, .. } => "stop the running timer".to_string(),
+        }
+    }
+
+    /// A timer that hasn't been started in Harvest yet
+    fn is_timer_start(&self) -> bool {
+        matches!(
+            self,
+            Operation::Create {
+                hours: None,
+                entry_id: None,
+                ..
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedOperation {
+    pub queued_at: DateTime<Utc>,
+    /// Harvest account the change is for
+    pub account_id: String,
+    #[serde(flatten)]
+    pub operation: Operation,
+}
+
+/// Queued operations, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineQueue {
+    version: u8,
+    #[serde(default)]
+    pub operations: Vec<QueuedOperation>,
+}
+
+impl Default for OfflineQueue {
+    fn default() -> Self {
+        Self {
+            version: QUEUE_VERSION,
+            operations: Vec::new(),
+        }
+    }
+}
+
+impl OfflineQueue {
+    /// Load the queue, empty if there is none
+    pub fn load() -> Result<Self> {
+        let path = queue_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let queue: OfflineQueue = serde_json::from_str(&contents)?;
+
+        if queue.version > QUEUE_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "Offline queue version {} is newer than supported version {}",
+                queue.version, QUEUE_VERSION
+            )));
+        }
+
+        Ok(queue)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = queue_path()?;
+
+        if self.operations.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(self)?)?;
+
+        debug!("Saved offline queue to {}", path.display());
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Queue an operation at the current time
+    pub fn push(&mut self, account_id: &str, operation: Operation) {
+        self.operations.push(QueuedOperation {
+            queued_at: Utc::now(),
+            account_id: account_id.to_string(),
+            operation,
+        });
+    }
+}
+
+/// Whether an error means Harvest couldn't be reached at all
+pub fn is_unreachable(error: &HarjiraError) -> bool {
+    matches!(error, HarjiraError::Network(_))
+}
+
+/// Add an operation to the queue on disk
+pub fn queue(account_id: &str, operation: Operation) -> Result<()> {
+    let mut queue = OfflineQueue::load()?;
+    queue.push(account_id, operation);
+    queue.save()
+}
+
+/// Entry to hand back for a queued create, like the ones dry runs return
+pub fn placeholder(operation: &Operation) -> TimeEntry {
+    let (spent_date, notes, hours) = match operation {
+        Operation::Create {
+            spent_date,
+            notes,
+            hours,
+            ..
+        } => (spent_date.clone(), Some(notes.clone()), *hours),
+        Operation::Stop { .. } => (Local::now().format("%Y-%m-%d").to_string(), None, None),
+    };
+    TimeEntry {
+        id: 0,
+        spent_date,
+        is_running: matches!(operation, Operation::Create { hours: None, .. }),
+        hours: Some(hours.unwrap_or(0.0)),
+        notes,
+        project: None,
+        task: None,
+        started_time: None,
+        billable: false,
+        billable_rate: None,
+        external_reference: None,
+        user: None,
+    }
+}
+
+/// What `flush` did
+#[derive(Debug, Default)]
+pub struct FlushReport {
+    pub sent: usize,
+    /// Operations Harvest rejected, with the reason. They are dropped.
+    pub rejected: Vec<(QueuedOperation, String)>,
+    /// Left in the queue because Harvest still can't be reached
+    pub remaining: usize,
+}
+
+/// Send the account's queued operations in the order they were queued
+///
+/// Stops at the first one that can't reach Harvest and keeps it and the
+/// rest for next time. Operations for other accounts are left alone.
+pub fn flush(client: &HarvestClient, ctx: &Context) -> Result<FlushReport> {
+    let mut queue = OfflineQueue::load()?;
+    let (mut pending, others): (Vec<_>, Vec<_>) = queue
+        .operations
+        .drain(..)
+        .partition(|op| op.account_id == client.account_id());
+    let mut report = FlushReport::default();
+
+    while !pending.is_empty() {
+        let mut queued = pending.remove(0);
+        // A timer started and stopped offline is logged as the time in between
+        let stop = if queued.operation.is_timer_start() {
+            pending
+                .iter()
+                .position(|op| {
+                    matches!(
+                        op.operation,
+                        Operation::Stop {
+                            entry_id: None,
+                            hours: None
+                        }
+                    )
+                })
+                .filter(|&i| !pending[..i].iter().any(|op| op.operation.is_timer_start()))
+        } else {
+            None
+        };
+        let stopped_at = stop.map(|i| pending[i].queued_at);
+
+        match send(client, &mut queued, stopped_at, ctx) {
+            Ok(()) => {
+                report.sent += 1;
+                if let Some(i) = stop {
+                    pending.remove(i);
+                    report.sent += 1;
+                }
+            }
+            Err(e) if is_unreachable(&e) => {
+                pending.insert(0, queued);
+                break;
+            }
+            Err(e) => {
+                let reason = e.to_string();
+                report.rejected.push((queued, reason.clone()));
+                // Sent on its own, the paired stop would stop whatever else is running
+                if let Some(i) = stop {
+                    report.rejected.push((pending.remove(i), reason));
+                }
+            }
+        }
+    }
+
+    report.remaining = pending.len();
+    if !ctx.dry_run {
+        queue.operations = pending.into_iter().chain(others).collect();
+        queue.save()?;
+    }
+    Ok(report)
+}
+
+/// Send one operation
+///
+/// Starting or stopping a timer takes two calls. Once the first has gone
+/// through, the operation is updated to only make the second, so resending it
+/// after a lost connection doesn't start or stop the timer twice.
+fn send(
+    client: &HarvestClient,
+    queued: &mut QueuedOperation,
+    stopped_at: Option<DateTime<Utc>>,
+    ctx: &Context,
+) -> Result<()> {
+    let queued_at = queued.queued_at;
+    let label = queued.operation.label();
+    let offline_hours = |until: DateTime<Utc>| -> f64 {
+        let hours = (until - queued_at).num_seconds().max(0) as f64 / 3600.0;
+        (hours * 100.0).round() / 100.0
+    };
+
+    match &mut queued.operation {
+        Operation::Create {
+            spent_date,
+            project_id,
+            task_id,
+            notes,
+            hours,
+            entry_id,
+        } => {
+            let hours = hours.or_else(|| stopped_at.map(offline_hours));
+            if let Some(hours) = hours {
+                client.create_stopped_time_entry_with_date(
+                    notes,
+                    *project_id,
+                    *task_id,
+                    hours,
+                    spent_date,
+                    ctx,
+                )?;
+                return Ok(());
+            }
+
+            // Still running: start it now and count the time since it was queued
+            let timer_id = match *entry_id {
+                Some(id) => id,
+                None => {
+                    let timer = client.create_time_entry_with_date(
+                        notes,
+                        *project_id,
+                        *task_id,
+                        spent_date,
+                        ctx,
+                    )?;
+                    *entry_id = Some(timer.id);
+                    timer.id
+                }
+            };
+            let update = UpdateTimeEntryRequest {
+                hours: Some(offline_hours(Utc::now())),
+                ..UpdateTimeEntryRequest::default()
+            };
+            client.update_time_entry(timer_id, &update, ctx)?;
+            Ok(())
+        }
+        Operation::Stop { entry_id, hours } => {
+            let timer_id = match *entry_id {
+                Some(id) => id,
+                None => match client.get_running_timer()? {
+                    Some(timer) => timer.id,
+                    None => {
+                        debug!("No running timer to stop for {}", label);
+                        return Ok(());
+                    }
+                },
+            };
+
+            let corrected = match *hours {
+                Some(corrected) => corrected,
+                None => {
+                    // The timer kept running until now, take off the time since the stop
+                    let stopped = client.stop_time_entry(timer_id, ctx)?;
+                    let extra = offline_hours(Utc::now());
+                    if extra <= 0.0 {
+                        return Ok(());
+                    }
+                    let corrected = (stopped.hours.unwrap_or(0.0) - extra).max(0.0);
+                    *entry_id = Some(timer_id);
+                    *hours = Some(corrected);
+                    corrected
+                }
+            };
+            let update = UpdateTimeEntryRequest {
+                hours: Some(corrected),
+                ..UpdateTimeEntryRequest::default()
+            };
+            client.update_time_entry(timer_id, &update, ctx)?;
+            Ok(())
+        }
+    }
+}
+
+fn queue_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("offline_queue.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_labels() {
+        let create = |hours| Operation::Create {
+            spent_date: "2026-10-14".to_string(),
+            project_id: 1,
+            task_id: 2,
+            notes: "PROJ-1 - Login".to_string(),
+            hours,
+            entry_id: None,
+        };
+
+        let start = create(None);
+        assert_eq!(start.label(), "start timer 'PROJ-1 - Login'");
+        assert!(placeholder(&start).is_running);
+
+        let log = create(Some(1.5));
+        assert_eq!(log.label(), "log 1.50h 'PROJ-1 - Login' on 2026-10-14");
+        assert!(!placeholder(&log).is_running);
+
+        assert_eq!(
+            Operation::Stop {
+                entry_id: None,
+                hours: None
+            }
+            .label(),
+            "stop the running timer"
+        );
+    }
+
+    #[test]
+    fn test_queued_operation_json() {
+        let queued = QueuedOperation {
+            queued_at: Utc::now(),
+            account_id: "123".to_string(),
+            operation: Operation::Stop {
+                entry_id: Some(9),
+                hours: Some(1.25),
+            },
+        };
+        let json = serde_json::to_value(&queued).unwrap();
+        assert_eq!(json["action"], "stop");
+        assert_eq!(json["entry_id"], 9);
+        assert_eq!(json["hours"], 1.25);
+        assert_eq!(
+            serde_json::from_value::<QueuedOperation>(json).unwrap(),
+            queued
+        );
+    }
+}
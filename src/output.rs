@@ -0,0 +1,230 @@
+use console::style;
+use log::{info, warn};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::io::Write;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// What a message means, so sinks can style or tag it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageKind {
+    /// Regular output: tables, summaries, blank lines
+    Plain,
+    Success,
+    Info,
+    Warning,
+    /// Machine-readable output, like a JSON plan, printed as is
+    Data,
+}
+
+/// Where user-facing output goes
+///
+/// Prompt and command code write through `line`/`success`/`info`/`warning`
+/// (or the `outln!` macro) instead of printing, so the same code can feed
+/// the terminal, a JSON stream, the log, or a test.
+pub trait Reporter: Send + Sync {
+    fn report(&self, kind: MessageKind, message: &str);
+}
+
+/// Process-wide sink, the terminal unless `set_reporter` was called
+static REPORTER: RwLock<Option<Arc<dyn Reporter>>> = RwLock::new(None);
+
+thread_local! {
+    /// Per-thread override installed by `with_reporter`
+    static OVERRIDE: RefCell<Option<Arc<dyn Reporter>>> = const { RefCell::new(None) };
+}
+
+/// Send all output on every thread to `reporter`
+pub fn set_reporter(reporter: impl Reporter + 'static) {
+    *REPORTER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(reporter));
+}
+
+/// Run `f` with this thread's output going to `reporter`
+///
+/// Other threads keep using the process-wide sink, so parallel tests can
+/// each capture their own output.
+pub fn with_reporter<T>(reporter: Arc<dyn Reporter>, f: impl FnOnce() -> T) -> T {
+    let previous = OVERRIDE.with(|o| o.borrow_mut().replace(reporter));
+    let result = f();
+    OVERRIDE.with(|o| *o.borrow_mut() = previous);
+    result
+}
+
+/// Send a message to the current sink
+pub fn report(kind: MessageKind, message: &str) {
+    if let Some(reporter) = OVERRIDE.with(|o| o.borrow().clone()) {
+        return reporter.report(kind, message);
+    }
+    match REPORTER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .as_ref()
+    {
+        Some(reporter) => reporter.report(kind, message),
+        None => TerminalReporter.report(kind, message),
+    }
+}
+
+pub fn line(message: &str) {
+    report(MessageKind::Plain, message);
+}
+
+pub fn success(message: &str) {
+    report(MessageKind::Success, message);
+}
+
+pub fn info(message: &str) {
+    report(MessageKind::Info, message);
+}
+
+pub fn warning(message: &str) {
+    report(MessageKind::Warning, message);
+}
+
+pub fn data(message: &str) {
+    report(MessageKind::Data, message);
+}
+
+/// `println!` for the current reporter
+#[macro_export]
+macro_rules! outln {
+    () => {
+        $crate::output::line("")
+    };
+    ($($arg:tt)*) => {
+        $crate::output::line(&format!($($arg)*))
+    };
+}
+
+/// Styled text on stdout, the default
+pub struct TerminalReporter;
+
+impl Reporter for TerminalReporter {
+    fn report(&self, kind: MessageKind, message: &str) {
+        match kind {
+            MessageKind::Plain | MessageKind::Data => println!("{}", message),
+            MessageKind::Success => {
+                println!("{} {}", style("✓").green().bold(), style(message).green())
+            }
+            MessageKind::Info => println!("{} {}", style("ℹ").cyan().bold(), style(message).cyan()),
+            MessageKind::Warning => {
+                println!("{} {}", style("⚠").yellow().bold(), style(message).yellow())
+            }
+        }
+    }
+}
+
+/// One JSON object per message on stdout, colors stripped
+pub struct JsonReporter;
+
+#[derive(Serialize)]
+struct JsonMessage<'a> {
+    kind: MessageKind,
+    message: &'a str,
+}
+
+impl Reporter for JsonReporter {
+    fn report(&self, kind: MessageKind, message: &str) {
+        let message = console::strip_ansi_codes(message);
+        let json = JsonMessage {
+            kind,
+            message: &message,
+        };
+        if let Ok(json) = serde_json::to_string(&json) {
+            let _ = writeln!(std::io::stdout().lock(), "{}", json);
+        }
+    }
+}
+
+/// Messages forwarded to the `log` crate, warnings at warn level
+///
+/// Data still goes to stdout, so it can be redirected without the rest.
+pub struct LogReporter;
+
+impl Reporter for LogReporter {
+    fn report(&self, kind: MessageKind, message: &str) {
+        let message = console::strip_ansi_codes(message);
+        match kind {
+            MessageKind::Data => println!("{}", message),
+            MessageKind::Warning => warn!("{}", message),
+            _ => info!("{}", message),
+        }
+    }
+}
+
+/// Keeps every message in memory, for tests
+#[derive(Default)]
+pub struct CaptureReporter {
+    messages: Mutex<Vec<(MessageKind, String)>>,
+}
+
+impl CaptureReporter {
+    /// Everything reported so far, colors stripped
+    pub fn messages(&self) -> Vec<(MessageKind, String)> {
+        self.messages
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// All messages joined into one string, one per line
+    pub fn text(&self) -> String {
+        self.messages()
+            .into_iter()
+            .map(|(_, message)| message)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Reporter for CaptureReporter {
+    fn report(&self, kind: MessageKind, message: &str) {
+        self.messages
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push((kind, console::strip_ansi_codes(message).into_owned()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_reporter_captures_output() {
+        let capture = Arc::new(CaptureReporter::default());
+        with_reporter(capture.clone(), || {
+            success("Timer started");
+            outln!();
+            outln!("Total: {}", style("8h").green());
+            warning("No commits found");
+            data("{}");
+        });
+
+        assert_eq!(
+            capture.messages(),
+            vec![
+                (MessageKind::Success, "Timer started".to_string()),
+                (MessageKind::Plain, String::new()),
+                (MessageKind::Plain, "Total: 8h".to_string()),
+                (MessageKind::Warning, "No commits found".to_string()),
+                (MessageKind::Data, "{}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_reporter_restores_previous() {
+        let outer = Arc::new(CaptureReporter::default());
+        let inner = Arc::new(CaptureReporter::default());
+        with_reporter(outer.clone(), || {
+            with_reporter(inner.clone(), || info("inner"));
+            info("outer");
+        });
+        assert_eq!(inner.text(), "inner");
+        assert_eq!(outer.text(), "outer");
+    }
+}
@@ -0,0 +1,123 @@
+use crate::error::{HarjiraError, Result};
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// API mutations skipped during this dry run, in the order they were planned
+static PLANNED: Mutex<Vec<PlannedCall>> = Mutex::new(Vec::new());
+
+/// A request a dry run would have sent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedCall {
+    pub method: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+}
+
+/// Output format for `--explain`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplainFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for ExplainFormat {
+    type Err = HarjiraError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ExplainFormat::Text),
+            "json" => Ok(ExplainFormat::Json),
+            _ => Err(HarjiraError::Config(format!(
+                "Unknown explain format: {}. Supported: text, json",
+                s
+            ))),
+        }
+    }
+}
+
+/// Note a mutation that dry-run mode skipped
+pub fn record<T: Serialize + ?Sized>(method: &str, url: &str, payload: Option<&T>) {
+    let call = PlannedCall {
+        method: method.to_string(),
+        url: url.to_string(),
+        payload: payload.and_then(|p| serde_json::to_value(p).ok()),
+    };
+    PLANNED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(call);
+}
+
+/// Everything recorded so far, leaving the plan empty
+pub fn take() -> Vec<PlannedCall> {
+    std::mem::take(
+        &mut *PLANNED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    )
+}
+
+/// Render the plan for `--explain`
+pub fn render(calls: &[PlannedCall], format: ExplainFormat) -> String {
+    match format {
+        ExplainFormat::Json => {
+            serde_json::to_string_pretty(calls).unwrap_or_else(|_| "[]".to_string())
+        }
+        ExplainFormat::Text => {
+            if calls.is_empty() {
+                return "No API changes planned".to_string();
+            }
+            let mut out = format!("Planned API changes ({}):", calls.len());
+            for (i, call) in calls.iter().enumerate() {
+                out.push_str(&format!("\n  {}. {} {}", i + 1, call.method, call.url));
+                if let Some(ref payload) = call.payload {
+                    out.push_str(&format!("\n     {}", payload));
+                }
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calls() -> Vec<PlannedCall> {
+        vec![
+            PlannedCall {
+                method: "PATCH".to_string(),
+                url: "https://api.harvestapp.com/v2/time_entries/1/stop".to_string(),
+                payload: None,
+            },
+            PlannedCall {
+                method: "POST".to_string(),
+                url: "https://api.harvestapp.com/v2/time_entries".to_string(),
+                payload: Some(serde_json::json!({ "notes": "ABC-1", "hours": 1.5 })),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_text() {
+        assert_eq!(
+            render(&calls(), ExplainFormat::Text),
+            "Planned API changes (2):\n\
+             \x20 1. PATCH https://api.harvestapp.com/v2/time_entries/1/stop\n\
+             \x20 2. POST https://api.harvestapp.com/v2/time_entries\n\
+             \x20    {\"hours\":1.5,\"notes\":\"ABC-1\"}"
+        );
+        assert_eq!(render(&[], ExplainFormat::Text), "No API changes planned");
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&calls(), ExplainFormat::Json)).unwrap();
+        assert_eq!(json[0]["method"], "PATCH");
+        assert!(json[0].get("payload").is_none());
+        assert_eq!(json[1]["payload"]["notes"], "ABC-1");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::models::Context;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressFinish, ProgressStyle};
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_millis(80);
+const SPINNER_TEMPLATE: &str = "{spinner:.cyan.bold} {msg}";
+const BAR_TEMPLATE: &str = "{spinner:.cyan.bold} {msg} [{bar:24.cyan/blue}] {pos}/{len}";
+
+/// Terminal spinner for long-running operations, a progress bar once the
+/// total is known
+///
+/// Drawn on stderr. Hidden when output is quiet or stderr is not a terminal
+/// (systemd, pipes), so callers can use it unconditionally.
+pub struct Spinner {
+    bar: ProgressBar,
+}
+
+impl Spinner {
+    /// Start a spinner with the given message
+    pub fn start(message: impl Into<String>, ctx: &Context) -> Self {
+        let target = if ctx.quiet || !console::user_attended_stderr() {
+            ProgressDrawTarget::hidden()
+        } else {
+            ProgressDrawTarget::stderr()
+        };
+        // Never leave a spinner drawn over prompts or error output
+        let bar = ProgressBar::with_draw_target(None, target)
+            .with_style(style(SPINNER_TEMPLATE))
+            .with_message(message.into())
+            .with_finish(ProgressFinish::AndClear);
+        bar.enable_steady_tick(TICK);
+        Self { bar }
+    }
+
+    /// Replace the spinner message
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.bar.set_message(message.into());
+    }
+
+    /// Show `done` of `total` as a progress bar, a plain spinner while
+    /// `total` is 0
+    pub fn set_progress(&self, label: &str, done: usize, total: usize) {
+        if total > 0 {
+            if self.bar.length().is_none() {
+                self.bar.set_style(style(BAR_TEMPLATE));
+            }
+            self.bar.set_length(total as u64);
+            self.bar.set_position(done as u64);
+        }
+        self.bar.set_message(label.to_string());
+    }
+
+    /// Stop and clear the spinner
+    pub fn finish(self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+fn style(template: &str) -> ProgressStyle {
+    ProgressStyle::with_template(template)
+        .expect("valid progress template")
+        .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏ ")
+}
+
+/// Pluralize a count for spinner messages, e.g. "3 tickets"
+pub fn count_label(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, singular)
+    } else {
+        format!("{} {}", count, plural)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_label() {
+        assert_eq!(count_label(1, "ticket", "tickets"), "1 ticket");
+        assert_eq!(count_label(4, "ticket", "tickets"), "4 tickets");
+    }
+
+    #[test]
+    fn test_quiet_spinner_is_hidden() {
+        let ctx = Context {
+            quiet: true,
+            ..Context::default()
+        };
+        let spinner = Spinner::start("Working", &ctx);
+        assert!(spinner.bar.is_hidden());
+        spinner.set_message("Still working");
+        spinner.set_progress("Fetching tasks", 3, 12);
+        assert_eq!(spinner.bar.position(), 3);
+        spinner.finish();
+    }
+}
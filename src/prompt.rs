@@ -1,20 +1,60 @@
+use crate::dedupe::DuplicateGroup;
 use crate::error::{HarjiraError, Result};
+use crate::expenses::ExpenseCategory;
+use crate::git::WorkInProgress;
+use crate::journal::Batch;
 use crate::models::{
-    ContinueMode, EntryType, HarvestProject, HarvestTask, ProposedTimeEntry, Ticket, TimeEntry,
+    ContinueCandidate, ContinueMode, EntryType, HarvestAccount, HarvestProject, HarvestTask,
+    ProposedTimeEntry, Ticket, TimeEntry,
 };
-use chrono::Local;
+use crate::outln;
+use crate::output;
+use crate::progress::count_label;
+use crate::review::{self, Catalog, EntryProblem, RebalanceOptions};
+use crate::ticket_time::{self, LoggedTime};
+use crate::time_parser::format_hours;
+use chrono::{DateTime, Local};
 use console::style;
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::{Confirm, Editor, FuzzySelect, Input, MultiSelect, Select};
+use std::collections::HashMap;
+
+/// Time already on a ticket for the picker, e.g. "1:30 today, logged 6:00 of 8:00 estimate"
+fn logged_label(ticket: &Ticket, logged: &HashMap<String, LoggedTime>) -> Option<String> {
+    let time = logged.get(&ticket.key).copied().unwrap_or_default();
+    let mut parts = Vec::new();
+    if time.today > 0.0 {
+        parts.push(format!("{} today", format_hours(time.today)));
+    }
+    let total = ticket_time::logged_hours(ticket, logged);
+    match ticket
+        .estimate
+        .and_then(|estimate| ticket_time::estimate_label(&estimate, total))
+    {
+        Some(label) => parts.push(label),
+        None if time.total > 0.0 => parts.push(format!("{} total", format_hours(time.total))),
+        None => {}
+    }
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
 
 /// Prompt user to select a Jira ticket from multiple options
-pub fn prompt_ticket_selection(tickets: &[Ticket]) -> Result<Ticket> {
+///
+/// With more than one ticket an entry opens a multi-select, so several can be
+/// picked at once. With `commit_view`, the list ends with an entry that prints
+/// it (which commits mention which ticket) and then asks again. Tickets in
+/// `logged` show the hours already booked on them.
+pub fn prompt_ticket_selection(
+    tickets: &[Ticket],
+    commit_view: Option<&str>,
+    logged: &HashMap<String, LoggedTime>,
+) -> Result<Vec<Ticket>> {
     if tickets.is_empty() {
         return Err(HarjiraError::NoTicketsFound);
     }
 
     // Build display items
-    let items: Vec<String> = tickets
+    let mut items: Vec<String> = tickets
         .iter()
         .map(|t| {
             let status_str = t
@@ -22,29 +62,104 @@ pub fn prompt_ticket_selection(tickets: &[Ticket]) -> Result<Ticket> {
                 .as_ref()
                 .map(|s| format!(" [{}]", s))
                 .unwrap_or_default();
-            format!("{} - {}{}", t.key, t.summary, status_str)
+            let confidence_str = t
+                .confidence
+                .map(|c| format!(" (AI guess, {:.0}%)", c * 100.0))
+                .unwrap_or_default();
+            let sprint_str = if t.in_sprint { " ★ sprint" } else { "" };
+            let logged_str = logged_label(t, logged)
+                .map(|label| style(format!(" · {}", label)).dim().to_string())
+                .unwrap_or_default();
+            format!(
+                "{} - {}{}{}{}{}",
+                t.key, t.summary, status_str, confidence_str, sprint_str, logged_str
+            )
         })
         .collect();
+    let ticket_items = items.clone();
+    let several = tickets.len() > 1;
+    if several {
+        items.push(style("Select several tickets...").dim().to_string());
+    }
+    if commit_view.is_some() {
+        items.push(style("Show commits per ticket...").dim().to_string());
+    }
+
+    outln!("\nMultiple tickets found in today's commits:");
+
+    loop {
+        let selection = Select::new()
+            .with_prompt("Select a ticket to track")
+            .items(&items)
+            .default(0)
+            .interact()
+            .map_err(|_| HarjiraError::UserCancelled)?;
+
+        if selection < tickets.len() {
+            return Ok(vec![tickets[selection].clone()]);
+        }
+        if several && selection == tickets.len() {
+            let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+                .with_prompt("Select the tickets you worked on (Space=toggle, Enter=confirm)")
+                .items(&ticket_items)
+                .interact()
+                .map_err(|_| HarjiraError::UserCancelled)?;
+            if !selections.is_empty() {
+                return Ok(selections.iter().map(|&i| tickets[i].clone()).collect());
+            }
+            continue;
+        }
+        if let Some(view) = commit_view {
+            outln!("\n{}", view);
+        }
+    }
+}
 
-    println!("\nMultiple Jira tickets found in today's commits:");
+/// Ask which of the picked tickets is being worked on now, None when all are done
+pub fn prompt_active_ticket(tickets: &[Ticket]) -> Result<Option<usize>> {
+    let mut items: Vec<String> = tickets
+        .iter()
+        .map(|t| format!("{} - {}", t.key, t.summary))
+        .collect();
+    items.push("None, I'm done with all of them".to_string());
 
-    let selection = Select::new()
-        .with_prompt("Select a ticket to track")
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Which ticket are you working on now?")
         .items(&items)
         .default(0)
         .interact()
         .map_err(|_| HarjiraError::UserCancelled)?;
+    Ok((selection < tickets.len()).then_some(selection))
+}
 
-    Ok(tickets[selection].clone())
+/// Ask how long a finished ticket took, None to log nothing for it
+pub fn prompt_finished_ticket_hours(ticket: &Ticket) -> Result<Option<f64>> {
+    let hours_str: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Hours spent on {} - {} (Enter to skip)",
+            ticket.key, ticket.summary
+        ))
+        .allow_empty(true)
+        .validate_with(|input: &String| -> std::result::Result<(), String> {
+            if input.trim().is_empty() {
+                return Ok(());
+            }
+            crate::time_parser::parse_hours(input)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact_text()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    if hours_str.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(crate::time_parser::parse_hours(&hours_str)?))
 }
 
 /// Confirm whether to stop the current timer and start a new one
 pub fn confirm_stop_timer(current_timer: &TimeEntry, new_ticket: &str) -> Result<bool> {
-    let current_notes = current_timer
-        .notes
-        .as_ref()
-        .map(|s| s.as_str())
-        .unwrap_or("Unknown");
+    let current_notes = current_timer.notes.as_deref().unwrap_or("Unknown");
 
     let project_info = current_timer
         .project
@@ -52,18 +167,22 @@ pub fn confirm_stop_timer(current_timer: &TimeEntry, new_ticket: &str) -> Result
         .map(|p| format!(" ({})", p.name))
         .unwrap_or_default();
 
-    println!("\n⚠️  Timer currently running:");
-    println!("   {}{}", current_notes, project_info);
+    outln!("\n⚠️  Timer currently running:");
+    outln!("   {}{}", current_notes, project_info);
+
+    if !current_timer.is_today() {
+        outln!("   Running since: {}", current_timer.spent_date);
+    }
 
     if let Some(started) = &current_timer.started_time {
-        println!("   Started at: {}", started);
+        outln!("   Started at: {}", started);
     }
 
     if let Some(hours) = current_timer.hours {
-        println!("   Duration: {:.2} hours", hours);
+        outln!("   Duration: {}", format_hours(hours));
     }
 
-    println!("\nNew ticket: {}", new_ticket);
+    outln!("\nNew ticket: {}", new_ticket);
 
     Confirm::new()
         .with_prompt("Stop current timer and start new one?")
@@ -74,24 +193,24 @@ pub fn confirm_stop_timer(current_timer: &TimeEntry, new_ticket: &str) -> Result
 
 /// Display a success message
 pub fn display_success(message: &str) {
-    println!("{} {}", style("✓").green().bold(), style(message).green());
+    output::success(message);
 }
 
 /// Display an info message
 pub fn display_info(message: &str) {
-    println!("{} {}", style("ℹ").cyan().bold(), style(message).cyan());
+    output::info(message);
 }
 
 /// Display a warning message
 pub fn display_warning(message: &str) {
-    println!("{} {}", style("⚠").yellow().bold(), style(message).yellow());
+    output::warning(message);
 }
 
 /// Prompt user to enter their work summary
 pub fn prompt_work_summary() -> Result<String> {
-    println!("\nEnter a summary of your work today:");
-    println!("(You can describe multiple activities)");
-    println!();
+    outln!("\nEnter a summary of your work today:");
+    outln!("(You can describe multiple activities)");
+    outln!();
 
     let summary = Editor::new()
         .edit("Enter your work summary here...\n")
@@ -103,50 +222,126 @@ pub fn prompt_work_summary() -> Result<String> {
 
 /// Display proposed entries and allow user to review/edit
 pub fn review_and_approve_entries(
+    title: &str,
     entries: &[ProposedTimeEntry],
-    projects: &[HarvestProject],
+    catalog: &Catalog,
+    overlaps: &[Option<&TimeEntry>],
+    rebalance: &RebalanceOptions,
+    auto_approve_confidence: Option<f64>,
 ) -> Result<Vec<ProposedTimeEntry>> {
-    println!("\n{}", style("=".repeat(80)).cyan().bold());
-    println!("{}", style("AI Generated Time Entries").cyan().bold());
-    println!("{}", style("=".repeat(80)).cyan().bold());
+    outln!("\n{}", style("=".repeat(80)).cyan().bold());
+    outln!("{}", style(title).cyan().bold());
+    outln!("{}", style("=".repeat(80)).cyan().bold());
+
+    // Entries below the confidence threshold go on top, where they get looked at
+    let order = crate::review::review_order(entries, auto_approve_confidence);
+    let overlaps: Vec<Option<&TimeEntry>> = order.iter().map(|&i| overlaps[i]).collect();
+    let mut entries: Vec<ProposedTimeEntry> = order.iter().map(|&i| entries[i].clone()).collect();
+    let unsure: Vec<bool> = entries
+        .iter()
+        .map(|e| crate::review::below_confidence(e, auto_approve_confidence))
+        .collect();
+
+    // Fix entries Harvest would reject before showing the list
+    for (idx, entry) in entries.iter_mut().enumerate() {
+        if let Some(problem) = catalog.check(entry) {
+            fix_invalid_entry(idx, entry, &problem, catalog)?;
+        }
+    }
+    let problems: Vec<Option<EntryProblem>> = entries.iter().map(|e| catalog.check(e)).collect();
 
     let total_hours: f64 = entries.iter().map(|e| e.hours).sum();
 
     // Build items for display and selection (plain text, colors will come from theme)
     let items: Vec<String> = entries
         .iter()
-        .map(|entry| {
-            let project_name = projects
-                .iter()
-                .find(|p| p.id == entry.project_id)
+        .zip(&overlaps)
+        .zip(&problems)
+        .zip(&unsure)
+        .map(|(((entry, overlap), problem), unsure)| {
+            let project_name = catalog
+                .project(entry.project_id)
                 .map(|p| p.name.as_str())
                 .unwrap_or("Unknown Project");
 
-            let confidence_str = if let Some(conf) = entry.confidence_score {
-                format!(" [confidence: {:.0}%]", conf * 100.0)
-            } else {
-                String::new()
+            let confidence_str = match entry.confidence_score {
+                Some(conf) if *unsure => {
+                    format!(" [confidence: {:.0}%] ⚠ low confidence", conf * 100.0)
+                }
+                Some(conf) => format!(" [confidence: {:.0}%]", conf * 100.0),
+                None if *unsure => " ⚠ no confidence score".to_string(),
+                None => String::new(),
             };
 
+            let overlap_str = overlap
+                .map(|existing| {
+                    format!(
+                        " ⚠ possible duplicate of '{}'",
+                        existing.notes.as_deref().unwrap_or("(no description)")
+                    )
+                })
+                .unwrap_or_default();
+
+            let problem_str = problem
+                .as_ref()
+                .map(|p| format!(" ✗ {}", p))
+                .unwrap_or_default();
+
             format!(
-                "{:.2}h - {} ({}){} ",
-                entry.hours,
+                "{} - {} ({}){}{}{} ",
+                format_hours(entry.hours),
                 entry.description,
                 project_name,
-                confidence_str
+                confidence_str,
+                overlap_str,
+                problem_str
             )
         })
         .collect();
 
-    println!();
-    println!("{} {}",
+    outln!();
+    outln!(
+        "{} {}",
         style("Total:").yellow().bold(),
-        style(format!("{:.2} hours", total_hours)).yellow().bold()
+        style(format_hours(total_hours)).yellow().bold()
     );
-    println!();
+    outln!();
+
+    let overlap_count = overlaps.iter().filter(|o| o.is_some()).count();
+    if overlap_count > 0 {
+        outln!(
+            "{}",
+            style(format!(
+                "⚠  {} may already be logged today (unselected by default)",
+                crate::progress::count_label(overlap_count, "entry", "entries")
+            ))
+            .yellow()
+        );
+        outln!();
+    }
+
+    let unsure_count = unsure.iter().filter(|u| **u).count();
+    if let (Some(threshold), true) = (auto_approve_confidence, unsure_count > 0) {
+        outln!(
+            "{}",
+            style(format!(
+                "⚠  {} below {:.0}% confidence (listed first, unselected by default)",
+                crate::progress::count_label(unsure_count, "entry is", "entries are"),
+                threshold * 100.0
+            ))
+            .yellow()
+        );
+        outln!();
+    }
 
     // Multi-select for approval with colorful theme
-    let defaults = vec![true; entries.len()];
+    // Likely duplicates, invalid and unsure entries start unselected so they are only created on purpose
+    let defaults: Vec<bool> = overlaps
+        .iter()
+        .zip(&problems)
+        .zip(&unsure)
+        .map(|((o, p), u)| o.is_none() && p.is_none() && !u)
+        .collect();
 
     let selections = MultiSelect::with_theme(&ColorfulTheme::default())
         .with_prompt("Select entries to create (Space=toggle, Enter=confirm, Ctrl+C=cancel)")
@@ -155,104 +350,94 @@ pub fn review_and_approve_entries(
         .interact()
         .map_err(|_| HarjiraError::UserCancelled)?;
 
-    if selections.is_empty() {
-        return Ok(Vec::new());
-    }
+    let mut approved: Vec<ProposedTimeEntry> =
+        selections.iter().map(|&idx| entries[idx].clone()).collect();
 
-    let mut approved: Vec<ProposedTimeEntry> = selections
-        .iter()
-        .map(|&idx| entries[idx].clone())
-        .collect();
+    // Let the user edit, split, remove or add entries before anything is created
+    loop {
+        outln!();
+        print_review_entries(&approved, catalog);
 
-    // Ask if user wants to edit any entries
-    println!();
-    let want_edit = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Would you like to edit any entries? (hours/description)")
-        .default(false)
-        .interact()
-        .map_err(|_| HarjiraError::UserCancelled)?;
+        let mut actions = vec!["Continue"];
+        if !approved.is_empty() {
+            actions.extend(["Edit an entry", "Split an entry", "Remove an entry"]);
+        }
+        actions.push("Add an entry");
 
-    if want_edit {
-        // Build list of entries to select for editing
-        let edit_items: Vec<String> = approved
-            .iter()
-            .enumerate()
-            .map(|(idx, entry)| {
-                format!(
-                    "{}. {:.2}h - {}",
-                    idx + 1,
-                    entry.hours,
-                    entry.description
-                )
-            })
-            .collect();
-
-        let edit_selections = MultiSelect::with_theme(&ColorfulTheme::default())
-            .with_prompt("Select entries to edit")
-            .items(&edit_items)
+        let action = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("What would you like to do?")
+            .items(&actions)
+            .default(0)
             .interact()
             .map_err(|_| HarjiraError::UserCancelled)?;
 
-        // Edit each selected entry
-        for &idx in &edit_selections {
-            let entry = &mut approved[idx];
-
-            println!();
-            println!("{}", style(format!("Editing entry {}", idx + 1)).cyan().bold());
-            println!("{}", style("=".repeat(60)).cyan());
-
-            // Edit hours
-            let hours_str: String = Input::with_theme(&ColorfulTheme::default())
-                .with_prompt("Hours (e.g., 1.5 or 1:30)")
-                .default(format!("{:.2}", entry.hours))
-                .validate_with(|input: &String| -> std::result::Result<(), String> {
-                    match crate::time_parser::parse_hours(input) {
-                        Ok(_) => Ok(()),
-                        Err(e) => Err(e.to_string()),
-                    }
-                })
-                .interact_text()
-                .map_err(|_| HarjiraError::UserCancelled)?;
-
-            let new_hours = crate::time_parser::parse_hours(&hours_str)?;
-
-            // Edit description
-            let new_description: String = Input::with_theme(&ColorfulTheme::default())
-                .with_prompt("Description")
-                .default(entry.description.clone())
-                .interact_text()
-                .map_err(|_| HarjiraError::UserCancelled)?;
-
-            entry.hours = new_hours;
-            entry.description = new_description;
-
-            println!("{}", style("✓ Entry updated").green());
+        match actions[action] {
+            "Edit an entry" => {
+                let idx = select_review_entry(&approved, "Select entry to edit")?;
+                edit_review_entry(idx, &mut approved[idx], catalog)?;
+            }
+            "Split an entry" => {
+                let idx = select_review_entry(&approved, "Select entry to split")?;
+                split_review_entry(idx, &mut approved)?;
+            }
+            "Remove an entry" => {
+                let idx = select_review_entry(&approved, "Select entry to remove")?;
+                let removed = approved.remove(idx);
+                outln!(
+                    "{}",
+                    style(format!("✓ Removed '{}'", removed.description)).green()
+                );
+            }
+            "Add an entry" => {
+                let description = prompt_description()?;
+                let (project_id, task_id) = prompt_project_and_task(catalog)?;
+                let hours = prompt_hours()?;
+                approved.push(ProposedTimeEntry {
+                    description,
+                    project_id,
+                    task_id,
+                    hours,
+                    confidence_score: None,
+                });
+                outln!("{}", style("✓ Entry added").green());
+            }
+            _ => break,
         }
     }
 
+    if approved.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    rebalance_review_entries(&mut approved, catalog, rebalance)?;
+
     // Confirm final entries
     let approved_total: f64 = approved.iter().map(|e| e.hours).sum();
-    println!();
-    println!("{}", style("=".repeat(80)).cyan().bold());
-    println!("{}", style("Final entries to create:").cyan().bold());
+    outln!();
+    outln!("{}", style("=".repeat(80)).cyan().bold());
+    outln!("{}", style("Final entries to create:").cyan().bold());
     for (idx, entry) in approved.iter().enumerate() {
-        println!(
-            "  {}. {} - {}",
+        let project_name = catalog
+            .project(entry.project_id)
+            .map(|p| p.name.as_str())
+            .unwrap_or("Unknown Project");
+        outln!(
+            "  {}. {} - {} {}",
             style(idx + 1).cyan().bold(),
-            style(format!("{:.2}h", entry.hours)).green().bold(),
-            style(&entry.description).white()
+            style(format_hours(entry.hours)).green().bold(),
+            style(&entry.description).white(),
+            style(format!("({})", project_name)).dim()
         );
     }
-    println!();
-    println!(
-        "{} {} {} {} {}",
+    outln!();
+    outln!(
+        "{} {} {} {}",
         style("Will create").white(),
         style(approved.len()).green().bold(),
         style("entries totaling").white(),
-        style(format!("{:.2}", approved_total)).green().bold(),
-        style("hours").white()
+        style(format_hours(approved_total)).green().bold()
     );
-    println!("{}", style("=".repeat(80)).cyan().bold());
+    outln!("{}", style("=".repeat(80)).cyan().bold());
 
     let confirmed = Confirm::with_theme(&ColorfulTheme::default())
         .with_prompt("Proceed with creation?")
@@ -267,6 +452,247 @@ pub fn review_and_approve_entries(
     Ok(approved)
 }
 
+/// Offer to pick a valid project and task for an entry Harvest would reject
+fn fix_invalid_entry(
+    idx: usize,
+    entry: &mut ProposedTimeEntry,
+    problem: &EntryProblem,
+    catalog: &Catalog,
+) -> Result<()> {
+    outln!();
+    display_warning(&format!(
+        "Entry {} '{}': {}",
+        idx + 1,
+        entry.description,
+        problem
+    ));
+
+    let choice = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("How do you want to fix it?")
+        .items(["Pick a project and task", "Leave it (unselected)"])
+        .default(0)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    if choice == 0 {
+        let (project_id, task_id) = prompt_project_and_task(catalog)?;
+        entry.project_id = project_id;
+        entry.task_id = task_id;
+    }
+    Ok(())
+}
+
+/// Print the entries currently queued in the review
+fn print_review_entries(entries: &[ProposedTimeEntry], catalog: &Catalog) {
+    if entries.is_empty() {
+        outln!("{}", style("No entries selected").yellow());
+        return;
+    }
+
+    for (idx, entry) in entries.iter().enumerate() {
+        let project_name = catalog
+            .project(entry.project_id)
+            .map(|p| p.name.as_str())
+            .unwrap_or("Unknown Project");
+        outln!(
+            "  {}. {} - {} {}",
+            style(idx + 1).cyan().bold(),
+            style(format_hours(entry.hours)).green().bold(),
+            style(&entry.description).white(),
+            style(format!("({})", project_name)).dim()
+        );
+    }
+    let total: f64 = entries.iter().map(|e| e.hours).sum();
+    outln!(
+        "  {} {}",
+        style("Total:").yellow().bold(),
+        style(format_hours(total)).yellow().bold()
+    );
+}
+
+/// Offer to scale entries or add a filler so the total hits the target
+fn rebalance_review_entries(
+    entries: &mut Vec<ProposedTimeEntry>,
+    catalog: &Catalog,
+    options: &RebalanceOptions,
+) -> Result<()> {
+    let target = options.target_hours;
+    let total: f64 = entries.iter().map(|e| e.hours).sum();
+    if target <= 0.0 || (total - target).abs() < 0.01 {
+        return Ok(());
+    }
+
+    let mut actions = vec![
+        "Keep as is".to_string(),
+        format!("Scale entries to {}", format_hours(target)),
+    ];
+    let filler = options
+        .filler
+        .as_ref()
+        .and_then(|f| review::filler_entry(entries, target, f));
+    if let Some(entry) = &filler {
+        actions.push(format!(
+            "Add {} '{}' on {}",
+            format_hours(entry.hours),
+            entry.description,
+            catalog
+                .task_name(entry.task_id)
+                .unwrap_or("the filler task")
+        ));
+    }
+
+    outln!();
+    let action = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Entries total {}, {} remain to reach today's target",
+            format_hours(total),
+            format_hours(target)
+        ))
+        .items(&actions)
+        .default(0)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    match (action, filler) {
+        (1, _) => {
+            review::scale_to_target(entries, target, options.rounding_minutes);
+        }
+        (2, Some(entry)) => entries.push(entry),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Pick one of the queued entries
+fn select_review_entry(entries: &[ProposedTimeEntry], prompt: &str) -> Result<usize> {
+    let items: Vec<String> = entries
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| {
+            format!(
+                "{}. {} - {}",
+                idx + 1,
+                format_hours(entry.hours),
+                entry.description
+            )
+        })
+        .collect();
+
+    Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&items)
+        .default(0)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)
+}
+
+/// Edit hours, description, project and task of a queued entry
+fn edit_review_entry(idx: usize, entry: &mut ProposedTimeEntry, catalog: &Catalog) -> Result<()> {
+    outln!();
+    outln!(
+        "{}",
+        style(format!("Editing entry {}", idx + 1)).cyan().bold()
+    );
+    outln!("{}", style("=".repeat(60)).cyan());
+
+    // Edit hours
+    let hours_str: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Hours (e.g., 1.5 or 1:30)")
+        .default(format!("{:.2}", entry.hours))
+        .validate_with(|input: &String| -> std::result::Result<(), String> {
+            match crate::time_parser::parse_hours(input) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(e.to_string()),
+            }
+        })
+        .interact_text()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    let new_hours = crate::time_parser::parse_hours(&hours_str)?;
+
+    // Edit description
+    let new_description: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Description")
+        .default(entry.description.clone())
+        .interact_text()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    entry.hours = new_hours;
+    entry.description = new_description;
+
+    // Reassign project/task, the AI most often gets these wrong
+    let current = format!(
+        "{} / {}",
+        catalog
+            .project(entry.project_id)
+            .map(|p| p.display_name())
+            .unwrap_or_else(|| "Unknown Project".to_string()),
+        catalog.task_name(entry.task_id).unwrap_or("Unknown Task")
+    );
+    let change_project = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Change project/task? (currently {})", current))
+        .default(false)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    if change_project {
+        let (project_id, task_id) = prompt_project_and_task(catalog)?;
+        entry.project_id = project_id;
+        entry.task_id = task_id;
+    }
+
+    outln!("{}", style("✓ Entry updated").green());
+    Ok(())
+}
+
+/// Split a queued entry in two, asking how many hours go to the first part
+fn split_review_entry(idx: usize, entries: &mut Vec<ProposedTimeEntry>) -> Result<()> {
+    let entry = entries[idx].clone();
+    if entry.hours <= 0.0 {
+        display_warning("An entry without hours can't be split");
+        return Ok(());
+    }
+
+    let hours_str: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Hours for the first part (of {:.2})", entry.hours))
+        .default(format!("{:.2}", entry.hours / 2.0))
+        .validate_with(|input: &String| -> std::result::Result<(), String> {
+            let hours = crate::time_parser::parse_hours(input).map_err(|e| e.to_string())?;
+            match review::split_entry(&entry, hours) {
+                Some(_) => Ok(()),
+                None => Err(format!("Must be between 0 and {:.2}", entry.hours)),
+            }
+        })
+        .interact_text()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    let first_hours = crate::time_parser::parse_hours(&hours_str)?;
+    let Some((first, mut second)) = review::split_entry(&entry, first_hours) else {
+        return Ok(());
+    };
+
+    second.description = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Description for the second part ({})",
+            format_hours(second.hours)
+        ))
+        .default(second.description.clone())
+        .interact_text()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    entries[idx] = first;
+    entries.insert(idx + 1, second);
+    outln!("{}", style("✓ Entry split").green());
+    Ok(())
+}
+
+/// Pick a project, then one of the tasks assigned to it
+fn prompt_project_and_task(catalog: &Catalog) -> Result<(u64, u64)> {
+    let project = prompt_project_selection(catalog.projects)?;
+    let task = prompt_task_selection(&catalog.tasks_for(project.id))?;
+    Ok((project.id, task.id))
+}
+
 /// Prompt user to select entry type (running timer vs stopped entry)
 pub fn prompt_entry_type() -> Result<EntryType> {
     let items = vec![
@@ -367,9 +793,9 @@ pub fn prompt_project_selection(projects: &[HarvestProject]) -> Result<HarvestPr
         .iter()
         .map(|p| {
             if let Some(code) = &p.code {
-                format!("{} [{}]", p.name, code)
+                format!("{} [{}]", p.display_name(), code)
             } else {
-                p.name.clone()
+                p.display_name()
             }
         })
         .collect();
@@ -384,6 +810,31 @@ pub fn prompt_project_selection(projects: &[HarvestProject]) -> Result<HarvestPr
     Ok(projects[selection].clone())
 }
 
+/// Prompt user to select a Harvest account, picking the only one without asking
+pub fn prompt_account_selection(accounts: &[HarvestAccount]) -> Result<HarvestAccount> {
+    match accounts {
+        [] => Err(HarjiraError::Config(
+            "This access token has no Harvest accounts".to_string(),
+        )),
+        [account] => Ok(account.clone()),
+        _ => {
+            let items: Vec<String> = accounts
+                .iter()
+                .map(|a| format!("{} ({})", a.name, a.id))
+                .collect();
+
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Select Harvest account")
+                .items(&items)
+                .default(0)
+                .interact()
+                .map_err(|_| HarjiraError::UserCancelled)?;
+
+            Ok(accounts[selection].clone())
+        }
+    }
+}
+
 /// Prompt user to select a task
 pub fn prompt_task_selection(tasks: &[HarvestTask]) -> Result<HarvestTask> {
     if tasks.is_empty() {
@@ -392,7 +843,13 @@ pub fn prompt_task_selection(tasks: &[HarvestTask]) -> Result<HarvestTask> {
         ));
     }
 
-    let items: Vec<String> = tasks.iter().map(|t| t.name.clone()).collect();
+    let items: Vec<String> = tasks
+        .iter()
+        .map(|t| match t.billable {
+            Some(false) => format!("{} (non-billable)", t.name),
+            _ => t.name.clone(),
+        })
+        .collect();
 
     let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
         .with_prompt("Select task (type to search)")
@@ -404,6 +861,17 @@ pub fn prompt_task_selection(tasks: &[HarvestTask]) -> Result<HarvestTask> {
     Ok(tasks[selection].clone())
 }
 
+/// Prompt for a favorite name
+pub fn prompt_favorite_name() -> Result<String> {
+    let name: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Favorite name (e.g., standup)")
+        .validate_with(|input: &String| crate::favorites::validate_name(input))
+        .interact_text()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    Ok(crate::favorites::normalize_name(&name))
+}
+
 /// Prompt for time entry description
 pub fn prompt_description() -> Result<String> {
     let description: String = Input::with_theme(&ColorfulTheme::default())
@@ -441,6 +909,60 @@ pub fn prompt_hours() -> Result<f64> {
     Ok(hours)
 }
 
+/// Prompt user to select an expense category
+pub fn prompt_expense_category_selection(
+    categories: &[ExpenseCategory],
+) -> Result<ExpenseCategory> {
+    if categories.is_empty() {
+        return Err(HarjiraError::Config(
+            "No active expense categories found in your Harvest account".to_string(),
+        ));
+    }
+
+    let items: Vec<String> = categories.iter().map(|c| c.name.clone()).collect();
+
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select expense category (type to search)")
+        .items(&items)
+        .default(0)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    Ok(categories[selection].clone())
+}
+
+/// Prompt for an expense amount with validation
+pub fn prompt_amount() -> Result<f64> {
+    let amount_str: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter amount (e.g., 12.50)")
+        .validate_with(|input: &String| -> std::result::Result<(), String> {
+            match crate::expenses::parse_amount(input) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(e.to_string()),
+            }
+        })
+        .interact_text()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    crate::expenses::parse_amount(&amount_str)
+}
+
+/// Prompt for optional receipt notes
+pub fn prompt_expense_notes() -> Result<Option<String>> {
+    let notes: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Receipt notes (optional)")
+        .allow_empty(true)
+        .interact_text()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+
+    let notes = notes.trim();
+    Ok(if notes.is_empty() {
+        None
+    } else {
+        Some(notes.to_string())
+    })
+}
+
 /// Confirm entry creation with full details
 pub fn confirm_entry_creation(
     entry_type: &EntryType,
@@ -449,27 +971,31 @@ pub fn confirm_entry_creation(
     task: &str,
     description: &str,
     hours: Option<f64>,
+    user: Option<&str>,
 ) -> Result<bool> {
-    println!();
-    println!("{}", style("=".repeat(60)).cyan().bold());
-    println!("{}", style("Entry Summary").cyan().bold());
-    println!("{}", style("=".repeat(60)).cyan().bold());
-    println!(
+    outln!();
+    outln!("{}", style("=".repeat(60)).cyan().bold());
+    outln!("{}", style("Entry Summary").cyan().bold());
+    outln!("{}", style("=".repeat(60)).cyan().bold());
+    outln!(
         "Type:        {}",
         match entry_type {
             EntryType::Running => style("Running Timer").green(),
             EntryType::Stopped => style("Stopped Entry").yellow(),
         }
     );
-    println!("Date:        {}", style(date).white());
-    println!("Project:     {}", style(project).white());
-    println!("Task:        {}", style(task).white());
-    println!("Description: {}", style(description).white());
+    if let Some(user) = user {
+        outln!("For:         {}", style(user).magenta().bold());
+    }
+    outln!("Date:        {}", style(date).white());
+    outln!("Project:     {}", style(project).white());
+    outln!("Task:        {}", style(task).white());
+    outln!("Description: {}", style(description).white());
     if let Some(h) = hours {
-        println!("Hours:       {}", style(format!("{:.2}h", h)).green().bold());
+        outln!("Hours:       {}", style(format_hours(h)).green().bold());
     }
-    println!("{}", style("=".repeat(60)).cyan().bold());
-    println!();
+    outln!("{}", style("=".repeat(60)).cyan().bold());
+    outln!();
 
     Confirm::with_theme(&ColorfulTheme::default())
         .with_prompt("Create this entry?")
@@ -478,25 +1004,67 @@ pub fn confirm_entry_creation(
         .map_err(|_| HarjiraError::UserCancelled)
 }
 
+/// Show the approved entries for every backfilled day and confirm creating them
+pub fn confirm_week_entries(
+    days: &[(String, Vec<ProposedTimeEntry>)],
+    catalog: &Catalog,
+) -> Result<bool> {
+    outln!();
+    outln!("{}", style("=".repeat(60)).cyan().bold());
+    outln!("{}", style("Week Summary").cyan().bold());
+    outln!("{}", style("=".repeat(60)).cyan().bold());
+
+    let mut week_total = 0.0;
+    for (date, entries) in days {
+        let day_total: f64 = entries.iter().map(|e| e.hours).sum();
+        week_total += day_total;
+        outln!(
+            "\n{} {}",
+            style(date).bold(),
+            style(format!("({})", format_hours(day_total))).green()
+        );
+        for entry in entries {
+            let project = catalog
+                .project(entry.project_id)
+                .map(|p| p.name.as_str())
+                .unwrap_or("Unknown Project");
+            outln!(
+                "  {:>6}  {} - {}",
+                format_hours(entry.hours),
+                project,
+                entry.description
+            );
+        }
+    }
+
+    outln!();
+    outln!("Total: {}", style(format_hours(week_total)).green().bold());
+    outln!("{}", style("=".repeat(60)).cyan().bold());
+    outln!();
+
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Create these entries?")
+        .default(true)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)
+}
+
 /// Confirm stopping existing timer for new manual entry
 pub fn confirm_stop_timer_for_new(current_timer: &TimeEntry) -> Result<bool> {
-    let current_notes = current_timer
-        .notes
-        .as_ref()
-        .map(|s| s.as_str())
-        .unwrap_or("Unknown");
+    let current_notes = current_timer.notes.as_deref().unwrap_or("Unknown");
 
-    println!(
-        "\n{}",
-        style("⚠ Timer currently running:").yellow().bold()
-    );
-    println!("   {}", current_notes);
+    outln!("\n{}", style("⚠ Timer currently running:").yellow().bold());
+    outln!("   {}", current_notes);
+
+    if !current_timer.is_today() {
+        outln!("   Running since: {}", current_timer.spent_date);
+    }
 
     if let Some(hours) = current_timer.hours {
-        println!("   Duration: {:.2} hours", hours);
+        outln!("   Duration: {}", format_hours(hours));
     }
 
-    println!();
+    outln!();
 
     Confirm::with_theme(&ColorfulTheme::default())
         .with_prompt("Stop current timer to create new entry?")
@@ -505,18 +1073,182 @@ pub fn confirm_stop_timer_for_new(current_timer: &TimeEntry) -> Result<bool> {
         .map_err(|_| HarjiraError::UserCancelled)
 }
 
+/// Ask whether to delete a timer that was only running for a moment
+pub fn confirm_discard_short_entry(timer: &TimeEntry) -> Result<bool> {
+    outln!(
+        "\n{}",
+        style("⚠ This timer only ran for a moment:").yellow().bold()
+    );
+    outln!(
+        "   {}",
+        timer.notes.as_deref().unwrap_or("(no description)")
+    );
+    outln!("   Duration: {}", format_hours(timer.hours.unwrap_or(0.0)));
+    outln!();
+
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Delete the entry instead of stopping it?")
+        .default(true)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)
+}
+
+/// Ask whether to start a timer for uncommitted work on a ticket's branch
+pub fn confirm_start_from_wip(wip: &WorkInProgress, ticket: &str) -> Result<bool> {
+    outln!(
+        "\nNo commits today, but {} has uncommitted work:",
+        wip.repository
+    );
+    outln!(
+        "   {} changed in {} on {}",
+        count_label(wip.lines, "line", "lines"),
+        count_label(wip.files, "file", "files"),
+        wip.branch
+    );
+    outln!();
+
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Start a timer for {}?", ticket))
+        .default(true)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)
+}
+
+/// Ask whether to pick another default project and task, explaining why the current ones fail
+pub fn confirm_repick_target(reason: &str) -> Result<bool> {
+    outln!("\n{}", style(format!("⚠ {}", reason)).yellow().bold());
+    outln!("   Harvest doesn't take new time on it, so timers can't start.");
+    outln!();
+
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Pick another project and task and save them to your config?")
+        .default(true)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)
+}
+
+/// Ask whether to stop a timer that kept running through a restart at its last activity
+pub fn confirm_trim_orphaned_timer(
+    timer: &TimeEntry,
+    boot: DateTime<Local>,
+    last_activity: DateTime<Local>,
+    trimmed: f64,
+) -> Result<bool> {
+    outln!(
+        "\n{}",
+        style(format!(
+            "⚠ This timer kept running while the machine was off (restarted {}):",
+            boot.format("%a %H:%M")
+        ))
+        .yellow()
+        .bold()
+    );
+    outln!(
+        "   {}",
+        timer.notes.as_deref().unwrap_or("(no description)")
+    );
+    outln!("   Duration: {}", format_hours(timer.hours.unwrap_or(0.0)));
+    outln!(
+        "   Last activity: {} ({} up to then)",
+        last_activity.format("%a %H:%M"),
+        format_hours(trimmed)
+    );
+    outln!();
+
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Stop the timer at the last activity?")
+        .default(true)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)
+}
+
+/// Show a group of duplicate entries and ask whether to merge them
+pub fn confirm_merge_duplicates(group: &DuplicateGroup) -> Result<bool> {
+    let project_name = group
+        .keep
+        .project
+        .as_ref()
+        .map(|p| p.name.as_str())
+        .unwrap_or("Unknown Project");
+    let task_name = group
+        .keep
+        .task
+        .as_ref()
+        .map(|t| t.name.as_str())
+        .unwrap_or("Unknown Task");
+
+    outln!();
+    outln!(
+        "{}",
+        style(format!(
+            "{} • {} > {}",
+            group.keep.spent_date, project_name, task_name
+        ))
+        .cyan()
+        .bold()
+    );
+    for entry in group.entries() {
+        let marker = if entry.id == group.keep.id {
+            style("keep").green()
+        } else {
+            style("delete").red()
+        };
+        outln!(
+            "  {} - {} [{}]",
+            format_hours(entry.hours.unwrap_or(0.0)),
+            entry.notes.as_deref().unwrap_or("(no description)"),
+            marker
+        );
+    }
+
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Merge {} entries into one ({})?",
+            group.extras.len() + 1,
+            format_hours(group.total_hours())
+        ))
+        .default(true)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)
+}
+
+/// Show what undoing a batch of changes does and ask whether to go ahead
+pub fn confirm_undo(batch: &Batch) -> Result<bool> {
+    outln!();
+    outln!(
+        "{}",
+        style(format!(
+            "harv {} • {}",
+            batch.command,
+            batch.at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        ))
+        .cyan()
+        .bold()
+    );
+    for change in batch.changes.iter().rev() {
+        outln!("  {}", change.undo_label());
+    }
+
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Undo these changes?")
+        .default(false)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)
+}
+
 /// Prompt user to select a time entry from a list
-pub fn prompt_entry_selection(entries: &[TimeEntry]) -> Result<&TimeEntry> {
-    if entries.is_empty() {
+pub fn prompt_entry_selection(candidates: &[ContinueCandidate]) -> Result<&ContinueCandidate> {
+    if candidates.is_empty() {
         return Err(HarjiraError::Harvest(
             "No time entries available".to_string(),
         ));
     }
 
     // Build display items
-    let items: Vec<String> = entries
+    let items: Vec<String> = candidates
         .iter()
-        .map(|e| {
+        .map(|c| {
+            let e = &c.entry;
             let notes = e.notes.as_deref().unwrap_or("(no description)");
 
             let project_name = e
@@ -533,10 +1265,16 @@ pub fn prompt_entry_selection(entries: &[TimeEntry]) -> Result<&TimeEntry> {
 
             let hours_str = e
                 .hours
-                .map(|h| format!(" ({:.2}h)", h))
+                .map(|h| format!(" ({})", format_hours(h)))
                 .unwrap_or_default();
 
-            let date_str = if e.spent_date != chrono::Local::now().format("%Y-%m-%d").to_string() {
+            if let Some(name) = &c.favorite {
+                return format!("★ @{} • {} • {} > {}", name, notes, project_name, task_name);
+            }
+
+            let date_str = if c.days > 1 {
+                format!(" [{} days, last {}]", c.days, e.spent_date)
+            } else if e.spent_date != chrono::Local::now().format("%Y-%m-%d").to_string() {
                 format!(" [{}]", e.spent_date)
             } else {
                 String::new()
@@ -549,7 +1287,7 @@ pub fn prompt_entry_selection(entries: &[TimeEntry]) -> Result<&TimeEntry> {
         })
         .collect();
 
-    println!("\nSelect a time entry to continue:");
+    outln!("\nSelect a time entry to continue:");
 
     let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
         .with_prompt("Search and select entry")
@@ -558,7 +1296,7 @@ pub fn prompt_entry_selection(entries: &[TimeEntry]) -> Result<&TimeEntry> {
         .interact()
         .map_err(|_| HarjiraError::UserCancelled)?;
 
-    Ok(&entries[selection])
+    Ok(&candidates[selection])
 }
 
 /// Prompt user to choose between restarting existing entry or creating new timer
@@ -567,9 +1305,9 @@ pub fn prompt_continue_mode(entry: &TimeEntry) -> Result<ContinueMode> {
     let today = Local::now().format("%Y-%m-%d").to_string();
     let is_today = date_str == &today;
 
-    println!();
-    println!("{}", style("How would you like to continue?").cyan().bold());
-    println!("{}", style("=".repeat(60)).cyan());
+    outln!();
+    outln!("{}", style("How would you like to continue?").cyan().bold());
+    outln!("{}", style("=".repeat(60)).cyan());
 
     let options = vec![
         format!(
@@ -581,19 +1319,19 @@ pub fn prompt_continue_mode(entry: &TimeEntry) -> Result<ContinueMode> {
 
     // Add context message if entry is from a past date
     if !is_today {
-        println!(
+        outln!(
             "{}",
             style(format!("\n⚠  Note: Entry is from {} (not today)", date_str)).yellow()
         );
-        println!(
+        outln!(
             "  • Restart: Timer will run on {}",
             style(date_str).yellow().bold()
         );
-        println!(
+        outln!(
             "  • New timer: Creates separate entry for {}",
             style(&today).green().bold()
         );
-        println!();
+        outln!();
     }
 
     let selection = Select::with_theme(&ColorfulTheme::default())
@@ -0,0 +1,220 @@
+use crate::models::TimeEntry;
+use crate::ticket_parser;
+
+/// Label used for entries whose notes contain no ticket key
+pub const NO_TICKET: &str = "(no ticket)";
+
+/// Time logged against a single ticket within a project
+#[derive(Debug, Clone, PartialEq)]
+pub struct TicketTotal {
+    pub ticket: String,
+    pub hours: f64,
+    pub entry_count: usize,
+    pub is_running: bool,
+}
+
+/// Time logged against a project, broken down by ticket
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectGroup {
+    pub project: String,
+    pub hours: f64,
+    pub tickets: Vec<TicketTotal>,
+}
+
+/// Group entries by project, then by the first ticket key found in their notes
+///
+/// Projects and tickets are ordered by hours (largest first).
+pub fn group_by_project_and_ticket(
+    entries: &[TimeEntry],
+    denylist: &[String],
+) -> Vec<ProjectGroup> {
+    let mut groups: Vec<ProjectGroup> = Vec::new();
+
+    for entry in entries {
+        let project_name = entry
+            .project
+            .as_ref()
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| "Unknown Project".to_string());
+
+        let ticket = entry
+            .notes
+            .as_ref()
+            .and_then(|notes| {
+                ticket_parser::extract_tickets(std::slice::from_ref(notes), denylist)
+                    .into_iter()
+                    .next()
+            })
+            .unwrap_or_else(|| NO_TICKET.to_string());
+
+        let hours = entry.hours.unwrap_or(0.0);
+
+        let group = match groups.iter().position(|g| g.project == project_name) {
+            Some(idx) => &mut groups[idx],
+            None => {
+                groups.push(ProjectGroup {
+                    project: project_name,
+                    hours: 0.0,
+                    tickets: Vec::new(),
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+
+        group.hours += hours;
+        match group.tickets.iter_mut().find(|t| t.ticket == ticket) {
+            Some(total) => {
+                total.hours += hours;
+                total.entry_count += 1;
+                total.is_running |= entry.is_running;
+            }
+            None => group.tickets.push(TicketTotal {
+                ticket,
+                hours,
+                entry_count: 1,
+                is_running: entry.is_running,
+            }),
+        }
+    }
+
+    for group in &mut groups {
+        group
+            .tickets
+            .sort_by(|a, b| b.hours.total_cmp(&a.hours).then(a.ticket.cmp(&b.ticket)));
+    }
+    groups.sort_by(|a, b| b.hours.total_cmp(&a.hours).then(a.project.cmp(&b.project)));
+
+    groups
+}
+
+/// Percentage of the target reached, 0 when no target is set
+pub fn percent_of_target(hours: f64, target: f64) -> f64 {
+    if target <= 0.0 {
+        0.0
+    } else {
+        hours / target * 100.0
+    }
+}
+
+/// Billable and non-billable hours of `entries`
+pub fn billable_split(entries: &[TimeEntry]) -> (f64, f64) {
+    entries.iter().fold((0.0, 0.0), |(billable, other), entry| {
+        let hours = entry.hours.unwrap_or(0.0);
+        if entry.billable {
+            (billable + hours, other)
+        } else {
+            (billable, other + hours)
+        }
+    })
+}
+
+/// What the billable entries are worth at their rates
+///
+/// None when no entry has a rate, i.e. the token can't see them.
+pub fn billable_amount<'a>(entries: impl IntoIterator<Item = &'a TimeEntry>) -> Option<f64> {
+    let mut amount = None;
+    for entry in entries {
+        if let (true, Some(rate)) = (entry.billable, entry.billable_rate) {
+            *amount.get_or_insert(0.0) += entry.hours.unwrap_or(0.0) * rate;
+        }
+    }
+    amount.map(|amount: f64| (amount * 100.0).round() / 100.0)
+}
+
+/// Render a fixed-width ASCII bar for `value` relative to `max`
+pub fn render_bar(value: f64, max: f64, width: usize) -> String {
+    let filled = if max <= 0.0 {
+        0
+    } else {
+        ((value / max) * width as f64)
+            .round()
+            .clamp(0.0, width as f64) as usize
+    };
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProjectInfo;
+
+    fn entry(project: &str, notes: &str, hours: f64) -> TimeEntry {
+        TimeEntry {
+            id: 1,
+            spent_date: "2025-01-10".to_string(),
+            hours: Some(hours),
+            notes: Some(notes.to_string()),
+            project: Some(ProjectInfo {
+                id: 1,
+                name: project.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_group_by_project_and_ticket() {
+        let entries = vec![
+            entry("Backend", "PROJ-1 - Login", 1.0),
+            entry("Backend", "PROJ-2 - Logout", 2.0),
+            entry("Backend", "proj-1 follow-up", 0.5),
+            entry("Internal", "Standup", 0.25),
+        ];
+
+        let groups = group_by_project_and_ticket(&entries, &[]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].project, "Backend");
+        assert_eq!(groups[0].hours, 3.5);
+        assert_eq!(groups[0].tickets[0].ticket, "PROJ-2");
+        assert_eq!(groups[0].tickets[1].ticket, "PROJ-1");
+        assert_eq!(groups[0].tickets[1].hours, 1.5);
+        assert_eq!(groups[0].tickets[1].entry_count, 2);
+        assert_eq!(groups[1].tickets[0].ticket, NO_TICKET);
+    }
+
+    #[test]
+    fn test_group_respects_denylist() {
+        let entries = vec![entry("Security", "Fix CWE-79", 1.0)];
+        let groups = group_by_project_and_ticket(&entries, &["CWE".to_string()]);
+        assert_eq!(groups[0].tickets[0].ticket, NO_TICKET);
+    }
+
+    #[test]
+    fn test_percent_of_target() {
+        assert_eq!(percent_of_target(4.0, 8.0), 50.0);
+        assert_eq!(percent_of_target(4.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_billable_split() {
+        let mut billable = entry("Backend", "PROJ-1 - Login", 3.0);
+        billable.billable = true;
+        let entries = [billable, entry("Internal", "Standup", 0.5)];
+        assert_eq!(billable_split(&entries), (3.0, 0.5));
+    }
+
+    #[test]
+    fn test_billable_amount() {
+        let rated = |hours: f64, rate: f64| {
+            let mut entry = entry("Backend", "PROJ-1 - Login", hours);
+            entry.billable = true;
+            entry.billable_rate = Some(rate);
+            entry
+        };
+        let entries = [
+            rated(1.5, 90.0),
+            rated(0.5, 60.0),
+            entry("Internal", "Standup", 1.0),
+        ];
+        assert_eq!(billable_amount(&entries), Some(165.0));
+        assert_eq!(billable_amount(&entries[2..]), None);
+    }
+
+    #[test]
+    fn test_render_bar() {
+        assert_eq!(render_bar(5.0, 10.0, 4), "██░░");
+        assert_eq!(render_bar(20.0, 10.0, 4), "████");
+        assert_eq!(render_bar(1.0, 0.0, 3), "░░░");
+    }
+}
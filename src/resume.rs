@@ -0,0 +1,134 @@
+//! Timers left running across a restart
+//!
+//! When the machine goes down with a timer running, the timer keeps counting
+//! in the backend. The first command after the restart compares the timer's
+//! start with the boot time, and if the timer is older offers to trim it back
+//! to the last sign of work before the restart: a commit or a harv change.
+
+use crate::error::{HarjiraError, Result};
+use chrono::{DateTime, Local, TimeZone};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const RESUME_FILE_VERSION: u8 = 1;
+
+/// The boot the running timer was last checked against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumeState {
+    version: u8,
+    #[serde(default)]
+    pub checked_boot: Option<DateTime<Local>>,
+}
+
+impl Default for ResumeState {
+    fn default() -> Self {
+        Self {
+            version: RESUME_FILE_VERSION,
+            checked_boot: None,
+        }
+    }
+}
+
+impl ResumeState {
+    /// Load the state, the default if the file doesn't exist
+    pub fn load() -> Result<Self> {
+        let path = resume_file_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let state: ResumeState = serde_json::from_str(&contents).map_err(|e| {
+            HarjiraError::Config(format!(
+                "Failed to parse resume state {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        if state.version > RESUME_FILE_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "Resume state version {} is newer than supported version {}",
+                state.version, RESUME_FILE_VERSION
+            )));
+        }
+
+        Ok(state)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = resume_file_path()?;
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(self)?)?;
+
+        debug!("Saved resume state to {}", path.display());
+        Ok(())
+    }
+}
+
+/// When the machine last booted, None where that can't be found out
+pub fn boot_time() -> Option<DateTime<Local>> {
+    let seconds = boot_seconds()?;
+    // Boot times wobble by a second between reads, so round to the minute
+    let seconds = seconds - seconds % 60;
+    Local.timestamp_opt(seconds, 0).single()
+}
+
+#[cfg(target_os = "linux")]
+fn boot_seconds() -> Option<i64> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|seconds| seconds.trim().parse().ok())
+}
+
+#[cfg(target_os = "macos")]
+fn boot_seconds() -> Option<i64> {
+    // "{ sec = 1760428800, usec = 0 } Tue Oct 14 10:00:00 2026"
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "kern.boottime"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let seconds = text.split("sec = ").nth(1)?.split(',').next()?;
+    seconds.trim().parse().ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn boot_seconds() -> Option<i64> {
+    None
+}
+
+/// The latest of `times` after `started` and before `boot`
+pub fn last_activity(
+    times: impl IntoIterator<Item = DateTime<Local>>,
+    started: DateTime<Local>,
+    boot: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    times
+        .into_iter()
+        .filter(|time| *time > started && *time <= boot)
+        .max()
+}
+
+fn resume_file_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("resume.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_activity_before_boot() {
+        let at = |hour: u32| Local.with_ymd_and_hms(2026, 10, 14, hour, 0, 0).unwrap();
+        let times = [at(8), at(11), at(13), at(16)];
+
+        // Timer started at 9, machine rebooted at 14
+        assert_eq!(last_activity(times, at(9), at(14)), Some(at(13)));
+        assert_eq!(last_activity(times, at(14), at(15)), None);
+    }
+}
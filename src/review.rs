@@ -0,0 +1,507 @@
+use crate::error::{HarjiraError, Result};
+use crate::models::{Commit, EntryPlan, HarvestProject, HarvestTask, ProposedTimeEntry};
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::fmt;
+
+/// Projects and their assigned tasks, as fetched from Harvest
+#[derive(Debug, Clone, Copy)]
+pub struct Catalog<'a> {
+    pub projects: &'a [HarvestProject],
+    /// (project_id, task) pairs for every task assignment
+    pub assignments: &'a [(u64, HarvestTask)],
+}
+
+impl<'a> Catalog<'a> {
+    pub fn new(projects: &'a [HarvestProject], assignments: &'a [(u64, HarvestTask)]) -> Self {
+        Self {
+            projects,
+            assignments,
+        }
+    }
+
+    pub fn project(&self, project_id: u64) -> Option<&'a HarvestProject> {
+        self.projects.iter().find(|p| p.id == project_id)
+    }
+
+    /// Tasks assigned to a project
+    pub fn tasks_for(&self, project_id: u64) -> Vec<HarvestTask> {
+        self.assignments
+            .iter()
+            .filter(|(pid, _)| *pid == project_id)
+            .map(|(_, task)| task.clone())
+            .collect()
+    }
+
+    /// Name of a task by id, from any project
+    pub fn task_name(&self, task_id: u64) -> Option<&'a str> {
+        self.assignments
+            .iter()
+            .find(|(_, t)| t.id == task_id)
+            .map(|(_, t)| t.name.as_str())
+    }
+
+    /// Why an entry can't be created as-is, if it can't
+    pub fn check(&self, entry: &ProposedTimeEntry) -> Option<EntryProblem> {
+        let Some(project) = self.project(entry.project_id) else {
+            return Some(EntryProblem::UnknownProject(entry.project_id));
+        };
+
+        if self
+            .assignments
+            .iter()
+            .any(|(pid, t)| *pid == entry.project_id && t.id == entry.task_id)
+        {
+            return None;
+        }
+
+        match self.task_name(entry.task_id) {
+            Some(task) => Some(EntryProblem::TaskNotAssigned {
+                task: task.to_string(),
+                project: project.name.clone(),
+            }),
+            None => Some(EntryProblem::UnknownTask(entry.task_id)),
+        }
+    }
+}
+
+/// Split an entry in two, giving `first_hours` to the first part
+///
+/// Both parts keep the original project, task and description. Returns
+/// None unless `first_hours` leaves hours for both parts.
+pub fn split_entry(
+    entry: &ProposedTimeEntry,
+    first_hours: f64,
+) -> Option<(ProposedTimeEntry, ProposedTimeEntry)> {
+    if first_hours <= 0.0 || first_hours >= entry.hours {
+        return None;
+    }
+
+    let mut first = entry.clone();
+    first.hours = first_hours;
+    let mut second = entry.clone();
+    second.hours = entry.hours - first_hours;
+    Some((first, second))
+}
+
+/// Whether an entry is below `ai.auto_approve_confidence` and must be reviewed
+///
+/// Entries without a score count as unsure. Without a threshold nothing is.
+pub fn below_confidence(entry: &ProposedTimeEntry, threshold: Option<f64>) -> bool {
+    threshold.is_some_and(|t| entry.confidence_score.is_none_or(|c| c < t))
+}
+
+/// Order to show entries in for review: unsure ones first, least confident first
+pub fn review_order(entries: &[ProposedTimeEntry], threshold: Option<f64>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by(|&a, &b| {
+        let key = |i: usize| {
+            let entry = &entries[i];
+            (
+                !below_confidence(entry, threshold),
+                entry.confidence_score.unwrap_or(0.0),
+            )
+        };
+        let (confident_a, score_a) = key(a);
+        let (confident_b, score_b) = key(b);
+        confident_a.cmp(&confident_b).then(if confident_a {
+            // Confident entries keep the AI's order
+            std::cmp::Ordering::Equal
+        } else {
+            score_a.total_cmp(&score_b)
+        })
+    });
+    order
+}
+
+/// How to bring the reviewed total in line with the day's target
+#[derive(Debug, Clone)]
+pub struct RebalanceOptions {
+    /// Hours still to log today
+    pub target_hours: f64,
+    /// Round scaled hours to this many minutes
+    pub rounding_minutes: Option<u32>,
+    /// Where to book the difference when the total is short
+    pub filler: Option<Filler>,
+}
+
+/// Project and task used for filler entries
+#[derive(Debug, Clone)]
+pub struct Filler {
+    pub project_id: u64,
+    pub task_id: u64,
+    pub notes: String,
+}
+
+/// Round hours to the nearest multiple of `minutes`
+pub fn round_hours(hours: f64, minutes: Option<u32>) -> f64 {
+    match minutes {
+        Some(m) if m > 0 => {
+            let increment = m as f64 / 60.0;
+            (hours / increment).round() * increment
+        }
+        _ => hours,
+    }
+}
+
+/// Scale entries proportionally so their hours add up to `target`
+///
+/// Each entry is rounded to the increment and kept at least one increment
+/// long. Any rounding difference goes to the largest entry, so the total
+/// always equals `target`. Returns false (leaving entries untouched) when
+/// there is nothing to scale.
+pub fn scale_to_target(
+    entries: &mut [ProposedTimeEntry],
+    target: f64,
+    rounding_minutes: Option<u32>,
+) -> bool {
+    let total: f64 = entries.iter().map(|e| e.hours).sum();
+    if total <= 0.0 || target <= 0.0 {
+        return false;
+    }
+
+    let minimum = round_hours(f64::MIN_POSITIVE, rounding_minutes).max(0.01);
+    let factor = target / total;
+    for entry in entries.iter_mut() {
+        entry.hours = round_hours(entry.hours * factor, rounding_minutes).max(minimum);
+    }
+
+    let scaled: f64 = entries.iter().map(|e| e.hours).sum();
+    let largest = (0..entries.len()).fold(0, |best, i| {
+        if entries[i].hours > entries[best].hours {
+            i
+        } else {
+            best
+        }
+    });
+    entries[largest].hours += target - scaled;
+    true
+}
+
+/// Entry that books the hours missing to reach `target`, if any
+pub fn filler_entry(
+    entries: &[ProposedTimeEntry],
+    target: f64,
+    filler: &Filler,
+) -> Option<ProposedTimeEntry> {
+    let total: f64 = entries.iter().map(|e| e.hours).sum();
+    let missing = target - total;
+    if missing < 0.01 {
+        return None;
+    }
+
+    Some(ProposedTimeEntry {
+        description: filler.notes.clone(),
+        project_id: filler.project_id,
+        task_id: filler.task_id,
+        hours: missing,
+        confidence_score: None,
+    })
+}
+
+/// Working days from Monday of `today`'s week up to and including `today`
+pub fn week_to_date(today: NaiveDate) -> Vec<NaiveDate> {
+    let monday = today.week(Weekday::Mon).first_day();
+    monday
+        .iter_days()
+        .take_while(|day| *day <= today)
+        .filter(|day| !matches!(day.weekday(), Weekday::Sat | Weekday::Sun))
+        .collect()
+}
+
+/// Work summary for one backfilled day, None when there is nothing to go on
+///
+/// Lists the first line of each commit, followed by the user's own summary
+/// for the week if one was given.
+pub fn day_summary(commits: &[Commit], week_summary: Option<&str>) -> Option<String> {
+    let week_summary = week_summary.map(str::trim).filter(|s| !s.is_empty());
+    if commits.is_empty() && week_summary.is_none() {
+        return None;
+    }
+
+    let mut summary = String::new();
+    if !commits.is_empty() {
+        summary.push_str("Commits made that day:\n");
+        for commit in commits {
+            summary.push_str(&format!(
+                "- {}\n",
+                commit.message.lines().next().unwrap_or("").trim()
+            ));
+        }
+    }
+    if let Some(notes) = week_summary {
+        if !summary.is_empty() {
+            summary.push('\n');
+        }
+        summary.push_str("Notes for the week:\n");
+        summary.push_str(notes);
+        summary.push('\n');
+    }
+    Some(summary)
+}
+
+/// Parse a plan written by `generate --dry-run --output json`, possibly edited since
+pub fn parse_plan(json: &str) -> Result<EntryPlan> {
+    let plan: EntryPlan = serde_json::from_str(json)
+        .map_err(|e| HarjiraError::Config(format!("Invalid plan file: {}", e)))?;
+
+    for day in &plan.days {
+        NaiveDate::parse_from_str(&day.date, "%Y-%m-%d").map_err(|_| {
+            HarjiraError::Config(format!(
+                "Invalid date in plan file: '{}'. Must be YYYY-MM-DD",
+                day.date
+            ))
+        })?;
+        if let Some(entry) = day.entries.iter().find(|e| e.hours <= 0.0) {
+            return Err(HarjiraError::Config(format!(
+                "Entry '{}' on {} in plan file needs more than 0 hours",
+                entry.description, day.date
+            )));
+        }
+    }
+    Ok(plan)
+}
+
+/// Reason a proposed entry would be rejected by Harvest
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntryProblem {
+    UnknownProject(u64),
+    UnknownTask(u64),
+    TaskNotAssigned { task: String, project: String },
+}
+
+impl fmt::Display for EntryProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryProblem::UnknownProject(id) => write!(f, "unknown project {}", id),
+            EntryProblem::UnknownTask(id) => write!(f, "unknown task {}", id),
+            EntryProblem::TaskNotAssigned { task, project } => {
+                write!(f, "task '{}' is not assigned to '{}'", task, project)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: u64, name: &str) -> HarvestProject {
+        HarvestProject {
+            id,
+            name: name.to_string(),
+            code: None,
+            client: None,
+        }
+    }
+
+    fn task(id: u64, name: &str) -> HarvestTask {
+        HarvestTask {
+            id,
+            name: name.to_string(),
+            billable: None,
+        }
+    }
+
+    fn proposed(project_id: u64, task_id: u64) -> ProposedTimeEntry {
+        ProposedTimeEntry {
+            description: "Work".to_string(),
+            project_id,
+            task_id,
+            hours: 1.0,
+            confidence_score: None,
+        }
+    }
+
+    #[test]
+    fn test_check_entry() {
+        let projects = vec![project(1, "Backend"), project(2, "Internal")];
+        let assignments = vec![(1, task(10, "Development")), (2, task(20, "Meetings"))];
+        let catalog = Catalog::new(&projects, &assignments);
+
+        assert_eq!(catalog.check(&proposed(1, 10)), None);
+        assert_eq!(
+            catalog.check(&proposed(3, 10)),
+            Some(EntryProblem::UnknownProject(3))
+        );
+        assert_eq!(
+            catalog.check(&proposed(1, 99)),
+            Some(EntryProblem::UnknownTask(99))
+        );
+        assert_eq!(
+            catalog.check(&proposed(1, 20)),
+            Some(EntryProblem::TaskNotAssigned {
+                task: "Meetings".to_string(),
+                project: "Backend".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_review_order_puts_unsure_entries_first() {
+        let scored = |score: Option<f64>| ProposedTimeEntry {
+            confidence_score: score,
+            ..proposed(1, 10)
+        };
+        let entries = vec![
+            scored(Some(0.95)),
+            scored(Some(0.6)),
+            scored(None),
+            scored(Some(0.9)),
+            scored(Some(0.8)),
+        ];
+
+        assert!(below_confidence(&entries[1], Some(0.9)));
+        assert!(below_confidence(&entries[2], Some(0.9)));
+        assert!(!below_confidence(&entries[3], Some(0.9)));
+        assert!(!below_confidence(&entries[2], None));
+
+        assert_eq!(review_order(&entries, Some(0.9)), vec![2, 1, 4, 0, 3]);
+        assert_eq!(review_order(&entries, None), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_parse_plan() {
+        let plan = parse_plan(
+            r#"{"days": [{"date": "2026-10-12", "entries": [
+                {"description": "Work", "project_id": 1, "task_id": 10, "hours": 2.5, "confidence": 0.8},
+                {"description": "Standup", "project_id": 1, "task_id": 10, "hours": 0.25}
+            ]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(plan.days[0].date, "2026-10-12");
+        assert_eq!(plan.days[0].entries[0].confidence_score, Some(0.8));
+        assert_eq!(plan.days[0].entries[1].confidence_score, None);
+
+        let written = serde_json::to_string(&plan).unwrap();
+        assert_eq!(parse_plan(&written).unwrap(), plan);
+
+        assert!(parse_plan(r#"{"days": [{"date": "12-10-2026", "entries": []}]}"#).is_err());
+        assert!(parse_plan(
+            r#"{"days": [{"date": "2026-10-12", "entries": [
+                {"description": "Work", "project_id": 1, "task_id": 10, "hours": 0}
+            ]}]}"#
+        )
+        .is_err());
+        assert!(parse_plan("[]").is_err());
+    }
+
+    #[test]
+    fn test_split_entry() {
+        let mut entry = proposed(1, 10);
+        entry.hours = 3.0;
+
+        let (first, second) = split_entry(&entry, 1.25).unwrap();
+        assert_eq!(first.hours, 1.25);
+        assert_eq!(second.hours, 1.75);
+        assert_eq!(second.project_id, 1);
+
+        assert!(split_entry(&entry, 0.0).is_none());
+        assert!(split_entry(&entry, 3.0).is_none());
+    }
+
+    fn with_hours(hours: &[f64]) -> Vec<ProposedTimeEntry> {
+        hours
+            .iter()
+            .map(|&h| {
+                let mut entry = proposed(1, 10);
+                entry.hours = h;
+                entry
+            })
+            .collect()
+    }
+
+    fn total(entries: &[ProposedTimeEntry]) -> f64 {
+        entries.iter().map(|e| e.hours).sum()
+    }
+
+    #[test]
+    fn test_round_hours() {
+        assert_eq!(round_hours(1.33, Some(15)), 1.25);
+        assert_eq!(round_hours(1.40, Some(15)), 1.5);
+        assert_eq!(round_hours(1.33, None), 1.33);
+    }
+
+    #[test]
+    fn test_scale_to_target_with_rounding() {
+        let mut entries = with_hours(&[1.0, 2.0]);
+        assert!(scale_to_target(&mut entries, 4.0, Some(15)));
+
+        assert_eq!(entries[0].hours, 1.25);
+        assert_eq!(entries[1].hours, 2.75);
+        assert!((total(&entries) - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scale_to_target_puts_drift_on_largest() {
+        let mut entries = with_hours(&[1.0, 1.0, 1.0]);
+        assert!(scale_to_target(&mut entries, 4.0, Some(60)));
+        assert_eq!(
+            entries.iter().map(|e| e.hours).collect::<Vec<_>>(),
+            vec![2.0, 1.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_scale_to_target_nothing_to_scale() {
+        let mut entries = with_hours(&[0.0]);
+        assert!(!scale_to_target(&mut entries, 4.0, None));
+        assert!(!scale_to_target(&mut [], 4.0, None));
+    }
+
+    #[test]
+    fn test_filler_entry() {
+        let filler = Filler {
+            project_id: 2,
+            task_id: 20,
+            notes: "General".to_string(),
+        };
+
+        let entry = filler_entry(&with_hours(&[2.5]), 4.0, &filler).unwrap();
+        assert_eq!(entry.hours, 1.5);
+        assert_eq!(entry.project_id, 2);
+        assert!(filler_entry(&with_hours(&[4.0]), 4.0, &filler).is_none());
+    }
+
+    #[test]
+    fn test_week_to_date() {
+        // Wednesday
+        let today = NaiveDate::from_ymd_opt(2025, 1, 8).unwrap();
+        let days = week_to_date(today);
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0], NaiveDate::from_ymd_opt(2025, 1, 6).unwrap());
+        assert_eq!(days[2], today);
+
+        // Weekends are left out
+        let sunday = NaiveDate::from_ymd_opt(2025, 1, 12).unwrap();
+        assert_eq!(week_to_date(sunday).len(), 5);
+    }
+
+    #[test]
+    fn test_day_summary() {
+        let commits = vec![Commit {
+            message: "PROJ-1 fix login\n\nDetails".to_string(),
+            author: "dev".to_string(),
+            timestamp: 0,
+            repository: String::new(),
+        }];
+
+        let summary = day_summary(&commits, Some("Sprint planning on Monday")).unwrap();
+        assert!(summary.contains("- PROJ-1 fix login\n"));
+        assert!(!summary.contains("Details"));
+        assert!(summary.contains("Sprint planning on Monday"));
+
+        assert!(day_summary(&[], Some("  ")).is_none());
+        assert!(day_summary(&[], None).is_none());
+    }
+
+    #[test]
+    fn test_tasks_for() {
+        let projects = vec![project(1, "Backend")];
+        let assignments = vec![(1, task(10, "Development")), (2, task(20, "Meetings"))];
+        let catalog = Catalog::new(&projects, &assignments);
+
+        let tasks = catalog.tasks_for(1);
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name, "Development");
+    }
+}
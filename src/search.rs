@@ -0,0 +1,306 @@
+use crate::models::{ContinueCandidate, HarvestProject, TimeEntry};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::collections::HashSet;
+
+/// Filter entries by fuzzy-matching `query` against notes, project and task names
+///
+/// Matches are returned best first; entries that don't match are dropped.
+pub fn filter_entries(entries: Vec<TimeEntry>, query: &str) -> Vec<TimeEntry> {
+    let query = query.trim();
+    if query.is_empty() {
+        return entries;
+    }
+
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut scored: Vec<(i64, TimeEntry)> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let score = searchable_fields(&entry)
+                .iter()
+                .filter_map(|field| matcher.fuzzy_match(field, query))
+                .max()?;
+            Some((score, entry))
+        })
+        .collect();
+
+    // Stable sort keeps the original (most recent first) order for equal scores
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Collapse entries with identical project, task and notes into one candidate
+///
+/// Each candidate keeps the most recent entry and counts the distinct days it
+/// appeared on. Candidates stay in order of first appearance.
+pub fn collapse_duplicates(entries: Vec<TimeEntry>) -> Vec<ContinueCandidate> {
+    let mut candidates: Vec<(ContinueCandidate, HashSet<String>)> = Vec::new();
+
+    for entry in entries {
+        let existing = candidates
+            .iter_mut()
+            .find(|(c, _)| same_work(&c.entry, &entry));
+
+        match existing {
+            Some((candidate, dates)) => {
+                dates.insert(entry.spent_date.clone());
+                candidate.days = dates.len();
+                if entry.spent_date > candidate.entry.spent_date {
+                    candidate.entry = entry;
+                }
+            }
+            None => {
+                let dates = HashSet::from([entry.spent_date.clone()]);
+                candidates.push((
+                    ContinueCandidate {
+                        entry,
+                        days: 1,
+                        favorite: None,
+                    },
+                    dates,
+                ));
+            }
+        }
+    }
+
+    candidates.into_iter().map(|(c, _)| c).collect()
+}
+
+/// Result of looking up a name typed on the command line
+#[derive(Debug)]
+pub enum NameMatch<'a, T> {
+    Found(&'a T),
+    /// Best match first
+    Ambiguous(Vec<&'a T>),
+    NotFound,
+}
+
+/// Find the item `query` names, by any of the names `names` gives for it
+///
+/// A name equal to `query` (ignoring case) wins. Otherwise `query` is fuzzy
+/// matched, which is only a match when a single item fits.
+pub fn match_name<'a, T>(
+    items: &'a [T],
+    query: &str,
+    names: impl Fn(&T) -> Vec<String>,
+) -> NameMatch<'a, T> {
+    let query = query.trim();
+    let exact: Vec<&T> = items
+        .iter()
+        .filter(|item| names(item).iter().any(|n| n.eq_ignore_ascii_case(query)))
+        .collect();
+    if !exact.is_empty() {
+        return one_or_ambiguous(exact);
+    }
+
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut scored: Vec<(i64, &T)> = items
+        .iter()
+        .filter_map(|item| {
+            let score = names(item)
+                .iter()
+                .filter_map(|n| matcher.fuzzy_match(n, query))
+                .max()?;
+            Some((score, item))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    one_or_ambiguous(scored.into_iter().map(|(_, item)| item).collect())
+}
+
+fn one_or_ambiguous<T>(mut items: Vec<&T>) -> NameMatch<'_, T> {
+    match items.len() {
+        0 => NameMatch::NotFound,
+        1 => NameMatch::Found(items.remove(0)),
+        _ => NameMatch::Ambiguous(items),
+    }
+}
+
+/// Keep projects whose client name contains `client` (case-insensitive)
+pub fn filter_projects_by_client(
+    projects: Vec<HarvestProject>,
+    client: &str,
+) -> Vec<HarvestProject> {
+    let client = client.trim().to_lowercase();
+    projects
+        .into_iter()
+        .filter(|p| {
+            p.client
+                .as_ref()
+                .is_some_and(|c| c.name.to_lowercase().contains(&client))
+        })
+        .collect()
+}
+
+fn same_work(a: &TimeEntry, b: &TimeEntry) -> bool {
+    a.project.as_ref().map(|p| p.id) == b.project.as_ref().map(|p| p.id)
+        && a.task.as_ref().map(|t| t.id) == b.task.as_ref().map(|t| t.id)
+        && a.notes.as_deref().map(str::trim) == b.notes.as_deref().map(str::trim)
+}
+
+fn searchable_fields(entry: &TimeEntry) -> Vec<&str> {
+    let mut fields = Vec::new();
+    if let Some(notes) = &entry.notes {
+        fields.push(notes.as_str());
+    }
+    if let Some(project) = &entry.project {
+        fields.push(project.name.as_str());
+    }
+    if let Some(task) = &entry.task {
+        fields.push(task.name.as_str());
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ProjectInfo, TaskInfo};
+
+    fn entry(id: u64, notes: &str, project: &str, task: &str) -> TimeEntry {
+        TimeEntry {
+            id,
+            spent_date: "2025-01-10".to_string(),
+            hours: Some(1.0),
+            notes: Some(notes.to_string()),
+            project: Some(ProjectInfo {
+                id: 1,
+                name: project.to_string(),
+            }),
+            task: Some(TaskInfo {
+                id: 1,
+                name: task.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn entries() -> Vec<TimeEntry> {
+        vec![
+            entry(1, "PROJ-1 - Login page", "Backend", "Programming"),
+            entry(2, "Daily standup", "Internal", "Meetings"),
+            entry(3, "PROJ-2 - Review", "Backend", "Code Review"),
+        ]
+    }
+
+    #[test]
+    fn test_filter_by_notes() {
+        let matches = filter_entries(entries(), "standup");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].id, 2);
+    }
+
+    #[test]
+    fn test_filter_is_fuzzy_and_case_insensitive() {
+        let matches = filter_entries(entries(), "stndp");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].id, 2);
+    }
+
+    #[test]
+    fn test_filter_by_project_and_task() {
+        let ids: Vec<u64> = filter_entries(entries(), "backend")
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec![1, 3]);
+
+        let matches = filter_entries(entries(), "meetings");
+        assert_eq!(matches[0].id, 2);
+    }
+
+    #[test]
+    fn test_no_match() {
+        assert!(filter_entries(entries(), "xyzzy").is_empty());
+    }
+
+    #[test]
+    fn test_collapse_duplicates() {
+        let mut standup_monday = entry(10, "Daily standup", "Internal", "Meetings");
+        standup_monday.spent_date = "2025-01-06".to_string();
+        let mut standup_tuesday = entry(11, "Daily standup", "Internal", "Meetings");
+        standup_tuesday.spent_date = "2025-01-07".to_string();
+        let mut standup_twice = entry(12, "Daily standup", "Internal", "Meetings");
+        standup_twice.spent_date = "2025-01-07".to_string();
+
+        let candidates = collapse_duplicates(vec![
+            standup_monday,
+            entry(1, "PROJ-1 - Login page", "Backend", "Programming"),
+            standup_tuesday,
+            standup_twice,
+        ]);
+
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].entry.id, 11);
+        assert_eq!(candidates[0].entry.spent_date, "2025-01-07");
+        assert_eq!(candidates[0].days, 2);
+        assert_eq!(candidates[1].days, 1);
+    }
+
+    #[test]
+    fn test_collapse_keeps_different_tasks_apart() {
+        let mut review = entry(2, "PROJ-1 - Login page", "Backend", "Code Review");
+        review.task.as_mut().unwrap().id = 2;
+
+        let candidates = collapse_duplicates(vec![
+            entry(1, "PROJ-1 - Login page", "Backend", "Programming"),
+            review,
+        ]);
+        assert_eq!(candidates.len(), 2);
+    }
+
+    #[test]
+    fn test_filter_projects_by_client() {
+        use crate::models::ClientInfo;
+
+        let project = |id: u64, client: Option<&str>| HarvestProject {
+            id,
+            name: "Website".to_string(),
+            code: None,
+            client: client.map(|name| ClientInfo {
+                id,
+                name: name.to_string(),
+            }),
+        };
+        let projects = vec![
+            project(1, Some("ACME Corp")),
+            project(2, Some("Globex")),
+            project(3, None),
+        ];
+
+        let ids: Vec<u64> = filter_projects_by_client(projects, "acme")
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[test]
+    fn test_empty_query_keeps_all() {
+        assert_eq!(filter_entries(entries(), "  ").len(), 3);
+    }
+
+    #[test]
+    fn test_match_name_prefers_exact_and_lists_ambiguous() {
+        let names = ["Platform", "Platform Maintenance", "Website Redesign"];
+        let by_name = |n: &&str| vec![n.to_string()];
+
+        // Equal names win over longer ones that also match
+        assert!(matches!(
+            match_name(&names, "platform", by_name),
+            NameMatch::Found(&"Platform")
+        ));
+        assert!(matches!(
+            match_name(&names, "website", by_name),
+            NameMatch::Found(&"Website Redesign")
+        ));
+        match match_name(&names, "plat", by_name) {
+            NameMatch::Ambiguous(found) => assert_eq!(found.len(), 2),
+            other => panic!("expected ambiguous, got {:?}", other),
+        }
+        assert!(matches!(
+            match_name(&names, "billing", by_name),
+            NameMatch::NotFound
+        ));
+    }
+}
@@ -0,0 +1,435 @@
+//! Tokens kept out of the config file in plain text
+//!
+//! A token can come from a command, e.g. `access_token_cmd = "pass show
+//! work/harvest"`, run each time the config is loaded.
+//!
+//! `harv config encrypt` moves the tokens out of their sections into one TOML
+//! document, which gpg or age encrypts into `[secrets] encrypted`. Loading the
+//! config runs the same tool to decrypt it, so gpg-agent or a passphrase prompt
+//! takes care of unlocking, and puts the tokens back in their sections.
+
+use crate::error::{HarjiraError, Result};
+use crate::logging::expand_home;
+use log::debug;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+/// Supported encryption tools
+pub const TOOLS: &[&str] = &["gpg", "age"];
+
+/// Config fields holding a token, as (section, key)
+pub const SECRET_FIELDS: &[(&str, &str)] = &[
+    ("harvest", "access_token"),
+    ("jira", "access_token"),
+    ("tempo", "access_token"),
+    ("toggl", "api_token"),
+    ("linear", "api_key"),
+    ("azure_devops", "access_token"),
+    ("github", "access_token"),
+    ("ai", "api_key"),
+];
+
+/// Decrypted tokens, so a run asks for the passphrase only once
+static DECRYPTED: OnceLock<(String, toml::Table)> = OnceLock::new();
+
+/// Tokens printed by `*_cmd` commands, so each command runs once per run
+static COMMAND_TOKENS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// How `[secrets]` says to encrypt and decrypt
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cipher {
+    pub tool: String,
+    pub recipient: Option<String>,
+    pub identity: Option<String>,
+}
+
+impl Cipher {
+    /// The cipher of a parsed config file's `[secrets]` section
+    pub fn from_table(secrets: &toml::Table) -> Self {
+        let text = |key: &str| {
+            secrets
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+        Self {
+            tool: text("tool").unwrap_or_else(|| "gpg".to_string()),
+            recipient: text("recipient"),
+            identity: text("identity"),
+        }
+    }
+
+    /// Encrypt `plain` to ASCII armor
+    pub fn encrypt(&self, plain: &str) -> Result<String> {
+        let mut args = vec!["--armor"];
+        match (self.tool.as_str(), self.recipient.as_deref()) {
+            ("gpg", Some(recipient)) => {
+                args.extend(["--quiet", "--encrypt", "--recipient", recipient])
+            }
+            ("gpg", None) => args.extend(["--quiet", "--symmetric"]),
+            ("age", Some(recipient)) => args.extend(["--recipient", recipient]),
+            ("age", None) => args.push("--passphrase"),
+            (tool, _) => return Err(unsupported(tool)),
+        }
+        let armored = run(&self.tool, &args, plain.as_bytes())?;
+        String::from_utf8(armored).map_err(|_| {
+            HarjiraError::Config(format!("{} returned output that isn't text", self.tool))
+        })
+    }
+
+    /// Decrypt an ASCII-armored blob
+    pub fn decrypt(&self, armored: &str) -> Result<String> {
+        let identity = self
+            .identity
+            .as_deref()
+            .map(|path| expand_home(path).display().to_string());
+        let mut args = vec!["--decrypt"];
+        match self.tool.as_str() {
+            "gpg" => args.push("--quiet"),
+            "age" => {
+                if let Some(identity) = identity.as_deref() {
+                    args.extend(["--identity", identity]);
+                }
+            }
+            tool => return Err(unsupported(tool)),
+        }
+        let plain = run(&self.tool, &args, armored.as_bytes())?;
+        String::from_utf8(plain)
+            .map_err(|_| HarjiraError::Config("The decrypted secrets aren't text".to_string()))
+    }
+}
+
+fn unsupported(tool: &str) -> HarjiraError {
+    HarjiraError::Config(format!(
+        "Unsupported secrets.tool: {}. Supported: {}",
+        tool,
+        TOOLS.join(", ")
+    ))
+}
+
+/// Run `program` with `input` on stdin, returning its stdout
+///
+/// stderr is left on the terminal, where gpg and age ask for passphrases.
+pub(crate) fn run(program: &str, args: &[&str], input: &[u8]) -> Result<Vec<u8>> {
+    debug!("Running {} {}", program, args.join(" "));
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| HarjiraError::Config(format!("Could not run {}: {}", program, e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(HarjiraError::Config(format!(
+            "{} failed ({})",
+            program, output.status
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Fill in tokens from their `*_cmd` fields, e.g. `access_token_cmd`
+///
+/// The command runs in the shell and the first line it prints is the token.
+/// A token written in plain text wins over its command.
+pub fn resolve_commands(config: &mut toml::Table) -> Result<()> {
+    for (section, key) in SECRET_FIELDS {
+        if let Some(fields) = config.get_mut(*section).and_then(|v| v.as_table_mut()) {
+            resolve_field(section, fields, key)?;
+        }
+    }
+
+    let accounts = config
+        .get_mut("harvest")
+        .and_then(|v| v.get_mut("accounts"))
+        .and_then(|v| v.as_table_mut());
+    for (name, account) in accounts.into_iter().flatten() {
+        if let Some(fields) = account.as_table_mut() {
+            resolve_field(
+                &format!("harvest.accounts.{}", name),
+                fields,
+                "access_token",
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn resolve_field(section: &str, fields: &mut toml::Table, key: &str) -> Result<()> {
+    let cmd_key = format!("{}_cmd", key);
+    let Some(command) = fields.get(&cmd_key).and_then(|v| v.as_str()) else {
+        return Ok(());
+    };
+    if fields
+        .get(key)
+        .and_then(|v| v.as_str())
+        .is_some_and(|v| !v.is_empty())
+    {
+        return Ok(());
+    }
+    let token = command_token(command)
+        .map_err(|e| HarjiraError::Config(format!("{}.{}: {}", section, cmd_key, e)))?;
+    fields.insert(key.to_string(), toml::Value::String(token));
+    Ok(())
+}
+
+/// The first line `command` prints
+fn command_token(command: &str) -> std::result::Result<String, String> {
+    if let Some(token) = COMMAND_TOKENS
+        .lock()
+        .ok()
+        .and_then(|t| t.get(command).cloned())
+    {
+        return Ok(token);
+    }
+
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+
+    debug!("Running token command: {}", command);
+    // stdin and stderr stay on the terminal for tools that ask to unlock
+    let output = Command::new(shell)
+        .arg(flag)
+        .arg(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("could not run `{}`: {}", command, e))?;
+    if !output.status.success() {
+        return Err(format!("`{}` failed ({})", command, output.status));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let token = stdout.lines().next().unwrap_or("").trim().to_string();
+    if token.is_empty() {
+        return Err(format!("`{}` printed no token", command));
+    }
+
+    if let Ok(mut tokens) = COMMAND_TOKENS.lock() {
+        tokens.insert(command.to_string(), token.clone());
+    }
+    Ok(token)
+}
+
+/// Put the tokens from `[secrets] encrypted` back in their sections
+///
+/// Tokens still written in plain text win, so a rotated token can be pasted
+/// in before running `harv config encrypt` again.
+pub fn decrypt_into(config: &mut toml::Table) -> Result<()> {
+    let Some(secrets) = config.get("secrets").and_then(|v| v.as_table()) else {
+        return Ok(());
+    };
+    let Some(armored) = secrets.get("encrypted").and_then(|v| v.as_str()) else {
+        return Ok(());
+    };
+
+    let tokens = match DECRYPTED.get() {
+        Some((blob, tokens)) if blob == armored => tokens.clone(),
+        _ => {
+            let plain = Cipher::from_table(secrets).decrypt(armored)?;
+            let tokens: toml::Table = toml::from_str(&plain)?;
+            let _ = DECRYPTED.set((armored.to_string(), tokens.clone()));
+            tokens
+        }
+    };
+    merge(config, tokens);
+    Ok(())
+}
+
+/// Add `tokens` to the sections of `config` that leave them out or empty
+fn merge(config: &mut toml::Table, tokens: toml::Table) {
+    for (section, fields) in tokens {
+        let toml::Value::Table(fields) = fields else {
+            continue;
+        };
+        let target = config
+            .entry(section)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if let toml::Value::Table(target) = target {
+            for (key, value) in fields {
+                let unset = target.get(&key).is_none_or(|v| v.as_str() == Some(""));
+                if unset {
+                    target.insert(key, value);
+                }
+            }
+        }
+    }
+}
+
+/// The non-empty tokens in a parsed config, one table per section
+pub fn collect(config: &toml::Table) -> toml::Table {
+    let mut tokens = toml::Table::new();
+    for (section, key) in SECRET_FIELDS {
+        let Some(value) = config
+            .get(*section)
+            .and_then(|v| v.get(*key))
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+        else {
+            continue;
+        };
+        let fields = tokens
+            .entry(section.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if let toml::Value::Table(fields) = fields {
+            fields.insert(key.to_string(), toml::Value::String(value.to_string()));
+        }
+    }
+    tokens
+}
+
+/// Config file contents with the token lines and any `[secrets]` section removed
+///
+/// Comments and the order of everything else are kept.
+pub fn strip_tokens(content: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut section = String::new();
+    let mut in_multiline = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if in_multiline {
+            in_multiline = !trimmed.ends_with("'''");
+            continue;
+        }
+        if trimmed.starts_with('[') {
+            section = trimmed.trim_matches(|c| c == '[' || c == ']').to_string();
+        }
+        if section == "secrets" {
+            in_multiline = trimmed.starts_with("encrypted") && trimmed.matches("'''").count() == 1;
+            continue;
+        }
+        let is_token = trimmed
+            .split_once('=')
+            .is_some_and(|(key, _)| SECRET_FIELDS.contains(&(section.as_str(), key.trim())));
+        if !is_token {
+            lines.push(line);
+        }
+    }
+
+    let mut stripped = lines.join("\n").trim_end().to_string();
+    stripped.push('\n');
+    stripped
+}
+
+/// The `[secrets]` section holding `armored`
+pub fn render_section(cipher: &Cipher, armored: &str) -> String {
+    let mut section = String::from(
+        "[secrets]\n\
+         # Tokens encrypted by `harv config encrypt`, decrypted when harv loads the config\n",
+    );
+    section.push_str(&format!("tool = \"{}\"\n", cipher.tool));
+    if let Some(recipient) = &cipher.recipient {
+        section.push_str(&format!("recipient = \"{}\"\n", recipient));
+    }
+    if let Some(identity) = &cipher.identity {
+        section.push_str(&format!("identity = \"{}\"\n", identity));
+    }
+    section.push_str(&format!("encrypted = '''\n{}\n'''\n", armored.trim()));
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"[harvest]
+# Your token
+access_token = "harvest-token"
+account_id = "123"
+
+[jira]
+access_token = ""
+base_url = "https://example.atlassian.net"
+
+[ai]
+enabled = true
+api_key = "sk-ai"
+"#;
+
+    #[test]
+    fn test_collect_and_strip() {
+        let table: toml::Table = toml::from_str(CONFIG).unwrap();
+        let tokens = collect(&table);
+        assert_eq!(
+            tokens["harvest"]["access_token"].as_str(),
+            Some("harvest-token")
+        );
+        assert_eq!(tokens["ai"]["api_key"].as_str(), Some("sk-ai"));
+        assert!(!tokens.contains_key("jira"));
+
+        let cipher = Cipher {
+            tool: "age".to_string(),
+            recipient: Some("age1example".to_string()),
+            identity: None,
+        };
+        let armored = "-----BEGIN AGE ENCRYPTED FILE-----\nabc\n-----END AGE ENCRYPTED FILE-----\n";
+        let rewritten = format!(
+            "{}\n{}",
+            strip_tokens(CONFIG),
+            render_section(&cipher, armored)
+        );
+        assert!(rewritten.contains("# Your token\naccount_id = \"123\""));
+        assert!(!rewritten.contains("harvest-token"));
+
+        // Encrypting again replaces the section instead of adding another
+        let again = strip_tokens(&rewritten);
+        assert!(!again.contains("[secrets]") && !again.contains("BEGIN AGE"));
+
+        let parsed: toml::Table = toml::from_str(&rewritten).unwrap();
+        assert_eq!(
+            Cipher::from_table(parsed["secrets"].as_table().unwrap()),
+            cipher
+        );
+        assert_eq!(parsed["secrets"]["encrypted"].as_str().unwrap(), armored);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_commands() {
+        let mut config: toml::Table = toml::from_str(
+            r#"
+            [harvest]
+            access_token_cmd = "printf 'from-cmd\\nurl: example.com\\n'"
+            [harvest.accounts.side]
+            account_id = "2"
+            access_token_cmd = "echo side-token"
+            [ai]
+            api_key = "plain"
+            api_key_cmd = "exit 1"
+            "#,
+        )
+        .unwrap();
+        resolve_commands(&mut config).unwrap();
+        assert_eq!(config["harvest"]["access_token"].as_str(), Some("from-cmd"));
+        assert_eq!(
+            config["harvest"]["accounts"]["side"]["access_token"].as_str(),
+            Some("side-token")
+        );
+        assert_eq!(config["ai"]["api_key"].as_str(), Some("plain"));
+
+        let mut failing: toml::Table =
+            toml::from_str("[jira]\naccess_token_cmd = \"exit 3\"").unwrap();
+        let err = resolve_commands(&mut failing).unwrap_err().to_string();
+        assert!(err.contains("jira.access_token_cmd"), "{}", err);
+    }
+
+    #[test]
+    fn test_merge_keeps_plain_tokens() {
+        let mut config: toml::Table =
+            toml::from_str("[harvest]\naccess_token = \"new\"\n[ai]\napi_key = \"\"").unwrap();
+        let tokens: toml::Table =
+            toml::from_str("[harvest]\naccess_token = \"old\"\n[ai]\napi_key = \"sk\"").unwrap();
+        merge(&mut config, tokens);
+        assert_eq!(config["harvest"]["access_token"].as_str(), Some("new"));
+        assert_eq!(config["ai"]["api_key"].as_str(), Some("sk"));
+    }
+}
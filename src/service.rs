@@ -0,0 +1,270 @@
+use crate::error::{HarjiraError, Result};
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// launchd job label, also the plist file name
+const LAUNCHD_LABEL: &str = "harv.sync";
+
+/// Arguments the background job passes to `harv`
+const SYNC_ARGS: &[&str] = &["sync", "--quiet", "--auto-start", "--auto-stop"];
+
+/// Parse an interval like "15m", "1h" or "90s" into seconds
+///
+/// A bare number is taken as minutes. Intervals under a minute are rejected.
+pub fn parse_interval(input: &str) -> Result<u64> {
+    let trimmed = input.trim().to_lowercase();
+    let (number, multiplier) = if let Some(n) = trimmed.strip_suffix('h') {
+        (n, 3600)
+    } else if let Some(n) = trimmed.strip_suffix('m') {
+        (n, 60)
+    } else if let Some(n) = trimmed.strip_suffix('s') {
+        (n, 1)
+    } else {
+        (trimmed.as_str(), 60)
+    };
+
+    let seconds = number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| {
+            HarjiraError::Config(format!(
+                "Invalid interval: '{}'. Use e.g. 15m, 1h or 90s",
+                input
+            ))
+        })?;
+
+    if seconds < 60 {
+        return Err(HarjiraError::Config(
+            "Interval must be at least one minute".to_string(),
+        ));
+    }
+
+    Ok(seconds)
+}
+
+/// systemd service unit running a single sync
+pub fn systemd_service_unit(exe: &Path) -> String {
+    format!(
+        r#"[Unit]
+Description=Smart Harvest time tracking
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=oneshot
+ExecStart={} {}
+StandardOutput=journal
+StandardError=journal
+
+[Install]
+WantedBy=default.target
+"#,
+        exe.display(),
+        SYNC_ARGS.join(" ")
+    )
+}
+
+/// systemd timer unit triggering the service every `interval_secs`
+pub fn systemd_timer_unit(interval_secs: u64) -> String {
+    format!(
+        r#"[Unit]
+Description=Harv Timer
+Requires=harv.service
+
+[Timer]
+# Run 2 minutes after boot
+OnBootSec=2min
+# Run every {interval}s
+OnUnitActiveSec={interval}s
+# Persist timer state across reboots (catches up missed runs)
+Persistent=true
+
+[Install]
+WantedBy=timers.target
+"#,
+        interval = interval_secs
+    )
+}
+
+/// launchd agent running a sync every `interval_secs`
+pub fn launchd_plist(exe: &Path, interval_secs: u64, log_path: &Path) -> String {
+    let arguments: String = std::iter::once(exe.display().to_string())
+        .chain(SYNC_ARGS.iter().map(|a| a.to_string()))
+        .map(|a| format!("        <string>{}</string>\n", a))
+        .collect();
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>StartInterval</key>
+    <integer>{interval}</integer>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>StandardOutPath</key>
+    <string>{log}</string>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+</dict>
+</plist>
+"#,
+        label = LAUNCHD_LABEL,
+        arguments = arguments,
+        interval = interval_secs,
+        log = log_path.display()
+    )
+}
+
+/// Write and enable the background sync job, returns the files written
+pub fn install(interval_secs: u64) -> Result<Vec<PathBuf>> {
+    let exe = std::env::current_exe()?;
+
+    if cfg!(target_os = "macos") {
+        let plist_path = launchd_plist_path()?;
+        let log_path = home_dir()?.join("Library/Logs/harv.log");
+        write_file(&plist_path, &launchd_plist(&exe, interval_secs, &log_path))?;
+
+        // Reload so a changed interval takes effect
+        let _ = run("launchctl", &["unload", &plist_path.to_string_lossy()]);
+        run("launchctl", &["load", "-w", &plist_path.to_string_lossy()])?;
+        Ok(vec![plist_path])
+    } else {
+        let dir = systemd_user_dir()?;
+        let service_path = dir.join("harv.service");
+        let timer_path = dir.join("harv.timer");
+        write_file(&service_path, &systemd_service_unit(&exe))?;
+        write_file(&timer_path, &systemd_timer_unit(interval_secs))?;
+
+        run("systemctl", &["--user", "daemon-reload"])?;
+        run("systemctl", &["--user", "enable", "--now", "harv.timer"])?;
+        Ok(vec![service_path, timer_path])
+    }
+}
+
+/// Disable the background sync job and remove its files, returns the files removed
+pub fn uninstall() -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+
+    if cfg!(target_os = "macos") {
+        let plist_path = launchd_plist_path()?;
+        if plist_path.exists() {
+            let _ = run(
+                "launchctl",
+                &["unload", "-w", &plist_path.to_string_lossy()],
+            );
+            fs::remove_file(&plist_path)?;
+            removed.push(plist_path);
+        }
+    } else {
+        let dir = systemd_user_dir()?;
+        let timer_path = dir.join("harv.timer");
+        if timer_path.exists() {
+            // Already stopped or never enabled is fine
+            let _ = run("systemctl", &["--user", "disable", "--now", "harv.timer"]);
+        }
+        for path in [timer_path, dir.join("harv.service")] {
+            if path.exists() {
+                fs::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+        if !removed.is_empty() {
+            run("systemctl", &["--user", "daemon-reload"])?;
+        }
+    }
+
+    Ok(removed)
+}
+
+fn home_dir() -> Result<PathBuf> {
+    dirs::home_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine home directory".to_string()))
+}
+
+fn systemd_user_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("systemd").join("user"))
+}
+
+fn launchd_plist_path() -> Result<PathBuf> {
+    Ok(home_dir()?
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", LAUNCHD_LABEL)))
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+        .map_err(|e| HarjiraError::Config(format!("Failed to write {}: {}", path.display(), e)))?;
+    debug!("Wrote {}", path.display());
+    Ok(())
+}
+
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    debug!("Running {} {}", program, args.join(" "));
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| HarjiraError::Config(format!("Failed to run {}: {}", program, e)))?;
+
+    if !output.status.success() {
+        return Err(HarjiraError::Config(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("15m").unwrap(), 900);
+        assert_eq!(parse_interval("1h").unwrap(), 3600);
+        assert_eq!(parse_interval("90s").unwrap(), 90);
+        assert_eq!(parse_interval("30").unwrap(), 1800);
+        assert!(parse_interval("30s").is_err());
+        assert!(parse_interval("soon").is_err());
+    }
+
+    #[test]
+    fn test_systemd_units() {
+        let service = systemd_service_unit(Path::new("/usr/local/bin/harv"));
+        assert!(service
+            .contains("ExecStart=/usr/local/bin/harv sync --quiet --auto-start --auto-stop\n"));
+
+        let timer = systemd_timer_unit(900);
+        assert!(timer.contains("OnUnitActiveSec=900s\n"));
+    }
+
+    #[test]
+    fn test_launchd_plist() {
+        let plist = launchd_plist(
+            Path::new("/usr/local/bin/harv"),
+            900,
+            Path::new("/Users/me/Library/Logs/harv.log"),
+        );
+        assert!(
+            plist.contains("<string>/usr/local/bin/harv</string>\n        <string>sync</string>")
+        );
+        assert!(plist.contains("<integer>900</integer>"));
+    }
+}
@@ -0,0 +1,274 @@
+use crate::config::{Config, HarvestConfig, TEMPLATE};
+use crate::error::{HarjiraError, Result};
+use crate::harvest::HarvestClient;
+use crate::models::HarvestUser;
+use crate::{outln, prompt};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input, Password, Select};
+use std::path::PathBuf;
+
+/// Everything the setup wizard asks for
+#[derive(Debug, Clone, Default)]
+pub struct SetupAnswers {
+    pub harvest_token: String,
+    pub harvest_account_id: String,
+    pub user_agent: String,
+    pub project_id: Option<u64>,
+    pub task_id: Option<u64>,
+    pub jira_base_url: String,
+    pub jira_token: String,
+    /// AI provider and API key, None to leave AI disabled
+    pub ai: Option<(String, String)>,
+}
+
+/// Ask for credentials and defaults, then write the configuration file
+///
+/// The Harvest credentials are checked against the API before moving on.
+pub fn run() -> Result<PathBuf> {
+    let config_path = Config::config_path()?;
+    if config_path.exists() {
+        return Err(HarjiraError::Config(format!(
+            "Configuration file already exists at {}",
+            config_path.display()
+        )));
+    }
+
+    outln!("Let's set up harv. Press Ctrl+C at any time to cancel.\n");
+
+    outln!("Harvest: create a personal access token at https://id.getharvest.com/developers");
+    let (harvest, user) = prompt_harvest()?;
+    let mut answers = SetupAnswers {
+        harvest_token: harvest.access_token.clone(),
+        harvest_account_id: harvest.account_id.clone(),
+        user_agent: harvest.user_agent.clone(),
+        ..SetupAnswers::default()
+    };
+    prompt::display_success(&format!(
+        "Connected to Harvest{}",
+        user.email
+            .as_deref()
+            .map(|email| format!(" as {}", email))
+            .unwrap_or_default()
+    ));
+
+    if confirm("Choose a default project and task for timers?", true)? {
+        let client = HarvestClient::new(harvest)?;
+        prompt::display_info("Fetching available projects...");
+        let projects = client.get_projects()?;
+        if projects.is_empty() {
+            prompt::display_warning("No active projects assigned to you, skipping");
+        } else {
+            let project = prompt::prompt_project_selection(&projects)?;
+            prompt::display_info("Fetching tasks...");
+            let tasks = client.get_project_tasks(project.id)?;
+            if tasks.is_empty() {
+                prompt::display_warning("This project has no active tasks, skipping");
+            } else {
+                let task = prompt::prompt_task_selection(&tasks)?;
+                answers.project_id = Some(project.id);
+                answers.task_id = Some(task.id);
+            }
+        }
+    }
+
+    outln!("\nJira: create an API token at https://id.atlassian.com/manage-profile/security/api-tokens");
+    answers.jira_base_url = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Jira URL (e.g., https://your-company.atlassian.net)")
+        .validate_with(|input: &String| -> std::result::Result<(), &str> {
+            if input.trim().starts_with("http") {
+                Ok(())
+            } else {
+                Err("Must start with http:// or https://")
+            }
+        })
+        .interact_text()
+        .map_err(|_| HarjiraError::UserCancelled)?
+        .trim()
+        .trim_end_matches('/')
+        .to_string();
+    answers.jira_token = password("Jira API token")?;
+
+    outln!();
+    if confirm("Enable AI-generated time entries (harv generate)?", false)? {
+        let providers = ["openai", "anthropic"];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("AI provider")
+            .items(providers)
+            .default(0)
+            .interact()
+            .map_err(|_| HarjiraError::UserCancelled)?;
+        let key = password("API key")?;
+        answers.ai = Some((providers[selection].to_string(), key));
+    }
+
+    Config::write_new(&render(&answers))
+}
+
+/// Ask for Harvest credentials until they work (or the user gives up)
+fn prompt_harvest() -> Result<(HarvestConfig, HarvestUser)> {
+    loop {
+        let access_token = password("Harvest access token")?;
+
+        prompt::display_info("Fetching your Harvest accounts...");
+        let account_id = match HarvestClient::list_accounts(&access_token, "harv") {
+            Ok(accounts) if !accounts.is_empty() => {
+                let account = prompt::prompt_account_selection(&accounts)?;
+                account.id.to_string()
+            }
+            _ => {
+                prompt::display_warning("Couldn't list accounts for this token");
+                Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Harvest account ID")
+                    .interact_text()
+                    .map_err(|_| HarjiraError::UserCancelled)?
+            }
+        };
+
+        let mut harvest = HarvestConfig {
+            access_token,
+            account_id: account_id.trim().to_string(),
+            user_agent: "harv".to_string(),
+            project_id: None,
+            task_id: None,
+            accounts: Default::default(),
+        };
+
+        prompt::display_info("Checking Harvest credentials...");
+        match HarvestClient::new(harvest.clone()).and_then(|c| c.get_current_user()) {
+            Ok(user) => {
+                if let Some(ref email) = user.email {
+                    harvest.user_agent = format!("harv ({})", email);
+                }
+                return Ok((harvest, user));
+            }
+            Err(e) => {
+                prompt::display_warning(&format!("Harvest rejected these credentials: {}", e));
+                if !confirm("Try again?", true)? {
+                    return Err(HarjiraError::UserCancelled);
+                }
+            }
+        }
+    }
+}
+
+fn password(label: &str) -> Result<String> {
+    let value = Password::with_theme(&ColorfulTheme::default())
+        .with_prompt(label)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)?;
+    Ok(value.trim().to_string())
+}
+
+fn confirm(question: &str, default: bool) -> Result<bool> {
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(question)
+        .default(default)
+        .interact()
+        .map_err(|_| HarjiraError::UserCancelled)
+}
+
+/// TOML string literal, quoted and escaped
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+/// The configuration template with the wizard's answers filled in
+///
+/// Working from the template keeps all of its comments for later editing.
+pub fn render(answers: &SetupAnswers) -> String {
+    let mut contents = TEMPLATE
+        .replace(
+            r#"access_token = "your_harvest_access_token_here""#,
+            &format!("access_token = {}", quote(&answers.harvest_token)),
+        )
+        .replace(
+            r#"account_id = "your_account_id_here""#,
+            &format!("account_id = {}", quote(&answers.harvest_account_id)),
+        )
+        .replace(
+            r#"user_agent = "harv (your.email@example.com)""#,
+            &format!("user_agent = {}", quote(&answers.user_agent)),
+        )
+        .replace(
+            r#"access_token = "your_jira_personal_access_token_here""#,
+            &format!("access_token = {}", quote(&answers.jira_token)),
+        )
+        .replace(
+            r#"base_url = "https://your-company.atlassian.net""#,
+            &format!("base_url = {}", quote(&answers.jira_base_url)),
+        );
+
+    if let (Some(project_id), Some(task_id)) = (answers.project_id, answers.task_id) {
+        contents = contents.replacen(
+            "# project_id = 12345678\n# task_id = 87654321\n",
+            &format!("project_id = {}\ntask_id = {}\n", project_id, task_id),
+            1,
+        );
+    }
+
+    if let Some((ref provider, ref api_key)) = answers.ai {
+        contents = contents
+            .replace(
+                "# Enable AI-powered time entry generation\nenabled = false",
+                "# Enable AI-powered time entry generation\nenabled = true",
+            )
+            .replace(
+                r#"provider = "openai""#,
+                &format!("provider = {}", quote(provider)),
+            )
+            .replace(
+                "api_key = \"\"\n",
+                &format!("api_key = {}\n", quote(api_key)),
+            );
+    }
+
+    contents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers() -> SetupAnswers {
+        SetupAnswers {
+            harvest_token: "harvest-token".to_string(),
+            harvest_account_id: "123456".to_string(),
+            user_agent: "harv (me@example.com)".to_string(),
+            project_id: Some(111),
+            task_id: Some(222),
+            jira_base_url: "https://acme.atlassian.net".to_string(),
+            jira_token: "jira \"token\"".to_string(),
+            ai: Some(("anthropic".to_string(), "sk-ant".to_string())),
+        }
+    }
+
+    #[test]
+    fn test_render_fills_in_answers() {
+        let config: Config = toml::from_str(&render(&answers())).unwrap();
+        assert_eq!(config.harvest.access_token, "harvest-token");
+        assert_eq!(config.harvest.account_id, "123456");
+        assert_eq!(config.harvest.user_agent, "harv (me@example.com)");
+        assert_eq!(config.harvest.project_id, Some(111));
+        assert_eq!(config.harvest.task_id, Some(222));
+        assert_eq!(config.jira.base_url, "https://acme.atlassian.net");
+        assert_eq!(config.jira.access_token, "jira \"token\"");
+        assert!(config.ai.enabled);
+        assert_eq!(config.ai.provider, "anthropic");
+        assert_eq!(config.ai.api_key, "sk-ant");
+    }
+
+    #[test]
+    fn test_render_keeps_optional_sections_off() {
+        let contents = render(&SetupAnswers {
+            project_id: None,
+            task_id: None,
+            ai: None,
+            ..answers()
+        });
+        assert!(contents.contains("# project_id = 12345678"));
+
+        let config: Config = toml::from_str(&contents).unwrap();
+        assert_eq!(config.harvest.project_id, None);
+        assert!(!config.ai.enabled);
+    }
+}
@@ -0,0 +1,171 @@
+//! Personal statistics over past entries, for `harv stats`
+
+use crate::models::TimeEntry;
+use crate::ticket_parser;
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
+use std::collections::BTreeMap;
+
+/// What a stretch of entries says about how the time was spent
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub total_hours: f64,
+    pub days_worked: usize,
+    /// Average time of the first entry of a day, over days with start times
+    pub average_start: Option<NaiveTime>,
+    /// Average end of the last entry of a day
+    pub average_stop: Option<NaiveTime>,
+    /// Hours logged per weekday, Monday first
+    pub hours_per_weekday: [f64; 7],
+    /// Projects by hours, most first
+    pub projects: Vec<(String, f64)>,
+    /// How often a day moved on to another ticket, on average
+    pub switches_per_day: f64,
+}
+
+/// Statistics for `entries`, tickets found in the notes as in reports
+pub fn compute(entries: &[TimeEntry], denylist: &[String]) -> Stats {
+    let mut days: BTreeMap<&str, Vec<&TimeEntry>> = BTreeMap::new();
+    for entry in entries {
+        days.entry(entry.spent_date.as_str())
+            .or_default()
+            .push(entry);
+    }
+
+    let mut hours_per_weekday = [0.0; 7];
+    let mut projects: Vec<(String, f64)> = Vec::new();
+    let mut starts = Vec::new();
+    let mut stops = Vec::new();
+    let mut switches = 0;
+
+    for (date, day) in &mut days {
+        let hours: f64 = day.iter().map(|e| e.hours.unwrap_or(0.0)).sum();
+        if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            hours_per_weekday[date.weekday().num_days_from_monday() as usize] += hours;
+        }
+
+        for entry in day.iter() {
+            let name = entry
+                .project
+                .as_ref()
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| "Unknown Project".to_string());
+            match projects.iter_mut().find(|(project, _)| *project == name) {
+                Some((_, total)) => *total += entry.hours.unwrap_or(0.0),
+                None => projects.push((name, entry.hours.unwrap_or(0.0))),
+            }
+        }
+
+        // Entries without a start time keep their place after the timed ones
+        day.sort_by_key(|e| (start_minutes(e).unwrap_or(u32::MAX), e.id));
+        let timed: Vec<(u32, f64)> = day
+            .iter()
+            .filter_map(|e| Some((start_minutes(e)?, e.hours.unwrap_or(0.0))))
+            .collect();
+        if let Some((first, _)) = timed.first() {
+            starts.push(*first);
+            let last_end = timed
+                .iter()
+                .map(|(start, hours)| start + (hours * 60.0).round() as u32)
+                .max()
+                .unwrap_or(*first);
+            stops.push(last_end.min(24 * 60 - 1));
+        }
+
+        let labels: Vec<String> = day.iter().map(|e| work_label(e, denylist)).collect();
+        switches += labels.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    }
+
+    projects.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Stats {
+        total_hours: entries.iter().map(|e| e.hours.unwrap_or(0.0)).sum(),
+        days_worked: days.len(),
+        average_start: average_time(&starts),
+        average_stop: average_time(&stops),
+        hours_per_weekday,
+        projects,
+        switches_per_day: if days.is_empty() {
+            0.0
+        } else {
+            switches as f64 / days.len() as f64
+        },
+    }
+}
+
+/// The ticket an entry is for, or its project without one
+fn work_label(entry: &TimeEntry, denylist: &[String]) -> String {
+    entry
+        .notes
+        .as_ref()
+        .and_then(|notes| {
+            ticket_parser::extract_tickets(std::slice::from_ref(notes), denylist)
+                .into_iter()
+                .next()
+        })
+        .or_else(|| entry.project.as_ref().map(|p| p.name.clone()))
+        .unwrap_or_default()
+}
+
+/// Minutes after midnight an entry started, from "14:05" or Harvest's "2:05pm"
+fn start_minutes(entry: &TimeEntry) -> Option<u32> {
+    let started = entry.started_time.as_deref()?.trim();
+    let time = NaiveTime::parse_from_str(started, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(&started.to_uppercase(), "%I:%M%p"))
+        .ok()?;
+    Some(time.hour() * 60 + time.minute())
+}
+
+fn average_time(minutes: &[u32]) -> Option<NaiveTime> {
+    if minutes.is_empty() {
+        return None;
+    }
+    let average = minutes.iter().sum::<u32>() / minutes.len() as u32;
+    NaiveTime::from_hms_opt(average / 60, average % 60, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProjectInfo;
+
+    fn entry(date: &str, started: &str, project: &str, notes: &str, hours: f64) -> TimeEntry {
+        TimeEntry {
+            id: 1,
+            spent_date: date.to_string(),
+            hours: Some(hours),
+            notes: Some(notes.to_string()),
+            project: Some(ProjectInfo {
+                id: 1,
+                name: project.to_string(),
+            }),
+            started_time: Some(started.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let entries = [
+            // Wednesday: PROJ-1, PROJ-2, back to PROJ-1
+            entry("2026-10-14", "1:00pm", "Platform", "PROJ-2 Search", 2.0),
+            entry("2026-10-14", "8:30am", "Platform", "PROJ-1 Login", 4.0),
+            entry("2026-10-14", "15:00", "Platform", "PROJ-1 Login", 1.5),
+            // Thursday: one meeting
+            entry("2026-10-15", "9:30am", "Internal", "Standup", 0.5),
+        ];
+
+        let stats = compute(&entries, &[]);
+        assert_eq!(stats.total_hours, 8.0);
+        assert_eq!(stats.days_worked, 2);
+        assert_eq!(stats.average_start, NaiveTime::from_hms_opt(9, 0, 0));
+        // 16:30 on Wednesday and 10:00 on Thursday
+        assert_eq!(stats.average_stop, NaiveTime::from_hms_opt(13, 15, 0));
+        assert_eq!(stats.hours_per_weekday[2], 7.5);
+        assert_eq!(stats.hours_per_weekday[3], 0.5);
+        assert_eq!(
+            stats.projects,
+            vec![("Platform".to_string(), 7.5), ("Internal".to_string(), 0.5)]
+        );
+        assert_eq!(stats.switches_per_day, 1.0);
+    }
+}
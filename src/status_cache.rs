@@ -0,0 +1,359 @@
+use crate::ai::render_template;
+use crate::error::{HarjiraError, Result};
+use crate::models::TimeEntry;
+use crate::ticket_parser;
+use chrono::{DateTime, Local, Utc};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const STATUS_CACHE_VERSION: u8 = 1;
+
+/// How long a cached status stays fresh
+pub const CACHE_TTL_SECS: i64 = 60;
+
+/// Default one-line format while a timer is running
+pub const DEFAULT_STATUS_FORMAT: &str = "⏱ {ticket} {hours}h ({today}/{target}h)";
+
+/// Default one-line format when no timer is running
+pub const DEFAULT_IDLE_FORMAT: &str = "⊗ {today}/{target}h";
+
+/// Last known copy of today's time entries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    version: u8,
+    pub fetched_at: DateTime<Utc>,
+    /// Local date the entries were fetched for (YYYY-MM-DD)
+    pub date: String,
+    /// Harvest account the entries belong to
+    #[serde(default)]
+    pub account_id: String,
+    pub entries: Vec<TimeEntry>,
+}
+
+impl StatusSnapshot {
+    /// Create a snapshot of an account's entries fetched just now
+    pub fn new(account_id: &str, entries: Vec<TimeEntry>) -> Self {
+        Self {
+            version: STATUS_CACHE_VERSION,
+            fetched_at: Utc::now(),
+            date: Local::now().format("%Y-%m-%d").to_string(),
+            account_id: account_id.to_string(),
+            entries,
+        }
+    }
+
+    /// Load the cached snapshot, returns None if missing or unreadable
+    pub fn load() -> Option<Self> {
+        match Self::load_internal() {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                debug!("No usable status cache: {}", e);
+                None
+            }
+        }
+    }
+
+    fn load_internal() -> Result<Self> {
+        let path = status_cache_path()?;
+        let contents = fs::read_to_string(&path)?;
+        let snapshot: StatusSnapshot = serde_json::from_str(&contents)?;
+
+        if snapshot.version > STATUS_CACHE_VERSION {
+            return Err(HarjiraError::Config(format!(
+                "Status cache version {} is newer than supported version {}",
+                snapshot.version, STATUS_CACHE_VERSION
+            )));
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Save snapshot to disk, logs errors but doesn't fail
+    pub fn save(&self) {
+        if let Err(e) = self.save_internal() {
+            warn!("Failed to save status cache: {}", e);
+        }
+    }
+
+    fn save_internal(&self) -> Result<()> {
+        let path = status_cache_path()?;
+
+        crate::fs::write_private_atomic(&path, serde_json::to_string(self)?)?;
+
+        debug!("Saved status cache to {}", path.display());
+        Ok(())
+    }
+
+    /// Whether the snapshot is for today and younger than the TTL
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        let today = now.with_timezone(&Local).format("%Y-%m-%d").to_string();
+        self.date == today && (now - self.fetched_at).num_seconds() < CACHE_TTL_SECS
+    }
+
+    /// The running timer, if any
+    pub fn running_entry(&self) -> Option<&TimeEntry> {
+        self.entries.iter().find(|e| e.is_running)
+    }
+
+    /// Hours elapsed since the snapshot was taken, used to keep a running timer ticking
+    fn elapsed_hours(&self, now: DateTime<Utc>) -> f64 {
+        ((now - self.fetched_at).num_seconds().max(0) as f64) / 3600.0
+    }
+
+    /// Hours on the running timer, extrapolated to `now`
+    pub fn running_hours(&self, now: DateTime<Utc>) -> Option<f64> {
+        self.running_entry()
+            .map(|e| e.hours.unwrap_or(0.0) + self.elapsed_hours(now))
+    }
+
+    /// Total hours today, extrapolated to `now` when a timer is running
+    pub fn total_hours(&self, now: DateTime<Utc>) -> f64 {
+        let logged: f64 = self.entries.iter().filter_map(|e| e.hours).sum();
+        if self.running_entry().is_some() {
+            logged + self.elapsed_hours(now)
+        } else {
+            logged
+        }
+    }
+
+    /// Billable part of `total_hours`
+    pub fn billable_hours(&self, now: DateTime<Utc>) -> f64 {
+        let logged: f64 = self
+            .entries
+            .iter()
+            .filter(|e| e.billable)
+            .filter_map(|e| e.hours)
+            .sum();
+        if self.running_entry().is_some_and(|e| e.billable) {
+            logged + self.elapsed_hours(now)
+        } else {
+            logged
+        }
+    }
+}
+
+/// Remove the cached status so the next `status` call hits the API
+pub fn invalidate() {
+    if let Ok(path) = status_cache_path() {
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Failed to clear status cache: {}", e);
+            }
+        }
+    }
+}
+
+/// Render the one-line status for `snapshot` using the given templates
+///
+/// Placeholders: `{ticket}`, `{notes}`, `{project}`, `{task}`, `{hours}`,
+/// `{today}` and `{target}`.
+pub fn render_short(
+    snapshot: &StatusSnapshot,
+    now: DateTime<Utc>,
+    running_format: &str,
+    idle_format: &str,
+    target_hours: f64,
+    denylist: &[String],
+) -> String {
+    let today = format!("{:.1}", snapshot.total_hours(now));
+    let target = format!("{}", target_hours);
+
+    let Some(entry) = snapshot.running_entry() else {
+        return render_template(idle_format, &[("today", today), ("target", target)]);
+    };
+
+    let notes = entry.notes.clone().unwrap_or_default();
+    let ticket = ticket_parser::extract_tickets(std::slice::from_ref(&notes), denylist)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| short_label(&notes));
+    let hours = format!("{:.1}", snapshot.running_hours(now).unwrap_or(0.0));
+    let project = entry
+        .project
+        .as_ref()
+        .map_or(String::new(), |p| p.name.clone());
+    let task = entry
+        .task
+        .as_ref()
+        .map_or(String::new(), |t| t.name.clone());
+
+    // One pass, so braces in the notes or names aren't taken for placeholders
+    render_template(
+        running_format,
+        &[
+            ("ticket", ticket),
+            ("notes", notes),
+            ("project", project),
+            ("task", task),
+            ("hours", hours),
+            ("today", today),
+            ("target", target),
+        ],
+    )
+}
+
+/// Fallback label for timers without a ticket key
+fn short_label(notes: &str) -> String {
+    const MAX_CHARS: usize = 20;
+
+    let notes = notes.trim();
+    if notes.is_empty() {
+        "timer".to_string()
+    } else if notes.chars().count() > MAX_CHARS {
+        format!("{}…", notes.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        notes.to_string()
+    }
+}
+
+fn status_cache_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("status_cache.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProjectInfo;
+    use chrono::Duration;
+
+    fn entry(notes: &str, hours: f64, is_running: bool) -> TimeEntry {
+        TimeEntry {
+            id: 1,
+            spent_date: "2025-01-10".to_string(),
+            hours: Some(hours),
+            notes: Some(notes.to_string()),
+            is_running,
+            project: Some(ProjectInfo {
+                id: 1,
+                name: "Backend".to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let snapshot = StatusSnapshot::new("123", Vec::new());
+        let now = snapshot.fetched_at;
+        assert!(snapshot.is_fresh(now + Duration::seconds(10)));
+        assert!(!snapshot.is_fresh(now + Duration::seconds(CACHE_TTL_SECS)));
+    }
+
+    #[test]
+    fn test_stale_on_date_change() {
+        let mut snapshot = StatusSnapshot::new("123", Vec::new());
+        snapshot.date = "2000-01-01".to_string();
+        assert!(!snapshot.is_fresh(snapshot.fetched_at));
+    }
+
+    #[test]
+    fn test_running_timer_is_extrapolated() {
+        let snapshot = StatusSnapshot::new(
+            "123",
+            vec![
+                entry("PROJ-1 - Review", 3.5, false),
+                entry("PROJ-123 - Login", 1.5, true),
+            ],
+        );
+        let later = snapshot.fetched_at + Duration::minutes(12);
+
+        assert!((snapshot.running_hours(later).unwrap() - 1.7).abs() < 1e-9);
+        assert!((snapshot.total_hours(later) - 5.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_billable_hours() {
+        let billable = |mut entry: TimeEntry| {
+            entry.billable = true;
+            entry
+        };
+        let snapshot = StatusSnapshot::new(
+            "123",
+            vec![
+                entry("Standup", 0.25, false),
+                billable(entry("PROJ-1 - Review", 3.5, false)),
+                billable(entry("PROJ-123 - Login", 1.5, true)),
+            ],
+        );
+        let later = snapshot.fetched_at + Duration::minutes(12);
+
+        assert!((snapshot.billable_hours(later) - 5.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_render_short_running() {
+        let snapshot = StatusSnapshot::new(
+            "123",
+            vec![
+                entry("PROJ-1 - Review", 3.5, false),
+                entry("PROJ-123 - Login", 1.7, true),
+            ],
+        );
+        let line = render_short(
+            &snapshot,
+            snapshot.fetched_at,
+            DEFAULT_STATUS_FORMAT,
+            DEFAULT_IDLE_FORMAT,
+            8.0,
+            &[],
+        );
+        assert_eq!(line, "⏱ PROJ-123 1.7h (5.2/8h)");
+    }
+
+    #[test]
+    fn test_render_short_placeholders() {
+        let snapshot = StatusSnapshot::new("123", vec![entry("Standup", 0.25, true)]);
+        let line = render_short(
+            &snapshot,
+            snapshot.fetched_at,
+            "{project}: {ticket} [{notes}]",
+            DEFAULT_IDLE_FORMAT,
+            8.0,
+            &[],
+        );
+        assert_eq!(line, "Backend: Standup [Standup]");
+    }
+
+    #[test]
+    fn test_render_short_leaves_braces_in_notes() {
+        let snapshot =
+            StatusSnapshot::new("123", vec![entry("Show {today} in the bar", 0.5, true)]);
+        let line = render_short(
+            &snapshot,
+            snapshot.fetched_at,
+            "{notes} {today}",
+            DEFAULT_IDLE_FORMAT,
+            8.0,
+            &[],
+        );
+        assert_eq!(line, "Show {today} in the bar 0.5");
+    }
+
+    #[test]
+    fn test_render_short_idle() {
+        let snapshot = StatusSnapshot::new("123", vec![entry("PROJ-1 - Review", 6.0, false)]);
+        let line = render_short(
+            &snapshot,
+            snapshot.fetched_at,
+            DEFAULT_STATUS_FORMAT,
+            DEFAULT_IDLE_FORMAT,
+            7.5,
+            &[],
+        );
+        assert_eq!(line, "⊗ 6.0/7.5h");
+    }
+
+    #[test]
+    fn test_short_label() {
+        assert_eq!(short_label(""), "timer");
+        assert_eq!(short_label("Standup"), "Standup");
+        assert_eq!(
+            short_label("A very long description of the work"),
+            "A very long descript…"
+        );
+    }
+}
@@ -0,0 +1,530 @@
+//! Tempo Timesheets backend
+//!
+//! Tempo only stores finished worklogs, so the running timer is kept in
+//! `~/.config/harv/tempo_timer.json` and turned into a worklog on the ticket
+//! when it stops. Restarting an entry times more work onto that worklog.
+
+use crate::backend::TimeBackend;
+use crate::config::{JiraConfig, TempoConfig};
+use crate::error::{HarjiraError, Result};
+use crate::http::ApiClient;
+use crate::jira::JiraClient;
+use crate::models::{
+    Context, TempoWorklog, TempoWorklogRequest, TempoWorklogsResponse, TimeEntry,
+    UpdateTimeEntryRequest,
+};
+use crate::status_cache;
+use chrono::{DateTime, Local};
+use log::{debug, info, warn};
+use reqwest::blocking::Response;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_BASE_URL: &str = "https://api.tempo.io/4";
+
+/// ID of the local running timer, Tempo worklog IDs start at 1
+pub const LOCAL_TIMER_ID: u64 = 0;
+
+/// Shortest worklog Tempo accepts
+const MIN_WORKLOG_SECONDS: u64 = 60;
+
+pub struct TempoClient {
+    client: ApiClient,
+    base_url: String,
+    config: TempoConfig,
+    /// Resolves ticket keys to the numeric issue IDs worklogs need
+    jira: JiraClient,
+}
+
+impl TempoClient {
+    pub fn new(config: TempoConfig, jira_config: JiraConfig) -> Result<Self> {
+        Self::with_jira(config, JiraClient::new(jira_config)?)
+    }
+
+    /// Client using an existing Jira client for issue lookups
+    pub fn with_jira(config: TempoConfig, jira: JiraClient) -> Result<Self> {
+        if config.access_token.is_empty() {
+            return Err(HarjiraError::Config(
+                "No Tempo access token configured in [tempo]".to_string(),
+            ));
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", config.access_token))
+                .map_err(|e| HarjiraError::Config(format!("Invalid Tempo access token: {}", e)))?,
+        );
+
+        let base_url = config
+            .base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_BASE_URL)
+            .trim_end_matches('/')
+            .to_string();
+
+        Ok(Self {
+            client: ApiClient::new(headers),
+            base_url,
+            config,
+            jira,
+        })
+    }
+
+    /// Send requests to another API root, e.g. a mock server in tests
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// The user's worklogs between two dates (YYYY-MM-DD, inclusive)
+    pub fn get_worklogs(&self, from_date: &str, to_date: &str) -> Result<Vec<TempoWorklog>> {
+        let mut url = format!(
+            "{}/worklogs/user/{}?from={}&to={}&limit=1000",
+            self.base_url, self.config.account_id, from_date, to_date
+        );
+        let mut worklogs = Vec::new();
+
+        loop {
+            debug!("GET {}", url);
+            let response =
+                self.client.get(&url).send().map_err(|e| {
+                    HarjiraError::Network(format!("Failed to fetch worklogs: {}", e))
+                })?;
+            let page: TempoWorklogsResponse = check_response(response)?.json().map_err(|e| {
+                HarjiraError::Tempo(format!("Failed to parse worklogs response: {}", e))
+            })?;
+
+            worklogs.extend(page.results);
+            match page.metadata.next {
+                Some(next) => url = next,
+                None => break,
+            }
+        }
+
+        debug!(
+            "Retrieved {} worklogs from {} to {}",
+            worklogs.len(),
+            from_date,
+            to_date
+        );
+        Ok(worklogs)
+    }
+
+    fn get_worklog(&self, worklog_id: u64) -> Result<TempoWorklog> {
+        let url = format!("{}/worklogs/{}", self.base_url, worklog_id);
+        debug!("GET {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to fetch worklog: {}", e)))?;
+
+        check_response(response)?
+            .json()
+            .map_err(|e| HarjiraError::Tempo(format!("Failed to parse worklog: {}", e)))
+    }
+
+    /// Create a worklog, or replace `worklog_id` when given
+    fn save_worklog(
+        &self,
+        worklog_id: Option<u64>,
+        request: &TempoWorklogRequest,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let (method, url) = match worklog_id {
+            Some(id) => ("PUT", format!("{}/worklogs/{}", self.base_url, id)),
+            None => ("POST", format!("{}/worklogs", self.base_url)),
+        };
+
+        if ctx.dry_run {
+            crate::plan::record(method, &url, Some(request));
+            info!("[DRY RUN] Would {} worklog: {:?}", method, request);
+            return Ok(TimeEntry {
+                id: worklog_id.unwrap_or(LOCAL_TIMER_ID),
+                spent_date: request.start_date.clone(),
+                hours: Some(request.time_spent_seconds as f64 / 3600.0),
+                notes: Some(request.description.clone()),
+                is_running: false,
+                project: None,
+                task: None,
+                started_time: request.start_time.clone(),
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
+            });
+        }
+
+        debug!("{} {}", method, url);
+        let builder = match worklog_id {
+            Some(_) => self.client.put(&url),
+            None => self.client.post(&url),
+        };
+        let response = builder
+            .json(request)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to save worklog: {}", e)))?;
+
+        let worklog: TempoWorklog = check_response(response)?
+            .json()
+            .map_err(|e| HarjiraError::Tempo(format!("Failed to parse saved worklog: {}", e)))?;
+
+        status_cache::invalidate();
+        info!(
+            "Saved worklog {} ({}s)",
+            worklog.tempo_worklog_id, worklog.time_spent_seconds
+        );
+        Ok(worklog.to_time_entry())
+    }
+
+    fn delete_worklog(&self, worklog_id: u64, ctx: &Context) -> Result<()> {
+        let url = format!("{}/worklogs/{}", self.base_url, worklog_id);
+
+        if ctx.dry_run {
+            crate::plan::record::<()>("DELETE", &url, None);
+            info!("[DRY RUN] Would delete worklog {}", worklog_id);
+            return Ok(());
+        }
+
+        debug!("DELETE {}", url);
+        let response = self
+            .client
+            .delete(&url)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to delete worklog: {}", e)))?;
+        check_response(response)?;
+
+        status_cache::invalidate();
+        info!("Deleted worklog {}", worklog_id);
+        Ok(())
+    }
+
+    fn worklog_request(&self, worklog: &TempoWorklog) -> TempoWorklogRequest {
+        TempoWorklogRequest {
+            author_account_id: self.config.account_id.clone(),
+            issue_id: worklog.issue.id,
+            start_date: worklog.start_date.clone(),
+            start_time: worklog.start_time.clone(),
+            time_spent_seconds: worklog.time_spent_seconds,
+            description: worklog.description.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl TimeBackend for TempoClient {
+    fn name(&self) -> &str {
+        "Tempo"
+    }
+
+    fn get_time_entries_for_date(&self, date: &str) -> Result<Vec<TimeEntry>> {
+        let mut entries: Vec<TimeEntry> = self
+            .get_worklogs(date, date)?
+            .iter()
+            .map(TempoWorklog::to_time_entry)
+            .collect();
+
+        if let Some(timer) = TempoTimer::load() {
+            let running = timer.to_time_entry(Local::now());
+            if running.spent_date == date {
+                // A restarted worklog is shown once, with the time running on it
+                entries.retain(|e| Some(e.id) != timer.worklog_id);
+                entries.push(running);
+            }
+        }
+        Ok(entries)
+    }
+
+    fn get_running_timers(&self) -> Result<Vec<TimeEntry>> {
+        Ok(TempoTimer::load()
+            .map(|timer| timer.to_time_entry(Local::now()))
+            .into_iter()
+            .collect())
+    }
+
+    fn create_time_entry(
+        &self,
+        ticket_key: &str,
+        description: &str,
+        _ticket_url: &str,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let timer = TempoTimer {
+            issue_id: self.jira.get_issue_id(ticket_key)?,
+            description: format!("{} - {}", ticket_key, description),
+            started_at: Local::now(),
+            worklog_id: None,
+            base_seconds: 0,
+        };
+
+        if ctx.dry_run {
+            info!("[DRY RUN] Would start a local timer for {}", ticket_key);
+        } else {
+            timer.save()?;
+            status_cache::invalidate();
+            info!("Started timer: {}", timer.description);
+        }
+        Ok(timer.to_time_entry(timer.started_at))
+    }
+
+    fn log_time_entry(
+        &self,
+        ticket_key: &str,
+        description: &str,
+        _ticket_url: &str,
+        hours: f64,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let request = TempoWorklogRequest {
+            author_account_id: self.config.account_id.clone(),
+            issue_id: self.jira.get_issue_id(ticket_key)?,
+            start_date: Local::now().format("%Y-%m-%d").to_string(),
+            start_time: None,
+            time_spent_seconds: (hours * 3600.0).round() as u64,
+            description: format!("{} - {}", ticket_key, description),
+        };
+        self.save_worklog(None, &request, ctx)
+    }
+
+    fn stop_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry> {
+        let timer = TempoTimer::load()
+            .filter(|_| entry_id == LOCAL_TIMER_ID)
+            .ok_or_else(|| {
+                HarjiraError::Tempo(format!("Entry {} is not a running timer", entry_id))
+            })?;
+
+        let elapsed = timer.elapsed_seconds(Local::now());
+        let request = TempoWorklogRequest {
+            author_account_id: self.config.account_id.clone(),
+            issue_id: timer.issue_id,
+            start_date: timer.started_at.format("%Y-%m-%d").to_string(),
+            start_time: Some(timer.started_at.format("%H:%M:%S").to_string()),
+            time_spent_seconds: timer.base_seconds + elapsed,
+            description: timer.description.clone(),
+        };
+        let request = match timer.worklog_id {
+            // Keep the restarted worklog's own date and start
+            Some(id) => {
+                let existing = self.get_worklog(id)?;
+                TempoWorklogRequest {
+                    start_date: existing.start_date,
+                    start_time: existing.start_time,
+                    ..request
+                }
+            }
+            None => request,
+        };
+
+        let entry = self.save_worklog(timer.worklog_id, &request, ctx)?;
+        if !ctx.dry_run {
+            TempoTimer::clear();
+        }
+        Ok(entry)
+    }
+
+    fn restart_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry> {
+        let worklog = self.get_worklog(entry_id)?;
+        let timer = TempoTimer {
+            issue_id: worklog.issue.id,
+            description: worklog.description.clone().unwrap_or_default(),
+            started_at: Local::now(),
+            worklog_id: Some(entry_id),
+            base_seconds: worklog.time_spent_seconds,
+        };
+
+        if ctx.dry_run {
+            info!("[DRY RUN] Would restart worklog {} locally", entry_id);
+        } else {
+            timer.save()?;
+            status_cache::invalidate();
+            info!("Restarted worklog {}", entry_id);
+        }
+        Ok(timer.to_time_entry(timer.started_at))
+    }
+
+    fn update_time_entry(
+        &self,
+        entry_id: u64,
+        update: &UpdateTimeEntryRequest,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let worklog = self.get_worklog(entry_id)?;
+        let mut request = self.worklog_request(&worklog);
+        if let Some(hours) = update.hours {
+            request.time_spent_seconds = ((hours * 3600.0).round() as u64).max(MIN_WORKLOG_SECONDS);
+        }
+        if let Some(notes) = &update.notes {
+            request.description = notes.clone();
+        }
+        self.save_worklog(Some(entry_id), &request, ctx)
+    }
+
+    fn delete_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<()> {
+        if entry_id != LOCAL_TIMER_ID {
+            return self.delete_worklog(entry_id, ctx);
+        }
+
+        // The running timer is local, along with the worklog it was restarted on
+        let timer = TempoTimer::load().ok_or_else(|| {
+            HarjiraError::Tempo(format!("Entry {} is not a running timer", entry_id))
+        })?;
+        if let Some(worklog_id) = timer.worklog_id {
+            self.delete_worklog(worklog_id, ctx)?;
+        }
+        if ctx.dry_run {
+            info!("[DRY RUN] Would discard the local timer");
+        } else {
+            TempoTimer::clear();
+            status_cache::invalidate();
+        }
+        Ok(())
+    }
+}
+
+/// The running Tempo timer, kept on disk between runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TempoTimer {
+    issue_id: u64,
+    /// Worklog description, "KEY - summary"
+    description: String,
+    started_at: DateTime<Local>,
+    /// Worklog the time is added to, set when an entry was restarted
+    #[serde(default)]
+    worklog_id: Option<u64>,
+    /// Seconds already on that worklog
+    #[serde(default)]
+    base_seconds: u64,
+}
+
+impl TempoTimer {
+    fn load() -> Option<Self> {
+        let path = timer_path().ok()?;
+        let contents = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(timer) => Some(timer),
+            Err(e) => {
+                warn!("Ignoring unreadable Tempo timer {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = timer_path()?;
+        crate::fs::write_private_atomic(&path, serde_json::to_string(self)?)?;
+        debug!("Saved Tempo timer to {}", path.display());
+        Ok(())
+    }
+
+    fn clear() {
+        if let Ok(path) = timer_path() {
+            if path.exists() {
+                if let Err(e) = fs::remove_file(&path) {
+                    warn!("Failed to clear Tempo timer: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Whole minutes since the timer started, at least one
+    fn elapsed_seconds(&self, now: DateTime<Local>) -> u64 {
+        let seconds = (now - self.started_at).num_seconds().max(0) as u64;
+        (seconds / 60 * 60).max(MIN_WORKLOG_SECONDS)
+    }
+
+    fn to_time_entry(&self, now: DateTime<Local>) -> TimeEntry {
+        let seconds = (now - self.started_at).num_seconds().max(0) as u64;
+        TimeEntry {
+            id: LOCAL_TIMER_ID,
+            spent_date: self.started_at.format("%Y-%m-%d").to_string(),
+            hours: Some((self.base_seconds + seconds) as f64 / 3600.0),
+            notes: Some(self.description.clone()),
+            is_running: true,
+            project: None,
+            task: None,
+            started_time: Some(self.started_at.format("%H:%M").to_string()),
+            billable: false,
+            billable_rate: None,
+            external_reference: None,
+            user: None,
+        }
+    }
+}
+
+fn timer_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("tempo_timer.json"))
+}
+
+fn check_response(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        #[serde(default)]
+        errors: Vec<ErrorMessage>,
+    }
+    #[derive(Deserialize)]
+    struct ErrorMessage {
+        message: String,
+    }
+
+    let body = response.text().unwrap_or_default();
+    let message = serde_json::from_str::<ErrorBody>(&body)
+        .ok()
+        .map(|b| {
+            b.errors
+                .into_iter()
+                .map(|e| e.message)
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| body.trim().to_string());
+
+    if status == 401 {
+        return Err(HarjiraError::Auth(
+            "Tempo authentication failed. Check tempo.access_token in your config.".to_string(),
+        ));
+    }
+    Err(HarjiraError::Tempo(format!("{}: {}", status, message)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn timer(base_seconds: u64) -> TempoTimer {
+        TempoTimer {
+            issue_id: 10001,
+            description: "ABC-1 - Fix login".to_string(),
+            started_at: Local::now() - Duration::seconds(125),
+            worklog_id: None,
+            base_seconds,
+        }
+    }
+
+    #[test]
+    fn test_elapsed_seconds_rounds_down_to_minutes() {
+        let timer = timer(0);
+        assert_eq!(timer.elapsed_seconds(Local::now()), 120);
+        assert_eq!(timer.elapsed_seconds(timer.started_at), MIN_WORKLOG_SECONDS);
+    }
+
+    #[test]
+    fn test_running_entry_includes_restarted_time() {
+        let entry = timer(3600).to_time_entry(Local::now());
+        assert_eq!(entry.id, LOCAL_TIMER_ID);
+        assert!(entry.is_running);
+        assert!(entry.hours.unwrap() > 1.0);
+        assert_eq!(entry.notes.as_deref(), Some("ABC-1 - Fix login"));
+    }
+}
@@ -1,11 +1,150 @@
+use crate::error::{HarjiraError, Result};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::HashSet;
+use std::sync::OnceLock;
 
 lazy_static! {
     /// Case-insensitive regex pattern for Jira tickets
     /// Matches patterns like: PROJECT-123, proj-456, Project-789
     static ref JIRA_TICKET_RE: Regex = Regex::new(r"(?i)\b([a-z]+)-(\d+)\b").unwrap();
+
+    /// Azure Boards work item mentions: AB#1234, or a bare #1234
+    static ref WORK_ITEM_RE: Regex = Regex::new(r"(?i)(?:^|[^\w/])(?:AB)?#(\d+)\b").unwrap();
+
+    /// GitHub issue mentions: org/repo#123, or a bare #123
+    static ref GITHUB_ISSUE_RE: Regex =
+        Regex::new(r"(?:^|[^\w/#])(?:([\w.-]+/[\w.-]+))?#(\d+)\b").unwrap();
+}
+
+/// Extra ticket pattern from `ticket_filter.pattern`
+struct CustomPattern {
+    regex: Regex,
+    replace_default: bool,
+}
+
+static CUSTOM_PATTERN: OnceLock<CustomPattern> = OnceLock::new();
+
+/// Compile a `ticket_filter.pattern`
+pub fn compile_pattern(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| {
+        HarjiraError::Config(format!(
+            "Invalid ticket_filter.pattern '{}': {}",
+            pattern, e
+        ))
+    })
+}
+
+/// Find tickets with `pattern` for the rest of the process, instead of
+/// `PROJ-123` keys with `replace_default` and next to them otherwise
+pub fn set_pattern(pattern: &str, replace_default: bool) -> Result<()> {
+    let regex = compile_pattern(pattern)?;
+    let _ = CUSTOM_PATTERN.set(CustomPattern {
+        regex,
+        replace_default,
+    });
+    Ok(())
+}
+
+/// Extract ticket keys in the reference style of an issue tracker provider
+pub fn extract_for_tracker(
+    provider: &str,
+    commit_messages: &[String],
+    denylist: &[String],
+) -> Vec<String> {
+    match provider.to_lowercase().as_str() {
+        "azure_devops" => extract_work_items(commit_messages),
+        _ => extract_tickets(commit_messages, denylist),
+    }
+}
+
+/// The part of a commit message that tickets are looked for in
+///
+/// With `whole_message` that is all of it. Otherwise it is the subject line
+/// and the value of each body line starting with one of `keys`, such as the
+/// trailer `Refs: PROJ-123` or `Fixes PROJ-7`, so a key mentioned in passing
+/// in the body isn't taken for the ticket worked on.
+pub fn searched_text(message: &str, keys: &[String], whole_message: bool) -> String {
+    if whole_message {
+        return message.to_string();
+    }
+
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or_default();
+    std::iter::once(subject)
+        .chain(lines.filter_map(|line| keyword_value(line, keys)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// What follows one of `keys` at the start of `line`, after a colon or a space
+fn keyword_value<'a>(line: &'a str, keys: &[String]) -> Option<&'a str> {
+    let line = line.trim_start();
+    keys.iter().find_map(|key| {
+        let head = line.get(..key.len())?;
+        if !head.eq_ignore_ascii_case(key) {
+            return None;
+        }
+        let rest = &line[key.len()..];
+        let value = rest
+            .strip_prefix(':')
+            .or_else(|| rest.starts_with(char::is_whitespace).then_some(rest))?;
+        Some(value.trim())
+    })
+}
+
+/// Extract Azure Boards work item mentions from commit messages
+///
+/// Returns deduplicated keys in the `AB#1234` form Azure Boards links, so
+/// `#1234` and `ab#1234` both become `AB#1234`.
+pub fn extract_work_items(commit_messages: &[String]) -> Vec<String> {
+    let mut items = HashSet::new();
+
+    for message in commit_messages {
+        for cap in WORK_ITEM_RE.captures_iter(message) {
+            items.insert(format!("AB#{}", &cap[1]));
+        }
+    }
+
+    let mut result: Vec<String> = items.into_iter().collect();
+    result.sort();
+    result
+}
+
+/// Extract GitHub issue mentions from commit messages
+///
+/// Returns deduplicated `org/repo#123` keys. Bare `#123` mentions refer to
+/// `repository`, the one the commits were made in, and are left out when
+/// it isn't known.
+pub fn extract_github_issues(commit_messages: &[String], repository: Option<&str>) -> Vec<String> {
+    let mut issues = HashSet::new();
+
+    for message in commit_messages {
+        for cap in GITHUB_ISSUE_RE.captures_iter(message) {
+            let repo = cap.get(1).map(|m| m.as_str()).or(repository);
+            if let Some(repo) = repo {
+                issues.insert(format!("{}#{}", repo, &cap[2]));
+            }
+        }
+    }
+
+    let mut result: Vec<String> = issues.into_iter().collect();
+    result.sort();
+    result
+}
+
+/// Repository and number of an `org/repo#123` GitHub issue key
+pub fn github_issue(key: &str) -> Option<(&str, u64)> {
+    let (repo, number) = key.rsplit_once('#')?;
+    if !repo.contains('/') {
+        return None;
+    }
+    Some((repo, number.parse().ok()?))
+}
+
+/// Numeric ID of an `AB#1234` work item key
+pub fn work_item_id(key: &str) -> Option<u64> {
+    key.rsplit('#').next()?.parse().ok()
 }
 
 /// Extract Jira ticket IDs from commit messages
@@ -16,22 +155,68 @@ lazy_static! {
 /// * `commit_messages` - List of commit messages to parse
 /// * `denylist` - Optional list of ticket prefixes to filter out (case-insensitive)
 pub fn extract_tickets(commit_messages: &[String], denylist: &[String]) -> Vec<String> {
+    match CUSTOM_PATTERN.get() {
+        Some(custom) => extract_tickets_with(
+            commit_messages,
+            denylist,
+            Some(&custom.regex),
+            !custom.replace_default,
+        ),
+        None => extract_tickets_with(commit_messages, denylist, None, true),
+    }
+}
+
+/// `extract_tickets` with an extra `pattern`, and the `PROJ-123` one when `default`
+///
+/// The first capture group of the pattern that took part in a match is the
+/// key, or the whole match without groups. The key's prefix for the
+/// denylist is the letters it starts with.
+fn extract_tickets_with(
+    commit_messages: &[String],
+    denylist: &[String],
+    pattern: Option<&Regex>,
+    default: bool,
+) -> Vec<String> {
     let mut tickets = HashSet::new();
 
     // Normalize denylist to uppercase for case-insensitive comparison
     let denylist_upper: Vec<String> = denylist.iter().map(|s| s.to_uppercase()).collect();
 
     for message in commit_messages {
-        for cap in JIRA_TICKET_RE.captures_iter(message) {
-            // Normalize to uppercase: PROJECT-123
-            let prefix = cap[1].to_uppercase();
-            let ticket = format!("{}-{}", prefix, &cap[2]);
+        if default {
+            for cap in JIRA_TICKET_RE.captures_iter(message) {
+                // Normalize to uppercase: PROJECT-123
+                let prefix = cap[1].to_uppercase();
+                let ticket = format!("{}-{}", prefix, &cap[2]);
+
+                // Skip if ticket prefix is in denylist
+                if denylist_upper.contains(&prefix) {
+                    continue;
+                }
+
+                tickets.insert(ticket);
+            }
+        }
 
-            // Skip if ticket prefix is in denylist
-            if denylist_upper.contains(&prefix) {
+        let Some(pattern) = pattern else {
+            continue;
+        };
+        for cap in pattern.captures_iter(message) {
+            let key = cap
+                .iter()
+                .skip(1)
+                .flatten()
+                .next()
+                .or_else(|| cap.get(0))
+                .map_or("", |m| m.as_str());
+            let ticket = key.trim().to_uppercase();
+            let prefix: String = ticket
+                .chars()
+                .take_while(|c| c.is_ascii_alphabetic())
+                .collect();
+            if ticket.is_empty() || denylist_upper.contains(&prefix) {
                 continue;
             }
-
             tickets.insert(ticket);
         }
     }
@@ -197,6 +382,80 @@ mod tests {
         assert_eq!(tickets[0], "PROJ-789");
     }
 
+    #[test]
+    fn test_extract_work_items() {
+        let messages = vec![
+            "AB#12: Fix login".to_string(),
+            "Follow-up for #12 and ab#7".to_string(),
+            "Not a work item: issue#3, org/repo#4, PROJ-5".to_string(),
+        ];
+
+        assert_eq!(extract_work_items(&messages), vec!["AB#12", "AB#7"]);
+        assert_eq!(
+            extract_for_tracker("azure_devops", &messages, &[]),
+            vec!["AB#12", "AB#7"]
+        );
+        assert_eq!(extract_for_tracker("jira", &messages, &[]), vec!["PROJ-5"]);
+        assert_eq!(work_item_id("AB#12"), Some(12));
+        assert_eq!(work_item_id("PROJ-5"), None);
+    }
+
+    #[test]
+    fn test_extract_github_issues() {
+        let messages = vec![
+            "Fix login (#12)".to_string(),
+            "Port from acme/api#7, see also PROJ-5 and AB#3".to_string(),
+        ];
+
+        assert_eq!(
+            extract_github_issues(&messages, Some("acme/web")),
+            vec!["acme/api#7", "acme/web#12"]
+        );
+        assert_eq!(extract_github_issues(&messages, None), vec!["acme/api#7"]);
+        assert_eq!(github_issue("acme/web#12"), Some(("acme/web", 12)));
+        assert_eq!(github_issue("#12"), None);
+    }
+
+    #[test]
+    fn test_searched_text_subject_and_trailers() {
+        let message = "Fix login redirect\n\nThe old flow came from PROJ-1, see CS-9 too.\nrefs PROJ-3\n\nReviewed-by: Ann\nRefs: PROJ-4, #12\nFixes:PROJ-5\nRefsX: PROJ-6\n";
+        let keys: Vec<String> = vec!["Refs".to_string(), "Fixes".to_string()];
+
+        let text = searched_text(message, &keys, false);
+        assert_eq!(text, "Fix login redirect\nPROJ-3\nPROJ-4, #12\nPROJ-5");
+        assert_eq!(
+            extract_tickets(&[text], &[]),
+            vec!["PROJ-3", "PROJ-4", "PROJ-5"]
+        );
+        assert_eq!(searched_text(message, &keys, true), message);
+    }
+
+    #[test]
+    fn test_custom_pattern() {
+        let messages = vec![
+            "ABC_123: Fix import, see #PROJ/77".to_string(),
+            "CWE_22 and DEF-4".to_string(),
+        ];
+        let pattern = compile_pattern(r"\b([A-Za-z]+_\d+)\b|#([A-Z]+/\d+)").unwrap();
+        let denylist = vec!["cwe".to_string()];
+
+        assert_eq!(
+            extract_tickets_with(&messages, &denylist, Some(&pattern), true),
+            vec!["ABC_123", "DEF-4", "PROJ/77"]
+        );
+        assert_eq!(
+            extract_tickets_with(&messages, &denylist, Some(&pattern), false),
+            vec!["ABC_123", "PROJ/77"]
+        );
+
+        let whole = compile_pattern(r"[A-Z]+_\d+").unwrap();
+        assert_eq!(
+            extract_tickets_with(&messages, &[], Some(&whole), false),
+            vec!["ABC_123", "CWE_22"]
+        );
+        assert!(compile_pattern("([A-Z]+").is_err());
+    }
+
     #[test]
     fn test_empty_denylist() {
         let messages = vec![
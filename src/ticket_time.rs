@@ -0,0 +1,222 @@
+//! Time booked on a ticket, for `harv link` and the sync picker
+//!
+//! An entry counts for a ticket when its external reference is the ticket,
+//! as for timers started by `harv sync`, or when the ticket key is in its
+//! notes, as for entries made by hand.
+
+use crate::models::{Ticket, TicketEstimate, TimeEntry};
+use crate::ticket_parser;
+use crate::time_parser::format_hours;
+use std::collections::HashMap;
+
+/// Hours already logged on a ticket
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoggedTime {
+    /// Hours on entries spent today
+    pub today: f64,
+    /// Hours on all the given entries
+    pub total: f64,
+}
+
+/// Whether `entry` was logged on `key`
+pub fn is_for_ticket(entry: &TimeEntry, key: &str, denylist: &[String]) -> bool {
+    if entry
+        .external_reference
+        .as_ref()
+        .is_some_and(|reference| reference.id.eq_ignore_ascii_case(key))
+    {
+        return true;
+    }
+    entry.notes.as_ref().is_some_and(|notes| {
+        // GitHub keys like "owner/repo#12" aren't found by the Jira-style parser
+        (key.contains('#') && notes.contains(key))
+            || ticket_parser::extract_tickets(std::slice::from_ref(notes), denylist)
+                .iter()
+                .any(|found| found.eq_ignore_ascii_case(key))
+    })
+}
+
+/// The entries logged on `key`, oldest first
+pub fn entries_for_ticket<'a>(
+    entries: &'a [TimeEntry],
+    key: &str,
+    denylist: &[String],
+) -> Vec<&'a TimeEntry> {
+    let mut found: Vec<&TimeEntry> = entries
+        .iter()
+        .filter(|entry| is_for_ticket(entry, key, denylist))
+        .collect();
+    found.sort_by(|a, b| a.spent_date.cmp(&b.spent_date).then(a.id.cmp(&b.id)));
+    found
+}
+
+/// Hours logged on `key`
+pub fn hours_on_ticket(entries: &[TimeEntry], key: &str, denylist: &[String]) -> f64 {
+    entries
+        .iter()
+        .filter(|entry| is_for_ticket(entry, key, denylist))
+        .filter_map(|entry| entry.hours)
+        .sum()
+}
+
+/// Hours logged on each of `keys`, today (`YYYY-MM-DD`) and overall
+///
+/// Keys without any logged time are left out.
+pub fn logged_per_ticket(
+    entries: &[TimeEntry],
+    keys: &[&str],
+    denylist: &[String],
+    today: &str,
+) -> HashMap<String, LoggedTime> {
+    let mut logged = HashMap::new();
+    for key in keys {
+        let mut time = LoggedTime::default();
+        for entry in entries.iter().filter(|e| is_for_ticket(e, key, denylist)) {
+            let hours = entry.hours.unwrap_or(0.0);
+            time.total += hours;
+            if entry.spent_date == today {
+                time.today += hours;
+            }
+        }
+        if time.total > 0.0 {
+            logged.insert(key.to_string(), time);
+        }
+    }
+    logged
+}
+
+/// Hours logged on `ticket`, from its time entries in `logged` or the
+/// tracker's own worklogs, whichever is more
+pub fn logged_hours(ticket: &Ticket, logged: &HashMap<String, LoggedTime>) -> f64 {
+    let booked = logged.get(&ticket.key).map_or(0.0, |time| time.total);
+    let spent = ticket.estimate.map_or(0.0, |estimate| estimate.spent_hours);
+    booked.max(spent)
+}
+
+/// Hours left on the estimate once `logged` hours are counted, negative when over
+///
+/// The tracker's remaining estimate only goes down with work logged there, so
+/// the original estimate minus `logged` is used when that is lower.
+pub fn remaining_estimate(estimate: &TicketEstimate, logged: f64) -> Option<f64> {
+    let from_original = estimate.original_hours.map(|original| original - logged);
+    match (from_original, estimate.remaining_hours) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// "logged 6:00 of 8:00 estimate", None without an original estimate
+pub fn estimate_label(estimate: &TicketEstimate, logged: f64) -> Option<String> {
+    estimate.original_hours.map(|original| {
+        format!(
+            "logged {} of {} estimate",
+            format_hours(logged),
+            format_hours(original)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ExternalReference;
+
+    fn entry(id: u64, notes: &str, reference: Option<&str>, hours: f64) -> TimeEntry {
+        TimeEntry {
+            id,
+            spent_date: "2026-10-14".to_string(),
+            hours: Some(hours),
+            notes: Some(notes.to_string()),
+            external_reference: reference.map(|id| ExternalReference {
+                id: id.to_string(),
+                group_id: "jira".to_string(),
+                permalink: String::new(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matches_reference_or_notes() {
+        let entries = [
+            entry(1, "Login form", Some("PROJ-42"), 2.0),
+            entry(2, "PROJ-42 review, PROJ-7", None, 0.5),
+            entry(3, "PROJ-420 Search", None, 1.0),
+            entry(4, "Standup", None, 0.25),
+        ];
+
+        let found: Vec<u64> = entries_for_ticket(&entries, "PROJ-42", &[])
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(found, [1, 2]);
+        assert_eq!(hours_on_ticket(&entries, "PROJ-42", &[]), 2.5);
+    }
+
+    #[test]
+    fn test_logged_per_ticket() {
+        let mut older = entry(5, "PROJ-7 Follow-up", None, 3.0);
+        older.spent_date = "2026-10-01".to_string();
+        let entries = [
+            entry(1, "Login form", Some("PROJ-42"), 2.0),
+            entry(2, "PROJ-42 review, PROJ-7", None, 0.5),
+            older,
+        ];
+
+        let logged = logged_per_ticket(
+            &entries,
+            &["PROJ-42", "PROJ-7", "PROJ-9"],
+            &[],
+            "2026-10-14",
+        );
+        assert_eq!(
+            logged["PROJ-42"],
+            LoggedTime {
+                today: 2.5,
+                total: 2.5
+            }
+        );
+        assert_eq!(
+            logged["PROJ-7"],
+            LoggedTime {
+                today: 0.5,
+                total: 3.5
+            }
+        );
+        assert!(!logged.contains_key("PROJ-9"));
+    }
+
+    #[test]
+    fn test_remaining_estimate() {
+        let estimate = TicketEstimate {
+            original_hours: Some(8.0),
+            remaining_hours: Some(8.0),
+            spent_hours: 0.0,
+        };
+        assert_eq!(remaining_estimate(&estimate, 6.0), Some(2.0));
+        assert_eq!(remaining_estimate(&estimate, 9.5), Some(-1.5));
+        assert_eq!(
+            estimate_label(&estimate, 6.0),
+            Some(format!(
+                "logged {} of {} estimate",
+                format_hours(6.0),
+                format_hours(8.0)
+            ))
+        );
+
+        // Work logged in Jira already lowered the remaining estimate
+        let tempo = TicketEstimate {
+            remaining_hours: Some(1.0),
+            spent_hours: 7.0,
+            ..estimate
+        };
+        assert_eq!(remaining_estimate(&tempo, 2.0), Some(1.0));
+
+        let remaining_only = TicketEstimate {
+            original_hours: None,
+            ..tempo
+        };
+        assert_eq!(remaining_estimate(&remaining_only, 2.0), Some(1.0));
+        assert_eq!(estimate_label(&remaining_only, 2.0), None);
+    }
+}
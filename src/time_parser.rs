@@ -1,4 +1,46 @@
 use crate::error::{HarjiraError, Result};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How hours are shown in output (`settings.time_display`)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeDisplay {
+    /// 1.75h
+    #[default]
+    Decimal,
+    /// 1:45
+    Clock,
+}
+
+static CLOCK_DISPLAY: AtomicBool = AtomicBool::new(false);
+
+/// Set how `format_hours` renders hours for the rest of the process
+pub fn set_time_display(display: TimeDisplay) {
+    CLOCK_DISPLAY.store(display == TimeDisplay::Clock, Ordering::Relaxed);
+}
+
+/// Render hours using the configured display, e.g. "1.75h" or "1:45"
+pub fn format_hours(hours: f64) -> String {
+    if CLOCK_DISPLAY.load(Ordering::Relaxed) {
+        format_hours_as(hours, TimeDisplay::Clock)
+    } else {
+        format_hours_as(hours, TimeDisplay::Decimal)
+    }
+}
+
+/// Render hours in a specific display
+pub fn format_hours_as(hours: f64, display: TimeDisplay) -> String {
+    match display {
+        TimeDisplay::Decimal => format!("{:.2}h", hours),
+        TimeDisplay::Clock => {
+            let minutes = (hours.abs() * 60.0).round() as u64;
+            let sign = if hours < 0.0 && minutes > 0 { "-" } else { "" };
+            format!("{}{}:{:02}", sign, minutes / 60, minutes % 60)
+        }
+    }
+}
 
 /// Parse hours from either decimal format (e.g., "1.5") or colon format (e.g., "1:30")
 ///
@@ -86,6 +128,26 @@ fn parse_colon_format(input: &str) -> Result<f64> {
     Ok(total_hours)
 }
 
+/// Parse a time of day in 24-hour "HH:MM" format (e.g., "18:30")
+pub fn parse_clock_time(input: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(input.trim(), "%H:%M").map_err(|_| {
+        HarjiraError::InvalidEntry(format!(
+            "Invalid time: '{}'. Use HH:MM (e.g., 18:30)",
+            input
+        ))
+    })
+}
+
+/// Hours between `stop_at` on `spent_date` and `now`, None if `now` is earlier
+pub fn hours_past(spent_date: &str, stop_at: NaiveTime, now: NaiveDateTime) -> Option<f64> {
+    let date = NaiveDate::parse_from_str(spent_date, "%Y-%m-%d").ok()?;
+    let overrun = now - date.and_time(stop_at);
+    if overrun.num_seconds() <= 0 {
+        return None;
+    }
+    Some(overrun.num_seconds() as f64 / 3600.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,4 +287,45 @@ mod tests {
         // Large hours with minutes
         assert_eq!(parse_hours("20:30").unwrap(), 20.5);
     }
+
+    #[test]
+    fn test_parse_clock_time() {
+        assert_eq!(
+            parse_clock_time("18:30").unwrap(),
+            NaiveTime::from_hms_opt(18, 30, 0).unwrap()
+        );
+        assert!(parse_clock_time("25:00").is_err());
+        assert!(parse_clock_time("6pm").is_err());
+    }
+
+    #[test]
+    fn test_hours_past() {
+        let stop_at = NaiveTime::from_hms_opt(18, 30, 0).unwrap();
+        let at = |date: &str, time: &str| {
+            NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+        };
+
+        assert_eq!(
+            hours_past("2025-01-10", stop_at, at("2025-01-10", "20:00")),
+            Some(1.5)
+        );
+        assert_eq!(
+            hours_past("2025-01-10", stop_at, at("2025-01-10", "17:00")),
+            None
+        );
+        // Timer left running overnight
+        assert_eq!(
+            hours_past("2025-01-10", stop_at, at("2025-01-11", "08:30")),
+            Some(14.0)
+        );
+    }
+
+    #[test]
+    fn test_format_hours_as() {
+        assert_eq!(format_hours_as(1.75, TimeDisplay::Decimal), "1.75h");
+        assert_eq!(format_hours_as(1.75, TimeDisplay::Clock), "1:45");
+        assert_eq!(format_hours_as(0.05, TimeDisplay::Clock), "0:03");
+        assert_eq!(format_hours_as(7.999, TimeDisplay::Clock), "8:00");
+        assert_eq!(format_hours_as(-0.5, TimeDisplay::Clock), "-0:30");
+    }
 }
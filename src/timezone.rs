@@ -0,0 +1,275 @@
+use crate::error::{HarjiraError, Result};
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const TIMEZONE_CACHE_VERSION: u8 = 1;
+
+/// How long the Harvest profile timezone is reused before asking again
+const CACHE_TTL_HOURS: i64 = 24;
+
+/// Harvest (Rails) timezone names and their IANA equivalents
+const HARVEST_ZONES: &[(&str, &str)] = &[
+    ("International Date Line West", "Etc/GMT+12"),
+    ("American Samoa", "Pacific/Pago_Pago"),
+    ("Midway Island", "Pacific/Midway"),
+    ("Hawaii", "Pacific/Honolulu"),
+    ("Alaska", "America/Juneau"),
+    ("Pacific Time (US & Canada)", "America/Los_Angeles"),
+    ("Tijuana", "America/Tijuana"),
+    ("Arizona", "America/Phoenix"),
+    ("Mazatlan", "America/Mazatlan"),
+    ("Mountain Time (US & Canada)", "America/Denver"),
+    ("Central America", "America/Guatemala"),
+    ("Central Time (US & Canada)", "America/Chicago"),
+    ("Chihuahua", "America/Chihuahua"),
+    ("Guadalajara", "America/Mexico_City"),
+    ("Mexico City", "America/Mexico_City"),
+    ("Monterrey", "America/Monterrey"),
+    ("Saskatchewan", "America/Regina"),
+    ("Bogota", "America/Bogota"),
+    ("Eastern Time (US & Canada)", "America/New_York"),
+    ("Indiana (East)", "America/Indiana/Indianapolis"),
+    ("Lima", "America/Lima"),
+    ("Quito", "America/Lima"),
+    ("Atlantic Time (Canada)", "America/Halifax"),
+    ("Caracas", "America/Caracas"),
+    ("Georgetown", "America/Guyana"),
+    ("La Paz", "America/La_Paz"),
+    ("Puerto Rico", "America/Puerto_Rico"),
+    ("Santiago", "America/Santiago"),
+    ("Newfoundland", "America/St_Johns"),
+    ("Brasilia", "America/Sao_Paulo"),
+    ("Buenos Aires", "America/Argentina/Buenos_Aires"),
+    ("Montevideo", "America/Montevideo"),
+    ("Greenland", "America/Godthab"),
+    ("Mid-Atlantic", "Atlantic/South_Georgia"),
+    ("Azores", "Atlantic/Azores"),
+    ("Cape Verde Is.", "Atlantic/Cape_Verde"),
+    ("Casablanca", "Africa/Casablanca"),
+    ("Dublin", "Europe/Dublin"),
+    ("Edinburgh", "Europe/London"),
+    ("Lisbon", "Europe/Lisbon"),
+    ("London", "Europe/London"),
+    ("Monrovia", "Africa/Monrovia"),
+    ("UTC", "Etc/UTC"),
+    ("Amsterdam", "Europe/Amsterdam"),
+    ("Belgrade", "Europe/Belgrade"),
+    ("Berlin", "Europe/Berlin"),
+    ("Bern", "Europe/Zurich"),
+    ("Bratislava", "Europe/Bratislava"),
+    ("Brussels", "Europe/Brussels"),
+    ("Budapest", "Europe/Budapest"),
+    ("Copenhagen", "Europe/Copenhagen"),
+    ("Ljubljana", "Europe/Ljubljana"),
+    ("Madrid", "Europe/Madrid"),
+    ("Paris", "Europe/Paris"),
+    ("Prague", "Europe/Prague"),
+    ("Rome", "Europe/Rome"),
+    ("Sarajevo", "Europe/Sarajevo"),
+    ("Skopje", "Europe/Skopje"),
+    ("Stockholm", "Europe/Stockholm"),
+    ("Vienna", "Europe/Vienna"),
+    ("Warsaw", "Europe/Warsaw"),
+    ("West Central Africa", "Africa/Algiers"),
+    ("Zagreb", "Europe/Zagreb"),
+    ("Zurich", "Europe/Zurich"),
+    ("Athens", "Europe/Athens"),
+    ("Bucharest", "Europe/Bucharest"),
+    ("Cairo", "Africa/Cairo"),
+    ("Harare", "Africa/Harare"),
+    ("Helsinki", "Europe/Helsinki"),
+    ("Jerusalem", "Asia/Jerusalem"),
+    ("Kaliningrad", "Europe/Kaliningrad"),
+    ("Kyiv", "Europe/Kiev"),
+    ("Pretoria", "Africa/Johannesburg"),
+    ("Riga", "Europe/Riga"),
+    ("Sofia", "Europe/Sofia"),
+    ("Tallinn", "Europe/Tallinn"),
+    ("Vilnius", "Europe/Vilnius"),
+    ("Baghdad", "Asia/Baghdad"),
+    ("Istanbul", "Europe/Istanbul"),
+    ("Kuwait", "Asia/Kuwait"),
+    ("Minsk", "Europe/Minsk"),
+    ("Moscow", "Europe/Moscow"),
+    ("Nairobi", "Africa/Nairobi"),
+    ("Riyadh", "Asia/Riyadh"),
+    ("St. Petersburg", "Europe/Moscow"),
+    ("Volgograd", "Europe/Volgograd"),
+    ("Tehran", "Asia/Tehran"),
+    ("Abu Dhabi", "Asia/Muscat"),
+    ("Baku", "Asia/Baku"),
+    ("Muscat", "Asia/Muscat"),
+    ("Samara", "Europe/Samara"),
+    ("Tbilisi", "Asia/Tbilisi"),
+    ("Yerevan", "Asia/Yerevan"),
+    ("Kabul", "Asia/Kabul"),
+    ("Almaty", "Asia/Almaty"),
+    ("Ekaterinburg", "Asia/Yekaterinburg"),
+    ("Islamabad", "Asia/Karachi"),
+    ("Karachi", "Asia/Karachi"),
+    ("Tashkent", "Asia/Tashkent"),
+    ("Chennai", "Asia/Kolkata"),
+    ("Kolkata", "Asia/Kolkata"),
+    ("Mumbai", "Asia/Kolkata"),
+    ("New Delhi", "Asia/Kolkata"),
+    ("Sri Jayawardenepura", "Asia/Colombo"),
+    ("Kathmandu", "Asia/Kathmandu"),
+    ("Astana", "Asia/Dhaka"),
+    ("Dhaka", "Asia/Dhaka"),
+    ("Urumqi", "Asia/Urumqi"),
+    ("Rangoon", "Asia/Rangoon"),
+    ("Bangkok", "Asia/Bangkok"),
+    ("Hanoi", "Asia/Bangkok"),
+    ("Jakarta", "Asia/Jakarta"),
+    ("Krasnoyarsk", "Asia/Krasnoyarsk"),
+    ("Novosibirsk", "Asia/Novosibirsk"),
+    ("Beijing", "Asia/Shanghai"),
+    ("Chongqing", "Asia/Chongqing"),
+    ("Hong Kong", "Asia/Hong_Kong"),
+    ("Irkutsk", "Asia/Irkutsk"),
+    ("Kuala Lumpur", "Asia/Kuala_Lumpur"),
+    ("Perth", "Australia/Perth"),
+    ("Singapore", "Asia/Singapore"),
+    ("Taipei", "Asia/Taipei"),
+    ("Ulaanbaatar", "Asia/Ulaanbaatar"),
+    ("Osaka", "Asia/Tokyo"),
+    ("Sapporo", "Asia/Tokyo"),
+    ("Seoul", "Asia/Seoul"),
+    ("Tokyo", "Asia/Tokyo"),
+    ("Yakutsk", "Asia/Yakutsk"),
+    ("Adelaide", "Australia/Adelaide"),
+    ("Darwin", "Australia/Darwin"),
+    ("Brisbane", "Australia/Brisbane"),
+    ("Canberra", "Australia/Melbourne"),
+    ("Guam", "Pacific/Guam"),
+    ("Hobart", "Australia/Hobart"),
+    ("Melbourne", "Australia/Melbourne"),
+    ("Port Moresby", "Pacific/Port_Moresby"),
+    ("Sydney", "Australia/Sydney"),
+    ("Vladivostok", "Asia/Vladivostok"),
+    ("Magadan", "Asia/Magadan"),
+    ("New Caledonia", "Pacific/Noumea"),
+    ("Solomon Is.", "Pacific/Guadalcanal"),
+    ("Srednekolymsk", "Asia/Srednekolymsk"),
+    ("Auckland", "Pacific/Auckland"),
+    ("Fiji", "Pacific/Fiji"),
+    ("Kamchatka", "Asia/Kamchatka"),
+    ("Marshall Is.", "Pacific/Majuro"),
+    ("Wellington", "Pacific/Auckland"),
+    ("Chatham Is.", "Pacific/Chatham"),
+    ("Nuku'alofa", "Pacific/Tongatapu"),
+    ("Samoa", "Pacific/Apia"),
+    ("Tokelau Is.", "Pacific/Fakaofo"),
+];
+
+/// IANA name for a timezone as Harvest or the user writes it
+///
+/// IANA names ("Europe/Amsterdam") pass through unchanged. Harvest names
+/// ("Amsterdam", "Eastern Time (US & Canada)") are mapped.
+pub fn to_iana(name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.contains('/') || name == "UTC" {
+        return Some(name.to_string());
+    }
+    HARVEST_ZONES
+        .iter()
+        .find(|(harvest, _)| harvest.eq_ignore_ascii_case(name))
+        .map(|(_, iana)| iana.to_string())
+}
+
+/// Use `name` as the local timezone for the rest of the process
+///
+/// Sets `TZ`, which chrono honours for every `Local` date, so git day
+/// ranges and Harvest date filters follow it. Must be called before any
+/// other threads are started.
+pub fn apply(name: &str) -> Result<()> {
+    let iana = to_iana(name)
+        .ok_or_else(|| HarjiraError::Config(format!("Unknown timezone: '{}'", name)))?;
+
+    #[cfg(unix)]
+    {
+        let zoneinfo = env::var("TZDIR").unwrap_or_else(|_| "/usr/share/zoneinfo".to_string());
+        if !PathBuf::from(&zoneinfo).join(&iana).exists() {
+            return Err(HarjiraError::Config(format!(
+                "Timezone '{}' not found in {}",
+                iana, zoneinfo
+            )));
+        }
+    }
+
+    debug!("Using timezone {}", iana);
+    env::set_var("TZ", iana);
+    Ok(())
+}
+
+/// Harvest profile timezone remembered between runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimezoneCache {
+    version: u8,
+    pub fetched_at: DateTime<Utc>,
+    /// Harvest timezone name, None if the profile has none
+    pub timezone: Option<String>,
+}
+
+impl TimezoneCache {
+    pub fn new(timezone: Option<String>) -> Self {
+        Self {
+            version: TIMEZONE_CACHE_VERSION,
+            fetched_at: Utc::now(),
+            timezone,
+        }
+    }
+
+    /// Load the cached timezone if it is still fresh
+    pub fn load_fresh() -> Option<Self> {
+        let path = timezone_cache_path().ok()?;
+        let contents = fs::read_to_string(path).ok()?;
+        let cache: TimezoneCache = serde_json::from_str(&contents).ok()?;
+
+        let fresh = cache.version <= TIMEZONE_CACHE_VERSION
+            && Utc::now() - cache.fetched_at < Duration::hours(CACHE_TTL_HOURS);
+        fresh.then_some(cache)
+    }
+
+    /// Save the cache, logs errors but doesn't fail
+    pub fn save(&self) {
+        if let Err(e) = self.save_internal() {
+            warn!("Failed to save timezone cache: {}", e);
+        }
+    }
+
+    fn save_internal(&self) -> Result<()> {
+        let path = timezone_cache_path()?;
+
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(self)?)?;
+
+        debug!("Saved timezone cache to {}", path.display());
+        Ok(())
+    }
+}
+
+fn timezone_cache_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| HarjiraError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("harv").join("timezone_cache.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_iana() {
+        assert_eq!(to_iana("Amsterdam").as_deref(), Some("Europe/Amsterdam"));
+        assert_eq!(
+            to_iana("eastern time (us & canada)").as_deref(),
+            Some("America/New_York")
+        );
+        assert_eq!(to_iana("Asia/Kolkata").as_deref(), Some("Asia/Kolkata"));
+        assert_eq!(to_iana("Atlantis"), None);
+    }
+}
@@ -0,0 +1,413 @@
+//! Toggl Track backend
+//!
+//! Timers map one-to-one onto Toggl time entries. Toggl can't restart an
+//! entry, so restarting starts a new one with the same description and
+//! project, which is what "continue" does in the Toggl apps.
+
+use crate::backend::TimeBackend;
+use crate::config::TogglConfig;
+use crate::error::{HarjiraError, Result};
+use crate::http::{ApiClient, ApiRequest};
+use crate::models::{
+    Context, TimeEntry, TogglProject, TogglTimeEntry, TogglTimeEntryRequest, UpdateTimeEntryRequest,
+};
+use crate::status_cache;
+use chrono::{Duration, Local, NaiveDate, SecondsFormat, Utc};
+use log::{debug, info};
+use reqwest::blocking::Response;
+use serde::de::DeserializeOwned;
+
+const DEFAULT_BASE_URL: &str = "https://api.track.toggl.com/api/v9";
+
+/// Sent as `created_with` so entries show where they came from
+const CREATED_WITH: &str = "harv";
+
+pub struct TogglClient {
+    client: ApiClient,
+    base_url: String,
+    config: TogglConfig,
+}
+
+impl TogglClient {
+    pub fn new(config: TogglConfig) -> Result<Self> {
+        if config.api_token.is_empty() {
+            return Err(HarjiraError::Config(
+                "No Toggl API token configured in [toggl]".to_string(),
+            ));
+        }
+
+        let base_url = config
+            .base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_BASE_URL)
+            .trim_end_matches('/')
+            .to_string();
+
+        Ok(Self {
+            client: ApiClient::default(),
+            base_url,
+            config,
+        })
+    }
+
+    /// Send requests to another API root, e.g. a mock server in tests
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Toggl takes the API token as the user name and "api_token" as password
+    fn authorized(&self, request: ApiRequest) -> ApiRequest {
+        request.basic_auth(&self.config.api_token, Some("api_token"))
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, String)]) -> Result<T> {
+        let url = format!("{}{}", self.base_url, path);
+        debug!("GET {}", url);
+
+        let response = self
+            .authorized(self.client.get(&url))
+            .query(query)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Request failed: {}", e)))?;
+
+        check_response(response)?
+            .json()
+            .map_err(|e| HarjiraError::Toggl(format!("Failed to parse response: {}", e)))
+    }
+
+    fn workspace_url(&self, path: &str) -> String {
+        format!(
+            "{}/workspaces/{}{}",
+            self.base_url, self.config.workspace_id, path
+        )
+    }
+
+    /// The user's entries that started on a day (YYYY-MM-DD)
+    pub fn get_time_entries_for_date(&self, date: &str) -> Result<Vec<TimeEntry>> {
+        let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| HarjiraError::InvalidEntry(format!("Invalid date: {}", date)))?;
+        let next_day = (day + Duration::days(1)).format("%Y-%m-%d").to_string();
+
+        let entries: Vec<TogglTimeEntry> = self.get(
+            "/me/time_entries",
+            &[("start_date", date.to_string()), ("end_date", next_day)],
+        )?;
+
+        let now = Utc::now();
+        let mut entries: Vec<TimeEntry> = entries
+            .iter()
+            .map(|e| e.to_time_entry(now))
+            .filter(|e| e.spent_date == date)
+            .collect();
+        // Toggl lists newest first, harv shows the day in order
+        entries.reverse();
+        debug!("Retrieved {} Toggl entries for {}", entries.len(), date);
+        Ok(entries)
+    }
+
+    /// Active projects in the workspace
+    pub fn get_projects(&self) -> Result<Vec<TogglProject>> {
+        let path = format!("/workspaces/{}/projects", self.config.workspace_id);
+        let projects: Vec<TogglProject> = self.get(&path, &[("active", "true".to_string())])?;
+        Ok(projects.into_iter().filter(|p| p.active).collect())
+    }
+
+    /// Log a finished entry of `hours` on `date`, starting at 09:00 local time
+    pub fn create_stopped_time_entry(
+        &self,
+        description: &str,
+        hours: f64,
+        date: &str,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let start = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(9, 0, 0))
+            .and_then(|t| t.and_local_timezone(Local).single())
+            .ok_or_else(|| HarjiraError::InvalidEntry(format!("Invalid date: {}", date)))?;
+
+        let request = TogglTimeEntryRequest {
+            created_with: CREATED_WITH.to_string(),
+            workspace_id: self.config.workspace_id,
+            project_id: self.config.project_id,
+            description: description.to_string(),
+            start: start.to_rfc3339_opts(SecondsFormat::Secs, true),
+            duration: (hours * 3600.0).round() as i64,
+        };
+        self.create(&request, ctx)
+    }
+
+    fn create(&self, request: &TogglTimeEntryRequest, ctx: &Context) -> Result<TimeEntry> {
+        let url = self.workspace_url("/time_entries");
+
+        if ctx.dry_run {
+            crate::plan::record("POST", &url, Some(request));
+            info!("[DRY RUN] Would create Toggl entry: {:?}", request);
+            return Ok(TimeEntry {
+                id: 0,
+                spent_date: Local::now().format("%Y-%m-%d").to_string(),
+                hours: Some(request.duration.max(0) as f64 / 3600.0),
+                notes: Some(request.description.clone()),
+                is_running: request.duration < 0,
+                project: None,
+                task: None,
+                started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
+            });
+        }
+
+        debug!("POST {}", url);
+        let response = self
+            .authorized(self.client.post(&url))
+            .json(request)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to create time entry: {}", e)))?;
+
+        let entry: TogglTimeEntry = check_response(response)?.json().map_err(|e| {
+            HarjiraError::Toggl(format!("Failed to parse created time entry: {}", e))
+        })?;
+
+        status_cache::invalidate();
+        info!("Created Toggl entry {}: {}", entry.id, request.description);
+        Ok(entry.to_time_entry(Utc::now()))
+    }
+
+    /// Start a running timer
+    fn start(
+        &self,
+        description: &str,
+        project_id: Option<u64>,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let request = TogglTimeEntryRequest {
+            created_with: CREATED_WITH.to_string(),
+            workspace_id: self.config.workspace_id,
+            project_id,
+            description: description.to_string(),
+            start: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            duration: -1,
+        };
+        self.create(&request, ctx)
+    }
+}
+
+impl TimeBackend for TogglClient {
+    fn name(&self) -> &str {
+        "Toggl Track"
+    }
+
+    fn get_time_entries_for_date(&self, date: &str) -> Result<Vec<TimeEntry>> {
+        TogglClient::get_time_entries_for_date(self, date)
+    }
+
+    fn get_running_timers(&self) -> Result<Vec<TimeEntry>> {
+        let current: Option<TogglTimeEntry> = self.get("/me/time_entries/current", &[])?;
+        Ok(current
+            .filter(TogglTimeEntry::is_running)
+            .map(|e| e.to_time_entry(Utc::now()))
+            .into_iter()
+            .collect())
+    }
+
+    fn create_time_entry(
+        &self,
+        ticket_key: &str,
+        description: &str,
+        _ticket_url: &str,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let notes = format!("{} - {}", ticket_key, description);
+        self.start(&notes, self.config.project_id, ctx)
+    }
+
+    fn log_time_entry(
+        &self,
+        ticket_key: &str,
+        description: &str,
+        _ticket_url: &str,
+        hours: f64,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let notes = format!("{} - {}", ticket_key, description);
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        self.create_stopped_time_entry(&notes, hours, &today, ctx)
+    }
+
+    fn stop_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry> {
+        let url = self.workspace_url(&format!("/time_entries/{}/stop", entry_id));
+
+        if ctx.dry_run {
+            crate::plan::record::<()>("PATCH", &url, None);
+            info!("[DRY RUN] Would stop Toggl entry {}", entry_id);
+            return Ok(TimeEntry {
+                id: entry_id,
+                spent_date: Local::now().format("%Y-%m-%d").to_string(),
+                hours: Some(0.0),
+                notes: None,
+                is_running: false,
+                project: None,
+                task: None,
+                started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
+            });
+        }
+
+        debug!("PATCH {}", url);
+        let response = self
+            .authorized(self.client.patch(&url))
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to stop time entry: {}", e)))?;
+
+        let entry: TogglTimeEntry = check_response(response)?.json().map_err(|e| {
+            HarjiraError::Toggl(format!("Failed to parse stopped time entry: {}", e))
+        })?;
+
+        status_cache::invalidate();
+        info!("Stopped Toggl entry {}", entry_id);
+        Ok(entry.to_time_entry(Utc::now()))
+    }
+
+    fn restart_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<TimeEntry> {
+        let entry: TogglTimeEntry = self.get(&format!("/me/time_entries/{}", entry_id), &[])?;
+        self.start(
+            entry.description.as_deref().unwrap_or_default(),
+            entry.project_id,
+            ctx,
+        )
+    }
+
+    fn update_time_entry(
+        &self,
+        entry_id: u64,
+        update: &UpdateTimeEntryRequest,
+        ctx: &Context,
+    ) -> Result<TimeEntry> {
+        let url = self.workspace_url(&format!("/time_entries/{}", entry_id));
+        let mut body = serde_json::Map::new();
+        if let Some(hours) = update.hours {
+            body.insert("duration".into(), ((hours * 3600.0).round() as i64).into());
+        }
+        if let Some(notes) = &update.notes {
+            body.insert("description".into(), notes.clone().into());
+        }
+
+        if ctx.dry_run {
+            crate::plan::record("PUT", &url, Some(&body));
+            info!(
+                "[DRY RUN] Would update Toggl entry {}: {:?}",
+                entry_id, update
+            );
+            return Ok(TimeEntry {
+                id: entry_id,
+                spent_date: Local::now().format("%Y-%m-%d").to_string(),
+                hours: update.hours,
+                notes: update.notes.clone(),
+                is_running: false,
+                project: None,
+                task: None,
+                started_time: None,
+                billable: false,
+                billable_rate: None,
+                external_reference: None,
+                user: None,
+            });
+        }
+
+        debug!("PUT {}", url);
+        let response = self
+            .authorized(self.client.put(&url))
+            .json(&body)
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to update time entry: {}", e)))?;
+
+        let entry: TogglTimeEntry = check_response(response)?.json().map_err(|e| {
+            HarjiraError::Toggl(format!("Failed to parse updated time entry: {}", e))
+        })?;
+
+        status_cache::invalidate();
+        info!("Updated Toggl entry {}: {:?}", entry_id, update);
+        Ok(entry.to_time_entry(Utc::now()))
+    }
+
+    fn delete_time_entry(&self, entry_id: u64, ctx: &Context) -> Result<()> {
+        let url = self.workspace_url(&format!("/time_entries/{}", entry_id));
+
+        if ctx.dry_run {
+            crate::plan::record::<()>("DELETE", &url, None);
+            info!("[DRY RUN] Would delete Toggl entry {}", entry_id);
+            return Ok(());
+        }
+
+        debug!("DELETE {}", url);
+        let response = self
+            .authorized(self.client.delete(&url))
+            .send()
+            .map_err(|e| HarjiraError::Network(format!("Failed to delete time entry: {}", e)))?;
+        check_response(response)?;
+
+        status_cache::invalidate();
+        info!("Deleted Toggl entry {}", entry_id);
+        Ok(())
+    }
+}
+
+fn check_response(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    // Toggl errors are plain text or a JSON string
+    let body = response.text().unwrap_or_default();
+    let message = serde_json::from_str::<String>(&body).unwrap_or_else(|_| body.trim().to_string());
+
+    if status == 401 || status == 403 {
+        return Err(HarjiraError::Auth(
+            "Toggl authentication failed. Check toggl.api_token in your config.".to_string(),
+        ));
+    }
+    Err(HarjiraError::Toggl(format!("{}: {}", status, message)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(start: &str, duration: i64) -> TogglTimeEntry {
+        TogglTimeEntry {
+            id: 9,
+            project_id: Some(3),
+            description: Some("ABC-1 - Fix login".to_string()),
+            start: start.to_string(),
+            duration,
+            billable: false,
+        }
+    }
+
+    #[test]
+    fn test_stopped_entry_hours() {
+        let entry = entry("2026-03-02T09:00:00Z", 5400).to_time_entry(Utc::now());
+        assert_eq!(entry.hours, Some(1.5));
+        assert!(!entry.is_running);
+    }
+
+    #[test]
+    fn test_running_entry_measured_to_now() {
+        let start = Utc::now() - Duration::minutes(30);
+        let entry = entry(&start.to_rfc3339(), -1).to_time_entry(Utc::now());
+        assert!(entry.is_running);
+        let hours = entry.hours.unwrap();
+        assert!((0.49..0.51).contains(&hours), "{}", hours);
+        assert_eq!(
+            entry.spent_date,
+            start.with_timezone(&Local).format("%Y-%m-%d").to_string()
+        );
+    }
+}
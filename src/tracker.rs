@@ -0,0 +1,72 @@
+use crate::error::Result;
+use crate::jira::JiraClient;
+use crate::models::Ticket;
+use log::warn;
+
+/// Issue tracker that ticket keys in commits and branches refer to
+///
+/// Tickets come back as plain `Ticket` values so `sync`, `log` and commit
+/// inference work the same whichever tracker is configured.
+pub trait IssueTracker {
+    /// Name shown in messages, e.g. "Jira"
+    fn name(&self) -> &str;
+
+    /// Title and status of one issue
+    fn get_issue(&self, ticket_key: &str) -> Result<Ticket>;
+
+    /// Issues assigned to the current user that are open or recently updated
+    fn get_my_recent_issues(&self, max_results: u32) -> Result<Vec<Ticket>>;
+
+    /// Link to the issue, used as the time entry's external reference
+    fn get_ticket_url(&self, ticket_key: &str) -> String;
+
+    /// Fetch several issues, reporting `(fetched, total)` before each one
+    ///
+    /// Issues that can't be fetched are kept with the error as their summary,
+    /// so a typo in one commit doesn't hide the other tickets.
+    fn get_issues_with_progress(
+        &self,
+        ticket_keys: &[String],
+        on_progress: &mut dyn FnMut(usize, usize),
+    ) -> Vec<Ticket> {
+        let mut tickets = Vec::new();
+
+        for (idx, key) in ticket_keys.iter().enumerate() {
+            on_progress(idx, ticket_keys.len());
+            match self.get_issue(key) {
+                Ok(ticket) => tickets.push(ticket),
+                Err(e) => {
+                    warn!("Failed to fetch {} ticket {}: {}", self.name(), key, e);
+                    tickets.push(Ticket {
+                        key: key.clone(),
+                        summary: format!("(Failed to fetch: {})", e),
+                        status: None,
+                        confidence: None,
+                        in_sprint: false,
+                        estimate: None,
+                    });
+                }
+            }
+        }
+
+        tickets
+    }
+}
+
+impl IssueTracker for JiraClient {
+    fn name(&self) -> &str {
+        "Jira"
+    }
+
+    fn get_issue(&self, ticket_key: &str) -> Result<Ticket> {
+        JiraClient::get_issue(self, ticket_key)
+    }
+
+    fn get_my_recent_issues(&self, max_results: u32) -> Result<Vec<Ticket>> {
+        JiraClient::get_my_recent_issues(self, max_results)
+    }
+
+    fn get_ticket_url(&self, ticket_key: &str) -> String {
+        JiraClient::get_ticket_url(self, ticket_key)
+    }
+}
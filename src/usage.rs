@@ -1,5 +1,5 @@
 use crate::error::{HarjiraError, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use log::{debug, warn};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
@@ -9,6 +9,15 @@ use std::path::PathBuf;
 
 const USAGE_FILE_VERSION: u8 = 1;
 
+/// Days after which a usage score counts for half as much
+const HALF_LIFE_DAYS: f64 = 14.0;
+
+/// Default age for `harv cache prune`, in months
+pub const DEFAULT_PRUNE_MONTHS: u32 = 6;
+
+/// Records unused for this many months are dropped whenever the cache is saved
+const AUTO_PRUNE_MONTHS: u32 = 12;
+
 /// Cache of project and task usage data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageCache {
@@ -17,6 +26,15 @@ pub struct UsageCache {
     projects: HashMap<u64, UsageRecord>,
     #[serde(default)]
     tasks: HashMap<u64, UsageRecord>,
+    /// Task usage within a specific project, keyed by `pair_key`
+    #[serde(default)]
+    project_tasks: HashMap<String, UsageRecord>,
+}
+
+impl Default for UsageCache {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Record of when and how often an item was used
@@ -24,6 +42,41 @@ pub struct UsageCache {
 pub struct UsageRecord {
     last_used: DateTime<Utc>,
     use_count: u64,
+    /// Use count with exponential decay applied, as of `last_used`
+    #[serde(default)]
+    decayed_count: Option<f64>,
+}
+
+impl UsageRecord {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            last_used: now,
+            use_count: 1,
+            decayed_count: Some(1.0),
+        }
+    }
+
+    /// Record another use at `now`
+    fn record_use(&mut self, now: DateTime<Utc>) {
+        self.decayed_count = Some(self.score_at(now) + 1.0);
+        self.last_used = now;
+        self.use_count += 1;
+    }
+
+    /// Blended recency + frequency score: uses decay by half every `HALF_LIFE_DAYS`
+    fn score_at(&self, now: DateTime<Utc>) -> f64 {
+        // Records written before decay was tracked fall back to the raw count
+        let count = self.decayed_count.unwrap_or(self.use_count as f64);
+        count * decay_factor(self.last_used, now)
+    }
+
+    fn to_score(&self, now: DateTime<Utc>) -> UsageScore {
+        UsageScore {
+            last_used: self.last_used,
+            use_count: self.use_count,
+            score: self.score_at(now),
+        }
+    }
 }
 
 /// Score for sorting items by usage
@@ -31,6 +84,14 @@ pub struct UsageRecord {
 pub struct UsageScore {
     pub last_used: DateTime<Utc>,
     pub use_count: u64,
+    /// Decayed use count, higher is more relevant
+    pub score: f64,
+}
+
+/// Fraction of a score left after the time between `then` and `now`
+fn decay_factor(then: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let elapsed_days = (now - then).num_seconds().max(0) as f64 / 86_400.0;
+    0.5_f64.powf(elapsed_days / HALF_LIFE_DAYS)
 }
 
 impl UsageCache {
@@ -40,6 +101,7 @@ impl UsageCache {
             version: USAGE_FILE_VERSION,
             projects: HashMap::new(),
             tasks: HashMap::new(),
+            project_tasks: HashMap::new(),
         }
     }
 
@@ -100,28 +162,14 @@ impl UsageCache {
     fn save_internal(&self) -> Result<()> {
         let path = usage_file_path()?;
 
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
-        // Write atomically using temp file + rename
-        let temp_path = path.with_extension("tmp");
-        let json = serde_json::to_string_pretty(self)?;
-
-        fs::write(&temp_path, json)?;
-
-        // Set permissions to 600 (user read/write only)
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&temp_path)?.permissions();
-            perms.set_mode(0o600);
-            fs::set_permissions(&temp_path, perms)?;
+        // Keep the file from growing forever
+        let mut pruned = self.clone();
+        let removed = pruned.prune(AUTO_PRUNE_MONTHS, Utc::now());
+        if removed > 0 {
+            debug!("Pruned {} stale usage records", removed);
         }
 
-        // Atomic rename
-        fs::rename(&temp_path, &path)?;
+        crate::fs::write_private_atomic(&path, serde_json::to_string_pretty(&pruned)?)?;
 
         debug!("Saved usage cache to {}", path.display());
         Ok(())
@@ -129,52 +177,99 @@ impl UsageCache {
 
     /// Record that a project was used
     pub fn record_project_usage(&mut self, project_id: u64) {
+        let now = Utc::now();
         self.projects
             .entry(project_id)
-            .and_modify(|record| {
-                record.last_used = Utc::now();
-                record.use_count += 1;
-            })
-            .or_insert(UsageRecord {
-                last_used: Utc::now(),
-                use_count: 1,
-            });
+            .and_modify(|record| record.record_use(now))
+            .or_insert_with(|| UsageRecord::new(now));
         debug!("Recorded project usage: {}", project_id);
     }
 
     /// Record that a task was used
     pub fn record_task_usage(&mut self, task_id: u64) {
+        let now = Utc::now();
         self.tasks
             .entry(task_id)
-            .and_modify(|record| {
-                record.last_used = Utc::now();
-                record.use_count += 1;
-            })
-            .or_insert(UsageRecord {
-                last_used: Utc::now(),
-                use_count: 1,
-            });
+            .and_modify(|record| record.record_use(now))
+            .or_insert_with(|| UsageRecord::new(now));
         debug!("Recorded task usage: {}", task_id);
     }
 
+    /// Record that a task was used within a project
+    pub fn record_project_task_usage(&mut self, project_id: u64, task_id: u64) {
+        let now = Utc::now();
+        self.project_tasks
+            .entry(pair_key(project_id, task_id))
+            .and_modify(|record| record.record_use(now))
+            .or_insert_with(|| UsageRecord::new(now));
+        debug!("Recorded task usage: {} in project {}", task_id, project_id);
+    }
+
+    /// Record that an entry was created or started on a project/task
+    pub fn record_entry_usage(&mut self, project_id: u64, task_id: u64) {
+        self.record_project_usage(project_id);
+        self.record_task_usage(task_id);
+        self.record_project_task_usage(project_id, task_id);
+    }
+
     /// Get usage score for a project
     pub fn get_project_score(&self, project_id: u64) -> Option<UsageScore> {
-        self.projects.get(&project_id).map(|record| UsageScore {
-            last_used: record.last_used,
-            use_count: record.use_count,
-        })
+        self.projects
+            .get(&project_id)
+            .map(|record| record.to_score(Utc::now()))
     }
 
     /// Get usage score for a task
     pub fn get_task_score(&self, task_id: u64) -> Option<UsageScore> {
-        self.tasks.get(&task_id).map(|record| UsageScore {
-            last_used: record.last_used,
-            use_count: record.use_count,
-        })
+        self.tasks
+            .get(&task_id)
+            .map(|record| record.to_score(Utc::now()))
+    }
+
+    /// Get usage score for a task within a project
+    ///
+    /// Projects without any pair records yet (caches written before pairs
+    /// were tracked) fall back to the global task score.
+    pub fn get_project_task_score(&self, project_id: u64, task_id: u64) -> Option<UsageScore> {
+        if let Some(record) = self.project_tasks.get(&pair_key(project_id, task_id)) {
+            return Some(record.to_score(Utc::now()));
+        }
+
+        let prefix = format!("{}:", project_id);
+        if self
+            .project_tasks
+            .keys()
+            .any(|key| key.starts_with(&prefix))
+        {
+            None
+        } else {
+            self.get_task_score(task_id)
+        }
+    }
+
+    /// Drop records not used in the last `months` months, returns how many were removed
+    pub fn prune(&mut self, months: u32, now: DateTime<Utc>) -> usize {
+        let cutoff = now - Duration::days(30 * months as i64);
+        let before = self.len();
+        self.projects.retain(|_, record| record.last_used >= cutoff);
+        self.tasks.retain(|_, record| record.last_used >= cutoff);
+        self.project_tasks
+            .retain(|_, record| record.last_used >= cutoff);
+        before - self.len()
+    }
+
+    /// Total number of usage records
+    pub fn len(&self) -> usize {
+        self.projects.len() + self.tasks.len() + self.project_tasks.len()
+    }
+
+    /// Whether the cache has no usage records
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 }
 
-/// Sort items by usage, with most recently used first
+/// Sort items by usage, with the highest decayed score first
 /// Items with no usage data are sorted alphabetically at the end
 pub fn sort_by_usage<T>(mut items: Vec<T>, score_fn: impl Fn(&T) -> Option<UsageScore>) -> Vec<T>
 where
@@ -187,11 +282,11 @@ where
         match (score_a, score_b) {
             // Both have usage data
             (Some(sa), Some(sb)) => {
-                // Primary: sort by recency (most recent first)
-                match sb.last_used.cmp(&sa.last_used) {
+                // Primary: blended recency + frequency score (higher first)
+                match sb.score.total_cmp(&sa.score) {
                     Ordering::Equal => {
-                        // Secondary: tie-break by use count (higher first)
-                        sb.use_count.cmp(&sa.use_count)
+                        // Secondary: tie-break by recency (most recent first)
+                        sb.last_used.cmp(&sa.last_used)
                     }
                     other => other,
                 }
@@ -208,6 +303,11 @@ where
     items
 }
 
+/// Map key for a project/task pair (JSON object keys must be strings)
+fn pair_key(project_id: u64, task_id: u64) -> String {
+    format!("{}:{}", project_id, task_id)
+}
+
 /// Trait for items that have a name for alphabetical sorting
 pub trait HasName {
     fn name(&self) -> &str;
@@ -263,6 +363,38 @@ mod tests {
         assert_eq!(score.use_count, 1);
     }
 
+    #[test]
+    fn test_project_task_scores_are_per_project() {
+        let mut cache = UsageCache::new();
+        cache.record_task_usage(10);
+        cache.record_project_task_usage(1, 10);
+        cache.record_project_task_usage(2, 20);
+
+        assert!(cache.get_project_task_score(1, 10).is_some());
+        // Task 10 was never used in project 2
+        assert!(cache.get_project_task_score(2, 10).is_none());
+    }
+
+    #[test]
+    fn test_project_task_score_falls_back_to_global() {
+        let mut cache = UsageCache::new();
+        cache.record_task_usage(10);
+
+        // No pair data for project 3 at all, so the global score is used
+        let score = cache.get_project_task_score(3, 10).unwrap();
+        assert_eq!(score.use_count, 1);
+    }
+
+    #[test]
+    fn test_record_entry_usage() {
+        let mut cache = UsageCache::new();
+        cache.record_entry_usage(1, 10);
+
+        assert!(cache.get_project_score(1).is_some());
+        assert!(cache.get_task_score(10).is_some());
+        assert!(cache.get_project_task_score(1, 10).is_some());
+    }
+
     #[test]
     fn test_get_score_for_missing_item() {
         let cache = UsageCache::new();
@@ -270,6 +402,51 @@ mod tests {
         assert!(cache.get_task_score(999).is_none());
     }
 
+    #[test]
+    fn test_score_decays_over_time() {
+        let now = Utc::now();
+        let record = UsageRecord::new(now - Duration::days(HALF_LIFE_DAYS as i64));
+        assert!((record.score_at(now) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_frequent_old_usage_beats_single_recent_use() {
+        let now = Utc::now();
+        let mut frequent = UsageRecord::new(now - Duration::days(10));
+        for _ in 0..5 {
+            frequent.record_use(now - Duration::days(7));
+        }
+        let recent = UsageRecord::new(now);
+
+        assert!(frequent.score_at(now) > recent.score_at(now));
+    }
+
+    #[test]
+    fn test_legacy_record_without_decayed_count() {
+        let json = r#"{"last_used": "2025-01-10T10:00:00Z", "use_count": 4}"#;
+        let record: UsageRecord = serde_json::from_str(json).unwrap();
+        assert!((record.score_at(record.last_used) - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_prune_removes_stale_records() {
+        let now = Utc::now();
+        let mut cache = UsageCache::new();
+        cache
+            .projects
+            .insert(1, UsageRecord::new(now - Duration::days(400)));
+        cache
+            .projects
+            .insert(2, UsageRecord::new(now - Duration::days(10)));
+        cache
+            .tasks
+            .insert(3, UsageRecord::new(now - Duration::days(200)));
+
+        assert_eq!(cache.prune(6, now), 2);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get_project_score(2).is_some());
+    }
+
     #[derive(Debug)]
     struct TestItem {
         id: u64,
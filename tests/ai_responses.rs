@@ -0,0 +1,75 @@
+//! Golden-file tests for AI response parsing
+//!
+//! Each `tests/fixtures/ai_responses/NAME.txt` is a provider reply and
+//! `NAME.json` the entries (or error) `parse_response` should produce. Run
+//! with `HARV_UPDATE_GOLDEN=1` to rewrite the expected files after an
+//! intended change, then review the diff.
+
+use harv::ai::parse_response;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+fn render(reply: &str) -> Value {
+    match parse_response(reply) {
+        Ok(entries) => entries
+            .iter()
+            .map(|e| {
+                json!({
+                    "description": e.description,
+                    "project_id": e.project_id,
+                    "task_id": e.task_id,
+                    "hours": e.hours,
+                    "confidence": e.confidence_score,
+                })
+            })
+            .collect(),
+        Err(e) => {
+            // The raw reply is echoed in the message; the fixture already has it
+            let message = e.to_string();
+            let message = message.split(". Raw response:").next().unwrap_or_default();
+            json!({ "error": message })
+        }
+    }
+}
+
+#[test]
+fn test_ai_response_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ai_responses");
+    let update = std::env::var_os("HARV_UPDATE_GOLDEN").is_some();
+
+    let mut replies: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    replies.sort();
+    assert!(!replies.is_empty(), "no fixtures in {}", dir.display());
+
+    let mut mismatches = Vec::new();
+    for reply_path in replies {
+        let actual = render(&fs::read_to_string(&reply_path).unwrap());
+        let expected_path = reply_path.with_extension("json");
+
+        if update {
+            let mut pretty = serde_json::to_string_pretty(&actual).unwrap();
+            pretty.push('\n');
+            fs::write(&expected_path, pretty).unwrap();
+            continue;
+        }
+
+        let expected: Value = fs::read_to_string(&expected_path)
+            .map(|text| serde_json::from_str(&text).unwrap())
+            .unwrap_or_else(|_| panic!("missing {}", expected_path.display()));
+        if actual != expected {
+            mismatches.push(format!(
+                "{}:\nexpected {}\n  actual {}",
+                reply_path.file_name().unwrap().to_string_lossy(),
+                expected,
+                actual
+            ));
+        }
+    }
+
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n\n"));
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::azure_devops_client;
+use harv::{HarjiraError, IssueTracker};
+use mockito::Matcher;
+
+fn work_item(id: u64, title: &str, state: &str) -> String {
+    format!(
+        r#"{{"id":{},"fields":{{"System.Title":"{}","System.State":"{}"}}}}"#,
+        id, title, state
+    )
+}
+
+#[test]
+fn test_get_work_item() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/Web%20Shop/_apis/wit/workitems/1234")
+        // Basic auth with an empty user name and the PAT as password
+        .match_header("authorization", "Basic OmFkby1wYXQ=")
+        .match_query(Matcher::UrlEncoded("api-version".into(), "7.1".into()))
+        .with_body(work_item(1234, "Fix login", "Active"))
+        .create();
+
+    let ticket = azure_devops_client(&server).get_issue("AB#1234").unwrap();
+    assert_eq!(ticket.key, "AB#1234");
+    assert_eq!(ticket.summary, "Fix login");
+    assert_eq!(ticket.status.as_deref(), Some("Active"));
+    mock.assert();
+}
+
+#[test]
+fn test_missing_work_item_is_not_found() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/Web%20Shop/_apis/wit/workitems/9")
+        .match_query(Matcher::Any)
+        .with_status(404)
+        .with_body(r#"{"message":"TF401232: Work item 9 does not exist"}"#)
+        .create();
+
+    assert!(matches!(
+        azure_devops_client(&server).get_issue("AB#9"),
+        Err(HarjiraError::AzureDevOps(msg)) if msg.contains("AB#9 not found")
+    ));
+}
+
+#[test]
+fn test_rejected_token_maps_to_auth_exit_code() {
+    let mut server = mockito::Server::new();
+    // Azure DevOps answers a bad PAT with a sign-in page
+    let _mock = server
+        .mock("GET", "/Web%20Shop/_apis/wit/workitems/1")
+        .match_query(Matcher::Any)
+        .with_status(203)
+        .with_body("<html>Sign in</html>")
+        .create();
+
+    let error = azure_devops_client(&server).get_issue("AB#1").unwrap_err();
+    assert_eq!(error.exit_code(), harv::error::exit_code::AUTH);
+}
+
+#[test]
+fn test_recent_work_items_keep_query_order() {
+    let mut server = mockito::Server::new();
+    let wiql = server
+        .mock("POST", "/Web%20Shop/_apis/wit/wiql")
+        .match_query(Matcher::UrlEncoded("$top".into(), "50".into()))
+        .match_body(Matcher::Regex("@Me".to_string()))
+        .with_body(r#"{"workItems":[{"id":7},{"id":3}]}"#)
+        .create();
+    let items = server
+        .mock("GET", "/Web%20Shop/_apis/wit/workitems")
+        .match_query(Matcher::UrlEncoded("ids".into(), "7,3".into()))
+        .with_body(format!(
+            r#"{{"value":[{},{}]}}"#,
+            work_item(3, "Older", "Active"),
+            work_item(7, "Newest", "New")
+        ))
+        .create();
+
+    let tickets = azure_devops_client(&server)
+        .get_my_recent_issues(50)
+        .unwrap();
+    let keys: Vec<&str> = tickets.iter().map(|t| t.key.as_str()).collect();
+    assert_eq!(keys, vec!["AB#7", "AB#3"]);
+    wiql.assert();
+    items.assert();
+}
+
+#[test]
+fn test_ticket_url_opens_work_item() {
+    let server = mockito::Server::new();
+    assert_eq!(
+        azure_devops_client(&server).get_ticket_url("AB#1234"),
+        format!("{}/Web%20Shop/_workitems/edit/1234", server.url())
+    );
+}
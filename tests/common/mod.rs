@@ -0,0 +1,186 @@
+//! Shared fixtures for the integration tests
+//!
+//! Every client points at a `mockito` server, and HOME is redirected to a
+//! temporary directory so caches, ledgers and favorites never touch the real
+//! configuration.
+
+#![allow(dead_code)]
+
+use harv::commands::Session;
+use harv::config::{
+    AzureDevOpsConfig, GitHubConfig, HarvestConfig, JiraConfig, LinearConfig, TempoConfig,
+    TogglConfig,
+};
+use harv::{
+    AzureDevOpsClient, Config, Context, GitHubClient, HarvestClient, JiraClient, LinearClient,
+    TempoClient, TogglClient,
+};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Point HOME and the XDG directories at a throwaway directory
+pub fn isolate_home() -> PathBuf {
+    static HOME: OnceLock<tempfile::TempDir> = OnceLock::new();
+    let home = HOME.get_or_init(|| {
+        let dir = tempfile::tempdir().expect("temporary HOME");
+        std::env::set_var("HOME", dir.path());
+        std::env::set_var("XDG_CONFIG_HOME", dir.path().join(".config"));
+        std::env::set_var("XDG_CACHE_HOME", dir.path().join(".cache"));
+        std::env::set_var("XDG_DATA_HOME", dir.path().join(".local/share"));
+        dir
+    });
+    home.path().to_path_buf()
+}
+
+pub fn config() -> Config {
+    toml::from_str(
+        r#"
+        [harvest]
+        access_token = "harvest-token"
+        account_id = "123456"
+        user_agent = "harv tests"
+        project_id = 11
+        task_id = 22
+
+        [jira]
+        access_token = "jira-token"
+        base_url = "https://acme.atlassian.net"
+        "#,
+    )
+    .expect("test configuration parses")
+}
+
+pub fn harvest_config() -> HarvestConfig {
+    config().harvest
+}
+
+pub fn jira_config() -> JiraConfig {
+    config().jira
+}
+
+/// ID of the user the test token belongs to
+pub const USER_ID: u64 = 7;
+
+pub fn harvest_client(server: &mockito::Server) -> HarvestClient {
+    HarvestClient::new(harvest_config())
+        .expect("harvest client")
+        .with_base_url(&server.url())
+        .with_user_id(USER_ID)
+}
+
+pub fn jira_client(server: &mockito::Server) -> JiraClient {
+    JiraClient::new(jira_config())
+        .expect("jira client")
+        .with_api_base_url(&server.url())
+}
+
+/// A Linear client for workspace "acme"
+pub fn linear_client(server: &mockito::Server) -> LinearClient {
+    let config = LinearConfig {
+        api_key: "lin_api_test".to_string(),
+        workspace: "acme".to_string(),
+        base_url: None,
+    };
+    LinearClient::new(config)
+        .expect("linear client")
+        .with_base_url(&format!("{}/graphql", server.url()))
+}
+
+/// An Azure DevOps client for project "Web Shop", the server standing in for dev.azure.com/acme
+pub fn azure_devops_client(server: &mockito::Server) -> AzureDevOpsClient {
+    let config = AzureDevOpsConfig {
+        organization: "acme".to_string(),
+        project: "Web Shop".to_string(),
+        access_token: "ado-pat".to_string(),
+        base_url: None,
+    };
+    AzureDevOpsClient::new(config)
+        .expect("azure devops client")
+        .with_base_url(&server.url())
+}
+
+/// A GitHub client with token "gh-token"
+pub fn github_client(server: &mockito::Server) -> GitHubClient {
+    let config = GitHubConfig {
+        access_token: "gh-token".to_string(),
+        repository: None,
+        base_url: None,
+    };
+    GitHubClient::new(config)
+        .expect("github client")
+        .with_base_url(&server.url())
+}
+
+/// A Tempo client for account "acc-1", resolving issues through the same server
+pub fn tempo_client(server: &mockito::Server) -> TempoClient {
+    let config = TempoConfig {
+        access_token: "tempo-token".to_string(),
+        account_id: "acc-1".to_string(),
+        base_url: None,
+    };
+    TempoClient::with_jira(config, jira_client(server))
+        .expect("tempo client")
+        .with_base_url(&server.url())
+}
+
+/// A Toggl client for workspace 55 with default project 3
+pub fn toggl_client(server: &mockito::Server) -> TogglClient {
+    let config = TogglConfig {
+        api_token: "toggl-token".to_string(),
+        workspace_id: 55,
+        project_id: Some(3),
+        base_url: None,
+    };
+    TogglClient::new(config)
+        .expect("toggl client")
+        .with_base_url(&server.url())
+}
+
+/// A session whose Harvest and Jira clients both talk to `server`
+pub fn session(server: &mockito::Server) -> Session {
+    isolate_home();
+    Session::new(config())
+        .with_harvest(harvest_client(server))
+        .with_jira(jira_client(server))
+}
+
+pub fn ctx() -> Context {
+    Context::default()
+}
+
+pub fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// A Harvest time entry for today as the API returns it
+pub fn time_entry(id: u64, notes: &str, hours: f64, is_running: bool) -> String {
+    time_entry_on(&today(), id, notes, hours, is_running)
+}
+
+pub fn time_entry_on(date: &str, id: u64, notes: &str, hours: f64, is_running: bool) -> String {
+    format!(
+        r#"{{"id":{},"spent_date":"{}","hours":{},"notes":"{}","is_running":{},
+            "project":{{"id":11,"name":"Acme Web"}},"task":{{"id":22,"name":"Development"}},
+            "started_time":null}}"#,
+        id, date, hours, notes, is_running
+    )
+}
+
+/// `entry` as another user's, the way an administrator token sees it
+pub fn logged_by(entry: String, user_id: u64) -> String {
+    let body = entry.trim_end().trim_end_matches('}');
+    format!(
+        r#"{},"user":{{"id":{},"name":"User {}"}}}}"#,
+        body, user_id, user_id
+    )
+}
+
+pub fn yesterday() -> String {
+    (chrono::Local::now() - chrono::Duration::days(1))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+pub fn time_entries(entries: &[String]) -> String {
+    format!(r#"{{"time_entries":[{}]}}"#, entries.join(","))
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::github_client;
+use harv::{HarjiraError, IssueTracker};
+use mockito::Matcher;
+
+#[test]
+fn test_get_issue_by_repository_and_number() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/repos/acme/web/issues/12")
+        .match_header("authorization", "Bearer gh-token")
+        .match_header("user-agent", "harv")
+        .with_body(r#"{"number":12,"title":"Fix login","state":"open"}"#)
+        .create();
+
+    let ticket = github_client(&server).get_issue("acme/web#12").unwrap();
+    assert_eq!(ticket.key, "acme/web#12");
+    assert_eq!(ticket.summary, "Fix login");
+    assert_eq!(ticket.status.as_deref(), Some("open"));
+    mock.assert();
+}
+
+#[test]
+fn test_missing_issue_is_not_found() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/repos/acme/web/issues/99")
+        .with_status(404)
+        .with_body(r#"{"message":"Not Found"}"#)
+        .create();
+
+    assert!(matches!(
+        github_client(&server).get_issue("acme/web#99"),
+        Err(HarjiraError::GitHub(msg)) if msg.contains("acme/web#99 not found")
+    ));
+}
+
+#[test]
+fn test_bad_credentials_map_to_auth_exit_code() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/repos/acme/web/issues/1")
+        .with_status(401)
+        .with_body(r#"{"message":"Bad credentials"}"#)
+        .create();
+
+    let error = github_client(&server).get_issue("acme/web#1").unwrap_err();
+    assert_eq!(error.exit_code(), harv::error::exit_code::AUTH);
+}
+
+#[test]
+fn test_recent_issues_skip_pull_requests_and_old_closed_ones() {
+    let mut server = mockito::Server::new();
+    let recent = chrono::Utc::now().to_rfc3339();
+    let mock = server
+        .mock("GET", "/issues")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("filter".into(), "assigned".into()),
+            Matcher::UrlEncoded("per_page".into(), "50".into()),
+        ]))
+        .with_body(format!(
+            r#"[
+            {{"number":1,"title":"Open","state":"open","updated_at":"2020-01-01T00:00:00Z",
+              "repository":{{"full_name":"acme/web"}}}},
+            {{"number":2,"title":"A PR","state":"open","updated_at":"{0}",
+              "repository":{{"full_name":"acme/web"}},"pull_request":{{}}}},
+            {{"number":3,"title":"Closed today","state":"closed","updated_at":"{0}",
+              "repository":{{"full_name":"acme/api"}}}},
+            {{"number":4,"title":"Closed long ago","state":"closed","updated_at":"2020-01-01T00:00:00Z",
+              "repository":{{"full_name":"acme/api"}}}}]"#,
+            recent
+        ))
+        .create();
+
+    let tickets = github_client(&server).get_my_recent_issues(50).unwrap();
+    let keys: Vec<&str> = tickets.iter().map(|t| t.key.as_str()).collect();
+    assert_eq!(keys, vec!["acme/web#1", "acme/api#3"]);
+    mock.assert();
+}
+
+#[test]
+fn test_ticket_url_links_to_issue() {
+    let server = mockito::Server::new();
+    assert_eq!(
+        github_client(&server).get_ticket_url("acme/web#12"),
+        "https://github.com/acme/web/issues/12"
+    );
+}
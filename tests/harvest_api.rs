@@ -0,0 +1,582 @@
+mod common;
+
+use common::{ctx, harvest_client, logged_by, time_entries, time_entry, time_entry_on};
+use harv::offline::{self, OfflineQueue, Operation};
+use harv::HarjiraError;
+use mockito::Matcher;
+use std::sync::Mutex;
+
+#[test]
+fn test_sends_auth_headers() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/users/me")
+        .match_header("authorization", "Bearer harvest-token")
+        .match_header("harvest-account-id", "123456")
+        .match_header("user-agent", "harv tests")
+        .with_body(
+            r#"{"id":7,"first_name":"Ada","last_name":"Lovelace","email":"ada@example.com"}"#,
+        )
+        .create();
+
+    let user = harvest_client(&server).get_current_user().unwrap();
+    assert_eq!(user.id, 7);
+    assert_eq!(user.email.as_deref(), Some("ada@example.com"));
+    mock.assert();
+}
+
+#[test]
+fn test_todays_entries_query_today() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("from".into(), common::today()),
+            Matcher::UrlEncoded("to".into(), common::today()),
+        ]))
+        .with_body(time_entries(&[
+            time_entry(1, "ABC-1 - Done", 1.5, false),
+            time_entry(2, "ABC-2 - Busy", 0.25, true),
+        ]))
+        .create();
+
+    let entries = harvest_client(&server).get_todays_time_entries().unwrap();
+    let ids: Vec<u64> = entries.iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[test]
+fn test_running_timers_newest_first() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::UrlEncoded("is_running".into(), "true".into()))
+        .with_body(time_entries(&[
+            time_entry_on(&common::yesterday(), 1, "ABC-1 - Old", 9.0, true),
+            time_entry(2, "ABC-2 - Busy", 0.25, true),
+            time_entry(3, "ABC-3 - Stopped", 1.0, false),
+        ]))
+        .create();
+
+    let timers = harvest_client(&server).get_running_timers().unwrap();
+    let ids: Vec<u64> = timers.iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![2, 1]);
+}
+
+#[test]
+fn test_running_timers_only_of_the_token_user() {
+    let mut server = mockito::Server::new();
+    let me = server
+        .mock("GET", "/users/me")
+        .with_body(r#"{"id":7,"first_name":"Ada","last_name":"Lovelace"}"#)
+        .expect(1)
+        .create();
+    let _running = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("is_running".into(), "true".into()),
+            Matcher::UrlEncoded("user_id".into(), "7".into()),
+        ]))
+        .with_body(time_entries(&[
+            logged_by(time_entry(1, "ABC-1 - Mine", 1.0, true), 7),
+            logged_by(time_entry(2, "ABC-2 - A teammate's", 2.0, true), 8),
+        ]))
+        .create();
+
+    let mut config = common::harvest_config();
+    config.access_token = "admin-token".to_string();
+    let client = harv::HarvestClient::new(config)
+        .unwrap()
+        .with_base_url(&server.url());
+    for _ in 0..2 {
+        let timers = client.get_running_timers().unwrap();
+        assert_eq!(timers.iter().map(|e| e.id).collect::<Vec<_>>(), [1]);
+    }
+    me.assert();
+}
+
+#[test]
+fn test_entries_range_only_of_the_token_user() {
+    let mut server = mockito::Server::new();
+    let _range = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("user_id".into(), common::USER_ID.to_string()),
+            Matcher::UrlEncoded("from".into(), "2026-10-12".into()),
+            Matcher::UrlEncoded("to".into(), "2026-10-14".into()),
+        ]))
+        .with_body(time_entries(&[
+            logged_by(time_entry_on("2026-10-13", 1, "Standup", 0.25, false), 7),
+            logged_by(time_entry_on("2026-10-13", 2, "Standup", 0.25, false), 8),
+        ]))
+        .create();
+
+    let entries = harvest_client(&server)
+        .get_time_entries_range("2026-10-12", "2026-10-14", &ctx())
+        .unwrap();
+    assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), [1]);
+}
+
+#[test]
+fn test_running_timer_from_yesterday() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::UrlEncoded("is_running".into(), "true".into()))
+        .with_body(time_entries(&[time_entry_on(
+            &common::yesterday(),
+            3,
+            "ABC-3 - Late night",
+            5.0,
+            true,
+        )]))
+        .create();
+
+    let timer = harvest_client(&server)
+        .get_running_timer()
+        .unwrap()
+        .unwrap();
+    assert_eq!(timer.id, 3);
+    assert!(!timer.is_today());
+    mock.assert();
+}
+
+#[test]
+fn test_projects_fall_back_to_assignments_on_403() {
+    let mut server = mockito::Server::new();
+    let _forbidden = server
+        .mock("GET", "/projects")
+        .match_query(Matcher::Any)
+        .with_status(403)
+        .with_body(r#"{"error":"forbidden","error_description":"Not allowed"}"#)
+        .create();
+    let assignments = server
+        .mock("GET", "/users/me/project_assignments")
+        .with_body(
+            r#"{"project_assignments":[
+                {"id":1,"is_active":true,"project":{"id":11,"name":"Web","code":null},
+                 "client":{"id":5,"name":"Acme"},"task_assignments":[]},
+                {"id":2,"is_active":false,"project":{"id":12,"name":"Old","code":null},
+                 "task_assignments":[]}]}"#,
+        )
+        .create();
+
+    let projects = harvest_client(&server).get_projects().unwrap();
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].display_name(), "Acme ▸ Web");
+    assignments.assert();
+}
+
+#[test]
+fn test_project_tasks_fall_back_to_assignments_on_403() {
+    let mut server = mockito::Server::new();
+    let _forbidden = server
+        .mock("GET", "/projects/11/task_assignments")
+        .with_status(403)
+        .create();
+    let _assignments = server
+        .mock("GET", "/users/me/project_assignments")
+        .with_body(
+            r#"{"project_assignments":[
+                {"id":1,"is_active":true,"project":{"id":11,"name":"Web","code":null},
+                 "task_assignments":[
+                    {"is_active":true,"task":{"id":22,"name":"Development"}},
+                    {"is_active":false,"task":{"id":23,"name":"Retired"}}]}]}"#,
+        )
+        .create();
+
+    let tasks = harvest_client(&server).get_project_tasks(11).unwrap();
+    let ids: Vec<u64> = tasks.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![22]);
+}
+
+#[test]
+fn test_account_task_assignments_follow_pagination() {
+    let mut server = mockito::Server::new();
+    let assignment = |project: u64, task: u64| {
+        format!(
+            r#"{{"is_active":true,"project":{{"id":{},"name":"P"}},"task":{{"id":{},"name":"T"}}}}"#,
+            project, task
+        )
+    };
+    let pages: Vec<_> = (1..=3)
+        .map(|page| {
+            let next = if page < 3 {
+                (page + 1).to_string()
+            } else {
+                "null".to_string()
+            };
+            server
+                .mock("GET", "/task_assignments")
+                .match_query(Matcher::UrlEncoded("page".into(), page.to_string()))
+                .with_body(format!(
+                    r#"{{"task_assignments":[{}],"total_pages":3,"next_page":{}}}"#,
+                    assignment(page, page * 10),
+                    next
+                ))
+                .expect(1)
+                .create()
+        })
+        .collect();
+
+    let tasks = harvest_client(&server).get_all_available_tasks().unwrap();
+    let pairs: Vec<(u64, u64)> = tasks.iter().map(|(pid, t)| (*pid, t.id)).collect();
+    assert_eq!(pairs, vec![(1, 10), (2, 20), (3, 30)]);
+    pages.iter().for_each(|page| page.assert());
+}
+
+#[test]
+fn test_team_entries_follow_pagination() {
+    let mut server = mockito::Server::new();
+    let entry = |id: u64, user: &str| {
+        format!(
+            r#"{{"id":{},"spent_date":"{}","hours":1.0,"notes":null,"is_running":true,"user":{{"id":{},"name":"{}"}}}}"#,
+            id,
+            common::today(),
+            id,
+            user
+        )
+    };
+    let _first = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("is_running".into(), "true".into()),
+            Matcher::UrlEncoded("page".into(), "1".into()),
+        ]))
+        .with_body(format!(
+            r#"{{"time_entries":[{}],"next_page":2}}"#,
+            entry(1, "Ada Lovelace")
+        ))
+        .create();
+    let _second = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("is_running".into(), "true".into()),
+            Matcher::UrlEncoded("page".into(), "2".into()),
+        ]))
+        .with_body(format!(
+            r#"{{"time_entries":[{}],"next_page":null}}"#,
+            entry(2, "Alan Turing")
+        ))
+        .create();
+
+    let entries = harvest_client(&server)
+        .get_team_time_entries(&[("is_running", "true".to_string())])
+        .unwrap();
+    let users: Vec<&str> = entries.iter().map(|e| e.user.name.as_str()).collect();
+    assert_eq!(users, vec!["Ada Lovelace", "Alan Turing"]);
+}
+
+#[test]
+fn test_create_entry_for_user_found_by_email() {
+    let mut server = mockito::Server::new();
+    let _users = server
+        .mock("GET", "/users")
+        .match_query(Matcher::UrlEncoded("is_active".into(), "true".into()))
+        .with_body(
+            r#"{"users":[{"id":7,"first_name":"Ada","last_name":"Lovelace","email":"ada@example.com"},{"id":8,"first_name":"Alan","last_name":"Turing","email":"alan@example.com"}],"next_page":null}"#,
+        )
+        .create();
+    let create = server
+        .mock("POST", "/time_entries")
+        .match_body(Matcher::PartialJsonString(
+            r#"{"user_id":8,"project_id":11,"task_id":22,"hours":1.5}"#.to_string(),
+        ))
+        .with_body(time_entry(5, "Pairing", 1.5, false))
+        .create();
+
+    let client = harvest_client(&server);
+    let user = client.find_user("Alan@Example.com").unwrap();
+    assert_eq!(user.name(), "Alan Turing");
+    client
+        .create_stopped_time_entry_for_user(
+            Some(user.id),
+            "Pairing",
+            11,
+            22,
+            1.5,
+            &common::today(),
+            &ctx(),
+        )
+        .unwrap();
+    create.assert();
+    assert!(client.find_user("grace@example.com").is_err());
+}
+
+#[test]
+fn test_finished_ticket_entry_links_the_ticket() {
+    let mut server = mockito::Server::new();
+    let create = server
+        .mock("POST", "/time_entries")
+        .match_body(Matcher::PartialJsonString(
+            r#"{"notes":"ABC-1 - Done","hours":1.25,"external_reference":{"id":"ABC-1","group_id":"jira"}}"#
+                .to_string(),
+        ))
+        .with_body(time_entry(5, "ABC-1 - Done", 1.25, false))
+        .create();
+
+    harvest_client(&server)
+        .create_stopped_ticket_entry("ABC-1", "Done", "https://jira/browse/ABC-1", 1.25, &ctx())
+        .unwrap();
+    create.assert();
+}
+
+#[test]
+fn test_users_need_admin_token() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/users")
+        .match_query(Matcher::Any)
+        .with_status(403)
+        .create();
+
+    let error = harvest_client(&server).get_users().unwrap_err();
+    assert!(matches!(error, HarjiraError::Auth(_)));
+}
+
+#[test]
+fn test_unbilled_entries_follow_pages() {
+    let mut server = mockito::Server::new();
+    let entry = |id: u64| {
+        format!(
+            r#"{{"id":{},"hours":1.5,"billable":true,"billable_rate":90.0,"project":{{"id":3,"name":"Website","code":"WEB"}},"task":{{"id":4,"name":"Development"}}}}"#,
+            id
+        )
+    };
+    let query = |page: &str| {
+        Matcher::AllOf(vec![
+            Matcher::UrlEncoded("client_id".into(), "5".into()),
+            Matcher::UrlEncoded("is_billed".into(), "false".into()),
+            Matcher::UrlEncoded("from".into(), "2026-09-01".into()),
+            Matcher::UrlEncoded("to".into(), "2026-09-30".into()),
+            Matcher::UrlEncoded("page".into(), page.into()),
+        ])
+    };
+    let _first = server
+        .mock("GET", "/time_entries")
+        .match_query(query("1"))
+        .with_body(format!(
+            r#"{{"time_entries":[{}],"next_page":2}}"#,
+            entry(1)
+        ))
+        .create();
+    let _second = server
+        .mock("GET", "/time_entries")
+        .match_query(query("2"))
+        .with_body(format!(
+            r#"{{"time_entries":[{}],"next_page":null}}"#,
+            entry(2)
+        ))
+        .create();
+
+    let entries = harvest_client(&server)
+        .get_unbilled_entries(5, "2026-09-01", "2026-09-30")
+        .unwrap();
+    assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(entries[0].billable_rate, Some(90.0));
+}
+
+#[test]
+fn test_invoice_imports_the_hours() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/invoices")
+        .match_body(Matcher::PartialJsonString(
+            r#"{"client_id":5,"line_items_import":{"project_ids":[3],"time":{"summary_type":"task","from":"2026-09-01","to":"2026-09-30"}}}"#
+                .to_string(),
+        ))
+        .with_body(
+            r#"{"id":9,"number":"42","amount":180.0,"state":"draft","line_items":[{"id":90,"project":{"id":3,"name":"Website"},"unit_price":90.0}]}"#,
+        )
+        .create();
+
+    let line = harv::invoices::InvoiceLine {
+        project_id: 3,
+        project: "Website".to_string(),
+        task: "Development".to_string(),
+        hours: 2.0,
+        rate: 90.0,
+    };
+    let request = harv::invoices::CreateInvoiceRequest::import(
+        5,
+        "Services".to_string(),
+        &[line.clone(), line],
+        "2026-09-01",
+        "2026-09-30",
+    );
+    let invoice = harvest_client(&server)
+        .create_invoice(&request, &ctx())
+        .unwrap();
+    assert_eq!(invoice.line_items[0].id, 90);
+    mock.assert();
+}
+
+#[test]
+fn test_create_entry_422_is_unprocessable() {
+    common::isolate_home();
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("POST", "/time_entries")
+        .match_body(Matcher::PartialJsonString(
+            r#"{"project_id":11,"task_id":22,"notes":"ABC-1 - Fix login"}"#.to_string(),
+        ))
+        .with_status(422)
+        .with_body(r#"{"message":"Project is archived"}"#)
+        .create();
+
+    let error = harvest_client(&server)
+        .create_time_entry(
+            "ABC-1",
+            "Fix login",
+            "https://acme.atlassian.net/browse/ABC-1",
+            &ctx(),
+        )
+        .unwrap_err();
+    match error {
+        HarjiraError::HarvestApi(api) => {
+            assert!(api.is_unprocessable());
+            assert_eq!(api.message, "Project is archived");
+        }
+        other => panic!("expected a Harvest API error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_rate_limit_keeps_retry_after() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_status(429)
+        .with_header("retry-after", "15")
+        .create();
+
+    let error = harvest_client(&server)
+        .get_todays_time_entries()
+        .unwrap_err();
+    match error {
+        HarjiraError::HarvestApi(api) => {
+            assert!(api.is_rate_limited());
+            assert_eq!(api.retry_after, Some(15));
+        }
+        other => panic!("expected a Harvest API error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_token_maps_to_auth_exit_code() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/users/me")
+        .with_status(401)
+        .with_body(r#"{"error":"invalid_token","error_description":"The access token is invalid"}"#)
+        .create();
+
+    let error = harvest_client(&server).get_current_user().unwrap_err();
+    assert_eq!(error.exit_code(), harv::error::exit_code::AUTH);
+}
+
+#[test]
+fn test_dry_run_sends_nothing() {
+    let mut server = mockito::Server::new();
+    let stop = server.mock("PATCH", Matcher::Any).expect(0).create();
+
+    let dry_run = harv::Context {
+        dry_run: true,
+        ..ctx()
+    };
+    let entry = harvest_client(&server)
+        .stop_time_entry(42, &dry_run)
+        .unwrap();
+    assert!(!entry.is_running);
+    stop.assert();
+}
+
+/// The flush tests share the queue file in the isolated HOME
+static OFFLINE_QUEUE: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_flush_does_not_start_a_half_sent_timer_again() {
+    let _queue = OFFLINE_QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+    common::isolate_home();
+    // An earlier flush started the timer but lost the connection before the update
+    let mut queue = OfflineQueue::default();
+    queue.push(
+        "123456",
+        Operation::Create {
+            spent_date: common::today(),
+            project_id: 11,
+            task_id: 22,
+            notes: "ABC-1 - Fix login".to_string(),
+            hours: None,
+            entry_id: Some(42),
+        },
+    );
+    queue.save().unwrap();
+
+    let mut server = mockito::Server::new();
+    let create = server.mock("POST", "/time_entries").expect(0).create();
+    let update = server
+        .mock("PATCH", "/time_entries/42")
+        .match_body(Matcher::PartialJsonString(r#"{"hours":0.0}"#.to_string()))
+        .with_body(time_entry(42, "ABC-1 - Fix login", 0.0, true))
+        .create();
+
+    let report = offline::flush(&harvest_client(&server), &ctx()).unwrap();
+
+    create.assert();
+    update.assert();
+    assert_eq!(report.sent, 1);
+    assert!(OfflineQueue::load().unwrap().is_empty());
+}
+
+#[test]
+fn test_flush_drops_the_stop_of_a_rejected_start() {
+    let _queue = OFFLINE_QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+    common::isolate_home();
+    let mut queue = OfflineQueue::default();
+    queue.push(
+        "123456",
+        Operation::Create {
+            spent_date: common::today(),
+            project_id: 11,
+            task_id: 22,
+            notes: "ABC-1 - Fix login".to_string(),
+            hours: None,
+            entry_id: None,
+        },
+    );
+    queue.push(
+        "123456",
+        Operation::Stop {
+            entry_id: None,
+            hours: None,
+        },
+    );
+    queue.save().unwrap();
+
+    let mut server = mockito::Server::new();
+    let _create = server
+        .mock("POST", "/time_entries")
+        .with_status(422)
+        .with_body(r#"{"message":"Project is archived"}"#)
+        .create();
+    let running = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[time_entry(40, "ABC-9 - Deploy", 1.0, true)]))
+        .expect(0)
+        .create();
+    let stop = server
+        .mock("PATCH", "/time_entries/40/stop")
+        .expect(0)
+        .create();
+
+    let report = offline::flush(&harvest_client(&server), &ctx()).unwrap();
+
+    running.assert();
+    stop.assert();
+    assert_eq!(report.sent, 0);
+    assert_eq!(report.rejected.len(), 2);
+    assert!(OfflineQueue::load().unwrap().is_empty());
+}
@@ -0,0 +1,594 @@
+//! End-to-end command flows against mock Harvest and Jira servers
+
+mod common;
+
+use common::{ctx, session, time_entries, time_entry, time_entry_on};
+use harv::commands;
+use harv::journal::{Batch, Change, Journal};
+use harv::offline::OfflineQueue;
+use harv::output::{self, CaptureReporter, MessageKind};
+use harv::{Context, HarvestClient};
+use mockito::Matcher;
+use std::sync::Arc;
+
+/// Run a command, returning its result and everything it printed
+fn capture<T>(f: impl FnOnce() -> T) -> (T, Arc<CaptureReporter>) {
+    let reporter = Arc::new(CaptureReporter::default());
+    let result = output::with_reporter(reporter.clone(), f);
+    (result, reporter)
+}
+
+#[test]
+fn test_stop_running_timer() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[time_entry(
+            42,
+            "ABC-1 - Fix login",
+            1.0,
+            true,
+        )]))
+        .create();
+    let stop = server
+        .mock("PATCH", "/time_entries/42/stop")
+        .with_body(time_entry(42, "ABC-1 - Fix login", 1.0, false))
+        .create();
+
+    let session = session(&server);
+    let (result, output) = capture(|| commands::run_stop(ctx(), &session, false, None, false));
+
+    result.unwrap();
+    stop.assert();
+    assert_eq!(
+        output.messages(),
+        vec![(MessageKind::Success, "Timer stopped".to_string())]
+    );
+}
+
+#[test]
+fn test_stop_timer_left_running_since_yesterday() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::UrlEncoded("is_running".into(), "true".into()))
+        .with_body(time_entries(&[time_entry_on(
+            &common::yesterday(),
+            40,
+            "ABC-9 - Deploy",
+            7.0,
+            true,
+        )]))
+        .create();
+    let stop = server
+        .mock("PATCH", "/time_entries/40/stop")
+        .with_body(time_entry_on(
+            &common::yesterday(),
+            40,
+            "ABC-9 - Deploy",
+            7.0,
+            false,
+        ))
+        .create();
+
+    let session = session(&server);
+    let (result, output) = capture(|| commands::run_stop(ctx(), &session, false, None, false));
+
+    result.unwrap();
+    stop.assert();
+    assert_eq!(
+        output.text(),
+        format!(
+            "Stopped timer 'ABC-9 - Deploy' running since {}",
+            common::yesterday()
+        )
+    );
+}
+
+#[test]
+fn test_stop_only_timers_on_the_given_day() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[time_entry(
+            42,
+            "ABC-1 - Fix login",
+            1.0,
+            true,
+        )]))
+        .create();
+    let stop = server.mock("PATCH", Matcher::Any).expect(0).create();
+
+    let session = session(&server);
+    let (result, output) = capture(|| commands::run_stop(ctx(), &session, false, None, true));
+
+    result.unwrap();
+    stop.assert();
+    assert_eq!(
+        output.text(),
+        format!("No timer running on {}", common::yesterday())
+    );
+}
+
+#[test]
+fn test_stop_without_timer() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[time_entry(
+            41,
+            "ABC-1 - Fix login",
+            1.0,
+            false,
+        )]))
+        .create();
+    let stop = server.mock("PATCH", Matcher::Any).expect(0).create();
+
+    let session = session(&server);
+    let (result, output) = capture(|| commands::run_stop(ctx(), &session, false, None, false));
+
+    result.unwrap();
+    stop.assert();
+    assert_eq!(output.text(), "No timer currently running");
+}
+
+#[test]
+fn test_stop_dry_run_leaves_timer_running() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[time_entry(
+            42,
+            "ABC-1 - Fix login",
+            1.0,
+            true,
+        )]))
+        .create();
+    let stop = server.mock("PATCH", Matcher::Any).expect(0).create();
+
+    let session = session(&server);
+    let dry_run = Context {
+        dry_run: true,
+        ..ctx()
+    };
+    let (result, _) = capture(|| commands::run_stop(dry_run, &session, false, None, false));
+
+    result.unwrap();
+    stop.assert();
+}
+
+#[test]
+fn test_status_lists_todays_entries() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[
+            time_entry(1, "ABC-1 - Fix login", 2.0, false),
+            time_entry(2, "ABC-2 - Review", 0.5, true),
+        ]))
+        .create();
+
+    let session = session(&server);
+    let (result, output) = capture(|| commands::run_status(ctx(), &session, true));
+
+    result.unwrap();
+    let text = output.text();
+    assert!(text.contains("✓ Timer Running"));
+    assert!(text.contains("Notes: ABC-2 - Review"));
+    assert!(text.contains("ABC-1 - Fix login"));
+    assert!(!text.contains("cached"));
+}
+
+#[test]
+fn test_status_for_another_day() {
+    let mut server = mockito::Server::new();
+    let yesterday = common::yesterday();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("from".into(), yesterday.clone()),
+            Matcher::UrlEncoded("to".into(), yesterday.clone()),
+        ]))
+        .with_body(time_entries(&[time_entry_on(
+            &yesterday,
+            1,
+            "ABC-1 - Fix login",
+            2.0,
+            false,
+        )]))
+        .create();
+
+    let session = session(&server);
+    let (result, output) =
+        capture(|| commands::run_status_day(ctx(), &session, Some(yesterday.clone()), false));
+
+    result.unwrap();
+    let text = output.text();
+    assert!(text.contains("No timer running"));
+    assert!(text.contains(&format!("Time Entries on {}:", yesterday)));
+    assert!(text.contains("ABC-1 - Fix login"));
+}
+
+#[test]
+fn test_today_groups_by_project_and_skips_forbidden_expenses() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[
+            time_entry(1, "ABC-1 - Fix login", 2.0, false),
+            time_entry(2, "ABC-1 - More login", 1.0, false),
+        ]))
+        .create();
+    let _expenses = server
+        .mock("GET", "/expenses")
+        .match_query(Matcher::Any)
+        .with_status(403)
+        .create();
+
+    let session = session(&server);
+    let quiet = Context {
+        quiet: true,
+        ..ctx()
+    };
+    let (result, output) = capture(|| commands::run_today(quiet, &session, false));
+
+    result.unwrap();
+    let text = output.text();
+    assert!(text.contains("Acme Web"));
+    assert!(text.contains("ABC-1"));
+    assert!(!text.contains("Expenses"));
+}
+
+#[test]
+fn test_harvest_errors_propagate_from_commands() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_status(401)
+        .with_body(r#"{"error":"invalid_token","error_description":"The access token is invalid"}"#)
+        .create();
+
+    let session = session(&server);
+    let (result, output) = capture(|| commands::run_stop(ctx(), &session, false, None, false));
+
+    let error = result.unwrap_err();
+    assert_eq!(error.exit_code(), harv::error::exit_code::AUTH);
+    assert!(output.messages().is_empty());
+}
+
+#[test]
+fn test_log_adds_to_existing_entry() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[time_entry(
+            7,
+            "ABC-1 - Fix login",
+            1.0,
+            false,
+        )]))
+        .create();
+    let update = server
+        .mock("PATCH", "/time_entries/7")
+        .match_body(Matcher::Json(serde_json::json!({
+            "hours": 2.5,
+            "notes": "ABC-1 - Fix login; code review"
+        })))
+        .with_body(time_entry(7, "ABC-1 - Fix login; code review", 2.5, false))
+        .create();
+
+    let session = session(&server);
+    let (result, output) = capture(|| {
+        commands::run_log(
+            ctx(),
+            &session,
+            "abc-1",
+            "1:30",
+            Some("code review".to_string()),
+            (None, None),
+            None,
+        )
+    });
+
+    result.unwrap();
+    update.assert();
+    assert_eq!(output.messages()[0].0, MessageKind::Success);
+    assert!(output.text().starts_with("Added 1.50h to ABC-1"));
+}
+
+#[test]
+fn test_log_creates_entry_with_jira_summary() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[]))
+        .create();
+    let _issue = server
+        .mock("GET", "/rest/api/3/issue/ABC-2")
+        .with_body(
+            r#"{"key":"ABC-2","fields":{"summary":"Billing export","status":{"name":"To Do"}}}"#,
+        )
+        .create();
+    let create = server
+        .mock("POST", "/time_entries")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "project_id": 11,
+            "task_id": 22,
+            "hours": 0.5,
+            "notes": "ABC-2 - Billing export"
+        })))
+        .with_body(time_entry(8, "ABC-2 - Billing export", 0.5, false))
+        .create();
+
+    let session = session(&server);
+    let (result, _) =
+        capture(|| commands::run_log(ctx(), &session, "ABC-2", "0.5", None, (None, None), None));
+
+    result.unwrap();
+    create.assert();
+}
+
+#[test]
+fn test_stop_with_toggl_backend() {
+    common::isolate_home();
+    let mut server = mockito::Server::new();
+    let start = (chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+    let _current = server
+        .mock("GET", "/me/time_entries/current")
+        .with_body(format!(
+            r#"{{"id":9,"description":"ABC-1 - Fix login","start":"{}","duration":-1}}"#,
+            start
+        ))
+        .create();
+    let stop = server
+        .mock("PATCH", "/workspaces/55/time_entries/9/stop")
+        .with_body(format!(
+            r#"{{"id":9,"description":"ABC-1 - Fix login","start":"{}","duration":3600}}"#,
+            start
+        ))
+        .create();
+    let harvest = server
+        .mock("GET", Matcher::Regex("^/time_entries".into()))
+        .expect(0)
+        .create();
+
+    let mut config = common::config();
+    config.backend.provider = "toggl".to_string();
+    let session = commands::Session::new(config).with_toggl(common::toggl_client(&server));
+    let (result, output) = capture(|| commands::run_stop(ctx(), &session, false, None, false));
+
+    result.unwrap();
+    stop.assert();
+    harvest.assert();
+    assert_eq!(output.text(), "Timer stopped");
+    assert!(session.harvest().is_err());
+}
+
+#[test]
+fn test_generate_from_file_creates_valid_plan_entries() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/time_entries")
+        .match_query(Matcher::Any)
+        .with_body(time_entries(&[]))
+        .create();
+    let _projects = server
+        .mock("GET", "/projects")
+        .match_query(Matcher::Any)
+        .with_body(r#"{"projects":[{"id":11,"name":"Acme Web","code":null}]}"#)
+        .create();
+    let _tasks = server
+        .mock("GET", "/task_assignments")
+        .match_query(Matcher::Any)
+        .with_body(
+            r#"{"task_assignments":[{"is_active":true,"project":{"id":11,"name":"Acme Web"},
+                "task":{"id":22,"name":"Development"}}],"next_page":null}"#,
+        )
+        .create();
+    let create = server
+        .mock("POST", "/time_entries")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "project_id": 11,
+            "task_id": 22,
+            "spent_date": common::yesterday(),
+            "notes": "Billing export",
+            "hours": 2.5,
+        })))
+        .with_body(time_entry_on(
+            &common::yesterday(),
+            50,
+            "Billing export",
+            2.5,
+            false,
+        ))
+        .expect(1)
+        .create();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("plan.json");
+    std::fs::write(
+        &path,
+        format!(
+            r#"{{"days": [{{"date": "{}", "entries": [
+                {{"description": "Billing export", "project_id": 11, "task_id": 22, "hours": 2.5}},
+                {{"description": "Unknown", "project_id": 99, "task_id": 22, "hours": 1.0}}
+            ]}}]}}"#,
+            common::yesterday()
+        ),
+    )
+    .unwrap();
+
+    let session = session(&server);
+    let (result, output) = capture(|| {
+        commands::run_generate(
+            ctx(),
+            session,
+            None,
+            None,
+            true,
+            None,
+            false,
+            false,
+            false,
+            Some(path.to_string_lossy().to_string()),
+            None,
+        )
+    });
+
+    result.unwrap();
+    create.assert();
+    let text = output.text();
+    assert!(
+        text.contains("Skipping 'Unknown': unknown project 99"),
+        "{}",
+        text
+    );
+    assert!(text.contains("Successfully created 1 time entry across 1 day"));
+}
+
+#[test]
+fn test_undo_reverses_the_last_command() {
+    let mut server = mockito::Server::new();
+    let delete = server.mock("DELETE", "/time_entries/41").create();
+    let restart = server
+        .mock("PATCH", "/time_entries/40/restart")
+        .with_body(time_entry(40, "ABC-9 - Deploy", 0.0, true))
+        .create();
+
+    let session = session(&server);
+    let mut journal = Journal::default();
+    journal.push(Batch {
+        at: chrono::Utc::now(),
+        command: "sync".to_string(),
+        account_id: session.config.harvest.account_id.clone(),
+        changes: vec![
+            Change::Stopped { entry_id: 40 },
+            Change::Created { entry_id: 41 },
+        ],
+    });
+    journal.save().unwrap();
+
+    let (result, output) = capture(|| commands::run_undo(ctx(), &session, true));
+
+    result.unwrap();
+    delete.assert();
+    restart.assert();
+    assert!(Journal::load().unwrap().batches.is_empty());
+    assert_eq!(
+        output.messages(),
+        vec![(
+            MessageKind::Success,
+            "Undid 2 changes from harv sync".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_log_offline_is_queued_and_flushed() {
+    // Nothing listens on the discard port, so every request fails to connect
+    let offline = HarvestClient::new(common::harvest_config())
+        .unwrap()
+        .with_base_url("http://127.0.0.1:9");
+    common::isolate_home();
+    let offline_session = commands::Session::new(common::config()).with_harvest(offline);
+
+    let (result, output) = capture(|| {
+        commands::run_log(
+            ctx(),
+            &offline_session,
+            "ABC-3",
+            "1.5",
+            Some("Offline work".to_string()),
+            (None, None),
+            None,
+        )
+    });
+    result.unwrap();
+    assert_eq!(output.messages()[0].0, MessageKind::Warning);
+    assert_eq!(OfflineQueue::load().unwrap().operations.len(), 1);
+
+    let mut server = mockito::Server::new();
+    let create = server
+        .mock("POST", "/time_entries")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "project_id": 11,
+            "task_id": 22,
+            "hours": 1.5,
+            "notes": "ABC-3 - Offline work"
+        })))
+        .with_body(time_entry(8, "ABC-3 - Offline work", 1.5, false))
+        .create();
+
+    let session = session(&server);
+    let (result, output) = capture(|| commands::run_flush(ctx(), &session));
+
+    result.unwrap();
+    create.assert();
+    assert!(OfflineQueue::load().unwrap().is_empty());
+    assert_eq!(
+        output.messages(),
+        vec![(
+            MessageKind::Success,
+            "Sent 1 change queued while offline".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_add_alias_checks_the_billable_flag() {
+    let mut server = mockito::Server::new();
+    let _projects = server
+        .mock("GET", "/projects")
+        .match_query(Matcher::Any)
+        .with_body(r#"{"projects":[{"id":11,"name":"Acme Web","code":"WEB"}]}"#)
+        .create();
+    let _tasks = server
+        .mock("GET", "/projects/11/task_assignments")
+        .match_query(Matcher::Any)
+        .with_body(
+            r#"{"task_assignments":[{"is_active":true,"billable":false,
+                "task":{"id":23,"name":"Internal"}}]}"#,
+        )
+        .create();
+    let create = server.mock("POST", "/time_entries").expect(0).create();
+
+    let mut session = session(&server);
+    session.config.aliases.insert(
+        "internal".to_string(),
+        harv::config::ProjectAlias {
+            project: "WEB".to_string(),
+            task: "Internal".to_string(),
+        },
+    );
+    let (result, _) = capture(|| {
+        commands::run_add(
+            ctx(),
+            &session,
+            None,
+            None,
+            Some("internal".to_string()),
+            Some("1".to_string()),
+            Some("Planning".to_string()),
+            Some(true),
+        )
+    });
+
+    let error = result.unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Alias 'internal' books on Internal, which is not billable"),
+        "{}",
+        error
+    );
+    create.assert();
+}
@@ -0,0 +1,268 @@
+mod common;
+
+use common::jira_client;
+use harv::{HarjiraError, IssueTracker};
+use mockito::Matcher;
+
+fn issue(key: &str, summary: &str, status: &str) -> String {
+    format!(
+        r#"{{"key":"{}","fields":{{"summary":"{}","status":{{"name":"{}"}}}}}}"#,
+        key, summary, status
+    )
+}
+
+#[test]
+fn test_get_issue() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/rest/api/3/issue/ABC-1")
+        .match_header("authorization", "Bearer jira-token")
+        .with_body(issue("ABC-1", "Fix login", "In Progress"))
+        .create();
+
+    let ticket = jira_client(&server).get_issue("ABC-1").unwrap();
+    assert_eq!(ticket.key, "ABC-1");
+    assert_eq!(ticket.summary, "Fix login");
+    assert_eq!(ticket.status.as_deref(), Some("In Progress"));
+    assert_eq!(ticket.estimate, None);
+    mock.assert();
+}
+
+#[test]
+fn test_get_issue_estimate() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/rest/api/3/issue/ABC-1")
+        .with_body(
+            r#"{"key":"ABC-1","fields":{"summary":"Fix login","status":{"name":"To Do"},
+                "timeoriginalestimate":28800,"timeestimate":7200,"timespent":null}}"#,
+        )
+        .create();
+
+    let estimate = jira_client(&server)
+        .get_issue("ABC-1")
+        .unwrap()
+        .estimate
+        .unwrap();
+    assert_eq!(estimate.original_hours, Some(8.0));
+    assert_eq!(estimate.remaining_hours, Some(2.0));
+    assert_eq!(estimate.spent_hours, 0.0);
+}
+
+#[test]
+fn test_get_server_info() {
+    let mut server = mockito::Server::new();
+    let _info = server
+        .mock("GET", "/rest/api/3/serverInfo")
+        .with_body(r#"{"baseUrl":"https://example.atlassian.net","version":"1001.0.0","serverTitle":"Jira"}"#)
+        .create();
+
+    let info = jira_client(&server).get_server_info().unwrap();
+    assert_eq!(info.version, "1001.0.0");
+    assert_eq!(info.server_title, "Jira");
+}
+
+#[test]
+fn test_get_issue_error_statuses() {
+    let mut server = mockito::Server::new();
+    let _missing = server
+        .mock("GET", "/rest/api/3/issue/ABC-404")
+        .with_status(404)
+        .create();
+    let _unauthorized = server
+        .mock("GET", "/rest/api/3/issue/ABC-401")
+        .with_status(401)
+        .create();
+    let _forbidden = server
+        .mock("GET", "/rest/api/3/issue/ABC-403")
+        .with_status(403)
+        .create();
+
+    let client = jira_client(&server);
+    assert!(matches!(
+        client.get_issue("ABC-404"),
+        Err(HarjiraError::Jira(msg)) if msg.contains("not found")
+    ));
+    assert!(matches!(
+        client.get_issue("ABC-401"),
+        Err(HarjiraError::Auth(_))
+    ));
+    assert!(matches!(
+        client.get_issue("ABC-403"),
+        Err(HarjiraError::Jira(msg)) if msg.contains("Access denied")
+    ));
+}
+
+#[test]
+fn test_get_issues_keeps_failed_keys() {
+    let mut server = mockito::Server::new();
+    let _found = server
+        .mock("GET", "/rest/api/3/issue/ABC-1")
+        .with_body(issue("ABC-1", "Fix login", "Done"))
+        .create();
+    let _missing = server
+        .mock("GET", "/rest/api/3/issue/ABC-2")
+        .with_status(404)
+        .create();
+
+    let mut progress = Vec::new();
+    let tickets = jira_client(&server).get_issues_with_progress(
+        &["ABC-1".to_string(), "ABC-2".to_string()],
+        &mut |done, total| progress.push((done, total)),
+    );
+
+    assert_eq!(tickets.len(), 2);
+    assert_eq!(tickets[0].summary, "Fix login");
+    assert_eq!(tickets[1].key, "ABC-2");
+    assert!(tickets[1].summary.starts_with("(Failed to fetch"));
+    assert_eq!(progress, vec![(0, 2), (1, 2)]);
+}
+
+#[test]
+fn test_recent_issues_search() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/rest/api/3/search/jql")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(
+                "fields".into(),
+                "summary,status,timeoriginalestimate,timeestimate,timespent".into(),
+            ),
+            Matcher::UrlEncoded("maxResults".into(), "25".into()),
+            Matcher::Regex("assignee".into()),
+        ]))
+        .with_body(format!(
+            r#"{{"issues":[{},{}]}}"#,
+            issue("ABC-1", "Fix login", "In Progress"),
+            issue("ABC-7", "Write docs", "To Do")
+        ))
+        .create();
+
+    let tickets = jira_client(&server).get_my_recent_issues(25).unwrap();
+    let keys: Vec<&str> = tickets.iter().map(|t| t.key.as_str()).collect();
+    assert_eq!(keys, vec!["ABC-1", "ABC-7"]);
+    mock.assert();
+}
+
+#[test]
+fn test_recent_issues_auth_failure() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/rest/api/3/search/jql")
+        .match_query(Matcher::Any)
+        .with_status(401)
+        .create();
+
+    assert!(matches!(
+        jira_client(&server).get_my_recent_issues(10),
+        Err(HarjiraError::Auth(_))
+    ));
+}
+
+#[test]
+fn test_ticket_url_uses_site_not_api_root() {
+    let server = mockito::Server::new();
+    assert_eq!(
+        jira_client(&server).get_ticket_url("ABC-1"),
+        "https://acme.atlassian.net/browse/ABC-1"
+    );
+}
+
+#[test]
+fn test_my_sprint_issues() {
+    let mut server = mockito::Server::new();
+    let sprints = server
+        .mock("GET", "/rest/agile/1.0/board/42/sprint")
+        .match_query(Matcher::UrlEncoded("state".into(), "active".into()))
+        .with_body(r#"{"values":[{"id":7,"name":"Sprint 12","state":"active"}]}"#)
+        .create();
+    let issues = server
+        .mock("GET", "/rest/agile/1.0/board/42/sprint/7/issue")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(
+                "fields".into(),
+                "summary,status,timeoriginalestimate,timeestimate,timespent".into(),
+            ),
+            Matcher::Regex("currentUser".into()),
+        ]))
+        .with_body(format!(
+            r#"{{"issues":[{}]}}"#,
+            issue("ABC-7", "Billing export", "In Progress")
+        ))
+        .create();
+
+    let tickets = jira_client(&server).get_my_sprint_issues(42).unwrap();
+    assert_eq!(tickets.len(), 1);
+    assert_eq!(tickets[0].key, "ABC-7");
+    assert!(tickets[0].in_sprint);
+    sprints.assert();
+    issues.assert();
+}
+
+#[test]
+fn test_no_active_sprint() {
+    let mut server = mockito::Server::new();
+    let _sprints = server
+        .mock("GET", "/rest/agile/1.0/board/42/sprint")
+        .match_query(Matcher::Any)
+        .with_body(r#"{"values":[]}"#)
+        .create();
+    let _missing = server
+        .mock("GET", "/rest/agile/1.0/board/404/sprint")
+        .match_query(Matcher::Any)
+        .with_status(404)
+        .create();
+
+    let client = jira_client(&server);
+    assert!(client.get_my_sprint_issues(42).unwrap().is_empty());
+    assert!(matches!(
+        client.get_my_sprint_issues(404),
+        Err(HarjiraError::Jira(msg)) if msg.contains("board_id")
+    ));
+}
+
+#[test]
+fn test_get_issue_details_converts_documents_to_text() {
+    let mut server = mockito::Server::new();
+    let paragraph = |text: &str| {
+        format!(
+            r#"{{"type":"paragraph","content":[{{"type":"text","text":"{}"}}]}}"#,
+            text
+        )
+    };
+    let comment = |author: &str, text: &str| {
+        format!(
+            r#"{{"author":{{"displayName":"{}"}},"body":{{"type":"doc","content":[{}]}}}}"#,
+            author,
+            paragraph(text)
+        )
+    };
+    let body = format!(
+        r#"{{"key":"ABC-1","fields":{{"summary":"Fix login","status":{{"name":"To Do"}},
+            "description":{{"type":"doc","content":[{},{{"type":"bulletList","content":[
+                {{"type":"listItem","content":[{}]}}]}}]}},
+            "comment":{{"comments":[{},{},{}]}}}}}}"#,
+        paragraph("Login fails on Safari"),
+        paragraph("Check cookies"),
+        comment("Ann", "First"),
+        comment("Bob", "Second"),
+        comment("Cy", "Third"),
+    );
+    let _mock = server
+        .mock("GET", "/rest/api/3/issue/ABC-1")
+        .with_body(body)
+        .create();
+
+    let details = jira_client(&server).get_issue_details("ABC-1", 2).unwrap();
+    assert_eq!(details.summary, "Fix login");
+    assert_eq!(
+        details.description.as_deref(),
+        Some("Login fails on Safari\nCheck cookies")
+    );
+    let comments: Vec<(&str, &str)> = details
+        .comments
+        .iter()
+        .map(|c| (c.author.as_str(), c.body.as_str()))
+        .collect();
+    assert_eq!(comments, vec![("Bob", "Second"), ("Cy", "Third")]);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::linear_client;
+use harv::{HarjiraError, IssueTracker};
+use mockito::Matcher;
+
+#[test]
+fn test_get_issue_by_identifier() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/graphql")
+        .match_header("authorization", "lin_api_test")
+        .match_body(Matcher::PartialJsonString(
+            r#"{"variables":{"id":"ENG-123"}}"#.to_string(),
+        ))
+        .with_body(
+            r#"{"data":{"issue":{"identifier":"ENG-123","title":"Fix login","state":{"name":"In Progress"}}}}"#,
+        )
+        .create();
+
+    let ticket = linear_client(&server).get_issue("ENG-123").unwrap();
+    assert_eq!(ticket.key, "ENG-123");
+    assert_eq!(ticket.summary, "Fix login");
+    assert_eq!(ticket.status.as_deref(), Some("In Progress"));
+    mock.assert();
+}
+
+#[test]
+fn test_unknown_issue_is_not_found() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("POST", "/graphql")
+        .with_body(r#"{"data":null,"errors":[{"message":"Entity not found: Issue"}]}"#)
+        .create();
+
+    assert!(matches!(
+        linear_client(&server).get_issue("ENG-999"),
+        Err(HarjiraError::Linear(msg)) if msg.contains("ENG-999 not found")
+    ));
+}
+
+#[test]
+fn test_graphql_errors_are_reported() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("POST", "/graphql")
+        .with_status(400)
+        .with_body(r#"{"errors":[{"message":"Argument first must be positive"}]}"#)
+        .create();
+
+    assert!(matches!(
+        linear_client(&server).get_my_recent_issues(0),
+        Err(HarjiraError::Linear(msg)) if msg == "Argument first must be positive"
+    ));
+}
+
+#[test]
+fn test_invalid_key_maps_to_auth_exit_code() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("POST", "/graphql")
+        .with_status(401)
+        .with_body(r#"{"errors":[{"message":"Authentication required"}]}"#)
+        .create();
+
+    let error = linear_client(&server).get_issue("ENG-1").unwrap_err();
+    assert_eq!(error.exit_code(), harv::error::exit_code::AUTH);
+}
+
+#[test]
+fn test_recent_issues_are_assigned_ones() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/graphql")
+        .match_body(Matcher::Regex("assignedIssues".to_string()))
+        .with_body(
+            r#"{"data":{"viewer":{"assignedIssues":{"nodes":[
+                {"identifier":"ENG-1","title":"One","state":{"name":"Todo"}},
+                {"identifier":"ENG-2","title":"Two","state":{"name":"Done"}}]}}}}"#,
+        )
+        .create();
+
+    let tickets = linear_client(&server).get_my_recent_issues(50).unwrap();
+    let keys: Vec<&str> = tickets.iter().map(|t| t.key.as_str()).collect();
+    assert_eq!(keys, vec!["ENG-1", "ENG-2"]);
+    mock.assert();
+}
+
+#[test]
+fn test_ticket_url_is_workspace_permalink() {
+    let server = mockito::Server::new();
+    assert_eq!(
+        linear_client(&server).get_ticket_url("ENG-123"),
+        "https://linear.app/acme/issue/ENG-123"
+    );
+}
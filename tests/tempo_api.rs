@@ -0,0 +1,110 @@
+mod common;
+
+use common::{ctx, tempo_client};
+use harv::tempo::LOCAL_TIMER_ID;
+use harv::{HarjiraError, TimeBackend};
+use mockito::Matcher;
+
+fn worklog(id: u64, issue_id: u64, seconds: u64, description: &str) -> String {
+    format!(
+        r#"{{"tempoWorklogId":{},"issue":{{"id":{}}},"timeSpentSeconds":{},
+            "startDate":"{}","startTime":"09:00:00","description":"{}"}}"#,
+        id,
+        issue_id,
+        seconds,
+        common::today(),
+        description
+    )
+}
+
+#[test]
+fn test_worklogs_follow_pagination() {
+    let mut server = mockito::Server::new();
+    let next = format!("{}/worklogs/user/acc-1?offset=1", server.url());
+    let first = server
+        .mock("GET", "/worklogs/user/acc-1")
+        .match_query(Matcher::UrlEncoded("from".into(), common::today()))
+        .match_header("authorization", "Bearer tempo-token")
+        .with_body(format!(
+            r#"{{"results":[{}],"metadata":{{"next":"{}"}}}}"#,
+            worklog(1, 10001, 5400, "ABC-1 - Fix login"),
+            next
+        ))
+        .create();
+    let second = server
+        .mock("GET", "/worklogs/user/acc-1")
+        .match_query(Matcher::UrlEncoded("offset".into(), "1".into()))
+        .with_body(format!(
+            r#"{{"results":[{}],"metadata":{{}}}}"#,
+            worklog(2, 10002, 1800, "ABC-2 - Review")
+        ))
+        .create();
+
+    let today = common::today();
+    let worklogs = tempo_client(&server).get_worklogs(&today, &today).unwrap();
+    let entries: Vec<_> = worklogs.iter().map(|w| w.to_time_entry()).collect();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].hours, Some(1.5));
+    assert_eq!(entries[1].notes.as_deref(), Some("ABC-2 - Review"));
+    first.assert();
+    second.assert();
+}
+
+#[test]
+fn test_invalid_token_is_auth_error() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/worklogs/user/acc-1")
+        .match_query(Matcher::Any)
+        .with_status(401)
+        .create();
+
+    let today = common::today();
+    assert!(matches!(
+        tempo_client(&server).get_worklogs(&today, &today),
+        Err(HarjiraError::Auth(_))
+    ));
+}
+
+#[test]
+fn test_local_timer_becomes_worklog() {
+    common::isolate_home();
+    let mut server = mockito::Server::new();
+    let _issue = server
+        .mock("GET", "/rest/api/3/issue/ABC-1")
+        .with_body(
+            r#"{"id":"10001","key":"ABC-1","fields":{"summary":"Fix login","status":{"name":"In Progress"}}}"#,
+        )
+        .create();
+    let _today = server
+        .mock("GET", "/worklogs/user/acc-1")
+        .match_query(Matcher::Any)
+        .with_body(r#"{"results":[],"metadata":{}}"#)
+        .create();
+    let create = server
+        .mock("POST", "/worklogs")
+        .match_body(Matcher::PartialJsonString(
+            r#"{"authorAccountId":"acc-1","issueId":10001,"timeSpentSeconds":60,
+                "description":"ABC-1 - Fix login"}"#
+                .to_string(),
+        ))
+        .with_body(worklog(77, 10001, 60, "ABC-1 - Fix login"))
+        .create();
+
+    let tempo = tempo_client(&server);
+    let started = tempo
+        .create_time_entry("ABC-1", "Fix login", "", &ctx())
+        .unwrap();
+    assert!(started.is_running);
+
+    let running = tempo.get_running_timer().unwrap().unwrap();
+    assert_eq!(running.id, LOCAL_TIMER_ID);
+    let today = tempo.get_todays_time_entries().unwrap();
+    assert_eq!(today.len(), 1);
+    assert!(today[0].is_running);
+
+    let stopped = tempo.stop_time_entry(running.id, &ctx()).unwrap();
+    assert_eq!(stopped.id, 77);
+    assert!(tempo.get_running_timer().unwrap().is_none());
+    create.assert();
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use common::{ctx, toggl_client};
+use harv::{HarjiraError, TimeBackend};
+use mockito::Matcher;
+
+const AUTH: &str = "Basic dG9nZ2wtdG9rZW46YXBpX3Rva2Vu";
+
+fn toggl_entry(id: u64, description: &str, start: &str, duration: i64) -> String {
+    format!(
+        r#"{{"id":{},"workspace_id":55,"project_id":3,"description":"{}","start":"{}","duration":{}}}"#,
+        id, description, start, duration
+    )
+}
+
+fn minutes_ago(minutes: i64) -> String {
+    (chrono::Utc::now() - chrono::Duration::minutes(minutes)).to_rfc3339()
+}
+
+#[test]
+fn test_start_timer_in_workspace() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/workspaces/55/time_entries")
+        .match_header("authorization", AUTH)
+        .match_body(Matcher::PartialJsonString(
+            r#"{"workspace_id":55,"project_id":3,"description":"ABC-1 - Fix login","duration":-1,"created_with":"harv"}"#
+                .to_string(),
+        ))
+        .with_body(toggl_entry(9, "ABC-1 - Fix login", &minutes_ago(0), -1))
+        .create();
+
+    let entry = toggl_client(&server)
+        .create_time_entry("ABC-1", "Fix login", "", &ctx())
+        .unwrap();
+    assert_eq!(entry.id, 9);
+    assert!(entry.is_running);
+    mock.assert();
+}
+
+#[test]
+fn test_running_timer_and_stop() {
+    let mut server = mockito::Server::new();
+    let _current = server
+        .mock("GET", "/me/time_entries/current")
+        .with_body(toggl_entry(9, "ABC-1 - Fix login", &minutes_ago(90), -1))
+        .create();
+    let stop = server
+        .mock("PATCH", "/workspaces/55/time_entries/9/stop")
+        .with_body(toggl_entry(9, "ABC-1 - Fix login", &minutes_ago(90), 5400))
+        .create();
+
+    let toggl = toggl_client(&server);
+    let timer = toggl.get_running_timer().unwrap().unwrap();
+    assert_eq!(timer.id, 9);
+    assert!(timer.hours.unwrap() >= 1.49);
+
+    let stopped = toggl.stop_time_entry(timer.id, &ctx()).unwrap();
+    assert_eq!(stopped.hours, Some(1.5));
+    assert!(!stopped.is_running);
+    stop.assert();
+}
+
+#[test]
+fn test_delete_entry() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("DELETE", "/workspaces/55/time_entries/9")
+        .match_header("authorization", AUTH)
+        .create();
+
+    toggl_client(&server).delete_time_entry(9, &ctx()).unwrap();
+    mock.assert();
+}
+
+#[test]
+fn test_no_running_timer() {
+    let mut server = mockito::Server::new();
+    let _current = server
+        .mock("GET", "/me/time_entries/current")
+        .with_body("null")
+        .create();
+
+    assert!(toggl_client(&server).get_running_timer().unwrap().is_none());
+}
+
+#[test]
+fn test_todays_entries_oldest_first() {
+    let mut server = mockito::Server::new();
+    let _entries = server
+        .mock("GET", "/me/time_entries")
+        .match_query(Matcher::UrlEncoded("start_date".into(), common::today()))
+        .with_body(format!(
+            "[{},{}]",
+            toggl_entry(2, "ABC-2 - Review", &minutes_ago(1), -1),
+            toggl_entry(1, "ABC-1 - Fix login", &minutes_ago(2), 30)
+        ))
+        .create();
+
+    let entries = toggl_client(&server).get_todays_time_entries().unwrap();
+    let ids: Vec<u64> = entries.iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[test]
+fn test_projects_skip_archived() {
+    let mut server = mockito::Server::new();
+    let _projects = server
+        .mock("GET", "/workspaces/55/projects")
+        .match_query(Matcher::Any)
+        .with_body(r#"[{"id":3,"name":"Web","active":true},{"id":4,"name":"Old","active":false}]"#)
+        .create();
+
+    let projects = toggl_client(&server).get_projects().unwrap();
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].name, "Web");
+}
+
+#[test]
+fn test_create_stopped_entry() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/workspaces/55/time_entries")
+        .match_body(Matcher::PartialJsonString(
+            r#"{"description":"Planning","duration":2700}"#.to_string(),
+        ))
+        .with_body(toggl_entry(12, "Planning", "2026-03-02T08:00:00Z", 2700))
+        .create();
+
+    let entry = toggl_client(&server)
+        .create_stopped_time_entry("Planning", 0.75, "2026-03-02", &ctx())
+        .unwrap();
+    assert_eq!(entry.hours, Some(0.75));
+    mock.assert();
+}
+
+#[test]
+fn test_invalid_token_is_auth_error() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/me/time_entries/current")
+        .with_status(403)
+        .create();
+
+    assert!(matches!(
+        toggl_client(&server).get_running_timer(),
+        Err(HarjiraError::Auth(_))
+    ));
+}